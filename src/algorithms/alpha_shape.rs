@@ -0,0 +1,102 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::delaunay_triangulation::{triangulate, EMPTY};
+use crate::structures::Point2D;
+
+/// Returns the polygon rings of the alpha shape of a set of points, i.e. the union of the
+/// Delaunay triangles with a circumradius no larger than `alpha`. Smaller values of `alpha`
+/// produce tighter, more concave shapes, while very large values approach the convex hull.
+///
+/// Rings follow the Shapefile convention, i.e. exterior rings are in clockwise order and
+/// holes are in counter-clockwise order, and each ring is closed (the first and last points
+/// are the same). Parts of the shape that only touch at a single point are returned as
+/// separate rings.
+pub fn alpha_shape(points: &[Point2D], alpha: f64) -> Vec<Vec<Point2D>> {
+    let mut rings = vec![];
+    let t = match triangulate(points) {
+        Some(t) => t,
+        None => return rings,
+    };
+    let alpha_sqrd = alpha * alpha;
+    let keep = (0..t.len())
+        .map(|tri| {
+            let p = t.points_of_triangle(tri);
+            let r = points[p[0]].circumradius2(&points[p[1]], &points[p[2]]);
+            r.is_finite() && r <= alpha_sqrd
+        })
+        .collect::<Vec<bool>>();
+    let is_boundary = |edge: usize| {
+        keep[edge / 3] && (t.halfedges[edge] == EMPTY || !keep[t.halfedges[edge] / 3])
+    };
+
+    // Boundary edges are followed in the direction of their triangles, which places the
+    // shape on the right-hand side of each ring.
+    let mut visited = vec![false; t.triangles.len()];
+    for start in 0..t.triangles.len() {
+        if visited[start] || !is_boundary(start) {
+            continue;
+        }
+        let mut ring = vec![];
+        let mut edge = start;
+        loop {
+            visited[edge] = true;
+            ring.push(points[t.triangles[edge]]);
+            // rotate about the end point of the edge, through the fan of kept triangles,
+            // until the next boundary edge is found
+            let mut next = t.next_halfedge(edge);
+            while !is_boundary(next) {
+                next = t.next_halfedge(t.halfedges[next]);
+            }
+            edge = next;
+            if edge == start || visited[edge] {
+                break;
+            }
+        }
+        if ring.len() >= 3 {
+            ring.push(ring[0]);
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+#[cfg(test)]
+mod test {
+    use super::alpha_shape;
+    use crate::algorithms::{is_clockwise_order, polygon_area};
+    use crate::structures::Point2D;
+
+    #[test]
+    fn test_alpha_shape() {
+        // an L-shaped grid of points with unit spacing and a 2 x 2 courtyard
+        let mut points = vec![];
+        for row in 0..=8 {
+            for col in 0..=8 {
+                let in_notch = row > 4 && col > 4;
+                let in_courtyard = row > 1 && row < 4 && col > 1 && col < 4;
+                if !in_notch && !in_courtyard {
+                    points.push(Point2D::new(col as f64, row as f64));
+                }
+            }
+        }
+        // the half cells at concave corners have the same circumradius as the full cells
+        let rings = alpha_shape(&points, 0.75);
+        assert_eq!(rings.len(), 2);
+        let exterior = rings.iter().find(|r| is_clockwise_order(r)).unwrap();
+        let hole = rings.iter().find(|r| !is_clockwise_order(r)).unwrap();
+        assert_eq!(exterior[0], exterior[exterior.len() - 1]);
+        assert!((polygon_area(exterior) - 48.5f64).abs() < 1e-9);
+        assert!((polygon_area(hole) - 7f64).abs() < 1e-9);
+
+        // a large alpha gives the convex hull
+        let rings = alpha_shape(&points, 1000f64);
+        assert_eq!(rings.len(), 1);
+        assert!((polygon_area(&rings[0]) - 56f64).abs() < 1e-9);
+    }
+}
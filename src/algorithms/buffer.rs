@@ -0,0 +1,313 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::{polygon_overlay, OverlayOperation};
+use crate::structures::Point2D;
+use std::f64::consts::PI;
+
+/// The number of vertices used to approximate a full circle. Arcs use a proportional number.
+const VERTICES_PER_CIRCLE: usize = 32;
+
+/// The maximum ratio of the mitre length to the buffer distance. Sharper mitre joins are
+/// bevelled instead.
+const MITRE_LIMIT: f64 = 5f64;
+
+/// The shape of the buffer at the ends of lines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BufferCapStyle {
+    /// A semi-circle centred on the end point.
+    Round,
+    /// The buffer ends at the end point, perpendicular to the line.
+    Flat,
+    /// The buffer extends beyond the end point by the buffer distance and is squared off.
+    Square,
+}
+
+/// The shape of the buffer on the outside of the vertices of lines and polygons.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BufferJoinStyle {
+    /// A circular arc centred on the vertex.
+    Round,
+    /// The offset edges are extended until they meet, unless the resulting corner would extend
+    /// more than five times the buffer distance from the vertex, in which case it is bevelled.
+    Mitre,
+    /// The ends of the offset edges are joined by a straight line.
+    Bevel,
+}
+
+/// Returns the polygon rings of the area within `distance` of any of a set of points. Round
+/// caps buffer each point with a circle and square caps with an axis-aligned square, while
+/// flat caps produce no buffer.
+///
+/// As with all of the buffer functions, the rings follow the Shapefile convention (clockwise
+/// exteriors, each followed by its counter-clockwise holes) and overlapping buffers are merged.
+pub fn buffer_points(points: &[Point2D], distance: f64, cap: BufferCapStyle) -> Vec<Vec<Point2D>> {
+    let mut pieces = vec![];
+    if distance > 0f64 {
+        for p in points {
+            point_piece(*p, distance, cap, &mut pieces);
+        }
+    }
+    polygon_overlay(&pieces, &[], OverlayOperation::Union, 0f64)
+}
+
+/// Returns the polygon rings of the area within `distance` of any of a set of polylines. A
+/// polyline that has collapsed to a single point is buffered as a point.
+pub fn buffer_polylines(
+    lines: &[Vec<Point2D>],
+    distance: f64,
+    cap: BufferCapStyle,
+    join: BufferJoinStyle,
+) -> Vec<Vec<Point2D>> {
+    let mut pieces = vec![];
+    if distance > 0f64 {
+        for line in lines {
+            line_pieces(line, false, distance, cap, join, &mut pieces);
+        }
+    }
+    polygon_overlay(&pieces, &[], OverlayOperation::Union, 0f64)
+}
+
+/// Returns the polygon rings of a polygon, given by its exterior and hole rings, that has been
+/// grown by `distance`, or shrunk if the distance is negative. A polygon that is shrunk by
+/// more than half its width may be split into several parts, or disappear entirely.
+pub fn buffer_polygon(
+    rings: &[Vec<Point2D>],
+    distance: f64,
+    join: BufferJoinStyle,
+) -> Vec<Vec<Point2D>> {
+    let mut pieces = vec![];
+    if distance != 0f64 {
+        for ring in rings {
+            line_pieces(
+                ring,
+                true,
+                distance.abs(),
+                BufferCapStyle::Flat,
+                join,
+                &mut pieces,
+            );
+        }
+    }
+    let operation = if distance < 0f64 {
+        OverlayOperation::Difference
+    } else {
+        OverlayOperation::Union
+    };
+    polygon_overlay(rings, &pieces, operation, 0f64)
+}
+
+/// Adds the buffer of a single point.
+fn point_piece(p: Point2D, distance: f64, cap: BufferCapStyle, pieces: &mut Vec<Vec<Point2D>>) {
+    match cap {
+        BufferCapStyle::Round => pieces.push(circle(p, distance)),
+        BufferCapStyle::Square => pieces.push(clockwise(vec![
+            Point2D::new(p.x - distance, p.y - distance),
+            Point2D::new(p.x - distance, p.y + distance),
+            Point2D::new(p.x + distance, p.y + distance),
+            Point2D::new(p.x + distance, p.y - distance),
+        ])),
+        BufferCapStyle::Flat => {}
+    }
+}
+
+/// Adds the pieces whose union is the buffer of a line, or of a ring if `closed` is true: a
+/// rectangle for each segment, a wedge on the outside of each vertex for the join, and the
+/// caps at the ends of an open line.
+fn line_pieces(
+    line: &[Point2D],
+    closed: bool,
+    distance: f64,
+    cap: BufferCapStyle,
+    join: BufferJoinStyle,
+    pieces: &mut Vec<Vec<Point2D>>,
+) {
+    let mut points: Vec<Point2D> = Vec::with_capacity(line.len());
+    for p in line {
+        if points.last() != Some(p) {
+            points.push(*p);
+        }
+    }
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let n = points.len();
+    if n == 0 {
+        return;
+    }
+    if n == 1 {
+        point_piece(points[0], distance, cap, pieces);
+        return;
+    }
+
+    let num_segments = if closed { n } else { n - 1 };
+    let direction = |i: usize| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let len = a.distance(&b);
+        ((b.x - a.x) / len, (b.y - a.y) / len)
+    };
+
+    for i in 0..num_segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let (ux, uy) = direction(i);
+        let normal = (-uy, ux);
+        let (mut a2, mut b2) = (a, b);
+        if !closed && cap == BufferCapStyle::Square {
+            if i == 0 {
+                a2 = offset(a, (ux, uy), -distance);
+            }
+            if i == num_segments - 1 {
+                b2 = offset(b, (ux, uy), distance);
+            }
+        }
+        pieces.push(clockwise(vec![
+            offset(a2, normal, distance),
+            offset(b2, normal, distance),
+            offset(b2, normal, -distance),
+            offset(a2, normal, -distance),
+        ]));
+    }
+
+    let joined_vertices = if closed { 0..n } else { 1..n - 1 };
+    for i in joined_vertices {
+        let v = points[i];
+        let u1 = direction((i + n - 1) % n);
+        let u2 = direction(i);
+        let cross = u1.0 * u2.1 - u1.1 * u2.0;
+        let dot = u1.0 * u2.0 + u1.1 * u2.1;
+        let turn = cross.atan2(dot);
+        if turn.abs() < 1.0e-9 {
+            continue;
+        }
+        // The outside of the join is on the right of a left turn, and vice versa.
+        let side = if turn > 0f64 { -distance } else { distance };
+        let n1 = (-u1.1, u1.0);
+        let n2 = (-u2.1, u2.0);
+        let p1 = offset(v, n1, side);
+        let p2 = offset(v, n2, side);
+        let mut wedge = vec![v, p1];
+        match join {
+            BufferJoinStyle::Round => {
+                let start = (p1.y - v.y).atan2(p1.x - v.x);
+                let steps = (turn.abs() / (2f64 * PI) * VERTICES_PER_CIRCLE as f64).ceil() as usize;
+                for k in 1..steps {
+                    let angle = start + turn * k as f64 / steps as f64;
+                    wedge.push(Point2D::new(
+                        v.x + distance * angle.cos(),
+                        v.y + distance * angle.sin(),
+                    ));
+                }
+            }
+            BufferJoinStyle::Mitre => {
+                let half_turn_cos = (turn.abs() / 2f64).cos();
+                if 1f64 / half_turn_cos <= MITRE_LIMIT {
+                    let (bx, by) = (n1.0 + n2.0, n1.1 + n2.1);
+                    let len = (bx * bx + by * by).sqrt();
+                    wedge.push(offset(v, (bx / len, by / len), side / half_turn_cos));
+                }
+            }
+            BufferJoinStyle::Bevel => {}
+        }
+        wedge.push(p2);
+        pieces.push(clockwise(wedge));
+    }
+
+    if !closed && cap == BufferCapStyle::Round {
+        pieces.push(circle(points[0], distance));
+        pieces.push(circle(points[n - 1], distance));
+    }
+}
+
+fn offset(p: Point2D, direction: (f64, f64), distance: f64) -> Point2D {
+    Point2D::new(p.x + direction.0 * distance, p.y + direction.1 * distance)
+}
+
+fn circle(centre: Point2D, radius: f64) -> Vec<Point2D> {
+    let mut points = Vec::with_capacity(VERTICES_PER_CIRCLE + 1);
+    for i in 0..VERTICES_PER_CIRCLE {
+        let angle = -2f64 * PI * i as f64 / VERTICES_PER_CIRCLE as f64;
+        points.push(Point2D::new(
+            centre.x + radius * angle.cos(),
+            centre.y + radius * angle.sin(),
+        ));
+    }
+    points.push(points[0]);
+    points
+}
+
+/// Closes a ring and puts it in clockwise order, so that overlapping pieces are merged by the
+/// non-zero winding rule of the overlay.
+fn clockwise(mut ring: Vec<Point2D>) -> Vec<Point2D> {
+    let mut area = 0f64;
+    for i in 0..ring.len() {
+        let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+        area += p.x * q.y - q.x * p.y;
+    }
+    if area > 0f64 {
+        ring.reverse();
+    }
+    ring.push(ring[0]);
+    ring
+}
+
+#[cfg(test)]
+mod test {
+    use super::{buffer_points, buffer_polygon, buffer_polylines, BufferCapStyle, BufferJoinStyle};
+    use crate::algorithms::polygon_area;
+    use crate::structures::Point2D;
+
+    fn area(rings: &[Vec<Point2D>]) -> f64 {
+        // none of the buffers tested here have holes
+        rings.iter().map(|r| polygon_area(r)).sum()
+    }
+
+    #[test]
+    fn test_buffer_lines_and_points() {
+        let line = vec![vec![Point2D::new(0.0, 0.0), Point2D::new(10.0, 0.0)]];
+        let flat = buffer_polylines(&line, 1.0, BufferCapStyle::Flat, BufferJoinStyle::Round);
+        assert_eq!(flat.len(), 1);
+        assert!((area(&flat) - 20.0).abs() < 1.0e-9);
+        let square = buffer_polylines(&line, 1.0, BufferCapStyle::Square, BufferJoinStyle::Round);
+        assert!((area(&square) - 24.0).abs() < 1.0e-9);
+        let round = buffer_polylines(&line, 1.0, BufferCapStyle::Round, BufferJoinStyle::Round);
+        assert!(area(&round) > 23.0 && area(&round) < 20.0 + std::f64::consts::PI);
+
+        // a right angle with a mitre join has a square outer corner
+        let line = vec![vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(10.0, 10.0),
+        ]];
+        let mitre = buffer_polylines(&line, 1.0, BufferCapStyle::Flat, BufferJoinStyle::Mitre);
+        assert!((area(&mitre) - 40.0).abs() < 1.0e-9);
+        let bevel = buffer_polylines(&line, 1.0, BufferCapStyle::Flat, BufferJoinStyle::Bevel);
+        assert!((area(&bevel) - 39.5).abs() < 1.0e-9);
+
+        // overlapping point buffers are merged
+        let points = [Point2D::new(0.0, 0.0), Point2D::new(1.0, 0.0)];
+        assert_eq!(buffer_points(&points, 1.0, BufferCapStyle::Square).len(), 1);
+    }
+
+    #[test]
+    fn test_buffer_polygon() {
+        let square = vec![vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.0, 10.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(0.0, 0.0),
+        ]];
+        let grown = buffer_polygon(&square, 1.0, BufferJoinStyle::Mitre);
+        assert_eq!(grown.len(), 1);
+        assert!((area(&grown) - 144.0).abs() < 1.0e-9);
+        let shrunk = buffer_polygon(&square, -1.0, BufferJoinStyle::Round);
+        assert_eq!(shrunk.len(), 1);
+        assert!((area(&shrunk) - 64.0).abs() < 1.0e-9);
+        assert!(buffer_polygon(&square, -6.0, BufferJoinStyle::Round).is_empty());
+    }
+}
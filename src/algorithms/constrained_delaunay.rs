@@ -0,0 +1,524 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::delaunay_triangulation::{triangulate, Triangulation, EMPTY};
+use crate::structures::Point2D;
+use std::collections::{HashMap, VecDeque};
+use std::f64;
+
+/// A constrained Delaunay triangulation, i.e. a Delaunay triangulation in which a set of
+/// constraint edges (e.g. breaklines and polygon boundaries) are guaranteed to be triangle
+/// edges. Triangles are Delaunay except where the constraints prevent it.
+///
+/// Constraints are inserted into an unconstrained triangulation using the edge-flipping
+/// algorithm of Sloan (1993), A fast algorithm for generating constrained Delaunay
+/// triangulations, *Computers & Structures*, 47(3), 441-450. Constraints that pass through
+/// other vertices are split at those vertices. Constraints that cross a previously inserted
+/// constraint cannot be inserted and are reported in `failed_constraints`; intersecting
+/// constraints should be split at their intersections beforehand.
+pub struct ConstrainedTriangulation {
+    /// The underlying triangulation, with the same connectivity conventions as `triangulate`.
+    pub triangulation: Triangulation,
+
+    /// For each halfedge, the index of the constraint that it belongs to, or `EMPTY`.
+    pub edge_constraints: Vec<usize>,
+
+    /// The indices of the constraints that could not be inserted.
+    pub failed_constraints: Vec<usize>,
+
+    // an outgoing halfedge for each point, or EMPTY for points not in the triangulation
+    point_edges: Vec<usize>,
+}
+
+impl ConstrainedTriangulation {
+    /// Returns true if a halfedge is constrained.
+    pub fn is_constrained(&self, edge: usize) -> bool {
+        self.edge_constraints[edge] != EMPTY
+    }
+
+    /// Returns the halfedges leaving a point, or an empty vector if the point is not a
+    /// vertex of the triangulation (e.g. it duplicates another point).
+    pub fn outgoing_edges(&self, point: usize) -> Vec<usize> {
+        let t = &self.triangulation;
+        let start = self.point_edges[point];
+        if start == EMPTY {
+            return vec![];
+        }
+        let mut edges = vec![start];
+        let mut e = start;
+        loop {
+            let twin = t.halfedges[t.prev_halfedge(e)];
+            if twin == start {
+                return edges;
+            }
+            if twin == EMPTY {
+                break;
+            }
+            edges.push(twin);
+            e = twin;
+        }
+        // the point is on the hull; rotate in the other direction
+        e = start;
+        loop {
+            let twin = t.halfedges[e];
+            if twin == EMPTY {
+                break;
+            }
+            e = t.next_halfedge(twin);
+            edges.push(e);
+        }
+        edges
+    }
+
+    /// Returns the halfedge from point `from` to point `to`, if they are connected.
+    pub fn find_edge(&self, from: usize, to: usize) -> Option<usize> {
+        let t = &self.triangulation;
+        self.outgoing_edges(from)
+            .into_iter()
+            .find(|&e| t.triangles[t.next_halfedge(e)] == to)
+    }
+
+    /// Identifies the triangles within the region enclosed by a set of boundary constraints,
+    /// e.g. the rings of a set of polygons. Triangles outside of the boundaries, or inside of
+    /// holes, are excluded, based on the number of boundary edges that must be crossed to
+    /// reach a triangle from the outside of the triangulation. `is_boundary` is passed the
+    /// constraint index of each constrained edge.
+    pub fn mask_triangles<F: Fn(usize) -> bool>(&self, is_boundary: F) -> Vec<bool> {
+        let t = &self.triangulation;
+        let num_triangles = t.triangles.len() / 3;
+        let crossing_cost = |e: usize| -> usize {
+            let c = self.edge_constraints[e];
+            if c != EMPTY && is_boundary(c) {
+                1
+            } else {
+                0
+            }
+        };
+        let mut depth = vec![EMPTY; num_triangles];
+        let mut queue = VecDeque::new();
+        for e in 0..t.halfedges.len() {
+            if t.halfedges[e] == EMPTY {
+                let d = crossing_cost(e);
+                let triangle = t.triangle_of_edge(e);
+                if d < depth[triangle] {
+                    depth[triangle] = d;
+                    if d == 0 {
+                        queue.push_front(triangle);
+                    } else {
+                        queue.push_back(triangle);
+                    }
+                }
+            }
+        }
+        // 0-1 breadth-first search, crossing boundary edges increments the depth
+        while let Some(triangle) = queue.pop_front() {
+            for &e in t.edges_of_triangle(triangle).iter() {
+                let twin = t.halfedges[e];
+                if twin == EMPTY {
+                    continue;
+                }
+                let cost = crossing_cost(e);
+                let d = depth[triangle] + cost;
+                let neighbour = t.triangle_of_edge(twin);
+                if d < depth[neighbour] {
+                    depth[neighbour] = d;
+                    if cost == 0 {
+                        queue.push_front(neighbour);
+                    } else {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+        depth.iter().map(|&d| d != EMPTY && d % 2 == 1).collect()
+    }
+
+    /// Estimates the values of points that are set to NaN, e.g. the vertices of soft breaklines,
+    /// by inverse-distance weighting of the values of their neighbouring vertices. Points without
+    /// any valued neighbours are estimated from previously estimated points.
+    pub fn interpolate_missing_values(&self, points: &[Point2D], values: &mut [f64]) {
+        let t = &self.triangulation;
+        loop {
+            let mut updates = vec![];
+            for p in 0..values.len() {
+                if !values[p].is_nan() {
+                    continue;
+                }
+                let (mut sum, mut sum_weights) = (0f64, 0f64);
+                for e in self.outgoing_edges(p) {
+                    for &q in [
+                        t.triangles[t.next_halfedge(e)],
+                        t.triangles[t.prev_halfedge(e)],
+                    ]
+                    .iter()
+                    {
+                        if !values[q].is_nan() {
+                            let w = 1f64 / points[p].distance_squared(&points[q]).max(1e-12);
+                            sum += w * values[q];
+                            sum_weights += w;
+                        }
+                    }
+                }
+                if sum_weights > 0f64 {
+                    updates.push((p, sum / sum_weights));
+                }
+            }
+            if updates.is_empty() {
+                break;
+            }
+            for (p, value) in updates {
+                values[p] = value;
+            }
+        }
+    }
+
+    fn flip(&mut self, a: usize) {
+        let t = &mut self.triangulation;
+        let b = t.halfedges[a];
+        let al = t.next_halfedge(a);
+        let ar = t.prev_halfedge(a);
+        let bl = t.prev_halfedge(b);
+        let br = t.next_halfedge(b);
+
+        let p0 = t.triangles[ar];
+        let pr = t.triangles[a];
+        let pl = t.triangles[al];
+        let p1 = t.triangles[bl];
+
+        t.triangles[a] = p1;
+        t.triangles[b] = p0;
+
+        let hbl = t.halfedges[bl];
+        let har = t.halfedges[ar];
+        t.halfedges[a] = hbl;
+        if hbl != EMPTY {
+            t.halfedges[hbl] = a;
+        }
+        t.halfedges[b] = har;
+        if har != EMPTY {
+            t.halfedges[har] = b;
+        }
+        t.halfedges[ar] = bl;
+        t.halfedges[bl] = ar;
+
+        self.edge_constraints[a] = self.edge_constraints[bl];
+        self.edge_constraints[b] = self.edge_constraints[ar];
+        self.edge_constraints[ar] = EMPTY;
+        self.edge_constraints[bl] = EMPTY;
+
+        self.point_edges[p0] = b;
+        self.point_edges[pr] = br;
+        self.point_edges[pl] = al;
+        self.point_edges[p1] = a;
+    }
+
+    // the end points of an edge and the opposite vertices in its two triangles
+    fn quad(&self, e: usize) -> (usize, usize, usize, usize) {
+        let t = &self.triangulation;
+        let twin = t.halfedges[e];
+        (
+            t.triangles[e],
+            t.triangles[t.next_halfedge(e)],
+            t.triangles[t.prev_halfedge(e)],
+            t.triangles[t.prev_halfedge(twin)],
+        )
+    }
+
+    fn is_illegal(&self, points: &[Point2D], e: usize) -> bool {
+        if self.triangulation.halfedges[e] == EMPTY || self.is_constrained(e) {
+            return false;
+        }
+        let (u, v, p, q) = self.quad(e);
+        points[u].in_circle(&points[v], &points[p], &points[q])
+    }
+
+    fn mark(&mut self, e: usize, constraint: usize) {
+        self.edge_constraints[e] = constraint;
+        let twin = self.triangulation.halfedges[e];
+        if twin != EMPTY {
+            self.edge_constraints[twin] = constraint;
+        }
+    }
+
+    fn insert_constraint(
+        &mut self,
+        points: &[Point2D],
+        constraint: usize,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        let mut pending = vec![(from, to)];
+        while let Some((i, j)) = pending.pop() {
+            if i == j {
+                continue;
+            }
+            if let Some(e) = self.find_edge(i, j) {
+                self.mark(e, constraint);
+                continue;
+            }
+            let (pi, pj) = (points[i], points[j]);
+            let ahead =
+                |p: &Point2D| (p.x - pi.x) * (pj.x - pi.x) + (p.y - pi.y) * (pj.y - pi.y) > 0f64;
+
+            // find the edges crossed by the constraint, walking from i towards j
+            let mut crossing: Vec<(usize, usize)> = vec![];
+            let mut end = j;
+            let mut current = EMPTY;
+            for e in self.outgoing_edges(i) {
+                let t = &self.triangulation;
+                let a = t.triangles[t.next_halfedge(e)];
+                let b = t.triangles[t.prev_halfedge(e)];
+                let sa = orient2d(&pi, &pj, &points[a]);
+                let sb = orient2d(&pi, &pj, &points[b]);
+                if sa == 0f64 && ahead(&points[a]) {
+                    end = a;
+                    break;
+                }
+                if sb == 0f64 && ahead(&points[b]) {
+                    end = b;
+                    break;
+                }
+                if sa * sb < 0f64
+                    && orient2d(&points[a], &points[b], &pi) * orient2d(&points[a], &points[b], &pj)
+                        < 0f64
+                {
+                    current = t.next_halfedge(e);
+                    break;
+                }
+            }
+            if end == j && current == EMPTY {
+                return false;
+            }
+            while current != EMPTY {
+                if self.is_constrained(current) {
+                    return false;
+                }
+                let t = &self.triangulation;
+                let (s, n) = (t.triangles[current], t.triangles[t.next_halfedge(current)]);
+                let (right, left) = if orient2d(&pi, &pj, &points[s]) < 0f64 {
+                    (s, n)
+                } else {
+                    (n, s)
+                };
+                crossing.push((right, left));
+                let twin = t.halfedges[current];
+                if twin == EMPTY {
+                    return false;
+                }
+                let v = t.triangles[t.prev_halfedge(twin)];
+                if v == j {
+                    break;
+                }
+                let sv = orient2d(&pi, &pj, &points[v]);
+                if sv == 0f64 {
+                    end = v;
+                    break;
+                }
+                let other = if sv > 0f64 { right } else { left };
+                let next = t.next_halfedge(twin);
+                current = if t.triangles[next] == other {
+                    next
+                } else {
+                    t.prev_halfedge(twin)
+                };
+            }
+            if end != j {
+                // the constraint passes through a vertex; split it there
+                pending.push((end, j));
+                if crossing.is_empty() {
+                    pending.push((i, end));
+                    continue;
+                }
+            }
+            let pe = points[end];
+
+            // flip the crossing edges until none remain
+            let max_iterations = 100 + 10 * crossing.len() * crossing.len();
+            let mut queue: VecDeque<(usize, usize)> = crossing.into_iter().collect();
+            let mut new_edges = vec![];
+            let mut iterations = 0;
+            while let Some((u, w)) = queue.pop_front() {
+                iterations += 1;
+                if iterations > max_iterations {
+                    return false;
+                }
+                let e = match self.find_edge(u, w) {
+                    Some(e) => e,
+                    None => return false,
+                };
+                let (_, _, p, q) = self.quad(e);
+                if orient2d(&points[p], &points[q], &points[u])
+                    * orient2d(&points[p], &points[q], &points[w])
+                    < 0f64
+                {
+                    self.flip(e);
+                    let is_constraint = (p == i && q == end) || (p == end && q == i);
+                    if !is_constraint
+                        && p != i
+                        && p != end
+                        && q != i
+                        && q != end
+                        && orient2d(&pi, &pe, &points[p]) * orient2d(&pi, &pe, &points[q]) < 0f64
+                        && orient2d(&points[p], &points[q], &pi)
+                            * orient2d(&points[p], &points[q], &pe)
+                            < 0f64
+                    {
+                        queue.push_back((p, q));
+                    } else if !is_constraint {
+                        new_edges.push((p, q));
+                    }
+                } else {
+                    queue.push_back((u, w));
+                }
+            }
+            match self.find_edge(i, end) {
+                Some(e) => self.mark(e, constraint),
+                None => return false,
+            }
+
+            // restore the Delaunay condition for the new, unconstrained edges
+            let mut swapped = true;
+            iterations = 0;
+            while swapped && iterations < max_iterations {
+                swapped = false;
+                iterations += 1;
+                for k in 0..new_edges.len() {
+                    let (u, w) = new_edges[k];
+                    if let Some(e) = self.find_edge(u, w) {
+                        if self.is_illegal(points, e) {
+                            let (_, _, p, q) = self.quad(e);
+                            self.flip(e);
+                            new_edges[k] = (p, q);
+                            swapped = true;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Triangulates a set of 2D points such that the constraint edges, pairs of indices into
+/// `points`, are edges of the triangulation. The end points of the constraints must be
+/// included in the points. Returns `None` if no triangulation exists for the input
+/// (e.g. all points are collinear).
+pub fn constrained_triangulate(
+    points: &[Point2D],
+    constraints: &[(usize, usize)],
+) -> Option<ConstrainedTriangulation> {
+    let triangulation = triangulate(points)?;
+    let mut point_edges = vec![EMPTY; points.len()];
+    for e in 0..triangulation.triangles.len() {
+        point_edges[triangulation.triangles[e]] = e;
+    }
+
+    // points that duplicate other points are not vertices of the triangulation
+    let mut vertex_map: Vec<usize> = (0..points.len()).collect();
+    if point_edges.iter().any(|&e| e == EMPTY) {
+        let mut locations = HashMap::new();
+        for p in 0..points.len() {
+            if point_edges[p] != EMPTY {
+                locations.insert((points[p].x.to_bits(), points[p].y.to_bits()), p);
+            }
+        }
+        for p in 0..points.len() {
+            if point_edges[p] == EMPTY {
+                vertex_map[p] = *locations
+                    .get(&(points[p].x.to_bits(), points[p].y.to_bits()))
+                    .unwrap_or(&EMPTY);
+            }
+        }
+    }
+
+    let num_edges = triangulation.triangles.len();
+    let mut result = ConstrainedTriangulation {
+        triangulation: triangulation,
+        edge_constraints: vec![EMPTY; num_edges],
+        failed_constraints: vec![],
+        point_edges: point_edges,
+    };
+    for (c, &(from, to)) in constraints.iter().enumerate() {
+        let (from, to) = (vertex_map[from], vertex_map[to]);
+        if from == EMPTY || to == EMPTY || !result.insert_constraint(points, c, from, to) {
+            result.failed_constraints.push(c);
+        }
+    }
+    Some(result)
+}
+
+// twice the signed area of the triangle (a, b, c); positive if c is to the left of a->b
+fn orient2d(a: &Point2D, b: &Point2D, c: &Point2D) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::constrained_triangulate;
+    use crate::algorithms::triangulate;
+    use crate::structures::Point2D;
+
+    #[test]
+    fn test_constrained_triangulate() {
+        // a grid of points, with a constraint that crosses many of the Delaunay edges
+        let mut points = vec![];
+        for row in 0..6 {
+            for col in 0..6 {
+                points.push(Point2D::new(
+                    col as f64 + 0.1 * (row % 2) as f64,
+                    row as f64,
+                ));
+            }
+        }
+        points.push(Point2D::new(0.3, 0.6));
+        points.push(Point2D::new(4.6, 4.3));
+        let (a, b) = (points.len() - 2, points.len() - 1);
+        let cdt = constrained_triangulate(&points, &[(a, b)]).unwrap();
+        assert!(cdt.failed_constraints.is_empty());
+        let e = cdt.find_edge(a, b).unwrap();
+        assert!(cdt.is_constrained(e));
+        // flipping edges does not change the number of triangles
+        assert_eq!(cdt.triangulation.len(), triangulate(&points).unwrap().len());
+    }
+
+    #[test]
+    fn test_mask_triangles() {
+        // a square boundary with a square hole, within a larger set of points
+        let mut points = vec![
+            Point2D::new(-5.0, -5.0),
+            Point2D::new(15.0, -5.0),
+            Point2D::new(15.0, 15.0),
+            Point2D::new(-5.0, 15.0),
+        ];
+        let outer = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let inner = [(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0)];
+        let mut constraints = vec![];
+        for ring in [outer, inner].iter() {
+            let first = points.len();
+            for &(x, y) in ring.iter() {
+                points.push(Point2D::new(x, y));
+            }
+            for k in 0..4 {
+                constraints.push((first + k, first + (k + 1) % 4));
+            }
+        }
+        let cdt = constrained_triangulate(&points, &constraints).unwrap();
+        assert!(cdt.failed_constraints.is_empty());
+        let mask = cdt.mask_triangles(|_| true);
+        let t = &cdt.triangulation;
+        let mut area = 0f64;
+        for triangle in 0..t.triangles.len() / 3 {
+            if mask[triangle] {
+                let p = t.points_of_triangle(triangle);
+                let (a, b, c) = (points[p[0]], points[p[1]], points[p[2]]);
+                area += ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.0;
+            }
+        }
+        assert!((area - 96.0).abs() < 1e-9);
+    }
+}
@@ -6,6 +6,9 @@ Last Modified: 25/09/2018
 License: MIT
 */
 // private sub-module defined in other files
+mod alpha_shape;
+mod buffer;
+mod constrained_delaunay;
 mod convex_hull;
 mod delaunay_triangulation;
 mod is_clockwise_order;
@@ -14,11 +17,19 @@ mod minimum_bounding_box;
 mod poly_area;
 mod poly_ops;
 mod poly_perimeter;
+mod polygon_overlay;
+mod simplify;
 mod smallest_enclosing_circle;
+mod trace_raster_polygons;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::alpha_shape::alpha_shape;
+pub use self::buffer::{
+    buffer_points, buffer_polygon, buffer_polylines, BufferCapStyle, BufferJoinStyle,
+};
+pub use self::constrained_delaunay::{constrained_triangulate, ConstrainedTriangulation};
 pub use self::convex_hull::convex_hull;
-pub use self::delaunay_triangulation::{triangulate, Triangulation};
+pub use self::delaunay_triangulation::{triangulate, Triangulation, EMPTY};
 pub use self::is_clockwise_order::is_clockwise_order;
 pub use self::line_ops::{
     do_polylines_intersect, find_line_intersections, find_split_points_at_line_intersections,
@@ -29,4 +40,7 @@ pub use self::poly_ops::{
     interior_point, point_in_poly, poly_in_poly, poly_is_convex, poly_overlaps_poly, winding_number,
 };
 pub use self::poly_perimeter::polygon_perimeter;
+pub use self::polygon_overlay::{polygon_overlay, OverlayOperation};
+pub use self::simplify::{douglas_peucker, visvalingam_whyatt};
 pub use self::smallest_enclosing_circle::smallest_enclosing_circle;
+pub use self::trace_raster_polygons::trace_raster_polygons;
@@ -0,0 +1,644 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::winding_number;
+use crate::structures::{BoundingBox, Point2D, RTree};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The maximum number of noding passes. Snapping a vertex can occasionally introduce a new
+/// crossing with a neighbouring edge, which is resolved by a further pass.
+const MAX_NODING_PASSES: usize = 8;
+
+/// The boolean set operations supported by `polygon_overlay`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OverlayOperation {
+    /// The area that is within both the subject and the clip polygons.
+    Intersection,
+    /// The area that is within either the subject or the clip polygons.
+    Union,
+    /// The area of the subject polygons that is not within the clip polygons.
+    Difference,
+    /// The area that is within either the subject or the clip polygons, but not both.
+    SymmetricalDifference,
+}
+
+impl OverlayOperation {
+    fn selects(&self, in_subject: bool, in_clip: bool) -> bool {
+        match self {
+            OverlayOperation::Intersection => in_subject && in_clip,
+            OverlayOperation::Union => in_subject || in_clip,
+            OverlayOperation::Difference => in_subject && !in_clip,
+            OverlayOperation::SymmetricalDifference => in_subject != in_clip,
+        }
+    }
+}
+
+/// Performs a boolean overlay of two sets of polygon rings and returns the rings of the
+/// resulting polygons.
+///
+/// Rings follow the Shapefile convention, i.e. exterior rings are in clockwise order and holes
+/// are in counter-clockwise order. Each operand is the union of its rings under the non-zero
+/// winding rule, so an operand may contain the parts of several overlapping polygons, and
+/// self-intersecting rings are resolved rather than propagated into the output. Input rings
+/// may be open or closed.
+///
+/// Vertices that are within `snap_tolerance` of one another are merged, and vertices that are
+/// within `snap_tolerance` of an edge are inserted into that edge, before the rings are split
+/// at their intersections. This removes the sliver polygons that would otherwise be created by
+/// nearly coincident boundaries. Subject vertices take precedence over clip vertices when they
+/// are merged. A tolerance of zero only merges vertices that differ by floating-point noise.
+///
+/// The returned rings are closed and non-overlapping, with each exterior ring (clockwise)
+/// followed by its holes (counter-clockwise). Polygons may touch at a vertex.
+pub fn polygon_overlay(
+    subject: &[Vec<Point2D>],
+    clip: &[Vec<Point2D>],
+    operation: OverlayOperation,
+    snap_tolerance: f64,
+) -> Vec<Vec<Point2D>> {
+    let mut max_coord = 0f64;
+    for ring in subject.iter().chain(clip.iter()) {
+        for p in ring {
+            max_coord = max_coord.max(p.x.abs()).max(p.y.abs());
+        }
+    }
+    let tolerance = snap_tolerance.max(max_coord * 1.0e-12).max(1.0e-12);
+
+    let mut graph = OverlayGraph::new(tolerance);
+    graph.add_rings(subject, 0);
+    graph.add_rings(clip, 1);
+    graph.node();
+    graph.extract(operation)
+}
+
+struct OverlayGraph {
+    tolerance: f64,
+    nodes: Vec<Point2D>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    // (operand, node sequence of an open ring)
+    rings: Vec<(usize, Vec<usize>)>,
+}
+
+impl OverlayGraph {
+    fn new(tolerance: f64) -> OverlayGraph {
+        OverlayGraph {
+            tolerance: tolerance,
+            nodes: vec![],
+            cells: HashMap::new(),
+            rings: vec![],
+        }
+    }
+
+    fn cell_of(&self, p: Point2D) -> (i64, i64) {
+        (
+            (p.x / self.tolerance).floor() as i64,
+            (p.y / self.tolerance).floor() as i64,
+        )
+    }
+
+    /// Returns the node that is nearest to p and within the tolerance, creating a new node at
+    /// p if there is no such node.
+    fn snap(&mut self, p: Point2D) -> usize {
+        let (col, row) = self.cell_of(p);
+        let mut nearest = None;
+        let mut min_dist = self.tolerance;
+        for c in col - 1..=col + 1 {
+            for r in row - 1..=row + 1 {
+                if let Some(ids) = self.cells.get(&(c, r)) {
+                    for &id in ids {
+                        let dist = self.nodes[id].distance(&p);
+                        if dist <= min_dist {
+                            min_dist = dist;
+                            nearest = Some(id);
+                        }
+                    }
+                }
+            }
+        }
+        match nearest {
+            Some(id) => id,
+            None => {
+                let id = self.nodes.len();
+                self.nodes.push(p);
+                self.cells.entry((col, row)).or_insert(vec![]).push(id);
+                id
+            }
+        }
+    }
+
+    fn add_rings(&mut self, rings: &[Vec<Point2D>], operand: usize) {
+        for ring in rings {
+            let nodes: Vec<usize> = ring.iter().map(|p| self.snap(*p)).collect();
+            self.push_ring(operand, nodes);
+        }
+    }
+
+    /// Stores a ring after removing repeated nodes and the closing node; rings that have
+    /// collapsed to fewer than three nodes are discarded.
+    fn push_ring(&mut self, operand: usize, nodes: Vec<usize>) {
+        let mut ring: Vec<usize> = Vec::with_capacity(nodes.len());
+        for n in nodes {
+            if ring.last() != Some(&n) {
+                ring.push(n);
+            }
+        }
+        while ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        if ring.len() >= 3 {
+            self.rings.push((operand, ring));
+        }
+    }
+
+    /// Splits the ring edges at every vertex that lies on another edge and at every crossing,
+    /// repeating until the rings are fully noded.
+    fn node(&mut self) {
+        for _ in 0..MAX_NODING_PASSES {
+            let mut edges: Vec<(usize, usize)> = vec![];
+            for (_, ring) in &self.rings {
+                for k in 0..ring.len() {
+                    edges.push((ring[k], ring[(k + 1) % ring.len()]));
+                }
+            }
+            let boxes: Vec<BoundingBox> = edges
+                .iter()
+                .map(|&(a, b)| {
+                    let mut bb = BoundingBox::from_two_points(self.nodes[a], self.nodes[b]);
+                    bb.expand_by(self.tolerance);
+                    bb
+                })
+                .collect();
+            let tree = RTree::new(&boxes);
+
+            let mut splits: Vec<Vec<usize>> = vec![vec![]; edges.len()];
+            let mut num_splits = 0;
+            for i in 0..edges.len() {
+                for j in tree.search(boxes[i]).into_iter().filter(|&j| j > i) {
+                    let (a, b) = edges[i];
+                    let (c, d) = edges[j];
+                    for &n in &[c, d] {
+                        if n != a && n != b && self.is_on_edge(n, a, b) {
+                            splits[i].push(n);
+                            num_splits += 1;
+                        }
+                    }
+                    for &n in &[a, b] {
+                        if n != c && n != d && self.is_on_edge(n, c, d) {
+                            splits[j].push(n);
+                            num_splits += 1;
+                        }
+                    }
+                    if let Some(p) = self.crossing_point(a, b, c, d) {
+                        let n = self.snap(p);
+                        if n != a && n != b {
+                            splits[i].push(n);
+                            num_splits += 1;
+                        }
+                        if n != c && n != d {
+                            splits[j].push(n);
+                            num_splits += 1;
+                        }
+                    }
+                }
+            }
+            if num_splits == 0 {
+                break;
+            }
+
+            let rings = std::mem::replace(&mut self.rings, vec![]);
+            let mut edge_num = 0;
+            for (operand, ring) in rings {
+                let mut noded: Vec<usize> = Vec::with_capacity(ring.len());
+                for k in 0..ring.len() {
+                    let (a, b) = edges[edge_num];
+                    noded.push(a);
+                    let s = &mut splits[edge_num];
+                    if !s.is_empty() {
+                        let (pa, pb) = (self.nodes[a], self.nodes[b]);
+                        let (dx, dy) = (pb.x - pa.x, pb.y - pa.y);
+                        let param = |n: usize, nodes: &Vec<Point2D>| {
+                            (nodes[n].x - pa.x) * dx + (nodes[n].y - pa.y) * dy
+                        };
+                        s.sort_by(|&m, &n| {
+                            param(m, &self.nodes)
+                                .partial_cmp(&param(n, &self.nodes))
+                                .unwrap()
+                        });
+                        s.dedup();
+                        noded.extend_from_slice(s);
+                    }
+                    edge_num += 1;
+                    debug_assert_eq!(b, ring[(k + 1) % ring.len()]);
+                }
+                self.push_ring(operand, noded);
+            }
+        }
+    }
+
+    /// Tests whether node n is within the tolerance of the interior of edge (a, b).
+    fn is_on_edge(&self, n: usize, a: usize, b: usize) -> bool {
+        let (p, pa, pb) = (self.nodes[n], self.nodes[a], self.nodes[b]);
+        let (dx, dy) = (pb.x - pa.x, pb.y - pa.y);
+        let len_sqr = dx * dx + dy * dy;
+        if len_sqr == 0f64 {
+            return false;
+        }
+        let t = ((p.x - pa.x) * dx + (p.y - pa.y) * dy) / len_sqr;
+        if t <= 0f64 || t >= 1f64 {
+            return false;
+        }
+        let q = Point2D::new(pa.x + t * dx, pa.y + t * dy);
+        p.distance(&q) <= self.tolerance
+    }
+
+    /// Returns the point at which edges (a, b) and (c, d) properly cross, if they do.
+    fn crossing_point(&self, a: usize, b: usize, c: usize, d: usize) -> Option<Point2D> {
+        if a == c || a == d || b == c || b == d {
+            return None;
+        }
+        let (pa, pb, pc, pd) = (self.nodes[a], self.nodes[b], self.nodes[c], self.nodes[d]);
+        let d1 = orientation(&pc, &pd, &pa);
+        let d2 = orientation(&pc, &pd, &pb);
+        let d3 = orientation(&pa, &pb, &pc);
+        let d4 = orientation(&pa, &pb, &pd);
+        if d1 * d2 < 0f64 && d3 * d4 < 0f64 {
+            let t = d1 / (d1 - d2);
+            Some(Point2D::new(
+                pa.x + t * (pb.x - pa.x),
+                pa.y + t * (pb.y - pa.y),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Builds the planar graph of the noded rings, labels each face with its winding number
+    /// with respect to each operand, and traces the boundary of the selected faces.
+    fn extract(&self, operation: OverlayOperation) -> Vec<Vec<Point2D>> {
+        // The winding depth of each undirected edge (a < b) is the number of times that the
+        // rings of each operand traverse it from a to b, less the number of times from b to a.
+        // It is the difference in winding number between the faces on its left and right.
+        // Edges with zero depth, e.g. boundaries shared by adjacent polygons of the same
+        // operand, do not separate faces and are left out of the graph.
+        let mut depths: HashMap<(usize, usize), [i32; 2]> = HashMap::new();
+        for (operand, ring) in &self.rings {
+            for k in 0..ring.len() {
+                let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                if a < b {
+                    depths.entry((a, b)).or_insert([0, 0])[*operand] += 1;
+                } else {
+                    depths.entry((b, a)).or_insert([0, 0])[*operand] -= 1;
+                }
+            }
+        }
+        let mut edges: Vec<((usize, usize), [i32; 2])> = depths
+            .into_iter()
+            .filter(|(_, d)| d[0] != 0 || d[1] != 0)
+            .collect();
+        if edges.is_empty() {
+            return vec![];
+        }
+        edges.sort_by(|e1, e2| e1.0.cmp(&e2.0));
+
+        // Half-edge 2e runs from a to b and half-edge 2e + 1 from b to a.
+        let num_half_edges = edges.len() * 2;
+        let origin = |h: usize| {
+            let (a, b) = edges[h / 2].0;
+            if h % 2 == 0 {
+                a
+            } else {
+                b
+            }
+        };
+        let depth = |h: usize, operand: usize| {
+            let d = edges[h / 2].1[operand];
+            if h % 2 == 0 {
+                d
+            } else {
+                -d
+            }
+        };
+        let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+        for h in 0..num_half_edges {
+            outgoing.entry(origin(h)).or_insert(vec![]).push(h);
+        }
+        let angle = |h: usize| {
+            let p = self.nodes[origin(h)];
+            let q = self.nodes[origin(h ^ 1)];
+            (q.y - p.y).atan2(q.x - p.x)
+        };
+        let mut position = vec![0usize; num_half_edges];
+        for hs in outgoing.values_mut() {
+            hs.sort_by(|&h1, &h2| angle(h1).partial_cmp(&angle(h2)).unwrap());
+            for (i, &h) in hs.iter().enumerate() {
+                position[h] = i;
+            }
+        }
+        // The next half-edge around the face on the left is the first outgoing half-edge
+        // clockwise from the twin.
+        let clockwise_from = |h: usize| {
+            let hs = &outgoing[&origin(h)];
+            hs[(position[h] + hs.len() - 1) % hs.len()]
+        };
+        let next = |h: usize| clockwise_from(h ^ 1);
+
+        // Trace the cycles of half-edges. Counter-clockwise cycles are the outer boundaries
+        // of bounded faces; clockwise cycles are the outer boundaries of connected components.
+        let mut cycle_of = vec![usize::max_value(); num_half_edges];
+        let mut cycles: Vec<Vec<usize>> = vec![];
+        for h in 0..num_half_edges {
+            if cycle_of[h] != usize::max_value() {
+                continue;
+            }
+            let mut cycle = vec![];
+            let mut e = h;
+            while cycle_of[e] == usize::max_value() {
+                cycle_of[e] = cycles.len();
+                cycle.push(e);
+                e = next(e);
+            }
+            cycles.push(cycle);
+        }
+        let cycle_points = |cycle: &Vec<usize>| {
+            let mut points: Vec<Point2D> = cycle.iter().map(|&h| self.nodes[origin(h)]).collect();
+            points.push(points[0]);
+            points
+        };
+        let cycle_area: Vec<f64> = cycles
+            .iter()
+            .map(|c| signed_area(&cycle_points(c)))
+            .collect();
+
+        // Connected components, used to find the face that encloses each clockwise cycle.
+        let mut component: Vec<usize> = (0..self.nodes.len()).collect();
+        fn find(component: &mut Vec<usize>, n: usize) -> usize {
+            let mut root = n;
+            while component[root] != root {
+                root = component[root];
+            }
+            let mut m = n;
+            while component[m] != root {
+                let parent = component[m];
+                component[m] = root;
+                m = parent;
+            }
+            root
+        }
+        for ((a, b), _) in &edges {
+            let (ra, rb) = (find(&mut component, *a), find(&mut component, *b));
+            component[ra] = rb;
+        }
+
+        // Face 0 is the unbounded face.
+        let mut face_of_cycle = vec![0usize; cycles.len()];
+        let mut num_faces = 1;
+        let mut bounded: Vec<usize> = vec![];
+        for c in 0..cycles.len() {
+            if cycle_area[c] > 0f64 {
+                face_of_cycle[c] = num_faces;
+                num_faces += 1;
+                bounded.push(c);
+            }
+        }
+        let bounded_points: Vec<Vec<Point2D>> =
+            bounded.iter().map(|&c| cycle_points(&cycles[c])).collect();
+        let bounded_tree = RTree::new(
+            &bounded_points
+                .iter()
+                .map(|points| BoundingBox::from_points(points))
+                .collect::<Vec<BoundingBox>>(),
+        );
+        for c in 0..cycles.len() {
+            if cycle_area[c] > 0f64 {
+                continue;
+            }
+            let n = origin(cycles[c][0]);
+            let comp = find(&mut component, n);
+            let p = self.nodes[n];
+            let mut min_area = f64::INFINITY;
+            for i in bounded_tree.search_point(p.x, p.y) {
+                let b = bounded[i];
+                if cycle_area[b] < min_area
+                    && find(&mut component, origin(cycles[b][0])) != comp
+                    && winding_number(&p, &bounded_points[i]) != 0
+                {
+                    min_area = cycle_area[b];
+                    face_of_cycle[c] = face_of_cycle[b];
+                }
+            }
+        }
+        let face = |h: usize| face_of_cycle[cycle_of[h]];
+
+        // Propagate the winding numbers outward from the unbounded face.
+        let mut face_edges: Vec<Vec<usize>> = vec![vec![]; num_faces];
+        for h in 0..num_half_edges {
+            face_edges[face(h)].push(h);
+        }
+        let mut winding: Vec<Option<[i32; 2]>> = vec![None; num_faces];
+        winding[0] = Some([0, 0]);
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+        while let Some(f) = queue.pop_front() {
+            let w = winding[f].unwrap();
+            for &h in &face_edges[f] {
+                let g = face(h ^ 1);
+                if winding[g].is_none() {
+                    winding[g] = Some([w[0] - depth(h, 0), w[1] - depth(h, 1)]);
+                    queue.push_back(g);
+                }
+            }
+        }
+        let selected: Vec<bool> = winding
+            .iter()
+            .map(|w| match w {
+                Some(w) => operation.selects(w[0] != 0, w[1] != 0),
+                None => false,
+            })
+            .collect();
+
+        // Trace the boundary of the selected region, keeping it on the left.
+        let is_boundary = |h: usize| selected[face(h)] && !selected[face(h ^ 1)];
+        let mut visited = vec![false; num_half_edges];
+        let mut exteriors: Vec<(Vec<usize>, f64)> = vec![];
+        let mut holes: Vec<(Vec<usize>, f64)> = vec![];
+        for h in 0..num_half_edges {
+            if visited[h] || !is_boundary(h) {
+                continue;
+            }
+            let mut ring = vec![];
+            let mut e = h;
+            while !visited[e] {
+                visited[e] = true;
+                ring.push(origin(e));
+                let mut f = clockwise_from(e ^ 1);
+                while !is_boundary(f) {
+                    f = clockwise_from(f);
+                }
+                e = f;
+            }
+            let points: Vec<Point2D> = ring
+                .iter()
+                .chain(ring.first())
+                .map(|&n| self.nodes[n])
+                .collect();
+            let area = signed_area(&points);
+            if area > 0f64 {
+                exteriors.push((ring, area));
+            } else if area < 0f64 {
+                holes.push((ring, -area));
+            }
+        }
+
+        // Assign each hole to the smallest exterior ring that contains it.
+        let exterior_points: Vec<Vec<Point2D>> = exteriors
+            .iter()
+            .map(|(r, _)| r.iter().chain(r.first()).map(|&n| self.nodes[n]).collect())
+            .collect();
+        let exterior_nodes: Vec<HashSet<usize>> = exteriors
+            .iter()
+            .map(|(r, _)| r.iter().cloned().collect())
+            .collect();
+        let exterior_tree = RTree::new(
+            &exterior_points
+                .iter()
+                .map(|points| BoundingBox::from_points(points))
+                .collect::<Vec<BoundingBox>>(),
+        );
+        let mut holes_of: Vec<Vec<usize>> = vec![vec![]; exteriors.len()];
+        for (i, (hole, hole_area)) in holes.iter().enumerate() {
+            let mut container = None;
+            let mut min_area = f64::INFINITY;
+            let p = self.nodes[hole[0]];
+            for j in exterior_tree.search_point(p.x, p.y) {
+                let area = exteriors[j].1;
+                if area <= *hole_area || area >= min_area {
+                    continue;
+                }
+                if let Some(&n) = hole.iter().find(|n| !exterior_nodes[j].contains(n)) {
+                    if winding_number(&self.nodes[n], &exterior_points[j]) != 0 {
+                        container = Some(j);
+                        min_area = area;
+                    }
+                }
+            }
+            if let Some(j) = container {
+                holes_of[j].push(i);
+            }
+        }
+
+        // Output in the Shapefile convention: clockwise exteriors and counter-clockwise holes.
+        let mut ret = vec![];
+        for (j, (exterior, _)) in exteriors.iter().enumerate() {
+            ret.push(self.closed_ring(exterior));
+            for &i in &holes_of[j] {
+                ret.push(self.closed_ring(&holes[i].0));
+            }
+        }
+        ret
+    }
+
+    /// Returns the points of a traced ring, closed and with its orientation reversed.
+    fn closed_ring(&self, ring: &[usize]) -> Vec<Point2D> {
+        let mut points: Vec<Point2D> = ring.iter().rev().map(|&n| self.nodes[n]).collect();
+        points.push(points[0]);
+        points
+    }
+}
+
+/// Returns the signed area of a closed ring, positive for counter-clockwise rings.
+fn signed_area(points: &[Point2D]) -> f64 {
+    let mut area = 0f64;
+    for i in 0..points.len() - 1 {
+        area += points[i].x * points[i + 1].y - points[i + 1].x * points[i].y;
+    }
+    area / 2f64
+}
+
+fn orientation(p0: &Point2D, p1: &Point2D, p2: &Point2D) -> f64 {
+    (p1.x - p0.x) * (p2.y - p0.y) - (p2.x - p0.x) * (p1.y - p0.y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{polygon_overlay, signed_area, OverlayOperation};
+    use crate::structures::Point2D;
+
+    fn square(min_x: f64, min_y: f64, size: f64) -> Vec<Point2D> {
+        // clockwise, as a Shapefile exterior ring
+        vec![
+            Point2D::new(min_x, min_y),
+            Point2D::new(min_x, min_y + size),
+            Point2D::new(min_x + size, min_y + size),
+            Point2D::new(min_x + size, min_y),
+            Point2D::new(min_x, min_y),
+        ]
+    }
+
+    fn area(rings: &[Vec<Point2D>]) -> f64 {
+        // exteriors are clockwise (negative) and holes counter-clockwise (positive)
+        -rings.iter().map(|r| signed_area(r)).sum::<f64>()
+    }
+
+    #[test]
+    fn test_polygon_overlay_operations() {
+        let a = vec![square(0.0, 0.0, 2.0)];
+        let b = vec![square(1.0, 1.0, 2.0)];
+        let result = polygon_overlay(&a, &b, OverlayOperation::Intersection, 0.0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(area(&result), 1.0);
+        let result = polygon_overlay(&a, &b, OverlayOperation::Union, 0.0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(area(&result), 7.0);
+        let result = polygon_overlay(&a, &b, OverlayOperation::Difference, 0.0);
+        assert_eq!(area(&result), 3.0);
+        let result = polygon_overlay(&a, &b, OverlayOperation::SymmetricalDifference, 0.0);
+        assert_eq!(result.len(), 2);
+        assert_eq!(area(&result), 6.0);
+    }
+
+    #[test]
+    fn test_polygon_overlay_coincident_and_holes() {
+        // identical polygons
+        let a = vec![square(0.0, 0.0, 4.0)];
+        let result = polygon_overlay(&a, &a, OverlayOperation::Intersection, 0.0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(area(&result), 16.0);
+        assert!(polygon_overlay(&a, &a, OverlayOperation::Difference, 0.0).is_empty());
+
+        // a square with a hole, minus a square sharing part of an edge with the hole
+        let mut hole = square(1.0, 1.0, 2.0);
+        hole.reverse();
+        let a = vec![square(0.0, 0.0, 4.0), hole];
+        let b = vec![square(3.0, 1.0, 0.5)];
+        let result = polygon_overlay(&a, &b, OverlayOperation::Difference, 0.0);
+        assert_eq!(area(&result), 11.75);
+        assert_eq!(result.len(), 2);
+        assert!(signed_area(&result[0]) < 0.0);
+        assert!(signed_area(&result[1]) > 0.0);
+
+        // adjacent clip polygons are dissolved
+        let b = vec![square(0.0, 0.0, 2.0), square(2.0, 0.0, 2.0)];
+        let result = polygon_overlay(&b, &[], OverlayOperation::Union, 0.0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 7);
+    }
+
+    #[test]
+    fn test_polygon_overlay_snapping() {
+        // nearly coincident edges leave a sliver unless they are snapped together
+        let a = vec![square(0.0, 0.0, 2.0)];
+        let b = vec![square(2.0 - 1.0e-7, 0.5, 1.0)];
+        let result = polygon_overlay(&a, &b, OverlayOperation::Intersection, 0.0);
+        assert_eq!(result.len(), 1);
+        let result = polygon_overlay(&a, &b, OverlayOperation::Intersection, 1.0e-6);
+        assert!(result.is_empty());
+        let result = polygon_overlay(&a, &b, OverlayOperation::Union, 1.0e-6);
+        assert_eq!(result.len(), 1);
+        assert!((area(&result) - 5.0).abs() < 1.0e-5);
+    }
+}
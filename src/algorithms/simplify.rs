@@ -0,0 +1,239 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::structures::Point2D;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Simplifies a line using the Douglas-Peucker algorithm, removing vertices that are within
+/// `tolerance` of the simplified line. The first and last points are always retained. If the
+/// line is closed (i.e. its first and last points are the same), the vertex furthest from the
+/// start point is also retained.
+pub fn douglas_peucker(points: &[Point2D], tolerance: f64) -> Vec<Point2D> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+    let mut stack = vec![];
+    if points[0] == points[n - 1] {
+        let mut furthest = 0;
+        let mut max_dist = 0f64;
+        for i in 1..n - 1 {
+            let dist = points[i].distance(&points[0]);
+            if dist > max_dist {
+                furthest = i;
+                max_dist = dist;
+            }
+        }
+        if furthest == 0 {
+            return vec![points[0], points[n - 1]];
+        }
+        keep[furthest] = true;
+        stack.push((0, furthest));
+        stack.push((furthest, n - 1));
+    } else {
+        stack.push((0, n - 1));
+    }
+
+    while let Some((start, end)) = stack.pop() {
+        let mut furthest = start;
+        let mut max_dist = tolerance;
+        for i in start + 1..end {
+            let dist = distance_to_segment(&points[i], &points[start], &points[end]);
+            if dist > max_dist {
+                furthest = i;
+                max_dist = dist;
+            }
+        }
+        if furthest != start {
+            keep[furthest] = true;
+            stack.push((start, furthest));
+            stack.push((furthest, end));
+        }
+    }
+
+    (0..n).filter(|i| keep[*i]).map(|i| points[i]).collect()
+}
+
+/// Simplifies a line using the Visvalingam-Whyatt algorithm, repeatedly removing the vertex
+/// with the smallest effective area, i.e. the area of the triangle that it forms with its
+/// neighbours, until no vertex has an effective area smaller than `min_area`. The first and
+/// last points are always retained. If the line is closed, the vertex furthest from the start
+/// point is also retained, and vertices are only removed while more than three distinct vertices
+/// remain, so that a ring never collapses.
+pub fn visvalingam_whyatt(points: &[Point2D], min_area: f64) -> Vec<Point2D> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    let mut fixed = vec![false; n];
+    fixed[0] = true;
+    fixed[n - 1] = true;
+    let mut num_remaining = n;
+    let mut min_remaining = 2;
+    if points[0] == points[n - 1] {
+        // the closing point duplicates the start point
+        min_remaining = 4;
+        let mut furthest = 0;
+        let mut max_dist = 0f64;
+        for i in 1..n - 1 {
+            let dist = points[i].distance(&points[0]);
+            if dist > max_dist {
+                furthest = i;
+                max_dist = dist;
+            }
+        }
+        fixed[furthest] = true;
+    }
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+    let mut removed = vec![false; n];
+    // the current effective area of each vertex, used to recognize out-of-date heap entries
+    let mut areas = vec![std::f64::INFINITY; n];
+    let mut heap = BinaryHeap::with_capacity(n);
+    for i in (1..n - 1).filter(|i| !fixed[*i]) {
+        areas[i] = triangle_area(&points[i - 1], &points[i], &points[i + 1]);
+        heap.push(Vertex {
+            index: i,
+            area: areas[i],
+        });
+    }
+
+    // Vertices are removed in order of increasing effective area. A vertex whose area would
+    // drop below that of an already removed vertex is given that area instead, so that it is
+    // not removed before the vertices whose removal made it insignificant.
+    let mut max_removed_area = 0f64;
+    while let Some(vertex) = heap.pop() {
+        let i = vertex.index;
+        if removed[i] || vertex.area != areas[i] {
+            continue;
+        }
+        if vertex.area >= min_area || num_remaining <= min_remaining {
+            break;
+        }
+        removed[i] = true;
+        num_remaining -= 1;
+        max_removed_area = max_removed_area.max(vertex.area);
+        let (p, q) = (prev[i], next[i]);
+        next[p] = q;
+        prev[q] = p;
+        for &j in &[p, q] {
+            if !fixed[j] {
+                areas[j] = triangle_area(&points[prev[j]], &points[j], &points[next[j]])
+                    .max(max_removed_area);
+                heap.push(Vertex {
+                    index: j,
+                    area: areas[j],
+                });
+            }
+        }
+    }
+
+    (0..n).filter(|i| !removed[*i]).map(|i| points[i]).collect()
+}
+
+fn distance_to_segment(p: &Point2D, a: &Point2D, b: &Point2D) -> f64 {
+    let len_sqrd = a.distance_squared(b);
+    if len_sqrd == 0f64 {
+        return p.distance(a);
+    }
+    let t = (((p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y)) / len_sqrd)
+        .max(0f64)
+        .min(1f64);
+    p.distance(&Point2D::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)))
+}
+
+fn triangle_area(a: &Point2D, b: &Point2D, c: &Point2D) -> f64 {
+    ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2f64
+}
+
+#[derive(PartialEq, Debug)]
+struct Vertex {
+    index: usize,
+    area: f64,
+}
+
+impl Eq for Vertex {}
+
+impl PartialOrd for Vertex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Vertex {
+    fn cmp(&self, other: &Vertex) -> Ordering {
+        // reversed, so that the heap is a min-heap of areas
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{douglas_peucker, visvalingam_whyatt};
+    use crate::structures::Point2D;
+
+    #[test]
+    fn test_simplify_line() {
+        let line = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(1.0, 0.1),
+            Point2D::new(2.0, -0.1),
+            Point2D::new(3.0, 0.0),
+            Point2D::new(3.1, 1.0),
+            Point2D::new(2.9, 2.0),
+            Point2D::new(3.0, 3.0),
+        ];
+        let expected = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(3.0, 0.0),
+            Point2D::new(3.0, 3.0),
+        ];
+        assert_eq!(douglas_peucker(&line, 0.5), expected);
+        assert_eq!(visvalingam_whyatt(&line, 1.0), expected);
+        assert_eq!(douglas_peucker(&line, 0.0).len(), line.len());
+        assert_eq!(visvalingam_whyatt(&line, 0.0).len(), line.len());
+    }
+
+    #[test]
+    fn test_simplify_closed_line() {
+        let ring = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.1, 5.0),
+            Point2D::new(0.0, 10.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(0.0, 0.0),
+        ];
+        let simplified = douglas_peucker(&ring, 0.5);
+        assert_eq!(simplified.len(), 5);
+        assert!(!simplified.contains(&Point2D::new(0.1, 5.0)));
+        let simplified = visvalingam_whyatt(&ring, 1.0);
+        assert_eq!(simplified.len(), 5);
+        assert!(!simplified.contains(&Point2D::new(0.1, 5.0)));
+
+        // the ring is reduced to a triangle of the start point, the opposite corner, and one
+        // other corner, however large the tolerance is
+        assert_eq!(
+            visvalingam_whyatt(&ring, 1000.0),
+            vec![
+                Point2D::new(0.0, 0.0),
+                Point2D::new(10.0, 10.0),
+                Point2D::new(10.0, 0.0),
+                Point2D::new(0.0, 0.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::structures::{Array2D, Point2D};
+use std::collections::{BTreeMap, HashMap};
+
+/// Traces the boundaries of the regions of equal, non-zero value within a grid of labels
+/// and returns the polygon rings of each region, keyed by label value. Grid cells with a
+/// value of zero are treated as background. The grid is georeferenced using the coordinates
+/// of its north-west corner (`west`, `north`) and the cell sizes (`res_x`, `res_y`).
+///
+/// Rings follow the Shapefile convention, i.e. exterior rings are in clockwise order and
+/// holes are in counter-clockwise order, and each ring is closed (the first and last points
+/// are the same). Cells of the same label that only touch diagonally are returned as separate
+/// rings of a multipart polygon. Vertices that are collinear with their neighbours are removed.
+pub fn trace_raster_polygons(
+    labels: &Array2D<u32>,
+    west: f64,
+    north: f64,
+    res_x: f64,
+    res_y: f64,
+) -> BTreeMap<u32, Vec<Vec<Point2D>>> {
+    let rows = labels.rows();
+    let columns = labels.columns();
+
+    // Directed cell edges, stored as (label, from vertex, to vertex), where vertices are
+    // (row, column) grid-line intersections. Edges are directed such that the region is on
+    // their right-hand side, which yields clockwise exterior rings.
+    let mut edges: Vec<(u32, (isize, isize), (isize, isize))> = vec![];
+    let mut z: u32;
+    for row in 0..rows {
+        for col in 0..columns {
+            z = labels.get_value(row, col);
+            if z == 0 {
+                continue;
+            }
+            if row == 0 || labels.get_value(row - 1, col) != z {
+                edges.push((z, (row, col), (row, col + 1))); // top
+            }
+            if col == columns - 1 || labels.get_value(row, col + 1) != z {
+                edges.push((z, (row, col + 1), (row + 1, col + 1))); // right
+            }
+            if row == rows - 1 || labels.get_value(row + 1, col) != z {
+                edges.push((z, (row + 1, col + 1), (row + 1, col))); // bottom
+            }
+            if col == 0 || labels.get_value(row, col - 1) != z {
+                edges.push((z, (row + 1, col), (row, col))); // left
+            }
+        }
+    }
+
+    let mut outgoing: HashMap<(u32, isize, isize), Vec<usize>> = HashMap::new();
+    for (i, e) in edges.iter().enumerate() {
+        outgoing
+            .entry((e.0, (e.1).0, (e.1).1))
+            .or_insert(vec![])
+            .push(i);
+    }
+
+    let to_point = |v: (isize, isize)| -> Point2D {
+        Point2D::new(west + v.1 as f64 * res_x, north - v.0 as f64 * res_y)
+    };
+
+    let mut polygons: BTreeMap<u32, Vec<Vec<Point2D>>> = BTreeMap::new();
+    let mut used = vec![false; edges.len()];
+    for first_edge in 0..edges.len() {
+        if used[first_edge] {
+            continue;
+        }
+        let label = edges[first_edge].0;
+        let mut vertices: Vec<(isize, isize)> = vec![];
+        let mut current = first_edge;
+        loop {
+            used[current] = true;
+            let (_, from, to) = edges[current];
+            vertices.push(from);
+            // in map coordinates, with y increasing upwards
+            let dir_in = ((to.1 - from.1) as f64, (from.0 - to.0) as f64);
+            let mut next_edge: Option<usize> = None;
+            if let Some(candidates) = outgoing.get(&(label, to.0, to.1)) {
+                for &c in candidates {
+                    if used[c] {
+                        continue;
+                    }
+                    match next_edge {
+                        None => next_edge = Some(c),
+                        Some(_) => {
+                            // Two edges leave a vertex only where cells touch diagonally. Taking the
+                            // right-hand turn keeps the rings of the two cells separate.
+                            let (_, c_from, c_to) = edges[c];
+                            let dir_out = ((c_to.1 - c_from.1) as f64, (c_from.0 - c_to.0) as f64);
+                            if dir_in.0 * dir_out.1 - dir_in.1 * dir_out.0 < 0f64 {
+                                next_edge = Some(c);
+                            }
+                        }
+                    }
+                }
+            }
+            match next_edge {
+                Some(e) => current = e,
+                None => break, // the ring is closed
+            }
+        }
+
+        // Remove the vertices that are collinear with their neighbours.
+        let n = vertices.len();
+        let mut ring: Vec<Point2D> = Vec::with_capacity(n + 1);
+        for i in 0..n {
+            let prev = vertices[(i + n - 1) % n];
+            let v = vertices[i];
+            let next = vertices[(i + 1) % n];
+            let cross = (v.0 - prev.0) * (next.1 - v.1) - (v.1 - prev.1) * (next.0 - v.0);
+            if cross != 0 {
+                ring.push(to_point(v));
+            }
+        }
+        if ring.len() < 3 {
+            continue;
+        }
+        ring.push(ring[0]);
+        polygons.entry(label).or_insert(vec![]).push(ring);
+    }
+
+    polygons
+}
+
+#[cfg(test)]
+mod test {
+    use super::trace_raster_polygons;
+    use crate::algorithms::is_clockwise_order;
+    use crate::structures::Array2D;
+
+    #[test]
+    fn test_trace_raster_polygons_with_hole() {
+        let mut labels: Array2D<u32> = Array2D::new(3, 3, 1u32, 0u32).unwrap();
+        labels.set_value(1, 1, 0u32);
+        let polys = trace_raster_polygons(&labels, 0f64, 3f64, 1f64, 1f64);
+        let rings = &polys[&1];
+        assert_eq!(rings.len(), 2);
+        let (exterior, hole) = if rings[0].iter().any(|p| p.x == 0f64) {
+            (&rings[0], &rings[1])
+        } else {
+            (&rings[1], &rings[0])
+        };
+        assert_eq!(exterior.len(), 5);
+        assert_eq!(hole.len(), 5);
+        assert!(is_clockwise_order(exterior));
+        assert!(!is_clockwise_order(hole));
+    }
+
+    #[test]
+    fn test_trace_raster_polygons_diagonal_cells() {
+        let mut labels: Array2D<u32> = Array2D::new(2, 2, 0u32, 0u32).unwrap();
+        labels.set_value(0, 0, 7u32);
+        labels.set_value(1, 1, 7u32);
+        labels.set_value(0, 1, 3u32);
+        let polys = trace_raster_polygons(&labels, 10f64, 20f64, 2f64, 2f64);
+        assert_eq!(polys[&7].len(), 2);
+        assert_eq!(polys[&3].len(), 1);
+        for ring in &polys[&7] {
+            assert_eq!(ring.len(), 5);
+            assert!(is_clockwise_order(ring));
+        }
+        assert_eq!(polys[&3][0][0].x.min(polys[&3][0][1].x), 12f64);
+    }
+}
@@ -0,0 +1,672 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::point_cloud::{map_colour, map_intensity, PointCloudData};
+use super::point_data::ColourData;
+use byteorder::{ByteOrder, LittleEndian};
+use std::fs;
+use std::io::{Error, ErrorKind};
+
+/// Reads the point data of an ASTM E57 file. Each of the file's scans (the children of
+/// the `data3D` vector) is transformed by its pose into the file's coordinate system.
+/// Cartesian or spherical coordinates, intensity, colour, return numbering, and normals
+/// (the `nor` extension) are mapped into the point records and points flagged as
+/// invalid are skipped. Only bitpacked point data, the default E57 codec, are supported.
+pub fn read_e57(file_name: &str) -> Result<PointCloudData, Error> {
+    let buffer = fs::read(file_name)?;
+    if buffer.len() < 48 || &buffer[0..8] != b"ASTM-E57" {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} does not appear to be an E57 file.", file_name),
+        ));
+    }
+    let xml_offset = LittleEndian::read_u64(&buffer[24..32]) as usize;
+    let xml_length = LittleEndian::read_u64(&buffer[32..40]) as usize;
+    let page_size = LittleEndian::read_u64(&buffer[40..48]) as usize;
+    if page_size <= 4 {
+        return Err(invalid_data("The E57 file has an invalid page size."));
+    }
+
+    // Each page ends with a 4-byte checksum. Removing these gives the logical file.
+    let mut logical = Vec::with_capacity(buffer.len());
+    for page in buffer.chunks(page_size) {
+        logical.extend_from_slice(&page[..page.len().min(page_size - 4)]);
+    }
+    let to_logical =
+        |physical: usize| physical / page_size * (page_size - 4) + physical % page_size;
+
+    let xml_start = to_logical(xml_offset);
+    if xml_start + xml_length > logical.len() {
+        return Err(invalid_data(
+            "The E57 XML section extends beyond the end of the file.",
+        ));
+    }
+    let xml = String::from_utf8_lossy(&logical[xml_start..xml_start + xml_length]).to_string();
+    let root = parse_xml(&xml)?;
+
+    let mut data = PointCloudData::default();
+    let scans = match root.child("data3D") {
+        Some(d) => &d.children,
+        None => return Ok(data),
+    };
+
+    let mut intensity = vec![];
+    let mut intensity_range: Option<(f64, f64)> = None;
+    let mut intensity_is_float = false;
+    let mut has_intensity = true;
+    let mut has_colour = true;
+    let mut has_normals = true;
+    for scan in scans {
+        let points = match scan.child("points") {
+            Some(p) => p,
+            None => continue,
+        };
+        let record_count = points
+            .attribute("recordCount")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        let file_offset = points
+            .attribute("fileOffset")
+            .and_then(|v| v.parse::<usize>().ok())
+            .ok_or_else(|| invalid_data("E57 points element is missing a fileOffset."))?;
+        if let Some(codecs) = points.child("codecs") {
+            if !codecs.children.is_empty() {
+                return Err(invalid_data("Unsupported E57 point data codec."));
+            }
+        }
+        let prototype = points
+            .child("prototype")
+            .ok_or_else(|| invalid_data("E57 points element is missing a prototype."))?;
+        let fields = prototype
+            .children
+            .iter()
+            .map(|e| Field::new(e))
+            .collect::<Result<Vec<Field>, Error>>()?;
+        let streams = read_compressed_vector(
+            &logical,
+            to_logical(file_offset),
+            &to_logical,
+            &fields,
+            record_count,
+        )?;
+
+        let field_index = |name: &str| fields.iter().position(|f| f.name == name);
+        let value = |f: Option<usize>, k: usize| match f {
+            Some(f) => fields[f].value(&streams[f], k),
+            None => 0f64,
+        };
+
+        let cartesian = [
+            field_index("cartesianX"),
+            field_index("cartesianY"),
+            field_index("cartesianZ"),
+        ];
+        let spherical = [
+            field_index("sphericalRange"),
+            field_index("sphericalAzimuth"),
+            field_index("sphericalElevation"),
+        ];
+        let is_cartesian = cartesian.iter().all(|f| f.is_some());
+        if !is_cartesian && !spherical.iter().all(|f| f.is_some()) {
+            return Err(invalid_data(
+                "E57 scans must contain either cartesian or spherical coordinates.",
+            ));
+        }
+        let invalid_state = if is_cartesian {
+            field_index("cartesianInvalidState")
+        } else {
+            field_index("sphericalInvalidState")
+        };
+        let intensity_field = field_index("intensity");
+        let colour_fields = [
+            field_index("colorRed"),
+            field_index("colorGreen"),
+            field_index("colorBlue"),
+        ];
+        let normal_fields = [
+            field_index("nor:normalX"),
+            field_index("nor:normalY"),
+            field_index("nor:normalZ"),
+        ];
+        let return_index = field_index("returnIndex");
+        let return_count = field_index("returnCount");
+        has_intensity = has_intensity && intensity_field.is_some();
+        has_colour = has_colour && colour_fields.iter().all(|f| f.is_some());
+        has_normals = has_normals && normal_fields.iter().all(|f| f.is_some());
+
+        // the intensity and colour value ranges
+        if let Some(f) = intensity_field {
+            let range = match scan.child("intensityLimits") {
+                Some(limits) => limit_range(limits, "intensityMinimum", "intensityMaximum"),
+                None => fields[f].range(),
+            };
+            intensity_is_float = intensity_is_float || fields[f].is_float();
+            if let Some((min, max)) = range {
+                intensity_range = match intensity_range {
+                    Some((a, b)) => Some((a.min(min), b.max(max))),
+                    None => Some((min, max)),
+                };
+            }
+        }
+        let mut colour_ranges = [None; 3];
+        let channels = [
+            ("colorRedMinimum", "colorRedMaximum"),
+            ("colorGreenMinimum", "colorGreenMaximum"),
+            ("colorBlueMinimum", "colorBlueMaximum"),
+        ];
+        for c in 0..3 {
+            if let Some(f) = colour_fields[c] {
+                colour_ranges[c] = match scan.child("colorLimits") {
+                    Some(limits) => limit_range(limits, channels[c].0, channels[c].1),
+                    None => fields[f].range(),
+                };
+            }
+        }
+
+        let pose = Pose::new(scan.child("pose"));
+        for k in 0..record_count {
+            if invalid_state.is_some() && value(invalid_state, k) != 0f64 {
+                continue;
+            }
+            let mut xyz = if is_cartesian {
+                [
+                    value(cartesian[0], k),
+                    value(cartesian[1], k),
+                    value(cartesian[2], k),
+                ]
+            } else {
+                let range = value(spherical[0], k);
+                let azimuth = value(spherical[1], k);
+                let elevation = value(spherical[2], k);
+                [
+                    range * elevation.cos() * azimuth.cos(),
+                    range * elevation.cos() * azimuth.sin(),
+                    range * elevation.sin(),
+                ]
+            };
+            xyz = pose.rotate(xyz);
+            let p = data.add_point(
+                xyz[0] + pose.translation[0],
+                xyz[1] + pose.translation[1],
+                xyz[2] + pose.translation[2],
+            );
+            if return_index.is_some() && return_count.is_some() {
+                let n = value(return_count, k).max(1f64).min(7f64) as u8;
+                let r = (value(return_index, k) + 1f64).max(1f64).min(n as f64) as u8;
+                p.set_number_of_returns(n);
+                p.set_return_number(r);
+            }
+            if has_intensity {
+                intensity.push(value(intensity_field, k));
+            }
+            if has_colour {
+                data.colour_data.push(ColourData {
+                    red: map_colour(value(colour_fields[0], k), colour_ranges[0]),
+                    green: map_colour(value(colour_fields[1], k), colour_ranges[1]),
+                    blue: map_colour(value(colour_fields[2], k), colour_ranges[2]),
+                    nir: 0u16,
+                });
+            }
+            if has_normals {
+                let n = pose.rotate([
+                    value(normal_fields[0], k),
+                    value(normal_fields[1], k),
+                    value(normal_fields[2], k),
+                ]);
+                data.normal_data
+                    .push([n[0] as f32, n[1] as f32, n[2] as f32]);
+            }
+        }
+    }
+
+    if has_intensity && intensity.len() == data.points.len() {
+        let values = map_intensity(&intensity, intensity_range, intensity_is_float);
+        for (p, v) in data.points.iter_mut().zip(values) {
+            p.intensity = v;
+        }
+    }
+
+    data.validate();
+    Ok(data)
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Reads the bytestreams of a CompressedVector binary section, one per prototype field.
+fn read_compressed_vector<F: Fn(usize) -> usize>(
+    logical: &[u8],
+    section_start: usize,
+    to_logical: &F,
+    fields: &[Field],
+    record_count: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
+    if section_start + 32 > logical.len() || logical[section_start] != 1 {
+        return Err(invalid_data("Invalid E57 CompressedVector section."));
+    }
+    let section_length = LittleEndian::read_u64(&logical[section_start + 8..]) as usize;
+    let data_offset = LittleEndian::read_u64(&logical[section_start + 16..]) as usize;
+    let section_end = (section_start + section_length).min(logical.len());
+
+    let required = fields
+        .iter()
+        .map(|f| f.bytes_required(record_count))
+        .collect::<Vec<usize>>();
+    let mut streams: Vec<Vec<u8>> = required.iter().map(|r| Vec::with_capacity(*r)).collect();
+    let mut pos = to_logical(data_offset);
+    while streams
+        .iter()
+        .zip(required.iter())
+        .any(|(s, r)| s.len() < *r)
+    {
+        if pos + 4 > section_end {
+            return Err(invalid_data(
+                "The E57 file contains fewer points than specified by its XML section.",
+            ));
+        }
+        let packet_type = logical[pos];
+        let packet_length = LittleEndian::read_u16(&logical[pos + 2..]) as usize + 1;
+        match packet_type {
+            1 => {
+                // data packet
+                let count = LittleEndian::read_u16(&logical[pos + 4..]) as usize;
+                if count != fields.len() || pos + 6 + 2 * count > section_end {
+                    return Err(invalid_data("Invalid E57 data packet."));
+                }
+                let mut start = pos + 6 + 2 * count;
+                for s in 0..count {
+                    let length = LittleEndian::read_u16(&logical[pos + 6 + 2 * s..]) as usize;
+                    if start + length > section_end {
+                        return Err(invalid_data("Invalid E57 data packet."));
+                    }
+                    streams[s].extend_from_slice(&logical[start..start + length]);
+                    start += length;
+                }
+            }
+            2 => {} // empty packet
+            _ => break,
+        }
+        pos += packet_length;
+    }
+    Ok(streams)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Encoding {
+    Float32,
+    Float64,
+    Integer {
+        minimum: i64,
+        bits: usize,
+        scale: f64,
+        offset: f64,
+    },
+}
+
+struct Field {
+    name: String,
+    encoding: Encoding,
+    maximum: i64,
+}
+
+impl Field {
+    fn new(element: &XmlElement) -> Result<Field, Error> {
+        let int_attribute = |name: &str, default: i64| {
+            element
+                .attribute(name)
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(default)
+        };
+        let float_attribute = |name: &str, default: f64| {
+            element
+                .attribute(name)
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+        let mut maximum = 0i64;
+        let encoding = match element.element_type() {
+            "Float" => {
+                if element.attribute("precision") == Some("single") {
+                    Encoding::Float32
+                } else {
+                    Encoding::Float64
+                }
+            }
+            t @ "Integer" | t @ "ScaledInteger" => {
+                let minimum = int_attribute("minimum", i64::min_value());
+                maximum = int_attribute("maximum", i64::max_value());
+                let range = maximum.wrapping_sub(minimum) as u64;
+                let (scale, offset) = if t == "ScaledInteger" {
+                    (
+                        float_attribute("scale", 1f64),
+                        float_attribute("offset", 0f64),
+                    )
+                } else {
+                    (1f64, 0f64)
+                };
+                Encoding::Integer {
+                    minimum: minimum,
+                    bits: (64 - range.leading_zeros()) as usize,
+                    scale: scale,
+                    offset: offset,
+                }
+            }
+            t => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Unsupported E57 point field type '{}' ({}).",
+                        t, element.name
+                    ),
+                ))
+            }
+        };
+        Ok(Field {
+            name: element.name.clone(),
+            encoding: encoding,
+            maximum: maximum,
+        })
+    }
+
+    fn is_float(&self) -> bool {
+        self.encoding == Encoding::Float32 || self.encoding == Encoding::Float64
+    }
+
+    /// The range of values that can be stored by an integer field.
+    fn range(&self) -> Option<(f64, f64)> {
+        match self.encoding {
+            Encoding::Integer {
+                minimum,
+                scale,
+                offset,
+                ..
+            } => Some((
+                minimum as f64 * scale + offset,
+                self.maximum as f64 * scale + offset,
+            )),
+            _ => None,
+        }
+    }
+
+    fn bytes_required(&self, record_count: usize) -> usize {
+        match self.encoding {
+            Encoding::Float32 => 4 * record_count,
+            Encoding::Float64 => 8 * record_count,
+            Encoding::Integer { bits, .. } => (bits * record_count + 7) / 8,
+        }
+    }
+
+    fn value(&self, stream: &[u8], k: usize) -> f64 {
+        match self.encoding {
+            Encoding::Float32 => LittleEndian::read_f32(&stream[4 * k..]) as f64,
+            Encoding::Float64 => LittleEndian::read_f64(&stream[8 * k..]),
+            Encoding::Integer {
+                minimum,
+                bits,
+                scale,
+                offset,
+            } => {
+                if bits == 0 {
+                    return minimum as f64 * scale + offset;
+                }
+                // values are bit-packed, least significant bit first
+                let bit = k * bits;
+                let mut raw = 0u128;
+                for (i, b) in stream[bit / 8..].iter().take(9).enumerate() {
+                    raw |= (*b as u128) << (8 * i);
+                }
+                raw >>= bit % 8;
+                let mask = if bits == 64 {
+                    u64::max_value()
+                } else {
+                    (1u64 << bits) - 1
+                };
+                let v = minimum.wrapping_add((raw as u64 & mask) as i64);
+                v as f64 * scale + offset
+            }
+        }
+    }
+}
+
+/// The rigid-body transformation of a scan into the file's coordinate system.
+struct Pose {
+    rotation: [[f64; 3]; 3],
+    translation: [f64; 3],
+}
+
+impl Pose {
+    fn new(element: Option<&XmlElement>) -> Pose {
+        let mut pose = Pose {
+            rotation: [[1f64, 0f64, 0f64], [0f64, 1f64, 0f64], [0f64, 0f64, 1f64]],
+            translation: [0f64; 3],
+        };
+        if let Some(e) = element {
+            if let Some(q) = e.child("rotation") {
+                let v = |name: &str| q.child(name).map(|c| c.numeric_value()).unwrap_or(0f64);
+                let (w, x, y, z) = (v("w"), v("x"), v("y"), v("z"));
+                pose.rotation = [
+                    [
+                        1f64 - 2f64 * (y * y + z * z),
+                        2f64 * (x * y - w * z),
+                        2f64 * (x * z + w * y),
+                    ],
+                    [
+                        2f64 * (x * y + w * z),
+                        1f64 - 2f64 * (x * x + z * z),
+                        2f64 * (y * z - w * x),
+                    ],
+                    [
+                        2f64 * (x * z - w * y),
+                        2f64 * (y * z + w * x),
+                        1f64 - 2f64 * (x * x + y * y),
+                    ],
+                ];
+            }
+            if let Some(t) = e.child("translation") {
+                let v = |name: &str| t.child(name).map(|c| c.numeric_value()).unwrap_or(0f64);
+                pose.translation = [v("x"), v("y"), v("z")];
+            }
+        }
+        pose
+    }
+
+    fn rotate(&self, p: [f64; 3]) -> [f64; 3] {
+        let r = &self.rotation;
+        [
+            r[0][0] * p[0] + r[0][1] * p[1] + r[0][2] * p[2],
+            r[1][0] * p[0] + r[1][1] * p[1] + r[1][2] * p[2],
+            r[2][0] * p[0] + r[2][1] * p[1] + r[2][2] * p[2],
+        ]
+    }
+}
+
+fn limit_range(limits: &XmlElement, min_name: &str, max_name: &str) -> Option<(f64, f64)> {
+    match (limits.child(min_name), limits.child(max_name)) {
+        (Some(min), Some(max)) => Some((min.numeric_value(), max.numeric_value())),
+        _ => None,
+    }
+}
+
+/// A minimal XML element tree, sufficient for reading the E57 XML section.
+#[derive(Default, Debug)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.0 == name)
+            .map(|a| a.1.as_ref())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn element_type(&self) -> &str {
+        self.attribute("type").unwrap_or("")
+    }
+
+    /// The value of a Float, Integer, or ScaledInteger element.
+    fn numeric_value(&self) -> f64 {
+        let v = self.text.trim().parse::<f64>().unwrap_or(0f64);
+        if self.element_type() == "ScaledInteger" {
+            let scale = self
+                .attribute("scale")
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(1f64);
+            let offset = self
+                .attribute("offset")
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0f64);
+            return v * scale + offset;
+        }
+        v
+    }
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_xml(xml: &str) -> Result<XmlElement, Error> {
+    let error = || invalid_data("The E57 XML section is formatted incorrectly.");
+    let mut stack: Vec<XmlElement> = vec![];
+    let mut pos = 0usize;
+    while let Some(p) = xml[pos..].find('<') {
+        let start = pos + p;
+        if let Some(e) = stack.last_mut() {
+            e.text.push_str(&unescape_xml(&xml[pos..start]));
+        }
+        let rest = &xml[start..];
+        if rest.starts_with("<?") {
+            pos = start + rest.find("?>").ok_or_else(error)? + 2;
+        } else if rest.starts_with("<!--") {
+            pos = start + rest.find("-->").ok_or_else(error)? + 3;
+        } else if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").ok_or_else(error)?;
+            if let Some(e) = stack.last_mut() {
+                e.text.push_str(&rest[9..end]);
+            }
+            pos = start + end + 3;
+        } else if rest.starts_with("<!") {
+            pos = start + rest.find('>').ok_or_else(error)? + 1;
+        } else if rest.starts_with("</") {
+            pos = start + rest.find('>').ok_or_else(error)? + 1;
+            let element = stack.pop().ok_or_else(error)?;
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => return Ok(element),
+            }
+        } else {
+            // a start tag; attribute values may contain '>' characters
+            let mut end = None;
+            let mut quote: Option<char> = None;
+            for (i, c) in rest.char_indices().skip(1) {
+                match quote {
+                    Some(q) if c == q => quote = None,
+                    Some(_) => {}
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    None if c == '>' => {
+                        end = Some(i);
+                        break;
+                    }
+                    None => {}
+                }
+            }
+            let end = end.ok_or_else(error)?;
+            let self_closing = rest[..end].ends_with('/');
+            let tag = rest[1..end].trim_end_matches('/');
+            let element = parse_start_tag(tag).ok_or_else(error)?;
+            pos = start + end + 1;
+            if self_closing {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            } else {
+                stack.push(element);
+            }
+        }
+    }
+    Err(error())
+}
+
+fn parse_start_tag(tag: &str) -> Option<XmlElement> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let mut element = XmlElement {
+        name: tag[..name_end].to_string(),
+        ..Default::default()
+    };
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=')?;
+        let name = rest[..eq].trim().to_string();
+        rest = rest[eq + 1..].trim_start();
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value_end = rest[1..].find(quote)? + 1;
+        element
+            .attributes
+            .push((name, unescape_xml(&rest[1..value_end])));
+        rest = rest[value_end + 1..].trim_start();
+    }
+    Some(element)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_xml, Field};
+
+    #[test]
+    fn test_e57_prototype_decoding() {
+        let xml = r#"<?xml version="1.0"?>
+            <e57Root type="Structure">
+            <!-- <notAnElement/> -->
+            <prototype type="Structure">
+            <cartesianX type="ScaledInteger" minimum="-1000" maximum="1000" scale="0.5" offset="10"/>
+            <intensity type="Integer" minimum="0" maximum="7"/>
+            </prototype>
+            <name type="String"><![CDATA[a <scan>]]></name>
+            </e57Root>"#;
+        let root = parse_xml(xml).unwrap();
+        assert_eq!(root.name, "e57Root");
+        assert_eq!(root.child("name").unwrap().text, "a <scan>");
+        let prototype = root.child("prototype").unwrap();
+        let fields = prototype
+            .children
+            .iter()
+            .map(|e| Field::new(e).unwrap())
+            .collect::<Vec<Field>>();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].range(), Some((0f64, 7f64)));
+
+        // 11-bit values 1000 and 2000 (i.e. 0 and 1000 less the minimum), then 3-bit values 5, 2, 7
+        let stream = [0xe8, 0x83, 0x3e];
+        assert_eq!(fields[0].bytes_required(2), 3);
+        assert_eq!(fields[0].value(&stream, 0), 10f64);
+        assert_eq!(fields[0].value(&stream, 1), 510f64);
+        let stream = [0b1101_0101, 0b1];
+        assert_eq!(fields[1].value(&stream, 0), 5f64);
+        assert_eq!(fields[1].value(&stream, 1), 2f64);
+        assert_eq!(fields[1].value(&stream, 2), 7f64);
+    }
+}
@@ -1,4 +1,5 @@
-use super::las::GlobalEncodingField;
+use super::las::{GlobalEncodingField, LasFile};
+use super::point_cloud::PointCloudFormat;
 use crate::utils::{ByteOrderReader, Endianness};
 use std::fmt;
 use std::fs::File;
@@ -132,6 +133,14 @@ impl LasHeader {
     header but don't want to read the file's data.
     */
     pub fn read_las_header(file_name: &str) -> Result<LasHeader, Error> {
+        if PointCloudFormat::from_file_name(file_name).is_some() {
+            // Non-LAS point clouds have no header and must be read in full.
+            return Ok(LasFile::new(file_name, "rh")?.header);
+        }
+        if file_name.to_lowercase().ends_with(".zip") {
+            // The header of a zipped LAS file is only available once it is decompressed.
+            return Ok(LasFile::new(file_name, "rh")?.header);
+        }
         let mut f = File::open(file_name)?;
         let mut buffer = vec![0; 375]; // A LAS header is about 375 bytes, depending on optional parameters.
 
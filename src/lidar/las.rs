@@ -2,14 +2,18 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 15/01/2017
-Last Modified: 07/12/2018
+Last Modified: 18/10/2026
 License: MIT
 */
 
 #![allow(dead_code, unused_assignments)]
+use super::e57::read_e57;
 use super::header::LasHeader;
+use super::ply::{read_ply, write_ply};
+use super::point_cloud::{PointCloudData, PointCloudFormat};
 use super::point_data::{ColourData, PointData, WaveformPacket};
 use super::vlr::Vlr;
+use super::xyz::read_xyz;
 use crate::raster::geotiff::geokeys::GeoKeys;
 use crate::spatial_ref_system::esri_wkt_from_epsg;
 use crate::structures::BoundingBox;
@@ -42,6 +46,7 @@ pub struct LasFile {
     gps_data: Vec<f64>,
     colour_data: Vec<ColourData>,
     waveform_data: Vec<WaveformPacket>,
+    normal_data: Vec<[f32; 3]>,
     pub geokeys: GeoKeys,
     pub wkt: String,
     // starting_point: usize,
@@ -71,7 +76,9 @@ impl LasFile {
     /// Constructs a new `LasFile` based on a file.
     /// The function takes the name of an existing raster file (`file_name`)
     /// and the `file_mode`, wich can be 'r' (read), 'rh' (read header), and
-    /// 'w' (write).
+    /// 'w' (write). Point clouds stored in PLY (*.ply), E57 (*.e57), and plain
+    /// text XYZ (*.xyz, *.pts) files are read into LAS point records, and files
+    /// with a *.ply extension are written as binary PLY files.
     pub fn new<'a>(file_name: &'a str, file_mode: &'a str) -> Result<LasFile, Error> {
         //LasFile {
         let mut lf = LasFile {
@@ -459,6 +466,30 @@ impl LasFile {
         self.gps_data.len() > 0
     }
 
+    /// Adds a point normal vector. Normals are not stored in LAS files but are
+    /// written to PLY outputs when every point record has one.
+    pub fn add_normal(&mut self, normal: [f32; 3]) {
+        if self.file_mode == "r" {
+            return;
+        }
+        self.normal_data.push(normal);
+    }
+
+    pub fn get_normal(&self, index: usize) -> Result<[f32; 3], Error> {
+        if index < self.normal_data.len() {
+            return Ok(self.normal_data[index]);
+        } else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "Normal vector not found, possibly because the file does not include normal data.",
+            ));
+        }
+    }
+
+    pub fn has_normals(&self) -> bool {
+        self.normal_data.len() > 0 && self.normal_data.len() == self.point_data.len()
+    }
+
     pub fn get_short_filename(&self) -> String {
         let path = Path::new(&self.file_name);
         let file_name = path.file_stem().unwrap();
@@ -484,6 +515,17 @@ impl LasFile {
     }
 
     pub fn read(&mut self) -> Result<(), Error> {
+        if let Some(format) = PointCloudFormat::from_file_name(&self.file_name) {
+            let data = match format {
+                PointCloudFormat::Ply => read_ply(&self.file_name)?,
+                PointCloudFormat::E57 => read_e57(&self.file_name)?,
+                PointCloudFormat::Xyz => read_xyz(&self.file_name, false)?,
+                PointCloudFormat::Pts => read_xyz(&self.file_name, true)?,
+            };
+            self.read_point_cloud(data);
+            return Ok(());
+        }
+
         let buffer = match self.file_name.to_lowercase().ends_with(".zip") {
             false => {
                 let mut f = File::open(&self.file_name).expect("Error opening LAS file.");
@@ -1162,6 +1204,52 @@ impl LasFile {
         Ok(())
     }
 
+    /// Maps the points read from a non-LAS point cloud format into point records.
+    fn read_point_cloud(&mut self, data: PointCloudData) {
+        let mut header = LasHeader {
+            file_signature: "LASF".to_string(),
+            project_id_used: true,
+            version_major: 1,
+            version_minor: 3,
+            header_size: 235,
+            offset_to_points: 235,
+            point_format: if data.colour_data.is_empty() { 0 } else { 2 },
+            point_record_length: if data.colour_data.is_empty() { 20 } else { 26 },
+            number_of_points: data.points.len() as u64,
+            number_of_points_old: data.points.len() as u32,
+            x_scale_factor: 0.001,
+            y_scale_factor: 0.001,
+            z_scale_factor: 0.001,
+            min_x: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            min_y: f64::INFINITY,
+            max_y: f64::NEG_INFINITY,
+            min_z: f64::INFINITY,
+            max_z: f64::NEG_INFINITY,
+            ..Default::default()
+        };
+        for p in &data.points {
+            header.min_x = header.min_x.min(p.x);
+            header.max_x = header.max_x.max(p.x);
+            header.min_y = header.min_y.min(p.y);
+            header.max_y = header.max_y.max(p.y);
+            header.min_z = header.min_z.min(p.z);
+            header.max_z = header.max_z.max(p.z);
+            let which_return = p.return_number() as usize;
+            if which_return <= 5 {
+                header.number_of_points_by_return_old[which_return - 1] += 1;
+            }
+            header.number_of_points_by_return[which_return - 1] += 1;
+        }
+        header.x_offset = header.min_x;
+        header.y_offset = header.min_y;
+        header.z_offset = header.min_z;
+        self.header = header;
+        self.point_data = data.points;
+        self.colour_data = data.colour_data;
+        self.normal_data = data.normal_data;
+    }
+
     pub fn write(&mut self) -> Result<(), Error> {
         if self.file_mode == "r" {
             return Err(Error::new(
@@ -1178,6 +1266,17 @@ impl LasFile {
             println!("WARNING: There are fewer than two points in the LAS file. This may cause some tools to fail when reading these data.");
         }
 
+        match PointCloudFormat::from_file_name(&self.file_name) {
+            Some(PointCloudFormat::Ply) => return write_ply(self, &self.file_name),
+            Some(_) => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "PLY is the only non-LAS point cloud format that can be written.",
+                ))
+            }
+            None => {}
+        }
+
         self.header.x_offset = self.header.min_x;
         self.header.y_offset = self.header.min_y;
         self.header.z_offset = self.header.min_z;
@@ -1,8 +1,14 @@
 // private sub-module defined in other files
+mod e57;
 mod header;
 mod las;
+mod ply;
+mod point_cloud;
 mod point_data;
+mod tile_set;
 mod vlr;
+mod voxel;
+mod xyz;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::header::LasHeader;
@@ -26,4 +32,8 @@ pub use self::point_data::convert_class_val_to_class_string;
 pub use self::point_data::ColourData;
 pub use self::point_data::PointData;
 pub use self::point_data::WaveformPacket;
+pub use self::tile_set::LasTileSet;
 pub use self::vlr::Vlr;
+pub use self::voxel::{
+    leaf_area_density_profile, vertical_complexity_index, Voxel, VoxelGrid, VoxelKey,
+};
@@ -0,0 +1,612 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::las::LasFile;
+use super::point_cloud::{map_colour, map_intensity, PointAttribute, PointCloudData};
+use super::point_data::ColourData;
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyDataType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PlyDataType {
+    fn from_str(s: &str) -> Option<PlyDataType> {
+        match s {
+            "char" | "int8" => Some(PlyDataType::Int8),
+            "uchar" | "uint8" => Some(PlyDataType::UInt8),
+            "short" | "int16" => Some(PlyDataType::Int16),
+            "ushort" | "uint16" => Some(PlyDataType::UInt16),
+            "int" | "int32" => Some(PlyDataType::Int32),
+            "uint" | "uint32" => Some(PlyDataType::UInt32),
+            "float" | "float32" => Some(PlyDataType::Float32),
+            "double" | "float64" => Some(PlyDataType::Float64),
+            _ => None,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlyDataType::Int8 | PlyDataType::UInt8 => 1,
+            PlyDataType::Int16 | PlyDataType::UInt16 => 2,
+            PlyDataType::Int32 | PlyDataType::UInt32 | PlyDataType::Float32 => 4,
+            PlyDataType::Float64 => 8,
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        *self == PlyDataType::Float32 || *self == PlyDataType::Float64
+    }
+}
+
+struct PlyProperty {
+    attribute: PointAttribute,
+    data_type: PlyDataType,
+    list_count_type: Option<PlyDataType>,
+}
+
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Reads the vertices of an ASCII or binary PLY file. The vertex properties x, y, z,
+/// intensity, red, green, blue, nx, ny, nz, and classification are mapped into the
+/// point records; all other properties and elements (e.g. faces) are ignored.
+pub fn read_ply(file_name: &str) -> Result<PointCloudData, Error> {
+    let buffer = fs::read(file_name)?;
+
+    // read the header
+    let mut pos = 0usize;
+    let mut format = PlyFormat::Ascii;
+    let mut elements: Vec<PlyElement> = vec![];
+    let mut line_num = 0;
+    loop {
+        let end = match buffer[pos..].iter().position(|b| *b == b'\n') {
+            Some(p) => pos + p,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The PLY header does not contain an end_header statement.",
+                ))
+            }
+        };
+        let line = String::from_utf8_lossy(&buffer[pos..end])
+            .trim()
+            .to_string();
+        pos = end + 1;
+        line_num += 1;
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        if line_num == 1 {
+            if line != "ply" {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} does not appear to be a PLY file.", file_name),
+                ));
+            }
+            continue;
+        }
+        if words.is_empty() {
+            continue;
+        }
+        match words[0] {
+            "format" => {
+                format = match words.get(1) {
+                    Some(&"ascii") => PlyFormat::Ascii,
+                    Some(&"binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                    Some(&"binary_big_endian") => PlyFormat::BinaryBigEndian,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("Unsupported PLY format statement: {}", line),
+                        ))
+                    }
+                };
+            }
+            "element" => {
+                if words.len() < 3 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid PLY element statement: {}", line),
+                    ));
+                }
+                let count = words[2].parse::<usize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid PLY element statement: {}", line),
+                    )
+                })?;
+                elements.push(PlyElement {
+                    name: words[1].to_string(),
+                    count: count,
+                    properties: vec![],
+                });
+            }
+            "property" => {
+                let property = if words.len() == 5 && words[1] == "list" {
+                    match (
+                        PlyDataType::from_str(words[2]),
+                        PlyDataType::from_str(words[3]),
+                    ) {
+                        (Some(ct), Some(dt)) => Some(PlyProperty {
+                            attribute: PointAttribute::Other,
+                            data_type: dt,
+                            list_count_type: Some(ct),
+                        }),
+                        _ => None,
+                    }
+                } else if words.len() == 3 {
+                    match PlyDataType::from_str(words[1]) {
+                        Some(dt) => Some(PlyProperty {
+                            attribute: PointAttribute::from_property_name(words[2]),
+                            data_type: dt,
+                            list_count_type: None,
+                        }),
+                        None => None,
+                    }
+                } else {
+                    None
+                };
+                match (property, elements.last_mut()) {
+                    (Some(p), Some(e)) => e.properties.push(p),
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("Invalid PLY property statement: {}", line),
+                        ))
+                    }
+                }
+            }
+            "end_header" => break,
+            _ => {} // comment, obj_info, etc.
+        }
+    }
+
+    let vertex_element = match elements.iter().position(|e| e.name == "vertex") {
+        Some(i) => i,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The PLY file does not contain a vertex element.",
+            ))
+        }
+    };
+    {
+        let attributes = elements[vertex_element]
+            .properties
+            .iter()
+            .map(|p| p.attribute)
+            .collect::<Vec<PointAttribute>>();
+        for a in [PointAttribute::X, PointAttribute::Y, PointAttribute::Z].iter() {
+            if !attributes.contains(a) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The PLY vertex element must contain x, y, and z properties.",
+                ));
+            }
+        }
+    }
+
+    let mut reader: Box<dyn PlyValueReader + '_> = match format {
+        PlyFormat::Ascii => {
+            let text = String::from_utf8_lossy(&buffer[pos..]).to_string();
+            Box::new(AsciiValueReader {
+                values: text
+                    .split_whitespace()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .into_iter(),
+            })
+        }
+        _ => Box::new(BinaryValueReader {
+            buffer: &buffer,
+            pos: pos,
+            big_endian: format == PlyFormat::BinaryBigEndian,
+        }),
+    };
+
+    // skip over any elements that precede the vertices
+    for e in 0..vertex_element {
+        for _ in 0..elements[e].count {
+            for p in &elements[e].properties {
+                read_property(&mut *reader, p)?;
+            }
+        }
+    }
+
+    let element = &elements[vertex_element];
+    let has_intensity = element
+        .properties
+        .iter()
+        .any(|p| p.attribute == PointAttribute::Intensity);
+    let intensity_is_float = element
+        .properties
+        .iter()
+        .any(|p| p.attribute == PointAttribute::Intensity && p.data_type.is_float());
+    let has_colour = element
+        .properties
+        .iter()
+        .any(|p| p.attribute == PointAttribute::Red);
+    let colour_is_float = element
+        .properties
+        .iter()
+        .any(|p| p.attribute == PointAttribute::Red && p.data_type.is_float());
+    let has_normals = element
+        .properties
+        .iter()
+        .any(|p| p.attribute == PointAttribute::NormalX);
+
+    let mut data = PointCloudData::default();
+    data.points.reserve(element.count);
+    let mut intensity = Vec::with_capacity(if has_intensity { element.count } else { 0 });
+    let mut colours = Vec::with_capacity(if has_colour { element.count } else { 0 });
+    for _ in 0..element.count {
+        let (mut x, mut y, mut z, mut i, mut c) = (0f64, 0f64, 0f64, 0f64, 0f64);
+        let mut rgb = [0f64; 3];
+        let mut normal = [0f32; 3];
+        for p in &element.properties {
+            let value = read_property(&mut *reader, p)?;
+            match p.attribute {
+                PointAttribute::X => x = value,
+                PointAttribute::Y => y = value,
+                PointAttribute::Z => z = value,
+                PointAttribute::Intensity => i = value,
+                PointAttribute::Red => rgb[0] = value,
+                PointAttribute::Green => rgb[1] = value,
+                PointAttribute::Blue => rgb[2] = value,
+                PointAttribute::NormalX => normal[0] = value as f32,
+                PointAttribute::NormalY => normal[1] = value as f32,
+                PointAttribute::NormalZ => normal[2] = value as f32,
+                PointAttribute::Classification => c = value,
+                PointAttribute::Other => {}
+            }
+        }
+        data.add_point(x, y, z)
+            .set_classification(c.max(0f64).min(255f64) as u8);
+        if has_intensity {
+            intensity.push(i);
+        }
+        if has_colour {
+            colours.push(rgb);
+        }
+        if has_normals {
+            data.normal_data.push(normal);
+        }
+    }
+
+    if has_intensity {
+        let values = map_intensity(&intensity, None, intensity_is_float);
+        for (p, v) in data.points.iter_mut().zip(values) {
+            p.intensity = v;
+        }
+    }
+
+    if has_colour {
+        let range = if colour_is_float
+            && colours
+                .iter()
+                .all(|rgb| rgb.iter().all(|v| *v >= 0f64 && *v <= 1f64))
+        {
+            Some((0f64, 1f64))
+        } else {
+            None
+        };
+        data.colour_data = colours
+            .iter()
+            .map(|rgb| ColourData {
+                red: map_colour(rgb[0], range),
+                green: map_colour(rgb[1], range),
+                blue: map_colour(rgb[2], range),
+                nir: 0u16,
+            })
+            .collect();
+    }
+
+    data.validate();
+    Ok(data)
+}
+
+/// Writes the points contained in a `LasFile` to a binary little-endian PLY file. The
+/// vertex element contains the x, y, z (double), intensity (ushort), and classification
+/// (uchar) properties, as well as red, green, blue (uchar) and nx, ny, nz (float)
+/// properties when the file contains colour and normal data respectively.
+pub fn write_ply(las: &LasFile, file_name: &str) -> Result<(), Error> {
+    let n_points = las.header.number_of_points as usize;
+    let has_rgb = las.has_rgb();
+    let has_normals = las.has_normals();
+
+    // 16-bit colour values are reduced to the 8-bit range used by most mesh software.
+    let mut colour_shift = 0u16;
+    if has_rgb {
+        for i in 0..n_points {
+            let clr = las.get_rgb(i)?;
+            if clr.red > 255 || clr.green > 255 || clr.blue > 255 {
+                colour_shift = 8;
+                break;
+            }
+        }
+    }
+
+    let f = File::create(file_name)?;
+    let mut writer = BufWriter::new(f);
+    let mut header = String::from("ply\nformat binary_little_endian 1.0\n");
+    header.push_str("comment Created by WhiteboxTools\n");
+    header.push_str(&format!("element vertex {}\n", n_points));
+    header.push_str("property double x\nproperty double y\nproperty double z\n");
+    header.push_str("property ushort intensity\nproperty uchar classification\n");
+    if has_rgb {
+        header.push_str("property uchar red\nproperty uchar green\nproperty uchar blue\n");
+    }
+    if has_normals {
+        header.push_str("property float nx\nproperty float ny\nproperty float nz\n");
+    }
+    header.push_str("end_header\n");
+    writer.write_all(header.as_bytes())?;
+
+    for i in 0..n_points {
+        let p = las.get_point_info(i);
+        writer.write_f64::<LittleEndian>(p.x)?;
+        writer.write_f64::<LittleEndian>(p.y)?;
+        writer.write_f64::<LittleEndian>(p.z)?;
+        writer.write_u16::<LittleEndian>(p.intensity)?;
+        writer.write_u8(p.classification())?;
+        if has_rgb {
+            let clr = las.get_rgb(i)?;
+            writer.write_u8((clr.red >> colour_shift).min(255) as u8)?;
+            writer.write_u8((clr.green >> colour_shift).min(255) as u8)?;
+            writer.write_u8((clr.blue >> colour_shift).min(255) as u8)?;
+        }
+        if has_normals {
+            let normal = las.get_normal(i)?;
+            for v in normal.iter() {
+                writer.write_f32::<LittleEndian>(*v)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_property(reader: &mut dyn PlyValueReader, property: &PlyProperty) -> Result<f64, Error> {
+    match property.list_count_type {
+        Some(count_type) => {
+            let count = reader.read_value(count_type)?;
+            for _ in 0..(count as usize) {
+                reader.read_value(property.data_type)?;
+            }
+            Ok(count)
+        }
+        None => reader.read_value(property.data_type),
+    }
+}
+
+trait PlyValueReader {
+    fn read_value(&mut self, data_type: PlyDataType) -> Result<f64, Error>;
+}
+
+struct AsciiValueReader {
+    values: std::vec::IntoIter<String>,
+}
+
+impl PlyValueReader for AsciiValueReader {
+    fn read_value(&mut self, _data_type: PlyDataType) -> Result<f64, Error> {
+        match self.values.next() {
+            Some(s) => s.parse::<f64>().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Error parsing PLY value: {}", s),
+                )
+            }),
+            None => Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The PLY file contains fewer values than specified by its header.",
+            )),
+        }
+    }
+}
+
+struct BinaryValueReader<'a> {
+    buffer: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> PlyValueReader for BinaryValueReader<'a> {
+    fn read_value(&mut self, data_type: PlyDataType) -> Result<f64, Error> {
+        let size = data_type.size();
+        if self.pos + size > self.buffer.len() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The PLY file contains fewer values than specified by its header.",
+            ));
+        }
+        let b = &self.buffer[self.pos..self.pos + size];
+        self.pos += size;
+        let value = if self.big_endian {
+            match data_type {
+                PlyDataType::Int8 => b[0] as i8 as f64,
+                PlyDataType::UInt8 => b[0] as f64,
+                PlyDataType::Int16 => BigEndian::read_i16(b) as f64,
+                PlyDataType::UInt16 => BigEndian::read_u16(b) as f64,
+                PlyDataType::Int32 => BigEndian::read_i32(b) as f64,
+                PlyDataType::UInt32 => BigEndian::read_u32(b) as f64,
+                PlyDataType::Float32 => BigEndian::read_f32(b) as f64,
+                PlyDataType::Float64 => BigEndian::read_f64(b),
+            }
+        } else {
+            match data_type {
+                PlyDataType::Int8 => b[0] as i8 as f64,
+                PlyDataType::UInt8 => b[0] as f64,
+                PlyDataType::Int16 => LittleEndian::read_i16(b) as f64,
+                PlyDataType::UInt16 => LittleEndian::read_u16(b) as f64,
+                PlyDataType::Int32 => LittleEndian::read_i32(b) as f64,
+                PlyDataType::UInt32 => LittleEndian::read_u32(b) as f64,
+                PlyDataType::Float32 => LittleEndian::read_f32(b) as f64,
+                PlyDataType::Float64 => LittleEndian::read_f64(b),
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_ply, write_ply};
+    use crate::lidar::las::LasFile;
+    use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+    use std::env;
+    use std::fs;
+
+    const VERTICES: [[f32; 9]; 3] = [
+        [1.5, 2.0, 3.25, 0.0, 0.0, 1.0, 255.0, 0.0, 10.0],
+        [-4.0, 5.5, 6.0, 0.6, 0.0, 0.8, 0.0, 128.0, 20.0],
+        [7.0, 8.0, -9.5, 1.0, 0.0, 0.0, 30.0, 40.0, 50.0],
+    ];
+
+    // A face element, which must be skipped, precedes the vertices.
+    fn header(format: &str) -> String {
+        format!(
+            "ply\nformat {} 1.0\ncomment a test file\nelement face 2\nproperty list uchar int vertex_indices\n\
+             element vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+             property float nx\nproperty float ny\nproperty float nz\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+            format
+        )
+    }
+
+    fn binary_ply<B: ByteOrder>(format: &str) -> Vec<u8> {
+        let mut buffer = header(format).into_bytes();
+        for face in [[0i32, 1, 2], [2, 1, 0]].iter() {
+            buffer.write_u8(3).unwrap();
+            for v in face.iter() {
+                buffer.write_i32::<B>(*v).unwrap();
+            }
+        }
+        for v in VERTICES.iter() {
+            for value in v[0..6].iter() {
+                buffer.write_f32::<B>(*value).unwrap();
+            }
+            for value in v[6..9].iter() {
+                buffer.write_u8(*value as u8).unwrap();
+            }
+        }
+        buffer
+    }
+
+    fn check_vertices(file_name: &str) {
+        let data = read_ply(file_name).unwrap();
+        assert_eq!(data.points.len(), 3);
+        assert_eq!(data.colour_data.len(), 3);
+        assert_eq!(data.normal_data.len(), 3);
+        for (i, v) in VERTICES.iter().enumerate() {
+            let p = &data.points[i];
+            assert_eq!((p.x, p.y, p.z), (v[0] as f64, v[1] as f64, v[2] as f64));
+            assert!((0..3).all(|j| (data.normal_data[i][j] - v[3 + j]).abs() < 1e-6));
+            let clr = &data.colour_data[i];
+            assert_eq!(
+                (clr.red, clr.green, clr.blue),
+                (v[6] as u16, v[7] as u16, v[8] as u16)
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_ply() {
+        let dir = env::temp_dir().join("wbt_read_ply");
+        fs::create_dir_all(&dir).unwrap();
+
+        let file_name = dir.join("le.ply").to_string_lossy().to_string();
+        fs::write(
+            &file_name,
+            binary_ply::<LittleEndian>("binary_little_endian"),
+        )
+        .unwrap();
+        check_vertices(&file_name);
+
+        let file_name = dir.join("be.ply").to_string_lossy().to_string();
+        fs::write(&file_name, binary_ply::<BigEndian>("binary_big_endian")).unwrap();
+        check_vertices(&file_name);
+
+        let mut text = header("ascii");
+        text.push_str("3 0 1 2\n3 2 1 0\n");
+        for v in VERTICES.iter() {
+            let values = v.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+            text.push_str(&format!("{}\n", values.join(" ")));
+        }
+        let file_name = dir.join("ascii.ply").to_string_lossy().to_string();
+        fs::write(&file_name, text).unwrap();
+        check_vertices(&file_name);
+
+        // too few vertex values
+        let mut buffer = binary_ply::<LittleEndian>("binary_little_endian");
+        buffer.truncate(buffer.len() - 4);
+        let file_name = dir.join("short.ply").to_string_lossy().to_string();
+        fs::write(&file_name, buffer).unwrap();
+        assert!(read_ply(&file_name).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_ply() {
+        let dir = env::temp_dir().join("wbt_write_ply");
+        fs::create_dir_all(&dir).unwrap();
+        let mut text = String::from(
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty double x\nproperty double y\n\
+             property double z\nproperty ushort intensity\nproperty uchar classification\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\n\
+             property float nx\nproperty float ny\nproperty float nz\nend_header\n",
+        );
+        text.push_str("100.5 200.25 10.125 300 2 255 0 10 0 0 1\n");
+        text.push_str("101.5 201.25 11.125 400 5 0 128 20 0.6 0 0.8\n");
+        text.push_str("102.5 202.25 12.125 500 6 30 40 50 1 0 0\n");
+        let input = dir.join("input.ply").to_string_lossy().to_string();
+        fs::write(&input, text).unwrap();
+
+        let las = LasFile::new(&input, "r").unwrap();
+        let output = dir.join("output.ply").to_string_lossy().to_string();
+        write_ply(&las, &output).unwrap();
+        let original = read_ply(&input).unwrap();
+        let data = read_ply(&output).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(data.points.len(), 3);
+        for i in 0..3 {
+            let (p, q) = (&original.points[i], &data.points[i]);
+            assert!((p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9);
+            assert!((p.z - q.z).abs() < 1e-9);
+            assert_eq!(p.intensity, q.intensity);
+            assert_eq!(p.classification(), q.classification());
+            let (c, d) = (&original.colour_data[i], &data.colour_data[i]);
+            assert_eq!((c.red, c.green, c.blue), (d.red, d.green, d.blue));
+            assert_eq!(original.normal_data[i], data.normal_data[i]);
+        }
+        assert_eq!(data.points[1].intensity, 400);
+        assert_eq!(data.points[2].classification(), 6);
+    }
+}
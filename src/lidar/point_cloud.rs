@@ -0,0 +1,150 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::point_data::{ColourData, PointData};
+use std::path::Path;
+
+/// The point data read from a non-LAS point cloud format (PLY, E57, or plain XYZ
+/// text), prior to being mapped into the point records of a `LasFile`.
+#[derive(Default, Clone)]
+pub struct PointCloudData {
+    pub points: Vec<PointData>,
+    pub colour_data: Vec<ColourData>,
+    pub normal_data: Vec<[f32; 3]>,
+}
+
+impl PointCloudData {
+    /// Adds a single-return point to the point cloud.
+    pub fn add_point(&mut self, x: f64, y: f64, z: f64) -> &mut PointData {
+        let mut p = PointData {
+            x: x,
+            y: y,
+            z: z,
+            ..Default::default()
+        };
+        p.set_return_number(1);
+        p.set_number_of_returns(1);
+        self.points.push(p);
+        self.points.last_mut().unwrap()
+    }
+
+    /// Drops the colour and normal data unless every point has a value.
+    pub fn validate(&mut self) {
+        if self.colour_data.len() != self.points.len() {
+            self.colour_data.clear();
+        }
+        if self.normal_data.len() != self.points.len() {
+            self.normal_data.clear();
+        }
+    }
+}
+
+/// The per-point properties of the supported point cloud formats that are mapped into
+/// `LasFile` point records.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointAttribute {
+    X,
+    Y,
+    Z,
+    Intensity,
+    Red,
+    Green,
+    Blue,
+    NormalX,
+    NormalY,
+    NormalZ,
+    Classification,
+    Other,
+}
+
+impl PointAttribute {
+    /// Returns the attribute associated with a (case-insensitive) property or column name.
+    pub fn from_property_name(name: &str) -> PointAttribute {
+        match name.to_lowercase().as_ref() {
+            "x" => PointAttribute::X,
+            "y" => PointAttribute::Y,
+            "z" => PointAttribute::Z,
+            "intensity" | "scalar_intensity" | "reflectance" | "scalar_reflectance" | "i" => {
+                PointAttribute::Intensity
+            }
+            "red" | "r" | "diffuse_red" => PointAttribute::Red,
+            "green" | "g" | "diffuse_green" => PointAttribute::Green,
+            "blue" | "b" | "diffuse_blue" => PointAttribute::Blue,
+            "nx" | "normal_x" => PointAttribute::NormalX,
+            "ny" | "normal_y" => PointAttribute::NormalY,
+            "nz" | "normal_z" => PointAttribute::NormalZ,
+            "classification" | "scalar_classification" | "class" => PointAttribute::Classification,
+            _ => PointAttribute::Other,
+        }
+    }
+}
+
+/// The non-LAS point cloud formats that can be read into a `LasFile`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointCloudFormat {
+    Ply,
+    E57,
+    Xyz,
+    Pts,
+}
+
+impl PointCloudFormat {
+    /// Returns the point cloud format associated with a file name's extension, if any.
+    pub fn from_file_name(file_name: &str) -> Option<PointCloudFormat> {
+        let extension = Path::new(file_name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_ref() {
+            "ply" => Some(PointCloudFormat::Ply),
+            "e57" => Some(PointCloudFormat::E57),
+            "xyz" => Some(PointCloudFormat::Xyz),
+            "pts" => Some(PointCloudFormat::Pts),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a set of raw intensity values into the 16-bit LAS intensity range. Values are
+/// rescaled from the range (`min`, `max`) when it is known; otherwise, floating-point
+/// intensities in the range 0-1 are stretched to 0-65535 and all other values are
+/// rounded and clamped.
+pub fn map_intensity(values: &[f64], range: Option<(f64, f64)>, is_float: bool) -> Vec<u16> {
+    let range = match range {
+        Some((min, max)) if max > min => Some((min, max)),
+        Some(_) => None,
+        None => {
+            if is_float && values.iter().all(|v| *v >= 0f64 && *v <= 1f64) {
+                Some((0f64, 1f64))
+            } else {
+                None
+            }
+        }
+    };
+    values
+        .iter()
+        .map(|v| match range {
+            Some((min, max)) => ((v - min) / (max - min) * 65535f64)
+                .round()
+                .max(0f64)
+                .min(65535f64) as u16,
+            None => v.round().max(0f64).min(65535f64) as u16,
+        })
+        .collect()
+}
+
+/// Maps a raw colour value into the 8-bit range used by the colour data of LAS files
+/// created by WhiteboxTools. Values are rescaled from the range (`min`, `max`) when it
+/// is known and are otherwise rounded and clamped.
+pub fn map_colour(value: f64, range: Option<(f64, f64)>) -> u16 {
+    let v = match range {
+        Some((min, max)) if max > min => (value - min) / (max - min) * 255f64,
+        _ => value,
+    };
+    v.round().max(0f64).min(65535f64) as u16
+}
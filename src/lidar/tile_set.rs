@@ -0,0 +1,247 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::header::LasHeader;
+use super::las::LasFile;
+use super::point_data::{ColourData, PointData};
+use crate::structures::BoundingBox;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// A set of LiDAR tiles and their extents, used to process tiled point clouds without edge
+/// effects. Each tile is processed along with a buffer of points from the neighbouring tiles,
+/// located using the extents stored in the tile headers, and the results are then trimmed to
+/// the core (i.e. unbuffered) extent of the tile.
+#[derive(Default, Clone, Debug)]
+pub struct LasTileSet {
+    pub files: Vec<String>,
+    pub extents: Vec<BoundingBox>,
+}
+
+impl LasTileSet {
+    /// Creates a tile set from a list of LAS files, reading the extent of each tile from its header.
+    pub fn new(files: Vec<String>) -> Result<LasTileSet, Error> {
+        let mut extents = Vec::with_capacity(files.len());
+        for file in &files {
+            let header = LasHeader::read_las_header(&file.replace("\"", ""))?;
+            extents.push(BoundingBox::new(
+                header.min_x,
+                header.max_x,
+                header.min_y,
+                header.max_y,
+            ));
+        }
+        Ok(LasTileSet {
+            files: files,
+            extents: extents,
+        })
+    }
+
+    /// Creates a tile set from all of the LAS files (*.las, *.zip) contained within a directory.
+    pub fn from_directory(directory: &str) -> Result<LasTileSet, Error> {
+        if !Path::new(directory).is_dir() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The input directory ({}) is incorrect.", directory),
+            ));
+        }
+        let mut files = vec![];
+        for entry in fs::read_dir(directory)? {
+            let s = entry?
+                .path()
+                .into_os_string()
+                .to_str()
+                .expect("Error reading path string")
+                .to_string();
+            let lower = s.to_lowercase();
+            if lower.ends_with(".las") || lower.ends_with(".zip") {
+                files.push(s);
+            }
+        }
+        files.sort();
+        LasTileSet::new(files)
+    }
+
+    /// Returns the number of tiles in the set.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if the set contains no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the extent of a tile expanded by a buffer distance.
+    pub fn buffered_extent(&self, tile: usize, buffer: f64) -> BoundingBox {
+        let bb = self.extents[tile];
+        BoundingBox::new(
+            bb.min_x - buffer,
+            bb.max_x + buffer,
+            bb.min_y - buffer,
+            bb.max_y + buffer,
+        )
+    }
+
+    /// Returns the indices of the tiles, including the tile itself, that overlap the
+    /// buffered extent of a tile.
+    pub fn overlapping_tiles(&self, tile: usize, buffer: f64) -> Vec<usize> {
+        let bb = self.buffered_extent(tile, buffer);
+        (0..self.len())
+            .filter(|&m| m == tile || self.extents[m].overlaps(bb))
+            .collect()
+    }
+
+    /// Returns the indices of the tiles, excluding the tile itself, that overlap the buffered
+    /// extent of a tile.
+    pub fn neighbours(&self, tile: usize, buffer: f64) -> Vec<usize> {
+        self.overlapping_tiles(tile, buffer)
+            .into_iter()
+            .filter(|&m| m != tile)
+            .collect()
+    }
+
+    /// Reads the points from the neighbouring tiles that are located within the buffered extent
+    /// of a tile, including those on its edges. Points of the tile itself are not included.
+    pub fn read_buffer_points(&self, tile: usize, buffer: f64) -> Result<Vec<PointData>, Error> {
+        self.read_buffer(tile, buffer, |_, _, p| Some(p))
+    }
+
+    /// Reads the points, with their colours, from the neighbouring tiles that are located
+    /// within the buffered extent of a tile. Tiles that do not contain colour data are skipped.
+    pub fn read_buffer_colour_points(
+        &self,
+        tile: usize,
+        buffer: f64,
+    ) -> Result<Vec<(PointData, ColourData)>, Error> {
+        self.read_buffer(tile, buffer, |input, i, p| {
+            if input.has_rgb() {
+                input.get_rgb(i).ok().map(|clr| (p, clr))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn read_buffer<T, F>(&self, tile: usize, buffer: f64, f: F) -> Result<Vec<T>, Error>
+    where
+        F: Fn(&LasFile, usize, PointData) -> Option<T>,
+    {
+        let bb = self.buffered_extent(tile, buffer);
+        let mut points = vec![];
+        for m in self.neighbours(tile, buffer) {
+            let input = LasFile::new(&self.files[m].replace("\"", ""), "r")?;
+            let n_points = input.header.number_of_points as usize;
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                // inclusive of the edges, unlike BoundingBox::is_point_in_box, so that points
+                // on a tile seam are not lost when the buffer is zero
+                if p.x >= bb.min_x && p.x <= bb.max_x && p.y >= bb.min_y && p.y <= bb.max_y {
+                    if let Some(value) = f(&input, i, p) {
+                        points.push(value);
+                    }
+                }
+            }
+        }
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LasTileSet;
+    use crate::lidar::{LasFile, LasHeader, LidarPointRecord, PointData};
+    use crate::structures::BoundingBox;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_tile_set_neighbours() {
+        // a 3 x 2 arrangement of 100 m tiles
+        let mut tiles = LasTileSet::default();
+        for row in 0..2 {
+            for col in 0..3 {
+                tiles.files.push(format!("tile_{}_{}.las", row, col));
+                tiles.extents.push(BoundingBox::new(
+                    col as f64 * 100f64,
+                    (col + 1) as f64 * 100f64 - 0.01,
+                    row as f64 * 100f64,
+                    (row + 1) as f64 * 100f64 - 0.01,
+                ));
+            }
+        }
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles.neighbours(0, 10f64), vec![1, 3, 4]);
+        assert_eq!(tiles.overlapping_tiles(4, 10f64), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(tiles.neighbours(2, 0f64), Vec::<usize>::new());
+        let bb = tiles.buffered_extent(0, 5f64);
+        assert_eq!(bb.min_x, -5f64);
+        assert_eq!(bb.max_y, 104.99f64);
+    }
+
+    #[test]
+    fn test_read_buffer_points_on_seam() {
+        // two tiles sharing a seam at x = 100, each with points on the seam
+        let tile_points = [
+            vec![(0.0, 0.0), (50.0, 50.0), (100.0, 100.0)],
+            vec![
+                (100.0, 0.0),
+                (100.0, 50.0),
+                (105.0, 100.0),
+                (150.0, 50.0),
+                (200.0, 0.0),
+            ],
+        ];
+        let mut files = vec![];
+        for (t, pts) in tile_points.iter().enumerate() {
+            let file_name = env::temp_dir()
+                .join(format!("wbt_test_tile_set_{}.las", t))
+                .to_str()
+                .unwrap()
+                .to_string();
+            let mut las = LasFile::new(&file_name, "w").unwrap();
+            let mut header = LasHeader::default();
+            header.project_id_used = true;
+            las.add_header(header);
+            // interior points, away from the seam, pad the file to a valid size
+            let filler = (0..8).map(|k| (25.0 + 150.0 * t as f64, 10.0 * k as f64));
+            for (x, y) in pts.iter().cloned().chain(filler) {
+                las.add_point_record(LidarPointRecord::PointRecord0 {
+                    point_data: PointData {
+                        x: x,
+                        y: y,
+                        z: 10.0,
+                        ..Default::default()
+                    },
+                });
+            }
+            las.write().unwrap();
+            files.push(file_name);
+        }
+        let tiles = LasTileSet::new(files.clone()).unwrap();
+        let buffer_x = |tile: usize, buffer: f64| -> Vec<f64> {
+            let mut x: Vec<f64> = tiles
+                .read_buffer_points(tile, buffer)
+                .unwrap()
+                .iter()
+                .map(|p| p.x)
+                .collect();
+            x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            x
+        };
+        // points on the edges of the tile extent, and of the buffered extent, are included
+        assert_eq!(buffer_x(0, 0.0), vec![100.0, 100.0]);
+        assert_eq!(buffer_x(0, 5.0), vec![100.0, 100.0, 105.0]);
+        assert_eq!(buffer_x(1, 0.0), vec![100.0]);
+        assert_eq!(buffer_x(1, 50.0), vec![50.0, 100.0]);
+        for file_name in &files {
+            let _ = fs::remove_file(file_name);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::las::LasFile;
+use super::point_data::PointData;
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::f64;
+
+/// The (column, row, layer) index of a voxel. Indices are measured from the coordinate origin,
+/// such that voxel grids built from adjoining tiles with the same voxel dimensions are aligned.
+pub type VoxelKey = (isize, isize, isize);
+
+/// The summary statistics of the points contained within a voxel.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Voxel {
+    pub count: usize,
+    pub first_returns: usize,
+    pub sum_x: f64,
+    pub sum_y: f64,
+    pub sum_z: f64,
+    pub sum_intensity: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+}
+
+impl Voxel {
+    /// Returns the centroid (mean x, y, z) of the points within the voxel.
+    pub fn centroid(&self) -> (f64, f64, f64) {
+        let n = self.count.max(1) as f64;
+        (self.sum_x / n, self.sum_y / n, self.sum_z / n)
+    }
+
+    /// Returns the mean intensity of the points within the voxel.
+    pub fn mean_intensity(&self) -> f64 {
+        self.sum_intensity / self.count.max(1) as f64
+    }
+}
+
+/// A sparse grid of voxels, in which only voxels containing points are stored. Voxels
+/// have a square footprint (`resolution`) and a separate vertical dimension (`layer_height`),
+/// so that the grid may be used both for isotropic voxelization and for the vertical profiles
+/// of columns with large footprints.
+#[derive(Default, Clone, Debug)]
+pub struct VoxelGrid {
+    pub resolution: f64,
+    pub layer_height: f64,
+    voxels: HashMap<VoxelKey, Voxel>,
+}
+
+impl VoxelGrid {
+    /// Creates an empty voxel grid.
+    pub fn new(resolution: f64, layer_height: f64) -> VoxelGrid {
+        if resolution <= 0f64 || layer_height <= 0f64 {
+            panic!("Voxel dimensions must be greater than zero.");
+        }
+        VoxelGrid {
+            resolution: resolution,
+            layer_height: layer_height,
+            voxels: HashMap::new(),
+        }
+    }
+
+    /// Creates a voxel grid from the points of a LAS file for which `include` returns true.
+    pub fn from_las<F>(input: &LasFile, resolution: f64, layer_height: f64, include: F) -> VoxelGrid
+    where
+        F: Fn(&PointData) -> bool,
+    {
+        let mut grid = VoxelGrid::new(resolution, layer_height);
+        let n_points = input.header.number_of_points as usize;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if include(&p) {
+                grid.add_point(&p);
+            }
+        }
+        grid
+    }
+
+    /// Returns the key of the voxel containing a location.
+    pub fn key(&self, x: f64, y: f64, z: f64) -> VoxelKey {
+        (
+            (x / self.resolution).floor() as isize,
+            (y / self.resolution).floor() as isize,
+            (z / self.layer_height).floor() as isize,
+        )
+    }
+
+    /// Returns the coordinates of the centre of a voxel.
+    pub fn centre(&self, key: VoxelKey) -> (f64, f64, f64) {
+        (
+            (key.0 as f64 + 0.5) * self.resolution,
+            (key.1 as f64 + 0.5) * self.resolution,
+            (key.2 as f64 + 0.5) * self.layer_height,
+        )
+    }
+
+    /// Adds a point to the voxel that contains it and returns the key of the voxel.
+    pub fn add_point(&mut self, p: &PointData) -> VoxelKey {
+        let key = self.key(p.x, p.y, p.z);
+        let voxel = self.voxels.entry(key).or_insert(Voxel {
+            min_z: f64::INFINITY,
+            max_z: f64::NEG_INFINITY,
+            ..Default::default()
+        });
+        voxel.count += 1;
+        if p.return_number() <= 1 {
+            voxel.first_returns += 1;
+        }
+        voxel.sum_x += p.x;
+        voxel.sum_y += p.y;
+        voxel.sum_z += p.z;
+        voxel.sum_intensity += p.intensity as f64;
+        voxel.min_z = voxel.min_z.min(p.z);
+        voxel.max_z = voxel.max_z.max(p.z);
+        key
+    }
+
+    /// Returns the voxel with a key, if it contains points.
+    pub fn get(&self, key: VoxelKey) -> Option<&Voxel> {
+        self.voxels.get(&key)
+    }
+
+    /// Returns the number of points in the voxel with a key.
+    pub fn count(&self, key: VoxelKey) -> usize {
+        match self.voxels.get(&key) {
+            Some(v) => v.count,
+            None => 0,
+        }
+    }
+
+    /// Returns the number of occupied voxels.
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    /// Returns true if the grid contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Returns an iterator over the occupied voxels and their keys.
+    pub fn iter(&self) -> hash_map::Iter<'_, VoxelKey, Voxel> {
+        self.voxels.iter()
+    }
+
+    /// Returns the range of keys, as (minimum, maximum), of the occupied voxels.
+    pub fn key_range(&self) -> Option<(VoxelKey, VoxelKey)> {
+        let mut keys = self.voxels.keys();
+        let first = *keys.next()?;
+        let (mut min, mut max) = (first, first);
+        for k in keys {
+            min = (min.0.min(k.0), min.1.min(k.1), min.2.min(k.2));
+            max = (max.0.max(k.0), max.1.max(k.1), max.2.max(k.2));
+        }
+        Some((min, max))
+    }
+
+    /// Returns the keys of the columns (column, row) that contain occupied voxels.
+    pub fn columns(&self) -> Vec<(isize, isize)> {
+        let mut columns: Vec<(isize, isize)> = self
+            .voxels
+            .keys()
+            .map(|k| (k.0, k.1))
+            .collect::<std::collections::HashSet<(isize, isize)>>()
+            .into_iter()
+            .collect();
+        columns.sort();
+        columns
+    }
+
+    /// Returns the number of points in each of the layers `min_layer` to `max_layer`
+    /// (inclusive) of a column, ordered from the bottom upwards.
+    pub fn column_profile(
+        &self,
+        column: isize,
+        row: isize,
+        min_layer: isize,
+        max_layer: isize,
+    ) -> Vec<usize> {
+        (min_layer..=max_layer)
+            .map(|layer| self.count((column, row, layer)))
+            .collect()
+    }
+}
+
+/// Estimates the leaf area density (LAD) of each layer of a vertical profile of return counts,
+/// ordered from the bottom upwards, using the MacArthur-Horn (Beer-Lambert) gap fraction
+/// method. `below` is the number of returns beneath the bottom of the profile (e.g. ground
+/// returns), `layer_height` is the thickness of the layers and `extinction` is the extinction
+/// coefficient (k), commonly 0.5 for a spherical leaf angle distribution. One is added to the
+/// return counts to avoid undefined values in layers that no pulse penetrates.
+pub fn leaf_area_density_profile(
+    counts: &[usize],
+    below: usize,
+    layer_height: f64,
+    extinction: f64,
+) -> Vec<f64> {
+    let mut lad = vec![0f64; counts.len()];
+    let mut n_out = below as f64;
+    for i in 0..counts.len() {
+        let n_in = n_out + counts[i] as f64;
+        lad[i] = ((n_in + 1f64) / (n_out + 1f64)).ln() / (extinction * layer_height);
+        n_out = n_in;
+    }
+    lad
+}
+
+/// Calculates the vertical complexity index (VCI) of a vertical profile of return counts,
+/// i.e. the Shannon entropy of the proportions of returns in each layer normalized by its
+/// maximum value, ln(number of layers) (van Ewijk et al., 2011). The VCI ranges from 0,
+/// when all returns fall within one layer, to 1, when the returns are evenly distributed.
+pub fn vertical_complexity_index(counts: &[usize]) -> Option<f64> {
+    let total: usize = counts.iter().sum();
+    if total == 0 || counts.len() < 2 {
+        return None;
+    }
+    let mut entropy = 0f64;
+    for &n in counts {
+        if n > 0 {
+            let p = n as f64 / total as f64;
+            entropy -= p * p.ln();
+        }
+    }
+    Some(entropy / (counts.len() as f64).ln())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{leaf_area_density_profile, vertical_complexity_index, VoxelGrid};
+    use crate::lidar::PointData;
+
+    #[test]
+    fn test_voxel_grid() {
+        let mut grid = VoxelGrid::new(1f64, 0.5f64);
+        let mut p = PointData::default();
+        for &(x, y, z) in [
+            (0.2, 0.2, 0.1),
+            (0.8, 0.9, 0.4),
+            (-0.5, 0.5, 0.1),
+            (0.5, 0.5, 1.2),
+        ]
+        .iter()
+        {
+            p.x = x;
+            p.y = y;
+            p.z = z;
+            grid.add_point(&p);
+        }
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.count((0, 0, 0)), 2);
+        assert_eq!(grid.count((-1, 0, 0)), 1);
+        assert_eq!(grid.column_profile(0, 0, 0, 2), vec![2, 0, 1]);
+        assert_eq!(grid.key_range(), Some(((-1, 0, 0), (0, 0, 2))));
+        assert_eq!(grid.columns(), vec![(-1, 0), (0, 0)]);
+        let (cx, cy, cz) = grid.get((0, 0, 0)).unwrap().centroid();
+        assert!((cx - 0.5).abs() < 1e-9 && (cy - 0.55).abs() < 1e-9 && (cz - 0.25).abs() < 1e-9);
+        assert_eq!(grid.centre((-1, 0, 2)), (-0.5, 0.5, 1.25));
+    }
+
+    #[test]
+    fn test_vertical_profiles() {
+        assert_eq!(vertical_complexity_index(&[10, 0, 0, 0]), Some(0f64));
+        assert!((vertical_complexity_index(&[5, 5, 5, 5]).unwrap() - 1f64).abs() < 1e-9);
+        assert_eq!(vertical_complexity_index(&[0, 0]), None);
+        let lad = leaf_area_density_profile(&[0, 9, 0], 9, 1f64, 0.5f64);
+        assert_eq!(lad[0], 0f64);
+        assert!((lad[1] - 2f64 * (19f64 / 10f64).ln()).abs() < 1e-9);
+        assert_eq!(lad[2], 0f64);
+    }
+}
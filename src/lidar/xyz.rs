@@ -0,0 +1,228 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::point_cloud::{map_colour, map_intensity, PointAttribute, PointCloudData};
+use super::point_data::ColourData;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+
+/// Reads a plain XYZ (or Leica PTS) text point cloud. Values may be separated by
+/// spaces, tabs, commas, or semicolons. If the file contains a header line of column
+/// names (e.g. `//X,Y,Z,R,G,B,Nx,Ny,Nz`), the columns are mapped by name; otherwise they
+/// are interpreted by the number of columns, i.e. x y z, x y z i, x y z r g b,
+/// x y z i r g b, x y z r g b nx ny nz, and x y z i r g b nx ny nz. PTS intensities
+/// are stored in the range -2048 to 2047 and are shifted into the LAS intensity range.
+pub fn read_xyz(file_name: &str, is_pts: bool) -> Result<PointCloudData, Error> {
+    let f = File::open(file_name)?;
+    let f = BufReader::new(f);
+
+    let mut columns: Vec<PointAttribute> = vec![];
+    let mut intensity = vec![];
+    let mut colours = vec![];
+    let mut data = PointCloudData::default();
+    let mut line_num = 0;
+    for line in f.lines() {
+        let line = line?;
+        line_num += 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let words = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>();
+        let values = words
+            .iter()
+            .map(|s| s.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>();
+        let values = match values {
+            Ok(v) => v,
+            Err(_) => {
+                if columns.is_empty() && data.points.is_empty() {
+                    // a header line of column names
+                    columns = words
+                        .iter()
+                        .map(|s| PointAttribute::from_property_name(s.trim_start_matches("//")))
+                        .collect();
+                    continue;
+                }
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Error parsing line {} of {}: {}", line_num, file_name, line),
+                ));
+            }
+        };
+        if values.len() < 3 {
+            if data.points.is_empty() {
+                // e.g. the point count at the top of a PTS file
+                continue;
+            }
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Line {} of {} contains fewer than three values.",
+                    line_num, file_name
+                ),
+            ));
+        }
+        if columns.is_empty() {
+            columns = default_columns(values.len());
+        }
+        let (mut x, mut y, mut z, mut i, mut c) = (0f64, 0f64, 0f64, None, 0f64);
+        let mut rgb = None;
+        let mut normal = None;
+        for (a, v) in columns.iter().zip(values.iter()) {
+            match a {
+                PointAttribute::X => x = *v,
+                PointAttribute::Y => y = *v,
+                PointAttribute::Z => z = *v,
+                PointAttribute::Intensity => i = Some(*v),
+                PointAttribute::Red => rgb.get_or_insert([0f64; 3])[0] = *v,
+                PointAttribute::Green => rgb.get_or_insert([0f64; 3])[1] = *v,
+                PointAttribute::Blue => rgb.get_or_insert([0f64; 3])[2] = *v,
+                PointAttribute::NormalX => normal.get_or_insert([0f32; 3])[0] = *v as f32,
+                PointAttribute::NormalY => normal.get_or_insert([0f32; 3])[1] = *v as f32,
+                PointAttribute::NormalZ => normal.get_or_insert([0f32; 3])[2] = *v as f32,
+                PointAttribute::Classification => c = *v,
+                PointAttribute::Other => {}
+            }
+        }
+        data.add_point(x, y, z)
+            .set_classification(c.max(0f64).min(255f64) as u8);
+        if let Some(i) = i {
+            intensity.push(if is_pts { i + 2048f64 } else { i });
+        }
+        if let Some(rgb) = rgb {
+            colours.push(rgb);
+        }
+        if let Some(normal) = normal {
+            data.normal_data.push(normal);
+        }
+    }
+
+    if intensity.len() == data.points.len() {
+        let range = if is_pts { Some((0f64, 4095f64)) } else { None };
+        let is_float = intensity.iter().any(|v| v.fract() != 0f64);
+        let values = map_intensity(&intensity, range, is_float);
+        for (p, v) in data.points.iter_mut().zip(values) {
+            p.intensity = v;
+        }
+    }
+
+    data.colour_data = colours
+        .iter()
+        .map(|rgb| ColourData {
+            red: map_colour(rgb[0], None),
+            green: map_colour(rgb[1], None),
+            blue: map_colour(rgb[2], None),
+            nir: 0u16,
+        })
+        .collect();
+
+    data.validate();
+    Ok(data)
+}
+
+fn default_columns(num_columns: usize) -> Vec<PointAttribute> {
+    use PointAttribute::*;
+    match num_columns {
+        4 => vec![X, Y, Z, Intensity],
+        6 => vec![X, Y, Z, Red, Green, Blue],
+        7 => vec![X, Y, Z, Intensity, Red, Green, Blue],
+        9 => vec![X, Y, Z, Red, Green, Blue, NormalX, NormalY, NormalZ],
+        10 => vec![
+            X, Y, Z, Intensity, Red, Green, Blue, NormalX, NormalY, NormalZ,
+        ],
+        _ => vec![X, Y, Z],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_xyz;
+    use std::env;
+    use std::fs;
+
+    fn read(file_name: &str, contents: &str, is_pts: bool) -> super::PointCloudData {
+        let dir = env::temp_dir().join("wbt_read_xyz");
+        fs::create_dir_all(&dir).unwrap();
+        let file_name = dir.join(file_name).to_string_lossy().to_string();
+        fs::write(&file_name, contents).unwrap();
+        let data = read_xyz(&file_name, is_pts);
+        let _ = fs::remove_file(&file_name);
+        data.unwrap()
+    }
+
+    #[test]
+    fn test_read_xyz() {
+        // space, tab, comma, and semicolon delimited
+        for contents in [
+            "1.5 2.5 3.5\n4 5 6\n",
+            "1.5\t2.5\t3.5\n\n4\t5\t6\n",
+            "1.5,2.5,3.5\n4,5,6\n",
+            "1.5; 2.5; 3.5\n4; 5; 6\n",
+        ]
+        .iter()
+        {
+            let data = read("points.xyz", contents, false);
+            assert_eq!(data.points.len(), 2);
+            let p = &data.points[0];
+            assert_eq!((p.x, p.y, p.z), (1.5, 2.5, 3.5));
+            assert_eq!(data.points[1].z, 6.0);
+            assert!(data.colour_data.is_empty() && data.normal_data.is_empty());
+        }
+
+        // columns interpreted by their number: x y z i r g b nx ny nz
+        let data = read("points.xyz", "1 2 3 100 10 20 30 0 0 1\n", false);
+        assert_eq!(data.points[0].intensity, 100);
+        let clr = &data.colour_data[0];
+        assert_eq!((clr.red, clr.green, clr.blue), (10, 20, 30));
+        assert_eq!(data.normal_data[0], [0.0, 0.0, 1.0]);
+
+        // a header of column names, in a non-default order
+        let data = read(
+            "points.csv",
+            "//X,Y,Z,R,G,B,Nx,Ny,Nz,Class\n1,2,3,10,20,30,1,0,0,6\n4,5,6,40,50,60,0,1,0,2\n",
+            false,
+        );
+        assert_eq!(data.points.len(), 2);
+        assert_eq!(data.points[1].classification(), 2);
+        assert_eq!(data.points[0].intensity, 0);
+        assert_eq!(data.colour_data[1].blue, 60);
+        assert_eq!(data.normal_data[1], [0.0, 1.0, 0.0]);
+
+        let data = read("points.txt", "x y z intensity\n1 2 3 0.5\n4 5 6 1\n", false);
+        assert_eq!(data.points[0].intensity, 32768);
+        assert_eq!(data.points[1].intensity, 65535);
+
+        // a non-numeric line after the first point is an error
+        let dir = env::temp_dir().join("wbt_read_xyz");
+        let file_name = dir.join("bad.xyz").to_string_lossy().to_string();
+        fs::write(&file_name, "1 2 3\nx y z\n").unwrap();
+        assert!(read_xyz(&file_name, false).is_err());
+        let _ = fs::remove_file(&file_name);
+    }
+
+    #[test]
+    fn test_read_pts() {
+        // the point count precedes x y z i r g b, with intensities in the range -2048 to 2047
+        let data = read(
+            "points.pts",
+            "3\n1 2 3 -2048 255 0 0\n4 5 6 2047 0 255 0\n7 8 9 0 0 0 255\n",
+            true,
+        );
+        assert_eq!(data.points.len(), 3);
+        assert_eq!(data.points[0].intensity, 0);
+        assert_eq!(data.points[1].intensity, 65535);
+        assert_eq!(data.points[2].intensity, 32776);
+        assert_eq!(data.colour_data[2].blue, 255);
+        assert_eq!(data.colour_data[0].red, 255);
+    }
+}
@@ -0,0 +1,837 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: Reading the tiled raster coverages of OGC GeoPackage (.gpkg) files. Both tile pyramid
+user data tables ('tiles', i.e. PNG and JPEG imagery) and tiled gridded coverages
+('2d-gridded-coverage', i.e. 16-bit PNG or 32-bit floating-point TIFF elevation tiles) are
+read, at the highest (most detailed) zoom level. A layer is selected using the syntax
+`data.gpkg:layer`; without a layer name, the first raster table is used. Imagery is read as
+RGBA32 data, with fully transparent pixels and missing tiles set to the nodata value, 0.
+GeoPackage rasters cannot be written.
+*/
+
+use super::*;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use crate::utils::{SqliteDatabase, SqliteTable, SqliteValue};
+use crate::vector::geopackage::split_layer;
+use libflate::zlib::Decoder;
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind, Read};
+use std::process;
+
+pub fn read_geopackage_raster(
+    file_name: &String,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    let (path, layer) = split_layer(file_name);
+    let db = SqliteDatabase::open(&path)?;
+    read_tiles(&db, layer, configs, data).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("Error reading GeoPackage raster {}: {}", file_name, e),
+        )
+    })
+}
+
+fn find_row(table: &SqliteTable, column: &str, value: &str) -> Option<usize> {
+    (0..table.rows.len()).find(|r| {
+        table
+            .get(*r, column)
+            .as_str()
+            .map_or(false, |v| v.eq_ignore_ascii_case(value))
+    })
+}
+
+fn missing(what: &str, table_name: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("The {} of the layer '{}' is not defined.", what, table_name),
+    )
+}
+
+fn read_tiles(
+    db: &SqliteDatabase,
+    layer: Option<String>,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    // find the raster layer
+    let contents = db.read_table("gpkg_contents")?;
+    let layers = (0..contents.rows.len())
+        .filter(|r| match contents.get(*r, "data_type").as_str() {
+            Some("tiles") | Some("2d-gridded-coverage") => true,
+            _ => false,
+        })
+        .filter_map(|r| {
+            contents
+                .get(r, "table_name")
+                .as_str()
+                .map(|s| s.to_string())
+        })
+        .collect::<Vec<String>>();
+    let table_name = match layer {
+        Some(l) => layers
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(&l))
+            .cloned()
+            .ok_or(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "There is no raster layer named '{}'. The raster layers are: {}",
+                    l,
+                    layers.join(", ")
+                ),
+            ))?,
+        None => layers.first().cloned().ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "The file does not contain any raster layers.",
+        ))?,
+    };
+    let is_coverage = find_row(&contents, "table_name", &table_name).map_or(false, |r| {
+        contents.get(r, "data_type").as_str() == Some("2d-gridded-coverage")
+    });
+
+    // the tile matrix set and the highest zoom level
+    let matrix_set = db.read_table("gpkg_tile_matrix_set")?;
+    let ms = find_row(&matrix_set, "table_name", &table_name)
+        .ok_or(missing("tile matrix set", &table_name))?;
+    let set_min_x = matrix_set.get(ms, "min_x").as_f64().unwrap_or(0f64);
+    let set_max_y = matrix_set.get(ms, "max_y").as_f64().unwrap_or(0f64);
+    let srs_id = matrix_set.get(ms, "srs_id").as_i64().unwrap_or(-1);
+    let matrices = db.read_table("gpkg_tile_matrix")?;
+    let m = (0..matrices.rows.len())
+        .filter(|r| {
+            matrices
+                .get(*r, "table_name")
+                .as_str()
+                .map_or(false, |t| t.eq_ignore_ascii_case(&table_name))
+        })
+        .max_by_key(|r| matrices.get(*r, "zoom_level").as_i64().unwrap_or(-1))
+        .ok_or(missing("tile matrix", &table_name))?;
+    let zoom_level = matrices.get(m, "zoom_level").as_i64().unwrap_or(0);
+    let tile_width = matrices.get(m, "tile_width").as_i64().unwrap_or(256) as usize;
+    let tile_height = matrices.get(m, "tile_height").as_i64().unwrap_or(256) as usize;
+    let pixel_x_size = matrices.get(m, "pixel_x_size").as_f64().unwrap_or(1f64);
+    let pixel_y_size = matrices.get(m, "pixel_y_size").as_f64().unwrap_or(1f64);
+
+    // the tiles of the zoom level, and the range of tile columns and rows they cover
+    let tiles = db.read_table(&table_name)?;
+    let tile_rows = (0..tiles.rows.len())
+        .filter(|r| tiles.get(*r, "zoom_level").as_i64() == Some(zoom_level))
+        .collect::<Vec<usize>>();
+    if tile_rows.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("The layer '{}' does not contain any tiles.", table_name),
+        ));
+    }
+    let tile_col = |r: usize| tiles.get(r, "tile_column").as_i64().unwrap_or(0) as usize;
+    let tile_row = |r: usize| tiles.get(r, "tile_row").as_i64().unwrap_or(0) as usize;
+    let min_col = tile_rows.iter().map(|r| tile_col(*r)).min().unwrap();
+    let max_col = tile_rows.iter().map(|r| tile_col(*r)).max().unwrap();
+    let min_row = tile_rows.iter().map(|r| tile_row(*r)).min().unwrap();
+    let max_row = tile_rows.iter().map(|r| tile_row(*r)).max().unwrap();
+
+    configs.columns = (max_col - min_col + 1) * tile_width;
+    configs.rows = (max_row - min_row + 1) * tile_height;
+    configs.bands = 1;
+    configs.resolution_x = pixel_x_size;
+    configs.resolution_y = pixel_y_size;
+    configs.west = set_min_x + (min_col * tile_width) as f64 * pixel_x_size;
+    configs.east = configs.west + configs.columns as f64 * pixel_x_size;
+    configs.north = set_max_y - (min_row * tile_height) as f64 * pixel_y_size;
+    configs.south = configs.north - configs.rows as f64 * pixel_y_size;
+    read_srs(db, srs_id, configs)?;
+
+    // the scale, offset, and nodata value of a gridded coverage
+    let mut scale = 1f64;
+    let mut offset = 0f64;
+    let mut data_null = f64::NAN;
+    let mut tile_ancillary: Option<SqliteTable> = None;
+    if is_coverage {
+        let ancillary = db.read_table("gpkg_2d_gridded_coverage_ancillary")?;
+        if let Some(a) = find_row(&ancillary, "tile_matrix_set_name", &table_name) {
+            scale = ancillary.get(a, "scale").as_f64().unwrap_or(1f64);
+            offset = ancillary.get(a, "offset").as_f64().unwrap_or(0f64);
+            data_null = ancillary.get(a, "data_null").as_f64().unwrap_or(f64::NAN);
+        }
+        if db.has_table("gpkg_2d_gridded_tile_ancillary") {
+            tile_ancillary = Some(db.read_table("gpkg_2d_gridded_tile_ancillary")?);
+        }
+        configs.nodata = -32768f64;
+        configs.data_type = DataType::F32;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+    } else {
+        configs.nodata = 0f64;
+        configs.data_type = DataType::RGBA32;
+        configs.photometric_interp = PhotometricInterpretation::RGB;
+    }
+
+    data.clear();
+    data.resize(configs.rows * configs.columns, configs.nodata);
+    for r in tile_rows {
+        let bytes = match tiles.get(r, "tile_data") {
+            SqliteValue::Blob(b) => b,
+            _ => continue,
+        };
+        let row_offset = (tile_row(r) - min_row) * tile_height;
+        let col_offset = (tile_col(r) - min_col) * tile_width;
+        let mut place = |x: usize, y: usize, value: f64| {
+            if x < tile_width && y < tile_height {
+                data[(row_offset + y) * configs.columns + col_offset + x] = value;
+            }
+        };
+        if is_coverage {
+            // the tile's own scale and offset, keyed by the tile's id
+            let (tile_scale, tile_offset) = match &tile_ancillary {
+                Some(t) => {
+                    let id = tiles.get(r, "id").as_i64();
+                    match (0..t.rows.len()).find(|a| {
+                        t.get(*a, "tpudt_id").as_i64() == id
+                            && t.get(*a, "tpudt_name")
+                                .as_str()
+                                .map_or(false, |n| n.eq_ignore_ascii_case(&table_name))
+                    }) {
+                        Some(a) => (
+                            t.get(a, "scale").as_f64().unwrap_or(1f64),
+                            t.get(a, "offset").as_f64().unwrap_or(0f64),
+                        ),
+                        None => (1f64, 0f64),
+                    }
+                }
+                None => (1f64, 0f64),
+            };
+            let (width, values) = if is_png(bytes) {
+                let image = decode_png(bytes)?;
+                let values = image
+                    .samples
+                    .iter()
+                    .step_by(image.channels)
+                    .map(|v| *v as f64);
+                (image.width, values.collect::<Vec<f64>>())
+            } else {
+                decode_tiff(bytes)?
+            };
+            for (i, v) in values.into_iter().enumerate() {
+                let value = if v == data_null || v.is_nan() {
+                    configs.nodata
+                } else {
+                    (v * tile_scale + tile_offset) * scale + offset
+                };
+                place(i % width, i / width, value);
+            }
+        } else {
+            let (width, pixels) = decode_image(bytes)?;
+            for (i, p) in pixels.into_iter().enumerate() {
+                let value = if p[3] == 0 {
+                    configs.nodata
+                } else {
+                    (((p[3] as u32) << 24)
+                        | ((p[2] as u32) << 16)
+                        | ((p[1] as u32) << 8)
+                        | p[0] as u32) as f64
+                };
+                place(i % width, i / width, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sets the projection of the raster from a GeoPackage spatial reference system.
+fn read_srs(db: &SqliteDatabase, srs_id: i64, configs: &mut RasterConfigs) -> Result<(), Error> {
+    if !db.has_table("gpkg_spatial_ref_sys") {
+        return Ok(());
+    }
+    let srs = db.read_table("gpkg_spatial_ref_sys")?;
+    if let Some(r) = (0..srs.rows.len()).find(|r| srs.get(*r, "srs_id").as_i64() == Some(srs_id)) {
+        let organization = srs.get(r, "organization").as_str().unwrap_or("");
+        let code = srs.get(r, "organization_coordsys_id").as_i64().unwrap_or(0);
+        let definition = srs.get(r, "definition").as_str().unwrap_or("");
+        if organization.eq_ignore_ascii_case("EPSG") && code > 0 && code <= u16::MAX as i64 {
+            configs.epsg_code = code as u16;
+            configs.coordinate_ref_system_wkt = esri_wkt_from_epsg(configs.epsg_code);
+        } else if definition != "undefined" {
+            configs.coordinate_ref_system_wkt = definition.to_string();
+        }
+    }
+    Ok(())
+}
+
+fn is_png(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A])
+}
+
+/// Decodes a PNG or JPEG image tile into its width and RGBA pixels.
+fn decode_image(bytes: &[u8]) -> Result<(usize, Vec<[u8; 4]>), Error> {
+    if is_png(bytes) {
+        let image = decode_png(bytes)?;
+        Ok((image.width, image.rgba()))
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        let mut decoder = jpeg_decoder::Decoder::new(bytes);
+        let pixels = decoder
+            .decode()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}", e)))?;
+        let info = decoder.info().unwrap();
+        let rgba = match info.pixel_format {
+            jpeg_decoder::PixelFormat::L8 => pixels.iter().map(|v| [*v, *v, *v, 255]).collect(),
+            jpeg_decoder::PixelFormat::RGB24 => {
+                pixels.chunks(3).map(|p| [p[0], p[1], p[2], 255]).collect()
+            }
+            jpeg_decoder::PixelFormat::CMYK32 => pixels
+                .chunks(4)
+                .map(|p| {
+                    let k = 255 - p[3] as u32;
+                    let c = |v: u8| ((255 - v as u32) * k / 255) as u8;
+                    [c(p[0]), c(p[1]), c(p[2]), 255]
+                })
+                .collect(),
+        };
+        Ok((info.width as usize, rgba))
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            "A tile is not in a supported image format (PNG or JPEG).",
+        ))
+    }
+}
+
+/// Decodes a floating-point TIFF coverage tile using the GeoTIFF reader.
+fn decode_tiff(bytes: &[u8]) -> Result<(usize, Vec<f64>), Error> {
+    if !bytes.starts_with(b"II*\0") && !bytes.starts_with(b"MM\0*") {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "A coverage tile is not in a supported format (PNG or TIFF).",
+        ));
+    }
+    let temp_file = env::temp_dir()
+        .join(format!("wbt_gpkg_tile_{}.tif", process::id()))
+        .to_string_lossy()
+        .to_string();
+    fs::write(&temp_file, bytes)?;
+    let mut configs = RasterConfigs::default();
+    let mut values = vec![];
+    let result = read_geotiff(&temp_file, &mut configs, &mut values);
+    let _ = fs::remove_file(&temp_file);
+    result?;
+    Ok((configs.columns, values))
+}
+
+/// A decoded PNG image, with its samples widened to 16 bits.
+struct PngImage {
+    width: usize,
+    height: usize,
+    color_type: u8,
+    bit_depth: u8,
+    channels: usize,
+    samples: Vec<u16>,
+    palette: Vec<[u8; 3]>,
+    transparency: Vec<u16>,
+}
+
+impl PngImage {
+    /// Returns the pixels as 8-bit RGBA.
+    fn rgba(&self) -> Vec<[u8; 4]> {
+        let to8 = |v: u16| match self.bit_depth {
+            16 => (v >> 8) as u8,
+            8 => v as u8,
+            d => (v as u32 * 255 / ((1u32 << d) - 1)) as u8,
+        };
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+        for s in self.samples.chunks(self.channels) {
+            pixels.push(match self.color_type {
+                0 => {
+                    let a = if self.transparency.first() == Some(&s[0]) {
+                        0
+                    } else {
+                        255
+                    };
+                    [to8(s[0]), to8(s[0]), to8(s[0]), a]
+                }
+                2 => {
+                    let a = if self.transparency.len() == 3 && self.transparency[..] == s[..] {
+                        0
+                    } else {
+                        255
+                    };
+                    [to8(s[0]), to8(s[1]), to8(s[2]), a]
+                }
+                3 => {
+                    let i = s[0] as usize;
+                    let c = self.palette.get(i).cloned().unwrap_or([0, 0, 0]);
+                    let a = self.transparency.get(i).map_or(255, |a| *a as u8);
+                    [c[0], c[1], c[2], a]
+                }
+                4 => [to8(s[0]), to8(s[0]), to8(s[0]), to8(s[1])],
+                _ => [to8(s[0]), to8(s[1]), to8(s[2]), to8(s[3])],
+            });
+        }
+        pixels
+    }
+}
+
+fn png_error(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("PNG decoding error: {}", message),
+    )
+}
+
+/// Decodes a non-interlaced PNG image of any colour type and bit depth.
+fn decode_png(bytes: &[u8]) -> Result<PngImage, Error> {
+    let mut pos = 8;
+    let mut image = PngImage {
+        width: 0,
+        height: 0,
+        color_type: 0,
+        bit_depth: 8,
+        channels: 1,
+        samples: vec![],
+        palette: vec![],
+        transparency: vec![],
+    };
+    let mut compressed = vec![];
+    while pos + 8 <= bytes.len() {
+        let length =
+            u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+                as usize;
+        let chunk_type = &bytes[pos + 4..pos + 8];
+        if pos + 12 + length > bytes.len() {
+            return Err(png_error("a chunk is truncated"));
+        }
+        let chunk = &bytes[pos + 8..pos + 8 + length];
+        match chunk_type {
+            b"IHDR" => {
+                if length < 13 {
+                    return Err(png_error("the header is truncated"));
+                }
+                image.width = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize;
+                image.height =
+                    u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as usize;
+                image.bit_depth = chunk[8];
+                image.color_type = chunk[9];
+                image.channels = match image.color_type {
+                    0 | 3 => 1,
+                    2 => 3,
+                    4 => 2,
+                    6 => 4,
+                    _ => return Err(png_error("the colour type is invalid")),
+                };
+                if chunk[12] != 0 {
+                    return Err(png_error("interlaced images are not supported"));
+                }
+            }
+            b"PLTE" => {
+                image.palette = chunk.chunks(3).map(|c| [c[0], c[1], c[2]]).collect();
+            }
+            b"tRNS" => {
+                image.transparency = if image.color_type == 3 {
+                    chunk.iter().map(|a| *a as u16).collect()
+                } else {
+                    chunk
+                        .chunks(2)
+                        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                        .collect()
+                };
+            }
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + length;
+    }
+    if image.width == 0 || image.height == 0 {
+        return Err(png_error("the image has no header"));
+    }
+
+    let mut raw = vec![];
+    let mut decoder = Decoder::new(&compressed[..])?;
+    decoder.read_to_end(&mut raw)?;
+
+    // reverse the scanline filters
+    let bits_per_pixel = image.channels * image.bit_depth as usize;
+    let bpp = ((bits_per_pixel + 7) / 8).max(1);
+    let stride = (image.width * bits_per_pixel + 7) / 8;
+    if raw.len() < image.height * (stride + 1) {
+        return Err(png_error("the image data are truncated"));
+    }
+    let mut lines = vec![0u8; image.height * stride];
+    for y in 0..image.height {
+        let filter = raw[y * (stride + 1)];
+        let src = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        let (previous, current) = lines.split_at_mut(y * stride);
+        let prior = if y > 0 {
+            &previous[(y - 1) * stride..]
+        } else {
+            &[][..]
+        };
+        let line = &mut current[..stride];
+        for x in 0..stride {
+            let a = if x >= bpp { line[x - bpp] as i16 } else { 0 };
+            let b = if y > 0 { prior[x] as i16 } else { 0 };
+            let c = if y > 0 && x >= bpp {
+                prior[x - bpp] as i16
+            } else {
+                0
+            };
+            let predictor = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => (a + b) / 2,
+                4 => {
+                    let p = a + b - c;
+                    let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+                    if pa <= pb && pa <= pc {
+                        a
+                    } else if pb <= pc {
+                        b
+                    } else {
+                        c
+                    }
+                }
+                _ => return Err(png_error("a scanline filter type is invalid")),
+            };
+            line[x] = src[x].wrapping_add(predictor as u8);
+        }
+    }
+
+    // unpack the samples
+    let samples_per_line = image.width * image.channels;
+    image.samples.reserve(image.height * samples_per_line);
+    for line in lines.chunks(stride) {
+        match image.bit_depth {
+            16 => image
+                .samples
+                .extend(line.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]]))),
+            8 => image.samples.extend(line.iter().map(|v| *v as u16)),
+            d => {
+                let d = d as usize;
+                let mask = (1u16 << d) - 1;
+                for i in 0..samples_per_line {
+                    let bit = i * d;
+                    let shift = 8 - d - bit % 8;
+                    image.samples.push((line[bit / 8] as u16 >> shift) & mask);
+                }
+            }
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod test {
+    use super::decode_png;
+    use crate::raster::Raster;
+    use crate::utils::{write_sqlite_database, SqliteTable, SqliteValue};
+    use crate::vector::geopackage::{GPKG_APPLICATION_ID, GPKG_USER_VERSION};
+    use libflate::zlib::Encoder;
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+
+    const SRS_TABLE_SQL: &str = "CREATE TABLE gpkg_spatial_ref_sys (srs_name TEXT NOT NULL, srs_id INTEGER PRIMARY KEY, organization TEXT NOT NULL, organization_coordsys_id INTEGER NOT NULL, definition TEXT NOT NULL, description TEXT)";
+
+    const CONTENTS_TABLE_SQL: &str = "CREATE TABLE gpkg_contents (table_name TEXT NOT NULL PRIMARY KEY, data_type TEXT NOT NULL, identifier TEXT UNIQUE, srs_id INTEGER)";
+
+    const TILE_MATRIX_SET_TABLE_SQL: &str = "CREATE TABLE gpkg_tile_matrix_set (table_name TEXT NOT NULL PRIMARY KEY, srs_id INTEGER NOT NULL, min_x DOUBLE NOT NULL, min_y DOUBLE NOT NULL, max_x DOUBLE NOT NULL, max_y DOUBLE NOT NULL)";
+
+    const TILE_MATRIX_TABLE_SQL: &str = "CREATE TABLE gpkg_tile_matrix (table_name TEXT NOT NULL, zoom_level INTEGER NOT NULL, matrix_width INTEGER NOT NULL, matrix_height INTEGER NOT NULL, tile_width INTEGER NOT NULL, tile_height INTEGER NOT NULL, pixel_x_size DOUBLE NOT NULL, pixel_y_size DOUBLE NOT NULL, CONSTRAINT pk_ttm PRIMARY KEY (table_name, zoom_level))";
+
+    const COVERAGE_ANCILLARY_TABLE_SQL: &str = "CREATE TABLE gpkg_2d_gridded_coverage_ancillary (id INTEGER PRIMARY KEY AUTOINCREMENT, tile_matrix_set_name TEXT NOT NULL UNIQUE, datatype TEXT NOT NULL DEFAULT 'integer', scale REAL NOT NULL DEFAULT 1.0, offset REAL NOT NULL DEFAULT 0.0, precision REAL DEFAULT 1.0, data_null REAL)";
+
+    const TILE_ANCILLARY_TABLE_SQL: &str = "CREATE TABLE gpkg_2d_gridded_tile_ancillary (id INTEGER PRIMARY KEY AUTOINCREMENT, tpudt_name TEXT NOT NULL, tpudt_id INTEGER NOT NULL, scale REAL NOT NULL DEFAULT 1.0, offset REAL NOT NULL DEFAULT 0.0, UNIQUE (tpudt_name, tpudt_id))";
+
+    const TILES_TABLE_SQL: &str = "CREATE TABLE {name} (id INTEGER PRIMARY KEY AUTOINCREMENT, zoom_level INTEGER NOT NULL, tile_column INTEGER NOT NULL, tile_row INTEGER NOT NULL, tile_data BLOB NOT NULL, UNIQUE (zoom_level, tile_column, tile_row))";
+
+    fn png(width: u32, height: u32, bit_depth: u8, color_type: u8, scanlines: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        let mut chunk = |chunk_type: &[u8], data: &[u8]| {
+            bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(chunk_type);
+            bytes.extend_from_slice(data);
+            bytes.extend_from_slice(&[0u8; 4]); // the CRC is not checked
+        };
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+        chunk(b"IHDR", &header);
+        let mut encoder = Encoder::new(Vec::new()).unwrap();
+        encoder.write_all(scanlines).unwrap();
+        chunk(b"IDAT", &encoder.finish().into_result().unwrap());
+        chunk(b"IEND", &[]);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_png() {
+        // 16-bit grey, with the Sub, Up, and Paeth filters
+        let scanlines = [
+            1, 0x01, 0x00, 0x00, 0x10, //
+            2, 0x00, 0x01, 0x00, 0x01, //
+            4, 0x00, 0x00, 0x00, 0x02,
+        ];
+        let image = decode_png(&png(2, 3, 16, 0, &scanlines)).unwrap();
+        assert_eq!(image.samples, vec![256, 272, 257, 273, 257, 275]);
+
+        // 8-bit RGBA, with the Average filter
+        let scanlines = [
+            0, 10, 20, 30, 255, 50, 60, 70, 0, //
+            3, 4, 6, 8, 0, 0, 0, 0, 127,
+        ];
+        let pixels = decode_png(&png(2, 2, 8, 6, &scanlines)).unwrap().rgba();
+        assert_eq!(
+            pixels,
+            vec![
+                [10, 20, 30, 255],
+                [50, 60, 70, 0],
+                [9, 16, 23, 127],
+                [29, 38, 46, 190]
+            ]
+        );
+
+        // 2-bit grey, scaled to 8 bits
+        let pixels = decode_png(&png(3, 1, 2, 0, &[0, 0b0001_1100]))
+            .unwrap()
+            .rgba();
+        assert_eq!(
+            pixels,
+            vec![[0, 0, 0, 255], [85, 85, 85, 255], [255, 255, 255, 255]]
+        );
+    }
+
+    fn table(name: &str, sql: &str, rows: Vec<Vec<SqliteValue>>) -> SqliteTable {
+        let mut table = SqliteTable::new(name, sql);
+        table.rows = rows;
+        table
+    }
+
+    // the scanlines of a 2 x 2 tile of 16-bit grey values, without filtering
+    fn grey16(values: [u16; 4]) -> Vec<u8> {
+        let mut scanlines = vec![];
+        for line in values.chunks(2) {
+            scanlines.push(0);
+            for v in line {
+                scanlines.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        png(2, 2, 16, 0, &scanlines)
+    }
+
+    #[test]
+    fn test_read_geopackage_raster() {
+        use SqliteValue::{Blob, Integer, Null, Real, Text};
+        let text = |s: &str| Text(s.to_string());
+        let tile_sql = |name: &str| TILES_TABLE_SQL.replace("{name}", name);
+        let tile = |id: i64, zoom: i64, column: i64, row: i64, data: Vec<u8>| {
+            vec![
+                Integer(id),
+                Integer(zoom),
+                Integer(column),
+                Integer(row),
+                Blob(data),
+            ]
+        };
+
+        let tables = vec![
+            table(
+                "gpkg_spatial_ref_sys",
+                SRS_TABLE_SQL,
+                vec![vec![
+                    text("NAD83 / UTM zone 17N"),
+                    Integer(26917),
+                    text("EPSG"),
+                    Integer(26917),
+                    text("undefined"),
+                    Null,
+                ]],
+            ),
+            table(
+                "gpkg_contents",
+                CONTENTS_TABLE_SQL,
+                vec![
+                    vec![
+                        text("elevation"),
+                        text("2d-gridded-coverage"),
+                        text("elevation"),
+                        Integer(26917),
+                    ],
+                    vec![
+                        text("imagery"),
+                        text("tiles"),
+                        text("imagery"),
+                        Integer(26917),
+                    ],
+                ],
+            ),
+            table(
+                "gpkg_tile_matrix_set",
+                TILE_MATRIX_SET_TABLE_SQL,
+                vec![
+                    vec![
+                        text("elevation"),
+                        Integer(26917),
+                        Real(100.0),
+                        Real(192.0),
+                        Real(108.0),
+                        Real(200.0),
+                    ],
+                    vec![
+                        text("imagery"),
+                        Integer(26917),
+                        Real(100.0),
+                        Real(198.0),
+                        Real(102.0),
+                        Real(200.0),
+                    ],
+                ],
+            ),
+            table(
+                "gpkg_tile_matrix",
+                TILE_MATRIX_TABLE_SQL,
+                vec![
+                    vec![
+                        text("elevation"),
+                        Integer(0),
+                        Integer(2),
+                        Integer(2),
+                        Integer(2),
+                        Integer(2),
+                        Real(2.0),
+                        Real(2.0),
+                    ],
+                    vec![
+                        text("elevation"),
+                        Integer(1),
+                        Integer(4),
+                        Integer(4),
+                        Integer(2),
+                        Integer(2),
+                        Real(1.0),
+                        Real(1.0),
+                    ],
+                    vec![
+                        text("imagery"),
+                        Integer(0),
+                        Integer(1),
+                        Integer(1),
+                        Integer(2),
+                        Integer(2),
+                        Real(1.0),
+                        Real(1.0),
+                    ],
+                ],
+            ),
+            table(
+                "gpkg_2d_gridded_coverage_ancillary",
+                COVERAGE_ANCILLARY_TABLE_SQL,
+                vec![vec![
+                    Integer(1),
+                    text("elevation"),
+                    text("integer"),
+                    Real(0.5),
+                    Real(100.0),
+                    Real(1.0),
+                    Real(65535.0),
+                ]],
+            ),
+            table(
+                "gpkg_2d_gridded_tile_ancillary",
+                TILE_ANCILLARY_TABLE_SQL,
+                vec![vec![
+                    Integer(1),
+                    text("elevation"),
+                    Integer(3),
+                    Real(1.0),
+                    Real(10.0),
+                ]],
+            ),
+            // a coarse tile at zoom level 0, and three of the four tiles in columns 1-2 and rows
+            // 0-1 of the 4 x 4 tile matrix at zoom level 1, one of which has its own offset of 10
+            // and one a null value
+            table(
+                "elevation",
+                &tile_sql("elevation"),
+                vec![
+                    tile(1, 0, 0, 0, grey16([1000, 1000, 1000, 1000])),
+                    tile(2, 1, 1, 0, grey16([0, 1, 2, 65535])),
+                    tile(3, 1, 2, 0, grey16([10, 20, 30, 40])),
+                    tile(4, 1, 1, 1, grey16([4, 5, 6, 7])),
+                ],
+            ),
+            // an 8-bit RGBA image tile
+            table(
+                "imagery",
+                &tile_sql("imagery"),
+                vec![tile(
+                    1,
+                    0,
+                    0,
+                    0,
+                    png(
+                        2,
+                        2,
+                        8,
+                        6,
+                        &[
+                            0, 10, 20, 30, 255, 1, 2, 3, 0, 0, 40, 50, 60, 128, 0, 0, 0, 255,
+                        ],
+                    ),
+                )],
+            ),
+        ];
+        let file_name = env::temp_dir()
+            .join("wbt_read_geopackage_raster.gpkg")
+            .to_string_lossy()
+            .to_string();
+        write_sqlite_database(&file_name, &tables, GPKG_APPLICATION_ID, GPKG_USER_VERSION).unwrap();
+
+        // the first raster layer is read by default, from the tiles of the highest zoom level
+        let raster = Raster::new(&file_name, "r").unwrap();
+        let nodata = raster.configs.nodata;
+        assert_eq!((raster.configs.rows, raster.configs.columns), (4, 4));
+        assert_eq!(raster.configs.resolution_x, 1.0);
+        assert_eq!(
+            (
+                raster.configs.west,
+                raster.configs.east,
+                raster.configs.north,
+                raster.configs.south
+            ),
+            (102.0, 106.0, 200.0, 196.0)
+        );
+        assert_eq!(raster.configs.epsg_code, 26917);
+        let expected = [
+            [100.0, 100.5, 110.0, 115.0],
+            [101.0, nodata, 120.0, 125.0],
+            [102.0, 102.5, nodata, nodata],
+            [103.0, 103.5, nodata, nodata],
+        ];
+        for row in 0..4 {
+            for col in 0..4 {
+                assert_eq!(
+                    raster.get_value(row, col),
+                    expected[row as usize][col as usize]
+                );
+            }
+        }
+
+        // a layer selected by name, with its pixels packed as RGBA32 values
+        let raster = Raster::new(&format!("{}:imagery", file_name), "r").unwrap();
+        let _ = fs::remove_file(&file_name);
+        assert_eq!((raster.configs.rows, raster.configs.columns), (2, 2));
+        assert_eq!(raster.configs.nodata, 0.0);
+        let rgba = |r: u32, g: u32, b: u32, a: u32| ((a << 24) | (b << 16) | (g << 8) | r) as f64;
+        assert_eq!(raster.get_value(0, 0), rgba(10, 20, 30, 255));
+        assert_eq!(raster.get_value(0, 1), 0.0);
+        assert_eq!(raster.get_value(1, 0), rgba(40, 50, 60, 128));
+        assert_eq!(raster.get_value(1, 1), rgba(0, 0, 0, 255));
+    }
+}
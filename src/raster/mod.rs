@@ -2,12 +2,13 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 02/06/2017
-Last Modified: 09/02/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
 pub mod arcascii_raster;
 pub mod arcbinary_raster;
+pub mod geopackage_raster;
 pub mod geotiff;
 pub mod grass_raster;
 pub mod idrisi_raster;
@@ -18,6 +19,7 @@ pub mod whitebox_raster;
 
 use self::arcascii_raster::*;
 use self::arcbinary_raster::*;
+use self::geopackage_raster::*;
 use self::geotiff::*;
 use self::grass_raster::*;
 use self::idrisi_raster::*;
@@ -43,7 +45,8 @@ use std::thread;
 
 /// Raster is a common data structure that abstracts over several raster data formats,
 /// including GeoTIFFs, ArcGIS ASCII and binary rasters, Whitebox rasters, Idrisi
-/// rasters, Saga rasters, and GRASS ASCII rasters.
+/// rasters, Saga rasters, and GRASS ASCII rasters. The tiled raster coverages of
+/// GeoPackages (`data.gpkg` or `data.gpkg:layer`) can also be read.
 ///
 /// Examples:
 ///
@@ -146,6 +149,11 @@ impl Raster {
                     r.update_min_max();
                     return Ok(r);
                 }
+                RasterType::GeoPackage => {
+                    let _ = read_geopackage_raster(&r.file_name, &mut r.configs, &mut r.data)?;
+                    r.update_min_max();
+                    return Ok(r);
+                }
                 RasterType::GrassAscii => {
                     let _ = read_grass_raster(&r.file_name, &mut r.configs, &mut r.data)?;
                     return Ok(r);
@@ -1104,6 +1112,12 @@ impl Raster {
                     Err(e) => println!("error while writing: {:?}", e),
                 };
             }
+            RasterType::GeoPackage => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "Writing GeoPackage rasters is not supported.",
+                ));
+            }
             RasterType::Unknown => {
                 return Err(Error::new(ErrorKind::Other, "Unrecognized raster type"));
             }
@@ -1241,6 +1255,7 @@ pub enum RasterType {
     Unknown,
     ArcAscii,
     ArcBinary,
+    GeoPackage,
     GeoTiff,
     GrassAscii,
     IdrisiBinary,
@@ -1276,6 +1291,9 @@ fn get_raster_type_from_file(file_name: String, file_mode: String) -> RasterType
         || extension == "gtiff"
     {
         return RasterType::GeoTiff;
+    } else if extension == "gpkg" || extension.starts_with("gpkg:") {
+        // a layer may be selected using the data.gpkg:layer syntax
+        return RasterType::GeoPackage;
     } else if extension == "flt" {
         return RasterType::ArcBinary;
     } else if extension == "rdc" || extension == "rst" {
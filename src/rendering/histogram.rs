@@ -38,6 +38,8 @@ impl Histogram {
         s.push_str(&format!(
             r#"
     <script>
+      // scoped so that several histograms may be placed within a single page
+      (function() {{
       var data = {};
       var cumulative = {};
       var xAxisLabel = "{}";
@@ -620,6 +622,7 @@ impl Histogram {
       }
 
       update(null);
+      })();
     </script>"#);
 
         s
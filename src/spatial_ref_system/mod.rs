@@ -1,3 +1,5 @@
 mod epsg_to_wkt;
+mod projection;
 
 pub use self::epsg_to_wkt::esri_wkt_from_epsg;
+pub use self::projection::{Projection, ProjectionMethod};
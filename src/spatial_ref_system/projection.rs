@@ -0,0 +1,734 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::esri_wkt_from_epsg;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+
+/// The map projection methods supported by `Projection`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionMethod {
+    Geographic,
+    TransverseMercator,
+    LambertConformalConic,
+    ObliqueStereographic,
+    AlbersEqualArea,
+    Mercator,
+}
+
+/// A coordinate reference system, defined by an ellipsoid, a map projection method and its
+/// parameters, and a linear unit. Coordinates are converted between geographic (longitude,
+/// latitude, in degrees) and projected (x, y, in the linear unit) coordinates on the same
+/// ellipsoid, using the formulae of the EPSG Guidance Note 7-2. The Transverse Mercator
+/// projection uses the 6th-order Krüger series, which is accurate to better than a millimetre
+/// within several thousand kilometres of the central meridian.
+///
+/// Note that no horizontal datum transformation is applied. Coordinates converted between
+/// coordinate systems based on different datums (e.g. NAD27 and NAD83) will therefore contain
+/// the datum shift, which can be up to several hundred metres. Datums sharing a common
+/// ellipsoid and realization (e.g. NAD83(CSRS) and NAD83) are treated as identical.
+#[derive(Clone, Debug)]
+pub struct Projection {
+    pub name: String,
+    pub datum: String,
+    pub method: ProjectionMethod,
+    /// The semi-major axis of the ellipsoid, in metres.
+    pub semi_major_axis: f64,
+    /// The inverse flattening of the ellipsoid; zero for a sphere.
+    pub inverse_flattening: f64,
+    pub false_easting: f64,
+    pub false_northing: f64,
+    /// The central meridian, in degrees.
+    pub central_meridian: f64,
+    pub scale_factor: f64,
+    /// The latitude of origin, in degrees.
+    pub latitude_of_origin: f64,
+    /// The first standard parallel, in degrees, if the projection has one.
+    pub standard_parallel_1: Option<f64>,
+    /// The second standard parallel, in degrees, if the projection has one.
+    pub standard_parallel_2: Option<f64>,
+    /// The size of the linear unit, in metres.
+    pub linear_unit: f64,
+    e: f64,
+    e2: f64,
+    consts: Vec<f64>,
+}
+
+impl Projection {
+    /// Creates a projection from an EPSG code.
+    pub fn from_epsg(code: u16) -> Result<Projection, Error> {
+        let wkt = esri_wkt_from_epsg(code);
+        if wkt == "Unknown EPSG Code" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unknown EPSG code ({}).", code),
+            ));
+        }
+        Projection::from_wkt(&wkt)
+    }
+
+    /// Creates a projection from an ESRI or OGC well-known text (WKT) coordinate system string.
+    pub fn from_wkt(wkt: &str) -> Result<Projection, Error> {
+        let wkt = wkt.trim();
+        let is_projected = wkt.to_uppercase().starts_with("PROJCS");
+        if !is_projected && !wkt.to_uppercase().starts_with("GEOGCS") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized well-known text coordinate system definition.",
+            ));
+        }
+        let name = wkt_values(wkt, "PROJCS")
+            .or_else(|| wkt_values(wkt, "GEOGCS"))
+            .map(|v| v[0].clone())
+            .unwrap_or_default();
+        let datum = wkt_values(wkt, "DATUM")
+            .map(|v| v[0].clone())
+            .unwrap_or_default();
+        let spheroid = match wkt_values(wkt, "SPHEROID").or_else(|| wkt_values(wkt, "ELLIPSOID")) {
+            Some(v) if v.len() >= 3 => v,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The coordinate system definition does not contain a valid SPHEROID.",
+                ))
+            }
+        };
+        let semi_major_axis = parse_wkt_number(&spheroid[1])?;
+        let inverse_flattening = parse_wkt_number(&spheroid[2])?;
+
+        let mut projection = Projection {
+            name: name,
+            datum: datum,
+            method: ProjectionMethod::Geographic,
+            semi_major_axis: semi_major_axis,
+            inverse_flattening: inverse_flattening,
+            false_easting: 0f64,
+            false_northing: 0f64,
+            central_meridian: 0f64,
+            scale_factor: 1f64,
+            latitude_of_origin: 0f64,
+            standard_parallel_1: None,
+            standard_parallel_2: None,
+            linear_unit: 1f64,
+            e: 0f64,
+            e2: 0f64,
+            consts: vec![],
+        };
+
+        if is_projected {
+            let method = wkt_values(wkt, "PROJECTION")
+                .map(|v| v[0].to_lowercase())
+                .unwrap_or_default();
+            projection.method = match method.as_ref() {
+                "transverse_mercator" | "gauss_kruger" => ProjectionMethod::TransverseMercator,
+                "lambert_conformal_conic"
+                | "lambert_conformal_conic_1sp"
+                | "lambert_conformal_conic_2sp" => ProjectionMethod::LambertConformalConic,
+                "double_stereographic" | "oblique_stereographic" => {
+                    ProjectionMethod::ObliqueStereographic
+                }
+                "albers" | "albers_conic_equal_area" => ProjectionMethod::AlbersEqualArea,
+                "mercator" | "mercator_1sp" | "mercator_2sp" => ProjectionMethod::Mercator,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unsupported projection method ({}).", method),
+                    ))
+                }
+            };
+            // The linear unit is the last UNIT within the PROJCS, following the GEOGCS.
+            if let Some(pos) = wkt.to_uppercase().rfind("UNIT[") {
+                if let Some(v) = wkt_values(&wkt[pos..], "UNIT") {
+                    if v.len() >= 2 {
+                        projection.linear_unit = parse_wkt_number(&v[1])?;
+                    }
+                }
+            }
+            for param in wkt_parameters(wkt) {
+                let value = param.1;
+                match param.0.to_lowercase().as_ref() {
+                    "false_easting" => projection.false_easting = value,
+                    "false_northing" => projection.false_northing = value,
+                    "central_meridian" | "longitude_of_origin" | "longitude_of_center" => {
+                        projection.central_meridian = value
+                    }
+                    "scale_factor" => projection.scale_factor = value,
+                    "latitude_of_origin" | "latitude_of_center" => {
+                        projection.latitude_of_origin = value
+                    }
+                    "standard_parallel_1" => projection.standard_parallel_1 = Some(value),
+                    "standard_parallel_2" => projection.standard_parallel_2 = Some(value),
+                    _ => {}
+                }
+            }
+        }
+
+        projection.initialize();
+        Ok(projection)
+    }
+
+    /// Returns true if the coordinate system is geographic, i.e. unprojected.
+    pub fn is_geographic(&self) -> bool {
+        self.method == ProjectionMethod::Geographic
+    }
+
+    /// Returns true if the two coordinate systems are based on the same ellipsoid.
+    pub fn has_same_ellipsoid(&self, other: &Projection) -> bool {
+        (self.semi_major_axis - other.semi_major_axis).abs() < 0.001
+            && (self.inverse_flattening - other.inverse_flattening).abs() < 1e-6
+    }
+
+    // Precomputes the constants of the projection that do not depend on the coordinates.
+    fn initialize(&mut self) {
+        let f = if self.inverse_flattening > 0f64 {
+            1f64 / self.inverse_flattening
+        } else {
+            0f64
+        };
+        self.e2 = f * (2f64 - f);
+        self.e = self.e2.sqrt();
+        let a = self.semi_major_axis;
+        let e = self.e;
+        let e2 = self.e2;
+        let lat0 = self.latitude_of_origin.to_radians();
+        match self.method {
+            ProjectionMethod::Geographic => {}
+            ProjectionMethod::TransverseMercator => {
+                // consts = [A, alpha 1-6, beta 1-6, M0]
+                let n = f / (2f64 - f);
+                let n2 = n * n;
+                let n3 = n2 * n;
+                let n4 = n3 * n;
+                let n5 = n4 * n;
+                let n6 = n5 * n;
+                let big_a = a / (1f64 + n) * (1f64 + n2 / 4f64 + n4 / 64f64 + n6 / 256f64);
+                let alpha = [
+                    n / 2f64 - 2f64 * n2 / 3f64 + 5f64 * n3 / 16f64 + 41f64 * n4 / 180f64
+                        - 127f64 * n5 / 288f64
+                        + 7891f64 * n6 / 37800f64,
+                    13f64 * n2 / 48f64 - 3f64 * n3 / 5f64
+                        + 557f64 * n4 / 1440f64
+                        + 281f64 * n5 / 630f64
+                        - 1983433f64 * n6 / 1935360f64,
+                    61f64 * n3 / 240f64 - 103f64 * n4 / 140f64
+                        + 15061f64 * n5 / 26880f64
+                        + 167603f64 * n6 / 181440f64,
+                    49561f64 * n4 / 161280f64 - 179f64 * n5 / 168f64 + 6601661f64 * n6 / 7257600f64,
+                    34729f64 * n5 / 80640f64 - 3418889f64 * n6 / 1995840f64,
+                    212378941f64 * n6 / 319334400f64,
+                ];
+                let beta = [
+                    n / 2f64 - 2f64 * n2 / 3f64 + 37f64 * n3 / 96f64
+                        - n4 / 360f64
+                        - 81f64 * n5 / 512f64
+                        + 96199f64 * n6 / 604800f64,
+                    n2 / 48f64 + n3 / 15f64 - 437f64 * n4 / 1440f64 + 46f64 * n5 / 105f64
+                        - 1118711f64 * n6 / 3870720f64,
+                    17f64 * n3 / 480f64 - 37f64 * n4 / 840f64 - 209f64 * n5 / 4480f64
+                        + 5569f64 * n6 / 90720f64,
+                    4397f64 * n4 / 161280f64 - 11f64 * n5 / 504f64 - 830251f64 * n6 / 7257600f64,
+                    4583f64 * n5 / 161280f64 - 108847f64 * n6 / 3991680f64,
+                    20648693f64 * n6 / 638668800f64,
+                ];
+                self.consts = vec![big_a];
+                self.consts.extend_from_slice(&alpha);
+                self.consts.extend_from_slice(&beta);
+                // the meridian distance of the latitude of origin
+                let chi0 = conformal_latitude(lat0, e);
+                let mut xi0 = chi0;
+                for j in 0..6 {
+                    xi0 += alpha[j] * (2f64 * (j + 1) as f64 * chi0).sin();
+                }
+                self.consts.push(big_a * xi0);
+            }
+            ProjectionMethod::LambertConformalConic => {
+                // consts = [n, F, r0]
+                let (n, big_f) = match (self.standard_parallel_1, self.standard_parallel_2) {
+                    (Some(sp1), Some(sp2)) => {
+                        let (lat1, lat2) = (sp1.to_radians(), sp2.to_radians());
+                        let (m1, m2) = (lcc_m(lat1, e2), lcc_m(lat2, e2));
+                        let (t1, t2) = (lcc_t(lat1, e), lcc_t(lat2, e));
+                        let n = if (lat1 - lat2).abs() > 1e-10 {
+                            (m1.ln() - m2.ln()) / (t1.ln() - t2.ln())
+                        } else {
+                            lat1.sin()
+                        };
+                        (n, m1 / (n * t1.powf(n)))
+                    }
+                    _ => {
+                        // 1SP; the natural origin is on the latitude of origin
+                        let n = lat0.sin();
+                        (n, lcc_m(lat0, e2) / (n * lcc_t(lat0, e).powf(n)))
+                    }
+                };
+                let r0 = a * big_f * lcc_t(lat0, e).powf(n) * self.scale_factor;
+                self.consts = vec![n, big_f, r0];
+            }
+            ProjectionMethod::ObliqueStereographic => {
+                // consts = [R, n, c, chi0]
+                let sin_lat0 = lat0.sin();
+                let rho0 = a * (1f64 - e2) / (1f64 - e2 * sin_lat0 * sin_lat0).powf(1.5);
+                let nu0 = a / (1f64 - e2 * sin_lat0 * sin_lat0).sqrt();
+                let r = (rho0 * nu0).sqrt();
+                let n = (1f64 + e2 * lat0.cos().powi(4) / (1f64 - e2)).sqrt();
+                let s1 = (1f64 + sin_lat0) / (1f64 - sin_lat0);
+                let s2 = (1f64 - e * sin_lat0) / (1f64 + e * sin_lat0);
+                let w1 = (s1 * s2.powf(e)).powf(n);
+                let sin_chi0 = (w1 - 1f64) / (w1 + 1f64);
+                let c = (n + sin_lat0) * (1f64 - sin_chi0) / ((n - sin_lat0) * (1f64 + sin_chi0));
+                let w2 = c * w1;
+                let chi0 = ((w2 - 1f64) / (w2 + 1f64)).asin();
+                self.consts = vec![r, n, c, chi0];
+            }
+            ProjectionMethod::AlbersEqualArea => {
+                // consts = [n, C, rho0]
+                let lat1 = self
+                    .standard_parallel_1
+                    .unwrap_or(self.latitude_of_origin)
+                    .to_radians();
+                let lat2 = self
+                    .standard_parallel_2
+                    .unwrap_or(lat1.to_degrees())
+                    .to_radians();
+                let (m1, m2) = (lcc_m(lat1, e2), lcc_m(lat2, e2));
+                let (a1, a2) = (albers_alpha(lat1, e), albers_alpha(lat2, e));
+                let n = if (lat1 - lat2).abs() > 1e-10 {
+                    (m1 * m1 - m2 * m2) / (a2 - a1)
+                } else {
+                    lat1.sin()
+                };
+                let c = m1 * m1 + n * a1;
+                let rho0 = a * (c - n * albers_alpha(lat0, e)).sqrt() / n;
+                self.consts = vec![n, c, rho0];
+            }
+            ProjectionMethod::Mercator => {
+                // consts = [k0]; variant B uses the standard parallel, variant A the scale factor
+                let k0 = match self.standard_parallel_1 {
+                    Some(sp1) => lcc_m(sp1.to_radians(), e2),
+                    None => self.scale_factor,
+                };
+                self.consts = vec![k0];
+            }
+        }
+    }
+
+    /// Converts geographic coordinates (longitude and latitude, in degrees) into projected
+    /// coordinates. For a geographic coordinate system, the coordinates are returned unchanged.
+    pub fn forward(&self, longitude: f64, latitude: f64) -> (f64, f64) {
+        if self.method == ProjectionMethod::Geographic {
+            return (longitude, latitude);
+        }
+        let a = self.semi_major_axis;
+        let e = self.e;
+        let lat = latitude.to_radians();
+        let dlon = normalize_longitude((longitude - self.central_meridian).to_radians());
+        let fe = self.false_easting * self.linear_unit;
+        let fn_ = self.false_northing * self.linear_unit;
+        let k0 = self.scale_factor;
+        let (x, y) = match self.method {
+            ProjectionMethod::TransverseMercator => {
+                let big_a = self.consts[0];
+                let alpha = &self.consts[1..7];
+                let m0 = self.consts[13];
+                let t = (lat.sin().atanh() - e * (e * lat.sin()).atanh()).sinh();
+                let xi_p = t.atan2(dlon.cos());
+                let eta_p = (dlon.sin() / (1f64 + t * t).sqrt()).atanh();
+                let mut xi = xi_p;
+                let mut eta = eta_p;
+                for j in 0..6 {
+                    let k = 2f64 * (j + 1) as f64;
+                    xi += alpha[j] * (k * xi_p).sin() * (k * eta_p).cosh();
+                    eta += alpha[j] * (k * xi_p).cos() * (k * eta_p).sinh();
+                }
+                (fe + k0 * big_a * eta, fn_ + k0 * (big_a * xi - m0))
+            }
+            ProjectionMethod::LambertConformalConic => {
+                let (n, big_f, r0) = (self.consts[0], self.consts[1], self.consts[2]);
+                let r = a * big_f * lcc_t(lat, e).powf(n) * k0;
+                let theta = n * dlon;
+                (fe + r * theta.sin(), fn_ + r0 - r * theta.cos())
+            }
+            ProjectionMethod::ObliqueStereographic => {
+                let (r, n, c, chi0) = (
+                    self.consts[0],
+                    self.consts[1],
+                    self.consts[2],
+                    self.consts[3],
+                );
+                let sin_lat = lat.sin();
+                let sa = (1f64 + sin_lat) / (1f64 - sin_lat);
+                let sb = (1f64 - e * sin_lat) / (1f64 + e * sin_lat);
+                let w = c * (sa * sb.powf(e)).powf(n);
+                let chi = ((w - 1f64) / (w + 1f64)).asin();
+                let dl = n * dlon;
+                let b = 1f64 + chi.sin() * chi0.sin() + chi.cos() * chi0.cos() * dl.cos();
+                (
+                    fe + 2f64 * r * k0 * chi.cos() * dl.sin() / b,
+                    fn_ + 2f64
+                        * r
+                        * k0
+                        * (chi.sin() * chi0.cos() - chi.cos() * chi0.sin() * dl.cos())
+                        / b,
+                )
+            }
+            ProjectionMethod::AlbersEqualArea => {
+                let (n, c, rho0) = (self.consts[0], self.consts[1], self.consts[2]);
+                let theta = n * dlon;
+                let rho = a * (c - n * albers_alpha(lat, e)).sqrt() / n;
+                (fe + rho * theta.sin(), fn_ + rho0 - rho * theta.cos())
+            }
+            ProjectionMethod::Mercator => {
+                let k0 = self.consts[0];
+                (
+                    fe + a * k0 * dlon,
+                    fn_ + a
+                        * k0
+                        * ((PI / 4f64 + lat / 2f64).tan()
+                            * ((1f64 - e * lat.sin()) / (1f64 + e * lat.sin())).powf(e / 2f64))
+                        .ln(),
+                )
+            }
+            ProjectionMethod::Geographic => (longitude, latitude),
+        };
+        (x / self.linear_unit, y / self.linear_unit)
+    }
+
+    /// Converts projected coordinates into geographic coordinates (longitude and latitude, in
+    /// degrees). For a geographic coordinate system, the coordinates are returned unchanged.
+    pub fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        if self.method == ProjectionMethod::Geographic {
+            return (x, y);
+        }
+        let a = self.semi_major_axis;
+        let e = self.e;
+        let e2 = self.e2;
+        let k0 = self.scale_factor;
+        let de = (x - self.false_easting) * self.linear_unit;
+        let dn = (y - self.false_northing) * self.linear_unit;
+        let (dlon, lat) = match self.method {
+            ProjectionMethod::TransverseMercator => {
+                let big_a = self.consts[0];
+                let beta = &self.consts[7..13];
+                let m0 = self.consts[13];
+                let xi = (dn + k0 * m0) / (k0 * big_a);
+                let eta = de / (k0 * big_a);
+                let mut xi_p = xi;
+                let mut eta_p = eta;
+                for j in 0..6 {
+                    let k = 2f64 * (j + 1) as f64;
+                    xi_p -= beta[j] * (k * xi).sin() * (k * eta).cosh();
+                    eta_p -= beta[j] * (k * xi).cos() * (k * eta).sinh();
+                }
+                let tau_p = xi_p.sin() / (eta_p.sinh().powi(2) + xi_p.cos().powi(2)).sqrt();
+                let dlon = eta_p.sinh().atan2(xi_p.cos());
+                (dlon, latitude_from_conformal_tangent(tau_p, e).atan())
+            }
+            ProjectionMethod::LambertConformalConic => {
+                let (n, big_f, r0) = (self.consts[0], self.consts[1], self.consts[2]);
+                let sign = n.signum();
+                let r = sign * (de * de + (r0 - dn) * (r0 - dn)).sqrt();
+                let t = (r / (a * k0 * big_f)).powf(1f64 / n);
+                let theta = (sign * de).atan2(sign * (r0 - dn));
+                (theta / n, latitude_from_isometric_t(t, e))
+            }
+            ProjectionMethod::ObliqueStereographic => {
+                let (r, n, c, chi0) = (
+                    self.consts[0],
+                    self.consts[1],
+                    self.consts[2],
+                    self.consts[3],
+                );
+                let g = 2f64 * r * k0 * (PI / 4f64 - chi0 / 2f64).tan();
+                let h = 4f64 * r * k0 * chi0.tan() + g;
+                let i = (de / (h + dn)).atan();
+                let j = (de / (g - dn)).atan() - i;
+                let chi = chi0 + 2f64 * ((dn - de * (j / 2f64).tan()) / (2f64 * r * k0)).atan();
+                let dl = j + 2f64 * i;
+                let psi = 0.5f64 * ((1f64 + chi.sin()) / (c * (1f64 - chi.sin()))).ln() / n;
+                let mut lat = 2f64 * psi.exp().atan() - PI / 2f64;
+                for _ in 0..20 {
+                    let sin_lat = lat.sin();
+                    let psi_i = ((lat / 2f64 + PI / 4f64).tan()
+                        * ((1f64 - e * sin_lat) / (1f64 + e * sin_lat)).powf(e / 2f64))
+                    .ln();
+                    let delta =
+                        (psi_i - psi) * lat.cos() * (1f64 - e2 * sin_lat * sin_lat) / (1f64 - e2);
+                    lat -= delta;
+                    if delta.abs() < 1e-14 {
+                        break;
+                    }
+                }
+                (dl / n, lat)
+            }
+            ProjectionMethod::AlbersEqualArea => {
+                let (n, c, rho0) = (self.consts[0], self.consts[1], self.consts[2]);
+                let sign = n.signum();
+                let rho = (de * de + (rho0 - dn) * (rho0 - dn)).sqrt();
+                let alpha = (c - rho * rho * n * n / (a * a)) / n;
+                let theta = (sign * de).atan2(sign * (rho0 - dn));
+                let lat = if e > 0f64 {
+                    let alpha_pole =
+                        1f64 - (1f64 - e2) / (2f64 * e) * ((1f64 - e) / (1f64 + e)).ln();
+                    let beta = (alpha / alpha_pole).max(-1f64).min(1f64).asin();
+                    let e4 = e2 * e2;
+                    let e6 = e4 * e2;
+                    beta + (e2 / 3f64 + 31f64 * e4 / 180f64 + 517f64 * e6 / 5040f64)
+                        * (2f64 * beta).sin()
+                        + (23f64 * e4 / 360f64 + 251f64 * e6 / 3780f64) * (4f64 * beta).sin()
+                        + (761f64 * e6 / 45360f64) * (6f64 * beta).sin()
+                } else {
+                    (alpha / 2f64).max(-1f64).min(1f64).asin()
+                };
+                (theta / n, lat)
+            }
+            ProjectionMethod::Mercator => {
+                let k0 = self.consts[0];
+                let t = (-dn / (a * k0)).exp();
+                (de / (a * k0), latitude_from_isometric_t(t, e))
+            }
+            ProjectionMethod::Geographic => (x, y),
+        };
+        (
+            normalize_longitude(dlon + self.central_meridian.to_radians()).to_degrees(),
+            lat.to_degrees(),
+        )
+    }
+
+    /// Transforms a coordinate from this coordinate system into another.
+    pub fn transform_to(&self, other: &Projection, x: f64, y: f64) -> (f64, f64) {
+        let (lon, lat) = self.inverse(x, y);
+        other.forward(lon, lat)
+    }
+}
+
+// Returns the quoted name and the values of the first WKT node with a given keyword,
+// e.g. SPHEROID["GRS_1980",6378137.0,298.257222101] yields ["GRS_1980", "6378137.0", "298.257222101"].
+fn wkt_values(wkt: &str, keyword: &str) -> Option<Vec<String>> {
+    let upper = wkt.to_uppercase();
+    let pattern = format!("{}[", keyword.to_uppercase());
+    let mut start = 0;
+    while let Some(pos) = upper[start..].find(&pattern) {
+        let p = start + pos;
+        // make sure the keyword is not the tail of a longer keyword
+        let preceding = upper[..p].chars().last();
+        if preceding.map_or(true, |c| !c.is_alphanumeric() && c != '_') {
+            return Some(split_wkt_node(&wkt[p + pattern.len()..]));
+        }
+        start = p + pattern.len();
+    }
+    None
+}
+
+// Splits the contents of a WKT node, up to its closing bracket, into its top-level values.
+fn split_wkt_node(s: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut current = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' | '(' if !in_quotes => {
+                depth += 1;
+                current.push(c);
+            }
+            ']' | ')' if !in_quotes => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+                current.push(c);
+            }
+            ',' if !in_quotes && depth == 0 => {
+                values.push(current.trim().to_string());
+                current = String::new();
+            }
+            _ => current.push(c),
+        }
+    }
+    values.push(current.trim().to_string());
+    values
+}
+
+// Returns the name and value of each of the PARAMETER nodes of a WKT string.
+fn wkt_parameters(wkt: &str) -> Vec<(String, f64)> {
+    let mut params = vec![];
+    let upper = wkt.to_uppercase();
+    let mut start = 0;
+    while let Some(pos) = upper[start..].find("PARAMETER[") {
+        let p = start + pos + "PARAMETER[".len();
+        let v = split_wkt_node(&wkt[p..]);
+        if v.len() >= 2 {
+            if let Ok(value) = parse_wkt_number(&v[1]) {
+                params.push((v[0].clone(), value));
+            }
+        }
+        start = p;
+    }
+    params
+}
+
+fn parse_wkt_number(s: &str) -> Result<f64, Error> {
+    s.trim().parse::<f64>().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Error parsing the coordinate system value ({}).", s),
+        )
+    })
+}
+
+// Wraps a longitude, in radians, into the range -PI to PI.
+fn normalize_longitude(lon: f64) -> f64 {
+    let mut lon = lon;
+    while lon > PI {
+        lon -= 2f64 * PI;
+    }
+    while lon < -PI {
+        lon += 2f64 * PI;
+    }
+    lon
+}
+
+fn conformal_latitude(lat: f64, e: f64) -> f64 {
+    (lat.sin().atanh() - e * (e * lat.sin()).atanh())
+        .sinh()
+        .atan()
+}
+
+// Solves for the tangent of the geodetic latitude from the tangent of the conformal latitude
+// using Newton's method (Karney, 2011).
+fn latitude_from_conformal_tangent(tau_p: f64, e: f64) -> f64 {
+    let e2m = 1f64 - e * e;
+    let mut tau = tau_p;
+    for _ in 0..10 {
+        let tau1 = (1f64 + tau * tau).sqrt();
+        let sig = (e * (e * tau / tau1).atanh()).sinh();
+        let tau_pi = tau * (1f64 + sig * sig).sqrt() - sig * tau1;
+        let dtau = (tau_p - tau_pi) / (1f64 + tau_pi * tau_pi).sqrt() * (1f64 + e2m * tau * tau)
+            / (e2m * tau1);
+        tau += dtau;
+        if dtau.abs() < 1e-14 * tau.abs().max(1f64) {
+            break;
+        }
+    }
+    tau
+}
+
+fn lcc_m(lat: f64, e2: f64) -> f64 {
+    lat.cos() / (1f64 - e2 * lat.sin() * lat.sin()).sqrt()
+}
+
+fn lcc_t(lat: f64, e: f64) -> f64 {
+    let es = e * lat.sin();
+    (PI / 4f64 - lat / 2f64).tan() / ((1f64 - es) / (1f64 + es)).powf(e / 2f64)
+}
+
+// The inverse of lcc_t, by iteration.
+fn latitude_from_isometric_t(t: f64, e: f64) -> f64 {
+    let mut lat = PI / 2f64 - 2f64 * t.atan();
+    for _ in 0..20 {
+        let es = e * lat.sin();
+        let new_lat = PI / 2f64 - 2f64 * (t * ((1f64 - es) / (1f64 + es)).powf(e / 2f64)).atan();
+        if (new_lat - lat).abs() < 1e-14 {
+            return new_lat;
+        }
+        lat = new_lat;
+    }
+    lat
+}
+
+fn albers_alpha(lat: f64, e: f64) -> f64 {
+    let sin_lat = lat.sin();
+    if e == 0f64 {
+        return 2f64 * sin_lat;
+    }
+    let e2 = e * e;
+    (1f64 - e2)
+        * (sin_lat / (1f64 - e2 * sin_lat * sin_lat)
+            - 1f64 / (2f64 * e) * ((1f64 - e * sin_lat) / (1f64 + e * sin_lat)).ln())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Projection, ProjectionMethod};
+
+    fn dms(d: f64, m: f64, s: f64) -> f64 {
+        d.signum() * (d.abs() + m / 60f64 + s / 3600f64)
+    }
+
+    #[test]
+    fn test_transverse_mercator() {
+        // EPSG Guidance Note 7-2 example; British National Grid
+        let p = Projection::from_epsg(27700).unwrap();
+        assert_eq!(p.method, ProjectionMethod::TransverseMercator);
+        let (x, y) = p.forward(0.5, 50.5);
+        assert!((x - 577274.99).abs() < 0.01);
+        assert!((y - 69740.50).abs() < 0.01);
+        let (lon, lat) = p.inverse(x, y);
+        assert!((lon - 0.5).abs() < 1e-9);
+        assert!((lat - 50.5).abs() < 1e-9);
+
+        // UTM zone 17N round trip, far from the central meridian
+        let p = Projection::from_epsg(2958).unwrap();
+        let (x, y) = p.forward(-76.5, 45.25);
+        let (lon, lat) = p.inverse(x, y);
+        assert!((lon + 76.5).abs() < 1e-9);
+        assert!((lat - 45.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lambert_conformal_conic() {
+        // EPSG Guidance Note 7-2 example; Texas South Central (NAD27), US survey feet
+        let wkt = "PROJCS[\"Texas_South_Central\",GEOGCS[\"GCS_North_American_1927\",DATUM[\"D_North_American_1927\",SPHEROID[\"Clarke_1866\",6378206.4,294.9786982]],PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]],PROJECTION[\"Lambert_Conformal_Conic\"],PARAMETER[\"False_Easting\",2000000.0],PARAMETER[\"False_Northing\",0.0],PARAMETER[\"Central_Meridian\",-99.0],PARAMETER[\"Standard_Parallel_1\",28.38333333333333],PARAMETER[\"Standard_Parallel_2\",30.28333333333333],PARAMETER[\"Latitude_Of_Origin\",27.83333333333333],UNIT[\"Foot_US\",0.3048006096012192]]";
+        let p = Projection::from_wkt(wkt).unwrap();
+        let (x, y) = p.forward(-96.0, 28.5);
+        assert!((x - 2963503.91).abs() < 0.01);
+        assert!((y - 254759.80).abs() < 0.01);
+        let (lon, lat) = p.inverse(x, y);
+        assert!((lon + 96.0).abs() < 1e-9);
+        assert!((lat - 28.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_oblique_stereographic() {
+        // EPSG Guidance Note 7-2 example; Amersfoort / RD New
+        let wkt = "PROJCS[\"RD_New\",GEOGCS[\"GCS_Amersfoort\",DATUM[\"D_Amersfoort\",SPHEROID[\"Bessel_1841\",6377397.155,299.1528128]],PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]],PROJECTION[\"Double_Stereographic\"],PARAMETER[\"False_Easting\",155000.0],PARAMETER[\"False_Northing\",463000.0],PARAMETER[\"Central_Meridian\",5.38763888888889],PARAMETER[\"Scale_Factor\",0.9999079],PARAMETER[\"Latitude_Of_Origin\",52.15616055555555],UNIT[\"Meter\",1.0]]";
+        let p = Projection::from_wkt(wkt).unwrap();
+        let (x, y) = p.forward(6.0, 53.0);
+        assert!((x - 196105.283).abs() < 0.01);
+        assert!((y - 557057.739).abs() < 0.01);
+        let (lon, lat) = p.inverse(x, y);
+        assert!((lon - 6.0).abs() < 1e-9);
+        assert!((lat - 53.0).abs() < 1e-9);
+        assert!((dms(52.0, 9.0, 22.178) - p.latitude_of_origin).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        // New Brunswick stereographic, Statistics Canada Lambert, and Web Mercator-like
+        for code in [2953u16, 3348, 3978, 3395, 4617].iter() {
+            let p = Projection::from_epsg(*code).unwrap();
+            let (x, y) = p.forward(-66.25, 46.75);
+            let (lon, lat) = p.inverse(x, y);
+            assert!((lon + 66.25).abs() < 1e-8, "EPSG {}", code);
+            assert!((lat - 46.75).abs() < 1e-8, "EPSG {}", code);
+        }
+        let wkt = "PROJCS[\"NAD_1983_Albers\",GEOGCS[\"GCS_North_American_1983\",DATUM[\"D_North_American_1983\",SPHEROID[\"GRS_1980\",6378137.0,298.257222101]],PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]],PROJECTION[\"Albers\"],PARAMETER[\"False_Easting\",0.0],PARAMETER[\"False_Northing\",0.0],PARAMETER[\"Central_Meridian\",-96.0],PARAMETER[\"Standard_Parallel_1\",29.5],PARAMETER[\"Standard_Parallel_2\",45.5],PARAMETER[\"Latitude_Of_Origin\",23.0],UNIT[\"Meter\",1.0]]";
+        let p = Projection::from_wkt(wkt).unwrap();
+        let (x, y) = p.forward(-75.0, 35.0);
+        let (lon, lat) = p.inverse(x, y);
+        assert!((lon + 75.0).abs() < 1e-7);
+        assert!((lat - 35.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(Projection::from_epsg(1).is_err());
+        assert!(Projection::from_wkt("LOCAL_CS[\"Unknown\"]").is_err());
+    }
+}
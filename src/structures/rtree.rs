@@ -0,0 +1,394 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: The R-tree is bulk-loaded using the Sort-Tile-Recursive (STR) algorithm of
+Leutenegger et al. (1997), which packs the nodes fully and gives good query performance for
+static data such as the records of a vector. The tree is stored level-by-level, with the
+children of node i being entries i * node_capacity to (i + 1) * node_capacity - 1 of the level
+below, so that it can be written to, and read from, a compact sidecar file.
+*/
+
+use super::BoundingBox;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f64;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+
+const MAGIC: &[u8; 8] = b"WBRTREE\0";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug)]
+struct RTreeEntry {
+    bb: BoundingBox,
+    id: usize, // an item id in the leaf level and a node index in the levels above
+}
+
+/// A static R-tree spatial index of bounding boxes, each identified by its index in the
+/// slice that the tree was built from (e.g. a record number).
+///
+/// ## Example
+///     let boxes: Vec<BoundingBox> = (0..input.num_records)
+///         .map(|r| input.get_record(r).get_bounding_box())
+///         .collect();
+///     let tree = RTree::new(&boxes);
+///
+///     // find the records that overlap an envelope
+///     let candidates = tree.search(BoundingBox::new(10.0, 20.0, 10.0, 20.0));
+///
+///     // find the two records whose bounding boxes are nearest to a point
+///     let nearest = tree.knn_search(15.0, 15.0, 2);
+#[derive(Clone, Debug)]
+pub struct RTree {
+    node_capacity: usize,
+    levels: Vec<Vec<RTreeEntry>>,
+}
+
+impl RTree {
+    /// Builds an R-tree from a slice of bounding boxes, using the default node capacity.
+    pub fn new(boxes: &[BoundingBox]) -> RTree {
+        RTree::with_node_capacity(boxes, 16)
+    }
+
+    /// Builds an R-tree from a slice of bounding boxes, with a specified maximum number of
+    /// children per node.
+    pub fn with_node_capacity(boxes: &[BoundingBox], node_capacity: usize) -> RTree {
+        let node_capacity = node_capacity.max(2);
+        let mut level = boxes
+            .iter()
+            .enumerate()
+            .map(|(id, bb)| RTreeEntry { bb: *bb, id: id })
+            .collect::<Vec<RTreeEntry>>();
+        let mut levels = vec![];
+        loop {
+            sort_tile_recursive(&mut level, node_capacity);
+            let parents = level
+                .chunks(node_capacity)
+                .enumerate()
+                .map(|(id, children)| {
+                    let mut bb = BoundingBox::default();
+                    for child in children {
+                        bb.expand_to(child.bb);
+                    }
+                    RTreeEntry { bb: bb, id: id }
+                })
+                .collect::<Vec<RTreeEntry>>();
+            levels.push(level);
+            if parents.len() <= 1 {
+                if !parents.is_empty() {
+                    levels.push(parents);
+                }
+                break;
+            }
+            level = parents;
+        }
+        RTree {
+            node_capacity: node_capacity,
+            levels: levels,
+        }
+    }
+
+    /// Returns the number of bounding boxes in the tree.
+    pub fn size(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns the bounding box of all of the entries in the tree.
+    pub fn get_bounding_box(&self) -> BoundingBox {
+        match self.levels.last() {
+            Some(root) if self.levels.len() > 1 => root[0].bb,
+            _ => BoundingBox::default(),
+        }
+    }
+
+    /// Returns the ids of the bounding boxes that overlap an envelope, in ascending order.
+    pub fn search(&self, envelope: BoundingBox) -> Vec<usize> {
+        let mut ret = vec![];
+        if self.levels.len() < 2 {
+            return ret;
+        }
+        let top = self.levels.len() - 1;
+        let mut stack = vec![(top, 0usize)];
+        while let Some((level, index)) = stack.pop() {
+            let entry = &self.levels[level][index];
+            if !entry.bb.overlaps(envelope) {
+                continue;
+            }
+            if level == 0 {
+                ret.push(entry.id);
+            } else {
+                for child in self.children(level, entry.id) {
+                    stack.push((level - 1, child));
+                }
+            }
+        }
+        ret.sort();
+        ret
+    }
+
+    /// Returns the ids of the bounding boxes that contain the point (x, y), in ascending order.
+    pub fn search_point(&self, x: f64, y: f64) -> Vec<usize> {
+        self.search(BoundingBox::new(x, x, y, y))
+    }
+
+    /// Finds the k bounding boxes nearest to the point (x, y), returning a vector of
+    /// (id, distance) tuples ordered by increasing distance. The distance to a bounding box
+    /// is zero if it contains the point.
+    pub fn knn_search(&self, x: f64, y: f64, num_neighbours: usize) -> Vec<(usize, f64)> {
+        self.knn_search_by(x, y, num_neighbours, |_, box_dist| box_dist)
+    }
+
+    /// Finds the k entries nearest to the point (x, y), using a function that calculates
+    /// the exact distance to an entry (e.g. to a feature's geometry rather than its
+    /// bounding box) from its id and the distance to its bounding box. The exact distance
+    /// must not be less than the distance to the bounding box.
+    pub fn knn_search_by<F>(
+        &self,
+        x: f64,
+        y: f64,
+        num_neighbours: usize,
+        distance: F,
+    ) -> Vec<(usize, f64)>
+    where
+        F: Fn(usize, f64) -> f64,
+    {
+        let mut ret = vec![];
+        if self.levels.len() < 2 || num_neighbours == 0 {
+            return ret;
+        }
+        let top = self.levels.len() - 1;
+        let mut queue = BinaryHeap::new();
+        queue.push(QueueItem {
+            dist: box_distance(&self.levels[top][0].bb, x, y),
+            level: top,
+            index: 0,
+            is_exact: false,
+        });
+        while let Some(item) = queue.pop() {
+            let entry = &self.levels[item.level][item.index];
+            if item.is_exact {
+                ret.push((entry.id, item.dist));
+                if ret.len() == num_neighbours {
+                    break;
+                }
+            } else if item.level == 0 {
+                // re-queue the entry using its exact distance
+                queue.push(QueueItem {
+                    dist: distance(entry.id, item.dist),
+                    is_exact: true,
+                    ..item
+                });
+            } else {
+                for child in self.children(item.level, entry.id) {
+                    queue.push(QueueItem {
+                        dist: box_distance(&self.levels[item.level - 1][child].bb, x, y),
+                        level: item.level - 1,
+                        index: child,
+                        is_exact: false,
+                    });
+                }
+            }
+        }
+        ret
+    }
+
+    /// Writes the tree to a file.
+    pub fn write(&self, file_name: &str) -> Result<(), Error> {
+        let f = File::create(file_name)?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all(MAGIC)?;
+        writer.write_u32::<LittleEndian>(VERSION)?;
+        writer.write_u32::<LittleEndian>(self.node_capacity as u32)?;
+        writer.write_u32::<LittleEndian>(self.levels.len() as u32)?;
+        for level in &self.levels {
+            writer.write_u64::<LittleEndian>(level.len() as u64)?;
+            for entry in level {
+                writer.write_f64::<LittleEndian>(entry.bb.min_x)?;
+                writer.write_f64::<LittleEndian>(entry.bb.min_y)?;
+                writer.write_f64::<LittleEndian>(entry.bb.max_x)?;
+                writer.write_f64::<LittleEndian>(entry.bb.max_y)?;
+                writer.write_u64::<LittleEndian>(entry.id as u64)?;
+            }
+        }
+        writer.flush()
+    }
+
+    /// Reads a tree that was written to a file using `write`.
+    pub fn read(file_name: &str) -> Result<RTree, Error> {
+        let f = File::open(file_name)?;
+        let mut reader = BufReader::new(f);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || reader.read_u32::<LittleEndian>()? != VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{} is not a recognized spatial index file.", file_name),
+            ));
+        }
+        let node_capacity = reader.read_u32::<LittleEndian>()? as usize;
+        let num_levels = reader.read_u32::<LittleEndian>()? as usize;
+        let mut levels = Vec::with_capacity(num_levels);
+        for _ in 0..num_levels {
+            let n = reader.read_u64::<LittleEndian>()? as usize;
+            let mut level = Vec::with_capacity(n);
+            for _ in 0..n {
+                let min_x = reader.read_f64::<LittleEndian>()?;
+                let min_y = reader.read_f64::<LittleEndian>()?;
+                let max_x = reader.read_f64::<LittleEndian>()?;
+                let max_y = reader.read_f64::<LittleEndian>()?;
+                level.push(RTreeEntry {
+                    bb: BoundingBox {
+                        min_x: min_x,
+                        min_y: min_y,
+                        max_x: max_x,
+                        max_y: max_y,
+                    },
+                    id: reader.read_u64::<LittleEndian>()? as usize,
+                });
+            }
+            levels.push(level);
+        }
+        let is_valid = node_capacity >= 2
+            && !levels.is_empty()
+            && (levels.len() == 1 || levels[levels.len() - 1].len() == 1)
+            && levels
+                .windows(2)
+                .all(|w| w[1].iter().all(|e| e.id * node_capacity < w[0].len()));
+        if !is_valid {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The spatial index file {} is corrupt.", file_name),
+            ));
+        }
+        Ok(RTree {
+            node_capacity: node_capacity,
+            levels: levels,
+        })
+    }
+
+    fn children(&self, level: usize, node: usize) -> std::ops::Range<usize> {
+        let start = node * self.node_capacity;
+        let end = (start + self.node_capacity).min(self.levels[level - 1].len());
+        start..end
+    }
+}
+
+/// Sorts entries into STR order, i.e. into vertical slices by x-centre and then by y-centre
+/// within each slice, such that consecutive runs of node_capacity entries form the nodes.
+fn sort_tile_recursive(entries: &mut [RTreeEntry], node_capacity: usize) {
+    let centre_x = |e: &RTreeEntry| e.bb.min_x + e.bb.max_x;
+    let centre_y = |e: &RTreeEntry| e.bb.min_y + e.bb.max_y;
+    entries.sort_by(|a, b| {
+        centre_x(a)
+            .partial_cmp(&centre_x(b))
+            .unwrap_or(Ordering::Equal)
+    });
+    let num_nodes = (entries.len() + node_capacity - 1) / node_capacity;
+    let num_slices = (num_nodes as f64).sqrt().ceil() as usize;
+    let slice_size = num_slices.max(1) * node_capacity;
+    for slice in entries.chunks_mut(slice_size) {
+        slice.sort_by(|a, b| {
+            centre_y(a)
+                .partial_cmp(&centre_y(b))
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+fn box_distance(bb: &BoundingBox, x: f64, y: f64) -> f64 {
+    let dx = (bb.min_x - x).max(0f64).max(x - bb.max_x);
+    let dy = (bb.min_y - y).max(0f64).max(y - bb.max_y);
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[derive(Clone, Copy)]
+struct QueueItem {
+    dist: f64,
+    level: usize,
+    index: usize,
+    is_exact: bool,
+}
+
+impl PartialEq for QueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueItem {}
+
+impl PartialOrd for QueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed, so that the BinaryHeap is a min-heap on distance; at equal distances,
+        // exact entries come first
+        other
+            .dist
+            .partial_cmp(&self.dist)
+            .unwrap_or(Ordering::Equal)
+            .then(self.is_exact.cmp(&other.is_exact))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RTree;
+    use crate::structures::BoundingBox;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_rtree_queries() {
+        let mut boxes = vec![];
+        for i in 0..50 {
+            for j in 0..40 {
+                let (x, y) = (i as f64 * 10.0, j as f64 * 10.0);
+                boxes.push(BoundingBox::new(x, x + 5.0, y, y + 5.0));
+            }
+        }
+        let tree = RTree::with_node_capacity(&boxes, 4);
+        assert_eq!(tree.size(), 2000);
+        assert_eq!(
+            tree.get_bounding_box(),
+            BoundingBox::new(0.0, 495.0, 0.0, 395.0)
+        );
+
+        let envelope = BoundingBox::new(12.0, 28.0, 3.0, 25.0);
+        let brute_force = (0..boxes.len())
+            .filter(|&i| boxes[i].overlaps(envelope))
+            .collect::<Vec<usize>>();
+        assert_eq!(brute_force.len(), 6);
+        assert_eq!(tree.search(envelope), brute_force);
+        assert_eq!(tree.search_point(102.0, 203.0), vec![10 * 40 + 20]);
+        assert!(tree.search_point(107.0, 203.0).is_empty());
+
+        let nearest = tree.knn_search(117.0, 203.0, 3);
+        assert_eq!(nearest[0], (11 * 40 + 20, 2.0));
+        assert_eq!(nearest[1], (12 * 40 + 20, 3.0));
+        assert_eq!(nearest[2], (11 * 40 + 21, 53f64.sqrt()));
+
+        let file_name = env::temp_dir()
+            .join("wbt_test_rtree.rix")
+            .to_str()
+            .unwrap()
+            .to_string();
+        tree.write(&file_name).unwrap();
+        let tree2 = RTree::read(&file_name).unwrap();
+        let _ = fs::remove_file(&file_name);
+        assert_eq!(tree2.search(envelope), brute_force);
+
+        let empty = RTree::new(&[]);
+        assert!(empty.search(envelope).is_empty());
+        assert!(empty.knn_search(0.0, 0.0, 1).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// The optional breakline and boundary inputs of the tools that build a constrained TIN, i.e.
+/// `ConstructVectorTIN`, `TINGridding` and `LidarTINGridding`.
+#[derive(Default)]
+pub struct BreaklineInputs {
+    pub breaklines_file: String,
+    pub breakline_field: String,
+    pub soft_breaklines_file: String,
+    pub boundary_file: String,
+}
+
+/// The positions at which the boundary begins in the TIN points and constraints, after the
+/// hard and soft breaklines.
+pub struct BoundaryStart {
+    pub vertex: usize,
+    pub constraint: usize,
+}
+
+impl BreaklineInputs {
+    /// The command-line flags of the inputs, as normalized by the argument parsing of the tools,
+    /// i.e. with a single leading dash.
+    pub const FLAGS: [&'static str; 4] = [
+        "-breaklines",
+        "-breakline_field",
+        "-soft_breaklines",
+        "-boundary",
+    ];
+
+    /// Returns the tool parameters of the inputs. The boundary is described as the boundary of
+    /// `boundary_of`, e.g. "the TIN".
+    pub fn parameters(boundary_of: &str) -> Vec<ToolParameter> {
+        vec![
+            ToolParameter {
+                name: "Input Hard Breaklines File (optional)".to_owned(),
+                flags: vec!["--breaklines".to_owned()],
+                description: "Optional input vector lines or polygons file of hard breaklines."
+                    .to_owned(),
+                parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                    VectorGeometryType::LineOrPolygon,
+                )),
+                default_value: None,
+                optional: true,
+            },
+            ToolParameter {
+                name: "Breakline Elevation Field (optional)".to_owned(),
+                flags: vec!["--breakline_field".to_owned()],
+                description:
+                    "Optional breakline elevation field; by default, breakline z values are used."
+                        .to_owned(),
+                parameter_type: ParameterType::VectorAttributeField(
+                    AttributeType::Number,
+                    "--breaklines".to_string(),
+                ),
+                default_value: None,
+                optional: true,
+            },
+            ToolParameter {
+                name: "Input Soft Breaklines File (optional)".to_owned(),
+                flags: vec!["--soft_breaklines".to_owned()],
+                description: "Optional input vector lines or polygons file of soft breaklines."
+                    .to_owned(),
+                parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                    VectorGeometryType::LineOrPolygon,
+                )),
+                default_value: None,
+                optional: true,
+            },
+            ToolParameter {
+                name: "Input Boundary File (optional)".to_owned(),
+                flags: vec!["--boundary".to_owned()],
+                description: format!(
+                    "Optional input vector polygons file of the {} boundary.",
+                    boundary_of
+                ),
+                parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                    VectorGeometryType::Polygon,
+                )),
+                default_value: None,
+                optional: true,
+            },
+        ]
+    }
+
+    /// Sets the input of one of the `FLAGS`.
+    pub fn set(&mut self, flag_val: &str, value: String) {
+        match flag_val {
+            "-breaklines" => self.breaklines_file = value,
+            "-breakline_field" => self.breakline_field = value,
+            "-soft_breaklines" => self.soft_breaklines_file = value,
+            "-boundary" => self.boundary_file = value,
+            _ => {}
+        }
+    }
+
+    /// Returns true if none of the files are specified.
+    pub fn is_empty(&self) -> bool {
+        self.breaklines_file.is_empty()
+            && self.soft_breaklines_file.is_empty()
+            && self.boundary_file.is_empty()
+    }
+
+    /// Returns true if a boundary file is specified.
+    pub fn has_boundary(&self) -> bool {
+        !self.boundary_file.is_empty()
+    }
+
+    /// Prefixes file names that are not paths with the working directory.
+    pub fn resolve_paths(&mut self, working_directory: &str) {
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        for file in [
+            &mut self.breaklines_file,
+            &mut self.soft_breaklines_file,
+            &mut self.boundary_file,
+        ]
+        .iter_mut()
+        {
+            if !file.is_empty() && !file.contains(&sep) && !file.contains("/") {
+                **file = format!("{}{}", working_directory, file);
+            }
+        }
+    }
+
+    /// Adds the hard breaklines, soft breaklines, and boundary, in that order, to the TIN points
+    /// and constraints, and returns where the boundary begins.
+    pub fn add_to(
+        &self,
+        points: &mut Vec<Point2D>,
+        z_values: &mut Vec<f64>,
+        constraints: &mut Vec<(usize, usize)>,
+    ) -> Result<BoundaryStart, Error> {
+        if !self.breaklines_file.is_empty() {
+            add_breaklines(
+                &self.breaklines_file,
+                &self.breakline_field,
+                true,
+                points,
+                z_values,
+                constraints,
+            )?;
+        }
+        if !self.soft_breaklines_file.is_empty() {
+            add_breaklines(
+                &self.soft_breaklines_file,
+                "",
+                false,
+                points,
+                z_values,
+                constraints,
+            )?;
+        }
+        let start = BoundaryStart {
+            vertex: points.len(),
+            constraint: constraints.len(),
+        };
+        if !self.boundary_file.is_empty() {
+            add_breaklines(
+                &self.boundary_file,
+                "",
+                false,
+                points,
+                z_values,
+                constraints,
+            )?;
+        }
+        Ok(start)
+    }
+}
+
+/// Adds the vertices of a file of breaklines (lines or polygons) to the TIN points, and their
+/// segments to the TIN constraints. The vertex values of hard breaklines are taken from an
+/// attribute field or, if no field is specified, from the z values of the geometries. The
+/// values of soft breaklines are NaN, to be interpolated from the surrounding vertices.
+fn add_breaklines(
+    file_name: &str,
+    field_name: &str,
+    hard: bool,
+    points: &mut Vec<Point2D>,
+    z_values: &mut Vec<f64>,
+    constraints: &mut Vec<(usize, usize)>,
+) -> Result<(), Error> {
+    let breaklines = Vector::read(file_name)?;
+    let shape_type = breaklines.header.shape_type.base_shape_type();
+    if shape_type != ShapeType::PolyLine && shape_type != ShapeType::Polygon {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The input breaklines files must be of polyline or polygon base shape type.",
+        ));
+    }
+    let use_field = hard && !field_name.is_empty();
+    if use_field {
+        match breaklines.attributes.get_field_num(field_name) {
+            Some(i) if breaklines.attributes.is_field_numeric(i) => {}
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The breakline field does not exist or is non-numeric.",
+                ))
+            }
+        }
+    } else if hard && breaklines.header.shape_type.dimension() != ShapeTypeDimension::Z {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Hard breaklines must either have z values or an elevation field (--breakline_field).",
+        ));
+    }
+    for record_num in 0..breaklines.num_records {
+        let record = breaklines.get_record(record_num);
+        let field_value = if use_field {
+            match breaklines.attributes.get_value(record_num, field_name) {
+                FieldData::Int(val) => val as f64,
+                FieldData::Real(val) => val,
+                _ => continue, // null elevation
+            }
+        } else {
+            f64::NAN
+        };
+        for part in 0..record.num_parts as usize {
+            let start = record.parts[part] as usize;
+            let end = if part < record.num_parts as usize - 1 {
+                record.parts[part + 1] as usize
+            } else {
+                record.num_points as usize
+            };
+            let first = points.len();
+            let mut previous: Option<usize> = None;
+            for i in start..end {
+                let index = if i == end - 1 && i > start && record.points[i] == record.points[start]
+                {
+                    first // the closing vertex of a ring
+                } else {
+                    points.push(record.points[i]);
+                    z_values.push(if use_field {
+                        field_value
+                    } else if hard {
+                        record.z_array[i]
+                    } else {
+                        f64::NAN
+                    });
+                    points.len() - 1
+                };
+                if let Some(p) = previous {
+                    constraints.push((p, index));
+                }
+                previous = Some(index);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::BreaklineInputs;
+    use crate::structures::Point2D;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_breakline_inputs() {
+        let dir = env::temp_dir().join("wbt_breakline_inputs");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("hard.geojson"),
+            r#"{ "type": "FeatureCollection", "features": [
+                { "type": "Feature", "properties": { "ELEV": 5.5 }, "geometry": { "type": "LineString",
+                  "coordinates": [[0, 0], [10, 0], [20, 5]] } } ] }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("boundary.geojson"),
+            r#"{ "type": "FeatureCollection", "features": [
+                { "type": "Feature", "properties": {}, "geometry": { "type": "Polygon",
+                  "coordinates": [[[-1, -1], [30, -1], [30, 30], [-1, -1]]] } } ] }"#,
+        )
+        .unwrap();
+
+        let mut inputs = BreaklineInputs::default();
+        for (flag, value) in &[
+            ("-breaklines", "hard.geojson"),
+            ("-breakline_field", "ELEV"),
+            ("-boundary", "boundary.geojson"),
+        ] {
+            assert!(BreaklineInputs::FLAGS.contains(flag));
+            inputs.set(flag, value.to_string());
+        }
+        let working_directory = format!("{}/", dir.to_string_lossy());
+        inputs.resolve_paths(&working_directory);
+        assert!(!inputs.is_empty() && inputs.has_boundary());
+
+        let mut points = vec![Point2D::new(3.0, 3.0)];
+        let mut z_values = vec![1.0];
+        let mut constraints = vec![];
+        let boundary = inputs
+            .add_to(&mut points, &mut z_values, &mut constraints)
+            .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(boundary.vertex, 4);
+        assert_eq!(boundary.constraint, 2);
+        assert_eq!(&z_values[1..4], &[5.5, 5.5, 5.5]);
+        // the closing vertex of the boundary ring is its first vertex
+        assert_eq!(points.len(), 7);
+        assert!(z_values[4..].iter().all(|z| z.is_nan()));
+        assert_eq!(constraints, vec![(1, 2), (2, 3), (4, 5), (5, 6), (6, 4)]);
+    }
+}
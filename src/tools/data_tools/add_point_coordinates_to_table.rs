@@ -157,7 +157,7 @@ impl WhiteboxTool for AddPointCoordinatesToTable {
             println!("Reading data...")
         };
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -171,7 +171,7 @@ impl WhiteboxTool for AddPointCoordinatesToTable {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
 
         // add the attributes
         output.attributes.add_field(&AttributeField::new(
@@ -176,11 +176,11 @@ impl WhiteboxTool for CleanVector {
         }
 
         // read the input file
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
 
         let mut num_vertices: usize;
 
@@ -8,7 +8,7 @@ License: MIT
 
 use crate::spatial_ref_system::esri_wkt_from_epsg;
 use crate::tools::*;
-use crate::vector::{AttributeField, FieldData, FieldDataType, ShapeType, Shapefile};
+use crate::vector::{AttributeField, FieldData, FieldDataType, ShapeType, Vector};
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
@@ -365,7 +365,7 @@ impl WhiteboxTool for CsvPointsToVector {
         }
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        let mut output = Vector::new(&output_file, ShapeType::Point)?;
 
         if projection_set {
             // set the projection information
@@ -7,7 +7,7 @@ License: MIT
 */
 
 use crate::tools::*;
-use crate::vector::{FieldData, Shapefile};
+use crate::vector::{FieldData, Vector};
 use std::env;
 use std::f64;
 use std::fs::File;
@@ -190,7 +190,7 @@ impl WhiteboxTool for ExportTableToCsv {
         if verbose {
             println!("Reading data...")
         };
-        let vector_data = Shapefile::read(&input_file)?;
+        let vector_data = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -7,7 +7,7 @@ License: MIT
 */
 
 use crate::tools::*;
-use crate::vector::{FieldData, Shapefile};
+use crate::vector::{FieldData, Vector};
 use std::collections::HashMap;
 use std::env;
 use std::f64;
@@ -258,18 +258,14 @@ impl WhiteboxTool for JoinTables {
         if verbose {
             println!("Reading data...")
         };
-        let input1 = Shapefile::read(&input1_file)?;
-        let input2 = Shapefile::read(&input2_file)?;
+        let input1 = Vector::read(&input1_file)?;
+        let input2 = Vector::read(&input2_file)?;
 
         let start = Instant::now();
 
         // create output file
-        let mut output = Shapefile::initialize_using_file(
-            &input1_file,
-            &input1,
-            input1.header.shape_type,
-            true,
-        )?;
+        let mut output =
+            Vector::initialize_using_file(&input1_file, &input1, input1.header.shape_type, true)?;
 
         // What is the index of the foreign field?
         let fkey_index = match input2.attributes.get_field_num(&foreign_key) {
@@ -182,7 +182,7 @@ impl WhiteboxTool for LinesToPolygons {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of polygon type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine {
@@ -194,7 +194,7 @@ impl WhiteboxTool for LinesToPolygons {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
 
         let (mut part_start, mut part_end): (usize, usize);
         let mut points_in_part: usize;
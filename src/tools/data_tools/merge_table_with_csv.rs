@@ -7,7 +7,7 @@ License: MIT
 */
 
 use crate::tools::*;
-use crate::vector::{AttributeField, FieldData, FieldDataType, Shapefile};
+use crate::vector::{AttributeField, FieldData, FieldDataType, Vector};
 use std::collections::HashMap;
 use std::env;
 use std::fs::File;
@@ -262,7 +262,7 @@ impl WhiteboxTool for MergeTableWithCsv {
         if verbose {
             println!("Reading data...")
         };
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // read in the CSV file
         let mut data_map = HashMap::new();
@@ -375,7 +375,7 @@ impl WhiteboxTool for MergeTableWithCsv {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
 
         // update the vector1 attribute table
         for i in 0..field_indices_to_append.len() {
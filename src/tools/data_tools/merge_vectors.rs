@@ -202,7 +202,7 @@ impl WhiteboxTool for MergeVectors {
 
         // We need to initialize output here, but in reality this can't be done
         // until we know the size of rows and columns, which occurs during the first loop.
-        let mut output: Shapefile = Shapefile::new(&output_file, ShapeType::Null)?;
+        let mut output: Shapefile = Vector::new(&output_file, ShapeType::Null)?;
         let mut read_first_file = false;
 
         // It will be necessary to find which fields in the attribute tables are in
@@ -230,7 +230,7 @@ impl WhiteboxTool for MergeVectors {
                     println!("Reading '{}'", input_file);
                 };
 
-                let input = Shapefile::read(&input_file)?;
+                let input = Vector::read(&input_file)?;
 
                 if !read_first_file {
                     read_first_file = true;
@@ -249,7 +249,7 @@ impl WhiteboxTool for MergeVectors {
                     }
 
                     // initialize the output
-                    output = Shapefile::initialize_using_file(
+                    output = Vector::initialize_using_file(
                         &output_file,
                         &input,
                         input.header.shape_type,
@@ -285,7 +285,7 @@ impl WhiteboxTool for MergeVectors {
 
         let mut fid = 1i32;
         for input_file in in_files {
-            let input = Shapefile::read(&input_file)?;
+            let input = Vector::read(&input_file)?;
             let short_name = input.get_short_filename().replace(".shp", "");
 
             for record_num in 0..input.num_records {
@@ -3,6 +3,7 @@ mod add_point_coordinates_to_table;
 mod clean_vector;
 mod convert_nodata_to_zero;
 mod convert_raster_format;
+mod create_spatial_index;
 mod csv_points_to_vector;
 mod export_table_to_csv;
 mod join_tables;
@@ -21,6 +22,8 @@ mod reinitialize_attribute_table;
 mod remove_polygon_holes;
 mod set_nodata_value;
 mod singlepart_to_multipart;
+mod validate_polygons;
+mod validate_topology;
 mod vector_lines_to_raster;
 mod vector_points_to_raster;
 mod vector_polygons_to_raster;
@@ -30,6 +33,7 @@ pub use self::add_point_coordinates_to_table::AddPointCoordinatesToTable;
 pub use self::clean_vector::CleanVector;
 pub use self::convert_nodata_to_zero::ConvertNodataToZero;
 pub use self::convert_raster_format::ConvertRasterFormat;
+pub use self::create_spatial_index::CreateSpatialIndex;
 pub use self::csv_points_to_vector::CsvPointsToVector;
 pub use self::export_table_to_csv::ExportTableToCsv;
 pub use self::join_tables::JoinTables;
@@ -48,6 +52,8 @@ pub use self::reinitialize_attribute_table::ReinitializeAttributeTable;
 pub use self::remove_polygon_holes::RemovePolygonHoles;
 pub use self::set_nodata_value::SetNodataValue;
 pub use self::singlepart_to_multipart::SinglePartToMultiPart;
+pub use self::validate_polygons::ValidatePolygons;
+pub use self::validate_topology::ValidateTopology;
 pub use self::vector_lines_to_raster::VectorLinesToRaster;
 pub use self::vector_points_to_raster::VectorPointsToRaster;
 pub use self::vector_polygons_to_raster::VectorPolygonsToRaster;
@@ -198,7 +198,7 @@ impl WhiteboxTool for MultiPartToSinglePart {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of polygon type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine
@@ -212,7 +212,7 @@ impl WhiteboxTool for MultiPartToSinglePart {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
 
         // add the attributes
         output
@@ -177,7 +177,7 @@ impl WhiteboxTool for PolygonsToLines {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of polygon type
         if input.header.shape_type.base_shape_type() != ShapeType::Polygon {
@@ -189,7 +189,7 @@ impl WhiteboxTool for PolygonsToLines {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
 
         for record_num in 0..input.num_records {
             let record = input.get_record(record_num);
@@ -200,7 +200,7 @@ impl WhiteboxTool for RasterToVectorLines {
         let nodata = input.configs.nodata;
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::PolyLine)?;
+        let mut output = Vector::new(&output_file, ShapeType::PolyLine)?;
 
         // set the projection information
         output.projection = input.configs.coordinate_ref_system_wkt.clone();
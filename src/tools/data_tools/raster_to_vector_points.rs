@@ -190,7 +190,7 @@ impl WhiteboxTool for RasterToVectorPoints {
         let columns = input.configs.columns as isize;
         let nodata = input.configs.nodata;
 
-        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        let mut output = Vector::new(&output_file, ShapeType::Point)?;
 
         // set the projection information
         output.projection = input.configs.coordinate_ref_system_wkt.clone();
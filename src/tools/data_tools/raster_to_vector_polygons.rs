@@ -204,7 +204,7 @@ impl WhiteboxTool for RasterToVectorPolygons {
         let get_x_from_column = |col| -> f64 { west + half_res_x + col as f64 * res_x };
         let get_y_from_row = |row| -> f64 { north - half_res_y - row as f64 * res_y };
 
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
 
         // set the projection information
         output.projection = input.configs.coordinate_ref_system_wkt.clone();
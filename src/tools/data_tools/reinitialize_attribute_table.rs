@@ -152,11 +152,11 @@ impl WhiteboxTool for ReinitializeAttributeTable {
             input_file = format!("{}{}", working_directory, input_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&input_file, &input, input.header.shape_type, false)?;
+            Vector::initialize_using_file(&input_file, &input, input.header.shape_type, false)?;
         // add the attributes
         let fid = AttributeField::new("FID", FieldDataType::Int, 6u8, 0u8);
         output.attributes.add_field(&fid);
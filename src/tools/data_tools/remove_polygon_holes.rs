@@ -180,7 +180,7 @@ impl WhiteboxTool for RemovePolygonHoles {
             println!("Reading data...")
         };
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -194,7 +194,7 @@ impl WhiteboxTool for RemovePolygonHoles {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
 
         let mut part_start: usize;
         let mut part_end: usize;
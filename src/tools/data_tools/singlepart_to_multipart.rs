@@ -212,7 +212,7 @@ impl WhiteboxTool for SinglePartToMultiPart {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         if !use_field {
             match input.header.shape_type.base_shape_type() {
@@ -221,19 +221,19 @@ impl WhiteboxTool for SinglePartToMultiPart {
 
                     // create output file
                     let mut output = match input.header.shape_type.dimension() {
-                        ShapeTypeDimension::XY => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::XY => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPoint,
                             false,
                         )?,
-                        ShapeTypeDimension::Measure => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::Measure => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPointM,
                             false,
                         )?,
-                        ShapeTypeDimension::Z => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::Z => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPointZ,
@@ -304,7 +304,7 @@ impl WhiteboxTool for SinglePartToMultiPart {
                     };
                 }
                 ShapeType::PolyLine => {
-                    let mut output = Shapefile::initialize_using_file(
+                    let mut output = Vector::initialize_using_file(
                         &output_file,
                         &input,
                         input.header.shape_type,
@@ -369,7 +369,7 @@ impl WhiteboxTool for SinglePartToMultiPart {
                     };
                 }
                 ShapeType::Polygon => {
-                    let mut output = Shapefile::initialize_using_file(
+                    let mut output = Vector::initialize_using_file(
                         &output_file,
                         &input,
                         input.header.shape_type,
@@ -506,19 +506,19 @@ impl WhiteboxTool for SinglePartToMultiPart {
 
                     // create output file
                     let mut output = match input.header.shape_type.dimension() {
-                        ShapeTypeDimension::XY => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::XY => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPoint,
                             false,
                         )?,
-                        ShapeTypeDimension::Measure => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::Measure => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPointM,
                             false,
                         )?,
-                        ShapeTypeDimension::Z => Shapefile::initialize_using_file(
+                        ShapeTypeDimension::Z => Vector::initialize_using_file(
                             &output_file,
                             &input,
                             ShapeType::MultiPointZ,
@@ -626,7 +626,7 @@ impl WhiteboxTool for SinglePartToMultiPart {
                     };
                 }
                 ShapeType::PolyLine => {
-                    let mut output = Shapefile::initialize_using_file(
+                    let mut output = Vector::initialize_using_file(
                         &output_file,
                         &input,
                         input.header.shape_type,
@@ -733,7 +733,7 @@ impl WhiteboxTool for SinglePartToMultiPart {
                     };
                 }
                 ShapeType::Polygon => {
-                    let mut output = Shapefile::initialize_using_file(
+                    let mut output = Vector::initialize_using_file(
                         &output_file,
                         &input,
                         input.header.shape_type,
@@ -0,0 +1,713 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{is_clockwise_order, point_in_poly, polygon_overlay, OverlayOperation};
+use crate::structures::{BoundingBox, LineSegment, Point2D, RTree};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+const DEGENERATE_RING: usize = 0;
+const UNCLOSED_RING: usize = 1;
+const DUPLICATE_VERTICES: usize = 2;
+const SPIKE: usize = 3;
+const SELF_INTERSECTION: usize = 4;
+const RING_ORIENTATION: usize = 5;
+const HOLE_OUTSIDE_SHELL: usize = 6;
+const ISSUE_NAMES: [&str; 7] = [
+    "degenerate ring",
+    "unclosed ring",
+    "duplicate vertices",
+    "spike",
+    "self-intersection",
+    "ring orientation",
+    "hole outside shell",
+];
+
+/// This tool checks each feature in a polygon vector file for the geometry problems that
+/// commonly cause other vector tools, particularly the overlay tools (e.g. `Clip`, `Intersect`,
+/// `Union`), to produce incorrect results. The following problems are detected:
+///
+/// - **degenerate ring**: a ring with fewer than three distinct vertices.
+/// - **unclosed ring**: a ring whose last vertex is not the same as its first vertex.
+/// - **duplicate vertices**: consecutive vertices at the same location.
+/// - **spike**: a vertex at which the boundary doubles back on itself, forming a zero-width
+///   spike or cut.
+/// - **self-intersection**: a ring that crosses or touches itself, such as a 'bow-tie'
+///   polygon, or two rings of the same feature that cross or overlap.
+/// - **ring orientation**: an exterior ring that is not in clockwise order, or a hole that is
+///   not in counter-clockwise order, as required by the Shapefile format.
+/// - **hole outside shell**: a hole (counter-clockwise ring) that does not lie within any of
+///   the feature's exterior rings.
+///
+/// The output file contains a copy of the input attribute table, along with a `VALID` field,
+/// which is 1 for valid features and 0 otherwise, and an `ISSUES` field that lists the problems
+/// found in each feature. A summary of the number of features affected by each problem is also
+/// printed when the tool is run in verbose mode.
+///
+/// When the `--repair` flag is specified, the invalid features are rebuilt in the output file.
+/// Rings are closed, and duplicate vertices, spikes, and degenerate rings are removed. Each ring
+/// is then oriented according to its nesting within the feature's other rings, such that rings
+/// that are not inside any other ring are exteriors, rings inside one exterior are holes, and so
+/// on, and the rings are merged into a valid polygon. Self-intersecting rings are divided at
+/// their intersections, such that both lobes of a bow-tie polygon are retained. Invalid features
+/// for which nothing remains after repair are output as null-shape records, so that their
+/// attributes and issues are still reported. Valid features are output unchanged.
+///
+/// # See Also
+/// `CleanVector`, `RemovePolygonHoles`
+pub struct ValidatePolygons {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ValidatePolygons {
+    pub fn new() -> ValidatePolygons {
+        // public constructor
+        let name = "ValidatePolygons".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Checks polygon features for invalid geometries, such as self-intersections, and optionally repairs them."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector polygon file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Repair invalid polygons?".to_owned(),
+            flags: vec!["--repair".to_owned()],
+            description:
+                "Optional flag to request that invalid polygons be repaired in the output."
+                    .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.shp -o=output.shp --repair",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        ValidatePolygons {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ValidatePolygons {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut repair = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-repair" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    repair = true;
+                }
+            }
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Vector::read(&input_file)?;
+
+        // make sure the input vector file is of polygon type
+        if input.header.shape_type.base_shape_type() != ShapeType::Polygon {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of POLYGON base shape type.",
+            ));
+        }
+
+        // create output file
+        let mut output =
+            Vector::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
+        output
+            .attributes
+            .add_field(&AttributeField::new("VALID", FieldDataType::Int, 1u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "ISSUES",
+            FieldDataType::Text,
+            120u8,
+            0u8,
+        ));
+
+        let mut num_invalid = 0;
+        let mut num_emptied = 0;
+        let mut issue_counts = [0usize; 7];
+        for record_num in 0..input.num_records {
+            let record = input.get_record(record_num);
+            let (issues, cleaned) = find_issues(&record.get_parts());
+
+            let mut issue_list = vec![];
+            for i in 0..issues.len() {
+                if issues[i] {
+                    issue_counts[i] += 1;
+                    issue_list.push(ISSUE_NAMES[i]);
+                }
+            }
+            let valid = issue_list.is_empty();
+            if !valid {
+                num_invalid += 1;
+            }
+
+            let geometry = if valid || !repair {
+                record.clone()
+            } else {
+                let repaired = repair_rings(&cleaned);
+                if repaired.len() > 0 {
+                    let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+                    for ring in &repaired {
+                        sfg.add_part(ring);
+                    }
+                    sfg
+                } else {
+                    num_emptied += 1;
+                    ShapefileGeometry::default()
+                }
+            };
+            output.add_record(geometry);
+            let mut atts = input.attributes.get_record(record_num);
+            atts.push(FieldData::Int(if valid { 1 } else { 0 }));
+            atts.push(FieldData::Text(issue_list.join("; ")));
+            output.attributes.add_record(atts, false);
+
+            if verbose {
+                progress =
+                    (100.0_f64 * (record_num + 1) as f64 / input.num_records as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!(
+                "Number of invalid features: {} of {}",
+                num_invalid, input.num_records
+            );
+            for i in 0..issue_counts.len() {
+                if issue_counts[i] > 0 {
+                    println!("  {}: {}", ISSUE_NAMES[i], issue_counts[i]);
+                }
+            }
+            if repair && num_emptied > 0 {
+                println!(
+                    "Number of features output as null shapes because nothing remained after repair: {}",
+                    num_emptied
+                );
+            }
+        }
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the problems found in the rings of a feature, and its cleaned rings.
+fn find_issues(rings: &[Vec<Point2D>]) -> ([bool; 7], Vec<Vec<Point2D>>) {
+    let mut issues = [false; 7];
+    let cleaned = clean_rings(rings, &mut issues);
+    if cleaned.len() > 0 {
+        check_intersections(&cleaned, &mut issues);
+        // the orientation of a self-intersecting ring is ill-defined
+        if !issues[SELF_INTERSECTION] {
+            check_nesting(&cleaned, &mut issues);
+        }
+    }
+    (issues, cleaned)
+}
+
+/// Returns the rings with consecutive duplicate vertices and spikes removed, closed, and with
+/// degenerate rings left out, recording the problems that were found.
+fn clean_rings(rings: &[Vec<Point2D>], issues: &mut [bool; 7]) -> Vec<Vec<Point2D>> {
+    let mut cleaned = vec![];
+    for ring in rings {
+        if ring.len() > 1 && !ring[0].nearly_equals(&ring[ring.len() - 1]) {
+            issues[UNCLOSED_RING] = true;
+        }
+        let mut points: Vec<Point2D> = Vec::with_capacity(ring.len());
+        for p in ring {
+            match points.last() {
+                Some(q) if q.nearly_equals(p) => issues[DUPLICATE_VERTICES] = true,
+                _ => points.push(*p),
+            }
+        }
+        // the closing vertex is handled separately
+        if points.len() > 1 && points[0].nearly_equals(&points[points.len() - 1]) {
+            points.pop();
+        }
+
+        // Remove spikes, i.e. vertices at which the boundary reverses direction, until there
+        // are none left, since removing one spike can expose another.
+        let mut found_spike = true;
+        while found_spike && points.len() > 2 {
+            found_spike = false;
+            let n = points.len();
+            for i in 0..n {
+                let prev = points[(i + n - 1) % n];
+                let next = points[(i + 1) % n];
+                if is_spike(prev, points[i], next) {
+                    points.remove(i);
+                    // removing the spike may leave the neighbouring vertices coincident
+                    let n = points.len();
+                    let j = i % n;
+                    if n > 1 && points[(j + n - 1) % n].nearly_equals(&points[j]) {
+                        points.remove(j);
+                    }
+                    issues[SPIKE] = true;
+                    found_spike = true;
+                    break;
+                }
+            }
+        }
+
+        if points.len() < 3 {
+            issues[DEGENERATE_RING] = true;
+        } else {
+            points.push(points[0]);
+            cleaned.push(points);
+        }
+    }
+    cleaned
+}
+
+/// Tests whether the boundary doubles back on itself at vertex `v`.
+fn is_spike(prev: Point2D, v: Point2D, next: Point2D) -> bool {
+    let (ax, ay) = (prev.x - v.x, prev.y - v.y);
+    let (bx, by) = (next.x - v.x, next.y - v.y);
+    let len_a = (ax * ax + ay * ay).sqrt();
+    let len_b = (bx * bx + by * by).sqrt();
+    let cross = ax * by - ay * bx;
+    let dot = ax * bx + ay * by;
+    dot > 0f64 && cross.abs() <= 1.0e-10 * len_a * len_b
+}
+
+/// Records a self-intersection if any ring crosses or touches itself, or if two rings cross or
+/// overlap. Rings may touch one another at a single point.
+fn check_intersections(rings: &[Vec<Point2D>], issues: &mut [bool; 7]) {
+    let mut segments = vec![];
+    let mut boxes = vec![];
+    for r in 0..rings.len() {
+        for i in 0..rings[r].len() - 1 {
+            let segment = LineSegment::new(rings[r][i], rings[r][i + 1]);
+            boxes.push(segment.get_bounding_box());
+            segments.push((r, i, segment));
+        }
+    }
+    let tree = RTree::new(&boxes);
+    for a in 0..segments.len() {
+        let (ring_a, i, segment_a) = segments[a];
+        for b in tree.search(boxes[a]) {
+            if b <= a {
+                continue;
+            }
+            let (ring_b, j, segment_b) = segments[b];
+            if let Some(intersection) = segment_a.get_intersection(&segment_b) {
+                if ring_a == ring_b {
+                    let n = rings[ring_a].len() - 1;
+                    let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+                    if !adjacent || intersection.p1 != intersection.p2 {
+                        issues[SELF_INTERSECTION] = true;
+                        return;
+                    }
+                } else {
+                    let p = intersection.p1;
+                    let is_endpoint =
+                        |s: &LineSegment| p.nearly_equals(&s.p1) || p.nearly_equals(&s.p2);
+                    if intersection.p1 != intersection.p2
+                        || (!is_endpoint(&segment_a) && !is_endpoint(&segment_b))
+                    {
+                        issues[SELF_INTERSECTION] = true;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the number of other rings that contain each ring.
+fn nesting_depths(rings: &[Vec<Point2D>]) -> Vec<usize> {
+    let boxes: Vec<BoundingBox> = rings.iter().map(|r| BoundingBox::from_points(r)).collect();
+    let tree = RTree::new(&boxes);
+    let mut depths = vec![0usize; rings.len()];
+    for r in 0..rings.len() {
+        // the midpoint of the longest edge is unlikely to fall on the boundary of another ring
+        let ring = &rings[r];
+        let mut longest = 0;
+        for i in 1..ring.len() - 1 {
+            if ring[i].distance(&ring[i + 1]) > ring[longest].distance(&ring[longest + 1]) {
+                longest = i;
+            }
+        }
+        let p = Point2D::midpoint(&ring[longest], &ring[longest + 1]);
+        for s in tree.search_point(p.x, p.y) {
+            if s != r && point_in_poly(&p, &rings[s]) {
+                depths[r] += 1;
+            }
+        }
+    }
+    depths
+}
+
+/// Records ring orientation problems. Rings that are not inside any other ring, or that are
+/// inside an even number of rings, should be clockwise exteriors and the others should be
+/// counter-clockwise holes.
+fn check_nesting(rings: &[Vec<Point2D>], issues: &mut [bool; 7]) {
+    let depths = nesting_depths(rings);
+    let has_exterior = rings.iter().any(|r| is_clockwise_order(r));
+    for r in 0..rings.len() {
+        let clockwise = is_clockwise_order(&rings[r]);
+        if depths[r] % 2 == 0 && !clockwise {
+            if has_exterior {
+                issues[HOLE_OUTSIDE_SHELL] = true;
+            } else {
+                issues[RING_ORIENTATION] = true;
+            }
+        } else if depths[r] % 2 == 1 && clockwise {
+            issues[RING_ORIENTATION] = true;
+        }
+    }
+}
+
+/// Orients cleaned rings according to their nesting and merges them into a valid polygon.
+fn repair_rings(rings: &[Vec<Point2D>]) -> Vec<Vec<Point2D>> {
+    let depths = nesting_depths(rings);
+    let mut oriented = rings.to_vec();
+    for r in 0..oriented.len() {
+        if is_clockwise_order(&oriented[r]) != (depths[r] % 2 == 0) {
+            oriented[r].reverse();
+        }
+    }
+    polygon_overlay(&oriented, &[], OverlayOperation::Union, 0f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        find_issues, repair_rings, ValidatePolygons, DEGENERATE_RING, DUPLICATE_VERTICES,
+        HOLE_OUTSIDE_SHELL, ISSUE_NAMES, RING_ORIENTATION, SELF_INTERSECTION, SPIKE, UNCLOSED_RING,
+    };
+    use crate::algorithms::is_clockwise_order;
+    use crate::structures::Point2D;
+    use crate::tools::WhiteboxTool;
+    use crate::vector::{FieldData, ShapeType, Vector};
+    use std::env;
+    use std::fs;
+
+    fn ring(coords: &[(f64, f64)]) -> Vec<Point2D> {
+        coords.iter().map(|&(x, y)| Point2D::new(x, y)).collect()
+    }
+
+    // a clockwise square, i.e. a valid exterior ring
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point2D> {
+        ring(&[
+            (x, y),
+            (x, y + size),
+            (x + size, y + size),
+            (x + size, y),
+            (x, y),
+        ])
+    }
+
+    // the issues found in a feature
+    fn issues_of(rings: &[Vec<Point2D>]) -> Vec<usize> {
+        let (issues, _) = find_issues(rings);
+        (0..issues.len()).filter(|i| issues[*i]).collect()
+    }
+
+    #[test]
+    fn test_validate_polygons_issues() {
+        let mut hole = square(2.0, 2.0, 2.0);
+        hole.reverse();
+        // a valid exterior with a counter-clockwise hole
+        assert!(issues_of(&[square(0.0, 0.0, 10.0), hole]).is_empty());
+
+        let bowtie = ring(&[
+            (0.0, 0.0),
+            (10.0, 10.0),
+            (10.0, 0.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]);
+        assert_eq!(issues_of(&[bowtie]), vec![SELF_INTERSECTION]);
+
+        let spike = ring(&[
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 5.0),
+            (15.0, 5.0),
+            (10.0, 5.0),
+            (10.0, 0.0),
+            (0.0, 0.0),
+        ]);
+        assert_eq!(issues_of(&[spike]), vec![SPIKE]);
+
+        let mut outside = square(20.0, 20.0, 2.0);
+        outside.reverse();
+        assert_eq!(
+            issues_of(&[square(0.0, 0.0, 10.0), outside]),
+            vec![HOLE_OUTSIDE_SHELL]
+        );
+
+        let degenerate = ring(&[(20.0, 20.0), (21.0, 21.0), (20.0, 20.0)]);
+        assert_eq!(
+            issues_of(&[square(0.0, 0.0, 10.0), degenerate]),
+            vec![DEGENERATE_RING]
+        );
+
+        let duplicates = ring(&[
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (0.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 0.0),
+            (0.0, 0.0),
+        ]);
+        assert_eq!(issues_of(&[duplicates]), vec![DUPLICATE_VERTICES]);
+
+        let mut unclosed = square(0.0, 0.0, 10.0);
+        unclosed.pop();
+        assert_eq!(issues_of(&[unclosed]), vec![UNCLOSED_RING]);
+
+        let mut counter_clockwise = square(0.0, 0.0, 10.0);
+        counter_clockwise.reverse();
+        assert_eq!(issues_of(&[counter_clockwise]), vec![RING_ORIENTATION]);
+        // a clockwise ring inside another is a hole with the wrong orientation
+        assert_eq!(
+            issues_of(&[square(0.0, 0.0, 10.0), square(2.0, 2.0, 2.0)]),
+            vec![RING_ORIENTATION]
+        );
+
+        // both lobes of a bow-tie are kept when it is repaired
+        let bowtie = ring(&[
+            (0.0, 0.0),
+            (10.0, 10.0),
+            (10.0, 0.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]);
+        let (_, cleaned) = find_issues(&[bowtie]);
+        let repaired = repair_rings(&cleaned);
+        assert_eq!(repaired.len(), 2);
+        assert!(repaired.iter().all(|r| is_clockwise_order(r)));
+        assert!(issues_of(&repaired).is_empty());
+    }
+
+    #[test]
+    fn test_validate_polygons_repair() {
+        // a valid square, a bow-tie, and a feature of which nothing remains after repair
+        let text = r#"{ "type": "FeatureCollection", "features": [
+            { "type": "Feature", "properties": { "name": "square" }, "geometry": { "type": "Polygon",
+              "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]] } },
+            { "type": "Feature", "properties": { "name": "bowtie" }, "geometry": { "type": "Polygon",
+              "coordinates": [[[20, 0], [30, 10], [30, 0], [20, 10], [20, 0]]] } },
+            { "type": "Feature", "properties": { "name": "sliver" }, "geometry": { "type": "Polygon",
+              "coordinates": [[[40, 0], [41, 1], [42, 2], [41, 1], [40, 0]]] } } ] }"#;
+        let dir = env::temp_dir().join("wbt_validate_polygons");
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.geojson").to_string_lossy().to_string();
+        fs::write(&input, text).unwrap();
+        let run = |repair: bool, output: &str| {
+            let output = dir.join(output).to_string_lossy().to_string();
+            let mut args = vec![format!("--input={}", input), format!("--output={}", output)];
+            if repair {
+                args.push("--repair".to_string());
+            }
+            ValidatePolygons::new().run(args, "", false).unwrap();
+            Vector::read(&output).unwrap()
+        };
+
+        for &repair in &[false, true] {
+            let output = run(
+                repair,
+                if repair {
+                    "repaired.shp"
+                } else {
+                    "checked.shp"
+                },
+            );
+            assert_eq!(output.num_records, 3);
+            let valid: Vec<FieldData> = (0..3)
+                .map(|r| output.attributes.get_value(r, "VALID"))
+                .collect();
+            assert_eq!(
+                valid,
+                vec![FieldData::Int(1), FieldData::Int(0), FieldData::Int(0)]
+            );
+            assert_eq!(
+                output.attributes.get_value(1, "ISSUES"),
+                FieldData::Text(ISSUE_NAMES[SELF_INTERSECTION].to_string())
+            );
+            assert_eq!(
+                output.attributes.get_value(2, "name"),
+                FieldData::Text("sliver".to_string())
+            );
+            assert_eq!(output.records[0].num_points, 5);
+            if repair {
+                assert_eq!(output.records[1].num_parts, 2);
+                assert_eq!(output.records[2].shape_type, ShapeType::Null);
+            } else {
+                assert_eq!(output.records[1].num_parts, 1);
+                assert_eq!(output.records[2].shape_type, ShapeType::Polygon);
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1042 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{is_clockwise_order, polygon_overlay, winding_number, OverlayOperation};
+use crate::structures::{BoundingBox, LineSegment, Point2D, RTree};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool checks the features in a vector file against one or more topology rules and outputs
+/// a vector file of the locations at which the rules are broken. It is intended to be used for the
+/// quality assurance of data sets, such as cadastral fabrics and road networks, in which features
+/// are expected to share boundaries and connect exactly. The rule (`--rule`) is one of the
+/// following:
+///
+/// | Rule                  | Input    | Error features                                           |
+/// |-----------------------|----------|----------------------------------------------------------|
+/// | no overlaps           | polygons | polygons of the areas in which two polygons overlap      |
+/// | no gaps               | polygons | polygons of the enclosed areas not covered by a polygon  |
+/// | no dangles            | lines    | points at line ends that do not touch any other line     |
+/// | no self-overlaps      | lines    | lines along which a line overlaps itself                 |
+/// | covered by boundaries | lines    | lines along which a line is not on a polygon boundary    |
+/// | inside polygons       | points   | points that are not inside or on the edge of a polygon   |
+///
+/// The last two rules relate the input features to the polygons in a second vector file, which
+/// is specified using the `--polygons` parameter. The snap tolerance (`--snap`), in the units of
+/// the input vector's coordinate reference system, is the distance within which features are
+/// considered to touch or lie on one another; it is used in the same way as the tolerance of
+/// the overlay tools (e.g. `Union`) and `SnapEndnodes`. Overlaps and gaps that are narrower than
+/// the snap tolerance are not reported.
+///
+/// The output vector contains one feature for each error, with attributes for the rule (`RULE`)
+/// and the features involved (`FEATURE1` and `FEATURE2`). Features are identified by their
+/// record number, starting at one; `FEATURE2` is the other feature of an overlapping pair and is
+/// zero otherwise. Gaps are not attributed to any feature, since they are generally bounded by
+/// several features. The output file is written even if no errors are found, in which case it
+/// contains no features.
+///
+/// Several rules may be checked in one run by listing them separated by commas, e.g.
+/// `--rule='no overlaps, no gaps'`, provided that they apply to the same type of input feature
+/// and produce the same type of error feature. The 'no dangles' rule, whose errors are points,
+/// must therefore be checked separately from the other line rules.
+///
+/// # See Also
+/// `ValidatePolygons`, `SnapEndnodes`, `Union`
+pub struct ValidateTopology {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ValidateTopology {
+    pub fn new() -> ValidateTopology {
+        // public constructor
+        let name = "ValidateTopology".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Checks a vector file against topology rules and outputs the locations of errors."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Polygons File".to_owned(),
+            flags: vec!["--polygons".to_owned()],
+            description:
+                "Input vector polygon file, for rules relating lines or points to polygons."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector file of topology errors.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Topology Rules".to_owned(),
+            flags: vec!["--rule".to_owned()],
+            description: "Comma-separated list of topology rules, of 'no overlaps' (default), 'no gaps', 'no dangles', 'no self-overlaps', 'covered by boundaries', and 'inside polygons'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("no overlaps".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap Tolerance".to_owned(),
+            flags: vec!["--snap".to_owned()],
+            description: "Snap tolerance.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=parcels.shp -o=errors.shp --rule='no overlaps, no gaps' --snap=0.001
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=roads.shp --polygons=parcels.shp -o=errors.shp --rule='covered by boundaries'",
+            short_exe, name
+        ).replace("*", &sep);
+
+        ValidateTopology {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ValidateTopology {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut polygons_file = String::new();
+        let mut output_file = String::new();
+        let mut rules = vec![TopologyRule::NoOverlaps];
+        let mut snap = 0f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-polygons" {
+                polygons_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-rule" {
+                let rule_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                rules = vec![];
+                for s in rule_str.to_lowercase().split(',') {
+                    let rule = TopologyRule::from_str(s.trim())?;
+                    if !rules.contains(&rule) {
+                        rules.push(rule);
+                    }
+                }
+            } else if flag_val == "-snap" {
+                snap = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                if snap < 0f64 {
+                    snap = 0f64;
+                }
+            }
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !polygons_file.is_empty()
+            && !polygons_file.contains(&sep)
+            && !polygons_file.contains("/")
+        {
+            polygons_file = format!("{}{}", working_directory, polygons_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        if rules.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No topology rule was specified (--rule).",
+            ));
+        }
+        let output_shape_type = rules[0].output_shape_type();
+        if let Some(rule) = rules
+            .iter()
+            .find(|r| r.output_shape_type() != output_shape_type)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The '{}' and '{}' rules produce different types of error features and must be checked separately.",
+                    rules[0].name(),
+                    rule.name()
+                ),
+            ));
+        }
+
+        let input = Arc::new(Vector::read(&input_file)?);
+        for rule in &rules {
+            if !rule.accepts(input.header.shape_type.base_shape_type()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The input vector data must be of {} base shape type for the '{}' rule.",
+                        rule.input_shape_type(),
+                        rule.name()
+                    ),
+                ));
+            }
+        }
+        let polygons = if let Some(rule) = rules.iter().find(|r| r.needs_polygons()) {
+            if polygons_file.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The '{}' rule requires an input polygons file (--polygons).",
+                        rule.name()
+                    ),
+                ));
+            }
+            let polygons = Vector::read(&polygons_file)?;
+            if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input polygons file must be of POLYGON base shape type.",
+                ));
+            }
+            Some(polygons)
+        } else {
+            None
+        };
+
+        // Coordinates closer than this are considered to coincide, even if the snap tolerance
+        // is zero, so that line ends that lie on other lines are not reported as dangles.
+        let max_coord = [
+            input.header.x_min.abs(),
+            input.header.x_max.abs(),
+            input.header.y_min.abs(),
+            input.header.y_max.abs(),
+        ]
+        .iter()
+        .fold(0f64, |a, b| a.max(*b));
+        let tolerance = snap.max(max_coord * 1.0e-12);
+
+        // Each error is its geometry parts, the record numbers of the features involved, and the
+        // rule that it breaks.
+        let mut errors: Vec<(
+            Vec<Vec<Point2D>>,
+            Option<usize>,
+            Option<usize>,
+            TopologyRule,
+        )> = vec![];
+        for &rule in &rules {
+            let mut rule_errors = vec![];
+            match rule {
+                TopologyRule::NoOverlaps => {
+                    let tree = Arc::new(input.get_spatial_index());
+                    let num_procs = num_cpus::get();
+                    let (tx, rx) = mpsc::channel();
+                    for tid in 0..num_procs {
+                        let input = input.clone();
+                        let tree = tree.clone();
+                        let tx = tx.clone();
+                        thread::spawn(move || {
+                            for i in (0..input.num_records).filter(|r| r % num_procs == tid) {
+                                let record = input.get_record(i);
+                                let rings = record.get_parts();
+                                let mut overlaps = vec![];
+                                let mut candidates = tree.search(record.get_bounding_box());
+                                candidates.sort();
+                                for j in candidates {
+                                    if j > i {
+                                        let overlap = polygon_overlay(
+                                            &rings,
+                                            &input.get_record(j).get_parts(),
+                                            OverlayOperation::Intersection,
+                                            snap,
+                                        );
+                                        if !overlap.is_empty() {
+                                            overlaps.push((overlap, Some(i), Some(j)));
+                                        }
+                                    }
+                                }
+                                tx.send(overlaps).unwrap();
+                            }
+                        });
+                    }
+
+                    for r in 0..input.num_records {
+                        rule_errors.extend(rx.recv().expect("Error receiving data from thread."));
+                        if verbose {
+                            progress =
+                                (100.0_f64 * (r + 1) as f64 / input.num_records as f64) as usize;
+                            if progress != old_progress {
+                                println!("Progress: {}%", progress);
+                                old_progress = progress;
+                            }
+                        }
+                    }
+                    rule_errors.sort_by(|a, b| (a.1, a.2).cmp(&(b.1, b.2)));
+                }
+                TopologyRule::NoGaps => {
+                    let mut rings = vec![];
+                    for i in 0..input.num_records {
+                        rings.extend(input.get_record(i).get_parts());
+                    }
+                    // The holes in the union of the polygons are the gaps between them.
+                    for mut ring in polygon_overlay(&rings, &[], OverlayOperation::Union, snap) {
+                        if !is_clockwise_order(&ring) {
+                            ring.reverse();
+                            rule_errors.push((vec![ring], None, None));
+                        }
+                    }
+                }
+                TopologyRule::NoDangles => {
+                    let (segments, tree) = segment_index(&input);
+                    // the index of the first segment of the current part
+                    let mut first = 0;
+                    for i in 0..input.num_records {
+                        for part in input.get_record(i).get_parts() {
+                            let num_segments = part.len().saturating_sub(1);
+                            first += num_segments;
+                            if num_segments == 0 || part[0].nearly_equals(&part[num_segments]) {
+                                continue;
+                            }
+                            // the ends of the part, and the segments they are on
+                            let ends = [
+                                (part[0], first - num_segments),
+                                (part[num_segments], first - 1),
+                            ];
+                            for (p, own_segment) in &ends {
+                                let mut bb = BoundingBox::from_two_points(*p, *p);
+                                bb.expand_by(tolerance);
+                                let touches = tree.search(bb).into_iter().any(|s| {
+                                    s != *own_segment
+                                        && distance_to_segment(*p, &segments[s]) <= tolerance
+                                });
+                                if !touches {
+                                    rule_errors.push((vec![vec![*p]], Some(i), None));
+                                }
+                            }
+                        }
+                    }
+                }
+                TopologyRule::NoSelfOverlaps => {
+                    for i in 0..input.num_records {
+                        let mut segments = vec![];
+                        for part in input.get_record(i).get_parts() {
+                            for k in 0..part.len().saturating_sub(1) {
+                                if part[k] != part[k + 1] {
+                                    segments.push(LineSegment::new(part[k], part[k + 1]));
+                                }
+                            }
+                        }
+                        let boxes: Vec<BoundingBox> =
+                            segments.iter().map(|s| s.get_bounding_box()).collect();
+                        let tree = RTree::new(&boxes);
+                        let mut overlaps = vec![];
+                        for a in 0..segments.len() {
+                            for b in tree.search(boxes[a]) {
+                                if b > a {
+                                    if let Some(overlap) =
+                                        segments[a].get_intersection(&segments[b])
+                                    {
+                                        if overlap.p1.distance(&overlap.p2) > tolerance {
+                                            overlaps.push(vec![overlap.p1, overlap.p2]);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if !overlaps.is_empty() {
+                            rule_errors.push((overlaps, Some(i), None));
+                        }
+                    }
+                }
+                TopologyRule::CoveredByBoundaries => {
+                    let polygons = polygons.as_ref().unwrap();
+                    let (segments, tree) = segment_index(&polygons);
+                    for i in 0..input.num_records {
+                        let mut uncovered = vec![];
+                        for part in input.get_record(i).get_parts() {
+                            uncovered.extend(uncovered_pieces(&part, &segments, &tree, tolerance));
+                        }
+                        if !uncovered.is_empty() {
+                            rule_errors.push((uncovered, Some(i), None));
+                        }
+                    }
+                }
+                TopologyRule::InsidePolygons => {
+                    let polygons = polygons.as_ref().unwrap();
+                    let tree = polygons.get_spatial_index();
+                    for i in 0..input.num_records {
+                        for p in &input.get_record(i).points {
+                            let mut bb = BoundingBox::from_two_points(*p, *p);
+                            bb.expand_by(tolerance);
+                            let inside = tree.search(bb).into_iter().any(|j| {
+                                polygon_covers_point(
+                                    &polygons.get_record(j).get_parts(),
+                                    *p,
+                                    tolerance,
+                                )
+                            });
+                            if !inside {
+                                rule_errors.push((vec![vec![*p]], Some(i), None));
+                            }
+                        }
+                    }
+                }
+            }
+            if verbose {
+                println!("Number of '{}' errors: {}", rule.name(), rule_errors.len());
+            }
+            errors.extend(rule_errors.into_iter().map(|(g, f1, f2)| (g, f1, f2, rule)));
+        }
+
+        // create output file
+        let mut output =
+            Vector::initialize_using_file(&output_file, &input, output_shape_type, false)?;
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("RULE", FieldDataType::Text, 25u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "FEATURE1",
+            FieldDataType::Int,
+            7u8,
+            0u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "FEATURE2",
+            FieldDataType::Int,
+            7u8,
+            0u8,
+        ));
+
+        for (fid, (parts, feature1, feature2, rule)) in errors.iter().enumerate() {
+            let mut sfg = ShapefileGeometry::new(output_shape_type);
+            if output_shape_type == ShapeType::Point {
+                sfg.add_point(parts[0][0]);
+            } else {
+                for part in parts {
+                    sfg.add_part(part);
+                }
+            }
+            output.add_record(sfg);
+            // features are identified by record number, starting at one
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(fid as i32 + 1),
+                    FieldData::Text(rule.name().to_string()),
+                    FieldData::Int(feature1.map_or(0, |r| r as i32 + 1)),
+                    FieldData::Int(feature2.map_or(0, |r| r as i32 + 1)),
+                ],
+                false,
+            );
+        }
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TopologyRule {
+    NoOverlaps,
+    NoGaps,
+    NoDangles,
+    NoSelfOverlaps,
+    CoveredByBoundaries,
+    InsidePolygons,
+}
+
+impl TopologyRule {
+    fn from_str(s: &str) -> Result<TopologyRule, Error> {
+        if s.contains("gap") {
+            Ok(TopologyRule::NoGaps)
+        } else if s.contains("dangle") {
+            Ok(TopologyRule::NoDangles)
+        } else if s.contains("self") {
+            Ok(TopologyRule::NoSelfOverlaps)
+        } else if s.contains("overlap") {
+            Ok(TopologyRule::NoOverlaps)
+        } else if s.contains("cover") {
+            Ok(TopologyRule::CoveredByBoundaries)
+        } else if s.contains("inside") {
+            Ok(TopologyRule::InsidePolygons)
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized topology rule '{}'.", s),
+            ))
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            TopologyRule::NoOverlaps => "no overlaps",
+            TopologyRule::NoGaps => "no gaps",
+            TopologyRule::NoDangles => "no dangles",
+            TopologyRule::NoSelfOverlaps => "no self-overlaps",
+            TopologyRule::CoveredByBoundaries => "covered by boundaries",
+            TopologyRule::InsidePolygons => "inside polygons",
+        }
+    }
+
+    fn input_shape_type(&self) -> ShapeType {
+        match self {
+            TopologyRule::NoOverlaps | TopologyRule::NoGaps => ShapeType::Polygon,
+            TopologyRule::NoDangles
+            | TopologyRule::NoSelfOverlaps
+            | TopologyRule::CoveredByBoundaries => ShapeType::PolyLine,
+            TopologyRule::InsidePolygons => ShapeType::Point,
+        }
+    }
+
+    fn accepts(&self, base_shape_type: ShapeType) -> bool {
+        base_shape_type == self.input_shape_type()
+            || (*self == TopologyRule::InsidePolygons && base_shape_type == ShapeType::MultiPoint)
+    }
+
+    fn output_shape_type(&self) -> ShapeType {
+        match self {
+            TopologyRule::NoOverlaps | TopologyRule::NoGaps => ShapeType::Polygon,
+            TopologyRule::NoSelfOverlaps | TopologyRule::CoveredByBoundaries => ShapeType::PolyLine,
+            TopologyRule::NoDangles | TopologyRule::InsidePolygons => ShapeType::Point,
+        }
+    }
+
+    fn needs_polygons(&self) -> bool {
+        *self == TopologyRule::CoveredByBoundaries || *self == TopologyRule::InsidePolygons
+    }
+}
+
+/// Returns the segments of all of the lines, or polygon rings, in a vector, in order, and an
+/// R-tree of the segment bounding boxes.
+fn segment_index(vector: &Vector) -> (Vec<LineSegment>, RTree) {
+    let mut segments = vec![];
+    for i in 0..vector.num_records {
+        for part in vector.get_record(i).get_parts() {
+            for s in 0..part.len().saturating_sub(1) {
+                segments.push(LineSegment::new(part[s], part[s + 1]));
+            }
+        }
+    }
+    let boxes: Vec<BoundingBox> = segments.iter().map(|s| s.get_bounding_box()).collect();
+    (segments, RTree::new(&boxes))
+}
+
+fn distance_to_segment(p: Point2D, segment: &LineSegment) -> f64 {
+    let (a, b) = (segment.p1, segment.p2);
+    let len_sqrd = a.distance_squared(&b);
+    if len_sqrd == 0f64 {
+        return p.distance(&a);
+    }
+    let t = (((p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y)) / len_sqrd)
+        .max(0f64)
+        .min(1f64);
+    p.distance(&Point2D::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)))
+}
+
+/// Returns the pieces of a line that do not lie along any of the boundary segments.
+fn uncovered_pieces(
+    line: &[Point2D],
+    segments: &[LineSegment],
+    tree: &RTree,
+    tolerance: f64,
+) -> Vec<Vec<Point2D>> {
+    let mut pieces = vec![];
+    let mut current: Vec<Point2D> = vec![];
+    for k in 0..line.len().saturating_sub(1) {
+        let (a, b) = (line[k], line[k + 1]);
+        let len = a.distance(&b);
+        if len == 0f64 {
+            continue;
+        }
+        let (ux, uy) = ((b.x - a.x) / len, (b.y - a.y) / len);
+
+        // find the intervals of the segment, as proportions of its length, that lie along a
+        // boundary segment
+        let mut covered = vec![];
+        let mut bb = BoundingBox::from_two_points(a, b);
+        bb.expand_by(tolerance);
+        for s in tree.search(bb) {
+            let (q1, q2) = (segments[s].p1, segments[s].p2);
+            let offset1 = ((q1.x - a.x) * uy - (q1.y - a.y) * ux).abs();
+            let offset2 = ((q2.x - a.x) * uy - (q2.y - a.y) * ux).abs();
+            if offset1 <= tolerance && offset2 <= tolerance {
+                let t1 = ((q1.x - a.x) * ux + (q1.y - a.y) * uy) / len;
+                let t2 = ((q2.x - a.x) * ux + (q2.y - a.y) * uy) / len;
+                let (lo, hi) = (t1.min(t2).max(0f64), t1.max(t2).min(1f64));
+                if hi > lo {
+                    covered.push((lo, hi));
+                }
+            }
+        }
+        covered.sort_by(|x, y| x.partial_cmp(y).unwrap());
+
+        // the remaining intervals are uncovered
+        let mut gaps = vec![];
+        let mut t = 0f64;
+        for (lo, hi) in covered {
+            if lo > t {
+                gaps.push((t, lo));
+            }
+            t = t.max(hi);
+        }
+        if t < 1f64 {
+            gaps.push((t, 1f64));
+        }
+
+        for (t1, t2) in gaps {
+            if (t2 - t1) * len <= tolerance {
+                continue;
+            }
+            let p1 = Point2D::new(a.x + t1 * (b.x - a.x), a.y + t1 * (b.y - a.y));
+            let p2 = Point2D::new(a.x + t2 * (b.x - a.x), a.y + t2 * (b.y - a.y));
+            // continue the current piece if this one starts where it ends
+            if current.last().map_or(false, |q| q.nearly_equals(&p1)) {
+                current.push(p2);
+            } else {
+                if current.len() > 1 {
+                    pieces.push(current);
+                }
+                current = vec![p1, p2];
+            }
+        }
+    }
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Tests whether a point is inside a polygon, given by its rings, or within the tolerance of
+/// its boundary.
+fn polygon_covers_point(rings: &[Vec<Point2D>], p: Point2D, tolerance: f64) -> bool {
+    let mut num_containing_rings = 0;
+    for ring in rings {
+        if ring.len() < 4 || !ring[0].nearly_equals(&ring[ring.len() - 1]) {
+            continue;
+        }
+        for k in 0..ring.len() - 1 {
+            if distance_to_segment(p, &LineSegment::new(ring[k], ring[k + 1])) <= tolerance {
+                return true;
+            }
+        }
+        if winding_number(&p, ring) != 0 {
+            num_containing_rings += 1;
+        }
+    }
+    // a point inside a hole is inside both the hole and the exterior ring
+    num_containing_rings % 2 == 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::ValidateTopology;
+    use crate::structures::Point2D;
+    use crate::tools::WhiteboxTool;
+    use crate::vector::{FieldData, ShapefileGeometry, Vector};
+    use std::env;
+    use std::fs;
+
+    fn collection(geometries: &[&str]) -> String {
+        let features = geometries
+            .iter()
+            .map(|g| {
+                format!(
+                    r#"{{ "type": "Feature", "properties": {{}}, "geometry": {} }}"#,
+                    g
+                )
+            })
+            .collect::<Vec<String>>();
+        format!(
+            r#"{{ "type": "FeatureCollection", "features": [{}] }}"#,
+            features.join(", ")
+        )
+    }
+
+    fn length(g: &ShapefileGeometry) -> f64 {
+        g.get_parts()
+            .iter()
+            .map(|part| {
+                (1..part.len())
+                    .map(|k| part[k - 1].distance(&part[k]))
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_validate_topology_rule_list() {
+        let square = |x: f64| {
+            format!(
+                r#"{{ "type": "Feature", "properties": {{}}, "geometry": {{ "type": "Polygon",
+                "coordinates": [[[{0}, 0], [{0}, 10], [{1}, 10], [{1}, 0], [{0}, 0]]] }} }}"#,
+                x,
+                x + 10f64
+            )
+        };
+        let dir = env::temp_dir().join("wbt_validate_topology");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        // two overlapping squares, and one on its own
+        fs::write(
+            path("overlapping.geojson"),
+            format!(
+                r#"{{ "type": "FeatureCollection", "features": [{}, {}] }}"#,
+                square(0f64),
+                square(8f64)
+            ),
+        )
+        .unwrap();
+        fs::write(
+            path("clean.geojson"),
+            format!(
+                r#"{{ "type": "FeatureCollection", "features": [{}] }}"#,
+                square(0f64)
+            ),
+        )
+        .unwrap();
+        let run = |input: &str, output: &str, rule: &str| {
+            ValidateTopology::new().run(
+                vec![
+                    format!("--input={}", path(input)),
+                    format!("--output={}", path(output)),
+                    format!("--rule={}", rule),
+                ],
+                "",
+                false,
+            )
+        };
+
+        // both rules are checked in one pass
+        run("overlapping.geojson", "errors.shp", "no overlaps, no gaps").unwrap();
+        let output = Vector::read(&path("errors.shp")).unwrap();
+        assert_eq!(output.num_records, 1);
+        assert_eq!(
+            output.attributes.get_value(0, "RULE"),
+            FieldData::Text("no overlaps".to_string())
+        );
+        assert_eq!(
+            output.attributes.get_value(0, "FEATURE1"),
+            FieldData::Int(1)
+        );
+        assert_eq!(
+            output.attributes.get_value(0, "FEATURE2"),
+            FieldData::Int(2)
+        );
+
+        // an empty output, with the error schema, is written when there are no errors
+        run("clean.geojson", "none.shp", "no overlaps,no gaps").unwrap();
+        let output = Vector::read(&path("none.shp")).unwrap();
+        assert_eq!(output.num_records, 0);
+        assert!(output.attributes.get_field_num("FEATURE2").is_some());
+
+        // rules with different types of error features can't be combined
+        assert!(run(
+            "overlapping.geojson",
+            "bad.shp",
+            "no overlaps, inside polygons"
+        )
+        .is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_validate_topology_line_and_point_rules() {
+        let dir = env::temp_dir().join("wbt_validate_topology_rules");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        let line =
+            |coords: &str| format!(r#"{{ "type": "LineString", "coordinates": {} }}"#, coords);
+        let point =
+            |x: f64, y: f64| format!(r#"{{ "type": "Point", "coordinates": [{}, {}] }}"#, x, y);
+        let write = |name: &str, geometries: Vec<String>| {
+            let geometries = geometries.iter().map(|g| g.as_str()).collect::<Vec<&str>>();
+            fs::write(path(name), collection(&geometries)).unwrap();
+        };
+        let run = |input: &str, rule: &str| {
+            ValidateTopology::new()
+                .run(
+                    vec![
+                        format!("--input={}", path(input)),
+                        format!("--polygons={}", path("polygons.geojson")),
+                        format!("--output={}", path("errors.shp")),
+                        format!("--rule={}", rule),
+                    ],
+                    "",
+                    false,
+                )
+                .unwrap();
+            Vector::read(&path("errors.shp")).unwrap()
+        };
+        let feature1 = |output: &Vector| {
+            (0..output.num_records)
+                .map(|i| output.attributes.get_value(i, "FEATURE1"))
+                .collect::<Vec<FieldData>>()
+        };
+
+        // two squares side by side, the first with a hole
+        write(
+            "polygons.geojson",
+            vec![
+                r#"{ "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                    [[2, 2], [2, 4], [4, 4], [4, 2], [2, 2]]] }"#
+                    .to_string(),
+                r#"{ "type": "Polygon", "coordinates": [[[10, 0], [20, 0], [20, 10], [10, 10], [10, 0]]] }"#
+                    .to_string(),
+            ],
+        );
+
+        // two lines meeting at a corner, and an isolated line
+        write(
+            "network.geojson",
+            vec![
+                line("[[0, 0], [10, 0]]"),
+                line("[[10, 0], [10, 10]]"),
+                line("[[5, 5], [8, 5]]"),
+            ],
+        );
+        let output = run("network.geojson", "no dangles");
+        let dangles = output
+            .records
+            .iter()
+            .map(|r| r.points[0])
+            .collect::<Vec<Point2D>>();
+        assert_eq!(
+            dangles,
+            vec![
+                Point2D::new(0.0, 0.0),
+                Point2D::new(10.0, 10.0),
+                Point2D::new(5.0, 5.0),
+                Point2D::new(8.0, 5.0)
+            ]
+        );
+        assert_eq!(
+            feature1(&output),
+            vec![
+                FieldData::Int(1),
+                FieldData::Int(2),
+                FieldData::Int(3),
+                FieldData::Int(3)
+            ]
+        );
+
+        // a line that doubles back on itself over 6 units, and one that turns a corner
+        write(
+            "doubling.geojson",
+            vec![
+                line("[[0, 20], [10, 20], [4, 20], [4, 30]]"),
+                line("[[0, 40], [10, 40], [10, 50]]"),
+            ],
+        );
+        let output = run("doubling.geojson", "no self-overlaps");
+        assert_eq!(output.num_records, 1);
+        assert_eq!(feature1(&output), vec![FieldData::Int(1)]);
+        assert!((length(&output.records[0]) - 6.0).abs() < 1e-9);
+        assert!(output.records[0].points.contains(&Point2D::new(4.0, 20.0)));
+        assert!(output.records[0].points.contains(&Point2D::new(10.0, 20.0)));
+
+        // the shared edge and the bottom edges are boundaries; the top edge line extends 5 units
+        // beyond the polygons and the interior line is entirely off the boundaries
+        write(
+            "edges.geojson",
+            vec![
+                line("[[10, 0], [10, 10]]"),
+                line("[[0, 0], [20, 0]]"),
+                line("[[0, 10], [25, 10]]"),
+                line("[[5, 5], [15, 5]]"),
+            ],
+        );
+        let output = run("edges.geojson", "covered by boundaries");
+        assert_eq!(
+            feature1(&output),
+            vec![FieldData::Int(3), FieldData::Int(4)]
+        );
+        assert!((length(&output.records[0]) - 5.0).abs() < 1e-9);
+        assert!(output.records[0].points.contains(&Point2D::new(20.0, 10.0)));
+        assert!((length(&output.records[1]) - 10.0).abs() < 1e-9);
+
+        // points inside a polygon and on an edge are valid; those outside or in a hole are not
+        write(
+            "points.geojson",
+            vec![
+                point(5.0, 5.0),
+                point(10.0, 5.0),
+                point(25.0, 5.0),
+                point(3.0, 3.0),
+            ],
+        );
+        let output = run("points.geojson", "inside polygons");
+        assert_eq!(
+            feature1(&output),
+            vec![FieldData::Int(3), FieldData::Int(4)]
+        );
+        assert_eq!(output.records[0].points[0], Point2D::new(25.0, 5.0));
+        assert_eq!(output.records[1].points[0], Point2D::new(3.0, 3.0));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -9,7 +9,7 @@ License: MIT
 use crate::raster::*;
 use crate::structures::BoundingBox;
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, Shapefile};
+use crate::vector::{FieldData, ShapeType, Vector};
 use std::env;
 use std::f64;
 use std::io::{Error, ErrorKind};
@@ -262,7 +262,7 @@ impl WhiteboxTool for VectorLinesToRaster {
         if verbose {
             println!("Reading data...")
         };
-        let vector_data = Shapefile::read(&input_file)?;
+        let vector_data = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -10,7 +10,7 @@ use crate::algorithms::point_in_poly;
 use crate::raster::*;
 use crate::structures::{Array2D, BoundingBox, Point2D};
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, Shapefile};
+use crate::vector::{FieldData, ShapeType, Vector};
 use std::collections::HashMap;
 use std::env;
 use std::f64;
@@ -240,7 +240,7 @@ impl WhiteboxTool for VectorPolygonsToRaster {
         if verbose {
             println!("Reading data...")
         };
-        let vector_data = Shapefile::read(&input_file)?;
+        let vector_data = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -185,14 +185,14 @@ impl WhiteboxTool for CentroidVector {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let (mut x_total, mut y_total): (f64, f64);
 
         if input.header.shape_type.base_shape_type() == ShapeType::Point {
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
+                Vector::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
 
             // add the attributes
             output
@@ -241,7 +241,7 @@ impl WhiteboxTool for CentroidVector {
         } else {
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
+                Vector::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
 
             // add the attributes
             output
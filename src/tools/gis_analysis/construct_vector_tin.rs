@@ -2,14 +2,15 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 21/09/2018
-Last Modified: 07/12/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
 use self::na::Vector3;
-use crate::algorithms::triangulate;
+use crate::algorithms::constrained_triangulate;
 use crate::na;
 use crate::structures::Point2D;
+use crate::tools::breaklines::BreaklineInputs;
 use crate::tools::*;
 use crate::vector::ShapefileGeometry;
 use crate::vector::*;
@@ -28,6 +29,13 @@ use std::path;
 /// in convex regions along the data boundary. To avoid these spurious triangles, the user may optionally
 /// specify the maximum allowable edge length of a triangular facet (`--max_triangle_edge_length`).
 ///
+/// Breaklines, such as ridges, shorelines and road edges, may optionally be enforced as the edges of the
+/// TIN using a constrained Delaunay triangulation. Hard breaklines (`--breaklines`) carry their own
+/// heights, taken from an attribute field (`--breakline_field`) or from the z values of the geometries,
+/// while the vertex heights of soft breaklines (`--soft_breaklines`) are interpolated from the neighbouring
+/// points. Triangles outside of the polygons of an optional boundary file (`--boundary`), or within their
+/// holes, are not output.
+///
 /// # See Also
 /// `LidarConstructVectorTIN`
 pub struct ConstructVectorTIN {
@@ -102,6 +110,8 @@ impl ConstructVectorTIN {
             optional: true,
         });
 
+        parameters.extend(BreaklineInputs::parameters("TIN"));
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -115,7 +125,8 @@ impl ConstructVectorTIN {
         }
         let usage = format!(
             ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --field=HEIGHT -o=tin.shp
->>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp",
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --breaklines=edges.shp --boundary=site.shp",
             short_exe, name
         )
         .replace("*", &sep);
@@ -177,6 +188,7 @@ impl WhiteboxTool for ConstructVectorTIN {
         let mut use_field = false;
         let mut output_file: String = "".to_string();
         let mut max_triangle_edge_length = f64::INFINITY;
+        let mut breakline_inputs = BreaklineInputs::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -232,6 +244,13 @@ impl WhiteboxTool for ConstructVectorTIN {
                 };
 
                 max_triangle_edge_length *= max_triangle_edge_length; // actually squared distance
+            } else if BreaklineInputs::FLAGS.contains(&flag_val.as_str()) {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                breakline_inputs.set(&flag_val, value);
             }
         }
 
@@ -254,8 +273,9 @@ impl WhiteboxTool for ConstructVectorTIN {
         if !output_file.contains(&sep) && !output_file.contains("/") {
             output_file = format!("{}{}", working_directory, output_file);
         }
+        breakline_inputs.resolve_paths(working_directory);
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of points type
         if input.header.shape_type.base_shape_type() != ShapeType::Point
@@ -299,7 +319,7 @@ impl WhiteboxTool for ConstructVectorTIN {
         let cos_theta = altitude.cos();
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
 
         // set the projection information
         output.projection = input.projection.clone();
@@ -347,6 +367,8 @@ impl WhiteboxTool for ConstructVectorTIN {
                             z_values.push(0f64);
                         }
                     }
+                } else {
+                    z_values.push(0f64);
                 }
             }
 
@@ -363,8 +385,26 @@ impl WhiteboxTool for ConstructVectorTIN {
         if verbose {
             println!("Performing triangulation...");
         }
+        let mut constraints = vec![];
+        let boundary_start = breakline_inputs
+            .add_to(&mut points, &mut z_values, &mut constraints)?
+            .constraint;
+
         // this is where the heavy-lifting is
-        let result = triangulate(&points).expect("No triangulation exists.");
+        let cdt = constrained_triangulate(&points, &constraints).expect("No triangulation exists.");
+        if verbose && !cdt.failed_constraints.is_empty() {
+            println!(
+                "Warning: {} breakline segments intersect other breaklines and could not be enforced.",
+                cdt.failed_constraints.len()
+            );
+        }
+        cdt.interpolate_missing_values(&points, &mut z_values);
+        let inside = if breakline_inputs.has_boundary() {
+            cdt.mask_triangles(|c| c >= boundary_start)
+        } else {
+            vec![true; cdt.triangulation.len()]
+        };
+        let result = &cdt.triangulation;
 
         let (mut p1, mut p2, mut p3): (usize, usize, usize);
         let (mut fx, mut fy): (f64, f64);
@@ -378,14 +418,15 @@ impl WhiteboxTool for ConstructVectorTIN {
             p2 = result.triangles[i + 1];
             p3 = result.triangles[i];
 
-            if max_distance_squared(
-                points[p1],
-                points[p2],
-                points[p3],
-                z_values[p1],
-                z_values[p2],
-                z_values[p3],
-            ) < max_triangle_edge_length
+            if inside[i / 3]
+                && max_distance_squared(
+                    points[p1],
+                    points[p2],
+                    points[p3],
+                    z_values[p1],
+                    z_values[p2],
+                    z_values[p3],
+                ) < max_triangle_edge_length
             {
                 let mut tri_points: Vec<Point2D> = Vec::with_capacity(4);
                 tri_points.push(points[p1].clone());
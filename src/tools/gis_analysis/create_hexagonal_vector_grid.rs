@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 15/09/2018
-Last Modified: 20/01/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
@@ -240,11 +240,8 @@ impl WhiteboxTool for CreateHexagonalVectorGrid {
         }
 
         // Get the spatial extent
-        let (extent, proj_info) = if input_file.to_lowercase().ends_with(".shp") {
-            // Note that this only works because at the moment, Shapefiles are the only supported vector.
-            // If additional vector formats are added in the future, this will need updating.
-
-            let input = Shapefile::read(&input_file)?;
+        let (extent, proj_info) = if is_vector_file(&input_file) {
+            let input = Vector::read(&input_file)?;
             (
                 BoundingBox::new(
                     input.header.x_min,
@@ -290,7 +287,7 @@ impl WhiteboxTool for CreateHexagonalVectorGrid {
         let (mut center_x, mut center_y): (f64, f64);
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
 
         // set the projection information
         if !proj_info.is_empty() && proj_info.to_lowercase() != "not specified" {
@@ -241,7 +241,7 @@ impl WhiteboxTool for Dissolve {
             println!("Reading data...")
         };
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
         let projection = input.projection.clone();
 
         // The overlay file must be of the same ShapeType as the input file
@@ -254,7 +254,7 @@ impl WhiteboxTool for Dissolve {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
         output.projection = projection;
 
         // What is the index of the foreign field?
@@ -499,7 +499,7 @@ impl WhiteboxTool for Dissolve {
         /*
         ////////////////////////////////////////////////////////////////////
         let mut output2 =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
         for i in 0..num_polylines {
             let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
             sfg.add_part(&(polylines[i].vertices));
@@ -215,7 +215,7 @@ impl WhiteboxTool for EliminateCoincidentPoints {
         }
 
         // Get the spatial extent
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
         let num_points = input.num_records;
 
         // make sure the input vector file is of points type
@@ -230,7 +230,7 @@ impl WhiteboxTool for EliminateCoincidentPoints {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::Point, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::Point, true)?;
 
         let mut frs: FixedRadiusSearch2D<usize> =
             FixedRadiusSearch2D::new(tolerance * 10f64, DistanceMetric::SquaredEuclidean);
@@ -170,7 +170,7 @@ impl WhiteboxTool for ElongationRatio {
             println!("Reading data...")
         };
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -184,7 +184,7 @@ impl WhiteboxTool for ElongationRatio {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&input_file, &input, input.header.shape_type, true)?;
 
         // add the attributes
         output.attributes.add_field(&AttributeField::new(
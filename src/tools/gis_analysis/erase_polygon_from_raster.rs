@@ -10,7 +10,7 @@ use crate::algorithms;
 use crate::raster::*;
 use crate::structures::Point2D;
 use crate::tools::*;
-use crate::vector::{ShapeType, Shapefile};
+use crate::vector::{ShapeType, Vector};
 use std::env;
 use std::io::{Error, ErrorKind};
 use std::path;
@@ -197,7 +197,7 @@ impl WhiteboxTool for ErasePolygonFromRaster {
         let columns = input.configs.columns as isize;
         let nodata = input.configs.nodata;
 
-        let polygons = Shapefile::read(&polygons_file)?;
+        let polygons = Vector::read(&polygons_file)?;
 
         // make sure the input vector file is of points type
         if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
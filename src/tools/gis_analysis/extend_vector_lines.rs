@@ -232,7 +232,7 @@ impl WhiteboxTool for ExtendVectorLines {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of polyline type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine {
@@ -244,7 +244,7 @@ impl WhiteboxTool for ExtendVectorLines {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
 
         let (mut x1, mut x2, mut y1, mut y2): (f64, f64, f64, f64);
         let (mut x_st, mut x_end, mut y_st, mut y_end): (f64, f64, f64, f64);
@@ -176,7 +176,7 @@ impl WhiteboxTool for ExtractNodes {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of line or polygon type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine
@@ -189,7 +189,7 @@ impl WhiteboxTool for ExtractNodes {
         }
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        let mut output = Vector::new(&output_file, ShapeType::Point)?;
         output.projection = input.projection.clone();
 
         // add the attributes
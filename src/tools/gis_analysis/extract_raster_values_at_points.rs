@@ -197,7 +197,7 @@ impl WhiteboxTool for ExtractRasterValuesAtPoints {
                                 "There is something incorrect about the input files. At least one input is required to operate this tool."));
         }
 
-        let mut points = Shapefile::read(&points_file)?;
+        let mut points = Vector::read(&points_file)?;
         points.file_mode = "rw".to_string(); // we need to be able to modify the attributes table
         let num_records = points.num_records;
 
@@ -218,7 +218,7 @@ impl WhiteboxTool for FindLowestOrHighestPoints {
         let columns = input.configs.columns as isize;
         let nodata = input.configs.nodata;
 
-        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        let mut output = Vector::new(&output_file, ShapeType::Point)?;
 
         // add the attributes
         let fid = AttributeField::new("FID", FieldDataType::Int, 2u8, 0u8);
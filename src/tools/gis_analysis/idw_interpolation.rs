@@ -20,7 +20,7 @@ Most IDW tool have the option to work either based on a fixed number of neighbou
 use crate::raster::*;
 use crate::structures::{DistanceMetric, FixedRadiusSearch2D};
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, Shapefile};
+use crate::vector::{FieldData, ShapeType, Vector};
 use num_cpus;
 use std::env;
 use std::f64;
@@ -330,7 +330,7 @@ impl WhiteboxTool for IdwInterpolation {
         if verbose {
             println!("Reading data...")
         };
-        let vector_data = Shapefile::read(&input_file)?;
+        let vector_data = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 31/09/2018
-Last Modified: 13/10/2018
+Last Modified: 18/10/2026
 License: MIT
 */
 
@@ -191,13 +191,13 @@ impl WhiteboxTool for LayerFootprint {
         }
 
         // is it a vector or a raster file?
-        if input_file.to_lowercase().ends_with(".shp") {
+        if is_vector_file(&input_file) {
             // The input file is a vector
-            let input = Shapefile::read(&input_file)?;
+            let input = Vector::read(&input_file)?;
 
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input, ShapeType::Polygon, false)?;
+                Vector::initialize_using_file(&output_file, &input, ShapeType::Polygon, false)?;
             // Add an FID attribute to the table
             output
                 .attributes
@@ -233,7 +233,7 @@ impl WhiteboxTool for LayerFootprint {
             let input = Raster::new(&input_file, "r")?;
 
             // create output file
-            let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+            let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
             output.projection = input.configs.coordinate_ref_system_wkt.clone();
             // Add an FID attribute to the table
             output
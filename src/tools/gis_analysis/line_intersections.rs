@@ -213,7 +213,7 @@ impl WhiteboxTool for LineIntersections {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input1 = Arc::new(Shapefile::read(&input1_file)?);
+        let input1 = Arc::new(Vector::read(&input1_file)?);
 
         // make sure the input vector file is of polyline type
         if input1.header.shape_type.base_shape_type() != ShapeType::PolyLine
@@ -225,7 +225,7 @@ impl WhiteboxTool for LineIntersections {
             ));
         }
 
-        let input2 = Arc::new(Shapefile::read(&input2_file)?);
+        let input2 = Arc::new(Vector::read(&input2_file)?);
 
         // make sure the input vector file is of polyline type
         if input2.header.shape_type.base_shape_type() != ShapeType::PolyLine
@@ -360,7 +360,7 @@ impl WhiteboxTool for LineIntersections {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input1, ShapeType::Point, false)?;
+            Vector::initialize_using_file(&output_file, &input1, ShapeType::Point, false)?;
 
         // add the attributes
         output
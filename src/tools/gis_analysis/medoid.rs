@@ -192,7 +192,7 @@ impl WhiteboxTool for Medoid {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let (mut x, mut y): (f64, f64);
         let (mut medx, mut medy): (f64, f64);
@@ -202,12 +202,8 @@ impl WhiteboxTool for Medoid {
 
         if input.header.shape_type.base_shape_type() == ShapeType::Point {
             // create output file
-            let mut output = Shapefile::initialize_using_file(
-                &output_file,
-                &input,
-                input.header.shape_type,
-                true,
-            )?;
+            let mut output =
+                Vector::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
 
             // read in the coordinates and find the median x and y coordinates
             let mut x_coordinates: Vec<f64> = Vec::with_capacity(input.num_records);
@@ -286,7 +282,7 @@ impl WhiteboxTool for Medoid {
         } else {
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
+                Vector::initialize_using_file(&output_file, &input, ShapeType::Point, false)?;
 
             // add the attributes
             output
@@ -215,7 +215,7 @@ impl WhiteboxTool for MergeLineSegments {
             println!("Reading data...")
         };
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
         let projection = input.projection.clone();
 
         // The overlay file must be of the same ShapeType as the input file
@@ -228,7 +228,7 @@ impl WhiteboxTool for MergeLineSegments {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, false)?;
         output.projection = projection;
 
         // add the attributes
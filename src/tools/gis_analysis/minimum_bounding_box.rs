@@ -226,7 +226,7 @@ impl WhiteboxTool for MinimumBoundingBox {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         if input.header.shape_type.base_shape_type() == ShapeType::Point {
             // Finding hulls around individual points makes no sense. Likely
@@ -237,7 +237,7 @@ impl WhiteboxTool for MinimumBoundingBox {
         if individual_feature_hulls {
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
+                Vector::initialize_using_file(&output_file, &input, ShapeType::Polygon, true)?;
 
             for record_num in 0..input.num_records {
                 let record = input.get_record(record_num);
@@ -280,7 +280,7 @@ impl WhiteboxTool for MinimumBoundingBox {
             };
         } else {
             // create output file
-            let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+            let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
             output.projection = input.projection.clone();
 
             // add the attributes
@@ -5,7 +5,7 @@ mod block_maximum;
 mod block_minimum;
 mod boundary_shape_complexity;
 mod buffer_raster;
-// mod buffer_vector;
+mod buffer_vector;
 mod centroid;
 mod centroid_vector;
 mod clip;
@@ -77,6 +77,7 @@ mod reclass_from_file;
 mod related_circumscribing_circle;
 mod shape_complexity_index;
 mod shape_complexity_raster;
+mod simplify_vectors;
 mod smooth_vectors;
 mod split_with_lines;
 mod sum_overlay;
@@ -95,7 +96,7 @@ pub use self::block_maximum::BlockMaximumGridding;
 pub use self::block_minimum::BlockMinimumGridding;
 pub use self::boundary_shape_complexity::BoundaryShapeComplexity;
 pub use self::buffer_raster::BufferRaster;
-// pub use self::buffer_vector::BufferVector;
+pub use self::buffer_vector::BufferVector;
 pub use self::centroid::Centroid;
 pub use self::centroid_vector::CentroidVector;
 pub use self::clip::Clip;
@@ -167,6 +168,7 @@ pub use self::reclass_from_file::ReclassFromFile;
 pub use self::related_circumscribing_circle::RelatedCircumscribingCircle;
 pub use self::shape_complexity_index::ShapeComplexityIndex;
 pub use self::shape_complexity_raster::ShapeComplexityIndexRaster;
+pub use self::simplify_vectors::SimplifyVectors;
 pub use self::smooth_vectors::SmoothVectors;
 pub use self::split_with_lines::SplitWithLines;
 pub use self::sum_overlay::SumOverlay;
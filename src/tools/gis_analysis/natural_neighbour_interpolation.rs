@@ -10,7 +10,7 @@ use crate::algorithms::{point_in_poly, polygon_area, triangulate, Triangulation}
 use crate::raster::*;
 use crate::structures::{BoundingBox, Point2D};
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, ShapeTypeDimension, Shapefile};
+use crate::vector::{FieldData, ShapeType, ShapeTypeDimension, Vector};
 use kdtree::distance::squared_euclidean;
 use kdtree::KdTree;
 use num_cpus;
@@ -303,7 +303,7 @@ impl WhiteboxTool for NaturalNeighbourInterpolation {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of points type
         if input.header.shape_type.base_shape_type() != ShapeType::Point
@@ -9,7 +9,7 @@ License: MIT
 use crate::raster::*;
 use crate::structures::{DistanceMetric, FixedRadiusSearch2D};
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, Shapefile};
+use crate::vector::{FieldData, ShapeType, Vector};
 use num_cpus;
 use std::env;
 use std::f64;
@@ -262,7 +262,7 @@ impl WhiteboxTool for NearestNeighbourGridding {
         if verbose {
             println!("Reading data...")
         };
-        let vector_data = Shapefile::read(&input_file)?;
+        let vector_data = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
@@ -183,7 +183,7 @@ impl WhiteboxTool for PolygonShortAxis {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         if input.header.shape_type.base_shape_type() != ShapeType::Polygon {
             return Err(Error::new(
@@ -194,7 +194,7 @@ impl WhiteboxTool for PolygonShortAxis {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
+            Vector::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
 
         for record_num in 0..input.num_records {
             let record = input.get_record(record_num);
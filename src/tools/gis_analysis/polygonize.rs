@@ -218,7 +218,7 @@ impl WhiteboxTool for Polygonize {
                     input_file = format!("{}{}", working_directory, input_file);
                 }
 
-                let input = Shapefile::read(&input_file)?;
+                let input = Vector::read(&input_file)?;
                 projection = input.projection.clone();
 
                 // make sure the input vector file is of polyline type
@@ -258,7 +258,7 @@ impl WhiteboxTool for Polygonize {
         }
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
         output.projection = projection;
 
         // add the attributes
@@ -10,7 +10,7 @@ use crate::algorithms::{convex_hull, point_in_poly};
 use crate::raster::*;
 use crate::structures::{Basis, Point2D, RadialBasisFunction};
 use crate::tools::*;
-use crate::vector::{FieldData, ShapeType, Shapefile};
+use crate::vector::{FieldData, ShapeType, Vector};
 use kdtree::distance::squared_euclidean;
 use kdtree::KdTree;
 use nalgebra::DVector;
@@ -378,7 +378,7 @@ impl WhiteboxTool for RadialBasisFunctionInterpolation {
         if verbose {
             println!("Reading data...")
         };
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         let start = Instant::now();
 
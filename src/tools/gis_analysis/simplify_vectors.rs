@@ -0,0 +1,654 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{douglas_peucker, visvalingam_whyatt};
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool reduces the number of vertices in a vector coverage of either a POLYLINE or POLYGON
+/// base ShapeType, e.g. to reduce the size of the dense polygons that are created by
+/// `RasterToVectorPolygons` for web publishing. Unlike `SmoothVectors`, which moves vertices, this
+/// tool removes the vertices that contribute least to the shape of each line or polygon ring,
+/// using one of two methods (`--method`):
+///
+/// - **douglas-peucker** (default): the Douglas-Peucker algorithm retains the vertices that are
+///   further than the tolerance (`--tolerance`) from the simplified line, in the units of the
+///   input vector's coordinate reference system.
+/// - **visvalingam-whyatt**: the Visvalingam-Whyatt algorithm repeatedly removes the vertex with
+///   the smallest effective area, i.e. the area of the triangle formed by the vertex and its
+///   neighbours, until none has an area smaller than the tolerance, in squared map units. This
+///   method tends to produce more natural-looking simplifications of smooth features.
+///
+/// The end points of lines are always retained. By default, each line and polygon ring is
+/// simplified independently, which may open gaps and overlaps between polygons that share a
+/// boundary, or separate lines that share vertices. When the `--topology` flag is specified, the
+/// lines and rings are instead divided into arcs at the vertices where features meet, and each
+/// arc is simplified once, such that features that share a boundary continue to do so after
+/// simplification. Topology preservation requires that shared vertices have exactly the same
+/// coordinates in each feature, as is the case for the output of `RasterToVectorPolygons`. The
+/// Visvalingam-Whyatt method always retains at least three vertices of each polygon ring. Polygon
+/// rings that would nonetheless collapse, i.e. be reduced to a line with no area, are left
+/// unsimplified, as are, when topology is preserved, the boundaries that they share with other
+/// polygons. Notice that
+/// neither method prevents simplified lines from crossing one another, and so large tolerances
+/// can produce invalid polygons; these can be detected with `ValidatePolygons`.
+///
+/// # See Also
+/// `SmoothVectors`, `RasterToVectorPolygons`, `ValidatePolygons`
+pub struct SimplifyVectors {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SimplifyVectors {
+    pub fn new() -> SimplifyVectors {
+        // public constructor
+        let name = "SimplifyVectors".to_string();
+        let toolbox = "GIS Analysis".to_string();
+        let description =
+            "Reduces the number of vertices in a vector coverage of either a POLYLINE or POLYGON base ShapeType."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector POLYLINE or POLYGON file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Simplification Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Simplification method; one of 'douglas-peucker' (default) and 'visvalingam-whyatt'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "douglas-peucker".to_owned(),
+                "visvalingam-whyatt".to_owned(),
+            ]),
+            default_value: Some("douglas-peucker".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Tolerance".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Simplification tolerance; a distance for Douglas-Peucker and an area for Visvalingam-Whyatt."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Preserve topology?".to_owned(),
+            flags: vec!["--topology".to_owned()],
+            description:
+                "Optional flag to request that shared boundaries be simplified consistently."
+                    .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=in_file.shp -o=out_file.shp --tolerance=10.0 --topology
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=in_file.shp -o=out_file.shp --method=visvalingam-whyatt --tolerance=100.0",
+            short_exe, name
+        ).replace("*", &sep);
+
+        SimplifyVectors {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SimplifyVectors {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut use_visvalingam = false;
+        let mut tolerance = 1f64;
+        let mut preserve_topology = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-method" {
+                let method = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                use_visvalingam = method.to_lowercase().contains("vis");
+            } else if flag_val == "-tolerance" {
+                tolerance = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-topology" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    preserve_topology = true;
+                }
+            }
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if tolerance < 0f64 {
+            tolerance = 0f64;
+        }
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Vector::read(&input_file)?;
+
+        // make sure the input vector file is of PolyLine or Polygon type
+        let base_shape_type = input.header.shape_type.base_shape_type();
+        if base_shape_type != ShapeType::PolyLine && base_shape_type != ShapeType::Polygon {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of POLYLINE or POLYGON base shape type.",
+            ));
+        }
+        let is_polygon = base_shape_type == ShapeType::Polygon;
+
+        let simplify = |points: &[Point2D]| {
+            if use_visvalingam {
+                visvalingam_whyatt(points, tolerance)
+            } else {
+                douglas_peucker(points, tolerance)
+            }
+        };
+
+        let parts: Vec<Vec<Vec<Point2D>>> = (0..input.num_records)
+            .map(|record_num| input.get_record(record_num).get_parts())
+            .collect();
+
+        let simplified_parts: Vec<Vec<Vec<Point2D>>> = if preserve_topology {
+            if verbose {
+                println!("Finding shared boundaries...")
+            };
+            let mut arcs = ArcSet::new(&parts, is_polygon);
+            let simplified_arcs: Vec<Vec<Point2D>> =
+                arcs.arcs.iter().map(|arc| simplify(arc)).collect();
+            let original_arcs = std::mem::replace(&mut arcs.arcs, simplified_arcs);
+
+            // Rings that collapse are rebuilt from their unsimplified arcs. This changes the
+            // other rings that share those arcs, which may in turn collapse, and so it is
+            // repeated until every ring is valid.
+            if is_polygon {
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for record_num in 0..parts.len() {
+                        for part in 0..parts[record_num].len() {
+                            if !is_collapsed(&arcs.assemble(record_num, part)) {
+                                continue;
+                            }
+                            for (a, _) in &arcs.part_arcs[record_num][part] {
+                                if arcs.arcs[*a] != original_arcs[*a] {
+                                    arcs.arcs[*a] = original_arcs[*a].clone();
+                                    changed = true;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            (0..parts.len())
+                .map(|record_num| {
+                    (0..parts[record_num].len())
+                        .map(|part| arcs.assemble(record_num, part))
+                        .collect()
+                })
+                .collect()
+        } else {
+            parts
+                .iter()
+                .map(|record_parts| {
+                    record_parts
+                        .iter()
+                        .map(|part| {
+                            let simplified = simplify(part);
+                            if is_polygon && is_collapsed(&simplified) {
+                                part.clone()
+                            } else {
+                                simplified
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        // create output file
+        let mut output =
+            Vector::initialize_using_file(&output_file, &input, base_shape_type, true)?;
+
+        let mut num_vertices_in = 0;
+        let mut num_vertices_out = 0;
+        for record_num in 0..input.num_records {
+            let mut sfg = ShapefileGeometry::new(base_shape_type);
+            for part in 0..parts[record_num].len() {
+                num_vertices_in += parts[record_num][part].len();
+                num_vertices_out += simplified_parts[record_num][part].len();
+                sfg.add_part(&simplified_parts[record_num][part]);
+            }
+            output.add_record(sfg);
+            output
+                .attributes
+                .add_record(input.attributes.get_record(record_num), false);
+
+            if verbose {
+                progress =
+                    (100.0_f64 * (record_num + 1) as f64 / input.num_records as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!(
+                "Number of vertices reduced from {} to {} ({:.1}%)",
+                num_vertices_in,
+                num_vertices_out,
+                100f64 * num_vertices_out as f64 / num_vertices_in.max(1) as f64
+            );
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// Tests whether a polygon ring has collapsed, i.e. it has fewer than three vertices that are not
+/// collinear, and so encloses no area.
+fn is_collapsed(ring: &[Point2D]) -> bool {
+    if ring.len() < 4 {
+        return true;
+    }
+    let (mut x_min, mut x_max) = (ring[0].x, ring[0].x);
+    let (mut y_min, mut y_max) = (ring[0].y, ring[0].y);
+    let mut area = 0f64;
+    for i in 0..ring.len() - 1 {
+        let (p, q) = (ring[i] - ring[0], ring[i + 1] - ring[0]);
+        area += p.x * q.y - q.x * p.y;
+        x_min = x_min.min(ring[i].x);
+        x_max = x_max.max(ring[i].x);
+        y_min = y_min.min(ring[i].y);
+        y_max = y_max.max(ring[i].y);
+    }
+    let size = (x_max - x_min).max(y_max - y_min);
+    (area / 2f64).abs() <= 1.0e-10 * size * size
+}
+
+type PointKey = (u64, u64);
+
+fn point_key(p: &Point2D) -> PointKey {
+    (p.x.to_bits(), p.y.to_bits())
+}
+
+/// The lines or polygon rings of a vector coverage, divided into arcs at the vertices where
+/// features meet, i.e. the nodes. Each arc is stored once, in a canonical direction, no matter
+/// how many parts it belongs to.
+struct ArcSet {
+    arcs: Vec<Vec<Point2D>>,
+    /// The arcs of each part of each record, each with whether it is reversed in the part.
+    part_arcs: Vec<Vec<Vec<(usize, bool)>>>,
+}
+
+impl ArcSet {
+    fn new(parts: &[Vec<Vec<Point2D>>], is_polygon: bool) -> ArcSet {
+        // A vertex is a node if it does not have exactly two neighbouring vertices, or if it is
+        // the end of a line.
+        let mut neighbours: HashMap<PointKey, Vec<PointKey>> = HashMap::new();
+        let mut nodes: HashSet<PointKey> = HashSet::new();
+        for part in parts.iter().flatten() {
+            if part.is_empty() {
+                continue;
+            }
+            for i in 0..part.len() - 1 {
+                let (a, b) = (point_key(&part[i]), point_key(&part[i + 1]));
+                if a != b {
+                    for (p, q) in &[(a, b), (b, a)] {
+                        let entry = neighbours.entry(*p).or_insert(vec![]);
+                        if !entry.contains(q) {
+                            entry.push(*q);
+                        }
+                    }
+                }
+            }
+            if !is_polygon {
+                nodes.insert(point_key(&part[0]));
+                nodes.insert(point_key(&part[part.len() - 1]));
+            }
+        }
+        for (p, n) in &neighbours {
+            if n.len() != 2 {
+                nodes.insert(*p);
+            }
+        }
+
+        let mut arcs = vec![];
+        let mut arc_ids: HashMap<Vec<PointKey>, usize> = HashMap::new();
+        let mut part_arcs = vec![];
+        for record_parts in parts {
+            let mut record_arcs = vec![];
+            for part in record_parts {
+                let mut points: Vec<Point2D> = vec![];
+                for p in part {
+                    if points.last() != Some(p) {
+                        points.push(*p);
+                    }
+                }
+                if is_polygon && points.len() > 1 && points[0] == points[points.len() - 1] {
+                    // Rotate the ring to start at a node. A ring without nodes starts at its
+                    // lowest vertex, so that identical rings are divided identically.
+                    points.pop();
+                    let first_node = (0..points.len())
+                        .find(|i| nodes.contains(&point_key(&points[*i])))
+                        .unwrap_or_else(|| {
+                            (0..points.len())
+                                .min_by(|i, j| point_key(&points[*i]).cmp(&point_key(&points[*j])))
+                                .unwrap_or(0)
+                        });
+                    points.rotate_left(first_node);
+                    if !points.is_empty() {
+                        points.push(points[0]);
+                    }
+                }
+
+                let mut arcs_of_part = vec![];
+                let mut start = 0;
+                for i in 1..points.len() {
+                    if i == points.len() - 1 || nodes.contains(&point_key(&points[i])) {
+                        let arc = &points[start..=i];
+                        let forward: Vec<PointKey> = arc.iter().map(|p| point_key(p)).collect();
+                        let backward: Vec<PointKey> = forward.iter().rev().cloned().collect();
+                        let reversed = backward < forward;
+                        let key = if reversed { backward } else { forward };
+                        let id = *arc_ids.entry(key).or_insert_with(|| {
+                            let mut canonical = arc.to_vec();
+                            if reversed {
+                                canonical.reverse();
+                            }
+                            arcs.push(canonical);
+                            arcs.len() - 1
+                        });
+                        arcs_of_part.push((id, reversed));
+                        start = i;
+                    }
+                }
+                if arcs_of_part.is_empty() && !points.is_empty() {
+                    // a part with a single distinct vertex
+                    arcs.push(points.clone());
+                    arcs_of_part.push((arcs.len() - 1, false));
+                }
+                record_arcs.push(arcs_of_part);
+            }
+            part_arcs.push(record_arcs);
+        }
+
+        ArcSet {
+            arcs: arcs,
+            part_arcs: part_arcs,
+        }
+    }
+
+    /// Joins the arcs of a part back together.
+    fn assemble(&self, record_num: usize, part: usize) -> Vec<Point2D> {
+        let mut points: Vec<Point2D> = vec![];
+        for (id, reversed) in &self.part_arcs[record_num][part] {
+            let mut arc = self.arcs[*id].clone();
+            if *reversed {
+                arc.reverse();
+            }
+            if !points.is_empty() {
+                arc.remove(0);
+            }
+            points.extend(arc);
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SimplifyVectors;
+    use crate::structures::Point2D;
+    use crate::tools::WhiteboxTool;
+    use crate::vector::Vector;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_simplify_vectors_adjacent_squares() {
+        // two 10 x 10 squares that share the edge x = 10, with collinear vertices along their
+        // edges, including one in the middle of the shared edge
+        let text = r#"{ "type": "FeatureCollection", "features": [
+            { "type": "Feature", "properties": { "id": 1 }, "geometry": { "type": "Polygon",
+              "coordinates": [[[0, 0], [0, 10], [5, 10], [10, 10], [10, 5], [10, 0], [5, 0], [0, 0]]] } },
+            { "type": "Feature", "properties": { "id": 2 }, "geometry": { "type": "Polygon",
+              "coordinates": [[[10, 0], [10, 5], [10, 10], [20, 10], [20, 0], [15, 0], [10, 0]]] } } ] }"#;
+        let dir = env::temp_dir().join("wbt_simplify_vectors");
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.geojson").to_string_lossy().to_string();
+        fs::write(&input, text).unwrap();
+        let original = Vector::read(&input).unwrap();
+        let run = |method: &str, tolerance: f64, topology: bool| {
+            let output = dir
+                .join(format!("{}_{}_{}.shp", method, tolerance, topology))
+                .to_string_lossy()
+                .to_string();
+            let mut args = vec![
+                format!("--input={}", input),
+                format!("--output={}", output),
+                format!("--method={}", method),
+                format!("--tolerance={}", tolerance),
+            ];
+            if topology {
+                args.push("--topology".to_string());
+            }
+            SimplifyVectors::new().run(args, "", false).unwrap();
+            let output = Vector::read(&output).unwrap();
+            (0..2)
+                .map(|r| output.get_record(r).points.clone())
+                .collect::<Vec<Vec<Point2D>>>()
+        };
+        let corners = |points: &[Point2D]| {
+            points
+                .iter()
+                .filter(|p| p.x % 10f64 == 0f64 && p.y % 10f64 == 0f64)
+                .count()
+                == points.len()
+        };
+
+        // rings that are rebuilt from their arcs may start at a different vertex
+        let unsimplified = |r: usize, points: &[Point2D]| {
+            let ring = &original.get_record(r).points;
+            points.len() == ring.len() && ring.iter().all(|p| points.contains(p))
+        };
+
+        for method in &["douglas-peucker", "visvalingam-whyatt"] {
+            for &topology in &[false, true] {
+                // a small tolerance removes only the collinear vertices
+                let polygons = run(method, 1f64, topology);
+                assert_eq!(polygons[0].len(), 5);
+                assert_eq!(polygons[1].len(), 5);
+                assert!(corners(&polygons[0]) && corners(&polygons[1]));
+
+                let polygons = run(method, 100f64, topology);
+                if topology {
+                    // Simplifying the shared edge and the rest of either square to straight
+                    // lines collapses it, which restores the shared edge; that in turn collapses
+                    // the other square, which must also be restored.
+                    for r in 0..2 {
+                        assert!(unsimplified(r, &polygons[r]));
+                    }
+                } else if *method == "douglas-peucker" {
+                    // the rings collapse, and are left unsimplified
+                    for r in 0..2 {
+                        assert!(unsimplified(r, &polygons[r]));
+                    }
+                } else {
+                    // the rings are reduced to triangles
+                    assert_eq!(polygons[0].len(), 4);
+                    assert_eq!(polygons[1].len(), 4);
+                    assert!(corners(&polygons[0]) && corners(&polygons[1]));
+                }
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
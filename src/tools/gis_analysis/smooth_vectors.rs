@@ -212,7 +212,7 @@ impl WhiteboxTool for SmoothVectors {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of PolyLine or Polygon type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine
@@ -226,7 +226,7 @@ impl WhiteboxTool for SmoothVectors {
 
         // create output file
         let mut output =
-            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+            Vector::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
 
         let (mut x, mut y): (f64, f64);
         let mut n: f64;
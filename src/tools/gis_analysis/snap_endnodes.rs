@@ -203,10 +203,10 @@ impl WhiteboxTool for SnapEndnodes {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // create output file
-        let mut output =  Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+        let mut output =  Vector::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
 
         // make sure the input vector file is of polyline type
         if input.header.shape_type.base_shape_type() != ShapeType::PolyLine {
@@ -218,7 +218,7 @@ impl WhiteboxTool for SplitWithLines {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input1 = Shapefile::read(&input1_file)?;
+        let input1 = Vector::read(&input1_file)?;
         let projection = input1.projection.clone();
 
         // make sure the input vector file is of polyline type
@@ -231,7 +231,7 @@ impl WhiteboxTool for SplitWithLines {
             ));
         }
 
-        let input2 = Shapefile::read(&input2_file)?;
+        let input2 = Vector::read(&input2_file)?;
 
         // make sure the input vector file is of polyline type
         if input2.header.shape_type.base_shape_type() != ShapeType::PolyLine {
@@ -310,12 +310,8 @@ impl WhiteboxTool for SplitWithLines {
 
         if input1.header.shape_type.base_shape_type() == ShapeType::PolyLine {
             // create output file
-            let mut output = Shapefile::initialize_using_file(
-                &output_file,
-                &input1,
-                ShapeType::PolyLine,
-                false,
-            )?;
+            let mut output =
+                Vector::initialize_using_file(&output_file, &input1, ShapeType::PolyLine, false)?;
             output.projection = projection;
 
             // add the attributes
@@ -615,7 +611,7 @@ impl WhiteboxTool for SplitWithLines {
 
             // create output file
             let mut output =
-                Shapefile::initialize_using_file(&output_file, &input1, ShapeType::Polygon, false)?;
+                Vector::initialize_using_file(&output_file, &input1, ShapeType::Polygon, false)?;
             output.projection = projection;
 
             // add the attributes
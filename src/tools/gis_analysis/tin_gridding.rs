@@ -2,15 +2,16 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 23/09/2018
-Last Modified: 18/10/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
 use self::na::Vector3;
-use crate::algorithms::{point_in_poly, triangulate};
+use crate::algorithms::{constrained_triangulate, point_in_poly};
 use crate::na;
 use crate::raster::*;
 use crate::structures::Point2D;
+use crate::tools::breaklines::BreaklineInputs;
 use crate::tools::*;
 use crate::vector::*;
 use std::env;
@@ -30,6 +31,15 @@ use std::path;
 /// outside of this region. Thus, it is recommended that the output surface be clipped to the convex hull of the input
 /// points (`--clip`).
 ///
+/// Breaklines, i.e. linear features such as ridges, shorelines, road edges and polygon boundaries, across
+/// which the surface should not be interpolated, may optionally be enforced as the edges of the TIN using
+/// a constrained Delaunay triangulation. Hard breaklines (`--breaklines`) carry their own elevations,
+/// taken from an attribute field (`--breakline_field`) or, if no field is specified, from the z values of
+/// the line or polygon geometries. Soft breaklines (`--soft_breaklines`) constrain the triangle edges
+/// only, with the values of their vertices interpolated from the neighbouring points. Lastly, an optional
+/// polygon boundary file (`--boundary`) restricts the gridded area to the interior of the polygons,
+/// excluding any holes. Breaklines that intersect one another should be split at their intersections.
+///
 /// # See Also
 /// `LidarTINGridding`, `ConstructVectorTIN`, `NaturalNeighbourInterpolation`
 pub struct TINGridding {
@@ -120,6 +130,8 @@ impl TINGridding {
             optional: true,
         });
 
+        parameters.extend(BreaklineInputs::parameters("interpolation"));
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -133,7 +145,8 @@ impl TINGridding {
         }
         let usage = format!(
             ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --field=HEIGHT -o=tin.shp --resolution=10.0
->>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --resolution=5.0",
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --resolution=5.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --resolution=1.0 --breaklines=edges.shp --breakline_field=ELEV --boundary=site.shp",
             short_exe, name
         ).replace("*", &sep);
 
@@ -196,6 +209,7 @@ impl WhiteboxTool for TINGridding {
         let mut grid_res: f64 = 0.0;
         let mut base_file = String::new();
         let mut max_triangle_edge_length = f64::INFINITY;
+        let mut breakline_inputs = BreaklineInputs::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -269,6 +283,13 @@ impl WhiteboxTool for TINGridding {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if BreaklineInputs::FLAGS.contains(&flag_val.as_str()) {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                breakline_inputs.set(&flag_val, value);
             }
         }
 
@@ -291,8 +312,9 @@ impl WhiteboxTool for TINGridding {
         if !output_file.contains(&sep) && !output_file.contains("/") {
             output_file = format!("{}{}", working_directory, output_file);
         }
+        breakline_inputs.resolve_paths(working_directory);
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of points type
         if input.header.shape_type.base_shape_type() != ShapeType::Point
@@ -451,8 +473,26 @@ impl WhiteboxTool for TINGridding {
         if verbose {
             println!("Performing triangulation...");
         }
+        let mut constraints = vec![];
+        let boundary_start = breakline_inputs
+            .add_to(&mut points, &mut z_values, &mut constraints)?
+            .constraint;
+
         // this is where the heavy-lifting is
-        let delaunay = triangulate(&points).expect("No triangulation exists.");
+        let cdt = constrained_triangulate(&points, &constraints).expect("No triangulation exists.");
+        if verbose && !cdt.failed_constraints.is_empty() {
+            println!(
+                "Warning: {} breakline segments intersect other breaklines and could not be enforced.",
+                cdt.failed_constraints.len()
+            );
+        }
+        cdt.interpolate_missing_values(&points, &mut z_values);
+        let inside = if breakline_inputs.has_boundary() {
+            cdt.mask_triangles(|c| c >= boundary_start)
+        } else {
+            vec![true; cdt.triangulation.len()]
+        };
+        let delaunay = &cdt.triangulation;
         let num_triangles = delaunay.triangles.len() / 3;
 
         let (mut p1, mut p2, mut p3): (usize, usize, usize);
@@ -476,14 +516,15 @@ impl WhiteboxTool for TINGridding {
             p1 = delaunay.triangles[i];
             p2 = delaunay.triangles[i + 1];
             p3 = delaunay.triangles[i + 2];
-            if max_distance_squared(
-                points[p1],
-                points[p2],
-                points[p3],
-                z_values[p1],
-                z_values[p2],
-                z_values[p3],
-            ) < max_triangle_edge_length
+            if inside[triangle]
+                && max_distance_squared(
+                    points[p1],
+                    points[p2],
+                    points[p3],
+                    z_values[p1],
+                    z_values[p2],
+                    z_values[p3],
+                ) < max_triangle_edge_length
             {
                 tri_points[0] = points[p1].clone();
                 tri_points[1] = points[p2].clone();
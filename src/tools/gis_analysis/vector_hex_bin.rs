@@ -252,7 +252,7 @@ impl WhiteboxTool for VectorHexBinning {
         }
 
         // Get the spatial extent
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
         let num_points = input.num_records;
 
         // make sure the input vector file is of points type
@@ -281,7 +281,7 @@ impl WhiteboxTool for VectorHexBinning {
         let (mut center_x, mut center_y): (f64, f64);
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
 
         // set the projection information
         output.projection = input.projection.clone();
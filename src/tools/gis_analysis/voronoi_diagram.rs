@@ -201,7 +201,7 @@ impl WhiteboxTool for VoronoiDiagram {
             output_file = format!("{}{}", working_directory, output_file);
         }
 
-        let input = Shapefile::read(&input_file)?;
+        let input = Vector::read(&input_file)?;
 
         // make sure the input vector file is of points type
         if input.header.shape_type.base_shape_type() != ShapeType::Point
@@ -217,7 +217,7 @@ impl WhiteboxTool for VoronoiDiagram {
         }
 
         // create output file
-        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        let mut output = Vector::new(&output_file, ShapeType::Polygon)?;
 
         // set the projection information
         output.projection = input.projection.clone();
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 22/06/2017
-Last Modified: 14/02/2020
+Last Modified: 18/10/2026
 License: MIT
 */
 
@@ -297,9 +297,7 @@ impl WhiteboxTool for Watershed {
         let low_value = f64::MIN;
         output.reinitialize_values(low_value);
 
-        if pourpts_file.to_lowercase().ends_with(".shp") {
-            // Note that this only works because at the moment, Shapefiles are the only supported vector.
-            // If additional vector formats are added in the future, this will need updating.
+        if is_vector_file(&pourpts_file) {
             let pourpts = Shapefile::read(&pourpts_file)?;
 
             // make sure the input vector file is of points type
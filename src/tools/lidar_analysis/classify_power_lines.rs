@@ -0,0 +1,1492 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{Matrix3, Vector3};
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D, FixedRadiusSearch3D};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use rand::Rng;
+use std::collections::HashSet;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool identifies the points belonging to overhead power lines and their supporting structures
+/// within a LiDAR point cloud (`--input`) and assigns them the ASPRS wire and tower classes:
+///
+/// | Class | Meaning                                    |
+/// |-------|--------------------------------------------|
+/// | 13    | Wire - guard (shield)                      |
+/// | 14    | Wire - conductor (phase)                   |
+/// | 15    | Transmission tower                         |
+/// | 16    | Wire-structure connector (e.g. insulator)  |
+///
+/// Wires are linear, elevated and sparsely sampled structures. The tool first estimates the height
+/// of each point above the ground, using the points classified as ground (class 2) if the file
+/// contains any, or else the lowest points within 5 m grid cells. Points that are at least
+/// `--min_height` above the ground are candidates. For each candidate, the eigenvalues
+/// (&lambda;<sub>1</sub> &ge; &lambda;<sub>2</sub> &ge; &lambda;<sub>3</sub>) of the covariance
+/// matrix of the neighbouring candidates within `--radius` are used to calculate the linearity,
+/// (&lambda;<sub>1</sub> - &lambda;<sub>2</sub>) / &lambda;<sub>1</sub>. Points with a linearity of at
+/// least `--linearity` and a principal direction that is within 30 degrees of horizontal are
+/// considered wire-like, which excludes the vertical members of towers and most vegetation.
+///
+/// The wire-like points are grouped into clusters of points separated by no more than `--max_gap`.
+/// Within each cluster, individual wires are extracted using the random sample consensus (RANSAC)
+/// method, first fitting a straight line in plan view and then fitting a catenary curve, i.e. the
+/// shape of a hanging cable, in the vertical plane of the line. Points within `--tolerance` of the
+/// fitted curve, both horizontally and vertically, are assigned to the wire, which is split into
+/// spans wherever the gap between consecutive points exceeds `--max_gap`. Spans that are shorter
+/// than `--min_length` are rejected. The final catenary of each span is fitted to its points by least
+/// squares and is used to pick up nearby candidates that were not themselves wire-like, e.g. near
+/// the ends of the span. The `--num_iter` parameter sets the number of RANSAC iterations.
+/// Where several wires share a corridor, the highest wire is classified as a guard wire if it lies
+/// at least 1 m above all of the others, and the remaining wires are classified as conductors.
+///
+/// Towers are sought at the ends of the spans. The non-ground points within half of
+/// `--max_tower_width` of a span end are classified as a tower if they form a vertically continuous
+/// structure rising from near the ground to the height of the wires. Tower points that are within
+/// `--radius` of a wire point are classified as wire-structure connectors. Notice that vegetation that
+/// is in contact with a tower may be classified as part of the tower.
+///
+/// Optionally, the tool will report the clearance between the wires and the vegetation beneath and
+/// beside them. If an output clearance file (`--clearance_file`) is specified, each vegetation point
+/// (classes 3-5), or unclassified point that is at least 0.5 m above the ground, that lies within
+/// `--clearance` of a wire point is written to a vector points file, with attributes for its
+/// distance to the nearest wire point (DIST), its height above ground (HEIGHT), its elevation (Z),
+/// its class value (CLASS), and the identifier of the nearest wire (WIRE).
+///
+/// The remaining points in the output LiDAR file (`--output`) retain their original class values.
+/// Points that are withheld or classified as noise are ignored.
+///
+/// # See Also
+/// `ClassifyBuildingsInLidar`, `LidarRansacPlanes`, `LidarGroundPointFilter`, `NormalVectors`
+pub struct ClassifyPowerLinesInLidar {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ClassifyPowerLinesInLidar {
+    /// public constructor
+    pub fn new() -> ClassifyPowerLinesInLidar {
+        let name = "ClassifyPowerLinesInLidar".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Classifies power line wires and transmission towers in a LiDAR point cloud."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search radius used to calculate local linearity.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height Above Ground".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description: "Minimum height of wire points above the ground.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("3.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Linearity Threshold".to_owned(),
+            flags: vec!["--linearity".to_owned()],
+            description: "Minimum linearity (0-1) of wire-like points.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.8".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Wire Fitting Tolerance".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Maximum distance between a wire point and the fitted wire.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.25".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Gap Between Wire Points".to_owned(),
+            flags: vec!["--max_gap".to_owned()],
+            description: "Maximum gap between consecutive points along a wire.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Span Length".to_owned(),
+            flags: vec!["--min_length".to_owned()],
+            description: "Minimum length of a wire span.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of RANSAC Iterations".to_owned(),
+            flags: vec!["--num_iter".to_owned()],
+            description: "Number of iterations used to fit each line and catenary.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("250".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Tower Width".to_owned(),
+            flags: vec!["--max_tower_width".to_owned()],
+            description: "Maximum horizontal width of a transmission tower.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Clearance File (optional)".to_owned(),
+            flags: vec!["--clearance_file".to_owned()],
+            description: "Optional output vector points file of vegetation within the clearance distance of wires.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Clearance Distance".to_owned(),
+            flags: vec!["--clearance".to_owned()],
+            description: "Clearance distance between wires and vegetation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=corridor.las -o=classified.las --radius=1.0 --min_height=3.0 --tolerance=0.25 --clearance_file=clearance.shp --clearance=5.0", short_exe, name).replace("*", &sep);
+
+        ClassifyPowerLinesInLidar {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ClassifyPowerLinesInLidar {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut clearance_file = String::new();
+        let mut radius = 1f64;
+        let mut min_height = 3f64;
+        let mut linearity_threshold = 0.8f64;
+        let mut tolerance = 0.25f64;
+        let mut max_gap = 5f64;
+        let mut min_length = 10f64;
+        let mut num_iter = 250usize;
+        let mut max_tower_width = 15f64;
+        let mut clearance = 5f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-clearance_file" {
+                clearance_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_iter" {
+                num_iter = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                };
+            } else if flag_val == "-radius"
+                || flag_val == "-min_height"
+                || flag_val == "-linearity"
+                || flag_val == "-tolerance"
+                || flag_val == "-max_gap"
+                || flag_val == "-min_length"
+                || flag_val == "-max_tower_width"
+                || flag_val == "-clearance"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-radius" => radius = value,
+                    "-min_height" => min_height = value,
+                    "-linearity" => linearity_threshold = value,
+                    "-tolerance" => tolerance = value,
+                    "-max_gap" => max_gap = value,
+                    "-min_length" => min_length = value,
+                    "-max_tower_width" => max_tower_width = value,
+                    _ => clearance = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        if !input_file.contains(sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !clearance_file.is_empty()
+            && !clearance_file.contains(sep)
+            && !clearance_file.contains("/")
+        {
+            clearance_file = format!("{}{}", working_directory, clearance_file);
+        }
+
+        if radius <= 0f64 || tolerance <= 0f64 || max_gap <= 0f64 || max_tower_width <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius, tolerance, maximum gap and maximum tower width must be greater than zero.",
+            ));
+        }
+        if num_iter < 1 {
+            num_iter = 1;
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = LasFile::new(&input_file, "r")?;
+        let n_points = input.header.number_of_points as usize;
+
+        let start = Instant::now();
+
+        let mut points: Vec<[f64; 3]> = Vec::with_capacity(n_points);
+        let mut new_class = vec![0u8; n_points];
+        let mut valid = vec![false; n_points];
+        let mut has_ground = false;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            points.push([p.x, p.y, p.z]);
+            new_class[i] = p.classification();
+            valid[i] = !p.withheld() && !p.is_classified_noise();
+            if valid[i] && new_class[i] == 2 {
+                has_ground = true;
+            }
+        }
+
+        /////////////////////////////////////////
+        // Estimate the height above the ground //
+        /////////////////////////////////////////
+        if verbose {
+            println!("Estimating heights above ground...");
+        }
+        let west = input.header.min_x;
+        let north = input.header.max_y;
+        let rows = ((north - input.header.min_y) / GROUND_CELL_SIZE).floor() as isize + 1;
+        let columns = ((input.header.max_x - west) / GROUND_CELL_SIZE).floor() as isize + 1;
+        let cell_of = |p: &[f64; 3]| -> (isize, isize) {
+            (
+                (((north - p[1]) / GROUND_CELL_SIZE).floor() as isize)
+                    .max(0)
+                    .min(rows - 1),
+                (((p[0] - west) / GROUND_CELL_SIZE).floor() as isize)
+                    .max(0)
+                    .min(columns - 1),
+            )
+        };
+        let mut cell_min = vec![f64::INFINITY; (rows * columns) as usize];
+        for i in 0..n_points {
+            if valid[i] && (!has_ground || new_class[i] == 2) {
+                let (row, col) = cell_of(&points[i]);
+                let k = (row * columns + col) as usize;
+                if points[i][2] < cell_min[k] {
+                    cell_min[k] = points[i][2];
+                }
+            }
+        }
+        // The ground surface is the lowest ground elevation in the 3 x 3 neighbourhood, and cells
+        // without ground points are filled from their neighbours.
+        let mut ground = vec![f64::INFINITY; (rows * columns) as usize];
+        for row in 0..rows {
+            for col in 0..columns {
+                let mut z = f64::INFINITY;
+                for r in (row - 1).max(0)..=(row + 1).min(rows - 1) {
+                    for c in (col - 1).max(0)..=(col + 1).min(columns - 1) {
+                        z = z.min(cell_min[(r * columns + c) as usize]);
+                    }
+                }
+                ground[(row * columns + col) as usize] = z;
+            }
+        }
+        for _ in 0..MAX_GROUND_FILL_PASSES {
+            let mut filled = ground.clone();
+            let mut num_unfilled = 0;
+            for row in 0..rows {
+                for col in 0..columns {
+                    if ground[(row * columns + col) as usize].is_infinite() {
+                        let mut z = f64::INFINITY;
+                        for r in (row - 1).max(0)..=(row + 1).min(rows - 1) {
+                            for c in (col - 1).max(0)..=(col + 1).min(columns - 1) {
+                                z = z.min(ground[(r * columns + c) as usize]);
+                            }
+                        }
+                        filled[(row * columns + col) as usize] = z;
+                        if z.is_infinite() {
+                            num_unfilled += 1;
+                        }
+                    }
+                }
+            }
+            ground = filled;
+            if num_unfilled == 0 {
+                break;
+            }
+        }
+        let mut height = vec![0f64; n_points];
+        for i in 0..n_points {
+            if valid[i] {
+                let (row, col) = cell_of(&points[i]);
+                let z = ground[(row * columns + col) as usize];
+                if z.is_finite() {
+                    height[i] = points[i][2] - z;
+                }
+            }
+        }
+
+        ///////////////////////////////////////
+        // Find the elevated wire-like points //
+        ///////////////////////////////////////
+        if verbose {
+            println!("Calculating point linearity...");
+        }
+        let candidates: Vec<usize> = (0..n_points)
+            .filter(|&i| valid[i] && new_class[i] != 2 && height[i] >= min_height)
+            .collect();
+        let num_candidates = candidates.len();
+        let mut frs = FixedRadiusSearch3D::new(radius, DistanceMetric::SquaredEuclidean);
+        for &i in &candidates {
+            frs.insert(points[i][0], points[i][1], points[i][2], i);
+        }
+        let points = Arc::new(points);
+        let candidates = Arc::new(candidates);
+        let frs = Arc::new(frs);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let points = points.clone();
+            let candidates = candidates.clone();
+            let frs = frs.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for k in (0..num_candidates).filter(|k| k % num_procs == tid) {
+                    let p = points[candidates[k]];
+                    let neighbours: Vec<[f64; 3]> = frs
+                        .search(p[0], p[1], p[2])
+                        .iter()
+                        .map(|&(j, _)| points[j])
+                        .collect();
+                    let is_wire_like = match linearity(&neighbours) {
+                        Some((value, direction)) => {
+                            value >= linearity_threshold && direction[2].abs() <= MAX_WIRE_DIP
+                        }
+                        None => false,
+                    };
+                    tx.send((k, is_wire_like)).unwrap();
+                }
+            });
+        }
+        let mut wire_like = vec![false; n_points];
+        for k in 0..num_candidates {
+            let (k2, is_wire_like) = rx.recv().expect("Error receiving data from thread.");
+            wire_like[candidates[k2]] = is_wire_like;
+            if verbose {
+                progress = (100.0_f64 * k as f64 / (num_candidates - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        ////////////////////////////////
+        // Cluster the wire-like points //
+        ////////////////////////////////
+        let mut frs_gap = FixedRadiusSearch3D::new(max_gap, DistanceMetric::SquaredEuclidean);
+        for i in 0..n_points {
+            if wire_like[i] {
+                frs_gap.insert(points[i][0], points[i][1], points[i][2], i);
+            }
+        }
+        let mut clusters: Vec<Vec<usize>> = vec![];
+        let mut visited = vec![false; n_points];
+        for i in 0..n_points {
+            if wire_like[i] && !visited[i] {
+                visited[i] = true;
+                let mut cluster = vec![];
+                let mut stack = vec![i];
+                while let Some(j) = stack.pop() {
+                    cluster.push(j);
+                    for (k, _) in frs_gap.search(points[j][0], points[j][1], points[j][2]) {
+                        if !visited[k] {
+                            visited[k] = true;
+                            stack.push(k);
+                        }
+                    }
+                }
+                if cluster.len() >= MIN_WIRE_POINTS {
+                    clusters.push(cluster);
+                }
+            }
+        }
+
+        ///////////////////////////////
+        // Fit the wires using RANSAC //
+        ///////////////////////////////
+        if verbose {
+            println!("Fitting wires...");
+        }
+        let mut rng = rand::thread_rng();
+        let mut wires: Vec<Wire> = vec![];
+        let mut wire_id = vec![-1isize; n_points];
+        let num_clusters = clusters.len();
+        old_progress = -1;
+        for (c, cluster) in clusters.iter().enumerate() {
+            let mut remaining = cluster.clone();
+            while remaining.len() >= MIN_WIRE_POINTS {
+                // Fit a line in plan view.
+                let mut line_inliers: Vec<usize> = vec![];
+                let mut line = ([0f64; 2], [1f64, 0f64]);
+                for _ in 0..num_iter {
+                    let a = points[remaining[rng.gen_range(0, remaining.len())]];
+                    let b = points[remaining[rng.gen_range(0, remaining.len())]];
+                    let len = (b[0] - a[0]).hypot(b[1] - a[1]);
+                    if len < radius {
+                        continue;
+                    }
+                    let dir = [(b[0] - a[0]) / len, (b[1] - a[1]) / len];
+                    let inliers: Vec<usize> = remaining
+                        .iter()
+                        .filter(|&&j| {
+                            ((points[j][0] - a[0]) * dir[1] - (points[j][1] - a[1]) * dir[0]).abs()
+                                <= tolerance
+                        })
+                        .cloned()
+                        .collect();
+                    if inliers.len() > line_inliers.len() {
+                        line_inliers = inliers;
+                        line = ([a[0], a[1]], dir);
+                    }
+                }
+                if line_inliers.len() < MIN_WIRE_POINTS {
+                    break;
+                }
+
+                // Fit a catenary in the vertical plane of the line.
+                let t: Vec<f64> = line_inliers
+                    .iter()
+                    .map(|&j| {
+                        (points[j][0] - line.0[0]) * line.1[0]
+                            + (points[j][1] - line.0[1]) * line.1[1]
+                    })
+                    .collect();
+                let mut curve_inliers: Vec<usize> = vec![];
+                for _ in 0..num_iter {
+                    let s = [
+                        rng.gen_range(0, t.len()),
+                        rng.gen_range(0, t.len()),
+                        rng.gen_range(0, t.len()),
+                    ];
+                    if (t[s[0]] - t[s[1]]).abs() < radius
+                        || (t[s[1]] - t[s[2]]).abs() < radius
+                        || (t[s[0]] - t[s[2]]).abs() < radius
+                    {
+                        continue;
+                    }
+                    let curve = match WireCurve::through(
+                        [t[s[0]], t[s[1]], t[s[2]]],
+                        [
+                            points[line_inliers[s[0]]][2],
+                            points[line_inliers[s[1]]][2],
+                            points[line_inliers[s[2]]][2],
+                        ],
+                    ) {
+                        Some(curve) => curve,
+                        None => continue,
+                    };
+                    let inliers: Vec<usize> = (0..t.len())
+                        .filter(|&k| {
+                            (points[line_inliers[k]][2] - curve.z(t[k])).abs() <= tolerance
+                        })
+                        .collect();
+                    if inliers.len() > curve_inliers.len() {
+                        curve_inliers = inliers;
+                    }
+                }
+                if curve_inliers.len() < MIN_WIRE_POINTS {
+                    // The line does not contain a wire; discard its points.
+                    let used: HashSet<usize> = line_inliers.iter().cloned().collect();
+                    remaining.retain(|j| !used.contains(j));
+                    continue;
+                }
+
+                // Split the wire into spans at gaps.
+                let mut along: Vec<(f64, usize)> = curve_inliers
+                    .iter()
+                    .map(|&k| (t[k], line_inliers[k]))
+                    .collect();
+                along.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                let mut spans: Vec<Vec<usize>> = vec![vec![along[0].1]];
+                for k in 1..along.len() {
+                    if along[k].0 - along[k - 1].0 > max_gap {
+                        spans.push(vec![]);
+                    }
+                    spans.last_mut().unwrap().push(along[k].1);
+                }
+                let num_wires = wires.len();
+                for span in spans {
+                    if span.len() >= MIN_WIRE_POINTS {
+                        let wire = Wire::fit(&points, &span);
+                        if wire.t_max - wire.t_min >= min_length {
+                            for &j in &span {
+                                wire_id[j] = wires.len() as isize;
+                            }
+                            wires.push(wire);
+                        }
+                    }
+                }
+                if wires.len() > num_wires {
+                    remaining.retain(|&j| wire_id[j] < 0);
+                } else {
+                    // No span was accepted; discard the points of the curve.
+                    let used: HashSet<usize> =
+                        curve_inliers.iter().map(|&k| line_inliers[k]).collect();
+                    remaining.retain(|j| !used.contains(j));
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * c as f64 / (num_clusters - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Tower cross-arms are also elevated linear structures, but unlike wire spans, they rest on a
+        // vertical structure at their mid-point.
+        let corridor_width = max_tower_width / 2f64;
+        let frs_site = structure_index(
+            &points,
+            &valid,
+            &new_class,
+            &height,
+            &wire_id,
+            corridor_width,
+        );
+        let mut is_span = vec![true; wires.len()];
+        for w in 0..wires.len() {
+            let t_mid = (wires[w].t_min + wires[w].t_max) / 2f64;
+            let (x, y) = wires[w].xy(t_mid);
+            let members: Vec<usize> = frs_site.search(x, y).iter().map(|&(j, _)| j).collect();
+            if is_vertical_structure(
+                &points,
+                &height,
+                &members,
+                wires[w].curve.z(t_mid),
+                min_height,
+            ) {
+                is_span[w] = false;
+            }
+        }
+        let mut new_ids = vec![-1isize; wires.len()];
+        let mut num_spans = 0;
+        for w in 0..wires.len() {
+            if is_span[w] {
+                new_ids[w] = num_spans;
+                num_spans += 1;
+            }
+        }
+        for i in 0..n_points {
+            if wire_id[i] >= 0 {
+                wire_id[i] = new_ids[wire_id[i] as usize];
+            }
+        }
+        let mut w = 0;
+        wires.retain(|_| {
+            w += 1;
+            is_span[w - 1]
+        });
+
+        // Pick up the candidates near each wire that were not wire-like.
+        let mut frs_tol =
+            FixedRadiusSearch3D::new(2f64 * tolerance, DistanceMetric::SquaredEuclidean);
+        for &i in candidates.iter() {
+            if wire_id[i] < 0 {
+                frs_tol.insert(points[i][0], points[i][1], points[i][2], i);
+            }
+        }
+        for (w, wire) in wires.iter().enumerate() {
+            let num_steps = ((wire.t_max - wire.t_min + 2f64 * radius) / tolerance).ceil() as usize;
+            for s in 0..=num_steps {
+                let t = wire.t_min - radius + s as f64 * tolerance;
+                let (x, y) = wire.xy(t);
+                for (j, _) in frs_tol.search(x, y, wire.curve.z(t)) {
+                    if wire_id[j] < 0 && wire.fits(&points[j], tolerance, radius) {
+                        wire_id[j] = w as isize;
+                    }
+                }
+            }
+        }
+
+        // Distinguish the guard wires from the conductors.
+        for w in 0..wires.len() {
+            let t_mid = (wires[w].t_min + wires[w].t_max) / 2f64;
+            let (x, y) = wires[w].xy(t_mid);
+            let z = wires[w].curve.z(t_mid);
+            let mut num_neighbours = 0;
+            let mut is_highest = true;
+            for v in 0..wires.len() {
+                if v != w {
+                    let t = wires[v].t(x, y);
+                    if wires[v].offset(x, y).abs() <= corridor_width
+                        && t >= wires[v].t_min
+                        && t <= wires[v].t_max
+                    {
+                        num_neighbours += 1;
+                        if z < wires[v].curve.z(t) + MIN_GUARD_SEPARATION {
+                            is_highest = false;
+                        }
+                    }
+                }
+            }
+            wires[w].class = if num_neighbours > 0 && is_highest {
+                13
+            } else {
+                14
+            };
+        }
+        for i in 0..n_points {
+            if wire_id[i] >= 0 {
+                new_class[i] = wires[wire_id[i] as usize].class;
+            }
+        }
+
+        //////////////////////////////////
+        // Find the towers at span ends //
+        //////////////////////////////////
+        if verbose {
+            println!("Locating towers...");
+        }
+        // Group the span ends into tower sites, each with the highest wire elevation.
+        let mut sites: Vec<(f64, f64, f64, usize)> = vec![];
+        for wire in &wires {
+            for &t in [wire.t_min, wire.t_max].iter() {
+                let (x, y) = wire.xy(t);
+                let z = wire.curve.z(t);
+                match sites
+                    .iter_mut()
+                    .find(|s| (s.0 - x).hypot(s.1 - y) <= max_tower_width)
+                {
+                    Some(site) => {
+                        let n = site.3 as f64;
+                        site.0 = (site.0 * n + x) / (n + 1f64);
+                        site.1 = (site.1 * n + y) / (n + 1f64);
+                        site.2 = site.2.max(z);
+                        site.3 += 1;
+                    }
+                    None => sites.push((x, y, z, 1)),
+                }
+            }
+        }
+        let frs_site = structure_index(
+            &points,
+            &valid,
+            &new_class,
+            &height,
+            &wire_id,
+            corridor_width,
+        );
+        let mut num_towers = 0;
+        for site in &sites {
+            let members: Vec<usize> = frs_site
+                .search(site.0, site.1)
+                .iter()
+                .map(|&(j, _)| j)
+                .collect();
+            if is_vertical_structure(&points, &height, &members, site.2, min_height) {
+                num_towers += 1;
+                for &j in &members {
+                    new_class[j] = 15;
+                }
+            }
+        }
+
+        // Tower points touching the wires are connectors.
+        let mut frs_wire = FixedRadiusSearch3D::new(radius, DistanceMetric::SquaredEuclidean);
+        for i in 0..n_points {
+            if wire_id[i] >= 0 {
+                frs_wire.insert(points[i][0], points[i][1], points[i][2], i);
+            }
+        }
+        for i in 0..n_points {
+            if new_class[i] == 15
+                && !frs_wire
+                    .search(points[i][0], points[i][1], points[i][2])
+                    .is_empty()
+            {
+                new_class[i] = 16;
+            }
+        }
+
+        if verbose {
+            let mut counts = [0usize; 4];
+            for i in 0..n_points {
+                if new_class[i] >= 13
+                    && new_class[i] <= 16
+                    && (wire_id[i] >= 0 || new_class[i] >= 15)
+                {
+                    counts[(new_class[i] - 13) as usize] += 1;
+                }
+            }
+            println!(
+                "Wire spans: {} ({} guard, {} conductor)",
+                wires.len(),
+                wires.iter().filter(|w| w.class == 13).count(),
+                wires.iter().filter(|w| w.class == 14).count()
+            );
+            println!("Towers: {}", num_towers);
+            println!("Guard wire points (class 13): {}", counts[0]);
+            println!("Conductor points (class 14): {}", counts[1]);
+            println!("Tower points (class 15): {}", counts[2]);
+            println!("Connector points (class 16): {}", counts[3]);
+        }
+
+        ////////////////////////////////
+        // Vegetation clearance report //
+        ////////////////////////////////
+        if !clearance_file.is_empty() {
+            let mut frs_clear =
+                FixedRadiusSearch3D::new(clearance, DistanceMetric::SquaredEuclidean);
+            for i in 0..n_points {
+                if wire_id[i] >= 0 {
+                    frs_clear.insert(
+                        points[i][0],
+                        points[i][1],
+                        points[i][2],
+                        wire_id[i] as usize,
+                    );
+                }
+            }
+            let mut output = Shapefile::new(&clearance_file, ShapeType::Point)?;
+            output.projection = input.get_wkt();
+            output
+                .attributes
+                .add_field(&AttributeField::new("FID", FieldDataType::Int, 9u8, 0u8));
+            output.attributes.add_field(&AttributeField::new(
+                "DIST",
+                FieldDataType::Real,
+                10u8,
+                3u8,
+            ));
+            output.attributes.add_field(&AttributeField::new(
+                "HEIGHT",
+                FieldDataType::Real,
+                10u8,
+                3u8,
+            ));
+            output
+                .attributes
+                .add_field(&AttributeField::new("Z", FieldDataType::Real, 12u8, 3u8));
+            output.attributes.add_field(&AttributeField::new(
+                "CLASS",
+                FieldDataType::Int,
+                3u8,
+                0u8,
+            ));
+            output
+                .attributes
+                .add_field(&AttributeField::new("WIRE", FieldDataType::Int, 7u8, 0u8));
+            let mut fid = 0;
+            for i in 0..n_points {
+                let class = new_class[i];
+                let is_vegetation =
+                    (class >= 3 && class <= 5) || (class <= 1 && height[i] >= MIN_STRUCTURE_HEIGHT);
+                if valid[i] && wire_id[i] < 0 && is_vegetation {
+                    let nearest = frs_clear
+                        .search(points[i][0], points[i][1], points[i][2])
+                        .into_iter()
+                        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                    if let Some((w, dist)) = nearest {
+                        fid += 1;
+                        output.add_point_record(points[i][0], points[i][1]);
+                        output.attributes.add_record(
+                            vec![
+                                FieldData::Int(fid),
+                                FieldData::Real(dist.sqrt()),
+                                FieldData::Real(height[i]),
+                                FieldData::Real(points[i][2]),
+                                FieldData::Int(class as i32),
+                                FieldData::Int(w as i32 + 1),
+                            ],
+                            false,
+                        );
+                    }
+                }
+            }
+            if verbose {
+                println!(
+                    "Vegetation points within the clearance distance of wires: {}",
+                    fid
+                );
+            }
+            output.write()?;
+        }
+
+        //////////////////////
+        // Output the points //
+        //////////////////////
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        for i in 0..n_points {
+            let pr = input.get_record(i);
+            let pr2: LidarPointRecord;
+            match pr {
+                LidarPointRecord::PointRecord0 { mut point_data } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord0 {
+                        point_data: point_data,
+                    };
+                }
+                LidarPointRecord::PointRecord1 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord1 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord2 {
+                    mut point_data,
+                    colour_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord2 {
+                        point_data: point_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord3 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord3 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord4 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord4 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord5 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord5 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord6 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord6 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord7 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord7 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord8 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord8 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord9 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord9 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord10 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.set_classification(new_class[i]);
+                    pr2 = LidarPointRecord::PointRecord10 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+            }
+            output.add_point_record(pr2);
+        }
+
+        if verbose {
+            let elapsed_time = get_formatted_elapsed_time(start);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+
+        Ok(())
+    }
+}
+
+/// The grid cell size used to estimate the ground surface.
+const GROUND_CELL_SIZE: f64 = 5.0;
+/// The maximum number of passes used to fill ground cells that lack ground points.
+const MAX_GROUND_FILL_PASSES: usize = 20;
+/// The minimum number of points in a wire span.
+const MIN_WIRE_POINTS: usize = 10;
+/// The maximum vertical component of the direction of a wire-like point (sin 30 degrees).
+const MAX_WIRE_DIP: f64 = 0.5;
+/// The minimum height above the highest neighbouring wire of a guard wire.
+const MIN_GUARD_SEPARATION: f64 = 1.0;
+/// The minimum height above ground of tower and vegetation points.
+const MIN_STRUCTURE_HEIGHT: f64 = 0.5;
+/// The minimum proportion of 1 m height bins between the ground and wires occupied by a tower.
+const MIN_TOWER_COVERAGE: f64 = 0.75;
+
+// Returns the linearity of a neighbourhood of points and its principal direction.
+fn linearity(points: &[[f64; 3]]) -> Option<(f64, [f64; 3])> {
+    if points.len() < 5 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mut c = [0f64; 3];
+    for p in points {
+        for j in 0..3 {
+            c[j] += p[j] / n;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        for r in 0..3 {
+            for k in 0..3 {
+                cov[(r, k)] += d[r] * d[k] / n;
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut order = [0usize, 1, 2];
+    order.sort_by(|&a, &b| {
+        eig.eigenvalues[b]
+            .partial_cmp(&eig.eigenvalues[a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let l1 = eig.eigenvalues[order[0]];
+    let l2 = eig.eigenvalues[order[1]];
+    if !(l1 > 0f64) {
+        return None;
+    }
+    let v = eig.eigenvectors.column(order[0]);
+    Some(((l1 - l2) / l1, [v[0], v[1], v[2]]))
+}
+
+// Indexes the non-ground, non-wire points that are above the ground, which may belong to towers.
+fn structure_index(
+    points: &[[f64; 3]],
+    valid: &[bool],
+    classes: &[u8],
+    height: &[f64],
+    wire_id: &[isize],
+    radius: f64,
+) -> FixedRadiusSearch2D<usize> {
+    let mut frs = FixedRadiusSearch2D::new(radius, DistanceMetric::SquaredEuclidean);
+    for i in 0..points.len() {
+        if valid[i] && wire_id[i] < 0 && classes[i] != 2 && height[i] >= MIN_STRUCTURE_HEIGHT {
+            frs.insert(points[i][0], points[i][1], i);
+        }
+    }
+    frs
+}
+
+// Returns true if a set of points forms a structure that is vertically continuous from near the
+// ground to the elevation of the wires that it supports.
+fn is_vertical_structure(
+    points: &[[f64; 3]],
+    height: &[f64],
+    members: &[usize],
+    wire_z: f64,
+    min_height: f64,
+) -> bool {
+    if members.is_empty() {
+        return false;
+    }
+    let ground_z = members
+        .iter()
+        .map(|&j| points[j][2] - height[j])
+        .sum::<f64>()
+        / members.len() as f64;
+    let wire_height = wire_z - ground_z;
+    if wire_height < min_height {
+        return false;
+    }
+    let num_bins = wire_height.ceil() as usize;
+    let mut occupied = vec![false; num_bins];
+    for &j in members {
+        let bin = height[j].floor();
+        if bin >= 0f64 && (bin as usize) < num_bins {
+            occupied[bin as usize] = true;
+        }
+    }
+    let coverage = occupied.iter().filter(|&&o| o).count() as f64 / num_bins as f64;
+    let top = members
+        .iter()
+        .map(|&j| points[j][2])
+        .fold(f64::NEG_INFINITY, f64::max);
+    coverage >= MIN_TOWER_COVERAGE && top >= wire_z - MIN_GUARD_SEPARATION
+}
+
+/// The vertical profile of a wire, as a function of the distance along the wire.
+#[derive(Clone, Copy, Debug)]
+enum WireCurve {
+    /// z = c0 + c1 t + c2 t^2
+    Parabola(f64, f64, f64),
+    /// z = z0 + a (cosh((t - t0) / a) - 1)
+    Catenary { t0: f64, z0: f64, a: f64 },
+}
+
+impl WireCurve {
+    fn z(&self, t: f64) -> f64 {
+        match *self {
+            WireCurve::Parabola(c0, c1, c2) => c0 + c1 * t + c2 * t * t,
+            WireCurve::Catenary { t0, z0, a } => z0 + a * (((t - t0) / a).cosh() - 1f64),
+        }
+    }
+
+    // The parabola passing through three points; hanging wires cannot curve downwards.
+    fn through(t: [f64; 3], z: [f64; 3]) -> Option<WireCurve> {
+        let m = Matrix3::new(
+            1f64,
+            t[0],
+            t[0] * t[0],
+            1f64,
+            t[1],
+            t[1] * t[1],
+            1f64,
+            t[2],
+            t[2] * t[2],
+        );
+        let c = m.try_inverse()? * Vector3::new(z[0], z[1], z[2]);
+        if c[2] < -MAX_NEGATIVE_CURVATURE {
+            return None;
+        }
+        Some(WireCurve::Parabola(c[0], c[1], c[2]))
+    }
+
+    // Fits a catenary to a set of points by least squares, using the Gauss-Newton method
+    // initialized with the least-squares parabola. The parabola is returned if the catenary
+    // does not converge, e.g. for a taut wire.
+    fn fit(t: &[f64], z: &[f64]) -> WireCurve {
+        let mut ata = Matrix3::<f64>::zeros();
+        let mut atb = Vector3::<f64>::zeros();
+        for k in 0..t.len() {
+            let row = Vector3::new(1f64, t[k], t[k] * t[k]);
+            ata += row * row.transpose();
+            atb += row * z[k];
+        }
+        let parabola = match ata.try_inverse() {
+            Some(inv) => {
+                let c = inv * atb;
+                WireCurve::Parabola(c[0], c[1], c[2])
+            }
+            None => {
+                let mean = z.iter().sum::<f64>() / z.len() as f64;
+                return WireCurve::Parabola(mean, 0f64, 0f64);
+            }
+        };
+        let (c0, c1, c2) = match parabola {
+            WireCurve::Parabola(c0, c1, c2) => (c0, c1, c2),
+            _ => unreachable!(),
+        };
+        if c2 <= MIN_CATENARY_CURVATURE {
+            return parabola;
+        }
+        let mut a = 1f64 / (2f64 * c2);
+        let mut t0 = -c1 / (2f64 * c2);
+        let mut z0 = c0 - c1 * c1 / (4f64 * c2);
+        for _ in 0..20 {
+            let mut jtj = Matrix3::<f64>::zeros();
+            let mut jtr = Vector3::<f64>::zeros();
+            for k in 0..t.len() {
+                let u = (t[k] - t0) / a;
+                if u.abs() > 50f64 {
+                    return parabola;
+                }
+                let (ch, sh) = (u.cosh(), u.sinh());
+                let r = z[k] - (z0 + a * (ch - 1f64));
+                let j = Vector3::new(1f64, -sh, ch - 1f64 - u * sh);
+                jtj += j * j.transpose();
+                jtr += j * r;
+            }
+            let delta = match jtj.try_inverse() {
+                Some(inv) => inv * jtr,
+                None => break,
+            };
+            z0 += delta[0];
+            t0 += delta[1];
+            a += delta[2];
+            if !(a > 0f64) || !z0.is_finite() || !t0.is_finite() {
+                return parabola;
+            }
+            if delta.norm() < 1e-9 {
+                break;
+            }
+        }
+        let catenary = WireCurve::Catenary { t0, z0, a };
+        let sse = |curve: &WireCurve| -> f64 {
+            (0..t.len())
+                .map(|k| (z[k] - curve.z(t[k])).powi(2))
+                .sum::<f64>()
+        };
+        if sse(&catenary) <= sse(&parabola) {
+            catenary
+        } else {
+            parabola
+        }
+    }
+}
+
+/// The most negative parabola curvature accepted for a RANSAC sample.
+const MAX_NEGATIVE_CURVATURE: f64 = 1e-4;
+/// The minimum parabola curvature for which a catenary is fitted.
+const MIN_CATENARY_CURVATURE: f64 = 1e-6;
+
+/// A wire span, consisting of a line in plan view and a vertical profile along it.
+struct Wire {
+    origin: [f64; 2],
+    direction: [f64; 2],
+    t_min: f64,
+    t_max: f64,
+    curve: WireCurve,
+    class: u8,
+}
+
+impl Wire {
+    fn fit(points: &[[f64; 3]], span: &[usize]) -> Wire {
+        let n = span.len() as f64;
+        let mx = span.iter().map(|&j| points[j][0]).sum::<f64>() / n;
+        let my = span.iter().map(|&j| points[j][1]).sum::<f64>() / n;
+        let (mut sxx, mut sxy, mut syy) = (0f64, 0f64, 0f64);
+        for &j in span {
+            let (dx, dy) = (points[j][0] - mx, points[j][1] - my);
+            sxx += dx * dx;
+            sxy += dx * dy;
+            syy += dy * dy;
+        }
+        let angle = 0.5 * (2f64 * sxy).atan2(sxx - syy);
+        let mut wire = Wire {
+            origin: [mx, my],
+            direction: [angle.cos(), angle.sin()],
+            t_min: f64::INFINITY,
+            t_max: f64::NEG_INFINITY,
+            curve: WireCurve::Parabola(0f64, 0f64, 0f64),
+            class: 14,
+        };
+        let t: Vec<f64> = span
+            .iter()
+            .map(|&j| wire.t(points[j][0], points[j][1]))
+            .collect();
+        let z: Vec<f64> = span.iter().map(|&j| points[j][2]).collect();
+        for &v in &t {
+            wire.t_min = wire.t_min.min(v);
+            wire.t_max = wire.t_max.max(v);
+        }
+        wire.curve = WireCurve::fit(&t, &z);
+        wire
+    }
+
+    fn t(&self, x: f64, y: f64) -> f64 {
+        (x - self.origin[0]) * self.direction[0] + (y - self.origin[1]) * self.direction[1]
+    }
+
+    fn offset(&self, x: f64, y: f64) -> f64 {
+        (x - self.origin[0]) * self.direction[1] - (y - self.origin[1]) * self.direction[0]
+    }
+
+    fn xy(&self, t: f64) -> (f64, f64) {
+        (
+            self.origin[0] + t * self.direction[0],
+            self.origin[1] + t * self.direction[1],
+        )
+    }
+
+    fn fits(&self, p: &[f64; 3], tolerance: f64, overhang: f64) -> bool {
+        let t = self.t(p[0], p[1]);
+        t >= self.t_min - overhang
+            && t <= self.t_max + overhang
+            && self.offset(p[0], p[1]).abs() <= tolerance
+            && (p[2] - self.curve.z(t)).abs() <= tolerance
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{linearity, Wire, WireCurve};
+
+    #[test]
+    fn test_linearity() {
+        let wire: Vec<[f64; 3]> = (0..20)
+            .map(|i| [i as f64 * 0.6, i as f64 * 0.8, 10.0])
+            .collect();
+        let (l, dir) = linearity(&wire).unwrap();
+        assert!((l - 1.0).abs() < 1e-9);
+        assert!((dir[0].abs() - 0.6).abs() < 1e-9 && (dir[1].abs() - 0.8).abs() < 1e-9);
+        assert!(dir[2].abs() < 1e-9);
+
+        let plane: Vec<[f64; 3]> = (0..25)
+            .map(|i| [(i % 5) as f64, (i / 5) as f64, 0.0])
+            .collect();
+        let (l, _) = linearity(&plane).unwrap();
+        assert!(l.abs() < 1e-9);
+        assert!(linearity(&wire[0..4]).is_none());
+    }
+
+    #[test]
+    fn test_wire_catenary_fit() {
+        // a wire hanging along a diagonal, with its low point 3 m from the span centre
+        let (t0, z0, a) = (3.0, 20.0, 150.0);
+        let expected = WireCurve::Catenary { t0, z0, a };
+        let d = 0.5f64.sqrt();
+        let points: Vec<[f64; 3]> = (0..=100)
+            .map(|i| {
+                let t = i as f64 - 50.0;
+                [100.0 + t * d, 200.0 + t * d, expected.z(t)]
+            })
+            .collect();
+        let span: Vec<usize> = (0..points.len()).collect();
+        let wire = Wire::fit(&points, &span);
+        assert!((wire.t_max - wire.t_min - 100.0).abs() < 1e-6);
+        match wire.curve {
+            WireCurve::Catenary { z0: fz0, a: fa, .. } => {
+                assert!((fz0 - z0).abs() < 1e-6);
+                assert!((fa - a).abs() < 1e-4);
+            }
+            _ => panic!("expected a catenary"),
+        }
+        for p in &points {
+            assert!((wire.curve.z(wire.t(p[0], p[1])) - p[2]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_wire_through() {
+        match WireCurve::through([0.0, 1.0, 2.0], [1.0, 0.0, 1.0]) {
+            Some(WireCurve::Parabola(c0, c1, c2)) => {
+                assert!((c0 - 1.0).abs() < 1e-12);
+                assert!((c1 + 2.0).abs() < 1e-12);
+                assert!((c2 - 1.0).abs() < 1e-12);
+            }
+            _ => panic!("expected a parabola"),
+        }
+        // hanging wires cannot curve downwards
+        assert!(WireCurve::through([0.0, 1.0, 2.0], [0.0, 1.0, 0.0]).is_none());
+    }
+}
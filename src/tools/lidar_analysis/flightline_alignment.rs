@@ -0,0 +1,1297 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{DMatrix, DVector, Matrix3};
+use crate::structures::{BoundingBox, DistanceMetric, FixedRadiusSearch2D};
+use crate::tools::*;
+use num_cpus;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool measures and corrects the misalignment between the overlapping flight lines (swaths)
+/// contained within a LiDAR file (`--input`). Flight lines are identified using the point source
+/// ID of each point. The `FlightlineOverlap` and `ClassifyOverlapPoints` tools can be used to map
+/// the areas of overlap; this tool quantifies the inter-swath consistency within those areas and
+/// estimates the adjustments needed to remove systematic offsets.
+///
+/// The alignment is estimated using a global point-to-plane iterative closest point (ICP)
+/// procedure. In each overlapping pair of flight lines, a sample of points from one line is
+/// matched to planes fitted to the neighbouring points (within `--search_radius`) of the other.
+/// Only planar neighbourhoods, i.e. those with a plane-fit root-mean-square error (RMSE) less than
+/// `--max_plane_rmse`, are used, which excludes vegetation and other rough surfaces. The distances
+/// of the points to the planes are then minimized simultaneously for all pairs by solving for a
+/// translation (dx, dy, dz) of each flight line and, if `--rotations` is specified, a small
+/// rotation (roll, pitch, yaw) about the flight line's centroid. One flight line, the reference
+/// (`--reference`; by default the line with the most points), is held fixed. The procedure is
+/// repeated, with correspondences re-established using the updated adjustments, for up to
+/// `--max_iterations` iterations or until the adjustments converge. Horizontal offsets can only be
+/// resolved where the overlap areas contain sloping planar surfaces, such as building roofs;
+/// over flat terrain, only the vertical offsets are estimated.
+///
+/// The tool outputs an HTML report (`--output`) containing the estimated adjustment for each
+/// flight line and, for each overlapping pair of flight lines, the number of point-to-plane
+/// correspondences and the mean, standard deviation and RMSE of the point-to-plane distances
+/// before and after adjustment. If an output LiDAR file is specified (`--output_las`), the
+/// adjustments are applied to the points of each flight line.
+///
+/// # See Also
+/// `FlightlineOverlap`, `ClassifyOverlapPoints`, `FindFlightlineEdgePoints`
+pub struct FlightlineAlignment {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl FlightlineAlignment {
+    pub fn new() -> FlightlineAlignment {
+        // public constructor
+        let name = "FlightlineAlignment".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Measures and corrects the misalignment between overlapping flight lines using point-to-plane ICP.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output HTML Report File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output HTML report file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Html),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Adjusted LiDAR File (optional)".to_owned(),
+            flags: vec!["--output_las".to_owned()],
+            description: "Optional output LiDAR file containing the adjusted points.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--search_radius".to_owned()],
+            description: "Search radius used to fit local planes, in xy units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Plane RMSE".to_owned(),
+            flags: vec!["--max_plane_rmse".to_owned()],
+            description:
+                "Maximum root-mean-square error of a local plane fit for the plane to be used."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.05".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Number of Iterations".to_owned(),
+            flags: vec!["--max_iterations".to_owned()],
+            description: "Maximum number of ICP iterations.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Estimate rotations?".to_owned(),
+            flags: vec!["--rotations".to_owned()],
+            description: "Estimate a small rotation (roll, pitch, yaw) of each flight line in addition to the translation.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Reference Point Source ID (optional)".to_owned(),
+            flags: vec!["--reference".to_owned()],
+            description: "Point source ID of the flight line held fixed; by default the flight line with the most points.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=alignment.html --output_las=adjusted.las --search_radius=1.5 --rotations", short_exe, name).replace("*", &sep);
+
+        FlightlineAlignment {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for FlightlineAlignment {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut output_las_file = String::new();
+        let mut search_radius = 1f64;
+        let mut max_plane_rmse = 0.05f64;
+        let mut max_iterations = 10usize;
+        let mut estimate_rotations = false;
+        let mut reference_id: Option<u16> = None;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_las" {
+                output_las_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-search_radius" {
+                search_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_plane_rmse" {
+                max_plane_rmse = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_iterations" {
+                max_iterations = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                };
+            } else if flag_val == "-rotations" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    estimate_rotations = true;
+                }
+            } else if flag_val == "-reference" {
+                reference_id = Some(if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                });
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        let start = Instant::now();
+
+        if !input_file.contains(sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !output_file.ends_with(".html") {
+            output_file = output_file + ".html";
+        }
+        if !output_las_file.is_empty()
+            && !output_las_file.contains(sep)
+            && !output_las_file.contains("/")
+        {
+            output_las_file = format!("{}{}", working_directory, output_las_file);
+        }
+
+        if search_radius <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+        // Group the points by flight line, i.e. point source ID.
+        let mut strip_index: HashMap<u16, usize> = HashMap::new();
+        let mut strips: Vec<Strip> = vec![];
+        let mut points: Vec<[f64; 3]> = Vec::with_capacity(n_points);
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if !p.withheld() && !p.is_classified_noise() {
+                let s = match strip_index.get(&p.point_source_id) {
+                    Some(s) => *s,
+                    None => {
+                        strips.push(Strip::new(p.point_source_id, search_radius));
+                        strip_index.insert(p.point_source_id, strips.len() - 1);
+                        strips.len() - 1
+                    }
+                };
+                strips[s].add_point(p.x, p.y, p.z, points.len());
+                points.push([p.x, p.y, p.z]);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Reading points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        strips.sort_by(|a, b| a.id.cmp(&b.id));
+        let num_strips = strips.len();
+        if num_strips < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file must contain at least two flight lines (point source IDs).",
+            ));
+        }
+        let mut strip_map: HashMap<u16, usize> = HashMap::new();
+        for s in 0..num_strips {
+            strips[s].finalize();
+            strip_map.insert(strips[s].id, s);
+        }
+
+        let reference = match reference_id {
+            Some(id) => match strips.iter().position(|s| s.id == id) {
+                Some(s) => s,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The reference point source ID ({}) is not in the input file.",
+                            id
+                        ),
+                    ))
+                }
+            },
+            None => {
+                let mut s = 0;
+                for m in 1..num_strips {
+                    if strips[m].points.len() > strips[s].points.len() {
+                        s = m;
+                    }
+                }
+                s
+            }
+        };
+
+        // Find the overlapping pairs and sample the points of the first strip in each pair
+        // that fall within the overlap area.
+        let mut pairs: Vec<(usize, usize, Vec<usize>)> = vec![];
+        for a in 0..num_strips {
+            for b in (a + 1)..num_strips {
+                if strips[a].extent.overlaps(strips[b].extent) {
+                    let overlap = BoundingBox::new(
+                        strips[a].extent.min_x.max(strips[b].extent.min_x),
+                        strips[a].extent.max_x.min(strips[b].extent.max_x),
+                        strips[a].extent.min_y.max(strips[b].extent.min_y),
+                        strips[a].extent.max_y.min(strips[b].extent.max_y),
+                    );
+                    let candidates: Vec<usize> = strips[a]
+                        .points
+                        .iter()
+                        .filter(|&&i| overlap.is_point_in_box(points[i][0], points[i][1]))
+                        .map(|&i| i)
+                        .collect();
+                    let stride = (candidates.len() / MAX_SAMPLES_PER_PAIR).max(1);
+                    let samples: Vec<usize> = candidates.into_iter().step_by(stride).collect();
+                    if !samples.is_empty() {
+                        pairs.push((a, b, samples));
+                    }
+                }
+            }
+        }
+        if pairs.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "None of the flight lines in the input file overlap.",
+            ));
+        }
+
+        let num_params = if estimate_rotations { 6 } else { 3 };
+        let points = Arc::new(points);
+        let strips = Arc::new(strips);
+        let pairs = Arc::new(pairs);
+        let mut transforms = vec![[0f64; 6]; num_strips];
+
+        let initial = find_correspondences(
+            &points,
+            &strips,
+            &pairs,
+            &transforms,
+            max_plane_rmse,
+            search_radius,
+        );
+        if initial.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No planar point-to-plane correspondences were found in the overlap areas. Try increasing the search radius or the maximum plane RMSE.",
+            ));
+        }
+        let before = pair_statistics(&initial, &transforms, &strips, pairs.len());
+
+        let mut correspondences = initial;
+        let mut num_iterations = 0;
+        for iteration in 0..max_iterations {
+            num_iterations = iteration + 1;
+            // Trim the outlying correspondences, i.e. those more than three times the RMSE
+            // from the planes under the current adjustment.
+            let residuals: Vec<f64> = correspondences
+                .iter()
+                .map(|c| c.residual(&transforms, &strips))
+                .collect();
+            let rmse = (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64)
+                .sqrt()
+                .max(0.01);
+            let used: Vec<&Correspondence> = correspondences
+                .iter()
+                .zip(residuals.iter())
+                .filter(|(_, r)| r.abs() <= 3f64 * rmse)
+                .map(|(c, _)| c)
+                .collect();
+
+            let solution = solve_adjustment(&used, &strips, reference, num_params)?;
+
+            let mut max_change = 0f64;
+            for s in 0..num_strips {
+                for j in 0..num_params {
+                    let change = (solution[s][j] - transforms[s][j]).abs();
+                    // rotations are compared in terms of the displacement 100 m from the centroid
+                    let change = if j < 3 { change } else { change * 100f64 };
+                    max_change = max_change.max(change);
+                }
+                transforms[s] = solution[s];
+            }
+
+            if verbose {
+                println!(
+                    "Iteration {}: {} correspondences, RMSE = {:.4}, max. change = {:.4}",
+                    num_iterations,
+                    used.len(),
+                    rmse,
+                    max_change
+                );
+            }
+            if max_change < 0.0005 {
+                break;
+            }
+
+            correspondences = find_correspondences(
+                &points,
+                &strips,
+                &pairs,
+                &transforms,
+                max_plane_rmse,
+                search_radius,
+            );
+        }
+
+        let final_correspondences = find_correspondences(
+            &points,
+            &strips,
+            &pairs,
+            &transforms,
+            max_plane_rmse,
+            search_radius,
+        );
+        let after = pair_statistics(&final_correspondences, &transforms, &strips, pairs.len());
+
+        // Write the report.
+        let mut f = File::create(output_file.as_str())?;
+        let mut s = "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">
+        <head>
+            <meta content=\"text/html; charset=UTF-8\" http-equiv=\"content-type\">
+            <title>Flightline Alignment</title>
+            <style  type=\"text/css\">
+                h1 {
+                    font-size: 14pt;
+                    margin-left: 15px;
+                    margin-right: 15px;
+                    text-align: center;
+                    font-family: Helvetica, Verdana, Geneva, Arial, sans-serif;
+                }
+                p, ol, ul, li {
+                    font-size: 12pt;
+                    font-family: Helvetica, Verdana, Geneva, Arial, sans-serif;
+                    margin-left: 15px;
+                    margin-right: 15px;
+                }
+                caption {
+                    font-family: Helvetica, Verdana, Geneva, Arial, sans-serif;
+                    font-size: 12pt;
+                    margin-left: 15px;
+                    margin-right: 15px;
+                }
+                table {
+                    font-size: 12pt;
+                    font-family: Helvetica, Verdana, Geneva, Arial, sans-serif;
+                    font-family: arial, sans-serif;
+                    border-collapse: collapse;
+                    align: center;
+                }
+                td {
+                    text-align: left;
+                    padding: 8px;
+                }
+                th {
+                    text-align: left;
+                    padding: 8px;
+                    background-color: #ffffff;
+                    border-bottom: 1px solid #333333;
+                    text-align: center;
+                }
+                tr:nth-child(1) {
+                    border-bottom: 1px solid #333333;
+                    border-top: 2px solid #333333;
+                }
+                tr:last-child {
+                    border-bottom: 2px solid #333333;
+                }
+                tr:nth-child(even) {
+                    background-color: #dddddd;
+                }
+                .numberCell {
+                    text-align: right;
+                }
+            </style>
+        </head>
+        <body>";
+        f.write_all(s.as_bytes())?;
+        s = "<h1>Flightline Alignment Report</h1>";
+        f.write_all(s.as_bytes())?;
+        let path = Path::new(&input_file);
+        f.write_all(
+            format!(
+                "<p><strong>Input Data:</strong> {}<br><strong>Number of flight lines:</strong> {}<br><strong>Reference flight line (point source ID):</strong> {}<br><strong>Search radius:</strong> {}<br><strong>Maximum plane RMSE:</strong> {}<br><strong>Rotations estimated:</strong> {}<br><strong>Iterations:</strong> {}</p>",
+                path.file_name().unwrap().to_str().unwrap(),
+                num_strips,
+                strips[reference].id,
+                search_radius,
+                max_plane_rmse,
+                estimate_rotations,
+                num_iterations
+            )
+            .as_bytes(),
+        )?;
+
+        s = "<br><table><caption>Flight Line Adjustments</caption><tr><th>Point Source ID</th><th>Points</th><th>dx</th><th>dy</th><th>dz</th>";
+        f.write_all(s.as_bytes())?;
+        if estimate_rotations {
+            f.write_all(
+                "<th>Roll (&deg;)</th><th>Pitch (&deg;)</th><th>Yaw (&deg;)</th>".as_bytes(),
+            )?;
+        }
+        f.write_all("</tr>".as_bytes())?;
+        for st in 0..num_strips {
+            let t = transforms[st];
+            let mut row = format!(
+                "<tr><td class=\"numberCell\">{}{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{:.4}</td><td class=\"numberCell\">{:.4}</td><td class=\"numberCell\">{:.4}</td>",
+                strips[st].id,
+                if st == reference { " (reference)" } else { "" },
+                strips[st].points.len(),
+                t[0],
+                t[1],
+                t[2]
+            );
+            if estimate_rotations {
+                row.push_str(&format!(
+                    "<td class=\"numberCell\">{:.5}</td><td class=\"numberCell\">{:.5}</td><td class=\"numberCell\">{:.5}</td>",
+                    t[3].to_degrees(),
+                    t[4].to_degrees(),
+                    t[5].to_degrees()
+                ));
+            }
+            row.push_str("</tr>");
+            f.write_all(row.as_bytes())?;
+        }
+        f.write_all("</table>".as_bytes())?;
+
+        s = "<br><table><caption>Point-to-Plane Distances in Overlap Areas</caption><tr><th rowspan=\"2\">Flight Lines</th><th colspan=\"4\">Before Adjustment</th><th colspan=\"4\">After Adjustment</th></tr><tr><th>N</th><th>Mean</th><th>Std. Dev.</th><th>RMSE</th><th>N</th><th>Mean</th><th>Std. Dev.</th><th>RMSE</th></tr>";
+        f.write_all(s.as_bytes())?;
+        for k in 0..pairs.len() {
+            if before[k].n == 0 && after[k].n == 0 {
+                continue;
+            }
+            let (a, b, _) = pairs[k];
+            let row = format!(
+                "<tr><td>{} - {}</td>{}{}</tr>",
+                strips[a].id,
+                strips[b].id,
+                before[k].to_html(),
+                after[k].to_html()
+            );
+            f.write_all(row.as_bytes())?;
+            if verbose {
+                println!(
+                    "Flight lines {} - {}: RMSE before = {:.4}, after = {:.4}",
+                    strips[a].id,
+                    strips[b].id,
+                    before[k].rmse(),
+                    after[k].rmse()
+                );
+            }
+        }
+        f.write_all("</table>".as_bytes())?;
+        s = "<p>Notes:<br>1. Distances are measured from the points of the first flight line in each pair to local planes fitted to the points of the second flight line, along the plane normal.<br>2. Outlying correspondences are excluded from the adjustment but are included in the statistics.</p></body>";
+        f.write_all(s.as_bytes())?;
+        let _ = f.flush();
+
+        // Apply the adjustments to the points.
+        if !output_las_file.is_empty() {
+            if verbose {
+                println!("Applying adjustments...");
+            }
+            let mut output = LasFile::initialize_using_file(&output_las_file, &input);
+            for i in 0..n_points {
+                let pr = input.get_record(i);
+                let pr2: LidarPointRecord;
+                match pr {
+                    LidarPointRecord::PointRecord0 { mut point_data } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord0 {
+                            point_data: point_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord1 {
+                        mut point_data,
+                        gps_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord1 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord2 {
+                        mut point_data,
+                        colour_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord2 {
+                            point_data: point_data,
+                            colour_data: colour_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord3 {
+                        mut point_data,
+                        gps_data,
+                        colour_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord3 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            colour_data: colour_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord4 {
+                        mut point_data,
+                        gps_data,
+                        wave_packet,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord4 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            wave_packet: wave_packet,
+                        };
+                    }
+                    LidarPointRecord::PointRecord5 {
+                        mut point_data,
+                        gps_data,
+                        colour_data,
+                        wave_packet,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord5 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            colour_data: colour_data,
+                            wave_packet: wave_packet,
+                        };
+                    }
+                    LidarPointRecord::PointRecord6 {
+                        mut point_data,
+                        gps_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord6 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord7 {
+                        mut point_data,
+                        gps_data,
+                        colour_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord7 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            colour_data: colour_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord8 {
+                        mut point_data,
+                        gps_data,
+                        colour_data,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord8 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            colour_data: colour_data,
+                        };
+                    }
+                    LidarPointRecord::PointRecord9 {
+                        mut point_data,
+                        gps_data,
+                        wave_packet,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord9 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            wave_packet: wave_packet,
+                        };
+                    }
+                    LidarPointRecord::PointRecord10 {
+                        mut point_data,
+                        gps_data,
+                        colour_data,
+                        wave_packet,
+                    } => {
+                        adjust_point(&mut point_data, &strip_map, &strips, &transforms);
+                        pr2 = LidarPointRecord::PointRecord10 {
+                            point_data: point_data,
+                            gps_data: gps_data,
+                            colour_data: colour_data,
+                            wave_packet: wave_packet,
+                        };
+                    }
+                }
+                output.add_point_record(pr2);
+            }
+
+            if verbose {
+                println!("Writing output LAS file...");
+            }
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Complete!")
+                    }
+                }
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            if cfg!(target_os = "macos") || cfg!(target_os = "ios") {
+                let output = Command::new("open")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            } else if cfg!(target_os = "windows") {
+                let output = Command::new("explorer.exe")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            } else if cfg!(target_os = "linux") {
+                let output = Command::new("xdg-open")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            }
+
+            println!("Please see {} for output.", output_file);
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The maximum number of points sampled from each overlapping pair of flight lines.
+const MAX_SAMPLES_PER_PAIR: usize = 25_000;
+
+/// The minimum number of neighbouring points needed to fit a local plane.
+const MIN_PLANE_POINTS: usize = 6;
+
+struct Strip {
+    id: u16,
+    points: Vec<usize>,
+    extent: BoundingBox,
+    centroid: [f64; 3],
+    frs: FixedRadiusSearch2D<usize>,
+}
+
+impl Strip {
+    fn new(id: u16, search_radius: f64) -> Strip {
+        Strip {
+            id: id,
+            points: vec![],
+            extent: BoundingBox::new(
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            centroid: [0f64; 3],
+            frs: FixedRadiusSearch2D::new(search_radius, DistanceMetric::SquaredEuclidean),
+        }
+    }
+
+    fn add_point(&mut self, x: f64, y: f64, z: f64, index: usize) {
+        self.points.push(index);
+        self.frs.insert(x, y, index);
+        self.extent.min_x = self.extent.min_x.min(x);
+        self.extent.max_x = self.extent.max_x.max(x);
+        self.extent.min_y = self.extent.min_y.min(y);
+        self.extent.max_y = self.extent.max_y.max(y);
+        self.centroid[0] += x;
+        self.centroid[1] += y;
+        self.centroid[2] += z;
+    }
+
+    fn finalize(&mut self) {
+        let n = self.points.len().max(1) as f64;
+        for j in 0..3 {
+            self.centroid[j] /= n;
+        }
+    }
+
+    // Applies an adjustment (dx, dy, dz, and small rotations about the x, y and z axes through
+    // the strip centroid) to a point.
+    fn apply(&self, t: &[f64; 6], p: [f64; 3]) -> [f64; 3] {
+        let d = [
+            p[0] - self.centroid[0],
+            p[1] - self.centroid[1],
+            p[2] - self.centroid[2],
+        ];
+        let w = cross(&[t[3], t[4], t[5]], &d);
+        [p[0] + t[0] + w[0], p[1] + t[1] + w[1], p[2] + t[2] + w[2]]
+    }
+
+    // The approximate inverse of apply, which is accurate for the small rotations involved.
+    fn invert(&self, t: &[f64; 6], p: [f64; 3]) -> [f64; 3] {
+        let q = [p[0] - t[0], p[1] - t[1], p[2] - t[2]];
+        let d = [
+            q[0] - self.centroid[0],
+            q[1] - self.centroid[1],
+            q[2] - self.centroid[2],
+        ];
+        let w = cross(&[t[3], t[4], t[5]], &d);
+        [q[0] - w[0], q[1] - w[1], q[2] - w[2]]
+    }
+}
+
+/// A point of one strip matched to a local plane of another. The point and plane are in the
+/// original (unadjusted) coordinates of their respective strips.
+struct Correspondence {
+    pair: usize,
+    strip_a: usize,
+    strip_b: usize,
+    point: [f64; 3],
+    plane_point: [f64; 3],
+    normal: [f64; 3],
+}
+
+impl Correspondence {
+    fn residual(&self, transforms: &[[f64; 6]], strips: &[Strip]) -> f64 {
+        let p = strips[self.strip_a].apply(&transforms[self.strip_a], self.point);
+        let q = strips[self.strip_b].apply(&transforms[self.strip_b], self.plane_point);
+        dot(&self.normal, &[p[0] - q[0], p[1] - q[1], p[2] - q[2]])
+    }
+
+    // The residual is linear in the adjustment parameters:
+    // r = n.(p - q) + n.t_a + w_a.(d_a x n) - n.t_b - w_b.(d_b x n)
+    fn linearize(&self, strips: &[Strip]) -> ([f64; 6], [f64; 6], f64) {
+        let n = self.normal;
+        let ca = strips[self.strip_a].centroid;
+        let cb = strips[self.strip_b].centroid;
+        let da = [
+            self.point[0] - ca[0],
+            self.point[1] - ca[1],
+            self.point[2] - ca[2],
+        ];
+        let db = [
+            self.plane_point[0] - cb[0],
+            self.plane_point[1] - cb[1],
+            self.plane_point[2] - cb[2],
+        ];
+        let ra = cross(&da, &n);
+        let rb = cross(&db, &n);
+        let constant = dot(
+            &n,
+            &[
+                self.point[0] - self.plane_point[0],
+                self.point[1] - self.plane_point[1],
+                self.point[2] - self.plane_point[2],
+            ],
+        );
+        (
+            [n[0], n[1], n[2], ra[0], ra[1], ra[2]],
+            [-n[0], -n[1], -n[2], -rb[0], -rb[1], -rb[2]],
+            constant,
+        )
+    }
+}
+
+// Assembles and solves the linearized least-squares system for the adjustment of each strip,
+// holding the reference strip fixed. Only the first `num_params` adjustment parameters (the
+// translations, and optionally the rotations) are estimated.
+fn solve_adjustment(
+    correspondences: &[&Correspondence],
+    strips: &[Strip],
+    reference: usize,
+    num_params: usize,
+) -> Result<Vec<[f64; 6]>, Error> {
+    let num_strips = strips.len();
+    let param_index = |s: usize| -> Option<usize> {
+        if s == reference {
+            None
+        } else if s < reference {
+            Some(s * num_params)
+        } else {
+            Some((s - 1) * num_params)
+        }
+    };
+    let n = (num_strips - 1) * num_params;
+    let mut ata = DMatrix::<f64>::zeros(n, n);
+    let mut atb = DVector::<f64>::zeros(n);
+    for c in correspondences {
+        let (coeffs_a, coeffs_b, constant) = c.linearize(strips);
+        let mut row: Vec<(usize, f64)> = Vec::with_capacity(2 * num_params);
+        if let Some(k) = param_index(c.strip_a) {
+            for j in 0..num_params {
+                row.push((k + j, coeffs_a[j]));
+            }
+        }
+        if let Some(k) = param_index(c.strip_b) {
+            for j in 0..num_params {
+                row.push((k + j, coeffs_b[j]));
+            }
+        }
+        for &(j1, v1) in &row {
+            atb[j1] -= v1 * constant;
+            for &(j2, v2) in &row {
+                ata[(j1, j2)] += v1 * v2;
+            }
+        }
+    }
+    let svd = ata.svd(true, true);
+    let max_sv = svd.singular_values.iter().fold(0f64, |m, v| m.max(*v));
+    let solution = match svd.solve(&atb, max_sv * 1e-6) {
+        Ok(x) => x,
+        Err(e) => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("Error solving the adjustment: {}", e),
+            ))
+        }
+    };
+    let mut transforms = vec![[0f64; 6]; num_strips];
+    for s in 0..num_strips {
+        if let Some(k) = param_index(s) {
+            for j in 0..num_params {
+                transforms[s][j] = solution[k + j];
+            }
+        }
+    }
+    Ok(transforms)
+}
+
+// Matches the sampled points of each overlapping pair to local planes under the current adjustments.
+fn find_correspondences(
+    points: &Arc<Vec<[f64; 3]>>,
+    strips: &Arc<Vec<Strip>>,
+    pairs: &Arc<Vec<(usize, usize, Vec<usize>)>>,
+    transforms: &[[f64; 6]],
+    max_plane_rmse: f64,
+    search_radius: f64,
+) -> Vec<Correspondence> {
+    let transforms = Arc::new(transforms.to_vec());
+    let num_procs = num_cpus::get();
+    let (tx, rx) = mpsc::channel();
+    for tid in 0..num_procs {
+        let points = points.clone();
+        let strips = strips.clone();
+        let pairs = pairs.clone();
+        let transforms = transforms.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut ret = vec![];
+            let mut k = 0usize;
+            for pair in 0..pairs.len() {
+                let (a, b, ref samples) = pairs[pair];
+                for &i in samples {
+                    k += 1;
+                    if k % num_procs != tid {
+                        continue;
+                    }
+                    // the point, in the original coordinates of strip b
+                    let p = strips[b]
+                        .invert(&transforms[b], strips[a].apply(&transforms[a], points[i]));
+                    let neighbours = strips[b].frs.search(p[0], p[1]);
+                    if neighbours.len() < MIN_PLANE_POINTS {
+                        continue;
+                    }
+                    let nbr_points: Vec<[f64; 3]> =
+                        neighbours.iter().map(|&(j, _)| points[j]).collect();
+                    if let Some((centroid, normal, rmse)) = fit_plane(&nbr_points) {
+                        if rmse > max_plane_rmse {
+                            continue;
+                        }
+                        let dist = dot(
+                            &normal,
+                            &[p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]],
+                        );
+                        if dist.abs() > search_radius {
+                            continue;
+                        }
+                        ret.push(Correspondence {
+                            pair: pair,
+                            strip_a: a,
+                            strip_b: b,
+                            point: points[i],
+                            plane_point: centroid,
+                            normal: normal,
+                        });
+                    }
+                }
+            }
+            tx.send(ret).unwrap();
+        });
+    }
+
+    let mut correspondences = vec![];
+    for _ in 0..num_procs {
+        correspondences.extend(rx.recv().expect("Error receiving data from thread."));
+    }
+    correspondences
+}
+
+// Fits a plane to a set of points, returning the centroid, the unit normal (oriented upwards)
+// and the root-mean-square distance of the points to the plane.
+fn fit_plane(points: &[[f64; 3]]) -> Option<([f64; 3], [f64; 3], f64)> {
+    let n = points.len() as f64;
+    let mut c = [0f64; 3];
+    for p in points {
+        for j in 0..3 {
+            c[j] += p[j];
+        }
+    }
+    for j in 0..3 {
+        c[j] /= n;
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        for r in 0..3 {
+            for k in 0..3 {
+                cov[(r, k)] += d[r] * d[k];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut m = 0;
+    for j in 1..3 {
+        if eig.eigenvalues[j] < eig.eigenvalues[m] {
+            m = j;
+        }
+    }
+    let v = eig.eigenvectors.column(m);
+    let mut normal = [v[0], v[1], v[2]];
+    let len = dot(&normal, &normal).sqrt();
+    if len == 0f64 || !len.is_finite() {
+        return None;
+    }
+    let sign = if normal[2] < 0f64 { -1f64 } else { 1f64 };
+    for j in 0..3 {
+        normal[j] *= sign / len;
+    }
+    Some((c, normal, (eig.eigenvalues[m].max(0f64) / n).sqrt()))
+}
+
+#[derive(Default, Clone, Copy)]
+struct ResidualStats {
+    n: usize,
+    sum: f64,
+    sum_sqr: f64,
+}
+
+impl ResidualStats {
+    fn mean(&self) -> f64 {
+        self.sum / self.n as f64
+    }
+
+    fn stdev(&self) -> f64 {
+        (self.sum_sqr / self.n as f64 - self.mean() * self.mean())
+            .max(0f64)
+            .sqrt()
+    }
+
+    fn rmse(&self) -> f64 {
+        (self.sum_sqr / self.n as f64).sqrt()
+    }
+
+    fn to_html(&self) -> String {
+        if self.n == 0 {
+            return "<td class=\"numberCell\">0</td><td></td><td></td><td></td>".to_string();
+        }
+        format!(
+            "<td class=\"numberCell\">{}</td><td class=\"numberCell\">{:.4}</td><td class=\"numberCell\">{:.4}</td><td class=\"numberCell\">{:.4}</td>",
+            self.n,
+            self.mean(),
+            self.stdev(),
+            self.rmse()
+        )
+    }
+}
+
+fn pair_statistics(
+    correspondences: &[Correspondence],
+    transforms: &[[f64; 6]],
+    strips: &[Strip],
+    num_pairs: usize,
+) -> Vec<ResidualStats> {
+    let mut stats = vec![ResidualStats::default(); num_pairs];
+    for c in correspondences {
+        let r = c.residual(transforms, strips);
+        stats[c.pair].n += 1;
+        stats[c.pair].sum += r;
+        stats[c.pair].sum_sqr += r * r;
+    }
+    stats
+}
+
+fn adjust_point(
+    point_data: &mut PointData,
+    strip_map: &HashMap<u16, usize>,
+    strips: &[Strip],
+    transforms: &[[f64; 6]],
+) {
+    if let Some(&s) = strip_map.get(&point_data.point_source_id) {
+        let p = strips[s].apply(&transforms[s], [point_data.x, point_data.y, point_data.z]);
+        point_data.x = p[0];
+        point_data.y = p[1];
+        point_data.z = p[2];
+    }
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve_adjustment, Correspondence, Strip};
+
+    #[test]
+    fn test_solve_adjustment_offset() {
+        // strip 1 is offset from the reference strip 0 by a constant shift; its points are
+        // matched to planes of various orientations in strip 0.
+        let offset = [0.3, -0.2, 0.5];
+        let normals: [[f64; 3]; 4] = [
+            [0.0, 0.0, 1.0],
+            [0.5, 0.0, 1.0],
+            [0.0, 0.5, 1.0],
+            [-0.5, 0.3, 1.0],
+        ];
+        let mut strips = vec![Strip::new(1, 1.0), Strip::new(2, 1.0)];
+        strips[0].centroid = [50.0, 50.0, 0.0];
+        strips[1].centroid = [50.0, 50.0, 0.0];
+        let mut correspondences = vec![];
+        for i in 0..100 {
+            let q = [
+                (i % 10) as f64 * 10.0,
+                (i / 10) as f64 * 10.0,
+                (i % 7) as f64,
+            ];
+            let n = normals[i % normals.len()];
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            correspondences.push(Correspondence {
+                pair: 0,
+                strip_a: 1,
+                strip_b: 0,
+                point: [q[0] + offset[0], q[1] + offset[1], q[2] + offset[2]],
+                plane_point: q,
+                normal: [n[0] / len, n[1] / len, n[2] / len],
+            });
+        }
+        let used: Vec<&Correspondence> = correspondences.iter().collect();
+        for &num_params in &[3, 6] {
+            let transforms = solve_adjustment(&used, &strips, 0, num_params).unwrap();
+            assert_eq!(transforms[0], [0f64; 6]);
+            for j in 0..3 {
+                assert!((transforms[1][j] + offset[j]).abs() < 1e-6);
+            }
+            for j in 3..6 {
+                assert!(transforms[1][j].abs() < 1e-6);
+            }
+            for c in &correspondences {
+                assert!(c.residual(&transforms, &strips).abs() < 1e-6);
+            }
+        }
+    }
+}
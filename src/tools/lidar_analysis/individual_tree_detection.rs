@@ -0,0 +1,625 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool identifies the locations of individual tree tops, either from a canopy height model (CHM) raster
+/// or directly from a height-normalized LiDAR point cloud (`--input`). The input type is determined by the
+/// file extension; LAS files (*.las, *.zip) are processed as point clouds and all other files are treated as
+/// rasters. In both cases, the input heights must be normalized to the ground surface, e.g. using the
+/// `HeightAboveGround` tool for point clouds.
+///
+/// Tree tops are identified as local maxima within a variable-sized circular search window, the radius of which
+/// increases linearly with the height of the candidate point (Popescu and Wynne, 2004). Candidates lower than
+/// the minimum height (`--min_height`) use the minimum search radius (`--min_search_radius`), those higher than
+/// the maximum height (`--max_height`) use the maximum search radius (`--max_search_radius`), and the radius is
+/// linearly interpolated for intermediate heights. Candidates that are lower than `--min_height` are never
+/// considered to be tree tops. If the maximum search radius is unspecified, a fixed-sized window is used, and if
+/// the maximum height is unspecified, it is set to the maximum height in the input data.
+///
+/// When a point cloud is used as the input, the `--only_use_veg` flag can be used to restrict the candidate
+/// points to those classified as low, medium or high vegetation (classes 3, 4 and 5). Withheld and noise points
+/// are always excluded.
+///
+/// The output (`--output`) is a vector point file with a `HEIGHT` attribute containing the height of each
+/// tree top. This output can be used with the `TreeCrownSegmentation` tool to delineate tree crowns.
+///
+/// # Reference
+/// Popescu, S. C., and Wynne, R. H. (2004). Seeing the trees in the forest. *Photogrammetric Engineering &
+/// Remote Sensing*, 70(5), 589-604.
+///
+/// # See Also
+/// `TreeCrownSegmentation`, `HeightAboveGround`, `LidarTophatTransform`
+pub struct IndividualTreeDetection {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl IndividualTreeDetection {
+    pub fn new() -> IndividualTreeDetection {
+        // public constructor
+        let name = "IndividualTreeDetection".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Identifies tree tops in a canopy height model or height-normalized LiDAR point cloud using a variable-window local maximum filter.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file or canopy height model raster."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector points file of tree tops.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Search Radius".to_owned(),
+            flags: vec!["--min_search_radius".to_owned()],
+            description: "Minimum search radius (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description: "Minimum height (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Search Radius".to_owned(),
+            flags: vec!["--max_search_radius".to_owned()],
+            description: "Maximum search radius (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Height".to_owned(),
+            flags: vec!["--max_height".to_owned()],
+            description: "Maximum height (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Only use veg. class points?".to_owned(),
+            flags: vec!["--only_use_veg".to_owned()],
+            description: "Only use LiDAR points classified as vegetation (classes 3-5).".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=chm.tif -o=tops.shp --min_search_radius=1.5 --min_height=2.0 --max_search_radius=8.0 --max_height=30.0", short_exe, name).replace("*", &sep);
+
+        IndividualTreeDetection {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for IndividualTreeDetection {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut min_search_radius = 1f64;
+        let mut min_height = 2f64;
+        let mut max_search_radius = f64::NEG_INFINITY;
+        let mut max_height = f64::NEG_INFINITY;
+        let mut only_use_veg = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-min_search_radius" {
+                min_search_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-min_height" {
+                min_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_search_radius" {
+                max_search_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_height" {
+                max_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-only_use_veg" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    only_use_veg = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if min_search_radius <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum search radius must be greater than zero.",
+            ));
+        }
+        if max_search_radius < min_search_radius {
+            max_search_radius = min_search_radius;
+        }
+
+        let start = Instant::now();
+
+        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "HEIGHT",
+            FieldDataType::Real,
+            10u8,
+            3u8,
+        ));
+
+        let lower = input_file.to_lowercase();
+        let tops: Vec<(f64, f64, f64)> = if lower.ends_with(".las") || lower.ends_with(".zip") {
+            if verbose {
+                println!("Reading input LAS file...");
+            }
+            let mut input = LasFile::new(&input_file, "r")?;
+            output.projection = input.get_wkt();
+
+            let n_points = input.header.number_of_points as usize;
+            let mut candidates = vec![];
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if !p.withheld() && !p.is_classified_noise() && p.z >= min_height {
+                    let class = p.classification();
+                    if !only_use_veg || (class >= 3 && class <= 5) {
+                        candidates.push((p.x, p.y, p.z));
+                    }
+                }
+            }
+            drop(input);
+
+            if max_height == f64::NEG_INFINITY {
+                max_height = candidates.iter().fold(min_height, |m, p| m.max(p.2));
+            }
+
+            let mut frs = FixedRadiusSearch2D::new(max_search_radius, DistanceMetric::Euclidean);
+            for i in 0..candidates.len() {
+                frs.insert(candidates[i].0, candidates[i].1, i);
+            }
+
+            let num_candidates = candidates.len();
+            let candidates = Arc::new(candidates);
+            let frs = Arc::new(frs);
+            let num_procs = num_cpus::get();
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let candidates = candidates.clone();
+                let frs = frs.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in (0..num_candidates).filter(|i| i % num_procs == tid) {
+                        let (x, y, z) = candidates[i];
+                        let radius = search_radius(
+                            z,
+                            min_height,
+                            max_height,
+                            min_search_radius,
+                            max_search_radius,
+                        );
+                        let mut is_top = true;
+                        for (j, dist) in frs.search(x, y) {
+                            if j != i && dist <= radius {
+                                let zn = candidates[j].2;
+                                if zn > z || (zn == z && j < i) {
+                                    is_top = false;
+                                    break;
+                                }
+                            }
+                        }
+                        tx.send(if is_top { Some(i) } else { None }).unwrap();
+                    }
+                });
+            }
+
+            let mut tops = vec![];
+            let mut progress: i32;
+            let mut old_progress: i32 = -1;
+            for n in 0..num_candidates {
+                if let Some(i) = rx.recv().expect("Error receiving data from thread.") {
+                    tops.push(i);
+                }
+                if verbose {
+                    progress = (100.0_f64 * n as f64 / num_candidates as f64) as i32;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+            tops.sort();
+            tops.iter().map(|&i| candidates[i]).collect()
+        } else {
+            if verbose {
+                println!("Reading input raster...");
+            }
+            let input = Arc::new(Raster::new(&input_file, "r")?);
+            output.projection = input.configs.coordinate_ref_system_wkt.clone();
+            let rows = input.configs.rows as isize;
+            let columns = input.configs.columns as isize;
+            let nodata = input.configs.nodata;
+            let res_x = input.configs.resolution_x;
+            let res_y = input.configs.resolution_y;
+
+            if max_height == f64::NEG_INFINITY {
+                max_height = input.configs.maximum.max(min_height);
+            }
+
+            let num_procs = num_cpus::get() as isize;
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut row_tops = vec![];
+                        for col in 0..columns {
+                            let z = input.get_value(row, col);
+                            if z == nodata || z < min_height {
+                                continue;
+                            }
+                            let radius = search_radius(
+                                z,
+                                min_height,
+                                max_height,
+                                min_search_radius,
+                                max_search_radius,
+                            );
+                            let dr = (radius / res_y).floor() as isize;
+                            let dc = (radius / res_x).floor() as isize;
+                            let mut is_top = true;
+                            'window: for r in -dr..=dr {
+                                for c in -dc..=dc {
+                                    if r == 0 && c == 0 {
+                                        continue;
+                                    }
+                                    let (dx, dy) = (c as f64 * res_x, r as f64 * res_y);
+                                    if dx * dx + dy * dy > radius * radius {
+                                        continue;
+                                    }
+                                    let zn = input.get_value(row + r, col + c);
+                                    if zn != nodata
+                                        && (zn > z || (zn == z && (r < 0 || (r == 0 && c < 0))))
+                                    {
+                                        is_top = false;
+                                        break 'window;
+                                    }
+                                }
+                            }
+                            if is_top {
+                                row_tops.push((
+                                    input.get_x_from_column(col),
+                                    input.get_y_from_row(row),
+                                    z,
+                                ));
+                            }
+                        }
+                        tx.send((row, row_tops)).unwrap();
+                    }
+                });
+            }
+
+            let mut row_data = vec![vec![]; rows as usize];
+            let mut progress: i32;
+            let mut old_progress: i32 = -1;
+            for r in 0..rows {
+                let (row, data) = rx.recv().expect("Error receiving data from thread.");
+                row_data[row as usize] = data;
+                if verbose {
+                    progress = (100.0_f64 * r as f64 / (rows - 1).max(1) as f64) as i32;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+            row_data.into_iter().flatten().collect()
+        };
+
+        for (fid, top) in tops.iter().enumerate() {
+            output.add_point_record(top.0, top.1);
+            output.attributes.add_record(
+                vec![FieldData::Int(fid as i32 + 1), FieldData::Real(top.2)],
+                false,
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of tree tops found: {}", tops.len());
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the search window radius for a candidate tree top of height `z`, linearly
+/// interpolated between the minimum and maximum radii.
+fn search_radius(
+    z: f64,
+    min_height: f64,
+    max_height: f64,
+    min_radius: f64,
+    max_radius: f64,
+) -> f64 {
+    if z <= min_height || max_height <= min_height {
+        return min_radius;
+    }
+    if z >= max_height {
+        return max_radius;
+    }
+    min_radius + (max_radius - min_radius) * (z - min_height) / (max_height - min_height)
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndividualTreeDetection;
+    use crate::raster::*;
+    use crate::tools::WhiteboxTool;
+    use crate::vector::{FieldData, Point2D, Shapefile};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_individual_tree_detection_chm() {
+        let dir = env::temp_dir().join("wbt_individual_tree_detection");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+        // a 1 m CHM of two conical trees, 20 m and 15 m high, on bare ground
+        let configs = RasterConfigs {
+            rows: 30,
+            columns: 30,
+            north: 30.0,
+            south: 0.0,
+            east: 30.0,
+            west: 0.0,
+            resolution_x: 1.0,
+            resolution_y: 1.0,
+            data_type: DataType::F32,
+            photometric_interp: PhotometricInterpretation::Continuous,
+            ..Default::default()
+        };
+        let mut chm = Raster::initialize_using_config(&path("chm.tif"), &configs);
+        for row in 0..30isize {
+            for col in 0..30isize {
+                let cone = |r: isize, c: isize, height: f64| {
+                    height - 2.0 * (((row - r).pow(2) + (col - c).pow(2)) as f64).sqrt()
+                };
+                chm.set_value(row, col, cone(8, 8, 20.0).max(cone(20, 20, 15.0)).max(0.0));
+            }
+        }
+        chm.write().unwrap();
+
+        let run = |max_search_radius: f64| {
+            IndividualTreeDetection::new()
+                .run(
+                    vec![
+                        format!("--input={}", path("chm.tif")),
+                        format!("--output={}", path("tops.shp")),
+                        "--min_search_radius=1.0".to_string(),
+                        format!("--max_search_radius={}", max_search_radius),
+                        "--min_height=2.0".to_string(),
+                    ],
+                    "",
+                    false,
+                )
+                .unwrap();
+            Shapefile::read(&path("tops.shp")).unwrap()
+        };
+
+        // only the cone apexes are local maxima, in row order
+        for max_search_radius in &[1.0, 5.0] {
+            let tops = run(*max_search_radius);
+            assert_eq!(tops.num_records, 2);
+            assert_eq!(tops.get_record(0).points[0], Point2D::new(8.5, 21.5));
+            assert_eq!(tops.get_record(1).points[0], Point2D::new(20.5, 9.5));
+            assert_eq!(
+                tops.attributes.get_value(0, "HEIGHT"),
+                FieldData::Real(20.0)
+            );
+            assert_eq!(
+                tops.attributes.get_value(1, "HEIGHT"),
+                FieldData::Real(15.0)
+            );
+        }
+
+        // The window radius of the lower tree, interpolated between 1 m at 2 m high and 25 m at
+        // the maximum height of 20 m, is 18.3 m, which reaches the top of the taller tree 17 m away.
+        let tops = run(25.0);
+        assert_eq!(tops.num_records, 1);
+        assert_eq!(tops.get_record(0).points[0], Point2D::new(8.5, 21.5));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1362 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{alpha_shape, is_clockwise_order, polygon_area, polygon_perimeter};
+use crate::lidar::*;
+use crate::na::Matrix3;
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool extracts building footprints, and optionally roof planes, from the building points
+/// (class 6) of a classified LiDAR point cloud (`--input`), such as one classified using the
+/// `ClassifyBuildingsInLidar` tool. The outputs are suitable for creating LoD1 (footprints extruded
+/// to a roof height) and LoD2-style (planar roof faces) building models.
+///
+/// Building points are first grouped into individual buildings by connecting points that are within
+/// the search radius (`--search_radius`) of one another. The outline of each building is then found
+/// as the alpha shape of its points, i.e. the union of the Delaunay triangles with a circumradius no
+/// larger than the alpha radius (`--alpha`). The alpha radius should be larger than the typical point
+/// spacing; smaller values produce more detailed, concave outlines, while larger values approach the
+/// convex hull of the points and will close in courtyards and other concavities.
+///
+/// Outlines are regularized to the dominant orientation of each building. Each outline is first
+/// simplified using the Douglas-Peucker algorithm with the regularization tolerance (`--tolerance`),
+/// and the dominant orientation is estimated as the length-weighted mean of the outline edge directions,
+/// modulo 90 degrees. Edges that are within the angular tolerance (`--angle_tolerance`) of the dominant
+/// orientation, or its perpendicular, are rotated to that direction, adjacent parallel edges that are
+/// offset by less than the tolerance are merged, and the footprint vertices are re-created at the
+/// intersections of the adjusted edges. Edges that do not align with the dominant directions (e.g. the
+/// walls of curved or angled buildings) are retained. Buildings with a footprint area smaller than the
+/// minimum area (`--min_area`) are ignored, as are interior courtyards smaller than this area.
+///
+/// The output footprint polygons (`--output`) have attributes for the building identifier (`BLDG_ID`),
+/// footprint area (`AREA`) and perimeter (`PERIMETER`), the azimuth of the dominant wall direction
+/// (`ORIENT`, 0-90 degrees), the ground elevation (`BASE_Z`), the median (`ROOF_Z`) and maximum
+/// (`MAX_Z`) roof elevations, the building height (`HEIGHT`, i.e. `ROOF_Z - BASE_Z`), and the number
+/// of building points (`NUM_PTS`). The ground elevation is the median elevation of the ground points
+/// (class 2) within twice the search radius of the footprint boundary; where there are no nearby ground
+/// points, the lowest building point elevation is used instead.
+///
+/// When an output roof planes file (`--out_roofs`) is specified, the points of each building are also
+/// segmented into planar roof faces using region growing. A plane is fitted to the neighbourhood of each
+/// point, and regions are grown from the most planar points into neighbouring points with normals within
+/// the plane angle threshold (`--plane_angle`) of the region's normal and that are within the plane
+/// distance threshold (`--plane_threshold`) of the region's plane. Regions containing fewer than the
+/// minimum number of points (`--min_plane_points`) are discarded. The outline of each roof plane is
+/// found and regularized in the same manner as the footprints. The output roof plane polygons have
+/// attributes for the building and plane identifiers (`BLDG_ID`, `PLANE_ID`), roof slope (`SLOPE`,
+/// degrees) and aspect (`ASPECT`, degrees clockwise from north, -1 for flat planes), planimetric
+/// (`AREA`) and sloping (`AREA_3D`) area, the mean plane elevation (`MEAN_Z`), the root-mean-square
+/// distance of the points to the plane (`RMSE`), and the number of points (`NUM_PTS`).
+///
+/// # See Also
+/// `ClassifyBuildingsInLidar`, `LidarRansacPlanes`, `LidarSegmentation`, `TreeCrownSegmentation`
+pub struct LidarBuildingFootprints {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarBuildingFootprints {
+    pub fn new() -> LidarBuildingFootprints {
+        // public constructor
+        let name = "LidarBuildingFootprints".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Extracts regularized building footprints and roof planes from classified LiDAR building points.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Footprints File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file of building footprints.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Roof Planes File (optional)".to_owned(),
+            flags: vec!["--out_roofs".to_owned()],
+            description: "Output vector polygon file of roof planes.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--search_radius".to_owned()],
+            description: "Search radius (m) used to group building points and fit local planes."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Alpha Radius".to_owned(),
+            flags: vec!["--alpha".to_owned()],
+            description: "Alpha-shape radius (m) used to create building outlines.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Building Area".to_owned(),
+            flags: vec!["--min_area".to_owned()],
+            description: "Minimum footprint area (m2).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("20.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Regularization Tolerance".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Distance tolerance (m) used to simplify and regularize outlines."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Angular Tolerance".to_owned(),
+            flags: vec!["--angle_tolerance".to_owned()],
+            description:
+                "Maximum deviation (degrees) of edges that are aligned to the dominant orientation."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Plane Distance Threshold".to_owned(),
+            flags: vec!["--plane_threshold".to_owned()],
+            description: "Maximum distance (m) of roof plane points from the plane.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.2".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Plane Angle Threshold".to_owned(),
+            flags: vec!["--plane_angle".to_owned()],
+            description: "Maximum angle (degrees) between the normals of points in a roof plane."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Points per Plane".to_owned(),
+            flags: vec!["--min_plane_points".to_owned()],
+            description: "Minimum number of points in a roof plane.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("30".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=footprints.shp --out_roofs=roofs.shp --search_radius=2.0 --alpha=2.0 --min_area=20.0 --tolerance=1.0", short_exe, name).replace("*", &sep);
+
+        LidarBuildingFootprints {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarBuildingFootprints {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut roofs_file = String::new();
+        let mut search_radius = 2f64;
+        let mut alpha = 2f64;
+        let mut min_area = 20f64;
+        let mut tolerance = 1f64;
+        let mut angle_tolerance = 15f64;
+        let mut plane_threshold = 0.2f64;
+        let mut plane_angle = 10f64;
+        let mut min_plane_points = 30usize;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_roofs" {
+                roofs_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-search_radius" {
+                search_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-alpha" {
+                alpha = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-min_area" {
+                min_area = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-tolerance" {
+                tolerance = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-angle_tolerance" {
+                angle_tolerance = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-plane_threshold" {
+                plane_threshold = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-plane_angle" {
+                plane_angle = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-min_plane_points" {
+                min_plane_points = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !roofs_file.is_empty() && !roofs_file.contains(&sep) && !roofs_file.contains("/") {
+            roofs_file = format!("{}{}", working_directory, roofs_file);
+        }
+
+        if search_radius <= 0f64 || alpha <= 0f64 || tolerance <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius, alpha radius, and tolerance must be greater than zero.",
+            ));
+        }
+        if angle_tolerance < 0f64 || angle_tolerance >= 45f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The angular tolerance must be between 0 and 45 degrees.",
+            ));
+        }
+        if min_plane_points < 3 {
+            min_plane_points = 3;
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = LasFile::new(&input_file, "r")?;
+        let projection = input.get_wkt();
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let mut points: Vec<[f64; 3]> = vec![];
+        let mut ground_frs =
+            FixedRadiusSearch2D::new(2f64 * search_radius, DistanceMetric::Euclidean);
+        let mut num_ground = 0;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if p.withheld() {
+                continue;
+            }
+            match p.classification() {
+                6 => points.push([p.x, p.y, p.z]),
+                2 => {
+                    ground_frs.insert(p.x, p.y, p.z);
+                    num_ground += 1;
+                }
+                _ => {}
+            }
+        }
+        drop(input);
+        let num_points = points.len();
+        if num_points == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any building (class 6) points.",
+            ));
+        }
+        if num_ground == 0 && verbose {
+            println!("Warning: The input file does not contain any ground (class 2) points. Building base elevations will be estimated from the building points.");
+        }
+
+        // Group the building points into buildings.
+        let mut frs = FixedRadiusSearch2D::new(search_radius, DistanceMetric::Euclidean);
+        for i in 0..num_points {
+            frs.insert(points[i][0], points[i][1], i);
+        }
+        let mut buildings: Vec<Vec<usize>> = vec![];
+        let mut visited = vec![false; num_points];
+        let mut queue = VecDeque::new();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for i in 0..num_points {
+            if visited[i] {
+                continue;
+            }
+            let mut building = vec![];
+            visited[i] = true;
+            queue.push_back(i);
+            while let Some(j) = queue.pop_front() {
+                building.push(j);
+                for (k, _) in frs.search(points[j][0], points[j][1]) {
+                    if !visited[k] {
+                        visited[k] = true;
+                        queue.push_back(k);
+                    }
+                }
+            }
+            if building.len() >= 3 {
+                buildings.push(building);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (num_points - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Grouping building points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        drop(frs);
+
+        let num_buildings = buildings.len();
+        let points = Arc::new(points);
+        let buildings = Arc::new(buildings);
+        let ground_frs = Arc::new(ground_frs);
+        let params = Arc::new(Parameters {
+            search_radius: search_radius,
+            alpha: alpha,
+            min_area: min_area,
+            tolerance: tolerance,
+            angle_tolerance: angle_tolerance.to_radians(),
+            plane_threshold: plane_threshold,
+            plane_angle: plane_angle.to_radians(),
+            min_plane_points: min_plane_points,
+            model_roofs: !roofs_file.is_empty(),
+        });
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let points = points.clone();
+            let buildings = buildings.clone();
+            let ground_frs = ground_frs.clone();
+            let params = params.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for b in (0..num_buildings).filter(|b| b % num_procs == tid) {
+                    let bldg_points = buildings[b]
+                        .iter()
+                        .map(|i| points[*i])
+                        .collect::<Vec<[f64; 3]>>();
+                    let result = model_building(&bldg_points, &ground_frs, &params);
+                    tx.send((b, result)).unwrap();
+                }
+            });
+        }
+
+        let mut results: Vec<Option<Building>> = (0..num_buildings).map(|_| None).collect();
+        for b in 0..num_buildings {
+            let (i, result) = rx.recv().expect("Error receiving data from thread.");
+            results[i] = result;
+            if verbose {
+                progress = (100.0_f64 * b as f64 / (num_buildings - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Modelling buildings: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // output the footprints, and optionally the roof planes
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = projection.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "BLDG_ID",
+            FieldDataType::Int,
+            7u8,
+            0u8,
+        ));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 12u8, 3u8));
+        output.attributes.add_field(&AttributeField::new(
+            "PERIMETER",
+            FieldDataType::Real,
+            12u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "ORIENT",
+            FieldDataType::Real,
+            7u8,
+            2u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "BASE_Z",
+            FieldDataType::Real,
+            10u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "ROOF_Z",
+            FieldDataType::Real,
+            10u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "MAX_Z",
+            FieldDataType::Real,
+            10u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "HEIGHT",
+            FieldDataType::Real,
+            10u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "NUM_PTS",
+            FieldDataType::Int,
+            9u8,
+            0u8,
+        ));
+
+        let mut roofs = if !roofs_file.is_empty() {
+            let mut roofs = Shapefile::new(&roofs_file, ShapeType::Polygon)?;
+            roofs.projection = projection;
+            roofs.attributes.add_fields(&vec![
+                AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8),
+                AttributeField::new("BLDG_ID", FieldDataType::Int, 7u8, 0u8),
+                AttributeField::new("PLANE_ID", FieldDataType::Int, 5u8, 0u8),
+                AttributeField::new("SLOPE", FieldDataType::Real, 7u8, 2u8),
+                AttributeField::new("ASPECT", FieldDataType::Real, 7u8, 2u8),
+                AttributeField::new("AREA", FieldDataType::Real, 12u8, 3u8),
+                AttributeField::new("AREA_3D", FieldDataType::Real, 12u8, 3u8),
+                AttributeField::new("MEAN_Z", FieldDataType::Real, 10u8, 3u8),
+                AttributeField::new("RMSE", FieldDataType::Real, 8u8, 4u8),
+                AttributeField::new("NUM_PTS", FieldDataType::Int, 9u8, 0u8),
+            ]);
+            Some(roofs)
+        } else {
+            None
+        };
+
+        let mut bldg_id = 0i32;
+        let mut roof_fid = 1i32;
+        for result in results {
+            let building = match result {
+                Some(b) => b,
+                None => continue,
+            };
+            bldg_id += 1;
+            output.add_record(polygon_geometry(&building.rings));
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(bldg_id),
+                    FieldData::Int(bldg_id),
+                    FieldData::Real(building.area),
+                    FieldData::Real(building.perimeter),
+                    FieldData::Real(building.orientation),
+                    FieldData::Real(building.base_z),
+                    FieldData::Real(building.roof_z),
+                    FieldData::Real(building.max_z),
+                    FieldData::Real(building.roof_z - building.base_z),
+                    FieldData::Int(building.num_points as i32),
+                ],
+                false,
+            );
+            if let Some(ref mut roofs) = roofs {
+                for (plane_id, plane) in building.roof_planes.iter().enumerate() {
+                    roofs.add_record(polygon_geometry(&plane.rings));
+                    roofs.attributes.add_record(
+                        vec![
+                            FieldData::Int(roof_fid),
+                            FieldData::Int(bldg_id),
+                            FieldData::Int(plane_id as i32 + 1),
+                            FieldData::Real(plane.slope),
+                            FieldData::Real(plane.aspect),
+                            FieldData::Real(plane.area),
+                            FieldData::Real(plane.area / plane.slope.to_radians().cos()),
+                            FieldData::Real(plane.mean_z),
+                            FieldData::Real(plane.rmse),
+                            FieldData::Int(plane.num_points as i32),
+                        ],
+                        false,
+                    );
+                    roof_fid += 1;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of buildings: {}", bldg_id);
+            if roofs.is_some() {
+                println!("Number of roof planes: {}", roof_fid - 1);
+            }
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(mut roofs) = roofs {
+            let _ = match roofs.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output roof planes file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+struct Parameters {
+    search_radius: f64,
+    alpha: f64,
+    min_area: f64,
+    tolerance: f64,
+    angle_tolerance: f64,
+    plane_threshold: f64,
+    plane_angle: f64,
+    min_plane_points: usize,
+    model_roofs: bool,
+}
+
+struct Building {
+    rings: Vec<Vec<Point2D>>,
+    area: f64,
+    perimeter: f64,
+    orientation: f64,
+    base_z: f64,
+    roof_z: f64,
+    max_z: f64,
+    num_points: usize,
+    roof_planes: Vec<RoofPlane>,
+}
+
+struct RoofPlane {
+    rings: Vec<Vec<Point2D>>,
+    slope: f64,
+    aspect: f64,
+    area: f64,
+    mean_z: f64,
+    rmse: f64,
+    num_points: usize,
+}
+
+fn model_building(
+    points: &[[f64; 3]],
+    ground_frs: &FixedRadiusSearch2D<f64>,
+    params: &Parameters,
+) -> Option<Building> {
+    let xy = points
+        .iter()
+        .map(|p| Point2D::new(p[0], p[1]))
+        .collect::<Vec<Point2D>>();
+    let outline = alpha_shape(&xy, params.alpha)
+        .iter()
+        .map(|r| simplify_ring(r, params.tolerance))
+        .filter(|r| r.len() >= 4 && polygon_area(r) >= params.min_area)
+        .collect::<Vec<Vec<Point2D>>>();
+    if !outline.iter().any(|r| is_clockwise_order(r)) {
+        return None;
+    }
+    let theta = dominant_orientation(&outline);
+    let rings = outline
+        .iter()
+        .map(|r| regularize_ring(r, theta, params.tolerance, params.angle_tolerance))
+        .collect::<Vec<Vec<Point2D>>>();
+    let mut area = 0f64;
+    let mut perimeter = 0f64;
+    for ring in &rings {
+        if is_clockwise_order(ring) {
+            area += polygon_area(ring);
+        } else {
+            area -= polygon_area(ring);
+        }
+        perimeter += polygon_perimeter(ring);
+    }
+
+    // the ground elevation around the building
+    let mut ground_z = vec![];
+    for ring in rings.iter().filter(|r| is_clockwise_order(r)) {
+        for p in densify(ring, params.search_radius) {
+            for (z, _) in ground_frs.search(p.x, p.y) {
+                ground_z.push(z);
+            }
+        }
+    }
+    let mut z = points.iter().map(|p| p[2]).collect::<Vec<f64>>();
+    z.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let base_z = if !ground_z.is_empty() {
+        ground_z.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        median(&ground_z)
+    } else {
+        z[0]
+    };
+
+    let roof_planes = if params.model_roofs {
+        segment_roof_planes(points, theta, params)
+    } else {
+        vec![]
+    };
+
+    Some(Building {
+        rings: rings,
+        area: area,
+        perimeter: perimeter,
+        orientation: (90f64 - theta.to_degrees()) % 90f64,
+        base_z: base_z,
+        roof_z: median(&z),
+        max_z: z[z.len() - 1],
+        num_points: points.len(),
+        roof_planes: roof_planes,
+    })
+}
+
+/// Segments the points of a building into planar roof faces using region growing.
+fn segment_roof_planes(points: &[[f64; 3]], theta: f64, params: &Parameters) -> Vec<RoofPlane> {
+    let n = points.len();
+    let mut frs = FixedRadiusSearch2D::new(params.search_radius, DistanceMetric::Euclidean);
+    for i in 0..n {
+        frs.insert(points[i][0], points[i][1], i);
+    }
+    let neighbours = (0..n)
+        .map(|i| {
+            frs.search(points[i][0], points[i][1])
+                .iter()
+                .map(|(j, _)| *j)
+                .collect::<Vec<usize>>()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    // the local plane at each point
+    let mut normals = vec![[0f64, 0f64, 1f64]; n];
+    let mut residuals = vec![f64::INFINITY; n];
+    for i in 0..n {
+        if neighbours[i].len() >= 3 {
+            let nbrs = neighbours[i]
+                .iter()
+                .map(|j| points[*j])
+                .collect::<Vec<[f64; 3]>>();
+            if let Some((_, normal, rmse)) = fit_plane(&nbrs) {
+                normals[i] = normal;
+                residuals[i] = rmse;
+            }
+        }
+    }
+    let mut seeds = (0..n)
+        .filter(|i| residuals[*i].is_finite())
+        .collect::<Vec<usize>>();
+    seeds.sort_by(|a, b| residuals[*a].partial_cmp(&residuals[*b]).unwrap());
+
+    let min_cos = params.plane_angle.cos();
+    let mut region = vec![0usize; n]; // zero is unassigned
+    let mut tried = vec![false; n];
+    let mut planes = vec![];
+    let mut queue = VecDeque::new();
+    for seed in seeds {
+        if region[seed] != 0 || tried[seed] {
+            continue;
+        }
+        let label = planes.len() + 1;
+        let mut members = vec![seed];
+        let mut centroid = points[seed];
+        let mut normal = normals[seed];
+        let mut fitted_size = 1;
+        region[seed] = label;
+        queue.push_back(seed);
+        while let Some(i) = queue.pop_front() {
+            for &j in &neighbours[i] {
+                if region[j] != 0 || !residuals[j].is_finite() {
+                    continue;
+                }
+                let d = [
+                    points[j][0] - centroid[0],
+                    points[j][1] - centroid[1],
+                    points[j][2] - centroid[2],
+                ];
+                if dot(&normals[j], &normal).abs() >= min_cos
+                    && dot(&d, &normal).abs() <= params.plane_threshold
+                {
+                    region[j] = label;
+                    members.push(j);
+                    queue.push_back(j);
+                }
+            }
+            if members.len() >= 2 * fitted_size && members.len() >= 3 {
+                let pts = members.iter().map(|j| points[*j]).collect::<Vec<_>>();
+                if let Some((c, nrml, _)) = fit_plane(&pts) {
+                    centroid = c;
+                    normal = nrml;
+                }
+                fitted_size = members.len();
+            }
+        }
+        if members.len() < params.min_plane_points {
+            for j in members {
+                region[j] = 0;
+                tried[j] = true;
+            }
+            continue;
+        }
+        let pts = members.iter().map(|j| points[*j]).collect::<Vec<_>>();
+        let (c, normal, rmse) = match fit_plane(&pts) {
+            Some(plane) => plane,
+            None => continue,
+        };
+        let xy = pts
+            .iter()
+            .map(|p| Point2D::new(p[0], p[1]))
+            .collect::<Vec<Point2D>>();
+        let rings = alpha_shape(&xy, params.alpha)
+            .iter()
+            .map(|r| simplify_ring(r, params.tolerance))
+            .filter(|r| r.len() >= 4)
+            .map(|r| regularize_ring(&r, theta, params.tolerance, params.angle_tolerance))
+            .collect::<Vec<Vec<Point2D>>>();
+        if !rings.iter().any(|r| is_clockwise_order(r)) {
+            continue;
+        }
+        let mut area = 0f64;
+        for ring in &rings {
+            if is_clockwise_order(ring) {
+                area += polygon_area(ring);
+            } else {
+                area -= polygon_area(ring);
+            }
+        }
+        let slope = normal[2].max(-1f64).min(1f64).acos().to_degrees();
+        let aspect = if slope < 1f64 {
+            -1f64
+        } else {
+            (normal[0].atan2(normal[1]).to_degrees() + 360f64) % 360f64
+        };
+        planes.push(RoofPlane {
+            rings: rings,
+            slope: slope,
+            aspect: aspect,
+            area: area,
+            mean_z: c[2],
+            rmse: rmse,
+            num_points: members.len(),
+        });
+    }
+    planes
+}
+
+fn polygon_geometry(rings: &[Vec<Point2D>]) -> ShapefileGeometry {
+    let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+    // Exterior rings are added before their holes.
+    for ring in rings.iter().filter(|r| is_clockwise_order(r)) {
+        sfg.add_part(ring);
+    }
+    for ring in rings.iter().filter(|r| !is_clockwise_order(r)) {
+        sfg.add_part(ring);
+    }
+    sfg
+}
+
+/// Estimates the dominant orientation (radians, 0 to pi/2) of a set of rings from the
+/// length-weighted mean of their edge directions, modulo 90 degrees.
+fn dominant_orientation(rings: &[Vec<Point2D>]) -> f64 {
+    let (mut sum_sin, mut sum_cos) = (0f64, 0f64);
+    for ring in rings {
+        for i in 0..ring.len() - 1 {
+            let dx = ring[i + 1].x - ring[i].x;
+            let dy = ring[i + 1].y - ring[i].y;
+            let length = (dx * dx + dy * dy).sqrt();
+            let phi = 4f64 * dy.atan2(dx);
+            sum_sin += length * phi.sin();
+            sum_cos += length * phi.cos();
+        }
+    }
+    let theta = sum_sin.atan2(sum_cos) / 4f64;
+    if theta < 0f64 {
+        theta + PI / 2f64
+    } else {
+        theta
+    }
+}
+
+/// Simplifies a closed ring using the Douglas-Peucker algorithm.
+fn simplify_ring(ring: &[Point2D], tolerance: f64) -> Vec<Point2D> {
+    let n = ring.len() - 1; // the last point repeats the first
+    if n < 3 {
+        return ring.to_vec();
+    }
+    // split the ring at the vertex farthest from the first
+    let mut far = 0;
+    let mut max_dist = 0f64;
+    for i in 1..n {
+        let dist = ring[0].distance_squared(&ring[i]);
+        if dist > max_dist {
+            max_dist = dist;
+            far = i;
+        }
+    }
+    if far == 0 {
+        return ring.to_vec();
+    }
+    let mut keep = vec![false; n + 1];
+    keep[0] = true;
+    keep[far] = true;
+    keep[n] = true;
+    douglas_peucker(ring, 0, far, tolerance, &mut keep);
+    douglas_peucker(ring, far, n, tolerance, &mut keep);
+    (0..=n)
+        .filter(|i| keep[*i])
+        .map(|i| ring[i])
+        .collect::<Vec<Point2D>>()
+}
+
+fn douglas_peucker(line: &[Point2D], start: usize, end: usize, tolerance: f64, keep: &mut [bool]) {
+    if end <= start + 1 {
+        return;
+    }
+    let mut index = start;
+    let mut max_dist = 0f64;
+    for i in start + 1..end {
+        let dist = distance_to_segment(&line[i], &line[start], &line[end]);
+        if dist > max_dist {
+            max_dist = dist;
+            index = i;
+        }
+    }
+    if max_dist > tolerance {
+        keep[index] = true;
+        douglas_peucker(line, start, index, tolerance, keep);
+        douglas_peucker(line, index, end, tolerance, keep);
+    }
+}
+
+fn distance_to_segment(p: &Point2D, a: &Point2D, b: &Point2D) -> f64 {
+    let dx = b.x - a.x;
+    let dy = b.y - a.y;
+    let len_sqrd = dx * dx + dy * dy;
+    if len_sqrd == 0f64 {
+        return p.distance(a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len_sqrd)
+        .max(0f64)
+        .min(1f64);
+    p.distance(&Point2D::new(a.x + t * dx, a.y + t * dy))
+}
+
+/// An infinite line through `point` in the direction of the unit vector `dir`, representing
+/// a ring edge of a given length.
+#[derive(Clone, Copy)]
+struct Edge {
+    point: Point2D,
+    dir: Point2D,
+    length: f64,
+    aligned: bool,
+}
+
+/// Regularizes a simplified closed ring to the dominant orientation `theta`. Edges within the
+/// angular tolerance of the dominant directions are rotated to them, adjacent parallel edges
+/// are merged, and vertices are placed at the intersections of adjacent edges. The simplified
+/// ring is returned unchanged if regularization fails to produce a valid ring.
+fn regularize_ring(
+    ring: &[Point2D],
+    theta: f64,
+    tolerance: f64,
+    angle_tolerance: f64,
+) -> Vec<Point2D> {
+    let n = ring.len() - 1;
+    let mut edges = vec![];
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[i + 1]);
+        let length = a.distance(&b);
+        if length == 0f64 {
+            continue;
+        }
+        let phi = (b.y - a.y).atan2(b.x - a.x);
+        // the nearest of the four dominant directions
+        let k = ((phi - theta) / (PI / 2f64)).round();
+        let snapped = theta + k * PI / 2f64;
+        let aligned = (phi - snapped).abs() <= angle_tolerance;
+        let dir = if aligned { snapped } else { phi };
+        edges.push(Edge {
+            point: Point2D::midpoint(&a, &b),
+            dir: Point2D::new(dir.cos(), dir.sin()),
+            length: length,
+            aligned: aligned,
+        });
+    }
+
+    // merge adjacent parallel edges that are offset by less than the tolerance
+    let mut merged = true;
+    while merged && edges.len() > 3 {
+        merged = false;
+        let m = edges.len();
+        for i in 0..m {
+            let j = (i + 1) % m;
+            let (e1, e2) = (edges[i], edges[j]);
+            let parallel =
+                e1.aligned && e2.aligned && e1.dir.x * e2.dir.x + e1.dir.y * e2.dir.y > 0.5;
+            if parallel && line_distance(&e1, &e2.point) < tolerance {
+                let w = e1.length + e2.length;
+                edges[i] = Edge {
+                    point: Point2D::new(
+                        (e1.point.x * e1.length + e2.point.x * e2.length) / w,
+                        (e1.point.y * e1.length + e2.point.y * e2.length) / w,
+                    ),
+                    dir: e1.dir,
+                    length: w,
+                    aligned: true,
+                };
+                edges.remove(j);
+                merged = true;
+                break;
+            }
+        }
+    }
+    if edges.len() < 3 {
+        return ring.to_vec();
+    }
+
+    let m = edges.len();
+    let mut vertices = vec![];
+    for i in 0..m {
+        let (e1, e2) = (edges[i], edges[(i + 1) % m]);
+        let cross = e1.dir.x * e2.dir.y - e1.dir.y * e2.dir.x;
+        let mut vertex = None;
+        if cross.abs() > 1e-6 {
+            let t = ((e2.point.x - e1.point.x) * e2.dir.y - (e2.point.y - e1.point.y) * e2.dir.x)
+                / cross;
+            let p = Point2D::new(e1.point.x + t * e1.dir.x, e1.point.y + t * e1.dir.y);
+            // distant intersections of nearly-parallel edges are replaced with a step
+            let shared = Point2D::midpoint(&project(&e1, &e2.point), &project(&e2, &e1.point));
+            if p.distance(&shared) <= e1.length.max(e2.length) + tolerance {
+                vertex = Some(p);
+            }
+        }
+        match vertex {
+            Some(p) => vertices.push(p),
+            None => {
+                // join the parallel edges with a perpendicular step at their shared end
+                let corner = Point2D::new(
+                    e1.point.x + e1.dir.x * e1.length / 2f64,
+                    e1.point.y + e1.dir.y * e1.length / 2f64,
+                );
+                vertices.push(project(&e1, &corner));
+                vertices.push(project(&e2, &corner));
+            }
+        }
+    }
+    // the vertex between edge m-1 and edge 0 closes the ring
+    vertices.insert(0, vertices[vertices.len() - 1]);
+    vertices.dedup_by(|a, b| a.distance(b) < 1e-9);
+    if vertices.len() < 4 || vertices[0].distance(&vertices[vertices.len() - 1]) > 1e-9 {
+        return ring.to_vec();
+    }
+
+    // check that the regularized ring is a valid substitute for the simplified ring
+    let area1 = polygon_area(ring);
+    let area2 = polygon_area(&vertices);
+    if is_clockwise_order(ring) != is_clockwise_order(&vertices)
+        || area2 < 0.5 * area1
+        || area2 > 1.5 * area1
+        || is_self_intersecting(&vertices)
+    {
+        return ring.to_vec();
+    }
+    vertices
+}
+
+fn project(edge: &Edge, p: &Point2D) -> Point2D {
+    let t = (p.x - edge.point.x) * edge.dir.x + (p.y - edge.point.y) * edge.dir.y;
+    Point2D::new(edge.point.x + t * edge.dir.x, edge.point.y + t * edge.dir.y)
+}
+
+fn line_distance(edge: &Edge, p: &Point2D) -> f64 {
+    ((p.x - edge.point.x) * edge.dir.y - (p.y - edge.point.y) * edge.dir.x).abs()
+}
+
+fn is_self_intersecting(ring: &[Point2D]) -> bool {
+    let n = ring.len() - 1;
+    let orient = |a: &Point2D, b: &Point2D, c: &Point2D| {
+        (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+    };
+    for i in 0..n {
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue; // adjacent through the closing vertex
+            }
+            let (a, b, c, d) = (&ring[i], &ring[i + 1], &ring[j], &ring[j + 1]);
+            let d1 = orient(a, b, c);
+            let d2 = orient(a, b, d);
+            let d3 = orient(c, d, a);
+            let d4 = orient(c, d, b);
+            if d1 * d2 < 0f64 && d3 * d4 < 0f64 {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Returns points along a closed ring at a maximum spacing.
+fn densify(ring: &[Point2D], spacing: f64) -> Vec<Point2D> {
+    let mut points = vec![];
+    for i in 0..ring.len() - 1 {
+        let length = ring[i].distance(&ring[i + 1]);
+        let num_steps = (length / spacing).ceil().max(1f64) as usize;
+        for s in 0..num_steps {
+            let t = s as f64 / num_steps as f64;
+            points.push(Point2D::new(
+                ring[i].x + t * (ring[i + 1].x - ring[i].x),
+                ring[i].y + t * (ring[i + 1].y - ring[i].y),
+            ));
+        }
+    }
+    points
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2f64
+    }
+}
+
+// Fits a plane to a set of points, returning the centroid, the unit normal (oriented upwards)
+// and the root-mean-square distance of the points to the plane.
+fn fit_plane(points: &[[f64; 3]]) -> Option<([f64; 3], [f64; 3], f64)> {
+    let n = points.len() as f64;
+    let mut c = [0f64; 3];
+    for p in points {
+        for j in 0..3 {
+            c[j] += p[j];
+        }
+    }
+    for j in 0..3 {
+        c[j] /= n;
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        for r in 0..3 {
+            for k in 0..3 {
+                cov[(r, k)] += d[r] * d[k];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut m = 0;
+    for j in 1..3 {
+        if eig.eigenvalues[j] < eig.eigenvalues[m] {
+            m = j;
+        }
+    }
+    let v = eig.eigenvectors.column(m);
+    let mut normal = [v[0], v[1], v[2]];
+    let len = dot(&normal, &normal).sqrt();
+    if len == 0f64 || !len.is_finite() {
+        return None;
+    }
+    let sign = if normal[2] < 0f64 { -1f64 } else { 1f64 };
+    for j in 0..3 {
+        normal[j] *= sign / len;
+    }
+    Some((c, normal, (eig.eigenvalues[m].max(0f64) / n).sqrt()))
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dominant_orientation, model_building, regularize_ring, Parameters};
+    use crate::structures::{DistanceMetric, FixedRadiusSearch2D, Point2D};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_dominant_orientation() {
+        let (c, s) = ((PI / 6f64).cos(), (PI / 6f64).sin());
+        let ring: Vec<Point2D> = [
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (20.0, 10.0),
+            (20.0, 0.0),
+            (0.0, 0.0),
+        ]
+        .iter()
+        .map(|&(x, y)| Point2D::new(x * c - y * s, x * s + y * c))
+        .collect();
+        assert!((dominant_orientation(&[ring]) - PI / 6f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_regularize_ring() {
+        // a clockwise rectangle with a slightly skewed top edge
+        let ring = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.0, 10.0),
+            Point2D::new(20.0, 10.2),
+            Point2D::new(20.0, 0.0),
+            Point2D::new(0.0, 0.0),
+        ];
+        let regular = regularize_ring(&ring, 0f64, 0.5, 5f64.to_radians());
+        assert_eq!(regular.len(), 5);
+        for p in &regular {
+            let on_x = p.x.abs() < 1e-9 || (p.x - 20.0).abs() < 1e-9;
+            let on_y = p.y.abs() < 1e-9 || (p.y - 10.1).abs() < 1e-9;
+            assert!(on_x && on_y);
+        }
+    }
+
+    #[test]
+    fn test_model_gable_roof() {
+        // a gable roof rising at a slope of 1:2 from z = 5 at the eaves to a north-south ridge
+        let mut points = vec![];
+        for i in 0..20 {
+            for j in 0..20 {
+                let (x, y) = (0.25 + i as f64 * 0.5, 0.25 + j as f64 * 0.5);
+                points.push([x, y, 7.5 - 0.5 * (x - 5.0).abs()]);
+            }
+        }
+        let params = Parameters {
+            search_radius: 1.0,
+            alpha: 1.0,
+            min_area: 10.0,
+            tolerance: 0.2,
+            angle_tolerance: 10f64.to_radians(),
+            plane_threshold: 0.1,
+            plane_angle: 10f64.to_radians(),
+            min_plane_points: 10,
+            model_roofs: true,
+        };
+        let ground_frs = FixedRadiusSearch2D::new(1.0, DistanceMetric::SquaredEuclidean);
+        let building = model_building(&points, &ground_frs, &params).unwrap();
+        assert!((building.area - 90.25).abs() < 1e-6);
+        assert!((building.perimeter - 38.0).abs() < 1e-6);
+        assert!(building.orientation.abs() < 1e-6);
+        assert_eq!(building.num_points, 400);
+        assert_eq!(building.roof_planes.len(), 2);
+        let mut aspects = vec![];
+        for plane in &building.roof_planes {
+            assert!((plane.slope - 0.5f64.atan().to_degrees()).abs() < 1e-6);
+            aspects.push(plane.aspect.round());
+        }
+        aspects.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(aspects, vec![90.0, 270.0]);
+    }
+}
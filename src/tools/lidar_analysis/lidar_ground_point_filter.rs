@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 02/06/2017
-Last Modified: 18/10/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
@@ -69,7 +69,7 @@ impl LidarGroundPointFilter {
             description: "Input LiDAR file.".to_owned(),
             parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
             default_value: None,
-            optional: false,
+            optional: true,
         });
 
         parameters.push(ToolParameter {
@@ -78,7 +78,7 @@ impl LidarGroundPointFilter {
             description: "Output LiDAR file.".to_owned(),
             parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
             default_value: None,
-            optional: false,
+            optional: true,
         });
 
         parameters.push(ToolParameter {
@@ -146,6 +146,15 @@ impl LidarGroundPointFilter {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Tile Buffer Size (optional)".to_owned(),
+            flags: vec!["--buffer".to_owned()],
+            description: "Optional size of the buffer of points from neighbouring tiles included when filtering each tile; defaults to twice the search radius.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -221,6 +230,7 @@ impl WhiteboxTool for LidarGroundPointFilter {
         let mut filter = true;
         let mut slope_norm = false;
         let mut height_above_ground = false;
+        let mut buffer = f64::NAN;
 
         // read the arguments
         if args.len() == 0 {
@@ -312,6 +322,18 @@ impl WhiteboxTool for LidarGroundPointFilter {
                     height_above_ground = true;
                     filter = false; // this doesn't make sense unless non-ground points are included in the output
                 }
+            } else if flag_val == "-buffer" {
+                buffer = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
             }
         }
 
@@ -321,28 +343,63 @@ impl WhiteboxTool for LidarGroundPointFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
+        if buffer.is_nan() {
+            buffer = 2f64 * search_radius;
         }
 
-        if verbose {
-            println!("Reading input LAS file...");
+        let sep = path::MAIN_SEPARATOR;
+        let tiles: LasTileSet;
+        let mut outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            // skip the outputs of any previous runs of the tool
+            let mut tile_set = LasTileSet::from_directory(working_directory)?;
+            let keep: Vec<bool> = tile_set
+                .files
+                .iter()
+                .map(|f| !f.to_lowercase().ends_with("_filtered.las"))
+                .collect();
+            let mut k = 0;
+            tile_set.files.retain(|_| {
+                k += 1;
+                keep[k - 1]
+            });
+            k = 0;
+            tile_set.extents.retain(|_| {
+                k += 1;
+                keep[k - 1]
+            });
+            tiles = tile_set;
+            for in_file in &tiles.files {
+                outputs.push(
+                    in_file
+                        .replace(".las", "_filtered.las")
+                        .replace(".LAS", "_filtered.las")
+                        .replace(".zip", "_filtered.las")
+                        .replace(".ZIP", "_filtered.las"),
+                );
+            }
+        } else {
+            if !input_file.contains(sep) && !input_file.contains("/") {
+                input_file = format!("{}{}", working_directory, input_file);
+            }
+            if output_file.is_empty() {
+                output_file = input_file
+                    .replace(".las", "_filtered.las")
+                    .replace(".LAS", "_filtered.las");
+            }
+            if !output_file.contains(sep) && !output_file.contains("/") {
+                output_file = format!("{}{}", working_directory, output_file);
+            }
+            tiles = LasTileSet::new(vec![input_file.clone()])?;
+            outputs.push(output_file);
         }
-        let input = match LasFile::new(&input_file, "r") {
-            Ok(lf) => lf,
-            Err(err) => panic!("Error reading file {}: {}", input_file, err),
-        };
 
         let start = Instant::now();
 
-        if verbose {
-            println!("Performing analysis...");
-        }
-
         if slope_threshold > 88f64 {
             eprintln!("Warning: the slope threshold cannot be greater than 88 degrees.");
             slope_threshold = 88f64;
@@ -350,441 +407,473 @@ impl WhiteboxTool for LidarGroundPointFilter {
 
         slope_threshold = slope_threshold.to_radians().tan();
 
-        let n_points = input.header.number_of_points as usize;
-        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
-
-        let mut residuals = vec![f64::MIN; n_points];
-        let mut is_off_terrain = vec![false; n_points];
-
-        let mut frs: FixedRadiusSearch2D<usize> =
-            FixedRadiusSearch2D::new(search_radius, DistanceMetric::SquaredEuclidean);
-
-        let mut progress: i32;
-        let mut old_progress: i32 = -1;
-        for i in 0..n_points {
-            let p: PointData = input.get_point_info(i);
-            if p.is_late_return() && !p.is_classified_noise() {
-                frs.insert(p.x, p.y, i);
-                if !slope_norm {
-                    residuals[i] = p.z;
+        /*
+        When a directory of tiles is processed, each tile is filtered along with the points
+        from adjacent tiles that are within the buffer distance of its edges, so that there
+        are no edge effects. Only the points of the tile itself are output.
+        */
+        let num_tiles = tiles.len();
+        for tile in 0..num_tiles {
+            let input_file = tiles.files[tile].replace("\"", "");
+            let output_file = outputs[tile].replace("\"", "");
+            if verbose {
+                if num_tiles > 1 {
+                    println!("Processing {} ({} of {})", input_file, tile + 1, num_tiles);
                 }
+                println!("Reading input LAS file...");
             }
+            let input = match LasFile::new(&input_file, "r") {
+                Ok(lf) => lf,
+                Err(err) => panic!("Error reading file {}: {}", input_file, err),
+            };
+            let buffer_points = tiles.read_buffer_points(tile, buffer)?;
+
             if verbose {
-                progress = (100.0_f64 * i as f64 / num_points) as i32;
-                if progress != old_progress {
-                    println!("Binning points: {}%", progress);
-                    old_progress = progress;
-                }
+                println!("Performing analysis...");
             }
-        }
 
-        let frs = Arc::new(frs); // wrap FRS in an Arc
-        let num_procs = num_cpus::get();
-        let input = Arc::new(input); // wrap input in an Arc
+            let n_points = input.header.number_of_points as usize;
+            let mut points: Vec<PointData> = Vec::with_capacity(n_points + buffer_points.len());
+            for i in 0..n_points {
+                points.push(input.get_point_info(i));
+            }
+            points.extend(buffer_points);
+            let n_total = points.len();
+            let num_total: f64 = (n_total - 1) as f64; // used for progress calculation only
+            let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+            let mut residuals = vec![f64::MIN; n_total];
+            let mut is_off_terrain = vec![false; n_total];
+
+            let mut frs: FixedRadiusSearch2D<usize> =
+                FixedRadiusSearch2D::new(search_radius, DistanceMetric::SquaredEuclidean);
+
+            let mut progress: i32;
+            let mut old_progress: i32 = -1;
+            for i in 0..n_total {
+                let p: PointData = points[i];
+                if p.is_late_return() && !p.is_classified_noise() {
+                    frs.insert(p.x, p.y, i);
+                    if !slope_norm {
+                        residuals[i] = p.z;
+                    }
+                }
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / num_total) as i32;
+                    if progress != old_progress {
+                        println!("Binning points: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
 
-        if slope_norm {
-            /////////////
-            // Erosion //
-            /////////////
-            let mut neighbourhood_min = vec![f64::MAX; n_points];
-            let (tx, rx) = mpsc::channel();
-            for tid in 0..num_procs {
-                let frs = frs.clone();
-                let input = input.clone();
-                let tx = tx.clone();
-                thread::spawn(move || {
-                    let mut index_n: usize;
-                    let mut z_n: f64;
-                    let mut min_z: f64;
-                    let mut ret: Vec<(usize, f64)>;
-                    for point_num in (0..n_points).filter(|point_num| point_num % num_procs == tid)
-                    {
-                        let p: PointData = input.get_point_info(point_num);
-                        if p.is_late_return() && !p.is_classified_noise() {
-                            ret = frs.search(p.x, p.y);
-                            min_z = f64::MAX;
-                            for j in 0..ret.len() {
-                                index_n = ret[j].0;
-                                z_n = input.get_point_info(index_n).z;
-                                if z_n < min_z {
-                                    min_z = z_n;
+            let frs = Arc::new(frs); // wrap FRS in an Arc
+            let num_procs = num_cpus::get();
+            let points = Arc::new(points); // wrap points in an Arc
+
+            if slope_norm {
+                /////////////
+                // Erosion //
+                /////////////
+                let mut neighbourhood_min = vec![f64::MAX; n_total];
+                let (tx, rx) = mpsc::channel();
+                for tid in 0..num_procs {
+                    let frs = frs.clone();
+                    let points = points.clone();
+                    let tx = tx.clone();
+                    thread::spawn(move || {
+                        let mut index_n: usize;
+                        let mut z_n: f64;
+                        let mut min_z: f64;
+                        let mut ret: Vec<(usize, f64)>;
+                        for point_num in
+                            (0..n_total).filter(|point_num| point_num % num_procs == tid)
+                        {
+                            let p: PointData = points[point_num];
+                            if p.is_late_return() && !p.is_classified_noise() {
+                                ret = frs.search(p.x, p.y);
+                                min_z = f64::MAX;
+                                for j in 0..ret.len() {
+                                    index_n = ret[j].0;
+                                    z_n = points[index_n].z;
+                                    if z_n < min_z {
+                                        min_z = z_n;
+                                    }
                                 }
+                                tx.send((point_num, min_z)).unwrap();
+                            } else {
+                                tx.send((point_num, f64::MAX)).unwrap();
                             }
-                            tx.send((point_num, min_z)).unwrap();
-                        } else {
-                            tx.send((point_num, f64::MAX)).unwrap();
+                        }
+                    });
+                }
+
+                for i in 0..n_total {
+                    let data = rx.recv().expect("Error receiving data from thread.");
+                    neighbourhood_min[data.0] = data.1;
+                    if verbose {
+                        progress = (100.0_f64 * i as f64 / num_total) as i32;
+                        if progress != old_progress {
+                            println!("Erosion: {}%", progress);
+                            old_progress = progress;
                         }
                     }
-                });
-            }
+                }
 
-            for i in 0..n_points {
-                let data = rx.recv().expect("Error receiving data from thread.");
-                neighbourhood_min[data.0] = data.1;
-                if verbose {
-                    progress = (100.0_f64 * i as f64 / num_points) as i32;
-                    if progress != old_progress {
-                        println!("Erosion: {}%", progress);
-                        old_progress = progress;
+                //////////////
+                // Dilation //
+                //////////////
+                let neighbourhood_min = Arc::new(neighbourhood_min); // wrap neighbourhood_min in an Arc
+                for tid in 0..num_procs {
+                    let frs = frs.clone();
+                    let points = points.clone();
+                    let neighbourhood_min = neighbourhood_min.clone();
+                    let tx = tx.clone();
+                    thread::spawn(move || {
+                        let mut index_n: usize;
+                        let mut z_n: f64;
+                        let mut max_z: f64;
+                        let mut ret: Vec<(usize, f64)>;
+                        for point_num in
+                            (0..n_total).filter(|point_num| point_num % num_procs == tid)
+                        {
+                            let p: PointData = points[point_num];
+                            if p.is_late_return() && !p.is_classified_noise() {
+                                ret = frs.search(p.x, p.y);
+                                max_z = f64::MIN;
+                                for j in 0..ret.len() {
+                                    index_n = ret[j].0;
+                                    z_n = neighbourhood_min[index_n];
+                                    if z_n > max_z {
+                                        max_z = z_n;
+                                    }
+                                }
+                                tx.send((point_num, max_z)).unwrap();
+                            } else {
+                                tx.send((point_num, f64::MIN)).unwrap();
+                            }
+                        }
+                    });
+                }
+
+                for i in 0..n_total {
+                    let data = rx.recv().expect("Error receiving data from thread.");
+                    if data.1 != f64::MIN {
+                        let z = points[data.0].z;
+                        residuals[data.0] = z - data.1;
+                    }
+                    if verbose {
+                        progress = (100.0_f64 * i as f64 / num_total) as i32;
+                        if progress != old_progress {
+                            println!("Dilation: {}%", progress);
+                            old_progress = progress;
+                        }
                     }
                 }
             }
 
-            //////////////
-            // Dilation //
-            //////////////
-            let neighbourhood_min = Arc::new(neighbourhood_min); // wrap neighbourhood_min in an Arc
+            ////////////////////////
+            // Slope-based filter //
+            ////////////////////////
+            let residuals = Arc::new(residuals);
+            let (tx, rx) = mpsc::channel();
             for tid in 0..num_procs {
                 let frs = frs.clone();
-                let input = input.clone();
-                let neighbourhood_min = neighbourhood_min.clone();
+                let points = points.clone();
+                let residuals = residuals.clone();
                 let tx = tx.clone();
                 thread::spawn(move || {
                     let mut index_n: usize;
-                    let mut z_n: f64;
-                    let mut max_z: f64;
+                    let mut max_slope: f64;
+                    let mut slope: f64;
+                    let mut dist: f64;
                     let mut ret: Vec<(usize, f64)>;
-                    for point_num in (0..n_points).filter(|point_num| point_num % num_procs == tid)
-                    {
-                        let p: PointData = input.get_point_info(point_num);
-                        if p.is_late_return() && !p.is_classified_noise() {
+                    for point_num in (0..n_total).filter(|point_num| point_num % num_procs == tid) {
+                        let p: PointData = points[point_num];
+                        if (!slope_norm || residuals[point_num] < height_threshold)
+                            && p.is_late_return()
+                            && !p.is_classified_noise()
+                        {
                             ret = frs.search(p.x, p.y);
-                            max_z = f64::MIN;
+                            if ret.len() < min_neighbours {
+                                ret = frs.knn_search(p.x, p.y, min_neighbours);
+                            }
+                            max_slope = f64::MIN;
                             for j in 0..ret.len() {
-                                index_n = ret[j].0;
-                                z_n = neighbourhood_min[index_n];
-                                if z_n > max_z {
-                                    max_z = z_n;
+                                dist = ret[j].1;
+                                if dist > 0f64 {
+                                    index_n = ret[j].0;
+                                    slope =
+                                        (residuals[point_num] - residuals[index_n]) / dist.sqrt();
+                                    if slope > max_slope {
+                                        max_slope = slope;
+                                    }
                                 }
                             }
-                            tx.send((point_num, max_z)).unwrap();
+                            if max_slope > slope_threshold {
+                                tx.send((point_num, true)).unwrap();
+                            } else {
+                                tx.send((point_num, false)).unwrap();
+                            }
                         } else {
-                            tx.send((point_num, f64::MIN)).unwrap();
+                            tx.send((point_num, true)).unwrap();
                         }
                     }
                 });
             }
 
-            for i in 0..n_points {
+            for i in 0..n_total {
                 let data = rx.recv().expect("Error receiving data from thread.");
-                if data.1 != f64::MIN {
-                    let z = input.get_point_info(data.0).z;
-                    residuals[data.0] = z - data.1;
-                }
+                is_off_terrain[data.0] = data.1;
                 if verbose {
-                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                    progress = (100.0_f64 * i as f64 / num_total) as i32;
                     if progress != old_progress {
-                        println!("Dilation: {}%", progress);
+                        println!("Slope-based Filter: {}%", progress);
                         old_progress = progress;
                     }
                 }
             }
-        }
 
-        ////////////////////////
-        // Slope-based filter //
-        ////////////////////////
-        let residuals = Arc::new(residuals);
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let frs = frs.clone();
-            let input = input.clone();
-            let residuals = residuals.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
+            /////////////////////
+            // Output the data //
+            /////////////////////
+            let mut output = LasFile::initialize_using_file(&output_file, &input);
+            let mut num_points_filtered = 0;
+            if filter {
+                output.header.system_id = "EXTRACTION".to_string();
+
+                for point_num in 0..n_points {
+                    if !is_off_terrain[point_num] {
+                        output.add_point_record(input.get_record(point_num));
+                    } else {
+                        num_points_filtered += 1;
+                    }
+                    if verbose {
+                        progress = (100.0_f64 * point_num as f64 / num_points) as i32;
+                        if progress != old_progress {
+                            println!("Saving data: {}%", progress);
+                            old_progress = progress;
+                        }
+                    }
+                }
+            } else {
+                // classify
+                let mut height: f64;
                 let mut index_n: usize;
-                let mut max_slope: f64;
-                let mut slope: f64;
-                let mut dist: f64;
                 let mut ret: Vec<(usize, f64)>;
-                for point_num in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
-                    let p: PointData = input.get_point_info(point_num);
-                    if (!slope_norm || residuals[point_num] < height_threshold)
-                        && p.is_late_return()
-                        && !p.is_classified_noise()
-                    {
-                        ret = frs.search(p.x, p.y);
-                        if ret.len() < min_neighbours {
-                            ret = frs.knn_search(p.x, p.y, min_neighbours);
-                        }
-                        max_slope = f64::MIN;
-                        for j in 0..ret.len() {
-                            dist = ret[j].1;
-                            if dist > 0f64 {
+                let mut p: PointData;
+                let mut total_ground_elev: f64;
+                let mut num_ground_pnts: f64;
+                for point_num in 0..n_points {
+                    let class_val = match !is_off_terrain[point_num] {
+                        true => ground_class_value,
+                        false => otp_class_value,
+                    };
+
+                    p = points[point_num];
+                    if !p.is_classified_noise() {
+                        if height_above_ground && class_val == otp_class_value {
+                            ret = frs.search(p.x, p.y);
+                            if ret.len() < min_neighbours {
+                                ret = frs.knn_search(p.x, p.y, min_neighbours);
+                            }
+                            total_ground_elev = 0f64;
+                            num_ground_pnts = 0f64;
+                            for j in 0..ret.len() {
                                 index_n = ret[j].0;
-                                slope = (residuals[point_num] - residuals[index_n]) / dist.sqrt();
-                                if slope > max_slope {
-                                    max_slope = slope;
+                                if !is_off_terrain[index_n] {
+                                    total_ground_elev += p.z - points[index_n].z;
+                                    num_ground_pnts += 1f64;
                                 }
                             }
-                        }
-                        if max_slope > slope_threshold {
-                            tx.send((point_num, true)).unwrap();
+                            if num_ground_pnts > 0f64 {
+                                height = total_ground_elev / num_ground_pnts;
+                            } else {
+                                height = 0f64;
+                            }
+                        } else if height_above_ground {
+                            height = 0f64;
                         } else {
-                            tx.send((point_num, false)).unwrap();
+                            height = p.z;
                         }
-                    } else {
-                        tx.send((point_num, true)).unwrap();
-                    }
-                }
-            });
-        }
 
-        for i in 0..n_points {
-            let data = rx.recv().expect("Error receiving data from thread.");
-            is_off_terrain[data.0] = data.1;
-            if verbose {
-                progress = (100.0_f64 * i as f64 / num_points) as i32;
-                if progress != old_progress {
-                    println!("Slope-based Filter: {}%", progress);
-                    old_progress = progress;
-                }
-            }
-        }
-
-        // now output the data
-        let mut output = LasFile::initialize_using_file(&output_file, &input);
-        output.header.system_id = "EXTRACTION".to_string();
-
-        /////////////////////
-        // Output the data //
-        /////////////////////
-        let mut output = LasFile::initialize_using_file(&output_file, &input);
-        let mut num_points_filtered = 0;
-        if filter {
-            output.header.system_id = "EXTRACTION".to_string();
-
-            for point_num in 0..n_points {
-                if !is_off_terrain[point_num] {
-                    output.add_point_record(input.get_record(point_num));
-                } else {
-                    num_points_filtered += 1;
-                }
-                if verbose {
-                    progress = (100.0_f64 * point_num as f64 / num_points) as i32;
-                    if progress != old_progress {
-                        println!("Saving data: {}%", progress);
-                        old_progress = progress;
-                    }
-                }
-            }
-        } else {
-            // classify
-            let mut height: f64;
-            let mut index_n: usize;
-            let mut ret: Vec<(usize, f64)>;
-            let mut p: PointData;
-            let mut total_ground_elev: f64;
-            let mut num_ground_pnts: f64;
-            for point_num in 0..n_points {
-                let class_val = match !is_off_terrain[point_num] {
-                    true => ground_class_value,
-                    false => otp_class_value,
-                };
-
-                p = input.get_point_info(point_num);
-                if !p.is_classified_noise() {
-                    if height_above_ground && class_val == otp_class_value {
-                        ret = frs.search(p.x, p.y);
-                        if ret.len() < min_neighbours {
-                            ret = frs.knn_search(p.x, p.y, min_neighbours);
-                        }
-                        total_ground_elev = 0f64;
-                        num_ground_pnts = 0f64;
-                        for j in 0..ret.len() {
-                            index_n = ret[j].0;
-                            if !is_off_terrain[index_n] {
-                                total_ground_elev += p.z - input.get_point_info(index_n).z;
-                                num_ground_pnts += 1f64;
+                        let pr = input.get_record(point_num);
+                        let pr2: LidarPointRecord;
+                        match pr {
+                            LidarPointRecord::PointRecord0 { mut point_data } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord0 {
+                                    point_data: point_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord1 {
+                                mut point_data,
+                                gps_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord1 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord2 {
+                                mut point_data,
+                                colour_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord2 {
+                                    point_data: point_data,
+                                    colour_data: colour_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord3 {
+                                mut point_data,
+                                gps_data,
+                                colour_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord3 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    colour_data: colour_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord4 {
+                                mut point_data,
+                                gps_data,
+                                wave_packet,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord4 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    wave_packet: wave_packet,
+                                };
+                            }
+                            LidarPointRecord::PointRecord5 {
+                                mut point_data,
+                                gps_data,
+                                colour_data,
+                                wave_packet,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord5 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    colour_data: colour_data,
+                                    wave_packet: wave_packet,
+                                };
+                            }
+                            LidarPointRecord::PointRecord6 {
+                                mut point_data,
+                                gps_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord6 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord7 {
+                                mut point_data,
+                                gps_data,
+                                colour_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord7 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    colour_data: colour_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord8 {
+                                mut point_data,
+                                gps_data,
+                                colour_data,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord8 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    colour_data: colour_data,
+                                };
+                            }
+                            LidarPointRecord::PointRecord9 {
+                                mut point_data,
+                                gps_data,
+                                wave_packet,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord9 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    wave_packet: wave_packet,
+                                };
+                            }
+                            LidarPointRecord::PointRecord10 {
+                                mut point_data,
+                                gps_data,
+                                colour_data,
+                                wave_packet,
+                            } => {
+                                point_data.z = height;
+                                point_data.set_classification(class_val);
+                                pr2 = LidarPointRecord::PointRecord10 {
+                                    point_data: point_data,
+                                    gps_data: gps_data,
+                                    colour_data: colour_data,
+                                    wave_packet: wave_packet,
+                                };
                             }
                         }
-                        if num_ground_pnts > 0f64 {
-                            height = total_ground_elev / num_ground_pnts;
-                        } else {
-                            height = 0f64;
-                        }
-                    } else if height_above_ground {
-                        height = 0f64;
+                        output.add_point_record(pr2);
                     } else {
-                        height = p.z;
+                        // Keep the classes of classified noise unaltered
+                        output.add_point_record(input.get_record(point_num));
                     }
-
-                    let pr = input.get_record(point_num);
-                    let pr2: LidarPointRecord;
-                    match pr {
-                        LidarPointRecord::PointRecord0 { mut point_data } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord0 {
-                                point_data: point_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord1 {
-                            mut point_data,
-                            gps_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord1 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord2 {
-                            mut point_data,
-                            colour_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord2 {
-                                point_data: point_data,
-                                colour_data: colour_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord3 {
-                            mut point_data,
-                            gps_data,
-                            colour_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord3 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                colour_data: colour_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord4 {
-                            mut point_data,
-                            gps_data,
-                            wave_packet,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord4 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                wave_packet: wave_packet,
-                            };
-                        }
-                        LidarPointRecord::PointRecord5 {
-                            mut point_data,
-                            gps_data,
-                            colour_data,
-                            wave_packet,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord5 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                colour_data: colour_data,
-                                wave_packet: wave_packet,
-                            };
-                        }
-                        LidarPointRecord::PointRecord6 {
-                            mut point_data,
-                            gps_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord6 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord7 {
-                            mut point_data,
-                            gps_data,
-                            colour_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord7 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                colour_data: colour_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord8 {
-                            mut point_data,
-                            gps_data,
-                            colour_data,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord8 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                colour_data: colour_data,
-                            };
-                        }
-                        LidarPointRecord::PointRecord9 {
-                            mut point_data,
-                            gps_data,
-                            wave_packet,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord9 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                wave_packet: wave_packet,
-                            };
-                        }
-                        LidarPointRecord::PointRecord10 {
-                            mut point_data,
-                            gps_data,
-                            colour_data,
-                            wave_packet,
-                        } => {
-                            point_data.z = height;
-                            point_data.set_classification(class_val);
-                            pr2 = LidarPointRecord::PointRecord10 {
-                                point_data: point_data,
-                                gps_data: gps_data,
-                                colour_data: colour_data,
-                                wave_packet: wave_packet,
-                            };
+                    if verbose {
+                        progress = (100.0_f64 * point_num as f64 / num_points) as i32;
+                        if progress != old_progress {
+                            println!("Saving data: {}%", progress);
+                            old_progress = progress;
                         }
                     }
-                    output.add_point_record(pr2);
-                } else {
-                    // Keep the classes of classified noise unaltered
-                    output.add_point_record(input.get_record(point_num));
-                }
-                if verbose {
-                    progress = (100.0_f64 * point_num as f64 / num_points) as i32;
-                    if progress != old_progress {
-                        println!("Saving data: {}%", progress);
-                        old_progress = progress;
-                    }
                 }
+                num_points_filtered = 1; // so it passes the saving
+            }
+
+            if num_points_filtered == 0 {
+                println!("Warning: No points were filtered from the point cloud.");
             }
-            num_points_filtered = 1; // so it passes the saving
-        }
 
-        if num_points_filtered == 0 {
-            println!("Warning: No points were filtered from the point cloud.");
+            if verbose {
+                println!("Writing output LAS file...");
+            }
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Complete!")
+                    }
+                }
+                Err(e) => println!("error while writing: {:?}", e),
+            };
         }
 
         let elapsed_time = get_formatted_elapsed_time(start);
-
-        if verbose {
-            println!("Writing output LAS file...");
-        }
-        let _ = match output.write() {
-            Ok(_) => {
-                if verbose {
-                    println!("Complete!")
-                }
-            }
-            Err(e) => println!("error while writing: {:?}", e),
-        };
         if verbose {
             println!(
                 "{}",
-                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
             );
         }
 
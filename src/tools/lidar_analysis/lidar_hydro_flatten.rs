@@ -0,0 +1,1020 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use super::lidar_tin_gridding::max_distance_squared;
+use crate::algorithms::constrained_triangulate;
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool creates a hydro-flattened digital elevation model (DEM) from the ground points of a LiDAR
+/// point cloud (`--input`), using water-body polygons (`--lakes`) and double-line river polygons
+/// (`--rivers`) with their centrelines (`--centrelines`). Hydro-flattening is required by many national
+/// DEM specifications, e.g. the USGS LiDAR Base Specification, because LiDAR returns from water surfaces
+/// are sparse and noisy, producing irregular, triangulated water surfaces in unedited DEMs. In a
+/// hydro-flattened DEM:
+///
+/// - each lake (or other closed water body) has a constant elevation, which is equal to the lowest
+///   elevation of the ground points along its shoreline;
+/// - the water surface of each river is flat from bank to bank and decreases monotonically downstream,
+///   i.e. never increases in the direction of flow; and
+/// - bridges are removed, such that the river surface continues beneath them.
+///
+/// The DEM is interpolated from a triangular irregular network (TIN), in the same way as the
+/// `LidarTINGridding` tool. Ground points located within the water polygons, which include the
+/// returns from bridge decks over rivers, are removed from the TIN. The shorelines of the lakes and the
+/// banks and centrelines of the rivers are then enforced as breaklines, i.e. they are densified into
+/// vertices, spaced at `--spacing` (by default, the grid resolution), that are inserted into the TIN with
+/// their flattened elevations, and their segments are made edges of the TIN using a constrained Delaunay
+/// triangulation, such that no triangle spans a shoreline. Finally, the grid cells within the water polygons are assigned the
+/// flattened water surface elevations.
+///
+/// Only ground (class 2) and model key-point (class 8) returns are used; points that are withheld or
+/// classified as bridge deck (class 17) are always excluded. The elevation of the shoreline at each
+/// breakline vertex is the lowest elevation of the ground points outside of the water polygons within a
+/// distance of `--shore_distance` of the vertex, so that the water surface is at or below the immediately
+/// surrounding terrain. For rivers, these bank elevations are assigned to the nearest points along the
+/// centrelines, and the resulting profile is made monotonic downstream. The direction of flow is determined
+/// from the bank elevations at either end of each centreline, so that centrelines do not need to be
+/// digitized in the downstream direction. Each river polygon must contain a centreline. Islands may be
+/// represented by holes in the water polygons.
+///
+/// Optionally, triangles with edges longer than `--max_triangle_edge_length` are not gridded, leaving
+/// NoData in areas without points.
+///
+/// # See Also
+/// `LidarTINGridding`, `FlattenLakes`, `LidarGroundPointFilter`
+pub struct LidarHydroFlatten {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarHydroFlatten {
+    pub fn new() -> LidarHydroFlatten {
+        // public constructor
+        let name = "LidarHydroFlatten".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Creates a hydro-flattened DEM from LiDAR ground points, lake polygons and river polygons and centrelines.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file containing classified ground points.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Lakes File (optional)".to_owned(),
+            flags: vec!["--lakes".to_owned()],
+            description: "Input vector polygons file of lakes and other closed water bodies."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Rivers File (optional)".to_owned(),
+            flags: vec!["--rivers".to_owned()],
+            description: "Input vector polygons file of double-line rivers.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input River Centrelines File (optional)".to_owned(),
+            flags: vec!["--centrelines".to_owned()],
+            description: "Input vector lines file of river centrelines; required with rivers."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Shoreline Search Distance".to_owned(),
+            flags: vec!["--shore_distance".to_owned()],
+            description:
+                "Distance from the water polygons within which shoreline elevations are measured."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Breakline Vertex Spacing (optional)".to_owned(),
+            flags: vec!["--spacing".to_owned()],
+            description: "Spacing of the breakline vertices; by default, the grid resolution."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Triangle Edge Length (optional)".to_owned(),
+            flags: vec!["--max_triangle_edge_length".to_owned()],
+            description: "Optional maximum triangle edge length; triangles larger than this size will not be gridded.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=ground.las --lakes=lakes.shp --rivers=rivers.shp --centrelines=centrelines.shp -o=dem.tif --resolution=1.0 --shore_distance=2.0", short_exe, name).replace("*", &sep);
+
+        LidarHydroFlatten {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarHydroFlatten {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut lakes_file = String::new();
+        let mut rivers_file = String::new();
+        let mut centrelines_file = String::new();
+        let mut output_file = String::new();
+        let mut grid_res = 1f64;
+        let mut shore_distance = 2f64;
+        let mut spacing = 0f64;
+        let mut max_triangle_edge_length = f64::INFINITY;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-lakes" {
+                lakes_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-rivers" {
+                rivers_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-centrelines" || flag_val == "-centerlines" {
+                centrelines_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution"
+                || flag_val == "-shore_distance"
+                || flag_val == "-spacing"
+                || flag_val == "-max_triangle_edge_length"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-resolution" => grid_res = value,
+                    "-shore_distance" => shore_distance = value,
+                    "-spacing" => spacing = value,
+                    _ => max_triangle_edge_length = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        for file in [
+            &mut input_file,
+            &mut lakes_file,
+            &mut rivers_file,
+            &mut centrelines_file,
+            &mut output_file,
+        ]
+        .iter_mut()
+        {
+            if !file.is_empty() && !file.contains(&sep) && !file.contains("/") {
+                **file = format!("{}{}", working_directory, file);
+            }
+        }
+
+        if lakes_file.is_empty() && rivers_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one of the lakes and rivers files must be specified.",
+            ));
+        }
+        if !rivers_file.is_empty() && centrelines_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A river centrelines file must be specified with the rivers file.",
+            ));
+        }
+        if grid_res <= 0f64 || shore_distance <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution and shoreline search distance must be greater than zero.",
+            ));
+        }
+        if spacing <= 0f64 {
+            spacing = grid_res;
+        }
+        if max_triangle_edge_length.is_finite() {
+            max_triangle_edge_length *= max_triangle_edge_length;
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let mut input = LasFile::new(&input_file, "r")?;
+
+        let read_polygons = |file_name: &str| -> Result<Vec<Vec<Vec<Point2D>>>, Error> {
+            let polygons = Shapefile::read(file_name)?;
+            if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input water-body files must be of polygon base shape type.",
+                ));
+            }
+            Ok((0..polygons.num_records)
+                .map(|r| parts_of(polygons.get_record(r)))
+                .collect())
+        };
+        let lakes = if !lakes_file.is_empty() {
+            read_polygons(&lakes_file)?
+        } else {
+            vec![]
+        };
+        let rivers = if !rivers_file.is_empty() {
+            read_polygons(&rivers_file)?
+        } else {
+            vec![]
+        };
+        let centrelines: Vec<Vec<Point2D>> = if !centrelines_file.is_empty() {
+            let lines = Shapefile::read(&centrelines_file)?;
+            if lines.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input centrelines file must be of polyline base shape type.",
+                ));
+            }
+            (0..lines.num_records)
+                .flat_map(|r| parts_of(lines.get_record(r)))
+                .filter(|part| part.len() > 1)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let start = Instant::now();
+
+        // Water bodies are indexed by lakes first, then rivers.
+        let num_lakes = lakes.len();
+        let bodies: Vec<&Vec<Vec<Point2D>>> = lakes.iter().chain(rivers.iter()).collect();
+        let water_index = WaterIndex::new(&bodies, spacing.max(grid_res) * 4f64);
+
+        // Read the ground points, separating those in the water bodies.
+        let n_points = input.header.number_of_points as usize;
+        let mut points: Vec<Point2D> = Vec::with_capacity(n_points);
+        let mut z_values: Vec<f64> = Vec::with_capacity(n_points);
+        let mut num_removed = 0usize;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            let class = p.classification();
+            if !p.withheld() && (class == 2 || class == 8) {
+                if water_index.body_containing(p.x, p.y).is_some() {
+                    num_removed += 1;
+                } else {
+                    points.push(Point2D::new(p.x, p.y));
+                    z_values.push(p.z);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (n_points - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Reading points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if points.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any ground points outside of the water bodies.",
+            ));
+        }
+        if verbose {
+            println!(
+                "Number of ground points removed from water bodies: {}",
+                num_removed
+            );
+        }
+
+        // Measure the shoreline elevation at each breakline vertex.
+        let mut frs = FixedRadiusSearch2D::new(shore_distance, DistanceMetric::SquaredEuclidean);
+        for (i, p) in points.iter().enumerate() {
+            frs.insert(p.x, p.y, z_values[i]);
+        }
+        let shore_elevation = |p: &Point2D| -> Option<f64> {
+            frs.search(p.x, p.y)
+                .iter()
+                .map(|&(z, _)| z)
+                .fold(None, |min: Option<f64>, z| match min {
+                    Some(m) => Some(m.min(z)),
+                    None => Some(z),
+                })
+        };
+
+        // The breakline vertices are appended to the ground points.
+        let num_ground_points = points.len();
+        let mut constraints: Vec<(usize, usize)> = vec![];
+
+        // Lakes are flattened to the lowest shoreline elevation.
+        let mut lake_elevations = vec![f64::NAN; num_lakes];
+        for (l, lake) in lakes.iter().enumerate() {
+            let rings: Vec<Vec<Point2D>> = lake
+                .iter()
+                .map(|ring| densify(ring, spacing, true))
+                .collect();
+            let elevation = rings
+                .iter()
+                .flat_map(|ring| ring.iter())
+                .filter_map(|v| shore_elevation(v))
+                .fold(f64::INFINITY, |a, b| a.min(b));
+            if elevation.is_finite() {
+                lake_elevations[l] = elevation;
+                for ring in rings {
+                    let vertices: Vec<(Point2D, f64)> =
+                        ring.into_iter().map(|v| (v, elevation)).collect();
+                    add_breakline(
+                        &vertices,
+                        true,
+                        &mut points,
+                        &mut z_values,
+                        &mut constraints,
+                    );
+                }
+            } else if verbose {
+                println!(
+                    "Warning: no shoreline points were found for lake {}; it will not be flattened.",
+                    l + 1
+                );
+            }
+        }
+
+        // Rivers are flattened across their banks, following a monotonic profile along their centrelines.
+        let mut stations: Vec<Station> = vec![];
+        for (line, centreline) in centrelines.iter().enumerate() {
+            let vertices = densify(centreline, spacing, false);
+            for v in vertices.iter() {
+                let river = match water_index.body_containing(v.x, v.y) {
+                    Some(b) if b >= num_lakes => Some(b),
+                    _ => None,
+                };
+                stations.push(Station {
+                    point: *v,
+                    line: line,
+                    river: river,
+                    bank_z: f64::INFINITY,
+                    z: f64::NAN,
+                });
+            }
+        }
+        let mut river_rings: Vec<Vec<(Point2D, usize)>> = vec![];
+        if !rivers.is_empty() {
+            if stations.iter().all(|s| s.river.is_none()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "None of the river centrelines are located within the river polygons.",
+                ));
+            }
+            let mut station_search =
+                FixedRadiusSearch2D::new(spacing * 10f64, DistanceMetric::SquaredEuclidean);
+            for (s, station) in stations.iter().enumerate() {
+                if station.river.is_some() {
+                    station_search.insert(station.point.x, station.point.y, s);
+                }
+            }
+            for (r, river) in rivers.iter().enumerate() {
+                for ring in river {
+                    let mut ring_vertices = vec![];
+                    for v in densify(ring, spacing, true) {
+                        let nearest = station_search.knn_search(v.x, v.y, 1);
+                        let s = match nearest
+                            .iter()
+                            .find(|n| stations[n.0].river == Some(num_lakes + r))
+                        {
+                            Some(n) => n.0,
+                            None => {
+                                // The nearest station is in another river; search more widely.
+                                match station_search
+                                    .knn_search(v.x, v.y, 10)
+                                    .iter()
+                                    .find(|n| stations[n.0].river == Some(num_lakes + r))
+                                {
+                                    Some(n) => n.0,
+                                    None => continue,
+                                }
+                            }
+                        };
+                        if let Some(z) = shore_elevation(&v) {
+                            stations[s].bank_z = stations[s].bank_z.min(z);
+                        }
+                        ring_vertices.push((v, s));
+                    }
+                    river_rings.push(ring_vertices);
+                }
+            }
+
+            // Make the profile of each centreline monotonic downstream.
+            for line in 0..centrelines.len() {
+                let idx: Vec<usize> = (0..stations.len())
+                    .filter(|&s| stations[s].line == line && stations[s].river.is_some())
+                    .collect();
+                let bank_z: Vec<f64> = idx.iter().map(|&s| stations[s].bank_z).collect();
+                for (&s, z) in idx.iter().zip(monotonic_profile(&bank_z)) {
+                    stations[s].z = z;
+                }
+            }
+            // The centrelines are divided into runs of consecutive stations within the rivers.
+            let mut run: Vec<(Point2D, f64)> = vec![];
+            for s in 0..stations.len() {
+                if s > 0
+                    && (stations[s].line != stations[s - 1].line
+                        || stations[s].river != stations[s - 1].river)
+                {
+                    add_breakline(&run, false, &mut points, &mut z_values, &mut constraints);
+                    run.clear();
+                }
+                if stations[s].river.is_some() && stations[s].z.is_finite() {
+                    run.push((stations[s].point, stations[s].z));
+                }
+            }
+            add_breakline(&run, false, &mut points, &mut z_values, &mut constraints);
+            for ring in &river_rings {
+                let vertices: Vec<(Point2D, f64)> = ring
+                    .iter()
+                    .filter(|&&(_, s)| stations[s].z.is_finite())
+                    .map(|&(v, s)| (v, stations[s].z))
+                    .collect();
+                add_breakline(
+                    &vertices,
+                    true,
+                    &mut points,
+                    &mut z_values,
+                    &mut constraints,
+                );
+            }
+        }
+        if verbose {
+            println!(
+                "Number of breakline vertices: {}",
+                points.len() - num_ground_points
+            );
+        }
+
+        // Build the TIN.
+        if verbose {
+            println!("Performing triangulation...");
+        }
+        let cdt = match constrained_triangulate(&points, &constraints) {
+            Some(r) => r,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "No triangulation exists for the input points.",
+                ))
+            }
+        };
+        if verbose && !cdt.failed_constraints.is_empty() {
+            println!(
+                "Warning: {} breakline segments intersect other breaklines and could not be enforced.",
+                cdt.failed_constraints.len()
+            );
+        }
+        let result = &cdt.triangulation;
+        let num_triangles = result.triangles.len() / 3;
+
+        let west = input.header.min_x;
+        let north = input.header.max_y;
+        let rows = (((north - input.header.min_y) / grid_res).ceil()) as isize;
+        let columns = (((input.header.max_x - west) / grid_res).ceil()) as isize;
+        let south = north - rows as f64 * grid_res;
+        let east = west + columns as f64 * grid_res;
+        let nodata = -32768.0f64;
+
+        let mut configs = RasterConfigs {
+            ..Default::default()
+        };
+        configs.rows = rows as usize;
+        configs.columns = columns as usize;
+        configs.north = north;
+        configs.south = south;
+        configs.east = east;
+        configs.west = west;
+        configs.resolution_x = grid_res;
+        configs.resolution_y = grid_res;
+        configs.nodata = nodata;
+        configs.data_type = DataType::F32;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        configs.coordinate_ref_system_wkt = input.get_wkt();
+
+        let mut output = Raster::initialize_using_config(&output_file, &configs);
+        for triangle in 0..num_triangles {
+            let i = triangle * 3;
+            let (p1, p2, p3) = (
+                result.triangles[i],
+                result.triangles[i + 1],
+                result.triangles[i + 2],
+            );
+            if max_triangle_edge_length.is_finite()
+                && max_distance_squared(
+                    points[p1],
+                    points[p2],
+                    points[p3],
+                    z_values[p1],
+                    z_values[p2],
+                    z_values[p3],
+                ) > max_triangle_edge_length
+            {
+                continue;
+            }
+            let (a, b, c) = (points[p1], points[p2], points[p3]);
+            let det = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+            if det == 0f64 {
+                continue;
+            }
+            let top_row = ((north - a.y.max(b.y).max(c.y)) / grid_res).floor() as isize;
+            let bottom_row = ((north - a.y.min(b.y).min(c.y)) / grid_res).ceil() as isize;
+            let left_col = ((a.x.min(b.x).min(c.x) - west) / grid_res).floor() as isize;
+            let right_col = ((a.x.max(b.x).max(c.x) - west) / grid_res).ceil() as isize;
+            for row in top_row.max(0)..=bottom_row.min(rows - 1) {
+                for col in left_col.max(0)..=right_col.min(columns - 1) {
+                    let x = west + (col as f64 + 0.5) * grid_res;
+                    let y = north - (row as f64 + 0.5) * grid_res;
+                    // barycentric coordinates
+                    let w1 = ((b.y - c.y) * (x - c.x) + (c.x - b.x) * (y - c.y)) / det;
+                    let w2 = ((c.y - a.y) * (x - c.x) + (a.x - c.x) * (y - c.y)) / det;
+                    let w3 = 1f64 - w1 - w2;
+                    if w1 >= 0f64 && w2 >= 0f64 && w3 >= 0f64 {
+                        output.set_value(
+                            row,
+                            col,
+                            w1 * z_values[p1] + w2 * z_values[p2] + w3 * z_values[p3],
+                        );
+                    }
+                }
+            }
+            if verbose {
+                progress =
+                    (100.0_f64 * triangle as f64 / (num_triangles - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Gridding TIN: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Assign the flattened water surface elevations to the cells within the water bodies.
+        let mut station_search =
+            FixedRadiusSearch2D::new(spacing * 10f64, DistanceMetric::SquaredEuclidean);
+        for (s, station) in stations.iter().enumerate() {
+            if station.river.is_some() && station.z.is_finite() {
+                station_search.insert(station.point.x, station.point.y, s);
+            }
+        }
+        for row in 0..rows {
+            let y = north - (row as f64 + 0.5) * grid_res;
+            for col in 0..columns {
+                let x = west + (col as f64 + 0.5) * grid_res;
+                if let Some(b) = water_index.body_containing(x, y) {
+                    if b < num_lakes {
+                        if lake_elevations[b].is_finite() {
+                            output.set_value(row, col, lake_elevations[b]);
+                        }
+                    } else if station_search.size() > 0 {
+                        if let Some(n) = station_search
+                            .knn_search(x, y, 10)
+                            .iter()
+                            .find(|n| stations[n.0].river == Some(b))
+                        {
+                            output.set_value(row, col, stations[n.0].z);
+                        }
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Flattening water bodies: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        if !lakes_file.is_empty() {
+            output.add_metadata_entry(format!("Lakes file: {}", lakes_file));
+        }
+        if !rivers_file.is_empty() {
+            output.add_metadata_entry(format!("Rivers file: {}", rivers_file));
+            output.add_metadata_entry(format!("Centrelines file: {}", centrelines_file));
+        }
+        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+        output.add_metadata_entry(format!("Shoreline search distance: {}", shore_distance));
+        output.add_metadata_entry(format!("Number of ground points: {}", num_ground_points));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A point along a river centreline.
+struct Station {
+    point: Point2D,
+    line: usize,
+    river: Option<usize>,
+    bank_z: f64,
+    z: f64,
+}
+
+/// Returns the water surface elevations of a sequence of centreline stations, given the lowest
+/// bank elevation measured at each (infinite where unmeasured), such that they do not increase
+/// downstream. The flow direction is determined by the bank elevations of the two end thirds of
+/// the line. The elevations are NaN if none of the stations are measured.
+fn monotonic_profile(bank_z: &[f64]) -> Vec<f64> {
+    let measured: Vec<usize> = (0..bank_z.len())
+        .filter(|&s| bank_z[s].is_finite())
+        .collect();
+    if measured.is_empty() {
+        return vec![f64::NAN; bank_z.len()];
+    }
+    let third = (measured.len() / 3).max(1);
+    let mean = |v: &[usize]| -> f64 { v.iter().map(|&s| bank_z[s]).sum::<f64>() / v.len() as f64 };
+    let first_mean = mean(&measured[0..third]);
+    let last_mean = mean(&measured[measured.len() - third..]);
+    let mut order: Vec<usize> = (0..bank_z.len()).collect();
+    if first_mean < last_mean {
+        order.reverse();
+    }
+    let mut current = bank_z[measured[0]].max(bank_z[*measured.last().unwrap()]);
+    let mut z = vec![f64::NAN; bank_z.len()];
+    for s in order {
+        if bank_z[s] < current {
+            current = bank_z[s];
+        }
+        z[s] = current;
+    }
+    z
+}
+
+/// Returns the parts of a polygon or polyline record.
+fn parts_of(record: &ShapefileGeometry) -> Vec<Vec<Point2D>> {
+    let mut parts = Vec::with_capacity(record.num_parts as usize);
+    for part in 0..record.num_parts as usize {
+        let start = record.parts[part] as usize;
+        let end = if part < record.num_parts as usize - 1 {
+            record.parts[part + 1] as usize
+        } else {
+            record.num_points as usize
+        };
+        parts.push(record.points[start..end].to_vec());
+    }
+    parts
+}
+
+/// Adds the vertices of a breakline, with their elevations, to the TIN points, and its segments
+/// to the TIN constraints.
+fn add_breakline(
+    vertices: &[(Point2D, f64)],
+    closed: bool,
+    points: &mut Vec<Point2D>,
+    z_values: &mut Vec<f64>,
+    constraints: &mut Vec<(usize, usize)>,
+) {
+    let first = points.len();
+    for (k, &(p, z)) in vertices.iter().enumerate() {
+        points.push(p);
+        z_values.push(z);
+        if k > 0 {
+            constraints.push((first + k - 1, first + k));
+        }
+    }
+    if closed && vertices.len() > 2 {
+        constraints.push((points.len() - 1, first));
+    }
+}
+
+/// Inserts vertices along a line such that they are no further apart than `spacing`. The final
+/// vertex of closed rings, which duplicates the first, is omitted.
+fn densify(line: &[Point2D], spacing: f64, closed: bool) -> Vec<Point2D> {
+    let mut vertices = vec![];
+    for i in 0..line.len().saturating_sub(1) {
+        let (a, b) = (line[i], line[i + 1]);
+        let n = (a.distance(&b) / spacing).ceil().max(1f64) as usize;
+        for j in 0..n {
+            let t = j as f64 / n as f64;
+            vertices.push(Point2D::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)));
+        }
+    }
+    if !closed || !line[0].nearly_equals(&line[line.len() - 1]) {
+        vertices.push(line[line.len() - 1]);
+    }
+    vertices
+}
+
+/// A spatial index of the edges of water-body polygons, divided into horizontal bands, that is
+/// used to determine which water body, if any, contains a location. Holes are handled by the
+/// even-odd rule.
+struct WaterIndex {
+    min_y: f64,
+    band_height: f64,
+    num_bodies: usize,
+    edges: Vec<(Point2D, Point2D, usize)>,
+    bands: Vec<Vec<usize>>,
+}
+
+impl WaterIndex {
+    fn new(bodies: &[&Vec<Vec<Point2D>>], band_height: f64) -> WaterIndex {
+        let mut edges = vec![];
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (b, body) in bodies.iter().enumerate() {
+            for ring in body.iter() {
+                for i in 0..ring.len() {
+                    let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+                    if p.y != q.y {
+                        edges.push((p, q, b));
+                        min_y = min_y.min(p.y.min(q.y));
+                        max_y = max_y.max(p.y.max(q.y));
+                    }
+                }
+            }
+        }
+        let num_bands = if edges.is_empty() {
+            0
+        } else {
+            ((max_y - min_y) / band_height).floor() as usize + 1
+        };
+        let mut bands = vec![vec![]; num_bands];
+        for (e, edge) in edges.iter().enumerate() {
+            let b0 = ((edge.0.y.min(edge.1.y) - min_y) / band_height).floor() as usize;
+            let b1 = ((edge.0.y.max(edge.1.y) - min_y) / band_height).floor() as usize;
+            for band in b0..=b1.min(num_bands - 1) {
+                bands[band].push(e);
+            }
+        }
+        WaterIndex {
+            min_y: min_y,
+            band_height: band_height,
+            num_bodies: bodies.len(),
+            edges: edges,
+            bands: bands,
+        }
+    }
+
+    /// Returns the index of the water body containing a location.
+    fn body_containing(&self, x: f64, y: f64) -> Option<usize> {
+        if self.bands.is_empty() || y < self.min_y {
+            return None;
+        }
+        let band = ((y - self.min_y) / self.band_height).floor() as usize;
+        if band >= self.bands.len() {
+            return None;
+        }
+        let mut inside = vec![false; self.num_bodies];
+        for &e in &self.bands[band] {
+            let (p, q, b) = self.edges[e];
+            if (p.y > y) != (q.y > y) {
+                let x_cross = p.x + (y - p.y) * (q.x - p.x) / (q.y - p.y);
+                if x < x_cross {
+                    inside[b] = !inside[b];
+                }
+            }
+        }
+        inside.iter().position(|&i| i)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{densify, monotonic_profile, WaterIndex};
+    use crate::structures::Point2D;
+    use std::f64;
+
+    #[test]
+    fn test_monotonic_profile() {
+        let inf = f64::INFINITY;
+        // digitized downstream, with a bump and an unmeasured station
+        let z = monotonic_profile(&[10.0, 9.0, 9.5, inf, 8.0, 7.0]);
+        assert_eq!(z, vec![10.0, 9.0, 9.0, 9.0, 8.0, 7.0]);
+        // digitized upstream
+        let z = monotonic_profile(&[7.0, 8.0, 7.5, 9.0, 10.0]);
+        assert_eq!(z, vec![7.0, 7.5, 7.5, 9.0, 10.0]);
+        assert!(monotonic_profile(&[inf, inf]).iter().all(|z| z.is_nan()));
+    }
+
+    #[test]
+    fn test_densify() {
+        let ring = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.0, 2.0),
+            Point2D::new(3.0, 2.0),
+            Point2D::new(0.0, 0.0),
+        ];
+        // 2 + 3 + 4 segments, with the closing vertex omitted
+        assert_eq!(densify(&ring, 1.0, true).len(), 9);
+        assert_eq!(densify(&ring, 1.0, false).len(), 10);
+    }
+
+    #[test]
+    fn test_water_index() {
+        let lake = vec![
+            vec![
+                Point2D::new(0.0, 0.0),
+                Point2D::new(0.0, 10.0),
+                Point2D::new(10.0, 10.0),
+                Point2D::new(10.0, 0.0),
+                Point2D::new(0.0, 0.0),
+            ],
+            vec![
+                Point2D::new(4.0, 4.0),
+                Point2D::new(6.0, 4.0),
+                Point2D::new(6.0, 6.0),
+                Point2D::new(4.0, 6.0),
+                Point2D::new(4.0, 4.0),
+            ],
+        ];
+        let river = vec![vec![
+            Point2D::new(10.0, 4.0),
+            Point2D::new(10.0, 6.0),
+            Point2D::new(20.0, 6.0),
+            Point2D::new(20.0, 4.0),
+            Point2D::new(10.0, 4.0),
+        ]];
+        let index = WaterIndex::new(&[&lake, &river], 1.0);
+        assert_eq!(index.body_containing(2.0, 2.0), Some(0));
+        assert_eq!(index.body_containing(5.0, 5.0), None); // the island
+        assert_eq!(index.body_containing(15.0, 5.0), Some(1));
+        assert_eq!(index.body_containing(15.0, 8.0), None);
+        assert_eq!(index.body_containing(5.0, -1.0), None);
+    }
+}
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 03/07/2017
-Last Modified: 30/08/2019
+Last Modified: 18/10/2026
 License: MIT
 
 NOTES:
@@ -13,12 +13,11 @@ NOTES:
 
 use crate::lidar::*;
 use crate::raster::*;
-use crate::structures::{BoundingBox, DistanceMetric, FixedRadiusSearch2D};
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D};
 use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -149,6 +148,15 @@ impl LidarIdwInterpolation {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Tile Buffer Size (optional)".to_owned(),
+            flags: vec!["--buffer".to_owned()],
+            description: "Optional size of the buffer of points from neighbouring tiles included when interpolating each tile; defaults to the search radius.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -227,6 +235,7 @@ impl WhiteboxTool for LidarIdwInterpolation {
         let mut exclude_cls_str = String::new();
         let mut max_z = f64::INFINITY;
         let mut min_z = f64::NEG_INFINITY;
+        let mut buffer = f64::NAN;
 
         // read the arguments
         if args.len() == 0 {
@@ -374,9 +383,25 @@ impl WhiteboxTool for LidarIdwInterpolation {
                         .parse::<f64>()
                         .expect(&format!("Error parsing {}", flag_val))
                 };
+            } else if flag_val == "-buffer" {
+                buffer = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
             }
         }
 
+        if buffer.is_nan() {
+            buffer = search_radius;
+        }
+
         let (all_returns, late_returns, early_returns): (bool, bool, bool);
         if return_type.contains("last") {
             all_returns = false;
@@ -401,48 +426,27 @@ impl WhiteboxTool for LidarIdwInterpolation {
 
         let start = Instant::now();
 
-        let mut inputs = vec![];
+        let tiles: LasTileSet;
         let mut outputs = vec![];
         if input_file.is_empty() {
             if working_directory.is_empty() {
                 return Err(Error::new(ErrorKind::InvalidInput,
                     "This tool must be run by specifying either an individual input file or a working directory."));
             }
-            if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                        .path()
-                        .into_os_string()
-                        .to_str()
-                        .expect("Error reading path string")
-                        .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
-                }
-            } else {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("The input directory ({}) is incorrect.", working_directory),
-                ));
+            tiles = LasTileSet::from_directory(working_directory)?;
+            for in_file in &tiles.files {
+                outputs.push(
+                    in_file
+                        .replace(".las", ".tif")
+                        .replace(".LAS", ".tif")
+                        .replace(".zip", ".tif")
+                        .replace(".ZIP", ".tif"),
+                );
             }
         } else {
             if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
                 input_file = format!("{}{}", working_directory, input_file);
             }
-            inputs.push(input_file.clone());
             if output_file.is_empty() {
                 output_file = input_file
                     .clone()
@@ -452,40 +456,32 @@ impl WhiteboxTool for LidarIdwInterpolation {
             if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
                 output_file = format!("{}{}", working_directory, output_file);
             }
+            tiles = LasTileSet::new(vec![input_file.clone()])?;
             outputs.push(output_file);
         }
 
         /*
-        If multiple files are being interpolated, we will need to know their bounding boxes,
-        in order to retrieve points from adjacent tiles. This is so that there are no edge
-        effects.
+        If multiple files are being interpolated, points from adjacent tiles within the buffer
+        distance of a tile are included in its interpolation, and the output grid is then
+        trimmed to the extent of the tile. This is so that there are no edge effects.
         */
-        let mut bounding_boxes = vec![];
-        for in_file in &inputs {
-            let header = LasHeader::read_las_header(&in_file.replace("\"", ""))?;
-            bounding_boxes.push(BoundingBox {
-                min_x: header.min_x,
-                max_x: header.max_x,
-                min_y: header.min_y,
-                max_y: header.max_y,
-            });
-        }
-
         if verbose {
             println!("Performing interpolation...");
         }
 
-        let num_tiles = inputs.len();
+        let num_tiles = tiles.len();
         let tile_list = Arc::new(Mutex::new(0..num_tiles));
-        let inputs = Arc::new(inputs);
+        let inputs = Arc::new(tiles.files.clone());
         let outputs = Arc::new(outputs);
-        let bounding_boxes = Arc::new(bounding_boxes);
+        let bounding_boxes = Arc::new(tiles.extents.clone());
+        let tiles = Arc::new(tiles);
         let num_procs2 = num_cpus::get() as isize;
         let (tx2, rx2) = mpsc::channel();
         for _ in 0..num_procs2 {
             let inputs = inputs.clone();
             let outputs = outputs.clone();
             let bounding_boxes = bounding_boxes.clone();
+            let tiles = tiles.clone();
             let tile_list = tile_list.clone();
             // copy over the string parameters
             let interp_parameter = interp_parameter.clone();
@@ -509,13 +505,6 @@ impl WhiteboxTool for LidarIdwInterpolation {
                     let input_file = inputs[tile].replace("\"", "").clone();
                     let output_file = outputs[tile].replace("\"", "").clone();
 
-                    // Expand the bounding box to include the areas of overlap
-                    let bb = BoundingBox {
-                        min_x: bounding_boxes[tile].min_x - search_radius,
-                        max_x: bounding_boxes[tile].max_x + search_radius,
-                        min_y: bounding_boxes[tile].min_y - search_radius,
-                        max_y: bounding_boxes[tile].max_y + search_radius,
-                    };
                     let mut frs: FixedRadiusSearch2D<f64> =
                         FixedRadiusSearch2D::new(search_radius, DistanceMetric::Euclidean);
 
@@ -526,268 +515,86 @@ impl WhiteboxTool for LidarIdwInterpolation {
                     let mut progress: i32;
                     let mut old_progress: i32 = -1;
 
-                    for m in 0..inputs.len() {
-                        if bounding_boxes[m].overlaps(bb) {
-                            let input =
-                                match LasFile::new(&inputs[m].replace("\"", "").clone(), "r") {
-                                    Ok(lf) => lf,
-                                    Err(err) => panic!(
-                                        "Error reading file {}: {}",
-                                        inputs[m].replace("\"", ""),
-                                        err
-                                    ),
-                                };
-
-                            let n_points = input.header.number_of_points as usize;
-                            let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+                    let is_included = |p: &PointData| -> bool {
+                        !p.withheld()
+                            && (all_returns
+                                || (p.is_late_return() & late_returns)
+                                || (p.is_early_return() & early_returns))
+                            && include_class_vals[p.classification() as usize]
+                            && p.z >= min_z
+                            && p.z <= max_z
+                    };
+                    let point_value = |p: &PointData| -> f64 {
+                        match &interp_parameter as &str {
+                            "elevation" | "z" => p.z,
+                            "intensity" => p.intensity as f64,
+                            "scan angle" | "scan_angle" => p.scan_angle as f64,
+                            "class" => p.classification() as f64,
+                            "return_number" => p.return_number() as f64,
+                            "number_of_returns" => p.number_of_returns() as f64,
+                            _ => p.user_data as f64, // user data
+                        }
+                    };
+                    let colour_value = |clr: &ColourData| -> f64 {
+                        ((255u32 << 24)
+                            | ((clr.blue as u32) << 16)
+                            | ((clr.green as u32) << 8)
+                            | (clr.red as u32)) as f64
+                    };
+                    let rgb = interp_parameter == "rgb";
 
-                            match &interp_parameter as &str {
-                                "elevation" | "z" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.z);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "intensity" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.intensity as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "scan angle" | "scan_angle" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.scan_angle as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "class" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(
-                                                            p.x,
-                                                            p.y,
-                                                            p.classification() as f64,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "return_number" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(
-                                                            p.x,
-                                                            p.y,
-                                                            p.return_number() as f64,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => panic!("Error reading file {}: {}", input_file, err),
+                    };
+                    let n_points = input.header.number_of_points as usize;
+                    let num_points: f64 = (n_points.max(2) - 1) as f64; // used for progress calculation only
+                    if rgb && !input.has_rgb() {
+                        println!("Error: The input LAS file does not contain RGB colour data. The interpolation will not proceed.");
+                    } else {
+                        for i in 0..n_points {
+                            let p: PointData = input[i];
+                            if is_included(&p) {
+                                if rgb {
+                                    match input.get_rgb(i) {
+                                        Ok(clr) => frs.insert(p.x, p.y, colour_value(&clr)),
+                                        Err(_) => break,
                                     }
+                                } else {
+                                    frs.insert(p.x, p.y, point_value(&p));
                                 }
-                                "number_of_returns" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(
-                                                            p.x,
-                                                            p.y,
-                                                            p.number_of_returns() as f64,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
+                            }
+                            if verbose && inputs.len() == 1 {
+                                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                                if progress != old_progress {
+                                    println!("Binning points: {}%", progress);
+                                    old_progress = progress;
                                 }
-                                "rgb" => {
-                                    if !input.has_rgb() {
-                                        println!("Error: The input LAS file does not contain RGB colour data. The interpolation will not proceed.");
-                                        break;
-                                    }
-                                    let mut clr: ColourData;
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        clr = match input.get_rgb(i) {
-                                                            Ok(value) => value,
-                                                            Err(_) => break,
-                                                        };
-                                                        frs.insert(
-                                                            p.x,
-                                                            p.y,
-                                                            ((255u32 << 24)
-                                                                | ((clr.blue as u32) << 16)
-                                                                | ((clr.green as u32) << 8)
-                                                                | (clr.red as u32))
-                                                                as f64,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
+                            }
+                        }
+
+                        // add the points of the neighbouring tiles within the buffer
+                        if rgb {
+                            match tiles.read_buffer_colour_points(tile, buffer) {
+                                Ok(buffer_points) => {
+                                    for (p, clr) in buffer_points {
+                                        if is_included(&p) {
+                                            frs.insert(p.x, p.y, colour_value(&clr));
                                         }
                                     }
                                 }
-                                _ => {
-                                    // user data
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.user_data as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
+                                Err(err) => panic!("Error reading the tile buffer: {}", err),
+                            }
+                        } else {
+                            match tiles.read_buffer_points(tile, buffer) {
+                                Ok(buffer_points) => {
+                                    for p in buffer_points {
+                                        if is_included(&p) {
+                                            frs.insert(p.x, p.y, point_value(&p));
                                         }
                                     }
                                 }
+                                Err(err) => panic!("Error reading the tile buffer: {}", err),
                             }
                         }
                     }
@@ -972,6 +779,7 @@ impl WhiteboxTool for LidarIdwInterpolation {
                     output.add_metadata_entry(format!("Input file: {}", input_file));
                     output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
                     output.add_metadata_entry(format!("Search radius: {}", search_radius));
+                    output.add_metadata_entry(format!("Tile buffer size: {}", buffer));
                     output.add_metadata_entry(format!("Weight: {}", weight));
                     output.add_metadata_entry(format!(
                         "Interpolation parameter: {}",
@@ -0,0 +1,520 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufWriter;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool estimates the vertical distribution of leaf area density (LAD; one-sided leaf area per unit
+/// volume, m<sup>2</sup>/m<sup>3</sup>) and the leaf area index (LAI; one-sided leaf area per unit ground area)
+/// from a LiDAR point cloud. The input LAS file (`--input`) must contain **height-normalized** point
+/// elevations, i.e. the z value of each point must be its height above the ground surface. The output of the
+/// `HeightAboveGround` tool is suitable for this purpose.
+///
+/// The points are divided into vertical columns with a size of `--resolution`, each of which is divided into
+/// voxel layers with a thickness of `--layer_height`, starting at a height of `--min_height`. Returns below the
+/// minimum height, including ground returns, are considered to have passed through the entire profile. The
+/// LAD of each layer is estimated using the MacArthur-Horn method, which applies the Beer-Lambert law to the
+/// proportion of returns entering the layer from above that pass through it:
+///
+/// > LAD<sub>i</sub> = ln(N<sub>in</sub> / N<sub>out</sub>) / (k &Delta;z)
+///
+/// where N<sub>in</sub> is the number of returns at or below the layer, N<sub>out</sub> is the number of returns
+/// below the layer, k is the extinction coefficient (`--extinction`; 0.5 for a spherical leaf angle
+/// distribution), and &Delta;z is the layer height. One is added to the numbers of returns, so that layers
+/// that no pulse penetrates have a finite LAD. All returns are used, excluding points that are withheld or
+/// classified as noise and points above the optional maximum height (`--max_height`). The column size should
+/// be large enough for each column to contain many pulses, e.g. 10-20 m for typical airborne surveys.
+///
+/// The tool outputs a raster of LAI (`--output`), i.e. the sum of the LAD of the layers of each column
+/// multiplied by the layer height. Optionally, the mean LAD profile of the area (`--profile`) and the LAD of
+/// each voxel (`--voxels`) may be output as comma-separated values (CSV) files. The voxels file contains the
+/// coordinates of the centre of each voxel (x, y, and the height of the middle of the layer) and its LAD,
+/// for each layer up to the highest occupied layer of each column.
+///
+/// # Reference
+/// Bouvier, M., Durrieu, S., Fournier, R. A., and Renaud, J. P. (2015). Generalizing predictive models of
+/// forest inventory attributes using an area-based approach with airborne LiDAR data. *Remote Sensing of
+/// Environment*, 156, 322-334.
+///
+/// MacArthur, R. H., and Horn, H. S. (1969). Foliage profile by vertical measurements. *Ecology*, 50(5),
+/// 802-804.
+///
+/// # See Also
+/// `LidarVerticalComplexityIndex`, `LidarVoxelize`, `LidarVegetationMetrics`, `HeightAboveGround`
+pub struct LidarLeafAreaDensity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarLeafAreaDensity {
+    pub fn new() -> LidarLeafAreaDensity {
+        // public constructor
+        let name = "LidarLeafAreaDensity".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Estimates leaf area density profiles and leaf area index from height-normalized LiDAR points.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output LAI Raster".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output leaf area index raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Column Size".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Horizontal size of the voxel columns and output grid resolution."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Layer Height".to_owned(),
+            flags: vec!["--layer_height".to_owned()],
+            description: "Vertical size of the voxel layers.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description:
+                "Height of the bottom of the profile; lower returns are treated as ground returns."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Height (optional)".to_owned(),
+            flags: vec!["--max_height".to_owned()],
+            description: "Optional maximum height; points above this height are excluded."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Extinction Coefficient".to_owned(),
+            flags: vec!["--extinction".to_owned()],
+            description: "Extinction coefficient (k) of the Beer-Lambert law.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Mean Profile File (optional)".to_owned(),
+            flags: vec!["--profile".to_owned()],
+            description: "Optional output CSV file of the mean LAD profile.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Voxels File (optional)".to_owned(),
+            flags: vec!["--voxels".to_owned()],
+            description: "Optional output CSV file of the LAD of each voxel.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=lai.tif --resolution=10.0 --layer_height=1.0 --min_height=1.0 --profile=lad_profile.csv", short_exe, name).replace("*", &sep);
+
+        LidarLeafAreaDensity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarLeafAreaDensity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut profile_file = String::new();
+        let mut voxels_file = String::new();
+        let mut resolution = 10f64;
+        let mut layer_height = 1f64;
+        let mut min_height = 1f64;
+        let mut max_height = f64::INFINITY;
+        let mut extinction = 0.5f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-profile" {
+                profile_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-voxels" {
+                voxels_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution"
+                || flag_val == "-layer_height"
+                || flag_val == "-min_height"
+                || flag_val == "-max_height"
+                || flag_val == "-extinction"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-resolution" => resolution = value,
+                    "-layer_height" => layer_height = value,
+                    "-min_height" => min_height = value,
+                    "-max_height" => max_height = value,
+                    _ => extinction = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        for file in [&mut output_file, &mut profile_file, &mut voxels_file].iter_mut() {
+            if !file.is_empty() && !file.contains(&sep) && !file.contains("/") {
+                **file = format!("{}{}", working_directory, file);
+            }
+        }
+
+        if resolution <= 0f64 || layer_height <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The column size and layer height must be greater than zero.",
+            ));
+        }
+        if extinction <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The extinction coefficient must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = LasFile::new(&input_file, "r")?;
+        let n_points = input.header.number_of_points as usize;
+
+        let start = Instant::now();
+
+        // Voxelize the points above the minimum height, measuring heights from the bottom of the
+        // profile, and count the returns below the profile in each column.
+        let mut grid = VoxelGrid::new(resolution, layer_height);
+        let mut below: HashMap<(isize, isize), usize> = HashMap::new();
+        for i in 0..n_points {
+            let mut p: PointData = input[i];
+            if p.withheld() || p.is_classified_noise() || p.z > max_height {
+                continue;
+            }
+            if p.z < min_height {
+                let key = grid.key(p.x, p.y, 0f64);
+                *below.entry((key.0, key.1)).or_insert(0) += 1;
+            } else {
+                p.z -= min_height;
+                grid.add_point(&p);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (n_points - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Voxelizing points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut columns = grid.columns();
+        for &c in below.keys() {
+            columns.push(c);
+        }
+        columns.sort();
+        columns.dedup();
+        if columns.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any points within the height range.",
+            ));
+        }
+        let max_layer = match grid.key_range() {
+            Some((_, max)) => max.2,
+            None => 0,
+        };
+        let num_layers = (max_layer + 1) as usize;
+
+        // Estimate the LAD profile of each column.
+        let num_columns = columns.len();
+        let mut profiles: Vec<Vec<f64>> = Vec::with_capacity(num_columns);
+        for (c, &(col, row)) in columns.iter().enumerate() {
+            let counts = grid.column_profile(col, row, 0, max_layer);
+            let top = counts
+                .iter()
+                .rposition(|&n| n > 0)
+                .map(|t| t + 1)
+                .unwrap_or(0);
+            let lad = leaf_area_density_profile(
+                &counts[0..top],
+                *below.get(&(col, row)).unwrap_or(&0),
+                layer_height,
+                extinction,
+            );
+            profiles.push(lad);
+            if verbose {
+                progress = (100.0_f64 * c as f64 / (num_columns - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Estimating LAD: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Output the LAI raster.
+        let min_col = columns.iter().map(|c| c.0).min().unwrap();
+        let max_col = columns.iter().map(|c| c.0).max().unwrap();
+        let min_row = columns.iter().map(|c| c.1).min().unwrap();
+        let max_row = columns.iter().map(|c| c.1).max().unwrap();
+        let rows = (max_row - min_row + 1) as usize;
+        let cols = (max_col - min_col + 1) as usize;
+        let west = min_col as f64 * resolution;
+        let north = (max_row + 1) as f64 * resolution;
+        let nodata = -32768.0f64;
+        let mut lai = vec![nodata; rows * cols];
+        for (c, &(col, row)) in columns.iter().enumerate() {
+            let k = (max_row - row) as usize * cols + (col - min_col) as usize;
+            lai[k] = profiles[c].iter().fold(0f64, |sum, lad| sum + lad) * layer_height;
+        }
+
+        let mut configs = RasterConfigs {
+            ..Default::default()
+        };
+        configs.rows = rows;
+        configs.columns = cols;
+        configs.north = north;
+        configs.south = north - rows as f64 * resolution;
+        configs.east = west + cols as f64 * resolution;
+        configs.west = west;
+        configs.resolution_x = resolution;
+        configs.resolution_y = resolution;
+        configs.nodata = nodata;
+        configs.data_type = DataType::F32;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        configs.coordinate_ref_system_wkt = input.get_wkt();
+
+        let mut output = Raster::initialize_using_config(&output_file, &configs);
+        for row in 0..rows {
+            output.set_row_data(row as isize, lai[row * cols..(row + 1) * cols].to_vec());
+        }
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Layer height: {}", layer_height));
+        output.add_metadata_entry(format!("Minimum height: {}", min_height));
+        output.add_metadata_entry(format!("Extinction coefficient: {}", extinction));
+
+        if !profile_file.is_empty() {
+            let f = File::create(&profile_file)?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all("HEIGHT_BOTTOM,HEIGHT_TOP,MEAN_LAD,STDEV_LAD\n".as_bytes())?;
+            for layer in 0..num_layers {
+                let values: Vec<f64> = profiles
+                    .iter()
+                    .map(|p| if layer < p.len() { p[layer] } else { 0f64 })
+                    .collect();
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+                writer.write_all(
+                    format!(
+                        "{},{},{:.6},{:.6}\n",
+                        min_height + layer as f64 * layer_height,
+                        min_height + (layer + 1) as f64 * layer_height,
+                        mean,
+                        variance.sqrt()
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            let _ = writer.flush();
+        }
+
+        if !voxels_file.is_empty() {
+            let f = File::create(&voxels_file)?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all("X,Y,Z,LAD\n".as_bytes())?;
+            for (c, &(col, row)) in columns.iter().enumerate() {
+                for (layer, lad) in profiles[c].iter().enumerate() {
+                    let (x, y, z) = grid.centre((col, row, layer as isize));
+                    writer.write_all(
+                        format!("{:.3},{:.3},{:.3},{:.6}\n", x, y, z + min_height, lad).as_bytes(),
+                    )?;
+                }
+            }
+            let _ = writer.flush();
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
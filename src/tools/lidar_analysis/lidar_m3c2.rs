@@ -0,0 +1,898 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::Matrix3;
+use crate::raster::*;
+use crate::structures::{DistanceMetric, FixedRadiusSearch3D};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::collections::HashSet;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool measures the change between two LiDAR point clouds of the same area (`--input1`, the
+/// earlier survey, and `--input2`, the later survey) using the Multiscale Model to Model Cloud
+/// Comparison (M3C2) method of Lague et al. (2013). Unlike a DEM of difference, which measures
+/// vertical change between two gridded surfaces, M3C2 measures the distance between the two point
+/// clouds directly, along the local surface normal, and is therefore well suited to measuring change
+/// on steep and overhanging surfaces, such as landslide scarps, cliffs and riverbanks.
+///
+/// The distance is measured at a set of core points, which are the points of the first point cloud
+/// thinned to a minimum spacing (`--core_spacing`; all points are used if the spacing is zero). At
+/// each core point, the surface normal is estimated by fitting a plane to the points of the first
+/// point cloud within the normal radius (`--normal_radius`), i.e. the normal scale, as in the
+/// `NormalVectors` tool. Normals are oriented upwards. Alternatively, vertical normals can be used
+/// (`--vertical`), which yields a point-based equivalent of a DEM of difference. The points of each
+/// point cloud within a cylinder centred on the core point and aligned with the normal, with a radius
+/// of `--projection_radius` and extending a distance of `--max_depth` on either side of the core
+/// point, are then projected onto the cylinder axis. The M3C2 distance is the difference between the
+/// mean positions of the second and first point clouds along the axis; positive values indicate that
+/// the surface has moved in the direction of the normal, e.g. deposition on upward-facing surfaces.
+///
+/// Each distance is accompanied by a level of detection at the 95% confidence level (LoD95),
+/// calculated from the local roughness of each point cloud, i.e. the standard deviation of the
+/// projected positions (&sigma;<sub>1</sub>, &sigma;<sub>2</sub>), the number of points in the
+/// cylinder (n<sub>1</sub>, n<sub>2</sub>), and the registration error between the surveys
+/// (`--registration_error`):
+///
+/// > LoD95 = &plusmn;1.96 (sqrt(&sigma;<sub>1</sub><sup>2</sup> / n<sub>1</sub> + &sigma;<sub>2</sub><sup>2</sup> / n<sub>2</sub>) + reg)
+///
+/// A change is considered significant when the absolute distance exceeds the LoD95. The distance is
+/// only calculated where each point cloud has at least five points within the cylinder.
+///
+/// The output (`--output`) is a vector points file of the core points with the attributes DIST
+/// (M3C2 distance), LOD95, SIG (1 for significant change, 0 otherwise), N1 and N2 (number of points),
+/// ROUGH1 and ROUGH2 (roughness), Z (core point elevation), and NX, NY and NZ (normal vector). The
+/// distances can optionally be gridded into a raster (`--output_raster`) with a grid resolution
+/// of `--resolution`, in which each cell contains the mean distance of the core points within it.
+/// If the `--significant_only` flag is specified, core points with non-significant change are
+/// assigned a distance of zero in the raster.
+///
+/// # Reference
+/// Lague, D., Brodu, N., and Leroux, J. (2013). Accurate 3D comparison of complex topography with
+/// terrestrial laser scanner: Application to the Rangitikei canyon (N-Z). ISPRS Journal of
+/// Photogrammetry and Remote Sensing, 82, 10-26.
+///
+/// # See Also
+/// `NormalVectors`, `FlightlineAlignment`
+pub struct LidarM3C2 {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarM3C2 {
+    pub fn new() -> LidarM3C2 {
+        // public constructor
+        let name = "LidarM3C2".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Measures the change between two point clouds along local surface normals using the M3C2 method.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File (Earlier Survey)".to_owned(),
+            flags: vec!["--i1".to_owned(), "--input1".to_owned()],
+            description: "Input LiDAR file of the earlier survey.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File (Later Survey)".to_owned(),
+            flags: vec!["--i2".to_owned(), "--input2".to_owned()],
+            description: "Input LiDAR file of the later survey.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Core Points File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector points file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Raster File (optional)".to_owned(),
+            flags: vec!["--output_raster".to_owned()],
+            description: "Optional output raster of the gridded M3C2 distances.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Normal Radius".to_owned(),
+            flags: vec!["--normal_radius".to_owned()],
+            description: "Radius of the neighbourhood used to estimate surface normals.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Projection Radius".to_owned(),
+            flags: vec!["--projection_radius".to_owned()],
+            description: "Radius of the projection cylinder.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Depth".to_owned(),
+            flags: vec!["--max_depth".to_owned()],
+            description: "Half-length of the projection cylinder, i.e. the maximum distance that can be measured.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Core Point Spacing".to_owned(),
+            flags: vec!["--core_spacing".to_owned()],
+            description: "Minimum spacing of the core points; all points of the first point cloud are used if zero.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Registration Error".to_owned(),
+            flags: vec!["--registration_error".to_owned()],
+            description: "Registration error between the two point clouds.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Use vertical normals?".to_owned(),
+            flags: vec!["--vertical".to_owned()],
+            description: "Measure change along vertical normals rather than local surface normals."
+                .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Significant change only in raster?".to_owned(),
+            flags: vec!["--significant_only".to_owned()],
+            description: "Assign a distance of zero to core points with non-significant change in the output raster.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --i1=survey2018.las --i2=survey2020.las -o=m3c2.shp --output_raster=m3c2.tif --resolution=1.0 --normal_radius=2.0 --projection_radius=1.0 --registration_error=0.03", short_exe, name).replace("*", &sep);
+
+        LidarM3C2 {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarM3C2 {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file1 = String::new();
+        let mut input_file2 = String::new();
+        let mut output_file = String::new();
+        let mut raster_file = String::new();
+        let mut grid_res = 1f64;
+        let mut normal_radius = 2f64;
+        let mut projection_radius = 1f64;
+        let mut max_depth = 5f64;
+        let mut core_spacing = 1f64;
+        let mut registration_error = 0f64;
+        let mut vertical_normals = false;
+        let mut significant_only = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i1" || flag_val == "-input1" {
+                input_file1 = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-i2" || flag_val == "-input2" {
+                input_file2 = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_raster" {
+                raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-vertical" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    vertical_normals = true;
+                }
+            } else if flag_val == "-significant_only" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    significant_only = true;
+                }
+            } else if flag_val == "-resolution"
+                || flag_val == "-normal_radius"
+                || flag_val == "-projection_radius"
+                || flag_val == "-max_depth"
+                || flag_val == "-core_spacing"
+                || flag_val == "-registration_error"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-resolution" => grid_res = value,
+                    "-normal_radius" => normal_radius = value,
+                    "-projection_radius" => projection_radius = value,
+                    "-max_depth" => max_depth = value,
+                    "-core_spacing" => core_spacing = value,
+                    _ => registration_error = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        if !input_file1.contains(sep) && !input_file1.contains("/") {
+            input_file1 = format!("{}{}", working_directory, input_file1);
+        }
+        if !input_file2.contains(sep) && !input_file2.contains("/") {
+            input_file2 = format!("{}{}", working_directory, input_file2);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !raster_file.is_empty() && !raster_file.contains(sep) && !raster_file.contains("/") {
+            raster_file = format!("{}{}", working_directory, raster_file);
+        }
+
+        if normal_radius <= 0f64 || projection_radius <= 0f64 || max_depth <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The normal radius, projection radius and maximum depth must be greater than zero.",
+            ));
+        }
+        if !raster_file.is_empty() && grid_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS files...");
+        }
+        let mut input1 = LasFile::new(&input_file1, "r")?;
+        let input2 = LasFile::new(&input_file2, "r")?;
+
+        let start = Instant::now();
+
+        let read_points = |input: &LasFile| -> Vec<[f64; 3]> {
+            let n_points = input.header.number_of_points as usize;
+            let mut points = Vec::with_capacity(n_points);
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if !p.withheld() && !p.is_classified_noise() {
+                    points.push([p.x, p.y, p.z]);
+                }
+            }
+            points
+        };
+        let points1 = read_points(&input1);
+        let points2 = read_points(&input2);
+        if points1.is_empty() || points2.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must contain points.",
+            ));
+        }
+
+        // Select the core points, thinning the first point cloud to the core point spacing.
+        let core_points: Vec<usize> = if core_spacing > 0f64 {
+            let mut occupied = HashSet::new();
+            let mut core_points = vec![];
+            for (i, p) in points1.iter().enumerate() {
+                let key = (
+                    (p[0] / core_spacing).floor() as i64,
+                    (p[1] / core_spacing).floor() as i64,
+                    (p[2] / core_spacing).floor() as i64,
+                );
+                if occupied.insert(key) {
+                    core_points.push(i);
+                }
+            }
+            core_points
+        } else {
+            (0..points1.len()).collect()
+        };
+        let num_core_points = core_points.len();
+        if verbose {
+            println!("Number of core points: {}", num_core_points);
+        }
+
+        let mut frs_normal =
+            FixedRadiusSearch3D::new(normal_radius, DistanceMetric::SquaredEuclidean);
+        if !vertical_normals {
+            for (i, p) in points1.iter().enumerate() {
+                frs_normal.insert(p[0], p[1], p[2], i);
+            }
+        }
+        let frs1 = cylinder_search(&points1, projection_radius);
+        let frs2 = cylinder_search(&points2, projection_radius);
+
+        let points1 = Arc::new(points1);
+        let points2 = Arc::new(points2);
+        let core_points = Arc::new(core_points);
+        let frs_normal = Arc::new(frs_normal);
+        let frs1 = Arc::new(frs1);
+        let frs2 = Arc::new(frs2);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let points1 = points1.clone();
+            let points2 = points2.clone();
+            let core_points = core_points.clone();
+            let frs_normal = frs_normal.clone();
+            let frs1 = frs1.clone();
+            let frs2 = frs2.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for c in (0..num_core_points).filter(|c| c % num_procs == tid) {
+                    let core = points1[core_points[c]];
+                    let normal = if vertical_normals {
+                        Some([0f64, 0f64, 1f64])
+                    } else {
+                        let neighbours: Vec<[f64; 3]> = frs_normal
+                            .search(core[0], core[1], core[2])
+                            .iter()
+                            .map(|&(j, _)| points1[j])
+                            .collect();
+                        surface_normal(&neighbours)
+                    };
+                    let result = match normal {
+                        Some(n) => {
+                            let s1 = cylinder_statistics(
+                                &points1,
+                                &frs1,
+                                core,
+                                n,
+                                projection_radius,
+                                max_depth,
+                            );
+                            let s2 = cylinder_statistics(
+                                &points2,
+                                &frs2,
+                                core,
+                                n,
+                                projection_radius,
+                                max_depth,
+                            );
+                            if s1.0 >= MIN_CYLINDER_POINTS && s2.0 >= MIN_CYLINDER_POINTS {
+                                let dist = s2.1 - s1.1;
+                                let lod = 1.96f64
+                                    * ((s1.2 * s1.2 / s1.0 as f64 + s2.2 * s2.2 / s2.0 as f64)
+                                        .sqrt()
+                                        + registration_error);
+                                Some(M3C2Result {
+                                    core: core,
+                                    normal: n,
+                                    distance: dist,
+                                    lod: lod,
+                                    n1: s1.0,
+                                    n2: s2.0,
+                                    roughness1: s1.2,
+                                    roughness2: s2.2,
+                                })
+                            } else {
+                                None
+                            }
+                        }
+                        None => None,
+                    };
+                    tx.send((c, result)).unwrap();
+                }
+            });
+        }
+
+        let mut results: Vec<Option<M3C2Result>> = vec![None; num_core_points];
+        for i in 0..num_core_points {
+            let (c, result) = rx.recv().expect("Error receiving data from thread.");
+            results[c] = result;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (num_core_points - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Output the core points.
+        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        output.projection = input1.get_wkt();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 9u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("DIST", FieldDataType::Real, 12u8, 4u8));
+        output.attributes.add_field(&AttributeField::new(
+            "LOD95",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        output
+            .attributes
+            .add_field(&AttributeField::new("SIG", FieldDataType::Int, 1u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("N1", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("N2", FieldDataType::Int, 7u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "ROUGH1",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "ROUGH2",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        output
+            .attributes
+            .add_field(&AttributeField::new("Z", FieldDataType::Real, 12u8, 3u8));
+        for name in ["NX", "NY", "NZ"].iter() {
+            output
+                .attributes
+                .add_field(&AttributeField::new(name, FieldDataType::Real, 8u8, 5u8));
+        }
+
+        let mut fid = 0;
+        let mut num_significant = 0;
+        for result in results.iter() {
+            if let Some(r) = result {
+                fid += 1;
+                let significant = r.distance.abs() > r.lod;
+                if significant {
+                    num_significant += 1;
+                }
+                output.add_point_record(r.core[0], r.core[1]);
+                output.attributes.add_record(
+                    vec![
+                        FieldData::Int(fid),
+                        FieldData::Real(r.distance),
+                        FieldData::Real(r.lod),
+                        FieldData::Int(significant as i32),
+                        FieldData::Int(r.n1 as i32),
+                        FieldData::Int(r.n2 as i32),
+                        FieldData::Real(r.roughness1),
+                        FieldData::Real(r.roughness2),
+                        FieldData::Real(r.core[2]),
+                        FieldData::Real(r.normal[0]),
+                        FieldData::Real(r.normal[1]),
+                        FieldData::Real(r.normal[2]),
+                    ],
+                    false,
+                );
+            }
+        }
+        if verbose {
+            println!(
+                "Distances measured at {} core points, of which {} show significant change.",
+                fid, num_significant
+            );
+            println!("Saving data...")
+        };
+        output.write()?;
+
+        if !raster_file.is_empty() {
+            let west = input1.header.min_x;
+            let north = input1.header.max_y;
+            let rows = (((north - input1.header.min_y) / grid_res).ceil() as usize).max(1);
+            let columns = (((input1.header.max_x - west) / grid_res).ceil() as usize).max(1);
+            let nodata = -32768.0f64;
+
+            let mut configs = RasterConfigs {
+                ..Default::default()
+            };
+            configs.rows = rows;
+            configs.columns = columns;
+            configs.north = north;
+            configs.south = north - rows as f64 * grid_res;
+            configs.east = west + columns as f64 * grid_res;
+            configs.west = west;
+            configs.resolution_x = grid_res;
+            configs.resolution_y = grid_res;
+            configs.nodata = nodata;
+            configs.data_type = DataType::F32;
+            configs.photometric_interp = PhotometricInterpretation::Continuous;
+            configs.coordinate_ref_system_wkt = input1.get_wkt();
+
+            let mut sum = vec![0f64; rows * columns];
+            let mut count = vec![0usize; rows * columns];
+            for result in results.iter() {
+                if let Some(r) = result {
+                    let col = (((r.core[0] - west) / grid_res).floor() as usize).min(columns - 1);
+                    let row = (((north - r.core[1]) / grid_res).floor() as usize).min(rows - 1);
+                    let value = if significant_only && r.distance.abs() <= r.lod {
+                        0f64
+                    } else {
+                        r.distance
+                    };
+                    sum[row * columns + col] += value;
+                    count[row * columns + col] += 1;
+                }
+            }
+
+            let mut output = Raster::initialize_using_config(&raster_file, &configs);
+            for row in 0..rows {
+                let data: Vec<f64> = (0..columns)
+                    .map(|col| {
+                        let k = row * columns + col;
+                        if count[k] > 0 {
+                            sum[k] / count[k] as f64
+                        } else {
+                            nodata
+                        }
+                    })
+                    .collect();
+                output.set_row_data(row as isize, data);
+            }
+
+            let elapsed_time = get_formatted_elapsed_time(start);
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("Input file 1: {}", input_file1));
+            output.add_metadata_entry(format!("Input file 2: {}", input_file2));
+            output.add_metadata_entry(format!("Normal radius: {}", normal_radius));
+            output.add_metadata_entry(format!("Projection radius: {}", projection_radius));
+            output.add_metadata_entry(format!("Registration error: {}", registration_error));
+            output.add_metadata_entry(format!("Significant change only: {}", significant_only));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            output.write()?;
+        }
+
+        if verbose {
+            let elapsed_time = get_formatted_elapsed_time(start);
+            println!("Output file(s) written");
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The minimum number of points of each point cloud within the projection cylinder.
+const MIN_CYLINDER_POINTS: usize = 5;
+
+#[derive(Clone, Copy)]
+struct M3C2Result {
+    core: [f64; 3],
+    normal: [f64; 3],
+    distance: f64,
+    lod: f64,
+    n1: usize,
+    n2: usize,
+    roughness1: f64,
+    roughness2: f64,
+}
+
+// Estimates the unit normal of the plane best fitting a set of points, oriented upwards.
+fn surface_normal(points: &[[f64; 3]]) -> Option<[f64; 3]> {
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mut c = [0f64; 3];
+    for p in points {
+        for j in 0..3 {
+            c[j] += p[j] / n;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        for r in 0..3 {
+            for k in 0..3 {
+                cov[(r, k)] += d[r] * d[k];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut m = 0;
+    for j in 1..3 {
+        if eig.eigenvalues[j] < eig.eigenvalues[m] {
+            m = j;
+        }
+    }
+    let v = eig.eigenvectors.column(m);
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len == 0f64 || !len.is_finite() {
+        return None;
+    }
+    let sign = if v[2] < 0f64 { -1f64 } else { 1f64 };
+    Some([sign * v[0] / len, sign * v[1] / len, sign * v[2] / len])
+}
+
+// Returns the search structure used to find the points within projection cylinders of the
+// given radius. The cylinders are searched with spheres spaced at the cylinder radius along
+// the axis, and a point on the edge of the cylinder, halfway between two sphere centres, is
+// sqrt(1.25) radii from both, so the spheres must be larger than the cylinder.
+fn cylinder_search(points: &[[f64; 3]], radius: f64) -> FixedRadiusSearch3D<usize> {
+    let mut frs = FixedRadiusSearch3D::new(radius * 2f64.sqrt(), DistanceMetric::SquaredEuclidean);
+    for (i, p) in points.iter().enumerate() {
+        frs.insert(p[0], p[1], p[2], i);
+    }
+    frs
+}
+
+// Returns the number of points within the projection cylinder and the mean and standard
+// deviation of their positions along the cylinder axis. The search structure must be created
+// by `cylinder_search` with the same radius.
+fn cylinder_statistics(
+    points: &[[f64; 3]],
+    frs: &FixedRadiusSearch3D<usize>,
+    core: [f64; 3],
+    normal: [f64; 3],
+    radius: f64,
+    max_depth: f64,
+) -> (usize, f64, f64) {
+    // Search a series of spheres, spaced at the cylinder radius, along the axis.
+    let num_steps = (max_depth / radius).ceil() as isize;
+    let mut candidates = vec![];
+    for k in -num_steps..=num_steps {
+        let t = (k as f64 * radius).max(-max_depth).min(max_depth);
+        for (j, _) in frs.search(
+            core[0] + t * normal[0],
+            core[1] + t * normal[1],
+            core[2] + t * normal[2],
+        ) {
+            candidates.push(j);
+        }
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let radius_sqrd = radius * radius;
+    let mut distances = vec![];
+    for j in candidates {
+        let v = [
+            points[j][0] - core[0],
+            points[j][1] - core[1],
+            points[j][2] - core[2],
+        ];
+        let along = v[0] * normal[0] + v[1] * normal[1] + v[2] * normal[2];
+        let radial_sqrd = v[0] * v[0] + v[1] * v[1] + v[2] * v[2] - along * along;
+        if along.abs() <= max_depth && radial_sqrd <= radius_sqrd {
+            distances.push(along);
+        }
+    }
+    let n = distances.len();
+    if n == 0 {
+        return (0, 0f64, 0f64);
+    }
+    let mean = distances.iter().sum::<f64>() / n as f64;
+    let variance = if n > 1 {
+        distances
+            .iter()
+            .map(|d| (d - mean) * (d - mean))
+            .sum::<f64>()
+            / (n - 1) as f64
+    } else {
+        0f64
+    };
+    (n, mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cylinder_search, cylinder_statistics, surface_normal};
+    use crate::structures::{DistanceMetric, FixedRadiusSearch3D};
+
+    #[test]
+    fn test_m3c2_parallel_planes() {
+        // two parallel planes sloping at z = 0.5x, the second offset 0.4 along the plane normal
+        let len = 1.25f64.sqrt();
+        let expected_normal = [-0.5 / len, 0.0, 1.0 / len];
+        let offset = 0.4;
+        let mut points1 = vec![];
+        let mut points2 = vec![];
+        for i in 0..40 {
+            for j in 0..40 {
+                let (x, y) = (i as f64 * 0.25, j as f64 * 0.25);
+                points1.push([x, y, 0.5 * x]);
+                points2.push([
+                    x + offset * expected_normal[0],
+                    y,
+                    0.5 * x + offset * expected_normal[2],
+                ]);
+            }
+        }
+        let mut frs_normal = FixedRadiusSearch3D::new(1.0, DistanceMetric::SquaredEuclidean);
+        for (i, p) in points1.iter().enumerate() {
+            frs_normal.insert(p[0], p[1], p[2], i);
+        }
+        let frs1 = cylinder_search(&points1, 1.0);
+        let frs2 = cylinder_search(&points2, 1.0);
+
+        let core = [5.0, 5.0, 2.5];
+        let neighbours: Vec<[f64; 3]> = frs_normal
+            .search(core[0], core[1], core[2])
+            .iter()
+            .map(|&(j, _)| points1[j])
+            .collect();
+        let normal = surface_normal(&neighbours).unwrap();
+        for k in 0..3 {
+            assert!((normal[k] - expected_normal[k]).abs() < 1e-9);
+        }
+
+        let (n1, mean1, stdev1) = cylinder_statistics(&points1, &frs1, core, normal, 1.0, 2.0);
+        let (n2, mean2, stdev2) = cylinder_statistics(&points2, &frs2, core, normal, 1.0, 2.0);
+        assert!(n1 > 0 && n2 > 0);
+        assert!(mean1.abs() < 1e-9 && stdev1 < 1e-9);
+        assert!((mean2 - mean1 - offset).abs() < 1e-9 && stdev2 < 1e-9);
+
+        // the second plane is beyond a cylinder depth shorter than the offset
+        let (n2, _, _) = cylinder_statistics(&points2, &frs2, core, normal, 1.0, 0.3);
+        assert_eq!(n2, 0);
+    }
+
+    #[test]
+    fn test_m3c2_cylinder_edge() {
+        // points near the edge of a vertical cylinder of radius 1, halfway between the centres
+        // of the search spheres, which are at whole-number heights, and points just outside it
+        let mut points = vec![];
+        for &z in &[-1.5, -0.5, 0.5, 1.5] {
+            for k in 0..8 {
+                let a = k as f64 * std::f64::consts::PI / 4.0;
+                points.push([0.95 * a.cos(), 0.95 * a.sin(), z]);
+                points.push([1.05 * a.cos(), 1.05 * a.sin(), z]);
+            }
+        }
+        points.push([0.0, 0.0, 3.0]); // beyond the cylinder depth
+        let frs = cylinder_search(&points, 1.0);
+        let (n, mean, stdev) =
+            cylinder_statistics(&points, &frs, [0.0, 0.0, 0.0], [0.0, 0.0, 1.0], 1.0, 2.0);
+        assert_eq!(n, 32);
+        assert!(mean.abs() < 1e-9);
+        // the sample standard deviation of 8 points at each of the heights +/-0.5 and +/-1.5
+        let expected = (8.0 * (2.0 * 0.25 + 2.0 * 2.25) / 31.0f64).sqrt();
+        assert!((stdev - expected).abs() < 1e-9);
+    }
+}
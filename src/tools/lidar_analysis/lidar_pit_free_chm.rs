@@ -0,0 +1,817 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::triangulate;
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{Array2D, BoundingBox, Point2D};
+use crate::tools::*;
+use num_cpus;
+use std::io::{Error, ErrorKind};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::{env, f64, fs, path, thread};
+
+/// This tool creates a pit-free canopy height model (CHM) from a height-normalized LiDAR point cloud. CHMs that
+/// are interpolated from first-return points, e.g. using the `LidarTINGridding` tool, commonly contain many
+/// pits, i.e. abrupt drops in canopy height within tree crowns, caused by laser pulses that penetrate deep into
+/// the canopy before registering their first return. These pits are problematic for many applications, such as
+/// individual tree detection.
+///
+/// By default, the tool uses the layered-TIN approach of Khosravipour et al. (2014). A triangular irregular
+/// network (TIN) is fitted to the first-return points that are at or above each of a series of height
+/// thresholds (`--height_thresholds`) and each TIN is rasterized. With the exception of the lowest layer,
+/// triangles with an edge longer than the maximum triangle edge length (`--max_triangle_edge_length`), or
+/// freeze distance, are excluded from the layer. Pits are removed because the points that cause them are
+/// excluded from the higher layers. The layers are then merged, with the output grid cell value being the
+/// maximum of the layers.
+///
+/// When the `--spike_free` flag is specified, the tool instead uses a variant of the spike-free algorithm of
+/// Khosravipour et al. (2016), which does not require height thresholds. Points are considered in order of
+/// descending height and are added to the TIN, unless they are located within a triangle of the current TIN
+/// that has no edges longer than the freeze distance and they are lower than the TIN surface by more than the
+/// insertion buffer (`--insertion_buffer`). For efficiency, points are added in height slices equal in
+/// thickness to the insertion buffer and the TIN surface is evaluated at the grid resolution.
+///
+/// The input file must be height-normalized, e.g. using the `HeightAboveGround` tool. Only first-return
+/// points are used and withheld and noise points are ignored. If an input file is not specified, all of the
+/// LAS files (*.las, *.zip) within the working directory will be processed and adjacent tiles will be used to
+/// avoid edge effects.
+///
+/// # References
+/// Khosravipour, A., Skidmore, A. K., Isenburg, M., Wang, T., and Hussin, Y. A. (2014). Generating pit-free
+/// canopy height models from airborne lidar. *Photogrammetric Engineering & Remote Sensing*, 80(9), 863-872.
+///
+/// Khosravipour, A., Skidmore, A. K., and Isenburg, M. (2016). Generating spike-free digital surface models
+/// using LiDAR raw point clouds: A new approach for forestry applications. *International Journal of Applied
+/// Earth Observation and Geoinformation*, 52, 104-114.
+///
+/// # See Also
+/// `LidarTINGridding`, `HeightAboveGround`, `IndividualTreeDetection`
+pub struct LidarPitFreeCHM {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarPitFreeCHM {
+    pub fn new() -> LidarPitFreeCHM {
+        // public constructor
+        let name = "LidarPitFreeCHM".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Creates a pit-free canopy height model from height-normalized LiDAR points using layered or spike-free TINs.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (including extension).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Thresholds".to_owned(),
+            flags: vec!["--height_thresholds".to_owned()],
+            description: "Comma-separated list of layer height thresholds (m).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("0,2,5,10,15,20,25,30,35,40,45,50".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Triangle Edge Length".to_owned(),
+            flags: vec!["--max_triangle_edge_length".to_owned()],
+            description: "Maximum triangle edge length, or freeze distance (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Use spike-free mode?".to_owned(),
+            flags: vec!["--spike_free".to_owned()],
+            description: "Use the spike-free algorithm rather than layered TINs.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Insertion Buffer".to_owned(),
+            flags: vec!["--insertion_buffer".to_owned()],
+            description: "Insertion buffer (m) used in spike-free mode.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=chm.tif --resolution=0.5 --height_thresholds='0,2,5,10,15,20,25' --max_triangle_edge_length=1.5
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=chm.tif --resolution=0.5 --spike_free --max_triangle_edge_length=1.5 --insertion_buffer=0.3", short_exe, name).replace("*", &sep);
+
+        LidarPitFreeCHM {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarPitFreeCHM {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut grid_res = 0.5f64;
+        let mut thresholds_str = "0,2,5,10,15,20,25,30,35,40,45,50".to_string();
+        let mut max_edge_length = 1f64;
+        let mut spike_free = false;
+        let mut insertion_buffer = 0.5f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-height_thresholds" {
+                thresholds_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-max_triangle_edge_length" {
+                max_edge_length = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-spike_free" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    spike_free = true;
+                }
+            } else if flag_val == "-insertion_buffer" {
+                insertion_buffer = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let start = Instant::now();
+
+        let mut thresholds = vec![];
+        for s in thresholds_str.split(",") {
+            if !s.trim().is_empty() {
+                thresholds.push(
+                    s.trim()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing height threshold '{}'", s.trim())),
+                );
+            }
+        }
+        thresholds.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        thresholds.dedup();
+        if !spike_free && thresholds.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one height threshold must be specified.",
+            ));
+        }
+        if grid_res <= 0f64 || max_edge_length <= 0f64 || insertion_buffer <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution, maximum triangle edge length and insertion buffer must be greater than zero.",
+            ));
+        }
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        outputs.push(default_output_file(&s));
+                        inputs.push(s);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
+                input_file = format!("{}{}", working_directory, input_file);
+            }
+            inputs.push(input_file.clone());
+            if output_file.is_empty() {
+                output_file = default_output_file(&input_file);
+            }
+            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
+                output_file = format!("{}{}", working_directory, output_file);
+            }
+            outputs.push(output_file);
+        }
+
+        /*
+        Points from adjacent tiles within a buffer of the tile edges are included in the
+        triangulations so that there are no edge effects.
+        */
+        let buffer = 2f64 * max_edge_length.max(grid_res);
+        let mut bounding_boxes = vec![];
+        for in_file in &inputs {
+            let header = LasHeader::read_las_header(&in_file.replace("\"", ""))?;
+            bounding_boxes.push(BoundingBox {
+                min_x: header.min_x,
+                max_x: header.max_x,
+                min_y: header.min_y,
+                max_y: header.max_y,
+            });
+        }
+
+        if verbose {
+            println!("Creating canopy height models...");
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let outputs = Arc::new(outputs);
+        let bounding_boxes = Arc::new(bounding_boxes);
+        let thresholds = Arc::new(thresholds);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..num_procs {
+            let inputs = inputs.clone();
+            let outputs = outputs.clone();
+            let bounding_boxes = bounding_boxes.clone();
+            let tile_list = tile_list.clone();
+            let thresholds = thresholds.clone();
+            let tool_name = self.get_tool_name();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                'tiles: while tile < num_tiles {
+                    // Get the next tile up for processing
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to process
+                    };
+                    let start_run = Instant::now();
+                    let input_file = inputs[tile].replace("\"", "");
+                    let output_file = outputs[tile].replace("\"", "");
+
+                    // Expand the bounding box to include the areas of overlap
+                    let bb = BoundingBox {
+                        min_x: bounding_boxes[tile].min_x - buffer,
+                        max_x: bounding_boxes[tile].max_x + buffer,
+                        min_y: bounding_boxes[tile].min_y - buffer,
+                        max_y: bounding_boxes[tile].max_y + buffer,
+                    };
+
+                    if verbose && num_tiles == 1 {
+                        println!("Reading input LAS file...");
+                    }
+                    let mut points: Vec<Point2D> = vec![];
+                    let mut z_values: Vec<f64> = vec![];
+                    let mut wkt = String::new();
+                    for m in 0..inputs.len() {
+                        if bounding_boxes[m].overlaps(bb) {
+                            let mut input = match LasFile::new(&inputs[m].replace("\"", ""), "r") {
+                                Ok(lf) => lf,
+                                Err(err) => {
+                                    let msg = format!(
+                                        "Error reading file {}: {}",
+                                        inputs[m].replace("\"", ""),
+                                        err
+                                    );
+                                    tx.send((tile, Err(msg))).unwrap();
+                                    continue 'tiles;
+                                }
+                            };
+                            if m == tile {
+                                wkt = input.get_wkt();
+                            }
+                            let n_points = input.header.number_of_points as usize;
+                            for i in 0..n_points {
+                                let p: PointData = input[i];
+                                if !p.withheld()
+                                    && !p.is_classified_noise()
+                                    && p.is_early_return()
+                                    && bb.is_point_in_box(p.x, p.y)
+                                {
+                                    points.push(Point2D::new(p.x, p.y));
+                                    z_values.push(p.z);
+                                }
+                            }
+                        }
+                    }
+
+                    let west = bounding_boxes[tile].min_x;
+                    let north = bounding_boxes[tile].max_y;
+                    let rows = (((north - bounding_boxes[tile].min_y) / grid_res).ceil()) as isize;
+                    let columns =
+                        (((bounding_boxes[tile].max_x - west) / grid_res).ceil()) as isize;
+                    let nodata = -32768.0f64;
+                    let mut chm: Array2D<f64> =
+                        Array2D::new(rows, columns, nodata, nodata).expect("Error creating grid.");
+
+                    let max_edge_sqrd = max_edge_length * max_edge_length;
+                    if !spike_free {
+                        for layer in 0..thresholds.len() {
+                            if verbose && num_tiles == 1 {
+                                println!(
+                                    "Creating layer {} of {} (height threshold {})...",
+                                    layer + 1,
+                                    thresholds.len(),
+                                    thresholds[layer]
+                                );
+                            }
+                            let mut layer_points = vec![];
+                            let mut layer_z = vec![];
+                            for i in 0..points.len() {
+                                if z_values[i] >= thresholds[layer] {
+                                    layer_points.push(points[i]);
+                                    layer_z.push(z_values[i]);
+                                }
+                            }
+                            // The lowest layer is not constrained by the triangle edge length.
+                            let edge_limit = if layer == 0 {
+                                f64::INFINITY
+                            } else {
+                                max_edge_sqrd
+                            };
+                            rasterize_tin(
+                                &layer_points,
+                                &layer_z,
+                                edge_limit,
+                                west,
+                                north,
+                                grid_res,
+                                &mut chm,
+                            );
+                        }
+                    } else {
+                        let mut order: Vec<usize> = (0..points.len()).collect();
+                        order.sort_by(|a, b| z_values[*b].partial_cmp(&z_values[*a]).unwrap());
+                        let mut tin_points: Vec<Point2D> = vec![];
+                        let mut tin_z: Vec<f64> = vec![];
+                        let mut k = 0;
+                        while k < order.len() {
+                            // The frozen surface consists of the triangles of the current TIN that
+                            // have no edges longer than the freeze distance.
+                            let mut frozen: Array2D<f64> =
+                                Array2D::new(rows, columns, nodata, nodata)
+                                    .expect("Error creating grid.");
+                            rasterize_tin(
+                                &tin_points,
+                                &tin_z,
+                                max_edge_sqrd,
+                                west,
+                                north,
+                                grid_res,
+                                &mut frozen,
+                            );
+                            let slice_bottom = z_values[order[k]] - insertion_buffer;
+                            while k < order.len() && z_values[order[k]] > slice_bottom {
+                                let i = order[k];
+                                let row = ((north - points[i].y) / grid_res).floor() as isize;
+                                let col = ((points[i].x - west) / grid_res).floor() as isize;
+                                let zf = frozen.get_value(row, col);
+                                if zf == nodata || z_values[i] >= zf - insertion_buffer {
+                                    tin_points.push(points[i]);
+                                    tin_z.push(z_values[i]);
+                                }
+                                k += 1;
+                            }
+                        }
+                        rasterize_tin(
+                            &tin_points,
+                            &tin_z,
+                            f64::INFINITY,
+                            west,
+                            north,
+                            grid_res,
+                            &mut chm,
+                        );
+                    }
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows as usize;
+                    configs.columns = columns as usize;
+                    configs.north = north;
+                    configs.south = north - rows as f64 * grid_res;
+                    configs.east = west + columns as f64 * grid_res;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F32;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+                    configs.coordinate_ref_system_wkt = wkt;
+
+                    let mut output = Raster::initialize_using_config(&output_file, &configs);
+                    for row in 0..rows {
+                        output.set_row_data(row, chm.get_row_data(row));
+                    }
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+                    output.add_metadata_entry(format!(
+                        "Created by whitebox_tools\' {} tool",
+                        tool_name
+                    ));
+                    output.add_metadata_entry(format!("Input file: {}", input_file));
+                    output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                    if spike_free {
+                        output.add_metadata_entry(format!(
+                            "Spike-free mode; insertion buffer: {}",
+                            insertion_buffer
+                        ));
+                    } else {
+                        output.add_metadata_entry(format!("Height thresholds: {:?}", thresholds));
+                    }
+                    output.add_metadata_entry(format!(
+                        "Maximum triangle edge length: {}",
+                        max_edge_length
+                    ));
+                    output.add_metadata_entry(format!(
+                        "Elapsed Time (including I/O): {}",
+                        elapsed_time_run
+                    ));
+
+                    if verbose && num_tiles == 1 {
+                        println!("Saving data...")
+                    };
+                    let result = match output.write() {
+                        Ok(_) => Ok(()),
+                        Err(e) => Err(format!("Error writing {}: {}", output_file, e)),
+                    };
+                    tx.send((tile, result)).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for t in 0..num_tiles {
+            let (tile, result) = rx.recv().expect("Error receiving data from thread.");
+            if let Err(e) = result {
+                return Err(Error::new(ErrorKind::Other, e));
+            }
+            if verbose {
+                println!(
+                    "Finished {} ({} of {})",
+                    inputs[tile]
+                        .replace("\"", "")
+                        .replace(working_directory, ""),
+                    t + 1,
+                    num_tiles
+                );
+                progress = (100.0_f64 * (t + 1) as f64 / num_tiles as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the name of the CHM of an input LAS file, i.e. the input file name with its .las or
+/// .zip extension replaced by `_chm.tif`.
+fn default_output_file(input_file: &str) -> String {
+    let lower = input_file.to_lowercase();
+    if lower.ends_with(".las") || lower.ends_with(".zip") {
+        format!("{}_chm.tif", &input_file[..input_file.len() - 4])
+    } else {
+        format!("{}_chm.tif", input_file)
+    }
+}
+
+/// Rasterizes the TIN of a set of points into a grid, keeping the maximum of the existing
+/// and interpolated values. Triangles with a planimetric edge length whose square exceeds
+/// `max_edge_sqrd` are skipped.
+fn rasterize_tin(
+    points: &[Point2D],
+    z_values: &[f64],
+    max_edge_sqrd: f64,
+    west: f64,
+    north: f64,
+    grid_res: f64,
+    grid: &mut Array2D<f64>,
+) {
+    if points.len() < 3 {
+        return;
+    }
+    let result = match triangulate(points) {
+        Some(r) => r,
+        None => return, // e.g. all of the points are collinear
+    };
+    let nodata = grid.nodata();
+    let rows = grid.rows();
+    let columns = grid.columns();
+    for t in 0..result.triangles.len() / 3 {
+        let p1 = result.triangles[t * 3];
+        let p2 = result.triangles[t * 3 + 1];
+        let p3 = result.triangles[t * 3 + 2];
+        let (a, b, c) = (points[p1], points[p2], points[p3]);
+        let max_edge = a
+            .distance_squared(&b)
+            .max(b.distance_squared(&c))
+            .max(c.distance_squared(&a));
+        if max_edge > max_edge_sqrd {
+            continue;
+        }
+
+        // barycentric interpolation of the grid cell centres within the triangle
+        let det = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+        if det == 0f64 {
+            continue;
+        }
+        let top_row = (((north - a.y.max(b.y).max(c.y)) / grid_res - 0.5).floor() as isize).max(0);
+        let bottom_row =
+            (((north - a.y.min(b.y).min(c.y)) / grid_res - 0.5).ceil() as isize).min(rows - 1);
+        let left_col = (((a.x.min(b.x).min(c.x) - west) / grid_res - 0.5).floor() as isize).max(0);
+        let right_col =
+            (((a.x.max(b.x).max(c.x) - west) / grid_res - 0.5).ceil() as isize).min(columns - 1);
+        for row in top_row..=bottom_row {
+            let y = north - (row as f64 + 0.5) * grid_res;
+            for col in left_col..=right_col {
+                let x = west + (col as f64 + 0.5) * grid_res;
+                let l1 = ((b.y - c.y) * (x - c.x) + (c.x - b.x) * (y - c.y)) / det;
+                let l2 = ((c.y - a.y) * (x - c.x) + (a.x - c.x) * (y - c.y)) / det;
+                let l3 = 1f64 - l1 - l2;
+                if l1 < 0f64 || l2 < 0f64 || l3 < 0f64 {
+                    continue;
+                }
+                let z = l1 * z_values[p1] + l2 * z_values[p2] + l3 * z_values[p3];
+                let existing = grid.get_value(row, col);
+                if existing == nodata || z > existing {
+                    grid.set_value(row, col, z);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{default_output_file, rasterize_tin, LidarPitFreeCHM};
+    use crate::lidar::*;
+    use crate::raster::Raster;
+    use crate::structures::{Array2D, Point2D};
+    use crate::tools::WhiteboxTool;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_rasterize_tin_max_merge() {
+        let corners = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(4.0, 0.0),
+            Point2D::new(4.0, 4.0),
+            Point2D::new(0.0, 4.0),
+        ];
+        let mut grid: Array2D<f64> = Array2D::new(4, 4, -32768f64, -32768f64).unwrap();
+
+        // a layer with a large maximum edge length is skipped entirely
+        rasterize_tin(&corners, &[1.0, 1.0, 1.0, 1.0], 1.0, 0.0, 4.0, 1.0, &mut grid);
+        assert_eq!(grid.get_value(0, 0), -32768f64);
+
+        // a flat layer at z = 1 followed by a layer sloping from z = 0 (west) to z = 2 (east)
+        rasterize_tin(&corners, &[1.0, 1.0, 1.0, 1.0], 100.0, 0.0, 4.0, 1.0, &mut grid);
+        rasterize_tin(&corners, &[0.0, 2.0, 2.0, 0.0], 100.0, 0.0, 4.0, 1.0, &mut grid);
+        let expected = [1.0, 1.0, 1.25, 1.75];
+        for row in 0..4 {
+            for col in 0..4 {
+                assert!((grid.get_value(row, col) - expected[col as usize]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_output_file() {
+        assert_eq!(default_output_file("/data/tile.las"), "/data/tile_chm.tif");
+        assert_eq!(default_output_file("/data/tile.LAS"), "/data/tile_chm.tif");
+        assert_eq!(default_output_file("/data/tile.zip"), "/data/tile_chm.tif");
+        assert_eq!(
+            default_output_file("/data/las.tiles/a.ZIP"),
+            "/data/las.tiles/a_chm.tif"
+        );
+    }
+
+    #[test]
+    fn test_lidar_pit_free_chm_files() {
+        let dir = env::temp_dir().join("wbt_lidar_pit_free_chm");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        let run = |input: &str| {
+            LidarPitFreeCHM::new().run(
+                vec![
+                    format!("--input={}", path(input)),
+                    "--resolution=1.0".to_string(),
+                ],
+                "",
+                false,
+            )
+        };
+
+        // a 4 x 4 grid of points sloping from z = 0 to z = 3
+        for name in &["tile.las", "tile.zip"] {
+            let mut las = LasFile::new(&path(name), "w").unwrap();
+            let mut header = LasHeader::default();
+            header.project_id_used = true;
+            las.add_header(header);
+            for k in 0..16 {
+                las.add_point_record(LidarPointRecord::PointRecord0 {
+                    point_data: PointData {
+                        x: (k % 4) as f64,
+                        y: (k / 4) as f64,
+                        z: (k % 4) as f64,
+                        ..Default::default()
+                    },
+                });
+            }
+            las.write().unwrap();
+        }
+        let zip_size = fs::metadata(path("tile.zip")).unwrap().len();
+
+        // the CHM of a compressed tile is written next to it, without replacing it
+        run("tile.zip").unwrap();
+        assert_eq!(fs::metadata(path("tile.zip")).unwrap().len(), zip_size);
+        assert_eq!(
+            LasFile::new(&path("tile.zip"), "r")
+                .unwrap()
+                .header
+                .number_of_points,
+            16
+        );
+        let chm = Raster::new(&path("tile_chm.tif"), "r").unwrap();
+        assert_eq!((chm.configs.rows, chm.configs.columns), (3, 3));
+        assert!((chm.get_value(0, 2) - 2.5).abs() < 1e-6);
+
+        // an error reading the points of a tile is reported, rather than panicking
+        let mut bytes = fs::read(path("tile.las")).unwrap();
+        bytes.truncate(bytes.len() - 100);
+        fs::write(path("truncated.las"), bytes).unwrap();
+        assert!(run("truncated.las").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
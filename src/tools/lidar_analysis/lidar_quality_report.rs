@@ -0,0 +1,1489 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::rendering::html::*;
+use crate::rendering::Histogram;
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufWriter;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::process::Command;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// This tool performs quality assurance and quality control (QA/QC) of a LiDAR data delivery. It
+/// scans all of the LAS files (including zipped LAS files) in the working directory, or a single
+/// input file (`--input`), and produces an HTML report (`--output`) describing:
+///
+/// - the number of points, extent, point density and header properties of each tile;
+/// - the aggregate pulse density and its distribution, and the proportion of the area that meets
+///   the required density;
+/// - the number of points in each class;
+/// - the return statistics, i.e. the number of points by return number and number of returns, and
+///   the numbers of single, first, intermediate and last returns;
+/// - the voids within the coverage;
+/// - the proportion of the area covered by more than one flight line (overlap coverage); and
+/// - the consistency of the tiles, including their LAS versions, point formats, coordinate reference
+///   systems (CRS) and scale factors, and the agreement of each header with its point records.
+///
+/// Point density is measured as pulse density, i.e. the number of first returns per unit area,
+/// within the cells of a grid with a resolution of `--resolution` that spans all of the tiles.
+/// A cell complies with the density specification if its pulse density is at least `--min_density`.
+/// The grid resolution should be large enough for each cell to contain several pulses; a cell size
+/// of twice the nominal pulse spacing is commonly used. Optionally, the pulse density and the
+/// compliance (1 for compliant and 0 for non-compliant cells) can be output as rasters
+/// (`--density_raster` and `--compliance_raster`).
+///
+/// Voids are groups of connected grid cells within the tile extents that do not contain any points.
+/// Voids that are connected to the edge of the tile coverage are excluded, because they generally
+/// represent the irregular boundary of the survey area, as are voids smaller than `--min_void_area`.
+/// The void polygons can be output as a vector file (`--voids`). Voids are commonly caused by water
+/// bodies, which return few pulses, as well as by gaps between flight lines. Flight lines are
+/// identified using the point source ID.
+///
+/// The compressed LAZ format is not supported for point-level analysis. LAZ files are listed in the
+/// report, together with the information contained in their headers, but are otherwise excluded
+/// from the analysis.
+///
+/// # See Also
+/// `LidarInfo`, `LidarPointDensity`, `LidarTileFootprint`, `FlightlineOverlap`
+pub struct LidarQualityReport {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarQualityReport {
+    pub fn new() -> LidarQualityReport {
+        // public constructor
+        let name = "LidarQualityReport".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Creates a QA/QC report for a directory of LiDAR tiles.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description:
+                "Input LiDAR file; if unspecified, all LAS files in the working directory are used."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output HTML File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output HTML report file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Html),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Resolution of the grid used to measure density and voids.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Pulse Density".to_owned(),
+            flags: vec!["--min_density".to_owned()],
+            description: "Required pulse density (first returns per unit area).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Density Raster (optional)".to_owned(),
+            flags: vec!["--density_raster".to_owned()],
+            description: "Optional output pulse density raster.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Compliance Raster (optional)".to_owned(),
+            flags: vec!["--compliance_raster".to_owned()],
+            description: "Optional output density compliance raster.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Voids File (optional)".to_owned(),
+            flags: vec!["--voids".to_owned()],
+            description: "Optional output vector polygon file of voids.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Void Area".to_owned(),
+            flags: vec!["--min_void_area".to_owned()],
+            description: "Minimum area of a reported void.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*tiles*\" -o=qaqc_report.html --resolution=2.0 --min_density=8.0 --density_raster=density.tif --voids=voids.shp", short_exe, name).replace("*", &sep);
+
+        LidarQualityReport {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarQualityReport {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut density_file = String::new();
+        let mut compliance_file = String::new();
+        let mut voids_file = String::new();
+        let mut grid_res = 2f64;
+        let mut min_density = 2f64;
+        let mut min_void_area = 10f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-density_raster" {
+                density_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-compliance_raster" {
+                compliance_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-voids" {
+                voids_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution"
+                || flag_val == "-min_density"
+                || flag_val == "-min_void_area"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-resolution" => grid_res = value,
+                    "-min_density" => min_density = value,
+                    _ => min_void_area = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        let start = Instant::now();
+
+        for file in [
+            &mut output_file,
+            &mut density_file,
+            &mut compliance_file,
+            &mut voids_file,
+        ]
+        .iter_mut()
+        {
+            if !file.is_empty() && !file.contains(&sep) && !file.contains("/") {
+                **file = format!("{}{}", working_directory, file);
+            }
+        }
+
+        if grid_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution must be greater than zero.",
+            ));
+        }
+
+        let mut inputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    let lower = s.to_lowercase();
+                    if lower.ends_with(".las") || lower.ends_with(".laz") || lower.ends_with(".zip")
+                    {
+                        inputs.push(s);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
+                input_file = format!("{}{}", working_directory, input_file);
+            }
+            inputs.push(input_file.clone());
+        }
+        inputs.sort();
+        let num_tiles = inputs.len();
+        if num_tiles == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No LiDAR files were found in the working directory.",
+            ));
+        }
+
+        // Read the headers to determine the extent of the grid.
+        let mut grid_west = f64::INFINITY;
+        let mut grid_east = f64::NEG_INFINITY;
+        let mut grid_south = f64::INFINITY;
+        let mut grid_north = f64::NEG_INFINITY;
+        for input in &inputs {
+            if let Ok(header) = LasHeader::read_las_header(input) {
+                if header.get_number_of_points() > 0 && !input.to_lowercase().ends_with(".laz") {
+                    grid_west = grid_west.min((header.min_x / grid_res).floor() * grid_res);
+                    grid_east = grid_east.max(header.max_x);
+                    grid_south = grid_south.min(header.min_y);
+                    grid_north = grid_north.max((header.max_y / grid_res).ceil() * grid_res);
+                }
+            }
+        }
+        let (rows, columns) = if grid_west.is_finite() {
+            (
+                (((grid_north - grid_south) / grid_res).floor() as isize + 1).max(1),
+                (((grid_east - grid_west) / grid_res).floor() as isize + 1).max(1),
+            )
+        } else {
+            (0isize, 0isize)
+        };
+        if (rows * columns) as f64 > MAX_GRID_CELLS {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The density grid is too large; please increase the grid resolution.",
+            ));
+        }
+        let grid = Arc::new(GridDef {
+            west: grid_west,
+            north: grid_north,
+            resolution: grid_res,
+            rows: rows,
+            columns: columns,
+        });
+
+        // Analyze the tiles.
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = channel();
+        for _ in 0..num_procs {
+            let inputs = inputs.clone();
+            let tile_list = tile_list.clone();
+            let grid = grid.clone();
+            let tx = tx.clone();
+            thread::spawn(move || loop {
+                let tile = match tile_list.lock().unwrap().next() {
+                    Some(val) => val,
+                    None => break,
+                };
+                let summary = analyze_tile(&inputs[tile], &grid);
+                tx.send((tile, summary)).unwrap();
+            });
+        }
+        let mut tiles: Vec<Option<TileSummary>> = (0..num_tiles).map(|_| None).collect();
+        for t in 0..num_tiles {
+            let (tile, summary) = rx.recv().expect("Error receiving data from thread.");
+            if verbose && num_tiles < 500 {
+                println!("Analyzed {}", summary.file_name);
+            }
+            tiles[tile] = Some(summary);
+            if verbose {
+                progress = (100.0_f64 * (t + 1) as f64 / num_tiles as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        let tiles: Vec<TileSummary> = tiles.into_iter().map(|t| t.unwrap()).collect();
+
+        // Merge the tile grids.
+        if verbose {
+            println!("Measuring density, overlap and voids...");
+        }
+        let num_cells = (rows * columns) as usize;
+        let mut covered = vec![false; num_cells];
+        let mut all_count = vec![0u32; num_cells];
+        let mut first_count = vec![0u32; num_cells];
+        let mut strips = vec![NO_STRIP; num_cells];
+        for tile in &tiles {
+            if let Some(g) = &tile.grid {
+                for r in 0..g.rows {
+                    for c in 0..g.columns {
+                        let k = r * g.columns + c;
+                        let kg = ((g.row0 + r) * columns + g.col0 + c) as usize;
+                        covered[kg] = true;
+                        all_count[kg] += g.all[k as usize];
+                        first_count[kg] += g.first[k as usize];
+                        strips[kg] = merge_strips(strips[kg], g.strips[k as usize]);
+                    }
+                }
+            }
+        }
+        let cell_area = grid_res * grid_res;
+        let density: Vec<f64> = first_count.iter().map(|&n| n as f64 / cell_area).collect();
+
+        // Summarize the grid cells in the extent of each tile.
+        let cell_stats = |min_x: f64, max_x: f64, min_y: f64, max_y: f64| -> CellStats {
+            let mut stats = CellStats::default();
+            if num_cells == 0 {
+                return stats;
+            }
+            let (r0, r1) = (
+                grid.row(max_y - grid_res / 2f64).max(0),
+                grid.row(min_y + grid_res / 2f64).min(rows - 1),
+            );
+            let (c0, c1) = (
+                grid.column(min_x + grid_res / 2f64).max(0),
+                grid.column(max_x - grid_res / 2f64).min(columns - 1),
+            );
+            for r in r0..=r1 {
+                for c in c0..=c1 {
+                    let k = (r * columns + c) as usize;
+                    if covered[k] {
+                        stats.num_cells += 1;
+                        if density[k] >= min_density {
+                            stats.compliant += 1;
+                        }
+                        if strips[k] == MULTIPLE_STRIPS {
+                            stats.overlap += 1;
+                        }
+                        if all_count[k] == 0 {
+                            stats.empty += 1;
+                        }
+                    }
+                }
+            }
+            stats
+        };
+
+        // Find the voids.
+        let mut voids: Vec<Vec<usize>> = vec![];
+        let mut visited = vec![false; num_cells];
+        for k in 0..num_cells {
+            if covered[k] && all_count[k] == 0 && !visited[k] {
+                visited[k] = true;
+                let mut cells = vec![];
+                let mut touches_edge = false;
+                let mut stack = vec![k];
+                while let Some(j) = stack.pop() {
+                    cells.push(j);
+                    let (r, c) = ((j as isize) / columns, (j as isize) % columns);
+                    for &(dr, dc) in [(-1isize, 0isize), (1, 0), (0, -1), (0, 1)].iter() {
+                        let (rn, cn) = (r + dr, c + dc);
+                        if rn < 0 || rn >= rows || cn < 0 || cn >= columns {
+                            touches_edge = true;
+                            continue;
+                        }
+                        let kn = (rn * columns + cn) as usize;
+                        if !covered[kn] {
+                            touches_edge = true;
+                        } else if all_count[kn] == 0 && !visited[kn] {
+                            visited[kn] = true;
+                            stack.push(kn);
+                        }
+                    }
+                }
+                if !touches_edge && cells.len() as f64 * cell_area >= min_void_area {
+                    voids.push(cells);
+                }
+            }
+        }
+        voids.sort_by(|a, b| b.len().cmp(&a.len()));
+
+        let wkt = tiles
+            .iter()
+            .find(|t| !t.wkt.is_empty())
+            .map(|t| t.wkt.clone())
+            .unwrap_or(String::new());
+
+        if !voids_file.is_empty() {
+            let mut output = Shapefile::new(&voids_file, ShapeType::Polygon)?;
+            output.projection = wkt.clone();
+            output
+                .attributes
+                .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+            output.attributes.add_field(&AttributeField::new(
+                "AREA",
+                FieldDataType::Real,
+                12u8,
+                2u8,
+            ));
+            for (v, cells) in voids.iter().enumerate() {
+                let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+                for ring in void_rings(cells, &grid) {
+                    sfg.add_part(&ring);
+                }
+                output.add_record(sfg);
+                output.attributes.add_record(
+                    vec![
+                        FieldData::Int(v as i32 + 1),
+                        FieldData::Real(cells.len() as f64 * cell_area),
+                    ],
+                    false,
+                );
+            }
+            output.write()?;
+        }
+
+        if num_cells > 0 && (!density_file.is_empty() || !compliance_file.is_empty()) {
+            let nodata = -32768.0f64;
+            let mut configs = RasterConfigs {
+                ..Default::default()
+            };
+            configs.rows = rows as usize;
+            configs.columns = columns as usize;
+            configs.north = grid_north;
+            configs.south = grid_north - rows as f64 * grid_res;
+            configs.east = grid_west + columns as f64 * grid_res;
+            configs.west = grid_west;
+            configs.resolution_x = grid_res;
+            configs.resolution_y = grid_res;
+            configs.nodata = nodata;
+            configs.data_type = DataType::F32;
+            configs.photometric_interp = PhotometricInterpretation::Continuous;
+            configs.coordinate_ref_system_wkt = wkt.clone();
+            for &(file, is_density) in [(&density_file, true), (&compliance_file, false)].iter() {
+                if file.is_empty() {
+                    continue;
+                }
+                let mut output = Raster::initialize_using_config(file, &configs);
+                for r in 0..rows {
+                    let data: Vec<f64> = (0..columns)
+                        .map(|c| {
+                            let k = (r * columns + c) as usize;
+                            if !covered[k] {
+                                nodata
+                            } else if is_density {
+                                density[k]
+                            } else if density[k] >= min_density {
+                                1f64
+                            } else {
+                                0f64
+                            }
+                        })
+                        .collect();
+                    output.set_row_data(r, data);
+                }
+                output.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                if !is_density {
+                    output.add_metadata_entry(format!("Minimum pulse density: {}", min_density));
+                }
+                output.write()?;
+            }
+        }
+
+        ////////////////////////
+        // Write the report //
+        ////////////////////////
+        if verbose {
+            println!("Writing the report...");
+        }
+        let readable: Vec<&TileSummary> = tiles.iter().filter(|t| t.grid.is_some()).collect();
+        let total = cell_stats(grid_west, grid_east, grid_south, grid_north);
+        let total_points: u64 = tiles.iter().map(|t| t.num_points as u64).sum();
+        let total_first: u64 = readable.iter().map(|t| t.pulses).sum();
+        let covered_area = total.num_cells as f64 * cell_area;
+        let void_area = voids.iter().map(|v| v.len()).sum::<usize>() as f64 * cell_area;
+        let percent = |n: usize, d: usize| -> f64 {
+            if d > 0 {
+                100f64 * n as f64 / d as f64
+            } else {
+                0f64
+            }
+        };
+
+        let f = File::create(output_file.clone())?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all(&r#"<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">
+        <head>
+            <meta content=\"text/html; charset=UTF-8\" http-equiv=\"content-type\">
+            <title>LiDAR QA/QC Report</title>"#.as_bytes())?;
+        writer.write_all(&get_css().as_bytes())?;
+        writer.write_all(
+            &r#"</head>
+        <body>
+            <h1>LiDAR QA/QC Report</h1>"#
+                .as_bytes(),
+        )?;
+
+        // Summary
+        let mut s = String::from("<h2>Summary</h2><p>");
+        s.push_str(&format!(
+            "<strong>Input</strong>: {}<br>",
+            if input_file.is_empty() {
+                working_directory.to_string()
+            } else {
+                input_file.clone()
+            }
+        ));
+        s.push_str(&format!(
+            "<strong>Number of tiles</strong>: {} ({} compressed, {} unreadable)<br>",
+            num_tiles,
+            tiles.iter().filter(|t| t.compressed).count(),
+            tiles.iter().filter(|t| t.error.is_some()).count()
+        ));
+        s.push_str(&format!(
+            "<strong>Number of points</strong>: {}<br>",
+            total_points
+        ));
+        s.push_str(&format!(
+            "<strong>Area analyzed</strong>: {:.1} (grid resolution {})<br>",
+            covered_area, grid_res
+        ));
+        s.push_str(&format!(
+            "<strong>Aggregate pulse density</strong>: {:.3} first returns per unit area<br>",
+            if covered_area > 0f64 {
+                total_first as f64 / covered_area
+            } else {
+                0f64
+            }
+        ));
+        s.push_str(&format!(
+            "<strong>Density compliance</strong>: {:.2}% of cells have a pulse density of at least {}<br>",
+            percent(total.compliant, total.num_cells),
+            min_density
+        ));
+        s.push_str(&format!(
+            "<strong>Overlap coverage</strong>: {:.2}% of cells contain points from more than one flight line<br>",
+            percent(total.overlap, total.num_cells)
+        ));
+        s.push_str(&format!(
+            "<strong>Voids</strong>: {} covering a total area of {:.1}<br>",
+            voids.len(),
+            void_area
+        ));
+        s.push_str(&format!(
+            "<strong>Elapsed time</strong>: {}</p>",
+            get_formatted_elapsed_time(start)
+        ));
+
+        // Consistency checks
+        s.push_str("<h2>Consistency Checks</h2>");
+        let mut problems: Vec<String> = vec![];
+        let distinct = |f: &dyn Fn(&TileSummary) -> String| -> Vec<(String, usize)> {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for t in tiles.iter().filter(|t| t.error.is_none()) {
+                *counts.entry(f(t)).or_insert(0) += 1;
+            }
+            let mut v: Vec<(String, usize)> = counts.into_iter().collect();
+            v.sort();
+            v
+        };
+        for (label, values) in [
+            (
+                "LAS versions",
+                distinct(&|t: &TileSummary| t.version.clone()),
+            ),
+            (
+                "point formats",
+                distinct(&|t: &TileSummary| format!("{}", t.point_format)),
+            ),
+            (
+                "coordinate reference systems",
+                distinct(&|t: &TileSummary| t.crs.clone()),
+            ),
+            (
+                "scale factors",
+                distinct(&|t: &TileSummary| {
+                    format!("{} {} {}", t.scale[0], t.scale[1], t.scale[2])
+                }),
+            ),
+        ]
+        .iter()
+        {
+            if values.len() > 1 {
+                problems.push(format!(
+                    "The tiles have inconsistent {}: {}.",
+                    label,
+                    values
+                        .iter()
+                        .map(|(v, n)| format!("{} ({} tiles)", v, n))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+            }
+        }
+        for t in &tiles {
+            for issue in &t.issues {
+                problems.push(format!("{}: {}", t.file_name, issue));
+            }
+        }
+        let mut num_overlapping_tiles = 0;
+        for a in 0..num_tiles {
+            for b in a + 1..num_tiles {
+                let (ta, tb) = (&tiles[a].bounds, &tiles[b].bounds);
+                if ta[0] < tb[1] && tb[0] < ta[1] && ta[2] < tb[3] && tb[2] < ta[3] {
+                    num_overlapping_tiles += 1;
+                }
+            }
+        }
+        if num_overlapping_tiles > 0 {
+            problems.push(format!(
+                "{} pairs of tiles have overlapping extents; this is expected for buffered tiles.",
+                num_overlapping_tiles
+            ));
+        }
+        if problems.is_empty() {
+            s.push_str("<p>No issues were found.</p>");
+        } else {
+            s.push_str("<ul>");
+            for p in &problems {
+                s.push_str(&format!("<li>{}</li>", p));
+            }
+            s.push_str("</ul>");
+        }
+
+        // Tiles
+        s.push_str("<h2>Tiles</h2><table align=\"center\">");
+        s.push_str("<tr><th>File</th><th>Version</th><th>Format</th><th>Points</th><th>Min X</th><th>Max X</th><th>Min Y</th><th>Max Y</th><th>Min Z</th><th>Max Z</th><th>Pulse Density</th><th>Compliant Cells (%)</th><th>Overlap (%)</th><th>Empty Cells (%)</th><th>Flight Lines</th><th>CRS</th><th>Issues</th></tr>");
+        for t in &tiles {
+            let stats = cell_stats(t.bounds[0], t.bounds[1], t.bounds[2], t.bounds[3]);
+            let (density_s, compliant_s, overlap_s, empty_s, strips_s) = if t.grid.is_some() {
+                let area = stats.num_cells as f64 * cell_area;
+                (
+                    format!(
+                        "{:.3}",
+                        if area > 0f64 {
+                            t.pulses as f64 / area
+                        } else {
+                            0f64
+                        }
+                    ),
+                    format!("{:.2}", percent(stats.compliant, stats.num_cells)),
+                    format!("{:.2}", percent(stats.overlap, stats.num_cells)),
+                    format!("{:.2}", percent(stats.empty, stats.num_cells)),
+                    format!("{}", t.num_strips),
+                )
+            } else {
+                (
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                )
+            };
+            s.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td>{}</td><td class=\"numberCell\">{}</td></tr>",
+                t.file_name,
+                t.version,
+                t.point_format,
+                t.num_points,
+                t.bounds[0],
+                t.bounds[1],
+                t.bounds[2],
+                t.bounds[3],
+                t.bounds[4],
+                t.bounds[5],
+                density_s,
+                compliant_s,
+                overlap_s,
+                empty_s,
+                strips_s,
+                t.crs,
+                t.issues.len()
+            ));
+        }
+        s.push_str("</table>");
+
+        // Classification
+        let mut class_counts = vec![0u64; 256];
+        let mut return_counts = vec![0u64; 16];
+        let mut num_returns_counts = vec![0u64; 16];
+        let (mut single, mut first, mut intermediate, mut last) = (0u64, 0u64, 0u64, 0u64);
+        let (mut withheld, mut synthetic, mut keypoint) = (0u64, 0u64, 0u64);
+        for t in &readable {
+            for c in 0..256 {
+                class_counts[c] += t.class_counts[c];
+            }
+            for r in 0..16 {
+                return_counts[r] += t.return_counts[r];
+                num_returns_counts[r] += t.num_returns_counts[r];
+            }
+            single += t.single;
+            first += t.first;
+            intermediate += t.intermediate;
+            last += t.last;
+            withheld += t.withheld;
+            synthetic += t.synthetic;
+            keypoint += t.keypoint;
+        }
+        let num_analyzed: u64 = readable.iter().map(|t| t.num_points as u64).sum();
+        let pct = |n: u64| -> f64 {
+            if num_analyzed > 0 {
+                100f64 * n as f64 / num_analyzed as f64
+            } else {
+                0f64
+            }
+        };
+        s.push_str("<h2>Classification</h2><table align=\"center\">");
+        s.push_str("<tr><th>Class</th><th>Name</th><th>Points</th><th>Percent</th></tr>");
+        let mut max_class = 0;
+        for c in 0..256 {
+            if class_counts[c] > 0 {
+                max_class = c;
+                s.push_str(&format!(
+                    "<tr><td class=\"numberCell\">{}</td><td>{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{:.3}%</td></tr>",
+                    c,
+                    convert_class_val_to_class_string(c as u8),
+                    class_counts[c],
+                    pct(class_counts[c])
+                ));
+            }
+        }
+        s.push_str("</table>");
+        s.push_str(&format!(
+            "<p>Withheld points: {} ({:.3}%); synthetic points: {} ({:.3}%); key-points: {} ({:.3}%)</p>",
+            withheld,
+            pct(withheld),
+            synthetic,
+            pct(synthetic),
+            keypoint,
+            pct(keypoint)
+        ));
+        if num_analyzed > 0 {
+            let histo = Histogram {
+                parent_id: "classHisto".to_owned(),
+                width: 700f64,
+                height: 500f64,
+                freq_data: (0..=max_class).map(|c| class_counts[c] as usize).collect(),
+                min_bin_val: 0f64,
+                bin_width: 1f64,
+                x_axis_label: "Class Value".to_owned(),
+                cumulative: false,
+            };
+            s.push_str(&format!(
+                "<div id='classHisto' align=\"center\">{}</div>",
+                histo.get_svg()
+            ));
+        }
+
+        // Returns
+        s.push_str("<h2>Return Statistics</h2><table align=\"center\">");
+        s.push_str("<tr><th>Value</th><th>Points by Return Number</th><th>Points by Number of Returns</th></tr>");
+        let mut max_return = 1;
+        for r in 1..16 {
+            if return_counts[r] > 0 || num_returns_counts[r] > 0 {
+                max_return = r;
+            }
+        }
+        for r in 1..=max_return {
+            s.push_str(&format!(
+                "<tr><td class=\"numberCell\">{}</td><td class=\"numberCell\">{} ({:.3}%)</td><td class=\"numberCell\">{} ({:.3}%)</td></tr>",
+                r,
+                return_counts[r],
+                pct(return_counts[r]),
+                num_returns_counts[r],
+                pct(num_returns_counts[r])
+            ));
+        }
+        s.push_str("</table>");
+        s.push_str(&format!(
+            "<p>Single returns: {} ({:.3}%); first of multiple returns: {} ({:.3}%); intermediate returns: {} ({:.3}%); last of multiple returns: {} ({:.3}%)</p>",
+            single,
+            pct(single),
+            first,
+            pct(first),
+            intermediate,
+            pct(intermediate),
+            last,
+            pct(last)
+        ));
+
+        // Density distribution
+        if total.num_cells > 0 {
+            s.push_str("<h2>Pulse Density Distribution</h2>");
+            let mut values: Vec<f64> = (0..num_cells)
+                .filter(|&k| covered[k])
+                .map(|k| density[k])
+                .collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let upper = values[((values.len() - 1) as f64 * 0.99) as usize].max(min_density);
+            let num_bins = 50usize;
+            let bin_width = upper / num_bins as f64;
+            let mut freq_data = vec![0usize; num_bins + 1];
+            for v in &values {
+                let bin = ((v / bin_width).floor() as usize).min(num_bins);
+                freq_data[bin] += 1;
+            }
+            s.push_str(&format!(
+                "<p>Pulse density of {} cells; the last bin contains all cells with a density greater than {:.3}.</p>",
+                values.len(),
+                upper
+            ));
+            let histo = Histogram {
+                parent_id: "densityHisto".to_owned(),
+                width: 700f64,
+                height: 500f64,
+                freq_data: freq_data,
+                min_bin_val: 0f64,
+                bin_width: bin_width,
+                x_axis_label: "Pulse Density".to_owned(),
+                cumulative: false,
+            };
+            s.push_str(&format!(
+                "<div id='densityHisto' align=\"center\">{}</div>",
+                histo.get_svg()
+            ));
+        }
+
+        // Voids
+        s.push_str("<h2>Voids</h2>");
+        if voids.is_empty() {
+            s.push_str("<p>No voids were found.</p>");
+        } else {
+            if voids.len() > MAX_REPORTED_VOIDS {
+                s.push_str(&format!(
+                    "<p>The {} largest of {} voids are listed.</p>",
+                    MAX_REPORTED_VOIDS,
+                    voids.len()
+                ));
+            }
+            s.push_str("<table align=\"center\"><tr><th>Void</th><th>Area</th><th>Centre X</th><th>Centre Y</th></tr>");
+            for (v, cells) in voids.iter().take(MAX_REPORTED_VOIDS).enumerate() {
+                let (mut cx, mut cy) = (0f64, 0f64);
+                for &k in cells {
+                    let (r, c) = ((k as isize) / columns, (k as isize) % columns);
+                    cx += grid.west + (c as f64 + 0.5) * grid_res;
+                    cy += grid.north - (r as f64 + 0.5) * grid_res;
+                }
+                s.push_str(&format!(
+                    "<tr><td class=\"numberCell\">{}</td><td class=\"numberCell\">{:.1}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td></tr>",
+                    v + 1,
+                    cells.len() as f64 * cell_area,
+                    cx / cells.len() as f64,
+                    cy / cells.len() as f64
+                ));
+            }
+            s.push_str("</table>");
+        }
+
+        s.push_str("</body>");
+        writer.write_all(s.as_bytes())?;
+        let _ = writer.flush();
+
+        if verbose {
+            let elapsed_time = get_formatted_elapsed_time(start);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        if verbose {
+            if cfg!(target_os = "macos") || cfg!(target_os = "ios") {
+                let output = Command::new("open")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            } else if cfg!(target_os = "windows") {
+                let output = Command::new("explorer.exe")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            } else if cfg!(target_os = "linux") {
+                let output = Command::new("xdg-open")
+                    .arg(output_file.clone())
+                    .output()
+                    .expect("failed to execute process");
+
+                let _ = output.stdout;
+            }
+            println!("Complete! Please see {} for output.", output_file);
+        }
+
+        Ok(())
+    }
+}
+
+/// The maximum number of cells in the density grid.
+const MAX_GRID_CELLS: f64 = 500_000_000f64;
+/// The maximum number of voids listed in the report.
+const MAX_REPORTED_VOIDS: usize = 100;
+/// Strip identifiers of grid cells with no points and with points from multiple flight lines.
+const NO_STRIP: u32 = 0;
+const MULTIPLE_STRIPS: u32 = u32::MAX;
+
+fn merge_strips(a: u32, b: u32) -> u32 {
+    if a == NO_STRIP || a == b {
+        b
+    } else if b == NO_STRIP {
+        a
+    } else {
+        MULTIPLE_STRIPS
+    }
+}
+
+/// The grid used to measure density, overlap and voids across all tiles.
+struct GridDef {
+    west: f64,
+    north: f64,
+    resolution: f64,
+    rows: isize,
+    columns: isize,
+}
+
+impl GridDef {
+    fn row(&self, y: f64) -> isize {
+        ((self.north - y) / self.resolution).floor() as isize
+    }
+
+    fn column(&self, x: f64) -> isize {
+        ((x - self.west) / self.resolution).floor() as isize
+    }
+}
+
+/// The part of the grid within the extent of a tile.
+struct TileGrid {
+    row0: isize,
+    col0: isize,
+    rows: isize,
+    columns: isize,
+    all: Vec<u32>,
+    first: Vec<u32>,
+    strips: Vec<u32>,
+}
+
+#[derive(Default)]
+struct CellStats {
+    num_cells: usize,
+    compliant: usize,
+    overlap: usize,
+    empty: usize,
+}
+
+struct TileSummary {
+    file_name: String,
+    compressed: bool,
+    error: Option<String>,
+    version: String,
+    point_format: u8,
+    num_points: usize,
+    bounds: [f64; 6],
+    scale: [f64; 3],
+    crs: String,
+    wkt: String,
+    class_counts: Vec<u64>,
+    return_counts: Vec<u64>,
+    num_returns_counts: Vec<u64>,
+    pulses: u64,
+    single: u64,
+    first: u64,
+    intermediate: u64,
+    last: u64,
+    withheld: u64,
+    synthetic: u64,
+    keypoint: u64,
+    num_strips: usize,
+    grid: Option<TileGrid>,
+    issues: Vec<String>,
+}
+
+impl TileSummary {
+    fn new(file_name: &str) -> TileSummary {
+        TileSummary {
+            file_name: path::Path::new(file_name)
+                .file_name()
+                .and_then(|f| f.to_str())
+                .unwrap_or(file_name)
+                .to_string(),
+            compressed: false,
+            error: None,
+            version: String::new(),
+            point_format: 0,
+            num_points: 0,
+            bounds: [0f64; 6],
+            scale: [0f64; 3],
+            crs: "Unknown".to_string(),
+            wkt: String::new(),
+            class_counts: vec![0u64; 256],
+            return_counts: vec![0u64; 16],
+            num_returns_counts: vec![0u64; 16],
+            pulses: 0,
+            single: 0,
+            first: 0,
+            intermediate: 0,
+            last: 0,
+            withheld: 0,
+            synthetic: 0,
+            keypoint: 0,
+            num_strips: 0,
+            grid: None,
+            issues: vec![],
+        }
+    }
+}
+
+fn analyze_tile(file_name: &str, grid: &GridDef) -> TileSummary {
+    let mut summary = TileSummary::new(file_name);
+    let header = match LasHeader::read_las_header(file_name) {
+        Ok(h) => h,
+        Err(e) => {
+            summary
+                .issues
+                .push(format!("The header could not be read ({}).", e));
+            summary.error = Some(format!("{}", e));
+            return summary;
+        }
+    };
+    summary.version = format!("{}.{}", header.version_major, header.version_minor);
+    summary.point_format = header.point_format;
+    summary.num_points = header.get_number_of_points() as usize;
+    summary.bounds = [
+        header.min_x,
+        header.max_x,
+        header.min_y,
+        header.max_y,
+        header.min_z,
+        header.max_z,
+    ];
+    summary.scale = [
+        header.x_scale_factor,
+        header.y_scale_factor,
+        header.z_scale_factor,
+    ];
+    if file_name.to_lowercase().ends_with(".laz") {
+        summary.compressed = true;
+        summary
+            .issues
+            .push("The file is compressed (LAZ); only its header has been analyzed.".to_string());
+        return summary;
+    }
+
+    let input = match LasFile::new(file_name, "r") {
+        Ok(f) => f,
+        Err(e) => {
+            summary
+                .issues
+                .push(format!("The file could not be read ({}).", e));
+            summary.error = Some(format!("{}", e));
+            return summary;
+        }
+    };
+    let n_points = input.header.number_of_points as usize;
+    if n_points == 0 {
+        summary
+            .issues
+            .push("The file does not contain any points.".to_string());
+    }
+    let epsg = input.geokeys.find_epsg_code();
+    summary.wkt = input.wkt.clone();
+    summary.crs = if epsg > 0 {
+        format!("EPSG:{}", epsg)
+    } else if !summary.wkt.is_empty() {
+        // Use the name of the CRS in the WKT.
+        summary.wkt.split('"').nth(1).unwrap_or("WKT").to_string()
+    } else {
+        summary
+            .issues
+            .push("The file does not specify a coordinate reference system.".to_string());
+        "None".to_string()
+    };
+
+    let row0 = grid.row(header.max_y).max(0);
+    let row1 = grid.row(header.min_y).min(grid.rows - 1);
+    let col0 = grid.column(header.min_x).max(0);
+    let col1 = grid.column(header.max_x).min(grid.columns - 1);
+    let (rows, columns) = ((row1 - row0 + 1).max(0), (col1 - col0 + 1).max(0));
+    let mut tile_grid = TileGrid {
+        row0: row0,
+        col0: col0,
+        rows: rows,
+        columns: columns,
+        all: vec![0u32; (rows * columns) as usize],
+        first: vec![0u32; (rows * columns) as usize],
+        strips: vec![NO_STRIP; (rows * columns) as usize],
+    };
+
+    let mut actual = [
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    let mut strip_ids = HashSet::new();
+    for i in 0..n_points {
+        let p: PointData = input[i];
+        actual[0] = actual[0].min(p.x);
+        actual[1] = actual[1].max(p.x);
+        actual[2] = actual[2].min(p.y);
+        actual[3] = actual[3].max(p.y);
+        actual[4] = actual[4].min(p.z);
+        actual[5] = actual[5].max(p.z);
+        summary.class_counts[p.classification() as usize] += 1;
+        let rn = (p.return_number() as usize).min(15);
+        let nr = (p.number_of_returns() as usize).min(15);
+        summary.return_counts[rn] += 1;
+        summary.num_returns_counts[nr] += 1;
+        if p.is_only_return() {
+            summary.single += 1;
+        }
+        if p.is_first_return() {
+            summary.first += 1;
+        }
+        if p.is_last_return() {
+            summary.last += 1;
+        }
+        if p.is_intermediate_return() {
+            summary.intermediate += 1;
+        }
+        if rn == 1 {
+            summary.pulses += 1;
+        }
+        if p.withheld() {
+            summary.withheld += 1;
+        }
+        if p.synthetic() {
+            summary.synthetic += 1;
+        }
+        if p.keypoint() {
+            summary.keypoint += 1;
+        }
+        strip_ids.insert(p.point_source_id);
+
+        let r = grid.row(p.y) - row0;
+        let c = grid.column(p.x) - col0;
+        if r >= 0 && r < rows && c >= 0 && c < columns {
+            let k = (r * columns + c) as usize;
+            tile_grid.all[k] += 1;
+            if rn == 1 {
+                tile_grid.first[k] += 1;
+            }
+            tile_grid.strips[k] = merge_strips(tile_grid.strips[k], p.point_source_id as u32 + 1);
+        }
+    }
+    summary.num_strips = strip_ids.len();
+    if summary.return_counts[0] > 0 {
+        summary.issues.push(format!(
+            "{} points have a return number of zero.",
+            summary.return_counts[0]
+        ));
+    }
+
+    // Check the header against the point records.
+    if n_points > 0 {
+        let tolerance = [summary.scale[0], summary.scale[1], summary.scale[2]];
+        let labels = ["x", "y", "z"];
+        for d in 0..3 {
+            if actual[2 * d] < summary.bounds[2 * d] - tolerance[d]
+                || actual[2 * d + 1] > summary.bounds[2 * d + 1] + tolerance[d]
+            {
+                summary.issues.push(format!(
+                    "The {} range of the points ({:.3} to {:.3}) exceeds the header extent ({:.3} to {:.3}).",
+                    labels[d],
+                    actual[2 * d],
+                    actual[2 * d + 1],
+                    summary.bounds[2 * d],
+                    summary.bounds[2 * d + 1]
+                ));
+            }
+        }
+        let by_return = if header.number_of_points_by_return.iter().any(|&n| n > 0) {
+            header.number_of_points_by_return.to_vec()
+        } else {
+            header
+                .number_of_points_by_return_old
+                .iter()
+                .map(|&n| n as u64)
+                .collect()
+        };
+        if by_return.iter().any(|&n| n > 0) {
+            for r in 0..by_return.len().min(15) {
+                if by_return[r] != summary.return_counts[r + 1] {
+                    summary.issues.push(format!(
+                        "The header reports {} points with return number {} but the file contains {}.",
+                        by_return[r],
+                        r + 1,
+                        summary.return_counts[r + 1]
+                    ));
+                }
+            }
+        }
+    }
+    if summary.withheld > 0 {
+        summary.issues.push(format!(
+            "The file contains {} withheld points.",
+            summary.withheld
+        ));
+    }
+
+    summary.grid = Some(tile_grid);
+    summary
+}
+
+// Traces the boundary of a void as a set of rings, with clockwise outer rings and
+// counter-clockwise holes, following the Shapefile convention.
+fn void_rings(cells: &[usize], grid: &GridDef) -> Vec<Vec<Point2D>> {
+    let members: HashSet<usize> = cells.iter().cloned().collect();
+    let columns = grid.columns;
+    let in_void = |r: isize, c: isize| -> bool {
+        r >= 0
+            && r < grid.rows
+            && c >= 0
+            && c < columns
+            && members.contains(&((r * columns + c) as usize))
+    };
+    // Directed edges between cell corners, keeping the void on the right.
+    let mut edges: HashMap<(isize, isize), Vec<(isize, isize)>> = HashMap::new();
+    for &k in cells {
+        let (r, c) = ((k as isize) / columns, (k as isize) % columns);
+        if !in_void(r - 1, c) {
+            edges.entry((r, c)).or_insert(vec![]).push((r, c + 1));
+        }
+        if !in_void(r, c + 1) {
+            edges
+                .entry((r, c + 1))
+                .or_insert(vec![])
+                .push((r + 1, c + 1));
+        }
+        if !in_void(r + 1, c) {
+            edges
+                .entry((r + 1, c + 1))
+                .or_insert(vec![])
+                .push((r + 1, c));
+        }
+        if !in_void(r, c - 1) {
+            edges.entry((r + 1, c)).or_insert(vec![]).push((r, c));
+        }
+    }
+    let corner = |v: (isize, isize)| -> Point2D {
+        Point2D::new(
+            grid.west + v.1 as f64 * grid.resolution,
+            grid.north - v.0 as f64 * grid.resolution,
+        )
+    };
+    let mut rings = vec![];
+    loop {
+        let start = match edges.iter().find(|(_, v)| !v.is_empty()) {
+            Some((&s, _)) => s,
+            None => break,
+        };
+        let mut ring = vec![corner(start)];
+        let mut current = start;
+        loop {
+            let next = match edges.get_mut(&current).and_then(|v| v.pop()) {
+                Some(n) => n,
+                None => break,
+            };
+            ring.push(corner(next));
+            current = next;
+            if current == start {
+                break;
+            }
+        }
+        // Remove collinear vertices.
+        let mut simplified: Vec<Point2D> = vec![ring[0]];
+        for i in 1..ring.len() - 1 {
+            let (a, b, c) = (simplified[simplified.len() - 1], ring[i], ring[i + 1]);
+            if ((b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x)).abs() > 0f64 {
+                simplified.push(b);
+            }
+        }
+        simplified.push(ring[ring.len() - 1]);
+        rings.push(simplified);
+    }
+    rings
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge_strips, void_rings, GridDef, MULTIPLE_STRIPS, NO_STRIP};
+    use crate::algorithms::{is_clockwise_order, polygon_area};
+
+    #[test]
+    fn test_merge_strips() {
+        assert_eq!(merge_strips(NO_STRIP, 3), 3);
+        assert_eq!(merge_strips(3, NO_STRIP), 3);
+        assert_eq!(merge_strips(3, 3), 3);
+        assert_eq!(merge_strips(3, 4), MULTIPLE_STRIPS);
+        assert_eq!(merge_strips(MULTIPLE_STRIPS, 3), MULTIPLE_STRIPS);
+    }
+
+    #[test]
+    fn test_void_rings() {
+        let grid = GridDef {
+            west: 100.0,
+            north: 200.0,
+            resolution: 2.0,
+            rows: 5,
+            columns: 5,
+        };
+        assert_eq!(grid.row(199.9), 0);
+        assert_eq!(grid.column(100.1), 0);
+        assert_eq!(grid.row(190.1), 4);
+
+        // a 3 x 3 block of cells with its centre cell occupied
+        let cells: Vec<usize> = (1..4)
+            .flat_map(|r| (1..4).map(move |c| r * 5 + c))
+            .filter(|&k| k != 12)
+            .collect();
+        let mut rings = void_rings(&cells, &grid);
+        assert_eq!(rings.len(), 2);
+        rings.sort_by(|a, b| polygon_area(b).partial_cmp(&polygon_area(a)).unwrap());
+        assert!((polygon_area(&rings[0]) - 36.0).abs() < 1e-9);
+        assert!((polygon_area(&rings[1]) - 4.0).abs() < 1e-9);
+        assert!(is_clockwise_order(&rings[0]));
+        assert!(!is_clockwise_order(&rings[1]));
+        for ring in &rings {
+            assert_eq!(ring[0], ring[ring.len() - 1]);
+        }
+    }
+}
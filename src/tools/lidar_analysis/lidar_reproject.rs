@@ -0,0 +1,701 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::spatial_ref_system::{esri_wkt_from_epsg, Projection};
+use crate::tools::*;
+use byteorder::{LittleEndian, WriteBytesExt};
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool reprojects the points in a LiDAR file (`--input`) from one coordinate reference
+/// system (CRS) into another, specified by its EPSG code (`--to_epsg`). The CRS of the input
+/// file is read from its GeoKey or OGC WKT variable length records (VLRs), unless it is
+/// specified using `--from_epsg`. Geographic coordinate systems, and projected coordinate systems
+/// based on the Transverse Mercator (including all UTM zones and Gauss-Kruger grids), Lambert
+/// Conformal Conic, Double (Oblique) Stereographic, Albers Equal Area, and Mercator projections
+/// are supported. This covers the UTM, state plane and most national and provincial grids, e.g.
+/// NAD83(CSRS) UTM zones and the New Brunswick Stereographic grid.
+///
+/// Note that the tool does **not** apply a horizontal datum transformation; the input and output
+/// coordinate systems should be based on the same datum (e.g. NAD83(CSRS) UTM zone 19N and NAD83(CSRS)
+/// New Brunswick Stereographic). A warning is issued if the datum names differ.
+///
+/// Point elevations may optionally be transformed between ellipsoidal heights and orthometric
+/// heights using a geoid model (`--geoid`). The geoid model must be a raster of geoid
+/// undulations (i.e. the height of the geoid above the ellipsoid, N), in metres, in a geographic
+/// (longitude, latitude) coordinate system, e.g. a GeoTIFF conversion of the CGG2013a or
+/// GEOID18 models. The undulation at each point is bilinearly interpolated from the grid. The
+/// `--vertical` parameter specifies the direction of the transformation: 'ellipsoid to geoid'
+/// (H = h - N) or 'geoid to ellipsoid' (h = H + N). Elevations are converted between the linear
+/// units of the input and output coordinate systems, with geographic systems using metres.
+///
+/// The scale factors of the output file are set to 0.001 units for projected coordinates and to
+/// 0.0000001 degrees (approximately 1 cm) for geographic coordinates, and the offsets are updated
+/// to the minimum of the reprojected coordinates. The CRS VLRs of the input file are replaced by
+/// GeoKey and OGC WKT VLRs describing the output CRS.
+pub struct LidarReproject {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarReproject {
+    pub fn new() -> LidarReproject {
+        // public constructor
+        let name = "LidarReproject".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Reprojects a LiDAR point cloud into another coordinate system, optionally applying a geoid model to the elevations."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output EPSG Code".to_owned(),
+            flags: vec!["--to_epsg".to_owned()],
+            description: "EPSG code of the output coordinate system.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input EPSG Code (optional)".to_owned(),
+            flags: vec!["--from_epsg".to_owned()],
+            description:
+                "EPSG code of the input coordinate system; read from the input file if unspecified."
+                    .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Geoid Model File (optional)".to_owned(),
+            flags: vec!["--geoid".to_owned()],
+            description: "Input geoid undulation raster, in geographic coordinates.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Vertical Transformation".to_owned(),
+            flags: vec!["--vertical".to_owned()],
+            description: "Vertical transformation; options are 'none' (default), 'ellipsoid to geoid', and 'geoid to ellipsoid'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "none".to_owned(),
+                "ellipsoid to geoid".to_owned(),
+                "geoid to ellipsoid".to_owned(),
+            ]),
+            default_value: Some("none".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.las --to_epsg=2953
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.las --from_epsg=2960 --to_epsg=2953 --geoid=CGG2013a.tif --vertical='ellipsoid to geoid'",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarReproject {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarReproject {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut to_epsg = 0u16;
+        let mut from_epsg = 0u16;
+        let mut geoid_file = String::new();
+        let mut vertical = "none".to_string();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-to_epsg" {
+                to_epsg = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-from_epsg" {
+                from_epsg = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<u16>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-geoid" {
+                geoid_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-vertical" {
+                vertical = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        let start = Instant::now();
+
+        if !input_file.contains(sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        // -1 for ellipsoid to geoid, 1 for geoid to ellipsoid
+        let vertical_sign = if vertical.contains("none") {
+            0f64
+        } else if vertical.starts_with("ellipsoid") {
+            -1f64
+        } else if vertical.starts_with("geoid") {
+            1f64
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized --vertical value; options are 'none', 'ellipsoid to geoid', and 'geoid to ellipsoid'.",
+            ));
+        };
+        if vertical_sign != 0f64 && geoid_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A geoid model (--geoid) must be specified to perform a vertical transformation.",
+            ));
+        }
+
+        if to_epsg == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The output EPSG code (--to_epsg) must be specified.",
+            ));
+        }
+        let to_proj = Projection::from_epsg(to_epsg)?;
+
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let from_proj = if from_epsg != 0 {
+            Projection::from_epsg(from_epsg)?
+        } else {
+            match Projection::from_wkt(&input.wkt)
+                .or_else(|_| Projection::from_epsg(input.geokeys.find_epsg_code()))
+            {
+                Ok(p) => p,
+                Err(_) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The coordinate system of the input file could not be determined. Please specify --from_epsg.",
+                    ))
+                }
+            }
+        };
+
+        if verbose {
+            println!("Input coordinate system: {}", from_proj.name);
+            println!("Output coordinate system: {}", to_proj.name);
+        }
+        if from_proj.datum != to_proj.datum || !from_proj.has_same_ellipsoid(&to_proj) {
+            println!(
+                "WARNING: The input ({}) and output ({}) datums differ. No datum transformation will be applied.",
+                from_proj.datum, to_proj.datum
+            );
+        }
+
+        let geoid = if !geoid_file.is_empty() {
+            if !geoid_file.contains(sep) && !geoid_file.contains("/") {
+                geoid_file = format!("{}{}", working_directory, geoid_file);
+            }
+            if verbose {
+                println!("Reading geoid model...");
+            }
+            Some(Raster::new(&geoid_file, "r")?)
+        } else {
+            None
+        };
+
+        // Elevations are converted between the linear units of the two systems.
+        let from_z_unit = if from_proj.is_geographic() {
+            1f64
+        } else {
+            from_proj.linear_unit
+        };
+        let to_z_unit = if to_proj.is_geographic() {
+            1f64
+        } else {
+            to_proj.linear_unit
+        };
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+        let input = Arc::new(input);
+        let from_proj = Arc::new(from_proj);
+        let to_proj = Arc::new(to_proj);
+        let geoid = Arc::new(geoid);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let input = input.clone();
+            let from_proj = from_proj.clone();
+            let to_proj = to_proj.clone();
+            let geoid = geoid.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut p: PointData;
+                for i in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                    p = input[i];
+                    let (lon, lat) = from_proj.inverse(p.x, p.y);
+                    let (x, y) = to_proj.forward(lon, lat);
+                    let mut z = p.z * from_z_unit;
+                    if vertical_sign != 0f64 {
+                        if let Some(ref geoid) = *geoid {
+                            match interpolate_undulation(geoid, lon, lat) {
+                                Some(n) => z += vertical_sign * n,
+                                None => {
+                                    tx.send(Err(i)).unwrap();
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                    tx.send(Ok((i, x, y, z / to_z_unit))).unwrap();
+                }
+            });
+        }
+
+        let mut transformed = vec![(0f64, 0f64, 0f64); n_points];
+        for i in 0..n_points {
+            match rx.recv().expect("Error receiving data from thread.") {
+                Ok((point_num, x, y, z)) => transformed[point_num] = (x, y, z),
+                Err(point_num) => {
+                    let p = input[point_num];
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The point ({}, {}) is located outside of the geoid model.",
+                            p.x, p.y
+                        ),
+                    ));
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Reprojecting points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        if to_proj.is_geographic() {
+            output.header.x_scale_factor = 0.0000001;
+            output.header.y_scale_factor = 0.0000001;
+        }
+
+        // Replace the coordinate system VLRs of the input file.
+        output.vlr_data.retain(|vlr| {
+            vlr.record_id != 34_735
+                && vlr.record_id != 34_736
+                && vlr.record_id != 34_737
+                && vlr.record_id != 2112
+        });
+
+        let mut key_directory = vec![1u16, 1, 0, 2];
+        if to_proj.is_geographic() {
+            key_directory.extend_from_slice(&[1024, 0, 1, 2, 2048, 0, 1, to_epsg]);
+        } else {
+            key_directory.extend_from_slice(&[1024, 0, 1, 1, 3072, 0, 1, to_epsg]);
+        }
+        let mut vlr1: Vlr = Default::default();
+        vlr1.user_id = String::from("LASF_Projection");
+        vlr1.record_id = 34_735u16;
+        vlr1.description = String::from("GeoTiff Projection Keys");
+        for n in key_directory {
+            vlr1.binary_data.write_u16::<LittleEndian>(n)?;
+        }
+        vlr1.record_length_after_header = vlr1.binary_data.len() as u16;
+        output.vlr_data.push(vlr1);
+
+        let wkt = esri_wkt_from_epsg(to_epsg);
+        let mut vlr2: Vlr = Default::default();
+        vlr2.user_id = String::from("LASF_Projection");
+        vlr2.record_id = 2112u16;
+        vlr2.description = String::from("OGC WKT Coordinate System");
+        vlr2.binary_data = (format!("{}\0", wkt)).as_bytes().to_vec();
+        vlr2.record_length_after_header = vlr2.binary_data.len() as u16;
+        output.vlr_data.push(vlr2);
+        output.header.number_of_vlrs = output.vlr_data.len() as u32;
+        output.wkt = wkt;
+
+        for i in 0..n_points {
+            let (x, y, z) = transformed[i];
+            let pr = input.get_record(i);
+            let pr2: LidarPointRecord;
+            match pr {
+                LidarPointRecord::PointRecord0 { mut point_data } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord0 {
+                        point_data: point_data,
+                    };
+                }
+                LidarPointRecord::PointRecord1 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord1 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord2 {
+                    mut point_data,
+                    colour_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord2 {
+                        point_data: point_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord3 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord3 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord4 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord4 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord5 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord5 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord6 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord6 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord7 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord7 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord8 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord8 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord9 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord9 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord10 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.x = x;
+                    point_data.y = y;
+                    point_data.z = z;
+                    pr2 = LidarPointRecord::PointRecord10 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+            }
+            output.add_point_record(pr2);
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Creating output: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// Bilinearly interpolates the geoid undulation at a location from a geographic grid.
+fn interpolate_undulation(geoid: &Raster, lon: f64, lat: f64) -> Option<f64> {
+    let nodata = geoid.configs.nodata;
+    let rows = geoid.configs.rows as isize;
+    let columns = geoid.configs.columns as isize;
+    // the grid may use either a -180 to 180 or a 0 to 360 longitude range
+    let lon = if lon < geoid.configs.west {
+        lon + 360f64
+    } else if lon > geoid.configs.east {
+        lon - 360f64
+    } else {
+        lon
+    };
+    // fractional cell coordinates relative to the cell centres
+    let c = (lon - geoid.configs.west) / geoid.configs.resolution_x - 0.5;
+    let r = (geoid.configs.north - lat) / geoid.configs.resolution_y - 0.5;
+    if c < -0.5 || r < -0.5 || c > columns as f64 - 0.5 || r > rows as f64 - 0.5 {
+        return None;
+    }
+    let c0 = (c.floor() as isize).max(0).min(columns - 1);
+    let r0 = (r.floor() as isize).max(0).min(rows - 1);
+    let c1 = (c0 + 1).min(columns - 1);
+    let r1 = (r0 + 1).min(rows - 1);
+    let fc = (c - c0 as f64).max(0f64).min(1f64);
+    let fr = (r - r0 as f64).max(0f64).min(1f64);
+    let z00 = geoid.get_value(r0, c0);
+    let z01 = geoid.get_value(r0, c1);
+    let z10 = geoid.get_value(r1, c0);
+    let z11 = geoid.get_value(r1, c1);
+    if z00 == nodata || z01 == nodata || z10 == nodata || z11 == nodata {
+        return None;
+    }
+    Some(
+        z00 * (1f64 - fc) * (1f64 - fr)
+            + z01 * fc * (1f64 - fr)
+            + z10 * (1f64 - fc) * fr
+            + z11 * fc * fr,
+    )
+}
@@ -2,23 +2,39 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 21/09/2018
-Last Modified: 31/08/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
 use self::na::Vector3;
-use crate::algorithms::{point_in_poly, triangulate};
+use crate::algorithms::{constrained_triangulate, point_in_poly};
 use crate::lidar::*;
 use crate::na;
 use crate::raster::*;
-use crate::structures::{BoundingBox, Point2D};
+use crate::structures::Point2D;
+use crate::tools::breaklines::BreaklineInputs;
 use crate::tools::*;
 use num_cpus;
 use std::io::{Error, ErrorKind};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
-use std::{env, f64, fs, path, thread};
-
+use std::{env, f64, path, thread};
+
+/// This tool creates a raster grid based on a Delaunay triangular irregular network (TIN) fitted to LiDAR
+/// points (`--input`) and linear interpolation within each triangular facet. If no input file is specified,
+/// all of the LAS files in the working directory are interpolated, with points from neighbouring tiles
+/// within the buffer distance (`--buffer`) included to avoid edge effects.
+///
+/// When interpolating elevation, breaklines may optionally be enforced as the edges of the TIN using a
+/// constrained Delaunay triangulation. Hard breaklines (`--breaklines`), such as surveyed shorelines or
+/// road edges, carry their own elevations, taken from an attribute field (`--breakline_field`) or from the
+/// z values of the line or polygon geometries. Soft breaklines (`--soft_breaklines`) constrain the
+/// triangle edges only, with the elevations of their vertices interpolated from the neighbouring points.
+/// An optional polygon boundary file (`--boundary`) restricts the gridded area to the interior of the
+/// polygons, excluding any holes.
+///
+/// # See Also
+/// `TINGridding`, `LidarHydroFlatten`, `LidarNearestNeighbourGridding`
 pub struct LidarTINGridding {
     name: String,
     description: String,
@@ -133,6 +149,17 @@ impl LidarTINGridding {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Tile Buffer Size".to_owned(),
+            flags: vec!["--buffer".to_owned()],
+            description: "Size of the buffer of points from neighbouring tiles included when interpolating each tile.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.extend(BreaklineInputs::parameters("interpolation"));
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -144,7 +171,8 @@ impl LidarTINGridding {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --returns=last --resolution=2.0 --exclude_cls='3,4,5,6,7,18' --max_triangle_edge_length=5.0", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --returns=last --resolution=2.0 --exclude_cls='3,4,5,6,7,18' --max_triangle_edge_length=5.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=1.0 --exclude_cls='3,4,5,6,7,18' --breaklines=shorelines.shp --breakline_field=ELEV", short_exe, name).replace("*", &sep);
 
         LidarTINGridding {
             name: name,
@@ -208,6 +236,8 @@ impl WhiteboxTool for LidarTINGridding {
         let mut max_z = f64::INFINITY;
         let mut min_z = f64::NEG_INFINITY;
         let mut max_triangle_edge_length = f64::INFINITY;
+        let mut buffer = 10f64;
+        let mut breakline_inputs = BreaklineInputs::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -339,6 +369,25 @@ impl WhiteboxTool for LidarTINGridding {
                 };
 
                 max_triangle_edge_length *= max_triangle_edge_length; // actually squared distance
+            } else if flag_val == "-buffer" {
+                buffer = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if BreaklineInputs::FLAGS.contains(&flag_val.as_str()) {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                breakline_inputs.set(&flag_val, value);
             }
         }
 
@@ -366,50 +415,27 @@ impl WhiteboxTool for LidarTINGridding {
             early_returns = false;
         }
 
-        let search_radius = 1f64;
-
-        let mut inputs = vec![];
+        let tiles: LasTileSet;
         let mut outputs = vec![];
         if input_file.is_empty() {
             if working_directory.is_empty() {
                 return Err(Error::new(ErrorKind::InvalidInput,
                     "This tool must be run by specifying either an individual input file or a working directory."));
             }
-            if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                        .path()
-                        .into_os_string()
-                        .to_str()
-                        .expect("Error reading path string")
-                        .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
-                }
-            } else {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("The input directory ({}) is incorrect.", working_directory),
-                ));
+            tiles = LasTileSet::from_directory(working_directory)?;
+            for in_file in &tiles.files {
+                outputs.push(
+                    in_file
+                        .replace(".las", ".tif")
+                        .replace(".LAS", ".tif")
+                        .replace(".zip", ".tif")
+                        .replace(".ZIP", ".tif"),
+                );
             }
         } else {
             if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
                 input_file = format!("{}{}", working_directory, input_file);
             }
-            inputs.push(input_file.clone());
             if output_file.is_empty() {
                 output_file = input_file
                     .clone()
@@ -419,40 +445,57 @@ impl WhiteboxTool for LidarTINGridding {
             if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
                 output_file = format!("{}{}", working_directory, output_file);
             }
+            tiles = LasTileSet::new(vec![input_file.clone()])?;
             outputs.push(output_file);
         }
 
-        /*
-        If multiple files are being interpolated, we will need to know their bounding boxes,
-        in order to retrieve points from adjacent tiles. This is so that there are no edge
-        effects.
-        */
-        let mut bounding_boxes = vec![];
-        for in_file in &inputs {
-            let header = LasHeader::read_las_header(&in_file.replace("\"", ""))?;
-            bounding_boxes.push(BoundingBox {
-                min_x: header.min_x,
-                max_x: header.max_x,
-                min_y: header.min_y,
-                max_y: header.max_y,
-            });
+        // read the breaklines, which are shared by all tiles
+        let mut breakline_points = vec![];
+        let mut breakline_z = vec![];
+        let mut breakline_constraints = vec![];
+        breakline_inputs.resolve_paths(working_directory);
+        if !breakline_inputs.is_empty() {
+            if interp_parameter != "elevation" && interp_parameter != "z" {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Breaklines and boundaries may only be used when interpolating elevation.",
+                ));
+            }
         }
+        let boundary = breakline_inputs.add_to(
+            &mut breakline_points,
+            &mut breakline_z,
+            &mut breakline_constraints,
+        )?;
+        let boundary_vertex_start = boundary.vertex;
+        let boundary_start = boundary.constraint;
+        let use_boundary = breakline_inputs.has_boundary();
+        let breakline_points = Arc::new(breakline_points);
+        let breakline_z = Arc::new(breakline_z);
+        let breakline_constraints = Arc::new(breakline_constraints);
 
+        /*
+        If multiple files are being interpolated, points from adjacent tiles within the buffer
+        distance of a tile are included in its triangulation, and the output grid is then
+        trimmed to the extent of the tile. This is so that there are no edge effects.
+        */
         if verbose {
             println!("Performing interpolation...");
         }
 
-        let num_tiles = inputs.len();
+        let num_tiles = tiles.len();
         let tile_list = Arc::new(Mutex::new(0..num_tiles));
-        let inputs = Arc::new(inputs);
+        let inputs = Arc::new(tiles.files.clone());
         let outputs = Arc::new(outputs);
-        let bounding_boxes = Arc::new(bounding_boxes);
+        let bounding_boxes = Arc::new(tiles.extents.clone());
+        let tiles = Arc::new(tiles);
         let num_procs2 = num_cpus::get() as isize;
         let (tx2, rx2) = mpsc::channel();
         for _ in 0..num_procs2 {
             let inputs = inputs.clone();
             let outputs = outputs.clone();
             let bounding_boxes = bounding_boxes.clone();
+            let tiles = tiles.clone();
             let tile_list = tile_list.clone();
             // copy over the string parameters
             let interp_parameter = interp_parameter.clone();
@@ -461,6 +504,9 @@ impl WhiteboxTool for LidarTINGridding {
             let tool_name = self.get_tool_name();
             let exclude_cls_str = exclude_cls_str.clone();
             let include_class_vals = include_class_vals.clone();
+            let breakline_points = breakline_points.clone();
+            let breakline_z = breakline_z.clone();
+            let breakline_constraints = breakline_constraints.clone();
             let tx2 = tx2.clone();
             thread::spawn(move || {
                 let mut tile = 0;
@@ -476,12 +522,7 @@ impl WhiteboxTool for LidarTINGridding {
                     let output_file = outputs[tile].replace("\"", "").clone();
 
                     // Expand the bounding box to include the areas of overlap
-                    let bb = BoundingBox {
-                        min_x: bounding_boxes[tile].min_x - search_radius,
-                        max_x: bounding_boxes[tile].max_x + search_radius,
-                        min_y: bounding_boxes[tile].min_y - search_radius,
-                        max_y: bounding_boxes[tile].max_y + search_radius,
-                    };
+                    let bb = tiles.buffered_extent(tile, buffer);
 
                     let mut points = vec![];
                     let mut z_values = vec![];
@@ -493,252 +534,61 @@ impl WhiteboxTool for LidarTINGridding {
                     let mut progress: i32;
                     let mut old_progress: i32 = -1;
 
-                    for m in 0..inputs.len() {
-                        if bounding_boxes[m].overlaps(bb) {
-                            let input =
-                                match LasFile::new(&inputs[m].replace("\"", "").clone(), "r") {
-                                    Ok(lf) => lf,
-                                    Err(err) => panic!(
-                                        "Error reading file {}: {}",
-                                        inputs[m].replace("\"", ""),
-                                        err
-                                    ),
-                                };
-
-                            let n_points = input.header.number_of_points as usize;
-                            let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
-
-                            match &interp_parameter as &str {
-                                "elevation" | "z" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.z);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "intensity" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.intensity as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "scan angle" | "scan_angle" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.scan_angle as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "class" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.classification() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "return_number" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.return_number() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "number_of_returns" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.number_of_returns() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "rgb" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        // let val = input.get_rgb(i); // ((a << 24) | (b << 16) | (g << 8) | r) as f64;
-                                                        z_values.push(p.number_of_returns() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                _ => {
-                                    // user data
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.user_data as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
+                    let is_included = |p: &PointData| -> bool {
+                        !p.withheld()
+                            && (all_returns
+                                || (p.is_late_return() & late_returns)
+                                || (p.is_early_return() & early_returns))
+                            && include_class_vals[p.classification() as usize]
+                            && p.z >= min_z
+                            && p.z <= max_z
+                    };
+                    let point_value = |p: &PointData| -> f64 {
+                        match &interp_parameter as &str {
+                            "elevation" | "z" => p.z,
+                            "intensity" => p.intensity as f64,
+                            "scan angle" | "scan_angle" => p.scan_angle as f64,
+                            "class" => p.classification() as f64,
+                            "return_number" => p.return_number() as f64,
+                            // let val = input.get_rgb(i); // ((a << 24) | (b << 16) | (g << 8) | r) as f64;
+                            "number_of_returns" | "rgb" => p.number_of_returns() as f64,
+                            _ => p.user_data as f64, // user data
+                        }
+                    };
+
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => panic!("Error reading file {}: {}", input_file, err),
+                    };
+                    let n_points = input.header.number_of_points as usize;
+                    let num_points: f64 = (n_points.max(2) - 1) as f64; // used for progress calculation only
+                    for i in 0..n_points {
+                        let p: PointData = input[i];
+                        if is_included(&p) {
+                            points.push(Point2D { x: p.x, y: p.y });
+                            z_values.push(point_value(&p));
+                        }
+                        if verbose && inputs.len() == 1 {
+                            progress = (100.0_f64 * i as f64 / num_points) as i32;
+                            if progress != old_progress {
+                                println!("Reading points: {}%", progress);
+                                old_progress = progress;
                             }
                         }
                     }
 
+                    // add the points of the neighbouring tiles within the buffer
+                    let buffer_points = match tiles.read_buffer_points(tile, buffer) {
+                        Ok(p) => p,
+                        Err(err) => panic!("Error reading the tile buffer: {}", err),
+                    };
+                    for p in buffer_points {
+                        if is_included(&p) {
+                            points.push(Point2D { x: p.x, y: p.y });
+                            z_values.push(point_value(&p));
+                        }
+                    }
+
                     if points.len() == 0 {
                         if verbose {
                             println!("No points found in {}", inputs[tile].clone());
@@ -781,7 +631,44 @@ impl WhiteboxTool for LidarTINGridding {
                     if num_tiles == 1 && verbose {
                         println!("Performing triangulation...");
                     }
-                    let result = triangulate(&points).expect("No triangulation exists.");
+                    // add the breaklines within the tile's buffered extent; boundaries are always
+                    // added in their entirety so that the interior can be identified
+                    let mut constraints = vec![];
+                    let mut tile_boundary_start = 0;
+                    let mut vertex_index = vec![None; breakline_points.len()];
+                    for k in 0..breakline_points.len() {
+                        if k >= boundary_vertex_start
+                            || bb.is_point_in_box(breakline_points[k].x, breakline_points[k].y)
+                        {
+                            vertex_index[k] = Some(points.len());
+                            points.push(breakline_points[k]);
+                            z_values.push(breakline_z[k]);
+                        }
+                    }
+                    for (c, &(a, b)) in breakline_constraints.iter().enumerate() {
+                        if let (Some(a), Some(b)) = (vertex_index[a], vertex_index[b]) {
+                            constraints.push((a, b));
+                        }
+                        if c + 1 == boundary_start {
+                            tile_boundary_start = constraints.len();
+                        }
+                    }
+
+                    let cdt = constrained_triangulate(&points, &constraints)
+                        .expect("No triangulation exists.");
+                    if verbose && num_tiles == 1 && !cdt.failed_constraints.is_empty() {
+                        println!(
+                            "Warning: {} breakline segments intersect other breaklines and could not be enforced.",
+                            cdt.failed_constraints.len()
+                        );
+                    }
+                    cdt.interpolate_missing_values(&points, &mut z_values);
+                    let inside = if use_boundary {
+                        cdt.mask_triangles(|c| c >= tile_boundary_start)
+                    } else {
+                        vec![true; cdt.triangulation.len()]
+                    };
+                    let result = &cdt.triangulation;
                     let num_triangles = result.triangles.len() / 3;
 
                     let (mut p1, mut p2, mut p3): (usize, usize, usize);
@@ -807,14 +694,15 @@ impl WhiteboxTool for LidarTINGridding {
                             p2 = result.triangles[i + 1];
                             p3 = result.triangles[i + 2];
 
-                            if max_distance_squared(
-                                points[p1],
-                                points[p2],
-                                points[p3],
-                                z_values[p1],
-                                z_values[p2],
-                                z_values[p3],
-                            ) < max_triangle_edge_length
+                            if inside[triangle]
+                                && max_distance_squared(
+                                    points[p1],
+                                    points[p2],
+                                    points[p3],
+                                    z_values[p1],
+                                    z_values[p2],
+                                    z_values[p3],
+                                ) < max_triangle_edge_length
                             {
                                 tri_points[0] = points[p1].clone();
                                 tri_points[1] = points[p2].clone();
@@ -878,14 +766,15 @@ impl WhiteboxTool for LidarTINGridding {
                             p2 = result.triangles[i + 1];
                             p3 = result.triangles[i + 2];
 
-                            if max_distance_squared(
-                                points[p1],
-                                points[p2],
-                                points[p3],
-                                z_values[p1],
-                                z_values[p2],
-                                z_values[p3],
-                            ) < max_triangle_edge_length
+                            if inside[triangle]
+                                && max_distance_squared(
+                                    points[p1],
+                                    points[p2],
+                                    points[p3],
+                                    z_values[p1],
+                                    z_values[p2],
+                                    z_values[p3],
+                                ) < max_triangle_edge_length
                             {
                                 tri_points[0] = points[p1].clone();
                                 tri_points[1] = points[p2].clone();
@@ -976,7 +865,7 @@ impl WhiteboxTool for LidarTINGridding {
                     ));
                     output.add_metadata_entry(format!("Input file: {}", input_file));
                     output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
-                    output.add_metadata_entry(format!("Search radius: {}", search_radius));
+                    output.add_metadata_entry(format!("Tile buffer size: {}", buffer));
                     output.add_metadata_entry(format!(
                         "Interpolation parameter: {}",
                         interp_parameter
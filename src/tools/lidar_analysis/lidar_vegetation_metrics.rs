@@ -0,0 +1,823 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// This tool calculates a suite of area-based vegetation structure metrics, of the kind commonly used in
+/// forest inventory modelling, for each grid cell of a user-specified resolution (`--resolution`). The input
+/// LAS file (`--input`) must contain **height-normalized** point elevations, i.e. the z value of each point
+/// must be its height above the ground surface. The output of the `HeightAboveGround` tool is suitable for
+/// this purpose.
+///
+/// Points with heights greater than the height break (`--height_break`, default 2.0) are considered to be
+/// canopy returns. The height distribution metrics (percentiles, moments and L-moments) are calculated from the
+/// canopy returns in each grid cell, while the cover and return-proportion metrics are calculated using all
+/// returns. Points that are withheld or classified as noise (classes 7 and 18) are always excluded, as are points
+/// with heights greater than the optional maximum height (`--max_height`), which can be used to remove
+/// remaining high outliers.
+///
+/// The user may select which groups of metrics to output using the following flags:
+///
+/// | Flag               | Output rasters                                                                       |
+/// | :----------------- | :----------------------------------------------------------------------------------- |
+/// | `--percentiles`    | Height percentiles (_p10, _p25, ...); the list of percentiles may be specified       |
+/// | `--moments`        | Max. (_max), mean (_mean), std. dev. (_stdev), skewness (_skew), kurtosis (_kurt)    |
+/// | `--cover`          | Canopy cover, the proportion of first returns above the height break (_cover)        |
+/// | `--return_ratios`  | Proportion of all returns above the break (_all_above), all returns above the break per first return (_all_above_first) and proportion of last returns above the break (_last_above) |
+/// | `--lmoments`       | L-moments (_l1, _l2, _l3, _l4), L-coefficient of variation (_lcv), L-skewness (_lskew) and L-kurtosis (_lkurt) |
+///
+/// The `--percentiles` parameter accepts a comma-separated list of percentile values (0-100), e.g.
+/// `--percentiles='10,25,50,75,90,95,99'`, which is the default. If none of the flags are specified, all of the
+/// metrics will be created. All output rasters will have the same base name as the input LAS file but will have
+/// a suffix that reflects the metric type, and are saved in the GeoTIFF (*.tif) format. Grid cells that contain
+/// too few canopy returns to estimate a metric (e.g. fewer than four for L-kurtosis) are assigned NoData.
+///
+/// Single returns are treated as both first and last returns.
+///
+/// Kurtosis is reported as the non-excess (Pearson) kurtosis, i.e. a normal distribution has a kurtosis of 3.
+/// L-moments are calculated from the sample probability-weighted moments (Hosking, 1990).
+///
+/// When the input parameter is not specified, the tool works on all LAS files contained within the working
+/// directory, processing tiles in parallel.
+///
+/// # Reference
+/// Hosking, J. R. M. (1990). L-moments: analysis and estimation of distributions using linear combinations
+/// of order statistics. *Journal of the Royal Statistical Society, Series B*, 52(1), 105-124.
+///
+/// # See Also
+/// `HeightAboveGround`, `LidarPointStats`, `LidarPointDensity`
+pub struct LidarVegetationMetrics {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarVegetationMetrics {
+    pub fn new() -> LidarVegetationMetrics {
+        // public constructor
+        let name = "LidarVegetationMetrics".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Calculates area-based vegetation structure metrics (height percentiles, canopy cover, moments, return ratios and L-moments) from height-normalized LiDAR points.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("20.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Break".to_owned(),
+            flags: vec!["--height_break".to_owned()],
+            description: "Height above which points are considered to be canopy returns."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Height (optional)".to_owned(),
+            flags: vec!["--max_height".to_owned()],
+            description: "Optional maximum height; points above this height are excluded."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Percentiles".to_owned(),
+            flags: vec!["--percentiles".to_owned()],
+            description: "Comma-separated list of height percentiles to output (e.g. '10,25,50,75,90,95,99').".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output height moments?".to_owned(),
+            flags: vec!["--moments".to_owned()],
+            description: "Flag indicating whether or not to output the max, mean, standard deviation, skewness and kurtosis of height rasters.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output canopy cover?".to_owned(),
+            flags: vec!["--cover".to_owned()],
+            description: "Flag indicating whether or not to output the canopy cover raster."
+                .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output return ratios?".to_owned(),
+            flags: vec!["--return_ratios".to_owned()],
+            description:
+                "Flag indicating whether or not to output the return-proportion ratio rasters."
+                    .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output L-moments?".to_owned(),
+            flags: vec!["--lmoments".to_owned()],
+            description: "Flag indicating whether or not to output the L-moment rasters."
+                .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las --resolution=20.0 --height_break=2.0 --percentiles='25,50,75,95' --cover --moments",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarVegetationMetrics {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarVegetationMetrics {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut grid_res: f64 = 20.0;
+        let mut height_break = 2.0f64;
+        let mut max_height = f64::INFINITY;
+        let mut percentiles_str = String::new();
+        let mut output_moments = false;
+        let mut output_cover = false;
+        let mut output_ratios = false;
+        let mut output_lmoments = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-height_break" {
+                height_break = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_height" {
+                max_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-percentiles" {
+                percentiles_str = if keyval {
+                    vec[1].to_string()
+                } else if i + 1 < args.len() && !args[i + 1].starts_with("-") {
+                    args[i + 1].to_string()
+                } else {
+                    "10,25,50,75,90,95,99".to_string()
+                };
+            } else if flag_val == "-moments" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    output_moments = true;
+                }
+            } else if flag_val == "-cover" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    output_cover = true;
+                }
+            } else if flag_val == "-return_ratios" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    output_ratios = true;
+                }
+            } else if flag_val == "-lmoments" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    output_lmoments = true;
+                }
+            }
+        }
+
+        if grid_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution must be greater than zero.",
+            ));
+        }
+
+        let start = Instant::now();
+
+        // check to see if all of the outputs are false and if so, set them all the true
+        if percentiles_str.is_empty()
+            && !output_moments
+            && !output_cover
+            && !output_ratios
+            && !output_lmoments
+        {
+            percentiles_str = "10,25,50,75,90,95,99".to_string();
+            output_moments = true;
+            output_cover = true;
+            output_ratios = true;
+            output_lmoments = true;
+        }
+
+        let mut percentiles = vec![];
+        for s in percentiles_str.split(|c| c == ',' || c == ';') {
+            if !s.trim().is_empty() {
+                let pct = s.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Error parsing the percentile value '{}'.", s.trim()),
+                    )
+                })?;
+                if pct < 0f64 || pct > 100f64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Percentile values must be between 0 and 100.",
+                    ));
+                }
+                percentiles.push(pct);
+            }
+        }
+
+        let mut inputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") {
+                        inputs.push(s);
+                    } else if s.to_lowercase().ends_with(".zip") {
+                        inputs.push(s);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
+                input_file = format!("{}{}", working_directory, input_file);
+            }
+            inputs.push(input_file.clone());
+        }
+
+        if inputs.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No LAS files were found in the working directory.",
+            ));
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let percentiles = Arc::new(percentiles);
+        let num_procs = num_cpus::get().min(num_tiles) as isize;
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..num_procs {
+            let inputs = inputs.clone();
+            let tile_list = tile_list.clone();
+            let percentiles = percentiles.clone();
+            let tool_name = self.get_tool_name();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                while tile < num_tiles {
+                    // Get the next tile up for processing
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to process
+                    };
+                    let start_run = Instant::now();
+
+                    let input_file = inputs[tile].replace("\"", "").clone();
+                    if verbose && num_tiles == 1 {
+                        println!("Reading input LAS file...");
+                    }
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => {
+                            tx.send((
+                                tile,
+                                Err(format!("Error reading file {}: {}", input_file, err)),
+                            ))
+                            .unwrap();
+                            continue;
+                        }
+                    };
+
+                    let west: f64 = input.header.min_x;
+                    let north: f64 = input.header.max_y;
+                    let rows = (((north - input.header.min_y) / grid_res).ceil() as usize).max(1);
+                    let columns = (((input.header.max_x - west) / grid_res).ceil() as usize).max(1);
+                    let south: f64 = north - rows as f64 * grid_res;
+                    let east = west + columns as f64 * grid_res;
+                    let nodata = -32768.0f64;
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows;
+                    configs.columns = columns;
+                    configs.north = north;
+                    configs.south = south;
+                    configs.east = east;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F32;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+                    // Bin the points by grid cell. Each entry stores the cell index, the height and
+                    // whether the point is a first and/or last return.
+                    let n_points = input.header.number_of_points as usize;
+                    let num_cells = rows * columns;
+                    let mut bins: Vec<(usize, f64, bool, bool)> = Vec::with_capacity(n_points);
+                    let mut progress: i32;
+                    let mut old_progress: i32 = -1;
+                    for i in 0..n_points {
+                        let p: PointData = input[i];
+                        if !p.withheld() && !p.is_classified_noise() && p.z <= max_height {
+                            let col = (((p.x - west) / grid_res).floor() as usize).min(columns - 1);
+                            let row = (((north - p.y) / grid_res).floor() as usize).min(rows - 1);
+                            bins.push((
+                                row * columns + col,
+                                p.z,
+                                p.is_early_return(),
+                                p.is_late_return(),
+                            ));
+                        }
+                        if verbose && num_tiles == 1 {
+                            progress = (100.0_f64 * i as f64 / n_points as f64) as i32;
+                            if progress != old_progress {
+                                println!("Binning points: {}%", progress);
+                                old_progress = progress;
+                            }
+                        }
+                    }
+                    drop(input);
+                    bins.sort_by(|a, b| a.0.cmp(&b.0));
+
+                    let num_outputs = percentiles.len()
+                        + if output_moments { 5 } else { 0 }
+                        + if output_cover { 1 } else { 0 }
+                        + if output_ratios { 3 } else { 0 }
+                        + if output_lmoments { 7 } else { 0 };
+                    let mut values = vec![vec![nodata; num_cells]; num_outputs];
+
+                    let mut heights: Vec<f64> = vec![];
+                    let mut i = 0;
+                    while i < bins.len() {
+                        let cell = bins[i].0;
+                        let mut j = i;
+                        heights.clear();
+                        let (mut num_first, mut num_first_above) = (0f64, 0f64);
+                        let (mut num_last, mut num_last_above) = (0f64, 0f64);
+                        let (mut num_all, mut num_all_above) = (0f64, 0f64);
+                        while j < bins.len() && bins[j].0 == cell {
+                            let (_, z, is_first, is_last) = bins[j];
+                            let above = z > height_break;
+                            num_all += 1f64;
+                            if above {
+                                num_all_above += 1f64;
+                                heights.push(z);
+                            }
+                            if is_first {
+                                num_first += 1f64;
+                                if above {
+                                    num_first_above += 1f64;
+                                }
+                            }
+                            if is_last {
+                                num_last += 1f64;
+                                if above {
+                                    num_last_above += 1f64;
+                                }
+                            }
+                            j += 1;
+                        }
+                        i = j;
+
+                        heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                        let mut k = 0;
+                        for pct in percentiles.iter() {
+                            if heights.len() > 0 {
+                                values[k][cell] = percentile(&heights, *pct);
+                            }
+                            k += 1;
+                        }
+                        if output_moments {
+                            if heights.len() > 0 {
+                                let (mean, stdev, skew, kurt) = moments(&heights);
+                                values[k][cell] = heights[heights.len() - 1];
+                                values[k + 1][cell] = mean;
+                                values[k + 2][cell] = stdev;
+                                if stdev > 0f64 {
+                                    values[k + 3][cell] = skew;
+                                    values[k + 4][cell] = kurt;
+                                }
+                            }
+                            k += 5;
+                        }
+                        if output_cover {
+                            if num_first > 0f64 {
+                                values[k][cell] = num_first_above / num_first;
+                            }
+                            k += 1;
+                        }
+                        if output_ratios {
+                            values[k][cell] = num_all_above / num_all;
+                            if num_first > 0f64 {
+                                values[k + 1][cell] = num_all_above / num_first;
+                            }
+                            if num_last > 0f64 {
+                                values[k + 2][cell] = num_last_above / num_last;
+                            }
+                            k += 3;
+                        }
+                        if output_lmoments {
+                            let l = l_moments(&heights);
+                            for m in 0..4 {
+                                if heights.len() > m {
+                                    values[k + m][cell] = l[m];
+                                }
+                            }
+                            if heights.len() > 1 && l[0] != 0f64 {
+                                values[k + 4][cell] = l[1] / l[0];
+                            }
+                            if heights.len() > 2 && l[1] > 0f64 {
+                                values[k + 5][cell] = l[2] / l[1];
+                            }
+                            if heights.len() > 3 && l[1] > 0f64 {
+                                values[k + 6][cell] = l[3] / l[1];
+                            }
+                        }
+                    }
+                    drop(bins);
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+
+                    let mut suffixes = vec![];
+                    for pct in percentiles.iter() {
+                        suffixes.push(format!("p{}", pct).replace(".", "_"));
+                    }
+                    if output_moments {
+                        for s in &["max", "mean", "stdev", "skew", "kurt"] {
+                            suffixes.push(s.to_string());
+                        }
+                    }
+                    if output_cover {
+                        suffixes.push("cover".to_string());
+                    }
+                    if output_ratios {
+                        for s in &["all_above", "all_above_first", "last_above"] {
+                            suffixes.push(s.to_string());
+                        }
+                    }
+                    if output_lmoments {
+                        for s in &["l1", "l2", "l3", "l4", "lcv", "lskew", "lkurt"] {
+                            suffixes.push(s.to_string());
+                        }
+                    }
+
+                    if verbose && num_tiles == 1 {
+                        println!("Saving data...")
+                    };
+
+                    let base_name = match input_file.rfind('.') {
+                        Some(idx) => input_file[0..idx].to_string(),
+                        None => input_file.clone(),
+                    };
+                    let mut result = Ok(());
+                    for (k, data) in values.into_iter().enumerate() {
+                        let output_file = format!("{}_{}.tif", base_name, suffixes[k]);
+                        let mut output = Raster::initialize_using_config(&output_file, &configs);
+                        for row in 0..rows {
+                            output.set_row_data(
+                                row as isize,
+                                data[row * columns..(row + 1) * columns].to_vec(),
+                            );
+                        }
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Metric: {}", suffixes[k]));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Height break: {}", height_break));
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (excluding I/O): {}",
+                            elapsed_time_run
+                        ));
+                        if let Err(e) = output.write() {
+                            result = Err(format!("Error writing {}: {}", output_file, e));
+                        }
+                    }
+
+                    tx.send((tile, result)).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for tile in 0..num_tiles {
+            let (tile_completed, result) = rx.recv().unwrap();
+            if let Err(msg) = result {
+                return Err(Error::new(ErrorKind::Other, msg));
+            }
+            if verbose {
+                println!(
+                    "Finished {} ({} of {})",
+                    inputs[tile_completed]
+                        .replace("\"", "")
+                        .replace(working_directory, "")
+                        .replace(".las", ""),
+                    tile + 1,
+                    num_tiles
+                );
+                progress = (100.0_f64 * (tile + 1) as f64 / num_tiles as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the linearly interpolated percentile (0-100) of a sorted, non-empty slice.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = pct / 100f64 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Returns the mean, standard deviation, skewness and (non-excess) kurtosis of a non-empty slice.
+fn moments(values: &[f64]) -> (f64, f64, f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let (mut m2, mut m3, mut m4) = (0f64, 0f64, 0f64);
+    for v in values {
+        let d = v - mean;
+        m2 += d * d;
+        m3 += d * d * d;
+        m4 += d * d * d * d;
+    }
+    m2 /= n;
+    m3 /= n;
+    m4 /= n;
+    let stdev = m2.sqrt();
+    if m2 > 0f64 {
+        (mean, stdev, m3 / m2.powf(1.5), m4 / (m2 * m2))
+    } else {
+        (mean, stdev, 0f64, 0f64)
+    }
+}
+
+/// Returns the first four sample L-moments of a sorted slice, calculated from the
+/// unbiased probability-weighted moments b0-b3. Moments that cannot be estimated
+/// from the sample size are returned as zero.
+fn l_moments(sorted: &[f64]) -> [f64; 4] {
+    let n = sorted.len();
+    if n == 0 {
+        return [0f64; 4];
+    }
+    let nf = n as f64;
+    let (mut b0, mut b1, mut b2, mut b3) = (0f64, 0f64, 0f64, 0f64);
+    for (i, x) in sorted.iter().enumerate() {
+        let j = i as f64; // zero-based rank
+        b0 += x;
+        if n > 1 {
+            b1 += x * j / (nf - 1f64);
+        }
+        if n > 2 {
+            b2 += x * j * (j - 1f64) / ((nf - 1f64) * (nf - 2f64));
+        }
+        if n > 3 {
+            b3 += x * j * (j - 1f64) * (j - 2f64) / ((nf - 1f64) * (nf - 2f64) * (nf - 3f64));
+        }
+    }
+    b0 /= nf;
+    b1 /= nf;
+    b2 /= nf;
+    b3 /= nf;
+    let mut l = [
+        b0,
+        2f64 * b1 - b0,
+        6f64 * b2 - 6f64 * b1 + b0,
+        20f64 * b3 - 30f64 * b2 + 12f64 * b1 - b0,
+    ];
+    for moment in l.iter_mut().skip(n) {
+        *moment = 0f64;
+    }
+    l
+}
+
+#[cfg(test)]
+mod test {
+    use super::{l_moments, moments, percentile};
+
+    #[test]
+    fn test_percentile() {
+        let sorted = [1f64, 2f64, 3f64, 4f64];
+        assert_eq!(percentile(&sorted, 0f64), 1f64);
+        assert_eq!(percentile(&sorted, 50f64), 2.5f64);
+        assert_eq!(percentile(&sorted, 100f64), 4f64);
+        assert_eq!(percentile(&[7f64], 95f64), 7f64);
+    }
+
+    #[test]
+    fn test_moments() {
+        let values = [2f64, 4f64, 4f64, 4f64, 5f64, 5f64, 7f64, 9f64];
+        let (mean, stdev, skew, kurt) = moments(&values);
+        assert!((mean - 5f64).abs() < 1e-12);
+        assert!((stdev - 2f64).abs() < 1e-12);
+        assert!((skew - 0.65625f64).abs() < 1e-12);
+        assert!((kurt - 2.78125f64).abs() < 1e-12);
+        assert_eq!(moments(&[3f64, 3f64]), (3f64, 0f64, 0f64, 0f64));
+    }
+
+    #[test]
+    fn test_l_moments() {
+        // a symmetric, evenly spaced sample has l2 = (n + 1) / 6 and no L-skewness or L-kurtosis
+        let l = l_moments(&[1f64, 2f64, 3f64, 4f64, 5f64]);
+        let expected = [3f64, 1f64, 0f64, 0f64];
+        for i in 0..4 {
+            assert!((l[i] - expected[i]).abs() < 1e-12);
+        }
+        // for [0, 0, 0, 1] every probability-weighted moment is 1/4
+        let l = l_moments(&[0f64, 0f64, 0f64, 1f64]);
+        for i in 0..4 {
+            assert!((l[i] - 0.25f64).abs() < 1e-12);
+        }
+        assert_eq!(l_moments(&[2f64]), [2f64, 0f64, 0f64, 0f64]);
+        assert_eq!(l_moments(&[]), [0f64; 4]);
+    }
+}
@@ -0,0 +1,400 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool calculates the vertical complexity index (VCI) of a LiDAR point cloud within the cells of a
+/// raster grid. The VCI (van Ewijk et al., 2011) describes how evenly LiDAR returns are distributed
+/// through the vertical profile of the vegetation. It is the Shannon entropy of the proportions (p<sub>i</sub>)
+/// of returns within each of the HB height layers of a voxel column, normalized by its maximum value:
+///
+/// > VCI = -&Sigma;(p<sub>i</sub> ln p<sub>i</sub>) / ln(HB)
+///
+/// The VCI ranges from 0, when all of the returns within a column fall within a single layer (e.g. bare
+/// ground), to 1, when the returns are evenly distributed between the ground and the maximum height. The
+/// input LAS file (`--input`) must contain **height-normalized** point elevations, i.e. the z value of each
+/// point must be its height above the ground surface. The output of the `HeightAboveGround` tool is
+/// suitable for this purpose.
+///
+/// The columns have a size of `--resolution`, which is also the resolution of the output raster (`--output`),
+/// and the layers have a thickness of `--layer_height`. The number of layers (HB) is determined by the maximum
+/// height (`--max_height`), which should be set to the maximum expected vegetation height of the study area, so
+/// that values are comparable among grid cells; if it is not specified, the maximum height of the points is
+/// used. Points above the maximum height and below the minimum height (`--min_height`, default 0.0) are
+/// excluded, as are points that are withheld or classified as noise. Slightly negative heights, which are
+/// common among ground returns after height normalization, are assigned to the lowest layer. Grid cells
+/// without returns are assigned NoData.
+///
+/// # Reference
+/// van Ewijk, K. Y., Treitz, P. M., and Scott, N. A. (2011). Characterizing forest succession in central
+/// Ontario using LiDAR-derived indices. *Photogrammetric Engineering and Remote Sensing*, 77(3), 261-269.
+///
+/// # See Also
+/// `LidarLeafAreaDensity`, `LidarVoxelize`, `LidarVegetationMetrics`, `HeightAboveGround`
+pub struct LidarVerticalComplexityIndex {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarVerticalComplexityIndex {
+    pub fn new() -> LidarVerticalComplexityIndex {
+        // public constructor
+        let name = "LidarVerticalComplexityIndex".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Calculates the vertical complexity index (VCI) of height-normalized LiDAR points within a raster grid.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution, i.e. the size of the voxel columns."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Layer Height".to_owned(),
+            flags: vec!["--layer_height".to_owned()],
+            description: "Vertical size of the voxel layers.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description: "Minimum height; points below this height are excluded.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Height (optional)".to_owned(),
+            flags: vec!["--max_height".to_owned()],
+            description: "Maximum height, which determines the number of layers; by default, the maximum height of the points.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=vci.tif --resolution=10.0 --layer_height=1.0 --max_height=40.0", short_exe, name).replace("*", &sep);
+
+        LidarVerticalComplexityIndex {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarVerticalComplexityIndex {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut resolution = 10f64;
+        let mut layer_height = 1f64;
+        let mut min_height = 0f64;
+        let mut max_height = f64::NAN;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution"
+                || flag_val == "-layer_height"
+                || flag_val == "-min_height"
+                || flag_val == "-max_height"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-resolution" => resolution = value,
+                    "-layer_height" => layer_height = value,
+                    "-min_height" => min_height = value,
+                    _ => max_height = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if resolution <= 0f64 || layer_height <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution and layer height must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = LasFile::new(&input_file, "r")?;
+        let n_points = input.header.number_of_points as usize;
+
+        let start = Instant::now();
+
+        let is_valid = |p: &PointData| -> bool {
+            !p.withheld() && !p.is_classified_noise() && p.z >= min_height
+        };
+        if max_height.is_nan() {
+            max_height = f64::NEG_INFINITY;
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if is_valid(&p) {
+                    max_height = max_height.max(p.z);
+                }
+            }
+        }
+        if !max_height.is_finite() || max_height <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The maximum height must be greater than zero.",
+            ));
+        }
+        let num_layers = ((max_height / layer_height).ceil() as isize).max(1);
+        if num_layers < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The maximum height must span at least two layers.",
+            ));
+        }
+
+        // Voxelize the points, clamping their heights to the range of the layers.
+        let mut grid = VoxelGrid::new(resolution, layer_height);
+        for i in 0..n_points {
+            let mut p: PointData = input[i];
+            if is_valid(&p) && p.z <= max_height {
+                p.z = p.z.max(0f64).min((num_layers as f64 - 0.5) * layer_height);
+                grid.add_point(&p);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (n_points - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Voxelizing points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        let columns = grid.columns();
+        if columns.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any points within the height range.",
+            ));
+        }
+
+        let min_col = columns.iter().map(|c| c.0).min().unwrap();
+        let max_col = columns.iter().map(|c| c.0).max().unwrap();
+        let min_row = columns.iter().map(|c| c.1).min().unwrap();
+        let max_row = columns.iter().map(|c| c.1).max().unwrap();
+        let rows = (max_row - min_row + 1) as usize;
+        let cols = (max_col - min_col + 1) as usize;
+        let west = min_col as f64 * resolution;
+        let north = (max_row + 1) as f64 * resolution;
+        let nodata = -32768.0f64;
+
+        let mut configs = RasterConfigs {
+            ..Default::default()
+        };
+        configs.rows = rows;
+        configs.columns = cols;
+        configs.north = north;
+        configs.south = north - rows as f64 * resolution;
+        configs.east = west + cols as f64 * resolution;
+        configs.west = west;
+        configs.resolution_x = resolution;
+        configs.resolution_y = resolution;
+        configs.nodata = nodata;
+        configs.data_type = DataType::F32;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        configs.coordinate_ref_system_wkt = input.get_wkt();
+
+        let mut output = Raster::initialize_using_config(&output_file, &configs);
+        for row in 0..rows {
+            let grid_row = max_row - row as isize;
+            let data: Vec<f64> = (0..cols)
+                .map(|col| {
+                    let profile =
+                        grid.column_profile(min_col + col as isize, grid_row, 0, num_layers - 1);
+                    vertical_complexity_index(&profile).unwrap_or(nodata)
+                })
+                .collect();
+            output.set_row_data(row as isize, data);
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Layer height: {}", layer_height));
+        output.add_metadata_entry(format!("Maximum height: {}", max_height));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool thins a LiDAR point cloud (`--input`) such that no more than one point exists within each
+/// voxel of a three-dimensional grid. Unlike the `LidarThin` and `LidarThinHighDensity` tools, which thin
+/// points within two-dimensional grid cells, voxel-based thinning retains points throughout the vertical
+/// profile, e.g. beneath a vegetation canopy, while reducing the density of oversampled surfaces. This
+/// makes the output suitable for analyses of three-dimensional structure that are sensitive to variable
+/// point density, e.g. caused by overlapping flight lines.
+///
+/// Voxels have a horizontal size of `--resolution` and a vertical size of `--layer_height`, which is equal
+/// to the horizontal size by default. The point that is retained within each voxel is selected using one of
+/// the following methods (`--method`):
+///
+/// | Method     | Retained point                                               |
+/// | :--------- | :----------------------------------------------------------- |
+/// | `first`    | The first point in the file                                  |
+/// | `last`     | The last point in the file                                   |
+/// | `lowest`   | The point with the lowest elevation                          |
+/// | `highest`  | The point with the highest elevation                         |
+/// | `nearest`  | The point nearest the centre of the voxel                    |
+/// | `centroid` | The point nearest the centroid of the points in the voxel    |
+///
+/// The `--save_filtered` flag can be used to save the removed points to a second file, with the same name
+/// as the output file and the suffix `_filtered_points`.
+///
+/// # See Also
+/// `LidarThin`, `LidarThinHighDensity`, `LidarVoxelize`
+pub struct LidarVoxelThin {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarVoxelThin {
+    pub fn new() -> LidarVoxelThin {
+        // public constructor
+        let name = "LidarVoxelThin".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Thins a LiDAR point cloud, retaining no more than one point in each voxel."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Voxel Size".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Horizontal size of the voxels.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Voxel Layer Height (optional)".to_owned(),
+            flags: vec!["--layer_height".to_owned()],
+            description: "Vertical size of the voxels; by default, equal to the voxel size."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Point Selection Method".to_string(),
+            flags: vec!["--method".to_string()],
+            description: "Point selection method; options are 'first', 'last', 'lowest', 'highest', 'nearest', 'centroid' (default).".to_string(),
+            parameter_type: ParameterType::OptionList(vec!["first".to_string(), "last".to_string(), "lowest".to_string(), "highest".to_string(), "nearest".to_string(), "centroid".to_string()]),
+            default_value: Some("centroid".to_string()),
+            optional: true
+        });
+
+        parameters.push(ToolParameter {
+            name: "Save filtered points to seperate file?".to_owned(),
+            flags: vec!["--save_filtered".to_owned()],
+            description: "Save filtered points to seperate file?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.las --resolution=0.5 --method=centroid --save_filtered", short_exe, name).replace("*", &sep);
+
+        LidarVoxelThin {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarVoxelThin {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut resolution = 1f64;
+        let mut layer_height = 0f64;
+        let mut method = "centroid".to_string();
+        let mut save_filtered = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                resolution = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-layer_height" {
+                layer_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-method" {
+                method = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                method = method.to_lowercase();
+            } else if flag_val == "-save_filtered" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    save_filtered = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if layer_height <= 0f64 {
+            layer_height = resolution;
+        }
+        if resolution <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The voxel size must be greater than zero.",
+            ));
+        }
+        if !["first", "last", "lowest", "highest", "nearest", "centroid"].contains(&method.as_str())
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Specified 'method' parameter ({}) is not recognized.",
+                    method
+                ),
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (n_points.max(2) - 1) as f64; // used for progress calculation only
+
+        let start = Instant::now();
+
+        // The centroid method requires the centroids of the voxels, which are found in a first pass.
+        let grid = if method == "centroid" {
+            VoxelGrid::from_las(&input, resolution, layer_height, |_| true)
+        } else {
+            VoxelGrid::new(resolution, layer_height)
+        };
+
+        // The retained point of each voxel and its selection score; lower scores are preferred.
+        let mut retained: HashMap<VoxelKey, (usize, f64)> = HashMap::new();
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            let key = grid.key(p.x, p.y, p.z);
+            let score = match method.as_ref() {
+                "first" => i as f64,
+                "last" => -(i as f64),
+                "lowest" => p.z,
+                "highest" => -p.z,
+                "nearest" => {
+                    let (x, y, z) = grid.centre(key);
+                    (p.x - x) * (p.x - x) + (p.y - y) * (p.y - y) + (p.z - z) * (p.z - z)
+                }
+                _ => {
+                    let (x, y, z) = grid.get(key).unwrap().centroid();
+                    (p.x - x) * (p.x - x) + (p.y - y) * (p.y - y) + (p.z - z) * (p.z - z)
+                }
+            };
+            let entry = retained.entry(key).or_insert((i, score));
+            if score < entry.1 {
+                *entry = (i, score);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut filtered = vec![true; n_points];
+        for &(i, _) in retained.values() {
+            filtered[i] = false;
+        }
+        if verbose {
+            println!(
+                "Number of retained points: {} ({:.2}%)",
+                retained.len(),
+                100f64 * retained.len() as f64 / n_points.max(1) as f64
+            );
+        }
+
+        // now output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "EXTRACTION".to_string();
+        let mut filtered_output = if save_filtered {
+            let p = path::Path::new(&output_file);
+            let mut extension = String::from(".");
+            let ext = p.extension().unwrap().to_str().unwrap();
+            extension.push_str(ext);
+            let filtered_output_file = output_file.replace(&extension, "_filtered_points.las");
+            let mut filtered_output = LasFile::initialize_using_file(&filtered_output_file, &input);
+            filtered_output.header.system_id = "EXTRACTION".to_string();
+            Some(filtered_output)
+        } else {
+            None
+        };
+
+        for i in 0..n_points {
+            if !filtered[i] {
+                output.add_point_record(input.get_record(i));
+            } else if let Some(ref mut fo) = filtered_output {
+                fo.add_point_record(input.get_record(i));
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as usize;
+                if progress != old_progress {
+                    println!("Saving data: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let end = get_formatted_elapsed_time(start);
+
+        if let Some(mut fo) = filtered_output {
+            let _ = match fo.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Filtered points LAS file saved")
+                    }
+                }
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        }
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!("{}", &format!("Elapsed Time (excluding I/O): {}", end));
+        }
+
+        Ok(())
+    }
+}
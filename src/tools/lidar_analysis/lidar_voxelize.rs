@@ -0,0 +1,379 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool divides a LiDAR point cloud (`--input`) into a regular three-dimensional grid of voxels
+/// and outputs a vector points file (`--output`) containing one point for each occupied voxel. Voxels
+/// have a horizontal size of `--resolution` and a vertical size of `--layer_height`, which is equal to
+/// the horizontal size by default. Voxels are aligned with the coordinate origin, such that the voxels
+/// of adjoining tiles processed with the same voxel dimensions match. Only voxels containing at least
+/// `--min_points` points are output; empty voxels are not stored. Points that are withheld or classified
+/// as noise (classes 7 and 18) are excluded.
+///
+/// The output points are located at the horizontal centres of the voxels and have the following attributes:
+///
+/// | Field      | Description                                                   |
+/// | :--------- | :------------------------------------------------------------ |
+/// | `COUNT`    | Number of points in the voxel                                 |
+/// | `FIRST`    | Number of first (including only) returns in the voxel         |
+/// | `DENSITY`  | Number of points per unit volume                              |
+/// | `CENTRE_Z` | Elevation of the centre of the voxel                          |
+/// | `MEAN_Z`   | Mean elevation of the points                                  |
+/// | `MIN_Z`    | Minimum elevation of the points                               |
+/// | `MAX_Z`    | Maximum elevation of the points                               |
+/// | `MEAN_INT` | Mean intensity of the points                                  |
+///
+/// Voxelization provides a means of characterizing the three-dimensional structure of vegetation,
+/// which is not captured by two-dimensional products such as canopy height models. When the input
+/// point elevations are height-normalized (e.g. using the `HeightAboveGround` tool), the voxel layers
+/// correspond to height above the ground.
+///
+/// # See Also
+/// `LidarVoxelThin`, `LidarLeafAreaDensity`, `LidarVerticalComplexityIndex`, `HeightAboveGround`
+pub struct LidarVoxelize {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarVoxelize {
+    pub fn new() -> LidarVoxelize {
+        // public constructor
+        let name = "LidarVoxelize".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Summarizes the points of a LiDAR point cloud within a three-dimensional voxel grid."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector points file of voxels.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Voxel Size".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Horizontal size of the voxels.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Voxel Layer Height (optional)".to_owned(),
+            flags: vec!["--layer_height".to_owned()],
+            description: "Vertical size of the voxels; by default, equal to the voxel size."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Number of Points".to_owned(),
+            flags: vec!["--min_points".to_owned()],
+            description: "Minimum number of points in an output voxel.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=voxels.shp --resolution=1.0 --layer_height=0.5 --min_points=2", short_exe, name).replace("*", &sep);
+
+        LidarVoxelize {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarVoxelize {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut resolution = 1f64;
+        let mut layer_height = 0f64;
+        let mut min_points = 1usize;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                resolution = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-layer_height" {
+                layer_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-min_points" {
+                min_points = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+
+        if !input_file.contains(sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+
+        if layer_height <= 0f64 {
+            layer_height = resolution;
+        }
+        if resolution <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The voxel size must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("Voxelizing points...");
+        }
+        let grid = VoxelGrid::from_las(&input, resolution, layer_height, |p| {
+            !p.withheld() && !p.is_classified_noise()
+        });
+        let mut keys: Vec<VoxelKey> = grid
+            .iter()
+            .filter(|(_, v)| v.count >= min_points)
+            .map(|(&k, _)| k)
+            .collect();
+        keys.sort();
+        if verbose {
+            println!("Number of occupied voxels: {}", keys.len());
+        }
+
+        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+        output.projection = input.get_wkt();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 9u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("COUNT", FieldDataType::Int, 9u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("FIRST", FieldDataType::Int, 9u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "DENSITY",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        for name in ["CENTRE_Z", "MEAN_Z", "MIN_Z", "MAX_Z"].iter() {
+            output
+                .attributes
+                .add_field(&AttributeField::new(name, FieldDataType::Real, 12u8, 3u8));
+        }
+        output.attributes.add_field(&AttributeField::new(
+            "MEAN_INT",
+            FieldDataType::Real,
+            10u8,
+            2u8,
+        ));
+
+        let volume = resolution * resolution * layer_height;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        let num_voxels = keys.len();
+        for (fid, &key) in keys.iter().enumerate() {
+            let voxel = grid.get(key).unwrap();
+            let (x, y, z) = grid.centre(key);
+            output.add_point_record(x, y);
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(fid as i32 + 1),
+                    FieldData::Int(voxel.count as i32),
+                    FieldData::Int(voxel.first_returns as i32),
+                    FieldData::Real(voxel.count as f64 / volume),
+                    FieldData::Real(z),
+                    FieldData::Real(voxel.centroid().2),
+                    FieldData::Real(voxel.min_z),
+                    FieldData::Real(voxel.max_z),
+                    FieldData::Real(voxel.mean_intensity()),
+                ],
+                false,
+            );
+            if verbose {
+                progress = (100.0_f64 * fid as f64 / (num_voxels - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
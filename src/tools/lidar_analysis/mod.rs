@@ -4,16 +4,20 @@ mod block_maximum;
 mod block_minimum;
 mod classify_buildings;
 mod classify_overlap_points;
+mod classify_power_lines;
 mod clip_lidar_to_polygon;
 mod erase_polygon_from_lidar;
 mod filter_lidar_classes;
 mod filter_lidar_scan_angles;
 mod find_flightline_edge_points;
+mod flightline_alignment;
 mod flightline_overlap;
 mod height_above_ground;
+mod individual_tree_detection;
 mod las_to_ascii;
 mod las_to_multipoint_shapefile;
 mod las_to_shapefile;
+mod lidar_building_footprints;
 mod lidar_classify_subset;
 mod lidar_colourize;
 mod lidar_construct_vector_tin;
@@ -22,16 +26,22 @@ mod lidar_ground_point_filter;
 mod lidar_hex_bin;
 mod lidar_hillshade;
 mod lidar_histogram;
+mod lidar_hydro_flatten;
 mod lidar_idw_interpolation;
 mod lidar_info;
 mod lidar_join;
 mod lidar_kappa;
+mod lidar_leaf_area_density;
+mod lidar_m3c2;
 mod lidar_nn_gridding;
 mod lidar_outliers;
+mod lidar_pit_free_chm;
 mod lidar_point_density;
 mod lidar_point_stats;
+mod lidar_quality_report;
 mod lidar_radial_basis_function_interpolation;
 mod lidar_ransac_planes;
+mod lidar_reproject;
 mod lidar_segmentation;
 mod lidar_segmentation_based_filter;
 mod lidar_thin;
@@ -40,9 +50,15 @@ mod lidar_tile;
 mod lidar_tile_footprint;
 mod lidar_tin_gridding;
 mod lidar_tophat_transform;
+mod lidar_vegetation_metrics;
+mod lidar_vertical_complexity_index;
+mod lidar_voxel_thin;
+mod lidar_voxelize;
 mod normal_vectors;
+mod normalize_lidar_intensity;
 mod remove_duplicates;
 mod select_tiles_by_polygon;
+mod tree_crown_segmentation;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::ascii_to_las::AsciiToLas;
@@ -50,16 +66,20 @@ pub use self::block_maximum::LidarBlockMaximum;
 pub use self::block_minimum::LidarBlockMinimum;
 pub use self::classify_buildings::ClassifyBuildingsInLidar;
 pub use self::classify_overlap_points::ClassifyOverlapPoints;
+pub use self::classify_power_lines::ClassifyPowerLinesInLidar;
 pub use self::clip_lidar_to_polygon::ClipLidarToPolygon;
 pub use self::erase_polygon_from_lidar::ErasePolygonFromLidar;
 pub use self::filter_lidar_classes::FilterLidarClasses;
 pub use self::filter_lidar_scan_angles::FilterLidarScanAngles;
 pub use self::find_flightline_edge_points::FindFlightlineEdgePoints;
+pub use self::flightline_alignment::FlightlineAlignment;
 pub use self::flightline_overlap::FlightlineOverlap;
 pub use self::height_above_ground::HeightAboveGround;
+pub use self::individual_tree_detection::IndividualTreeDetection;
 pub use self::las_to_ascii::LasToAscii;
 pub use self::las_to_multipoint_shapefile::LasToMultipointShapefile;
 pub use self::las_to_shapefile::LasToShapefile;
+pub use self::lidar_building_footprints::LidarBuildingFootprints;
 pub use self::lidar_classify_subset::LidarClassifySubset;
 pub use self::lidar_colourize::LidarColourize;
 pub use self::lidar_construct_vector_tin::LidarConstructVectorTIN;
@@ -68,16 +88,22 @@ pub use self::lidar_ground_point_filter::LidarGroundPointFilter;
 pub use self::lidar_hex_bin::LidarHexBinning;
 pub use self::lidar_hillshade::LidarHillshade;
 pub use self::lidar_histogram::LidarHistogram;
+pub use self::lidar_hydro_flatten::LidarHydroFlatten;
 pub use self::lidar_idw_interpolation::LidarIdwInterpolation;
 pub use self::lidar_info::LidarInfo;
 pub use self::lidar_join::LidarJoin;
 pub use self::lidar_kappa::LidarKappaIndex;
+pub use self::lidar_leaf_area_density::LidarLeafAreaDensity;
+pub use self::lidar_m3c2::LidarM3C2;
 pub use self::lidar_nn_gridding::LidarNearestNeighbourGridding;
 pub use self::lidar_outliers::LidarRemoveOutliers;
+pub use self::lidar_pit_free_chm::LidarPitFreeCHM;
 pub use self::lidar_point_density::LidarPointDensity;
 pub use self::lidar_point_stats::LidarPointStats;
+pub use self::lidar_quality_report::LidarQualityReport;
 pub use self::lidar_radial_basis_function_interpolation::LidarRbfInterpolation;
 pub use self::lidar_ransac_planes::LidarRansacPlanes;
+pub use self::lidar_reproject::LidarReproject;
 pub use self::lidar_segmentation::LidarSegmentation;
 pub use self::lidar_segmentation_based_filter::LidarSegmentationBasedFilter;
 pub use self::lidar_thin::LidarThin;
@@ -86,6 +112,12 @@ pub use self::lidar_tile::LidarTile;
 pub use self::lidar_tile_footprint::LidarTileFootprint;
 pub use self::lidar_tin_gridding::LidarTINGridding;
 pub use self::lidar_tophat_transform::LidarTophatTransform;
+pub use self::lidar_vegetation_metrics::LidarVegetationMetrics;
+pub use self::lidar_vertical_complexity_index::LidarVerticalComplexityIndex;
+pub use self::lidar_voxel_thin::LidarVoxelThin;
+pub use self::lidar_voxelize::LidarVoxelize;
 pub use self::normal_vectors::NormalVectors;
+pub use self::normalize_lidar_intensity::NormalizeLidarIntensity;
 pub use self::remove_duplicates::LidarRemoveDuplicates;
 pub use self::select_tiles_by_polygon::SelectTilesByPolygon;
+pub use self::tree_crown_segmentation::TreeCrownSegmentation;
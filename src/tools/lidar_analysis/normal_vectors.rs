@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 26/06/2017
-Last Modified: 22/10/2019
+Last Modified: 18/10/2026
 License: MIT
 */
 
@@ -21,6 +21,8 @@ use std::sync::Arc;
 use std::thread;
 
 /// Calculates normal vectors for points within a LAS file and stores these data (XYZ vector components) in the RGB field.
+/// When the output file has a *.ply extension, the normal vectors are also written to the nx, ny, and nz vertex
+/// properties, which are recognized by most mesh-processing software.
 pub struct NormalVectors {
     name: String,
     description: String,
@@ -283,6 +285,11 @@ impl WhiteboxTool for NormalVectors {
                 colour_data: rgb,
             };
             output.add_point_record(lpr);
+            output.add_normal([
+                normal_values[i].x as f32,
+                normal_values[i].y as f32,
+                normal_values[i].z as f32,
+            ]);
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as i32;
                 if progress != old_progress {
@@ -0,0 +1,939 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{DMatrix, DVector, Matrix3};
+use crate::structures::{DistanceMetric, FixedRadiusSearch3D};
+use crate::tools::*;
+use num_cpus;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool normalizes the intensity values of a LiDAR point cloud (`--input`) for the effects of
+/// the range, i.e. the distance between the sensor and the target, and, optionally, the angle of
+/// incidence of the laser beam on the target surface. Raw LiDAR intensity varies strongly with both
+/// of these factors, which differ between flight lines and across each swath, causing visible banding
+/// in intensity images and hindering their use for land-cover classification. The normalized
+/// intensity is:
+///
+/// > I<sub>n</sub> = I (R / R<sub>ref</sub>)<sup>2</sup> / cos(&alpha;)
+///
+/// where I is the raw intensity, R is the range, R<sub>ref</sub> is the reference range
+/// (`--ref_range`), which defaults to the mean range of the points, and &alpha; is the angle of
+/// incidence.
+///
+/// The range is calculated from the sensor trajectory if an input trajectory file (`--trajectory`)
+/// is specified. The trajectory file is an ASCII text file with one sensor position per line,
+/// containing the GPS time, x, y, and z values, separated by commas, spaces or tabs, in the same
+/// coordinate system as the point cloud. Lines that do not contain four numeric values, e.g. a header
+/// line, are ignored. The sensor position of each point is interpolated from the trajectory using the
+/// point's GPS time, and so the input LAS file must contain GPS time data. Points with GPS times
+/// outside of the time span of the trajectory are not normalized. If no trajectory is available, the
+/// range is instead derived from the scan angle of each point and the elevation of the sensor
+/// (`--altitude`), i.e. R = (altitude - z) / cos(&theta;), where &theta; is the scan angle.
+///
+/// When the `--incidence` flag is specified, the intensity is also corrected for the angle of
+/// incidence, the angle between the laser beam and the normal of the target surface. Surface normals
+/// are estimated by fitting a plane to the points within a search radius (`--radius`), as in the
+/// `NormalVectors` tool. Without a trajectory, the direction of the beam is derived from the scan
+/// angle and the heading of the flight line, which is estimated from the GPS times of the points
+/// within each flight line (point source ID); by convention, negative scan angles are on the left
+/// side of the aircraft. To avoid over-correction at grazing angles, the incidence correction is
+/// limited to a factor of five.
+///
+/// Finally, when the `--balance` flag is specified, the intensities of overlapping flight lines are
+/// balanced. The mean normalized intensity of each flight line is calculated within the cells of a
+/// grid with a resolution of `--resolution`, and a gain is estimated for each flight line by least
+/// squares, such that the mean intensities of flight lines within each shared grid cell agree. The
+/// gains are constrained to have a geometric mean of one, preserving the overall intensity level.
+/// Flight lines are identified by the point source ID. The tool reports the mean intensity and gain
+/// of each flight line and the mean intensity discrepancy between overlapping flight lines before and
+/// after balancing.
+///
+/// Points that are withheld or classified as noise are not used to estimate surface normals or gains
+/// but are normalized.
+///
+/// # See Also
+/// `NormalVectors`, `FlightlineAlignment`, `LidarColourize`, `LidarHillshade`
+pub struct NormalizeLidarIntensity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl NormalizeLidarIntensity {
+    pub fn new() -> NormalizeLidarIntensity {
+        // public constructor
+        let name = "NormalizeLidarIntensity".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Normalizes LiDAR intensity for range and incidence angle and balances intensity between flight lines.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Trajectory File (optional)".to_owned(),
+            flags: vec!["--trajectory".to_owned()],
+            description: "Optional input ASCII text file of sensor positions (GPS time, x, y, z)."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Sensor Altitude".to_owned(),
+            flags: vec!["--altitude".to_owned()],
+            description:
+                "Sensor elevation, used with scan angles when no trajectory file is specified."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Reference Range".to_owned(),
+            flags: vec!["--ref_range".to_owned()],
+            description: "Reference range; the mean range of the points is used if unspecified."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Correct for incidence angle?".to_owned(),
+            flags: vec!["--incidence".to_owned()],
+            description: "Correct intensity for the angle of incidence.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search radius used to estimate surface normals.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Balance flight lines?".to_owned(),
+            flags: vec!["--balance".to_owned()],
+            description: "Balance intensity between overlapping flight lines.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Balancing Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Grid resolution used to compare overlapping flight lines.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --trajectory=sbet.txt --incidence --radius=1.0 --balance --resolution=2.0
+        >>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --altitude=1500.0 --ref_range=1000.0", short_exe, name).replace("*", &sep);
+
+        NormalizeLidarIntensity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for NormalizeLidarIntensity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut trajectory_file = String::new();
+        let mut altitude = f64::NAN;
+        let mut ref_range = f64::NAN;
+        let mut correct_incidence = false;
+        let mut radius = 1f64;
+        let mut balance = false;
+        let mut grid_res = 2f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-trajectory" {
+                trajectory_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-incidence" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    correct_incidence = true;
+                }
+            } else if flag_val == "-balance" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    balance = true;
+                }
+            } else if flag_val == "-altitude"
+                || flag_val == "-ref_range"
+                || flag_val == "-radius"
+                || flag_val == "-resolution"
+            {
+                let value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+                match flag_val.as_ref() {
+                    "-altitude" => altitude = value,
+                    "-ref_range" => ref_range = value,
+                    "-radius" => radius = value,
+                    _ => grid_res = value,
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep = path::MAIN_SEPARATOR;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        if !input_file.contains(sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !output_file.contains(sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !trajectory_file.is_empty()
+            && !trajectory_file.contains(sep)
+            && !trajectory_file.contains("/")
+        {
+            trajectory_file = format!("{}{}", working_directory, trajectory_file);
+        }
+
+        if trajectory_file.is_empty() && altitude.is_nan() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either a trajectory file (--trajectory) or the sensor altitude (--altitude) must be specified.",
+            ));
+        }
+        if radius <= 0f64 || grid_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius and grid resolution must be greater than zero.",
+            ));
+        }
+
+        // Read the trajectory.
+        let mut trajectory: Vec<[f64; 4]> = vec![];
+        if !trajectory_file.is_empty() {
+            let f = BufReader::new(File::open(&trajectory_file)?);
+            for line in f.lines() {
+                let line = line?;
+                let values: Vec<f64> = line
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|s| !s.is_empty())
+                    .map_while(|s| s.parse::<f64>().ok())
+                    .collect();
+                if values.len() >= 4 {
+                    trajectory.push([values[0], values[1], values[2], values[3]]);
+                }
+            }
+            if trajectory.len() < 2 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The trajectory file must contain at least two positions.",
+                ));
+            }
+            trajectory.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap());
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+        let n_points = input.header.number_of_points as usize;
+        let has_gps_time = input.has_gps_time();
+        if !trajectory.is_empty() && !has_gps_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input LAS file must contain GPS time data to be used with a trajectory.",
+            ));
+        }
+        if trajectory.is_empty() && correct_incidence && !has_gps_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The incidence angle correction requires either a trajectory file or GPS time data, from which the flight line headings are estimated.",
+            ));
+        }
+        // Scan angles are in whole degrees in the legacy point formats and in 0.006 degree
+        // increments in point formats 6-10.
+        let scan_angle_units = if input.header.point_format >= 6 {
+            0.006f64
+        } else {
+            1f64
+        };
+
+        let start = Instant::now();
+
+        let mut points: Vec<[f64; 3]> = Vec::with_capacity(n_points);
+        let mut times = vec![0f64; n_points];
+        let mut scan_angles = vec![0f64; n_points];
+        let mut strip_ids = vec![0u16; n_points];
+        let mut intensity = vec![0f64; n_points];
+        let mut valid = vec![false; n_points];
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            points.push([p.x, p.y, p.z]);
+            if has_gps_time {
+                times[i] = input.get_gps_time(i)?;
+            }
+            scan_angles[i] = (p.scan_angle as f64 * scan_angle_units).to_radians();
+            strip_ids[i] = p.point_source_id;
+            intensity[i] = p.intensity as f64;
+            valid[i] = !p.withheld() && !p.is_classified_noise();
+        }
+
+        // Estimate the heading of each flight line from the rate of change of position with time.
+        let mut headings: HashMap<u16, [f64; 2]> = HashMap::new();
+        if trajectory.is_empty() && correct_incidence {
+            let mut sums: HashMap<u16, [f64; 9]> = HashMap::new();
+            for i in 0..n_points {
+                let s = sums.entry(strip_ids[i]).or_insert([0f64; 9]);
+                let (t, x, y) = (times[i], points[i][0], points[i][1]);
+                s[0] += 1f64;
+                s[1] += t;
+                s[2] += t * t;
+                s[3] += x;
+                s[4] += y;
+                s[5] += t * x;
+                s[6] += t * y;
+            }
+            for (id, s) in sums {
+                let var_t = s[2] / s[0] - (s[1] / s[0]).powi(2);
+                let vx = s[5] / s[0] - s[1] / s[0] * s[3] / s[0];
+                let vy = s[6] / s[0] - s[1] / s[0] * s[4] / s[0];
+                let len = vx.hypot(vy);
+                if var_t > 0f64 && len > 0f64 {
+                    headings.insert(id, [vx / len, vy / len]);
+                }
+            }
+        }
+
+        // Calculate the range and the unit vector from each point towards the sensor.
+        let mut ranges = vec![f64::NAN; n_points];
+        let mut to_sensor = vec![[0f64, 0f64, 1f64]; n_points];
+        for i in 0..n_points {
+            let p = points[i];
+            if !trajectory.is_empty() {
+                let t = times[i];
+                if t < trajectory[0][0] || t > trajectory[trajectory.len() - 1][0] {
+                    continue;
+                }
+                let k = match trajectory.binary_search_by(|v| v[0].partial_cmp(&t).unwrap()) {
+                    Ok(k) => k.min(trajectory.len() - 2),
+                    Err(k) => k - 1,
+                };
+                let (a, b) = (trajectory[k], trajectory[k + 1]);
+                let w = if b[0] > a[0] {
+                    (t - a[0]) / (b[0] - a[0])
+                } else {
+                    0f64
+                };
+                let sensor = [
+                    a[1] + w * (b[1] - a[1]),
+                    a[2] + w * (b[2] - a[2]),
+                    a[3] + w * (b[3] - a[3]),
+                ];
+                let v = [sensor[0] - p[0], sensor[1] - p[1], sensor[2] - p[2]];
+                let r = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+                if r > 0f64 {
+                    ranges[i] = r;
+                    to_sensor[i] = [v[0] / r, v[1] / r, v[2] / r];
+                }
+            } else {
+                let height = altitude - p[2];
+                let theta = scan_angles[i];
+                if height > 0f64 && theta.cos() > 0f64 {
+                    ranges[i] = height / theta.cos();
+                    if let Some(h) = headings.get(&strip_ids[i]) {
+                        // The point lies to the right of the track for positive scan angles.
+                        let right = [h[1], -h[0]];
+                        to_sensor[i] = [
+                            -right[0] * theta.sin(),
+                            -right[1] * theta.sin(),
+                            theta.cos(),
+                        ];
+                    } else {
+                        to_sensor[i] = [0f64, 0f64, 1f64];
+                    }
+                }
+            }
+        }
+        let num_ranged = ranges.iter().filter(|r| !r.is_nan()).count();
+        if num_ranged == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The range could not be calculated for any of the points. Check the trajectory time span or the sensor altitude.",
+            ));
+        }
+        if ref_range.is_nan() {
+            ref_range = ranges.iter().filter(|r| !r.is_nan()).sum::<f64>() / num_ranged as f64;
+        }
+        if verbose {
+            println!(
+                "Range calculated for {} of {} points (reference range {:.2})",
+                num_ranged, n_points, ref_range
+            );
+        }
+
+        // Calculate the incidence angle corrections.
+        let mut incidence_factor = vec![1f64; n_points];
+        if correct_incidence {
+            if verbose {
+                println!("Estimating surface normals...");
+            }
+            let mut frs = FixedRadiusSearch3D::new(radius, DistanceMetric::SquaredEuclidean);
+            for i in 0..n_points {
+                if valid[i] {
+                    frs.insert(points[i][0], points[i][1], points[i][2], i);
+                }
+            }
+            let points = Arc::new(points.clone());
+            let to_sensor = Arc::new(to_sensor.clone());
+            let frs = Arc::new(frs);
+            let num_procs = num_cpus::get();
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let points = points.clone();
+                let to_sensor = to_sensor.clone();
+                let frs = frs.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in (0..n_points).filter(|i| i % num_procs == tid) {
+                        let p = points[i];
+                        let neighbours: Vec<[f64; 3]> = frs
+                            .search(p[0], p[1], p[2])
+                            .iter()
+                            .map(|&(j, _)| points[j])
+                            .collect();
+                        let factor = match plane_normal(&neighbours) {
+                            Some(n) => {
+                                let b = to_sensor[i];
+                                let cos_incidence = (n[0] * b[0] + n[1] * b[1] + n[2] * b[2])
+                                    .abs()
+                                    .max(1f64 / MAX_INCIDENCE_CORRECTION);
+                                1f64 / cos_incidence
+                            }
+                            None => 1f64,
+                        };
+                        tx.send((i, factor)).unwrap();
+                    }
+                });
+            }
+            for i in 0..n_points {
+                let (j, factor) = rx.recv().expect("Error receiving data from thread.");
+                incidence_factor[j] = factor;
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / (n_points - 1).max(1) as f64) as i32;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let mut normalized = intensity.clone();
+        for i in 0..n_points {
+            if !ranges[i].is_nan() {
+                normalized[i] =
+                    intensity[i] * (ranges[i] / ref_range).powi(2) * incidence_factor[i];
+            }
+        }
+
+        // Balance the flight lines.
+        let mut strip_list: Vec<u16> = strip_ids.clone();
+        strip_list.sort();
+        strip_list.dedup();
+        let num_strips = strip_list.len();
+        let strip_index: HashMap<u16, usize> = strip_list
+            .iter()
+            .enumerate()
+            .map(|(k, &id)| (id, k))
+            .collect();
+        let mut gains = vec![1f64; num_strips];
+        if balance && num_strips > 1 {
+            if verbose {
+                println!("Balancing flight lines...");
+            }
+            let west = input.header.min_x;
+            let north = input.header.max_y;
+            let mut cells: HashMap<(isize, isize), HashMap<usize, (f64, usize)>> = HashMap::new();
+            for i in 0..n_points {
+                if valid[i] && normalized[i] > 0f64 {
+                    let key = (
+                        ((north - points[i][1]) / grid_res).floor() as isize,
+                        ((points[i][0] - west) / grid_res).floor() as isize,
+                    );
+                    let entry = cells
+                        .entry(key)
+                        .or_insert(HashMap::new())
+                        .entry(strip_index[&strip_ids[i]])
+                        .or_insert((0f64, 0));
+                    entry.0 += normalized[i];
+                    entry.1 += 1;
+                }
+            }
+
+            // Each shared cell provides an observation of the difference in log gain between
+            // two flight lines.
+            let mut observations: Vec<(usize, usize, f64)> = vec![];
+            for strips in cells.values() {
+                let means: Vec<(usize, f64)> = strips
+                    .iter()
+                    .map(|(&s, &(sum, n))| (s, (sum / n as f64).ln()))
+                    .collect();
+                for a in 0..means.len() {
+                    for b in a + 1..means.len() {
+                        observations.push((means[a].0, means[b].0, means[b].1 - means[a].1));
+                    }
+                }
+            }
+            if observations.is_empty() {
+                if verbose {
+                    println!("Warning: the flight lines do not overlap and cannot be balanced.");
+                }
+            } else {
+                gains = balance_gains(&observations, num_strips)?;
+                if verbose {
+                    let discrepancy = |g: &Vec<f64>| -> f64 {
+                        observations
+                            .iter()
+                            .map(|&(a, b, d)| (d - g[a].ln() + g[b].ln()).abs())
+                            .sum::<f64>()
+                            / observations.len() as f64
+                    };
+                    println!(
+                        "Mean intensity discrepancy between overlapping flight lines: {:.2}% before, {:.2}% after balancing",
+                        100f64 * (discrepancy(&vec![1f64; num_strips]).exp() - 1f64),
+                        100f64 * (discrepancy(&gains).exp() - 1f64)
+                    );
+                }
+            }
+        }
+        for i in 0..n_points {
+            normalized[i] *= gains[strip_index[&strip_ids[i]]];
+        }
+
+        if verbose {
+            println!("Flight line statistics:");
+            println!("Point Source ID | Raw Mean Intensity | Normalized Mean Intensity | Gain");
+            for s in 0..num_strips {
+                let mut n = 0;
+                let mut raw = 0f64;
+                let mut norm = 0f64;
+                for i in 0..n_points {
+                    if strip_index[&strip_ids[i]] == s {
+                        n += 1;
+                        raw += intensity[i];
+                        norm += normalized[i];
+                    }
+                }
+                println!(
+                    "{} | {:.2} | {:.2} | {:.4}",
+                    strip_list[s],
+                    raw / n as f64,
+                    norm / n as f64,
+                    gains[s]
+                );
+            }
+        }
+
+        // Output the points.
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        for i in 0..n_points {
+            let value = normalized[i].round().max(0f64).min(u16::MAX as f64) as u16;
+            let pr = input.get_record(i);
+            let pr2: LidarPointRecord;
+            match pr {
+                LidarPointRecord::PointRecord0 { mut point_data } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord0 {
+                        point_data: point_data,
+                    };
+                }
+                LidarPointRecord::PointRecord1 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord1 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord2 {
+                    mut point_data,
+                    colour_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord2 {
+                        point_data: point_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord3 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord3 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord4 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord4 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord5 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord5 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord6 {
+                    mut point_data,
+                    gps_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord6 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                    };
+                }
+                LidarPointRecord::PointRecord7 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord7 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord8 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord8 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                    };
+                }
+                LidarPointRecord::PointRecord9 {
+                    mut point_data,
+                    gps_data,
+                    wave_packet,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord9 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+                LidarPointRecord::PointRecord10 {
+                    mut point_data,
+                    gps_data,
+                    colour_data,
+                    wave_packet,
+                } => {
+                    point_data.intensity = value;
+                    pr2 = LidarPointRecord::PointRecord10 {
+                        point_data: point_data,
+                        gps_data: gps_data,
+                        colour_data: colour_data,
+                        wave_packet: wave_packet,
+                    };
+                }
+            }
+            output.add_point_record(pr2);
+        }
+
+        if verbose {
+            let elapsed_time = get_formatted_elapsed_time(start);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+
+        Ok(())
+    }
+}
+
+/// The maximum factor by which intensity is increased to correct for the incidence angle.
+const MAX_INCIDENCE_CORRECTION: f64 = 5.0;
+
+// Solves for the gain of each flight line from observations (a, b, d) of the difference in log
+// mean intensity, d = log m_b - log m_a, between flight lines a and b, such that the balanced
+// intensities agree in the least-squares sense and the log gains sum to zero.
+fn balance_gains(
+    observations: &[(usize, usize, f64)],
+    num_strips: usize,
+) -> Result<Vec<f64>, Error> {
+    let mut ata = DMatrix::<f64>::zeros(num_strips, num_strips);
+    let mut atb = DVector::<f64>::zeros(num_strips);
+    for &(a, b, d) in observations {
+        // log g_a - log g_b = log m_b - log m_a
+        ata[(a, a)] += 1f64;
+        ata[(b, b)] += 1f64;
+        ata[(a, b)] -= 1f64;
+        ata[(b, a)] -= 1f64;
+        atb[a] += d;
+        atb[b] -= d;
+    }
+    // The log gains sum to zero.
+    for a in 0..num_strips {
+        for b in 0..num_strips {
+            ata[(a, b)] += 1f64;
+        }
+    }
+    let svd = ata.svd(true, true);
+    let max_sv = svd.singular_values.iter().fold(0f64, |m, v| m.max(*v));
+    match svd.solve(&atb, max_sv * 1e-9) {
+        Ok(x) => Ok((0..num_strips).map(|s| x[s].exp()).collect()),
+        Err(e) => Err(Error::new(
+            ErrorKind::Other,
+            format!("Error solving for the flight line gains: {}", e),
+        )),
+    }
+}
+
+// Estimates the unit normal of the plane best fitting a set of points.
+fn plane_normal(points: &[[f64; 3]]) -> Option<[f64; 3]> {
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mut c = [0f64; 3];
+    for p in points {
+        for j in 0..3 {
+            c[j] += p[j] / n;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        for r in 0..3 {
+            for k in 0..3 {
+                cov[(r, k)] += d[r] * d[k];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut m = 0;
+    for j in 1..3 {
+        if eig.eigenvalues[j] < eig.eigenvalues[m] {
+            m = j;
+        }
+    }
+    let v = eig.eigenvectors.column(m);
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len == 0f64 || !len.is_finite() {
+        return None;
+    }
+    Some([v[0] / len, v[1] / len, v[2] / len])
+}
+
+#[cfg(test)]
+mod test {
+    use super::{balance_gains, plane_normal};
+
+    #[test]
+    fn test_balance_gains() {
+        // the mean intensities of the flight lines are biased by factors of 1, 2 and 4
+        let ln2 = 2f64.ln();
+        let observations = vec![(0, 1, ln2), (1, 2, ln2), (0, 2, 2f64 * ln2), (0, 1, ln2)];
+        let gains = balance_gains(&observations, 3).unwrap();
+        let expected = [2.0, 1.0, 0.5];
+        for s in 0..3 {
+            assert!((gains[s] - expected[s]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_plane_normal() {
+        let points: Vec<[f64; 3]> = (0..25)
+            .map(|i| {
+                let (x, y) = ((i % 5) as f64, (i / 5) as f64);
+                [x, y, 2.0 * x + 10.0]
+            })
+            .collect();
+        let n = plane_normal(&points).unwrap();
+        let len = 5f64.sqrt();
+        assert!((n[0].abs() - 2.0 / len).abs() < 1e-9);
+        assert!(n[1].abs() < 1e-9);
+        assert!((n[2].abs() - 1.0 / len).abs() < 1e-9);
+        assert!(plane_normal(&points[0..2]).is_none());
+    }
+}
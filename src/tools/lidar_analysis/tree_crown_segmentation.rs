@@ -0,0 +1,870 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{convex_hull, is_clockwise_order, polygon_area, trace_raster_polygons};
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{Array2D, DistanceMetric, FixedRadiusSearch2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool delineates individual tree crowns using a set of tree tops (`--tops`), such as those produced by
+/// the `IndividualTreeDetection` tool, and either a canopy height model (CHM) raster or a height-normalized
+/// LiDAR point cloud (`--input`). The input type is determined by the file extension; LAS files (*.las, *.zip)
+/// are processed as point clouds and all other files are treated as rasters.
+///
+/// When a CHM is used, crowns are segmented using a marker-controlled watershed. Each tree top seeds a region,
+/// and regions are grown downslope from their tops, in order of descending canopy height, into neighbouring
+/// cells. When a point cloud is used, points are processed in order of descending height and each point is
+/// assigned to the tree of the nearest top among the already-assigned points within the search radius
+/// (`--search_radius`), in a manner similar to the region growing method of Li et al. (2012). Crown polygons are
+/// then created from the convex hull of the points belonging to each tree.
+///
+/// In both cases, crown growth is constrained by three parameters. Areas lower than the minimum height
+/// (`--min_height`) are never assigned to a crown, nor are areas lower than a proportion (`--crown_ratio`) of
+/// the tree top height, nor those that are farther than the maximum crown radius (`--max_crown_radius`) from
+/// the tree top.
+///
+/// The output (`--output`) is a vector polygon file of tree crowns, with attributes containing the tree
+/// identifier (`TREE_ID`, the record number of the tree top in the input tops file), the tree top coordinates
+/// (`TOP_X`, `TOP_Y`), the tree height (`HEIGHT`), the crown area (`CROWN_AREA`), and the diameter of a circle
+/// of equal area to the crown (`CROWN_DIAM`). Tree heights are read from the `HEIGHT` field of the tops file if
+/// it exists, and are otherwise taken from the input data. Optionally, a vector points file containing the tree
+/// tops with the same set of attributes (`--out_tops`) and, when the input is a CHM, a raster of crown
+/// identifiers (`--out_raster`) may also be output.
+///
+/// # Reference
+/// Li, W., Guo, Q., Jakubowski, M. K., and Kelly, M. (2012). A new method for segmenting individual trees from
+/// the lidar point cloud. *Photogrammetric Engineering & Remote Sensing*, 78(1), 75-84.
+///
+/// # See Also
+/// `IndividualTreeDetection`, `HeightAboveGround`, `Watershed`
+pub struct TreeCrownSegmentation {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl TreeCrownSegmentation {
+    pub fn new() -> TreeCrownSegmentation {
+        // public constructor
+        let name = "TreeCrownSegmentation".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Delineates individual tree crowns from tree tops and a canopy height model or height-normalized LiDAR point cloud.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file or canopy height model raster."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Tree Tops File".to_owned(),
+            flags: vec!["--tops".to_owned()],
+            description: "Input vector points file of tree tops.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file of tree crowns.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Tree Tops File (optional)".to_owned(),
+            flags: vec!["--out_tops".to_owned()],
+            description: "Output vector points file of tree tops with crown attributes.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Crown Raster File (optional)".to_owned(),
+            flags: vec!["--out_raster".to_owned()],
+            description: "Output raster of crown identifiers (CHM inputs only).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description: "Minimum height (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Crown Height Ratio".to_owned(),
+            flags: vec!["--crown_ratio".to_owned()],
+            description:
+                "Minimum height of crown areas as a proportion of the tree top height (0-1)."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Crown Radius".to_owned(),
+            flags: vec!["--max_crown_radius".to_owned()],
+            description: "Maximum crown radius (m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--search_radius".to_owned()],
+            description: "Point search radius (m) used for region growing (LiDAR inputs only)."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=chm.tif --tops=tops.shp -o=crowns.shp --min_height=2.0 --crown_ratio=0.5 --max_crown_radius=8.0", short_exe, name).replace("*", &sep);
+
+        TreeCrownSegmentation {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for TreeCrownSegmentation {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut tops_file = String::new();
+        let mut output_file = String::new();
+        let mut out_tops_file = String::new();
+        let mut out_raster_file = String::new();
+        let mut min_height = 2f64;
+        let mut crown_ratio = 0.5f64;
+        let mut max_crown_radius = 10f64;
+        let mut search_radius = 1f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-tops" {
+                tops_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_tops" {
+                out_tops_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_raster" {
+                out_raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-min_height" {
+                min_height = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-crown_ratio" {
+                crown_ratio = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-max_crown_radius" {
+                max_crown_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-search_radius" {
+                search_radius = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        if !input_file.contains(&sep) && !input_file.contains("/") {
+            input_file = format!("{}{}", working_directory, input_file);
+        }
+        if !tops_file.contains(&sep) && !tops_file.contains("/") {
+            tops_file = format!("{}{}", working_directory, tops_file);
+        }
+        if !output_file.contains(&sep) && !output_file.contains("/") {
+            output_file = format!("{}{}", working_directory, output_file);
+        }
+        if !out_tops_file.is_empty()
+            && !out_tops_file.contains(&sep)
+            && !out_tops_file.contains("/")
+        {
+            out_tops_file = format!("{}{}", working_directory, out_tops_file);
+        }
+        if !out_raster_file.is_empty()
+            && !out_raster_file.contains(&sep)
+            && !out_raster_file.contains("/")
+        {
+            out_raster_file = format!("{}{}", working_directory, out_raster_file);
+        }
+
+        if crown_ratio < 0f64 || crown_ratio > 1f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The crown height ratio must be between 0 and 1.",
+            ));
+        }
+        if max_crown_radius <= 0f64 || search_radius <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The maximum crown radius and search radius must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading tree tops...");
+        }
+        let tops = Shapefile::read(&tops_file)?;
+        if tops.header.shape_type.base_shape_type() != ShapeType::Point {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input tops file must be of a point base shape type.",
+            ));
+        }
+        let height_field = tops.attributes.get_field_num("HEIGHT");
+        let mut top_x = Vec::with_capacity(tops.num_records);
+        let mut top_y = Vec::with_capacity(tops.num_records);
+        let mut top_z = Vec::with_capacity(tops.num_records);
+        for record_num in 0..tops.num_records {
+            let record = tops.get_record(record_num);
+            top_x.push(record.points[0].x);
+            top_y.push(record.points[0].y);
+            top_z.push(match height_field {
+                Some(_) => match tops.attributes.get_value(record_num, "HEIGHT") {
+                    FieldData::Real(v) => v,
+                    FieldData::Int(v) => v as f64,
+                    _ => f64::NAN,
+                },
+                None => f64::NAN,
+            });
+        }
+        let num_tops = tops.num_records;
+
+        let start = Instant::now();
+
+        // the crown polygon parts and area of each tree
+        let mut crowns: Vec<(Vec<Vec<Point2D>>, f64)> = vec![(vec![], 0f64); num_tops];
+        let projection: String;
+        let max_crown_radius_sqrd = max_crown_radius * max_crown_radius;
+
+        let lower = input_file.to_lowercase();
+        if lower.ends_with(".las") || lower.ends_with(".zip") {
+            if !out_raster_file.is_empty() {
+                println!("Warning: The --out_raster parameter is ignored for LiDAR inputs.");
+            }
+            if verbose {
+                println!("Reading input LAS file...");
+            }
+            let mut input = LasFile::new(&input_file, "r")?;
+            projection = input.get_wkt();
+
+            let n_points = input.header.number_of_points as usize;
+            let mut points = vec![];
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if !p.withheld() && !p.is_classified_noise() && p.z >= min_height {
+                    points.push((p.x, p.y, p.z));
+                }
+            }
+            drop(input);
+            points.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal));
+            let num_points = points.len();
+
+            let mut frs = FixedRadiusSearch2D::new(search_radius, DistanceMetric::Euclidean);
+            for i in 0..num_points {
+                frs.insert(points[i].0, points[i].1, i);
+            }
+
+            // Each tree is seeded by the highest point within the search radius of its top.
+            let mut tree: Vec<usize> = vec![num_tops; num_points];
+            for t in 0..num_tops {
+                let mut seed = num_points;
+                for (j, _) in frs.search(top_x[t], top_y[t]) {
+                    if tree[j] == num_tops && j < seed {
+                        seed = j;
+                    }
+                }
+                if seed < num_points {
+                    tree[seed] = t;
+                    if top_z[t].is_nan() {
+                        top_z[t] = points[seed].2;
+                    }
+                }
+            }
+
+            let mut progress: usize;
+            let mut old_progress: usize = 1;
+            for i in 0..num_points {
+                if tree[i] == num_tops {
+                    let (x, y, z) = points[i];
+                    let mut min_dist = f64::INFINITY;
+                    for (j, _) in frs.search(x, y) {
+                        // only higher points have been assigned to trees
+                        let t = tree[j];
+                        if j < i && t < num_tops && z >= crown_ratio * top_z[t] {
+                            let dist =
+                                (x - top_x[t]) * (x - top_x[t]) + (y - top_y[t]) * (y - top_y[t]);
+                            if dist <= max_crown_radius_sqrd && dist < min_dist {
+                                min_dist = dist;
+                                tree[i] = t;
+                            }
+                        }
+                    }
+                }
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / (num_points - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Growing crowns: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+
+            let mut tree_points: Vec<Vec<Point2D>> = vec![vec![]; num_tops];
+            for i in 0..num_points {
+                if tree[i] < num_tops {
+                    tree_points[tree[i]].push(Point2D::new(points[i].0, points[i].1));
+                }
+            }
+            for t in 0..num_tops {
+                if tree_points[t].len() < 3 {
+                    continue;
+                }
+                let mut hull = convex_hull(&mut tree_points[t]);
+                if hull.len() < 3 {
+                    continue;
+                }
+                // convex_hull returns points in a counter-clockwise order but we need it to be clockwise for a shapefile poly.
+                hull.reverse();
+                let p = hull[0];
+                hull.push(p);
+                let area = polygon_area(&hull);
+                crowns[t] = (vec![hull], area);
+            }
+        } else {
+            if verbose {
+                println!("Reading input raster...");
+            }
+            let input = Raster::new(&input_file, "r")?;
+            projection = input.configs.coordinate_ref_system_wkt.clone();
+            let rows = input.configs.rows as isize;
+            let columns = input.configs.columns as isize;
+            let nodata = input.configs.nodata;
+            let res_x = input.configs.resolution_x;
+            let res_y = input.configs.resolution_y;
+
+            // Crown labels are the tree top record number plus one; zero is unassigned.
+            let mut labels: Array2D<u32> = Array2D::new(rows, columns, 0u32, 0u32)?;
+            let mut queue = BinaryHeap::with_capacity((rows * columns) as usize);
+            for t in 0..num_tops {
+                let row = input.get_row_from_y(top_y[t]);
+                let col = input.get_column_from_x(top_x[t]);
+                let z = input.get_value(row, col);
+                if z == nodata || labels.get_value(row, col) != 0 {
+                    continue;
+                }
+                if top_z[t].is_nan() {
+                    top_z[t] = z;
+                }
+                if z >= min_height {
+                    labels.set_value(row, col, t as u32 + 1);
+                    queue.push(GridCell {
+                        row: row,
+                        column: col,
+                        priority: z,
+                    });
+                }
+            }
+
+            let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+            let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+            let num_cells = (rows * columns) as f64;
+            let mut num_solved = 0f64;
+            let mut progress: usize;
+            let mut old_progress: usize = 1;
+            while let Some(cell) = queue.pop() {
+                let label = labels.get_value(cell.row, cell.column);
+                let t = label as usize - 1;
+                for n in 0..8 {
+                    let row_n = cell.row + dy[n];
+                    let col_n = cell.column + dx[n];
+                    if labels.get_value(row_n, col_n) != 0 {
+                        continue;
+                    }
+                    let zn = input.get_value(row_n, col_n);
+                    if zn == nodata || zn < min_height || zn < crown_ratio * top_z[t] {
+                        continue;
+                    }
+                    let x = input.get_x_from_column(col_n) - top_x[t];
+                    let y = input.get_y_from_row(row_n) - top_y[t];
+                    if x * x + y * y > max_crown_radius_sqrd {
+                        continue;
+                    }
+                    labels.set_value(row_n, col_n, label);
+                    queue.push(GridCell {
+                        row: row_n,
+                        column: col_n,
+                        priority: zn,
+                    });
+                }
+                if verbose {
+                    num_solved += 1f64;
+                    progress = (100f64 * num_solved / num_cells) as usize;
+                    if progress != old_progress {
+                        println!("Growing crowns: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+
+            if verbose {
+                println!("Tracing crown boundaries...");
+            }
+            let cell_area = res_x * res_y;
+            for row in 0..rows {
+                for col in 0..columns {
+                    let label = labels.get_value(row, col);
+                    if label > 0 {
+                        crowns[label as usize - 1].1 += cell_area;
+                    }
+                }
+            }
+            let west = input.configs.west;
+            let north = input.configs.north;
+            for (label, rings) in trace_raster_polygons(&labels, west, north, res_x, res_y) {
+                crowns[label as usize - 1].0 = rings;
+            }
+
+            if !out_raster_file.is_empty() {
+                let mut configs = input.configs.clone();
+                configs.nodata = 0f64;
+                configs.data_type = DataType::I32;
+                configs.photometric_interp = PhotometricInterpretation::Categorical;
+                let mut output = Raster::initialize_using_config(&out_raster_file, &configs);
+                for row in 0..rows {
+                    for col in 0..columns {
+                        output.set_value(row, col, labels.get_value(row, col) as f64);
+                    }
+                }
+                output.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                output.add_metadata_entry(format!("Input CHM file: {}", input_file));
+                output.add_metadata_entry(format!("Input tops file: {}", tops_file));
+                if verbose {
+                    println!("Saving crown raster...")
+                };
+                let _ = match output.write() {
+                    Ok(_) => {
+                        if verbose {
+                            println!("Output file written")
+                        }
+                    }
+                    Err(e) => return Err(e),
+                };
+            }
+        }
+
+        // output the crown polygons, and optionally the tops
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = projection.clone();
+        let fields = vec![
+            AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8),
+            AttributeField::new("TREE_ID", FieldDataType::Int, 7u8, 0u8),
+            AttributeField::new("TOP_X", FieldDataType::Real, 14u8, 3u8),
+            AttributeField::new("TOP_Y", FieldDataType::Real, 14u8, 3u8),
+            AttributeField::new("HEIGHT", FieldDataType::Real, 10u8, 3u8),
+            AttributeField::new("CROWN_AREA", FieldDataType::Real, 12u8, 3u8),
+            AttributeField::new("CROWN_DIAM", FieldDataType::Real, 10u8, 3u8),
+        ];
+        output.attributes.add_fields(&fields);
+
+        let mut out_tops = if !out_tops_file.is_empty() {
+            let mut out_tops = Shapefile::new(&out_tops_file, ShapeType::Point)?;
+            out_tops.projection = projection;
+            out_tops.attributes.add_fields(&fields);
+            Some(out_tops)
+        } else {
+            None
+        };
+
+        let mut fid = 1i32;
+        for t in 0..num_tops {
+            let (rings, area) = &crowns[t];
+            if rings.is_empty() {
+                continue;
+            }
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            // Exterior rings are added before their holes.
+            for ring in rings.iter().filter(|r| is_clockwise_order(r)) {
+                sfg.add_part(ring);
+            }
+            for ring in rings.iter().filter(|r| !is_clockwise_order(r)) {
+                sfg.add_part(ring);
+            }
+            output.add_record(sfg);
+            let atts = vec![
+                FieldData::Int(fid),
+                FieldData::Int(t as i32 + 1),
+                FieldData::Real(top_x[t]),
+                FieldData::Real(top_y[t]),
+                FieldData::Real(top_z[t]),
+                FieldData::Real(*area),
+                FieldData::Real(2f64 * (area / PI).sqrt()),
+            ];
+            if let Some(ref mut out_tops) = out_tops {
+                out_tops.add_point_record(top_x[t], top_y[t]);
+                out_tops.attributes.add_record(atts.clone(), false);
+            }
+            output.attributes.add_record(atts, false);
+            fid += 1;
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of crowns delineated: {}", fid - 1);
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(mut out_tops) = out_tops {
+            let _ = match out_tops.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output tops file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct GridCell {
+    row: isize,
+    column: isize,
+    priority: f64,
+}
+
+impl Eq for GridCell {}
+
+impl PartialOrd for GridCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // a max-heap, such that the highest cells are processed first
+        self.priority.partial_cmp(&other.priority)
+    }
+}
+
+impl Ord for GridCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TreeCrownSegmentation;
+    use crate::raster::*;
+    use crate::tools::WhiteboxTool;
+    use crate::vector::{FieldData, Shapefile};
+    use std::env;
+    use std::f64::consts::PI;
+    use std::fs;
+
+    #[test]
+    fn test_tree_crown_segmentation_chm() {
+        let dir = env::temp_dir().join("wbt_tree_crown_segmentation");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+        // a 1 m CHM of two conical trees, 20 m and 15 m high, on bare ground
+        let configs = RasterConfigs {
+            rows: 30,
+            columns: 30,
+            north: 30.0,
+            south: 0.0,
+            east: 30.0,
+            west: 0.0,
+            resolution_x: 1.0,
+            resolution_y: 1.0,
+            data_type: DataType::F32,
+            photometric_interp: PhotometricInterpretation::Continuous,
+            ..Default::default()
+        };
+        let mut chm = Raster::initialize_using_config(&path("chm.tif"), &configs);
+        for row in 0..30isize {
+            for col in 0..30isize {
+                let cone = |r: isize, c: isize, height: f64| {
+                    height - 2.0 * (((row - r).pow(2) + (col - c).pow(2)) as f64).sqrt()
+                };
+                chm.set_value(row, col, cone(8, 8, 20.0).max(cone(20, 20, 15.0)).max(0.0));
+            }
+        }
+        chm.write().unwrap();
+
+        // the tops of the trees, preceded by one on the ground, without heights
+        let point = |x: f64, y: f64| {
+            format!(
+                r#"{{ "type": "Feature", "properties": {{}}, "geometry": {{ "type": "Point", "coordinates": [{}, {}] }} }}"#,
+                x, y
+            )
+        };
+        fs::write(
+            path("tops.geojson"),
+            format!(
+                r#"{{ "type": "FeatureCollection", "features": [{}, {}, {}] }}"#,
+                point(0.5, 0.5),
+                point(8.5, 21.5),
+                point(20.5, 9.5)
+            ),
+        )
+        .unwrap();
+
+        let run = |max_crown_radius: f64| {
+            TreeCrownSegmentation::new()
+                .run(
+                    vec![
+                        format!("--input={}", path("chm.tif")),
+                        format!("--tops={}", path("tops.geojson")),
+                        format!("--output={}", path("crowns.shp")),
+                        format!("--out_raster={}", path("crowns.tif")),
+                        "--crown_ratio=0.5".to_string(),
+                        format!("--max_crown_radius={}", max_crown_radius),
+                    ],
+                    "",
+                    false,
+                )
+                .unwrap();
+            Shapefile::read(&path("crowns.shp")).unwrap()
+        };
+
+        // Crowns extend down to half of the tree height, i.e. 5 m from the top of the 20 m tree
+        // and 3.75 m from that of the 15 m tree, covering the 81 and 45 cells within these radii.
+        let crowns = run(10.0);
+        assert_eq!(crowns.num_records, 2);
+        let expected = [
+            (2, 20.0, 81.0, [3.0, 14.0, 16.0, 27.0]),
+            (3, 15.0, 45.0, [17.0, 24.0, 6.0, 13.0]),
+        ];
+        for (i, (tree_id, height, area, extent)) in expected.iter().enumerate() {
+            assert_eq!(
+                crowns.attributes.get_value(i, "TREE_ID"),
+                FieldData::Int(*tree_id)
+            );
+            assert_eq!(
+                crowns.attributes.get_value(i, "HEIGHT"),
+                FieldData::Real(*height)
+            );
+            assert_eq!(
+                crowns.attributes.get_value(i, "CROWN_AREA"),
+                FieldData::Real(*area)
+            );
+            match crowns.attributes.get_value(i, "CROWN_DIAM") {
+                FieldData::Real(d) => assert!((d - 2.0 * (area / PI).sqrt()).abs() < 0.001),
+                _ => panic!("The crown diameter is missing."),
+            }
+            let record = crowns.get_record(i);
+            assert_eq!(
+                [record.x_min, record.x_max, record.y_min, record.y_max],
+                *extent
+            );
+        }
+
+        let labels = Raster::new(&path("crowns.tif"), "r").unwrap();
+        assert_eq!(labels.get_value(8, 8), 2.0);
+        assert_eq!(labels.get_value(13, 8), 2.0);
+        assert_eq!(labels.get_value(14, 8), labels.configs.nodata);
+        assert_eq!(labels.get_value(20, 23), 3.0);
+        assert_eq!(labels.get_value(20, 24), labels.configs.nodata);
+        assert_eq!(labels.get_value(0, 0), labels.configs.nodata);
+
+        // both crowns are limited to the 29 cells within 3 m of their tops
+        let crowns = run(3.0);
+        for i in 0..2 {
+            assert_eq!(
+                crowns.attributes.get_value(i, "CROWN_AREA"),
+                FieldData::Real(29.0)
+            );
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -7,6 +7,8 @@ pub mod math_stat_analysis;
 pub mod stream_network_analysis;
 pub mod terrain_analysis;
 
+mod breaklines;
+
 use crate::utils::get_formatted_elapsed_time;
 use serde_json;
 use std::io::{Error, ErrorKind};
@@ -31,6 +33,7 @@ impl ToolManager {
         tool_names.push("CleanVector".to_string());
         tool_names.push("ConvertNodataToZero".to_string());
         tool_names.push("ConvertRasterFormat".to_string());
+        tool_names.push("CreateSpatialIndex".to_string());
         tool_names.push("CsvPointsToVector".to_string());
         tool_names.push("ExportTableToCsv".to_string());
         tool_names.push("JoinTables".to_string());
@@ -49,6 +52,8 @@ impl ToolManager {
         tool_names.push("RemovePolygonHoles".to_string());
         tool_names.push("SetNodataValue".to_string());
         tool_names.push("SinglePartToMultiPart".to_string());
+        tool_names.push("ValidatePolygons".to_string());
+        tool_names.push("ValidateTopology".to_string());
         tool_names.push("VectorLinesToRaster".to_string());
         tool_names.push("VectorPointsToRaster".to_string());
         tool_names.push("VectorPolygonsToRaster".to_string());
@@ -60,7 +65,7 @@ impl ToolManager {
         tool_names.push("BlockMinimumGridding".to_string());
         tool_names.push("BoundaryShapeComplexity".to_string());
         tool_names.push("BufferRaster".to_string());
-        // tool_names.push("BufferVector".to_string());
+        tool_names.push("BufferVector".to_string());
         tool_names.push("Centroid".to_string());
         tool_names.push("CentroidVector".to_string());
         tool_names.push("Clip".to_string());
@@ -132,6 +137,7 @@ impl ToolManager {
         tool_names.push("RelatedCircumscribingCircle".to_string());
         tool_names.push("ShapeComplexityIndex".to_string());
         tool_names.push("ShapeComplexityIndexRaster".to_string());
+        tool_names.push("SimplifyVectors".to_string());
         tool_names.push("SmoothVectors".to_string());
         tool_names.push("SplitWithLines".to_string());
         tool_names.push("SumOverlay".to_string());
@@ -267,6 +273,9 @@ impl ToolManager {
 
         // lidar_analysis
         tool_names.push("AsciiToLas".to_string());
+        tool_names.push("ClassifyPowerLinesInLidar".to_string());
+        tool_names.push("FlightlineAlignment".to_string());
+        tool_names.push("IndividualTreeDetection".to_string());
         tool_names.push("LidarBlockMaximum".to_string());
         tool_names.push("LidarBlockMinimum".to_string());
         tool_names.push("ClassifyBuildingsInLidar".to_string());
@@ -281,6 +290,7 @@ impl ToolManager {
         tool_names.push("LasToAscii".to_string());
         tool_names.push("LasToMultipointShapefile".to_string());
         tool_names.push("LasToShapefile".to_string());
+        tool_names.push("LidarBuildingFootprints".to_string());
         tool_names.push("LidarClassifySubset".to_string());
         tool_names.push("LidarColourize".to_string());
         tool_names.push("LidarConstructVectorTIN".to_string());
@@ -289,17 +299,23 @@ impl ToolManager {
         tool_names.push("LidarHexBinning".to_string());
         tool_names.push("LidarHillshade".to_string());
         tool_names.push("LidarHistogram".to_string());
+        tool_names.push("LidarHydroFlatten".to_string());
         tool_names.push("LidarIdwInterpolation".to_string());
         tool_names.push("LidarInfo".to_string());
         tool_names.push("LidarJoin".to_string());
         tool_names.push("LidarKappaIndex".to_string());
+        tool_names.push("LidarLeafAreaDensity".to_string());
+        tool_names.push("LidarM3C2".to_string());
         tool_names.push("LidarNearestNeighbourGridding".to_string());
+        tool_names.push("LidarPitFreeCHM".to_string());
         tool_names.push("LidarPointDensity".to_string());
         tool_names.push("LidarPointStats".to_string());
+        tool_names.push("LidarQualityReport".to_string());
         tool_names.push("LidarRbfInterpolation".to_string());
         tool_names.push("LidarRansacPlanes".to_string());
         tool_names.push("LidarRemoveDuplicates".to_string());
         tool_names.push("LidarRemoveOutliers".to_string());
+        tool_names.push("LidarReproject".to_string());
         tool_names.push("LidarSegmentation".to_string());
         tool_names.push("LidarSegmentationBasedFilter".to_string());
         tool_names.push("LidarThin".to_string());
@@ -308,8 +324,14 @@ impl ToolManager {
         tool_names.push("LidarTileFootprint".to_string());
         tool_names.push("LidarTINGridding".to_string());
         tool_names.push("LidarTophatTransform".to_string());
+        tool_names.push("LidarVegetationMetrics".to_string());
+        tool_names.push("LidarVerticalComplexityIndex".to_string());
+        tool_names.push("LidarVoxelize".to_string());
+        tool_names.push("LidarVoxelThin".to_string());
+        tool_names.push("NormalizeLidarIntensity".to_string());
         tool_names.push("NormalVectors".to_string());
         tool_names.push("SelectTilesByPolygon".to_string());
+        tool_names.push("TreeCrownSegmentation".to_string());
 
         // mathematical and statistical_analysis
         tool_names.push("AbsoluteValue".to_string());
@@ -501,6 +523,7 @@ impl ToolManager {
             "cleanvector" => Some(Box::new(data_tools::CleanVector::new())),
             "convertnodatatozero" => Some(Box::new(data_tools::ConvertNodataToZero::new())),
             "convertrasterformat" => Some(Box::new(data_tools::ConvertRasterFormat::new())),
+            "createspatialindex" => Some(Box::new(data_tools::CreateSpatialIndex::new())),
             "csvpointstovector" => Some(Box::new(data_tools::CsvPointsToVector::new())),
             "exporttabletocsv" => Some(Box::new(data_tools::ExportTableToCsv::new())),
             "jointables" => Some(Box::new(data_tools::JoinTables::new())),
@@ -521,6 +544,8 @@ impl ToolManager {
             "removepolygonholes" => Some(Box::new(data_tools::RemovePolygonHoles::new())),
             "setnodatavalue" => Some(Box::new(data_tools::SetNodataValue::new())),
             "singleparttomultipart" => Some(Box::new(data_tools::SinglePartToMultiPart::new())),
+            "validatepolygons" => Some(Box::new(data_tools::ValidatePolygons::new())),
+            "validatetopology" => Some(Box::new(data_tools::ValidateTopology::new())),
             "vectorlinestoraster" => Some(Box::new(data_tools::VectorLinesToRaster::new())),
             "vectorpointstoraster" => Some(Box::new(data_tools::VectorPointsToRaster::new())),
             "vectorpolygonstoraster" => Some(Box::new(data_tools::VectorPolygonsToRaster::new())),
@@ -534,7 +559,7 @@ impl ToolManager {
                 Some(Box::new(gis_analysis::BoundaryShapeComplexity::new()))
             }
             "bufferraster" => Some(Box::new(gis_analysis::BufferRaster::new())),
-            // "buffervector" => Some(Box::new(gis_analysis::BufferVector::new())),
+            "buffervector" => Some(Box::new(gis_analysis::BufferVector::new())),
             "centroid" => Some(Box::new(gis_analysis::Centroid::new())),
             "centroidvector" => Some(Box::new(gis_analysis::CentroidVector::new())),
             "clip" => Some(Box::new(gis_analysis::Clip::new())),
@@ -630,6 +655,7 @@ impl ToolManager {
             "shapecomplexityindexraster" => {
                 Some(Box::new(gis_analysis::ShapeComplexityIndexRaster::new()))
             }
+            "simplifyvectors" => Some(Box::new(gis_analysis::SimplifyVectors::new())),
             "smoothvectors" => Some(Box::new(gis_analysis::SmoothVectors::new())),
             "splitwithlines" => Some(Box::new(gis_analysis::SplitWithLines::new())),
             "sumoverlay" => Some(Box::new(gis_analysis::SumOverlay::new())),
@@ -825,6 +851,13 @@ impl ToolManager {
 
             // lidar_analysis
             "asciitolas" => Some(Box::new(lidar_analysis::AsciiToLas::new())),
+            "classifypowerlinesinlidar" => {
+                Some(Box::new(lidar_analysis::ClassifyPowerLinesInLidar::new()))
+            }
+            "flightlinealignment" => Some(Box::new(lidar_analysis::FlightlineAlignment::new())),
+            "individualtreedetection" => {
+                Some(Box::new(lidar_analysis::IndividualTreeDetection::new()))
+            }
             "lidarblockmaximum" => Some(Box::new(lidar_analysis::LidarBlockMaximum::new())),
             "lidarblockminimum" => Some(Box::new(lidar_analysis::LidarBlockMinimum::new())),
             "classifybuildingsinlidar" => {
@@ -845,6 +878,9 @@ impl ToolManager {
                 Some(Box::new(lidar_analysis::LasToMultipointShapefile::new()))
             }
             "lastoshapefile" => Some(Box::new(lidar_analysis::LasToShapefile::new())),
+            "lidarbuildingfootprints" => {
+                Some(Box::new(lidar_analysis::LidarBuildingFootprints::new()))
+            }
             "lidarclassifysubset" => Some(Box::new(lidar_analysis::LidarClassifySubset::new())),
             "lidarcolourize" => Some(Box::new(lidar_analysis::LidarColourize::new())),
             "lidarconstructvectortin" => {
@@ -857,19 +893,25 @@ impl ToolManager {
             "lidarhexbinning" => Some(Box::new(lidar_analysis::LidarHexBinning::new())),
             "lidarhillshade" => Some(Box::new(lidar_analysis::LidarHillshade::new())),
             "lidarhistogram" => Some(Box::new(lidar_analysis::LidarHistogram::new())),
+            "lidarhydroflatten" => Some(Box::new(lidar_analysis::LidarHydroFlatten::new())),
             "lidaridwinterpolation" => Some(Box::new(lidar_analysis::LidarIdwInterpolation::new())),
             "lidarinfo" => Some(Box::new(lidar_analysis::LidarInfo::new())),
             "lidarjoin" => Some(Box::new(lidar_analysis::LidarJoin::new())),
             "lidarkappaindex" => Some(Box::new(lidar_analysis::LidarKappaIndex::new())),
+            "lidarleafareadensity" => Some(Box::new(lidar_analysis::LidarLeafAreaDensity::new())),
+            "lidarm3c2" => Some(Box::new(lidar_analysis::LidarM3C2::new())),
             "lidarnearestneighbourgridding" => Some(Box::new(
                 lidar_analysis::LidarNearestNeighbourGridding::new(),
             )),
+            "lidarpitfreechm" => Some(Box::new(lidar_analysis::LidarPitFreeCHM::new())),
             "lidarpointdensity" => Some(Box::new(lidar_analysis::LidarPointDensity::new())),
             "lidarpointstats" => Some(Box::new(lidar_analysis::LidarPointStats::new())),
+            "lidarqualityreport" => Some(Box::new(lidar_analysis::LidarQualityReport::new())),
             "lidarrbfinterpolation" => Some(Box::new(lidar_analysis::LidarRbfInterpolation::new())),
             "lidarransacplanes" => Some(Box::new(lidar_analysis::LidarRansacPlanes::new())),
             "lidarremoveduplicates" => Some(Box::new(lidar_analysis::LidarRemoveDuplicates::new())),
             "lidarremoveoutliers" => Some(Box::new(lidar_analysis::LidarRemoveOutliers::new())),
+            "lidarreproject" => Some(Box::new(lidar_analysis::LidarReproject::new())),
             "lidarsegmentation" => Some(Box::new(lidar_analysis::LidarSegmentation::new())),
             "lidarsegmentationbasedfilter" => {
                 Some(Box::new(lidar_analysis::LidarSegmentationBasedFilter::new()))
@@ -880,8 +922,20 @@ impl ToolManager {
             "lidartilefootprint" => Some(Box::new(lidar_analysis::LidarTileFootprint::new())),
             "lidartingridding" => Some(Box::new(lidar_analysis::LidarTINGridding::new())),
             "lidartophattransform" => Some(Box::new(lidar_analysis::LidarTophatTransform::new())),
+            "lidarvegetationmetrics" => {
+                Some(Box::new(lidar_analysis::LidarVegetationMetrics::new()))
+            }
+            "lidarverticalcomplexityindex" => {
+                Some(Box::new(lidar_analysis::LidarVerticalComplexityIndex::new()))
+            }
+            "lidarvoxelize" => Some(Box::new(lidar_analysis::LidarVoxelize::new())),
+            "lidarvoxelthin" => Some(Box::new(lidar_analysis::LidarVoxelThin::new())),
+            "normalizelidarintensity" => {
+                Some(Box::new(lidar_analysis::NormalizeLidarIntensity::new()))
+            }
             "normalvectors" => Some(Box::new(lidar_analysis::NormalVectors::new())),
             "selecttilesbypolygon" => Some(Box::new(lidar_analysis::SelectTilesByPolygon::new())),
+            "treecrownsegmentation" => Some(Box::new(lidar_analysis::TreeCrownSegmentation::new())),
 
             // mathematical and statistical_analysis
             "absolutevalue" => Some(Box::new(math_stat_analysis::AbsoluteValue::new())),
@@ -1,11 +1,15 @@
 // private sub-module defined in other files
 mod byte_order_reader;
 mod byte_order_writer;
+mod sqlite;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::byte_order_reader::ByteOrderReader;
 pub use self::byte_order_reader::Endianness;
 pub use self::byte_order_writer::ByteOrderWriter;
+pub use self::sqlite::{
+    write_sqlite_database, SqliteColumn, SqliteDatabase, SqliteTable, SqliteValue,
+};
 
 use std::time::Instant;
 
@@ -0,0 +1,1134 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: A minimal, embedded reader and writer for SQLite version 3 database files, sufficient
+for GeoPackage support. Rowid tables are read by walking their table b-trees directly; indexes,
+virtual tables, and WITHOUT ROWID tables are not read, and a database is written as a new file
+containing rowid tables only. The file format is described at https://www.sqlite.org/fileformat.html.
+*/
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+
+/// A value stored in a SQLite database.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqliteValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqliteValue {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            SqliteValue::Integer(v) => Some(*v),
+            SqliteValue::Real(v) => Some(*v as i64),
+            SqliteValue::Text(s) => s.trim().parse::<i64>().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SqliteValue::Integer(v) => Some(*v as f64),
+            SqliteValue::Real(v) => Some(*v),
+            SqliteValue::Text(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SqliteValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_blob(&self) -> Option<&[u8]> {
+        match self {
+            SqliteValue::Blob(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+/// An entry of the sqlite_master schema table.
+#[derive(Clone, Debug)]
+pub struct SchemaEntry {
+    pub entry_type: String,
+    pub name: String,
+    pub table_name: String,
+    pub root_page: u32,
+    pub sql: String,
+}
+
+/// A table column, as declared in a CREATE TABLE statement.
+#[derive(Clone, Debug)]
+pub struct SqliteColumn {
+    pub name: String,
+    pub declared_type: String,
+    /// True for an INTEGER PRIMARY KEY column, which is an alias for the rowid.
+    pub is_rowid_alias: bool,
+}
+
+/// An in-memory SQLite table.
+#[derive(Clone, Debug, Default)]
+pub struct SqliteTable {
+    pub name: String,
+    pub sql: String,
+    pub columns: Vec<SqliteColumn>,
+    pub rows: Vec<Vec<SqliteValue>>,
+}
+
+impl SqliteTable {
+    /// Creates an empty table from a CREATE TABLE statement.
+    pub fn new(name: &str, sql: &str) -> SqliteTable {
+        SqliteTable {
+            name: name.to_string(),
+            sql: sql.to_string(),
+            columns: parse_columns(sql),
+            rows: vec![],
+        }
+    }
+
+    /// Returns the index of a column, ignoring case.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns a value of a row by column name, or Null if the column does not exist.
+    pub fn get<'a>(&'a self, row: usize, column: &str) -> &'a SqliteValue {
+        match self.column_index(column) {
+            Some(c) if c < self.rows[row].len() => &self.rows[row][c],
+            _ => &SqliteValue::Null,
+        }
+    }
+}
+
+/// A read-only SQLite database, held in memory.
+pub struct SqliteDatabase {
+    buffer: Vec<u8>,
+    page_size: usize,
+    usable_size: usize,
+    text_encoding: u32,
+    pub schema: Vec<SchemaEntry>,
+}
+
+impl SqliteDatabase {
+    pub fn open(file_name: &str) -> Result<SqliteDatabase, Error> {
+        let mut f = File::open(file_name)?;
+        let mut buffer = vec![];
+        f.read_to_end(&mut buffer)?;
+        SqliteDatabase::from_bytes(buffer)
+    }
+
+    pub fn from_bytes(buffer: Vec<u8>) -> Result<SqliteDatabase, Error> {
+        if buffer.len() < 100 || &buffer[0..16] != b"SQLite format 3\0" {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The file is not a SQLite database.",
+            ));
+        }
+        if buffer[18] == 2 || buffer[19] == 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The SQLite database is in WAL mode; checkpoint it and change its journal mode (e.g. PRAGMA journal_mode=DELETE) before reading it.",
+            ));
+        }
+        let page_size = match read_u16(&buffer, 16) as usize {
+            1 => 65536,
+            n => n,
+        };
+        if page_size < 512
+            || !page_size.is_power_of_two()
+            || page_size - (buffer[20] as usize) < 480
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The database header contains an invalid page size.",
+            ));
+        }
+        let usable_size = page_size - buffer[20] as usize;
+        let text_encoding = read_u32(&buffer, 56);
+        let mut db = SqliteDatabase {
+            buffer: buffer,
+            page_size: page_size,
+            usable_size: usable_size,
+            text_encoding: text_encoding,
+            schema: vec![],
+        };
+        let mut records = vec![];
+        db.read_btree(1, &mut records, 0)?;
+        for (_, values) in records {
+            let text = |i: usize| match values.get(i) {
+                Some(SqliteValue::Text(s)) => s.clone(),
+                _ => String::new(),
+            };
+            db.schema.push(SchemaEntry {
+                entry_type: text(0),
+                name: text(1),
+                table_name: text(2),
+                root_page: values.get(3).and_then(|v| v.as_i64()).unwrap_or(0) as u32,
+                sql: text(4),
+            });
+        }
+        Ok(db)
+    }
+
+    /// Returns the application ID stored in the database header.
+    pub fn application_id(&self) -> u32 {
+        read_u32(&self.buffer, 68)
+    }
+
+    /// Returns true if the database contains a table, ignoring case.
+    pub fn has_table(&self, name: &str) -> bool {
+        self.find_table(name).is_some()
+    }
+
+    fn find_table(&self, name: &str) -> Option<&SchemaEntry> {
+        self.schema
+            .iter()
+            .find(|e| e.entry_type == "table" && e.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Reads all of the rows of a table.
+    pub fn read_table(&self, name: &str) -> Result<SqliteTable, Error> {
+        let entry = self.find_table(name).ok_or(Error::new(
+            ErrorKind::NotFound,
+            format!("The database does not contain a table named '{}'.", name),
+        ))?;
+        if entry.root_page == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The table '{}' is a virtual table.", name),
+            ));
+        }
+        if entry.sql.to_uppercase().contains("WITHOUT ROWID") {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The WITHOUT ROWID table '{}' is not supported.", name),
+            ));
+        }
+        let mut table = SqliteTable::new(&entry.name, &entry.sql);
+        let mut records = vec![];
+        self.read_btree(entry.root_page as usize, &mut records, 0)?;
+        let rowid_col = table.columns.iter().position(|c| c.is_rowid_alias);
+        let num_columns = table.columns.len();
+        for (rowid, mut values) in records {
+            // columns added by ALTER TABLE are missing from older records
+            values.resize(num_columns.max(values.len()), SqliteValue::Null);
+            if let Some(c) = rowid_col {
+                values[c] = SqliteValue::Integer(rowid);
+            }
+            table.rows.push(values);
+        }
+        Ok(table)
+    }
+
+    fn page_offset(&self, page: usize) -> Result<usize, Error> {
+        if page == 0 || page * self.page_size > self.buffer.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The database references a missing page ({}).", page),
+            ));
+        }
+        Ok((page - 1) * self.page_size)
+    }
+
+    fn read_btree(
+        &self,
+        page: usize,
+        records: &mut Vec<(i64, Vec<SqliteValue>)>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        if depth > 64 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The database contains a malformed b-tree.",
+            ));
+        }
+        let page_start = self.page_offset(page)?;
+        let header = if page == 1 { 100 } else { 0 };
+        let b = checked_slice(&self.buffer, page_start, self.page_size)?;
+        let page_type = b[header];
+        let num_cells = read_u16(b, header + 3) as usize;
+        match page_type {
+            0x05 => {
+                // interior table page
+                for i in 0..num_cells {
+                    let cell = read_u16(checked_slice(b, header + 12 + 2 * i, 2)?, 0) as usize;
+                    let child = read_u32(checked_slice(b, cell, 4)?, 0) as usize;
+                    self.read_btree(child, records, depth + 1)?;
+                }
+                let right = read_u32(b, header + 8) as usize;
+                self.read_btree(right, records, depth + 1)
+            }
+            0x0D => {
+                // leaf table page
+                for i in 0..num_cells {
+                    let mut pos = read_u16(checked_slice(b, header + 8 + 2 * i, 2)?, 0) as usize;
+                    let (payload_size, n) = checked_varint(b, pos)?;
+                    pos += n;
+                    let (rowid, n) = checked_varint(b, pos)?;
+                    pos += n;
+                    let payload = self.read_payload(b, pos, payload_size as usize)?;
+                    records.push((rowid as i64, self.decode_record(&payload)?));
+                }
+                Ok(())
+            }
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported b-tree page type ({}).", page_type),
+            )),
+        }
+    }
+
+    fn read_payload(&self, b: &[u8], pos: usize, size: usize) -> Result<Vec<u8>, Error> {
+        let local = local_payload_size(self.usable_size, size);
+        let mut payload = checked_slice(b, pos, local)?.to_vec();
+        if local < size {
+            let mut next = read_u32(checked_slice(b, pos + local, 4)?, 0) as usize;
+            while payload.len() < size {
+                let offset = self.page_offset(next)?;
+                let n = (size - payload.len()).min(self.usable_size - 4);
+                payload.extend_from_slice(checked_slice(&self.buffer, offset + 4, n)?);
+                next = read_u32(&self.buffer, offset) as usize;
+            }
+        }
+        Ok(payload)
+    }
+
+    fn decode_record(&self, payload: &[u8]) -> Result<Vec<SqliteValue>, Error> {
+        let (header_size, mut hpos) = checked_varint(payload, 0)?;
+        let header_size = header_size as usize;
+        let mut pos = header_size;
+        let mut values = vec![];
+        while hpos < header_size {
+            let (serial_type, n) = checked_varint(payload, hpos)?;
+            hpos += n;
+            let size = serial_type_size(serial_type);
+            let data = checked_slice(payload, pos, size)?;
+            pos += size;
+            values.push(match serial_type {
+                0 => SqliteValue::Null,
+                1..=6 => {
+                    let mut v = if data[0] & 0x80 != 0 { -1i64 } else { 0i64 };
+                    for byte in data {
+                        v = (v << 8) | *byte as i64;
+                    }
+                    SqliteValue::Integer(v)
+                }
+                7 => SqliteValue::Real(f64::from_bits(read_u64(data, 0))),
+                8 => SqliteValue::Integer(0),
+                9 => SqliteValue::Integer(1),
+                n if n >= 12 && n % 2 == 0 => SqliteValue::Blob(data.to_vec()),
+                n if n >= 13 => SqliteValue::Text(self.decode_text(data)),
+                _ => SqliteValue::Null,
+            });
+        }
+        Ok(values)
+    }
+
+    fn decode_text(&self, data: &[u8]) -> String {
+        match self.text_encoding {
+            2 | 3 => {
+                let units = data
+                    .chunks(2)
+                    .filter(|c| c.len() == 2)
+                    .map(|c| {
+                        if self.text_encoding == 2 {
+                            u16::from_le_bytes([c[0], c[1]])
+                        } else {
+                            u16::from_be_bytes([c[0], c[1]])
+                        }
+                    })
+                    .collect::<Vec<u16>>();
+                String::from_utf16_lossy(&units)
+            }
+            _ => String::from_utf8_lossy(data).to_string(),
+        }
+    }
+}
+
+/// Writes a new SQLite database file (UTF-8, 4096-byte pages) containing a set of rowid tables.
+/// Rows are stored in order, and the rowid of each row is the value of its INTEGER PRIMARY KEY
+/// column, if the table has one and the value is not null, or otherwise one more than that of
+/// the previous row; rows must therefore be in ascending order of any INTEGER PRIMARY KEY. The
+/// automatic indexes of PRIMARY KEY and UNIQUE constraints are written, and must each fit on a
+/// single page, which is ample for small metadata tables.
+pub fn write_sqlite_database(
+    file_name: &str,
+    tables: &[SqliteTable],
+    application_id: u32,
+    user_version: u32,
+) -> Result<(), Error> {
+    let mut builder = BTreeBuilder {
+        page_size: 4096,
+        pages: vec![vec![]], // page 1 is reserved for the sqlite_master root
+    };
+    let mut master_cells = vec![];
+    for table in tables {
+        let rowid_col = table.columns.iter().position(|c| c.is_rowid_alias);
+        let mut cells = Vec::with_capacity(table.rows.len());
+        let mut rowids = Vec::with_capacity(table.rows.len());
+        for row in &table.rows {
+            let last_rowid = rowids.last().cloned();
+            let rowid = match rowid_col.and_then(|c| row.get(c)).and_then(|v| v.as_i64()) {
+                Some(v) if last_rowid.map_or(true, |l| v > l) => v,
+                _ => last_rowid.map_or(1, |l| l + 1),
+            };
+            rowids.push(rowid);
+            let mut values = row.clone();
+            if let Some(c) = rowid_col {
+                if c < values.len() {
+                    values[c] = SqliteValue::Null;
+                }
+            }
+            cells.push((rowid, encode_record(&values)));
+        }
+        let root = builder.build(cells, false);
+        master_cells.push(encode_record(&vec![
+            SqliteValue::Text("table".to_string()),
+            SqliteValue::Text(table.name.clone()),
+            SqliteValue::Text(table.name.clone()),
+            SqliteValue::Integer(root as i64),
+            SqliteValue::Text(table.sql.clone()),
+        ]));
+
+        // the automatic indexes of the table's PRIMARY KEY and UNIQUE constraints
+        for (n, key) in parse_unique_constraints(&table.sql, &table.columns)
+            .iter()
+            .enumerate()
+        {
+            let mut entries = table
+                .rows
+                .iter()
+                .zip(rowids.iter())
+                .map(|(row, rowid)| {
+                    let mut entry = key
+                        .iter()
+                        .map(|c| row.get(*c).cloned().unwrap_or(SqliteValue::Null))
+                        .collect::<Vec<SqliteValue>>();
+                    entry.push(SqliteValue::Integer(*rowid));
+                    entry
+                })
+                .collect::<Vec<Vec<SqliteValue>>>();
+            entries.sort_by(|a, b| compare_records(a, b));
+            let name = format!("sqlite_autoindex_{}_{}", table.name, n + 1);
+            let root = builder.build_index(entries).ok_or(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The index {} is too large to be written.", name),
+            ))?;
+            master_cells.push(encode_record(&vec![
+                SqliteValue::Text("index".to_string()),
+                SqliteValue::Text(name),
+                SqliteValue::Text(table.name.clone()),
+                SqliteValue::Integer(root as i64),
+                SqliteValue::Null,
+            ]));
+        }
+    }
+    let master_cells = master_cells
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| (i as i64 + 1, record))
+        .collect();
+    builder.build(master_cells, true);
+
+    // the database header
+    let num_pages = builder.pages.len() as u32;
+    let mut header = vec![0u8; 100];
+    header[0..16].copy_from_slice(b"SQLite format 3\0");
+    header[16..18].copy_from_slice(&(builder.page_size as u16).to_be_bytes());
+    header[18] = 1; // file format write version (legacy)
+    header[19] = 1; // file format read version (legacy)
+    header[20] = 0; // reserved space per page
+    header[21] = 64; // maximum embedded payload fraction
+    header[22] = 32; // minimum embedded payload fraction
+    header[23] = 32; // leaf payload fraction
+    header[24..28].copy_from_slice(&1u32.to_be_bytes()); // file change counter
+    header[28..32].copy_from_slice(&num_pages.to_be_bytes());
+    header[40..44].copy_from_slice(&1u32.to_be_bytes()); // schema cookie
+    header[44..48].copy_from_slice(&4u32.to_be_bytes()); // schema format number
+    header[56..60].copy_from_slice(&1u32.to_be_bytes()); // UTF-8
+    header[60..64].copy_from_slice(&user_version.to_be_bytes());
+    header[68..72].copy_from_slice(&application_id.to_be_bytes());
+    header[92..96].copy_from_slice(&1u32.to_be_bytes()); // version-valid-for number
+    header[96..100].copy_from_slice(&3031001u32.to_be_bytes()); // SQLite version number
+    builder.pages[0][0..100].copy_from_slice(&header);
+
+    let f = File::create(file_name)?;
+    let mut writer = BufWriter::new(f);
+    for page in &builder.pages {
+        writer.write_all(page)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+struct BTreeBuilder {
+    page_size: usize,
+    pages: Vec<Vec<u8>>,
+}
+
+impl BTreeBuilder {
+    fn allocate(&mut self) -> usize {
+        self.pages.push(vec![0u8; self.page_size]);
+        self.pages.len()
+    }
+
+    /// Builds a table b-tree from (rowid, record) cells, in rowid order, returning the root
+    /// page number. If `on_page_one` is true, the root is placed on the first page.
+    fn build(&mut self, cells: Vec<(i64, Vec<u8>)>, on_page_one: bool) -> usize {
+        let usable = self.page_size;
+        // encode the leaf cells, spilling large payloads onto overflow pages
+        let mut leaf_cells = Vec::with_capacity(cells.len());
+        for (rowid, payload) in cells {
+            let mut cell = vec![];
+            write_varint(payload.len() as u64, &mut cell);
+            write_varint(rowid as u64, &mut cell);
+            let local = local_payload_size(usable, payload.len());
+            cell.extend_from_slice(&payload[..local]);
+            if local < payload.len() {
+                let chunks = payload[local..].chunks(usable - 4).collect::<Vec<&[u8]>>();
+                let first = self.pages.len() + 1;
+                cell.extend_from_slice(&(first as u32).to_be_bytes());
+                for (k, chunk) in chunks.iter().enumerate() {
+                    let page = self.allocate();
+                    let next = if k < chunks.len() - 1 { page + 1 } else { 0 };
+                    self.pages[page - 1][0..4].copy_from_slice(&(next as u32).to_be_bytes());
+                    self.pages[page - 1][4..4 + chunk.len()].copy_from_slice(chunk);
+                }
+            }
+            leaf_cells.push((rowid, cell));
+        }
+
+        // a tree that fits on a single page
+        let root_capacity = usable - 8 - if on_page_one { 100 } else { 0 };
+        let total = leaf_cells.iter().map(|(_, c)| c.len() + 2).sum::<usize>();
+        if total <= root_capacity {
+            let page = if on_page_one {
+                self.pages[0] = vec![0u8; self.page_size];
+                1
+            } else {
+                self.allocate()
+            };
+            let cells = leaf_cells.into_iter().map(|(_, c)| c).collect();
+            self.write_page(page, 0x0D, cells, 0);
+            return page;
+        }
+
+        // pack the leaves
+        let mut children: Vec<(usize, i64)> = vec![];
+        let mut current = vec![];
+        let mut used = 0;
+        let mut max_rowid = 0;
+        for (rowid, cell) in leaf_cells {
+            if used + cell.len() + 2 > usable - 8 && !current.is_empty() {
+                let page = self.allocate();
+                self.write_page(page, 0x0D, current, 0);
+                children.push((page, max_rowid));
+                current = vec![];
+                used = 0;
+            }
+            used += cell.len() + 2;
+            max_rowid = rowid;
+            current.push(cell);
+        }
+        if !current.is_empty() {
+            let page = self.allocate();
+            self.write_page(page, 0x0D, current, 0);
+            children.push((page, max_rowid));
+        }
+
+        // build the interior levels
+        loop {
+            let interior_cell = |child: usize, rowid: i64| {
+                let mut cell = (child as u32).to_be_bytes().to_vec();
+                write_varint(rowid as u64, &mut cell);
+                cell
+            };
+            let total = children[..children.len() - 1]
+                .iter()
+                .map(|(p, r)| interior_cell(*p, *r).len() + 2)
+                .sum::<usize>();
+            if total <= usable - 12 - if on_page_one { 100 } else { 0 } {
+                let page = if on_page_one {
+                    self.pages[0] = vec![0u8; self.page_size];
+                    1
+                } else {
+                    self.allocate()
+                };
+                let (right, _) = children[children.len() - 1];
+                let cells = children[..children.len() - 1]
+                    .iter()
+                    .map(|(p, r)| interior_cell(*p, *r))
+                    .collect();
+                self.write_page(page, 0x05, cells, right);
+                return page;
+            }
+            let mut parents: Vec<(usize, i64)> = vec![];
+            let mut group: Vec<(usize, i64)> = vec![];
+            let mut used = 0;
+            for (child, rowid) in children {
+                let size = interior_cell(child, rowid).len() + 2;
+                if used + size > usable - 12 && group.len() > 1 {
+                    // the last child of the group becomes the right-most pointer
+                    let (right, right_rowid) = group.pop().unwrap();
+                    let page = self.allocate();
+                    let cells = group.iter().map(|(p, r)| interior_cell(*p, *r)).collect();
+                    self.write_page(page, 0x05, cells, right);
+                    parents.push((page, right_rowid));
+                    group = vec![];
+                    used = 0;
+                }
+                used += size;
+                group.push((child, rowid));
+            }
+            let (right, right_rowid) = group.pop().unwrap();
+            let page = self.allocate();
+            let cells = group.iter().map(|(p, r)| interior_cell(*p, *r)).collect();
+            self.write_page(page, 0x05, cells, right);
+            parents.push((page, right_rowid));
+            children = parents;
+        }
+    }
+
+    /// Builds an index b-tree that fits on a single leaf page from records in key order,
+    /// returning the root page number, or None if the records do not fit.
+    fn build_index(&mut self, records: Vec<Vec<SqliteValue>>) -> Option<usize> {
+        let max_local = (self.page_size - 12) * 64 / 255 - 23;
+        let mut cells = vec![];
+        let mut total = 0;
+        for record in records {
+            let payload = encode_record(&record);
+            if payload.len() > max_local {
+                return None;
+            }
+            let mut cell = vec![];
+            write_varint(payload.len() as u64, &mut cell);
+            cell.extend_from_slice(&payload);
+            total += cell.len() + 2;
+            cells.push(cell);
+        }
+        if total > self.page_size - 8 {
+            return None;
+        }
+        let page = self.allocate();
+        self.write_page(page, 0x0A, cells, 0);
+        Some(page)
+    }
+
+    fn write_page(&mut self, page: usize, page_type: u8, cells: Vec<Vec<u8>>, right: usize) {
+        let header = if page == 1 { 100 } else { 0 };
+        let header_size = if page_type == 0x05 { 12 } else { 8 };
+        let b = &mut self.pages[page - 1];
+        let mut content = b.len();
+        for (i, cell) in cells.iter().enumerate() {
+            content -= cell.len();
+            b[content..content + cell.len()].copy_from_slice(cell);
+            let ptr = header + header_size + 2 * i;
+            b[ptr..ptr + 2].copy_from_slice(&(content as u16).to_be_bytes());
+        }
+        b[header] = page_type;
+        b[header + 1..header + 3].copy_from_slice(&0u16.to_be_bytes());
+        b[header + 3..header + 5].copy_from_slice(&(cells.len() as u16).to_be_bytes());
+        let content_start = if content == 65536 { 0 } else { content as u16 };
+        b[header + 5..header + 7].copy_from_slice(&content_start.to_be_bytes());
+        b[header + 7] = 0;
+        if page_type == 0x05 {
+            b[header + 8..header + 12].copy_from_slice(&(right as u32).to_be_bytes());
+        }
+    }
+}
+
+/// Compares two records using the BINARY collation, in which NULLs sort before numbers,
+/// numbers before text, and text before blobs.
+fn compare_records(a: &[SqliteValue], b: &[SqliteValue]) -> Ordering {
+    let class = |v: &SqliteValue| match v {
+        SqliteValue::Null => 0,
+        SqliteValue::Integer(_) | SqliteValue::Real(_) => 1,
+        SqliteValue::Text(_) => 2,
+        SqliteValue::Blob(_) => 3,
+    };
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x, y) {
+            (SqliteValue::Integer(i), SqliteValue::Integer(j)) => i.cmp(j),
+            (SqliteValue::Text(s), SqliteValue::Text(t)) => s.as_bytes().cmp(t.as_bytes()),
+            (SqliteValue::Blob(s), SqliteValue::Blob(t)) => s.cmp(t),
+            _ if class(x) == 1 && class(y) == 1 => x
+                .as_f64()
+                .unwrap()
+                .partial_cmp(&y.as_f64().unwrap())
+                .unwrap_or(Ordering::Equal),
+            _ => class(x).cmp(&class(y)),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+fn encode_record(values: &[SqliteValue]) -> Vec<u8> {
+    let mut types = vec![];
+    let mut body = vec![];
+    for value in values {
+        match value {
+            SqliteValue::Null => write_varint(0, &mut types),
+            SqliteValue::Integer(v) => {
+                let v = *v;
+                let (serial_type, size) = if v == 0 {
+                    (8, 0)
+                } else if v == 1 {
+                    (9, 0)
+                } else if v >= -128 && v <= 127 {
+                    (1, 1)
+                } else if v >= -32768 && v <= 32767 {
+                    (2, 2)
+                } else if v >= -8388608 && v <= 8388607 {
+                    (3, 3)
+                } else if v >= -2147483648 && v <= 2147483647 {
+                    (4, 4)
+                } else if v >= -140737488355328 && v <= 140737488355327 {
+                    (5, 6)
+                } else {
+                    (6, 8)
+                };
+                write_varint(serial_type, &mut types);
+                body.extend_from_slice(&v.to_be_bytes()[8 - size..]);
+            }
+            SqliteValue::Real(v) => {
+                write_varint(7, &mut types);
+                body.extend_from_slice(&v.to_bits().to_be_bytes());
+            }
+            SqliteValue::Text(s) => {
+                write_varint(13 + 2 * s.len() as u64, &mut types);
+                body.extend_from_slice(s.as_bytes());
+            }
+            SqliteValue::Blob(b) => {
+                write_varint(12 + 2 * b.len() as u64, &mut types);
+                body.extend_from_slice(b);
+            }
+        }
+    }
+    // the header size includes its own varint
+    let mut header_size = types.len() + 1;
+    while varint_len(header_size as u64) + types.len() != header_size {
+        header_size = types.len() + varint_len(header_size as u64);
+    }
+    let mut record = vec![];
+    write_varint(header_size as u64, &mut record);
+    record.extend_from_slice(&types);
+    record.extend_from_slice(&body);
+    record
+}
+
+/// Returns the number of bytes of a table b-tree leaf cell payload that are stored on the
+/// b-tree page, with the remainder stored on overflow pages.
+fn local_payload_size(usable: usize, payload_size: usize) -> usize {
+    let max_local = usable - 35;
+    if payload_size <= max_local {
+        return payload_size;
+    }
+    let min_local = (usable - 12) * 32 / 255 - 23;
+    let k = min_local + (payload_size - min_local) % (usable - 4);
+    if k <= max_local {
+        k
+    } else {
+        min_local
+    }
+}
+
+fn serial_type_size(serial_type: u64) -> usize {
+    match serial_type {
+        0 | 8 | 9 | 10 | 11 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 3,
+        4 => 4,
+        5 => 6,
+        6 | 7 => 8,
+        n if n % 2 == 0 => ((n - 12) / 2) as usize,
+        n => ((n - 13) / 2) as usize,
+    }
+}
+
+fn read_varint(b: &[u8], pos: usize) -> (u64, usize) {
+    let mut v = 0u64;
+    for i in 0..8 {
+        let c = b[pos + i];
+        v = (v << 7) | (c & 0x7f) as u64;
+        if c & 0x80 == 0 {
+            return (v, i + 1);
+        }
+    }
+    ((v << 8) | b[pos + 8] as u64, 9)
+}
+
+/// Returns the `len` bytes of `b` starting at `pos`, or an error if they extend past its end,
+/// as they do in a truncated or corrupt file.
+fn checked_slice(b: &[u8], pos: usize, len: usize) -> Result<&[u8], Error> {
+    b.get(pos..pos.saturating_add(len)).ok_or(Error::new(
+        ErrorKind::InvalidData,
+        "The database file is truncated or corrupt.",
+    ))
+}
+
+/// Reads a varint, returning an error rather than panicking if it extends past the end of `b`.
+fn checked_varint(b: &[u8], pos: usize) -> Result<(u64, usize), Error> {
+    let bytes = b
+        .get(pos..b.len().min(pos.saturating_add(9)))
+        .unwrap_or(&[]);
+    if bytes.len() == 9 || bytes.iter().any(|c| c & 0x80 == 0) {
+        Ok(read_varint(bytes, 0))
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            "The database file is truncated or corrupt.",
+        ))
+    }
+}
+
+fn varint_len(v: u64) -> usize {
+    let mut buf = vec![];
+    write_varint(v, &mut buf);
+    buf.len()
+}
+
+fn write_varint(v: u64, out: &mut Vec<u8>) {
+    if v & 0xff00_0000_0000_0000 != 0 {
+        let mut buf = [0u8; 9];
+        buf[8] = v as u8;
+        let mut x = v >> 8;
+        for i in (0..8).rev() {
+            buf[i] = (x & 0x7f) as u8 | 0x80;
+            x >>= 7;
+        }
+        out.extend_from_slice(&buf);
+        return;
+    }
+    let mut buf = vec![];
+    let mut x = v;
+    loop {
+        buf.push((x & 0x7f) as u8 | 0x80);
+        x >>= 7;
+        if x == 0 {
+            break;
+        }
+    }
+    buf[0] &= 0x7f;
+    buf.reverse();
+    out.extend_from_slice(&buf);
+}
+
+fn read_u16(b: &[u8], pos: usize) -> u16 {
+    u16::from_be_bytes([b[pos], b[pos + 1]])
+}
+
+fn read_u32(b: &[u8], pos: usize) -> u32 {
+    u32::from_be_bytes([b[pos], b[pos + 1], b[pos + 2], b[pos + 3]])
+}
+
+fn read_u64(b: &[u8], pos: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&b[pos..pos + 8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Splits the body of a CREATE TABLE statement into its column and constraint definitions.
+fn split_definitions(sql: &str) -> Vec<String> {
+    let start = match sql.find('(') {
+        Some(i) => i + 1,
+        None => return vec![],
+    };
+    let end = sql.rfind(')').unwrap_or(sql.len()).max(start);
+    // split the definitions at top-level commas
+    let mut definitions = vec![];
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut current = String::new();
+    for c in sql[start..end].chars() {
+        match quote {
+            Some(q) => {
+                if c == q || (q == '[' && c == ']') {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' | '`' | '[' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    definitions.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+    }
+    definitions.push(current.trim().to_string());
+    definitions
+}
+
+/// Returns true if a definition is a table constraint rather than a column definition.
+fn is_table_constraint(def: &str) -> bool {
+    let upper_name = split_identifier(def).0.to_uppercase();
+    ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"].contains(&upper_name.as_str())
+        && !def.starts_with(|c| c == '"' || c == '[' || c == '`')
+}
+
+/// Parses the column definitions of a CREATE TABLE statement.
+fn parse_columns(sql: &str) -> Vec<SqliteColumn> {
+    let definitions = split_definitions(sql);
+    let mut columns = vec![];
+    let mut table_pk = None;
+    for def in definitions {
+        let (name, rest) = split_identifier(&def);
+        if is_table_constraint(&def) {
+            let upper = def.to_uppercase();
+            if let Some(i) = upper.find("PRIMARY KEY") {
+                let pk = def[i + 11..]
+                    .trim()
+                    .trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .to_string();
+                if !pk.contains(',') {
+                    table_pk = Some(split_identifier(pk.trim()).0);
+                }
+            }
+            continue;
+        }
+        if name.is_empty() {
+            continue;
+        }
+        let upper_rest = rest.to_uppercase();
+        let keywords = [
+            " CONSTRAINT",
+            " PRIMARY",
+            " NOT",
+            " NULL",
+            " UNIQUE",
+            " CHECK",
+            " DEFAULT",
+            " COLLATE",
+            " REFERENCES",
+            " GENERATED",
+            " AS",
+        ];
+        let padded = format!(" {}", upper_rest);
+        let type_end = keywords
+            .iter()
+            .filter_map(|k| {
+                padded.find(&format!("{} ", k)).or(if padded.ends_with(k) {
+                    Some(padded.len() - k.len())
+                } else {
+                    None
+                })
+            })
+            .min()
+            .unwrap_or(padded.len());
+        let declared_type = padded[..type_end].trim().to_string();
+        let is_rowid_alias = declared_type.eq_ignore_ascii_case("INTEGER")
+            && upper_rest.contains("PRIMARY KEY")
+            && !upper_rest.contains("PRIMARY KEY DESC");
+        columns.push(SqliteColumn {
+            name: name,
+            declared_type: declared_type,
+            is_rowid_alias: is_rowid_alias,
+        });
+    }
+    if let Some(pk) = table_pk {
+        for column in columns.iter_mut() {
+            if column.name.eq_ignore_ascii_case(&pk)
+                && column.declared_type.eq_ignore_ascii_case("INTEGER")
+            {
+                column.is_rowid_alias = true;
+            }
+        }
+    }
+    columns
+}
+
+/// Returns the column indices of the PRIMARY KEY and UNIQUE constraints of a CREATE TABLE
+/// statement that require automatic indexes, in order of declaration.
+fn parse_unique_constraints(sql: &str, columns: &[SqliteColumn]) -> Vec<Vec<usize>> {
+    let find_column = |name: &str| {
+        columns
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+    };
+    let mut constraints: Vec<Vec<usize>> = vec![];
+    for def in split_definitions(sql) {
+        let upper = def.to_uppercase();
+        if is_table_constraint(&def) {
+            let i = match upper.find("PRIMARY KEY").or(upper.find("UNIQUE")) {
+                Some(i) => i,
+                None => continue,
+            };
+            let list = match (def[i..].find('('), def[i..].find(')')) {
+                (Some(a), Some(b)) if a < b => &def[i + a + 1..i + b],
+                _ => continue,
+            };
+            let key = list
+                .split(',')
+                .filter_map(|c| find_column(&split_identifier(c).0))
+                .collect::<Vec<usize>>();
+            if !key.is_empty() && !(key.len() == 1 && columns[key[0]].is_rowid_alias) {
+                constraints.push(key);
+            }
+        } else if let Some(c) = find_column(&split_identifier(&def).0) {
+            let rest = split_identifier(&def).1.to_uppercase();
+            let words = rest
+                .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                .collect::<Vec<&str>>();
+            let is_pk = words.windows(2).any(|w| w == ["PRIMARY", "KEY"]);
+            if (is_pk && !columns[c].is_rowid_alias) || words.contains(&"UNIQUE") {
+                constraints.push(vec![c]);
+            }
+        }
+    }
+    constraints.dedup();
+    constraints
+}
+
+/// Splits the leading identifier, which may be quoted, from the rest of a definition.
+fn split_identifier(s: &str) -> (String, String) {
+    let s = s.trim();
+    let close = match s.chars().next() {
+        Some('"') => Some('"'),
+        Some('`') => Some('`'),
+        Some('[') => Some(']'),
+        Some('\'') => Some('\''),
+        _ => None,
+    };
+    match close {
+        Some(q) => match s[1..].find(q) {
+            Some(i) => (s[1..i + 1].to_string(), s[i + 2..].to_string()),
+            None => (s[1..].to_string(), String::new()),
+        },
+        None => {
+            let i = s.find(char::is_whitespace).unwrap_or(s.len());
+            (s[..i].to_string(), s[i..].to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{write_sqlite_database, SqliteDatabase, SqliteTable, SqliteValue};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_sqlite_round_trip() {
+        let mut small = SqliteTable::new(
+            "small",
+            "CREATE TABLE small (id INTEGER PRIMARY KEY AUTOINCREMENT, \"the name\" TEXT NOT NULL, value DOUBLE)",
+        );
+        small.rows.push(vec![
+            SqliteValue::Integer(5),
+            SqliteValue::Text("Montréal".to_string()),
+            SqliteValue::Real(1.5),
+        ]);
+        small.rows.push(vec![
+            SqliteValue::Integer(9),
+            SqliteValue::Text("b".to_string()),
+            SqliteValue::Null,
+        ]);
+        // enough rows, and large enough blobs, to need interior and overflow pages
+        let mut large = SqliteTable::new("large", "CREATE TABLE large (n INTEGER, data BLOB)");
+        for i in 0..3000i64 {
+            let size = if i % 500 == 0 { 10000 } else { 20 };
+            large.rows.push(vec![
+                SqliteValue::Integer(i * 1000000007),
+                SqliteValue::Blob(vec![(i % 251) as u8; size]),
+            ]);
+        }
+        let file_name = env::temp_dir()
+            .join("wbt_sqlite_round_trip.sqlite")
+            .to_string_lossy()
+            .to_string();
+        write_sqlite_database(
+            &file_name,
+            &[small.clone(), large.clone()],
+            0x47504B47,
+            10200,
+        )
+        .unwrap();
+        let db = SqliteDatabase::open(&file_name).unwrap();
+        let _ = fs::remove_file(&file_name);
+        assert_eq!(db.application_id(), 0x47504B47);
+
+        let t = db.read_table("SMALL").unwrap();
+        assert_eq!(t.columns[1].name, "the name");
+        assert_eq!(t.columns[2].declared_type, "DOUBLE");
+        assert!(t.columns[0].is_rowid_alias);
+        assert_eq!(t.rows, small.rows);
+        assert_eq!(t.get(0, "the name").as_str(), Some("Montréal"));
+
+        let t = db.read_table("large").unwrap();
+        assert_eq!(t.rows.len(), 3000);
+        assert_eq!(t.rows, large.rows);
+    }
+
+    #[test]
+    fn test_sqlite_truncated_and_wal() {
+        let mut table = SqliteTable::new("t", "CREATE TABLE t (n INTEGER, data BLOB)");
+        for i in 0..500i64 {
+            table.rows.push(vec![
+                SqliteValue::Integer(i),
+                SqliteValue::Blob(vec![7u8; 5000]),
+            ]);
+        }
+        let file_name = env::temp_dir()
+            .join("wbt_sqlite_truncated.sqlite")
+            .to_string_lossy()
+            .to_string();
+        write_sqlite_database(&file_name, &[table], 0, 0).unwrap();
+        let buffer = fs::read(&file_name).unwrap();
+        let _ = fs::remove_file(&file_name);
+
+        // cutting the file anywhere, including part way through a page, must be an error
+        for &len in &[
+            50,
+            100,
+            4000,
+            4096,
+            10000,
+            buffer.len() / 2,
+            buffer.len() - 1,
+        ] {
+            let result = SqliteDatabase::from_bytes(buffer[..len].to_vec())
+                .and_then(|db| db.read_table("t").map(|_| ()));
+            assert!(result.is_err(), "truncated to {} bytes", len);
+        }
+        assert!(SqliteDatabase::from_bytes(buffer.clone())
+            .unwrap()
+            .read_table("t")
+            .is_ok());
+
+        // a corrupt cell count sends the cell pointers past the end of the page
+        let mut corrupt = buffer.clone();
+        corrupt[103] = 0xff;
+        corrupt[104] = 0xff;
+        assert!(SqliteDatabase::from_bytes(corrupt).is_err());
+
+        let mut wal = buffer.clone();
+        wal[18] = 2;
+        wal[19] = 2;
+        assert!(SqliteDatabase::from_bytes(wal).is_err());
+    }
+}
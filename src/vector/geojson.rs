@@ -0,0 +1,675 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: Reading and writing GeoJSON (RFC 7946) files using the in-memory Shapefile data model.
+*/
+
+use super::shapefile::attributes::*;
+use super::shapefile::geometry::*;
+use super::simple_features::*;
+use super::Vector;
+use crate::algorithms::is_clockwise_order;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use crate::structures::Point2D;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+
+/// Returns true if the file name has a GeoJSON extension (.geojson or .json).
+pub fn is_geojson_file(file_name: &str) -> bool {
+    let f = file_name.to_lowercase();
+    f.ends_with(".geojson") || f.ends_with(".json")
+}
+
+/// Reads a GeoJSON file into a Vector. Features are mapped to records of a single
+/// ShapeType, i.e. Point (MultiPoint if any feature contains more than one point),
+/// LineString and MultiLineString (PolyLine), and Polygon and MultiPolygon (Polygon),
+/// with the Z variants used if any coordinates have an elevation. Polygon rings are
+/// re-ordered to the Shapefile convention of clockwise exteriors and counter-clockwise
+/// holes. Feature properties are mapped to attribute fields in order of appearance.
+///
+/// The coordinate reference system is WGS84 (EPSG:4326) unless the file contains a
+/// legacy `crs` member naming an EPSG code.
+pub fn read_geojson(sf: &mut Vector) -> Result<(), Error> {
+    let bytes = fs::read(&sf.file_name)?;
+    read_geojson_bytes(sf, &bytes).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("Error reading GeoJSON file {}: {}", sf.file_name, e),
+        )
+    })
+}
+
+/// Writes a Vector to a GeoJSON FeatureCollection. Polygon rings follow the RFC 7946
+/// right-hand rule (counter-clockwise exteriors and clockwise holes). Coordinates are
+/// not transformed; if the projection is not WGS84 and contains an EPSG authority code,
+/// it is recorded in the legacy `crs` member.
+pub fn write_geojson(sf: &Vector) -> Result<(), Error> {
+    let f = File::create(&sf.file_name)?;
+    let mut writer = BufWriter::new(f);
+    write_geojson_features(sf, &mut writer)
+}
+
+fn read_geojson_bytes(sf: &mut Vector, bytes: &[u8]) -> Result<(), Error> {
+    // serde_json is built with the preserve_order feature, so that object members are kept
+    // in their file order, which is used for the order of the attribute fields, and with
+    // arbitrary_precision, so that numbers keep their text representation, which is used to
+    // infer field widths and is parsed exactly.
+    let bytes = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        &bytes[3..] // a UTF-8 byte order mark
+    } else {
+        bytes
+    };
+    let root: Value = serde_json::from_slice(bytes)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("invalid JSON ({})", e)))?;
+
+    // gather the (geometry, properties) pairs of the features
+    let features: Vec<(&Value, Option<&Value>)> = match get_str(&root, "type") {
+        Some("FeatureCollection") => match root.get("features") {
+            Some(Value::Array(features)) => features
+                .iter()
+                .map(|f| {
+                    (
+                        f.get("geometry").unwrap_or(&Value::Null),
+                        f.get("properties"),
+                    )
+                })
+                .collect(),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The FeatureCollection does not contain a features array.",
+                ))
+            }
+        },
+        Some("Feature") => vec![(
+            root.get("geometry").unwrap_or(&Value::Null),
+            root.get("properties"),
+        )],
+        Some(_) => vec![(&root, None)], // a bare geometry
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The root object does not have a type member.",
+            ))
+        }
+    };
+
+    // read the geometries and find the ShapeType
+    let mut geometries = Vec::with_capacity(features.len());
+    let mut has_z = false;
+    for (geometry, _) in &features {
+        let mut fg = FeatureGeometry::default();
+        if !geometry.is_null() {
+            read_geometry(geometry, &mut fg, &mut has_z)?;
+        }
+        geometries.push(fg);
+    }
+    let shape_type = find_shape_type(&geometries, has_z, false)?;
+    sf.header.shape_type = shape_type;
+    sf.header.version = 1000;
+
+    for fg in geometries {
+        sf.records.push(to_shapefile_geometry(fg, shape_type));
+    }
+    sf.num_records = sf.records.len();
+    sf.calculate_extent();
+
+    // read the attributes
+    let mut fields: Vec<FieldInfo> = vec![];
+    let mut field_map = HashMap::new();
+    for (_, properties) in &features {
+        if let Some(Value::Object(members)) = properties {
+            for (name, value) in members {
+                let index = *field_map.entry(name.clone()).or_insert_with(|| {
+                    fields.push(FieldInfo::new(name));
+                    fields.len() - 1
+                });
+                fields[index].update(value);
+            }
+        }
+    }
+    let attribute_fields = fields
+        .iter()
+        .map(|f| f.to_attribute_field())
+        .collect::<Vec<AttributeField>>();
+    sf.attributes.add_fields(&attribute_fields);
+    for (_, properties) in &features {
+        let mut rec = vec![FieldData::Null; fields.len()];
+        if let Some(Value::Object(members)) = properties {
+            for (name, value) in members {
+                let index = field_map[name];
+                rec[index] = fields[index].to_field_data(value);
+            }
+        }
+        sf.attributes.add_record(rec, false);
+    }
+
+    sf.projection = match root.get("crs").and_then(read_crs) {
+        Some(code) => projection_from_epsg(code),
+        None => esri_wkt_from_epsg(4326),
+    };
+
+    Ok(())
+}
+
+fn write_geojson_features<W: Write>(sf: &Vector, writer: &mut W) -> Result<(), Error> {
+    writer.write_all(b"{\"type\":\"FeatureCollection\",")?;
+    if let Some(code) = find_epsg_code(&sf.projection) {
+        if code != 4326 {
+            writer.write_all(
+                format!(
+                    "\"crs\":{{\"type\":\"name\",\"properties\":{{\"name\":\"urn:ogc:def:crs:EPSG::{}\"}}}},",
+                    code
+                )
+                .as_bytes(),
+            )?;
+        }
+    }
+    writer.write_all(b"\"features\":[\n")?;
+    let fields = sf.attributes.get_fields();
+    for i in 0..sf.records.len() {
+        let mut s = String::from("{\"type\":\"Feature\",\"properties\":{");
+        if i < sf.attributes.header.num_records as usize {
+            let rec = sf.attributes.get_record(i);
+            for (j, field) in fields.iter().enumerate() {
+                if j > 0 {
+                    s.push(',');
+                }
+                push_json_string(&mut s, &field.name);
+                s.push(':');
+                match rec.get(j) {
+                    Some(FieldData::Int(v)) => s.push_str(&v.to_string()),
+                    Some(FieldData::Real(v)) => push_json_number(&mut s, *v),
+                    Some(FieldData::Text(v)) => push_json_string(&mut s, v),
+                    Some(FieldData::Date(v)) => push_json_string(
+                        &mut s,
+                        &format!("{:04}-{:02}-{:02}", v.year, v.month, v.day),
+                    ),
+                    Some(FieldData::Bool(v)) => s.push_str(if *v { "true" } else { "false" }),
+                    Some(FieldData::Null) | None => s.push_str("null"),
+                }
+            }
+        }
+        s.push_str("},\"geometry\":");
+        push_geometry(&mut s, &sf.records[i]);
+        s.push('}');
+        if i < sf.records.len() - 1 {
+            s.push(',');
+        }
+        s.push('\n');
+        writer.write_all(s.as_bytes())?;
+    }
+    writer.write_all(b"]}\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_geometry(
+    geometry: &Value,
+    fg: &mut FeatureGeometry,
+    has_z: &mut bool,
+) -> Result<(), Error> {
+    let geometry_type = get_str(geometry, "type").ok_or(Error::new(
+        ErrorKind::InvalidData,
+        "A geometry does not have a type member.",
+    ))?;
+    if geometry_type == "GeometryCollection" {
+        if let Some(Value::Array(geometries)) = geometry.get("geometries") {
+            for g in geometries {
+                read_geometry(g, fg, has_z)?;
+            }
+        }
+        return Ok(());
+    }
+    let coordinates = geometry.get("coordinates").unwrap_or(&Value::Null);
+    match geometry_type {
+        "Point" => {
+            if let Some(p) = read_position(coordinates, has_z)? {
+                fg.points.push(p);
+            }
+        }
+        "MultiPoint" => {
+            for p in read_positions(coordinates, has_z)? {
+                fg.points.push(p);
+            }
+        }
+        "LineString" => {
+            let line = read_positions(coordinates, has_z)?;
+            if !line.is_empty() {
+                fg.lines.push(line);
+            }
+        }
+        "MultiLineString" | "Polygon" => {
+            let mut lines = vec![];
+            for c in as_array(coordinates) {
+                let line = read_positions(c, has_z)?;
+                if !line.is_empty() {
+                    lines.push(line);
+                }
+            }
+            if geometry_type == "Polygon" {
+                if !lines.is_empty() {
+                    fg.polygons.push(lines);
+                }
+            } else {
+                fg.lines.extend(lines);
+            }
+        }
+        "MultiPolygon" => {
+            for poly in as_array(coordinates) {
+                let mut rings = vec![];
+                for c in as_array(poly) {
+                    let ring = read_positions(c, has_z)?;
+                    if !ring.is_empty() {
+                        rings.push(ring);
+                    }
+                }
+                if !rings.is_empty() {
+                    fg.polygons.push(rings);
+                }
+            }
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unrecognized geometry type: {}", geometry_type),
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn read_position(value: &Value, has_z: &mut bool) -> Result<Option<[f64; 4]>, Error> {
+    let values = as_array(value);
+    if values.is_empty() {
+        return Ok(None);
+    }
+    let mut p = [0f64, 0f64, f64::NAN, f64::NAN];
+    if values.len() < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "A position contains fewer than two coordinates.",
+        ));
+    }
+    for i in 0..values.len().min(3) {
+        p[i] = values[i].as_f64().ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "A position contains a non-numeric coordinate.",
+        ))?;
+    }
+    if values.len() > 2 {
+        *has_z = true;
+    }
+    Ok(Some(p))
+}
+
+fn read_positions(value: &Value, has_z: &mut bool) -> Result<Vec<[f64; 4]>, Error> {
+    let mut points = vec![];
+    for v in as_array(value) {
+        if let Some(p) = read_position(v, has_z)? {
+            points.push(p);
+        }
+    }
+    Ok(points)
+}
+
+fn push_geometry(s: &mut String, sfg: &ShapefileGeometry) {
+    if sfg.shape_type == ShapeType::Null || sfg.points.is_empty() {
+        s.push_str("null");
+        return;
+    }
+    let has_z = sfg.has_z_data();
+    let num_points = sfg.points.len();
+    match sfg.shape_type.base_shape_type() {
+        ShapeType::Point => {
+            s.push_str("{\"type\":\"Point\",\"coordinates\":");
+            push_position(s, sfg, 0, has_z);
+        }
+        ShapeType::MultiPoint => {
+            s.push_str("{\"type\":\"MultiPoint\",\"coordinates\":");
+            push_positions(s, sfg, 0..num_points, has_z, false);
+        }
+        ShapeType::PolyLine => {
+            if sfg.parts.len() == 1 {
+                s.push_str("{\"type\":\"LineString\",\"coordinates\":");
+                push_positions(s, sfg, 0..num_points, has_z, false);
+            } else {
+                s.push_str("{\"type\":\"MultiLineString\",\"coordinates\":[");
+                for part in 0..sfg.parts.len() {
+                    if part > 0 {
+                        s.push(',');
+                    }
+                    push_positions(s, sfg, part_range(sfg, part), has_z, false);
+                }
+                s.push(']');
+            }
+        }
+        _ => {
+            let rings = (0..sfg.parts.len())
+                .map(|part| sfg.points[part_range(sfg, part)].to_vec())
+                .collect::<Vec<Vec<Point2D>>>();
+            let polygons = group_polygon_rings(sfg);
+            if polygons.len() == 1 {
+                s.push_str("{\"type\":\"Polygon\",\"coordinates\":");
+            } else {
+                s.push_str("{\"type\":\"MultiPolygon\",\"coordinates\":[");
+            }
+            for (k, polygon) in polygons.iter().enumerate() {
+                if k > 0 {
+                    s.push(',');
+                }
+                s.push('[');
+                for (r, part) in polygon.iter().enumerate() {
+                    if r > 0 {
+                        s.push(',');
+                    }
+                    // the right-hand rule: exteriors are counter-clockwise and holes are clockwise
+                    let reverse = is_clockwise_order(&rings[*part]) == (r == 0);
+                    push_positions(s, sfg, part_range(sfg, *part), has_z, reverse);
+                }
+                s.push(']');
+            }
+            if polygons.len() > 1 {
+                s.push(']');
+            }
+        }
+    }
+    s.push('}');
+}
+
+fn push_position(s: &mut String, sfg: &ShapefileGeometry, i: usize, has_z: bool) {
+    s.push('[');
+    push_json_number(s, sfg.points[i].x);
+    s.push(',');
+    push_json_number(s, sfg.points[i].y);
+    if has_z && i < sfg.z_array.len() {
+        s.push(',');
+        push_json_number(s, sfg.z_array[i]);
+    }
+    s.push(']');
+}
+
+fn push_positions(
+    s: &mut String,
+    sfg: &ShapefileGeometry,
+    range: std::ops::Range<usize>,
+    has_z: bool,
+    reverse: bool,
+) {
+    s.push('[');
+    let indices: Vec<usize> = if reverse {
+        range.rev().collect()
+    } else {
+        range.collect()
+    };
+    for (k, i) in indices.into_iter().enumerate() {
+        if k > 0 {
+            s.push(',');
+        }
+        push_position(s, sfg, i, has_z);
+    }
+    s.push(']');
+}
+
+fn push_json_number(s: &mut String, v: f64) {
+    if v.is_finite() {
+        s.push_str(&v.to_string());
+    } else {
+        s.push_str("null");
+    }
+}
+
+fn push_json_string(s: &mut String, v: &str) {
+    s.push('"');
+    for c in v.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => s.push_str(&format!("\\u{:04x}", c as u32)),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
+
+/// Reads an EPSG code from a legacy GeoJSON crs member, e.g. a named CRS such as
+/// `urn:ogc:def:crs:EPSG::26917` or `EPSG:26917`, or an `EPSG` type with a code property.
+fn read_crs(crs: &Value) -> Option<u16> {
+    let properties = crs.get("properties")?;
+    if let Some(name) = get_str(properties, "name") {
+        if name.ends_with("CRS84") {
+            return Some(4326);
+        }
+        return name.rsplit(':').next()?.trim().parse::<u16>().ok();
+    }
+    properties
+        .get("code")
+        .and_then(|c| c.as_f64())
+        .map(|c| c as u16)
+}
+
+/// The attribute field type inferred from the values of a property.
+#[derive(Clone, Copy, PartialEq)]
+enum FieldKind {
+    Unknown,
+    Int,
+    Real,
+    Bool,
+    Text,
+}
+
+struct FieldInfo {
+    name: String,
+    kind: FieldKind,
+    int_digits: usize,
+    decimals: usize,
+    text_length: usize,
+}
+
+impl FieldInfo {
+    fn new(name: &str) -> FieldInfo {
+        FieldInfo {
+            name: name.to_string(),
+            kind: FieldKind::Unknown,
+            int_digits: 1,
+            decimals: 0,
+            text_length: 1,
+        }
+    }
+
+    fn update(&mut self, value: &Value) {
+        let kind = match value {
+            Value::Null => return,
+            Value::Bool(_) => FieldKind::Bool,
+            Value::Number(n) => {
+                let n = &n.to_string();
+                let mantissa = n.split(|c| c == 'e' || c == 'E').next().unwrap_or(n);
+                let mut parts = mantissa.splitn(2, '.');
+                let int_part = parts.next().unwrap_or("");
+                let frac_part = parts.next().unwrap_or("");
+                self.int_digits = self.int_digits.max(int_part.len());
+                self.decimals = self.decimals.max(frac_part.len());
+                let is_int =
+                    frac_part.is_empty() && mantissa.len() == n.len() && n.parse::<i32>().is_ok();
+                if is_int {
+                    FieldKind::Int
+                } else {
+                    FieldKind::Real
+                }
+            }
+            _ => FieldKind::Text,
+        };
+        self.text_length = self.text_length.max(text_value(value).len()).min(254);
+        self.kind = match (self.kind, kind) {
+            (FieldKind::Unknown, k) => k,
+            (a, b) if a == b => a,
+            (FieldKind::Int, FieldKind::Real) | (FieldKind::Real, FieldKind::Int) => {
+                FieldKind::Real
+            }
+            _ => FieldKind::Text,
+        };
+    }
+
+    fn to_attribute_field(&self) -> AttributeField {
+        match self.kind {
+            FieldKind::Int => AttributeField::new(
+                &self.name,
+                FieldDataType::Int,
+                self.int_digits.max(1) as u8,
+                0u8,
+            ),
+            FieldKind::Real => {
+                let decimals = self.decimals.max(1).min(15);
+                let width = (self.int_digits + 1 + decimals).min(255);
+                AttributeField::new(&self.name, FieldDataType::Real, width as u8, decimals as u8)
+            }
+            FieldKind::Bool => AttributeField::new(&self.name, FieldDataType::Bool, 1u8, 0u8),
+            _ => AttributeField::new(&self.name, FieldDataType::Text, self.text_length as u8, 0u8),
+        }
+    }
+
+    fn to_field_data(&self, value: &Value) -> FieldData {
+        if value.is_null() {
+            return FieldData::Null;
+        }
+        match self.kind {
+            FieldKind::Int => match value.as_f64() {
+                Some(v) => FieldData::Int(v as i32),
+                None => FieldData::Null,
+            },
+            FieldKind::Real => match value.as_f64() {
+                Some(v) => FieldData::Real(v),
+                None => FieldData::Null,
+            },
+            FieldKind::Bool => FieldData::Bool(value.as_bool() == Some(true)),
+            _ => FieldData::Text(text_value(value)),
+        }
+    }
+}
+
+fn text_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Returns the elements of a JSON array, or an empty slice for any other value.
+fn as_array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(values) => values,
+        _ => &[],
+    }
+}
+
+fn get_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_geojson_bytes, write_geojson_features};
+    use crate::vector::*;
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let text = r#"{
+            "type": "FeatureCollection",
+            "crs": { "type": "name", "properties": { "name": "urn:ogc:def:crs:EPSG::26917" } },
+            "features": [
+                { "type": "Feature",
+                  "properties": { "name": "A \"quoted\" name", "count": 3, "area": 2.5, "ok": true },
+                  "geometry": { "type": "Polygon", "coordinates": [
+                      [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                      [[2, 2], [2, 4], [4, 4], [4, 2], [2, 2]] ] } },
+                { "type": "Feature",
+                  "properties": { "name": "B", "count": 7, "area": 12, "ok": null },
+                  "geometry": { "type": "MultiPolygon", "coordinates": [
+                      [[[20, 0], [30, 0], [30, 10], [20, 0]]],
+                      [[[40, 0], [50, 0], [50, 10], [40, 0]]] ] } },
+                { "type": "Feature", "properties": { "name": "C" }, "geometry": null }
+            ]
+        }"#;
+        let mut sf = Vector::default();
+        read_geojson_bytes(&mut sf, text.as_bytes()).unwrap();
+        assert_eq!(sf.header.shape_type, ShapeType::Polygon);
+        assert_eq!(sf.num_records, 3);
+        assert_eq!(sf.records[0].num_parts, 2);
+        assert!(!sf.records[0].is_hole(0));
+        assert!(sf.records[0].is_hole(1));
+        assert_eq!(sf.records[1].num_parts, 2);
+        assert_eq!(sf.records[2].shape_type, ShapeType::Null);
+        assert!(sf.projection.contains("26917"));
+
+        // fields are kept in order of appearance, with inferred types
+        let names = sf
+            .attributes
+            .get_fields()
+            .iter()
+            .map(|f| f.name.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["name", "count", "area", "ok"]);
+        assert_eq!(sf.attributes.get_field(1).field_type, 'N');
+        assert_eq!(sf.attributes.get_field(2).field_type, 'F');
+        assert_eq!(sf.attributes.get_field(3).field_type, 'L');
+        assert_eq!(
+            sf.attributes.get_value(0, "name"),
+            FieldData::Text("A \"quoted\" name".to_string())
+        );
+        assert_eq!(sf.attributes.get_value(1, "area"), FieldData::Real(12f64));
+        assert_eq!(sf.attributes.get_value(1, "ok"), FieldData::Null);
+        assert_eq!(sf.attributes.get_value(2, "count"), FieldData::Null);
+
+        // writing and re-reading gives the same geometries and attributes
+        let mut buf = vec![];
+        write_geojson_features(&sf, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("urn:ogc:def:crs:EPSG::26917"));
+        // the exterior ring follows the right-hand rule
+        assert!(
+            text.contains("[[[0,0],[10,0],[10,10],[0,10],[0,0]],[[2,2],[2,4],[4,4],[4,2],[2,2]]]")
+        );
+        assert!(text.contains("\"MultiPolygon\""));
+        let mut sf2 = Vector::default();
+        read_geojson_bytes(&mut sf2, text.as_bytes()).unwrap();
+        assert_eq!(sf2.num_records, 3);
+        for i in 0..3 {
+            assert_eq!(sf2.records[i].points, sf.records[i].points);
+            assert_eq!(sf2.attributes.get_record(i), sf.attributes.get_record(i));
+        }
+    }
+
+    #[test]
+    fn test_geojson_string_escapes() {
+        // with a leading byte order mark
+        let feature = |name: &str| -> String {
+            format!(
+                "\u{feff}{}",
+                r#"{"type":"Feature","properties":{"name":"NAME"},"geometry":{"type":"Point","coordinates":[1,2]}}"#
+                    .replace("NAME", name)
+            )
+        };
+        let mut sf = Vector::default();
+        read_geojson_bytes(&mut sf, feature("caf\\u00e9 \\ud83d\\ude00").as_bytes()).unwrap();
+        assert_eq!(
+            sf.attributes.get_value(0, "name"),
+            FieldData::Text("caf\u{e9} \u{1f600}".to_string())
+        );
+        // a lone surrogate, even when followed by another escape, is an error rather than a panic
+        let mut sf = Vector::default();
+        assert!(read_geojson_bytes(&mut sf, feature("\\ud800\\u0041").as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,1054 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: Reading and writing the feature tables of OGC GeoPackage (.gpkg) files using the
+in-memory Shapefile data model. A layer of a multi-layer GeoPackage is selected by appending
+its table name to the file name, e.g. `data.gpkg:roads`; without a layer name, the first
+feature table is used.
+*/
+
+use super::shapefile::attributes::*;
+use super::shapefile::geometry::*;
+use super::simple_features::*;
+use super::Vector;
+use crate::algorithms::is_clockwise_order;
+use crate::utils::{write_sqlite_database, SqliteDatabase, SqliteTable, SqliteValue};
+use chrono::prelude::*;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// The SQLite application ID of a GeoPackage ('GPKG').
+pub const GPKG_APPLICATION_ID: u32 = 0x4750_4B47;
+
+/// The SQLite user version of a version 1.2 GeoPackage.
+pub const GPKG_USER_VERSION: u32 = 10200;
+
+const SRS_TABLE_SQL: &str = "CREATE TABLE gpkg_spatial_ref_sys (srs_name TEXT NOT NULL, srs_id INTEGER PRIMARY KEY, organization TEXT NOT NULL, organization_coordsys_id INTEGER NOT NULL, definition TEXT NOT NULL, description TEXT)";
+
+const CONTENTS_TABLE_SQL: &str = "CREATE TABLE gpkg_contents (table_name TEXT NOT NULL PRIMARY KEY, data_type TEXT NOT NULL, identifier TEXT UNIQUE, description TEXT DEFAULT '', last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')), min_x DOUBLE, min_y DOUBLE, max_x DOUBLE, max_y DOUBLE, srs_id INTEGER, CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id))";
+
+const GEOMETRY_COLUMNS_TABLE_SQL: &str = "CREATE TABLE gpkg_geometry_columns (table_name TEXT NOT NULL, column_name TEXT NOT NULL, geometry_type_name TEXT NOT NULL, srs_id INTEGER NOT NULL, z TINYINT NOT NULL, m TINYINT NOT NULL, CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name), CONSTRAINT uk_gc_table_name UNIQUE (table_name), CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name), CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id))";
+
+const SEQUENCE_TABLE_SQL: &str = "CREATE TABLE sqlite_sequence(name,seq)";
+
+const WGS84_WKT: &str = "GEOGCS[\"WGS 84\",DATUM[\"WGS_84\",SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0,AUTHORITY[\"EPSG\",\"8901\"]],UNIT[\"degree\",0.0174532925199433,AUTHORITY[\"EPSG\",\"9122\"]],AUTHORITY[\"EPSG\",\"4326\"]]";
+
+/// Returns true if the file name refers to a GeoPackage (.gpkg), optionally with a layer
+/// name, e.g. `data.gpkg:roads`.
+pub fn is_geopackage_file(file_name: &str) -> bool {
+    let f = file_name.to_lowercase();
+    f.ends_with(".gpkg") || f.contains(".gpkg:")
+}
+
+/// Splits a GeoPackage file name of the form `data.gpkg:layer` into the path of the file
+/// and the layer name, if there is one.
+pub fn split_layer(file_name: &str) -> (String, Option<String>) {
+    match file_name.to_lowercase().rfind(".gpkg:") {
+        Some(i) => {
+            let layer = &file_name[i + 6..];
+            let layer = if layer.is_empty() {
+                None
+            } else {
+                Some(layer.to_string())
+            };
+            (file_name[..i + 5].to_string(), layer)
+        }
+        None => (file_name.to_string(), None),
+    }
+}
+
+/// Returns the names of the feature tables (layers) of a GeoPackage.
+pub fn feature_layers(file_name: &str) -> Result<Vec<String>, Error> {
+    let db = SqliteDatabase::open(&split_layer(file_name).0)?;
+    Ok(contents_layers(
+        &db.read_table("gpkg_contents")?,
+        "features",
+    ))
+}
+
+fn contents_layers(contents: &SqliteTable, data_type: &str) -> Vec<String> {
+    (0..contents.rows.len())
+        .filter(|r| contents.get(*r, "data_type").as_str() == Some(data_type))
+        .filter_map(|r| {
+            contents
+                .get(r, "table_name")
+                .as_str()
+                .map(|s| s.to_string())
+        })
+        .collect()
+}
+
+/// Reads a feature table of a GeoPackage into a Vector. Geometries in the GeoPackage
+/// binary format are mapped to records of a single ShapeType, with polygon rings re-ordered
+/// to the Shapefile convention of clockwise exteriors and counter-clockwise holes. Columns
+/// other than the primary key and geometry are mapped to attribute fields by their declared
+/// types; BLOB columns are not read.
+pub fn read_geopackage(sf: &mut Vector) -> Result<(), Error> {
+    let (path, layer) = split_layer(&sf.file_name);
+    let db = SqliteDatabase::open(&path)?;
+    read_features(sf, &db, layer).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("Error reading GeoPackage {}: {}", sf.file_name, e),
+        )
+    })
+}
+
+fn read_features(sf: &mut Vector, db: &SqliteDatabase, layer: Option<String>) -> Result<(), Error> {
+    let layers = contents_layers(&db.read_table("gpkg_contents")?, "features");
+    let table_name = match layer {
+        Some(l) => layers
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(&l))
+            .cloned()
+            .ok_or(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "There is no feature layer named '{}'. The feature layers are: {}",
+                    l,
+                    layers.join(", ")
+                ),
+            ))?,
+        None => layers.first().cloned().ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "The file does not contain any feature layers.",
+        ))?,
+    };
+
+    let geometry_columns = db.read_table("gpkg_geometry_columns")?;
+    let row = (0..geometry_columns.rows.len())
+        .find(|r| {
+            geometry_columns
+                .get(*r, "table_name")
+                .as_str()
+                .map_or(false, |t| t.eq_ignore_ascii_case(&table_name))
+        })
+        .ok_or(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The layer '{}' does not have a geometry column.",
+                table_name
+            ),
+        ))?;
+    let geometry_column = geometry_columns
+        .get(row, "column_name")
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+    let srs_id = geometry_columns.get(row, "srs_id").as_i64().unwrap_or(-1);
+    sf.projection = read_projection(db, srs_id)?;
+
+    let table = db.read_table(&table_name)?;
+    let geometry_index = table.column_index(&geometry_column).ok_or(Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "The layer '{}' does not contain the column '{}'.",
+            table_name, geometry_column
+        ),
+    ))?;
+
+    // read the geometries and find the ShapeType
+    let mut geometries = Vec::with_capacity(table.rows.len());
+    let mut has_z = false;
+    let mut has_m = false;
+    for row in &table.rows {
+        let mut fg = FeatureGeometry::default();
+        if let SqliteValue::Blob(b) = &row[geometry_index] {
+            read_gpkg_geometry(b, &mut fg, &mut has_z, &mut has_m)?;
+        }
+        geometries.push(fg);
+    }
+    let shape_type = find_shape_type(&geometries, has_z, has_m)?;
+    sf.header.shape_type = shape_type;
+    sf.header.version = 1000;
+    for fg in geometries {
+        sf.records.push(to_shapefile_geometry(fg, shape_type));
+    }
+    sf.num_records = sf.records.len();
+    sf.calculate_extent();
+
+    // read the attributes
+    let mut columns = vec![];
+    let mut fields = vec![];
+    for (c, column) in table.columns.iter().enumerate() {
+        if c == geometry_index || column.is_rowid_alias {
+            continue;
+        }
+        if let Some(field) = field_from_column(&table, c) {
+            columns.push(c);
+            fields.push(field);
+        }
+    }
+    sf.attributes.add_fields(&fields);
+    for row in &table.rows {
+        let rec = columns
+            .iter()
+            .zip(fields.iter())
+            .map(|(c, field)| field_data(&row[*c], field.field_type))
+            .collect::<Vec<FieldData>>();
+        sf.attributes.add_record(rec, false);
+    }
+
+    Ok(())
+}
+
+/// Returns the projection of a spatial reference system, as the ESRI WKT of its EPSG code if
+/// it has one, and otherwise its definition.
+fn read_projection(db: &SqliteDatabase, srs_id: i64) -> Result<String, Error> {
+    if !db.has_table("gpkg_spatial_ref_sys") {
+        return Ok(String::new());
+    }
+    let srs = db.read_table("gpkg_spatial_ref_sys")?;
+    for r in 0..srs.rows.len() {
+        if srs.get(r, "srs_id").as_i64() != Some(srs_id) {
+            continue;
+        }
+        let organization = srs.get(r, "organization").as_str().unwrap_or("");
+        let code = srs.get(r, "organization_coordsys_id").as_i64().unwrap_or(0);
+        if organization.eq_ignore_ascii_case("EPSG") && code > 0 && code <= u16::MAX as i64 {
+            let wkt = projection_from_epsg(code as u16);
+            if !wkt.is_empty() {
+                return Ok(wkt);
+            }
+        }
+        let definition = srs.get(r, "definition").as_str().unwrap_or("");
+        if definition != "undefined" {
+            return Ok(definition.to_string());
+        }
+    }
+    Ok(String::new())
+}
+
+/// Creates an attribute field for a column from its declared type, with a width that fits
+/// its values, or returns None for BLOB columns.
+fn field_from_column(table: &SqliteTable, c: usize) -> Option<AttributeField> {
+    let name = &table.columns[c].name;
+    let declared = table.columns[c].declared_type.to_uppercase();
+    let values = table.rows.iter().map(|row| &row[c]);
+    if declared.starts_with("BOOLEAN") {
+        return Some(AttributeField::new(name, FieldDataType::Bool, 1u8, 0u8));
+    }
+    if declared.contains("INT") {
+        let fits_i32 = values.clone().all(|v| match v.as_i64() {
+            Some(v) => v >= i32::MIN as i64 && v <= i32::MAX as i64,
+            None => true,
+        });
+        if fits_i32 {
+            let digits = values
+                .filter_map(|v| v.as_i64())
+                .map(|v| v.to_string().len())
+                .max()
+                .unwrap_or(1);
+            return Some(AttributeField::new(
+                name,
+                FieldDataType::Int,
+                digits.max(1) as u8,
+                0u8,
+            ));
+        }
+    } else if declared.starts_with("DATETIME") {
+        // a date-time is kept as text
+    } else if declared.starts_with("DATE") {
+        return Some(AttributeField::new(name, FieldDataType::Date, 8u8, 0u8));
+    } else if declared.contains("BLOB") || declared == "GEOMETRY" {
+        return None;
+    }
+    if declared.contains("INT")
+        || declared.contains("REAL")
+        || declared.contains("FLOA")
+        || declared.contains("DOUB")
+        || declared.contains("NUMERIC")
+        || declared.contains("DECIMAL")
+    {
+        let mut int_digits = 1;
+        let mut decimals = 1;
+        for v in values.filter_map(|v| v.as_f64()).filter(|v| v.is_finite()) {
+            let s = v.to_string();
+            let mut parts = s.splitn(2, '.');
+            int_digits = int_digits.max(parts.next().unwrap_or("").len());
+            decimals = decimals.max(parts.next().unwrap_or("").len());
+        }
+        let decimals = decimals.min(15);
+        let width = (int_digits + 1 + decimals).min(255);
+        return Some(AttributeField::new(
+            name,
+            FieldDataType::Real,
+            width as u8,
+            decimals as u8,
+        ));
+    }
+    let length = values
+        .map(|v| match v {
+            SqliteValue::Text(s) => s.len(),
+            SqliteValue::Null => 1,
+            v => v.as_f64().map_or(1, |f| f.to_string().len()),
+        })
+        .max()
+        .unwrap_or(1)
+        .max(1)
+        .min(254);
+    Some(AttributeField::new(
+        name,
+        FieldDataType::Text,
+        length as u8,
+        0u8,
+    ))
+}
+
+fn field_data(value: &SqliteValue, field_type: char) -> FieldData {
+    if *value == SqliteValue::Null {
+        return FieldData::Null;
+    }
+    match field_type {
+        'N' => value
+            .as_i64()
+            .map_or(FieldData::Null, |v| FieldData::Int(v as i32)),
+        'F' => value.as_f64().map_or(FieldData::Null, FieldData::Real),
+        'L' => value
+            .as_i64()
+            .map_or(FieldData::Null, |v| FieldData::Bool(v != 0)),
+        'D' => {
+            let s = value.as_str().unwrap_or("");
+            let parts = s
+                .splitn(3, '-')
+                .map(|p| p.get(0..2.min(p.len())).unwrap_or(""))
+                .collect::<Vec<&str>>();
+            let year = s.get(0..4).and_then(|y| y.parse::<u16>().ok());
+            match (year, parts.len()) {
+                (Some(year), 3) => match (parts[1].parse::<u8>(), parts[2].parse::<u8>()) {
+                    (Ok(month), Ok(day)) => FieldData::Date(DateData {
+                        year: year,
+                        month: month,
+                        day: day,
+                    }),
+                    _ => FieldData::Null,
+                },
+                _ => FieldData::Null,
+            }
+        }
+        _ => match value {
+            SqliteValue::Text(s) => FieldData::Text(s.clone()),
+            SqliteValue::Integer(v) => FieldData::Text(v.to_string()),
+            SqliteValue::Real(v) => FieldData::Text(v.to_string()),
+            _ => FieldData::Null,
+        },
+    }
+}
+
+/// Reads a geometry in the GeoPackage binary format, i.e. a header followed by a
+/// well-known binary (WKB) geometry.
+fn read_gpkg_geometry(
+    b: &[u8],
+    fg: &mut FeatureGeometry,
+    has_z: &mut bool,
+    has_m: &mut bool,
+) -> Result<(), Error> {
+    if b.len() < 8 || &b[0..2] != b"GP" {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "A geometry is not in the GeoPackage binary format.",
+        ));
+    }
+    let flags = b[3];
+    if flags & 0x20 != 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Extended GeoPackage geometry types are not supported.",
+        ));
+    }
+    let envelope_size = match (flags >> 1) & 0x07 {
+        0 => 0,
+        1 => 32,
+        2 | 3 => 48,
+        4 => 64,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A geometry has an invalid envelope indicator.",
+            ))
+        }
+    };
+    if flags & 0x10 != 0 {
+        return Ok(()); // an empty geometry
+    }
+    let mut reader = WkbReader {
+        bytes: b,
+        pos: 8 + envelope_size,
+    };
+    read_wkb(&mut reader, fg, has_z, has_m)
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> WkbReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.pos + n > self.bytes.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A geometry ended unexpectedly.",
+            ));
+        }
+        let s = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self, little_endian: bool) -> Result<f64, Error> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+}
+
+/// Reads a WKB geometry, in either the ISO (type codes offset by 1000, 2000, and 3000) or
+/// extended (high-bit flags) encoding of z and m coordinates.
+fn read_wkb(
+    r: &mut WkbReader,
+    fg: &mut FeatureGeometry,
+    has_z: &mut bool,
+    has_m: &mut bool,
+) -> Result<(), Error> {
+    let little_endian = r.take(1)?[0] == 1;
+    let raw_type = r.read_u32(little_endian)?;
+    let mut z = raw_type & 0x8000_0000 != 0;
+    let mut m = raw_type & 0x4000_0000 != 0;
+    if raw_type & 0x2000_0000 != 0 {
+        r.read_u32(little_endian)?; // an embedded SRID
+    }
+    let geometry_type = raw_type & 0x0fff_ffff;
+    match geometry_type / 1000 {
+        1 => z = true,
+        2 => m = true,
+        3 => {
+            z = true;
+            m = true;
+        }
+        _ => {}
+    }
+    *has_z |= z;
+    *has_m |= m;
+    let read_points = |r: &mut WkbReader, n: usize| -> Result<Vec<[f64; 4]>, Error> {
+        let mut points = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut p = [f64::NAN; 4];
+            p[0] = r.read_f64(little_endian)?;
+            p[1] = r.read_f64(little_endian)?;
+            if z {
+                p[2] = r.read_f64(little_endian)?;
+            }
+            if m {
+                p[3] = r.read_f64(little_endian)?;
+            }
+            // an empty point has NaN coordinates
+            if !p[0].is_nan() && !p[1].is_nan() {
+                points.push(p);
+            }
+        }
+        Ok(points)
+    };
+    match geometry_type % 1000 {
+        1 => {
+            let points = read_points(r, 1)?;
+            fg.points.extend(points);
+        }
+        2 => {
+            let n = r.read_u32(little_endian)? as usize;
+            let line = read_points(r, n)?;
+            if !line.is_empty() {
+                fg.lines.push(line);
+            }
+        }
+        3 => {
+            let num_rings = r.read_u32(little_endian)? as usize;
+            let mut rings = vec![];
+            for _ in 0..num_rings {
+                let n = r.read_u32(little_endian)? as usize;
+                let ring = read_points(r, n)?;
+                if !ring.is_empty() {
+                    rings.push(ring);
+                }
+            }
+            if !rings.is_empty() {
+                fg.polygons.push(rings);
+            }
+        }
+        4..=7 => {
+            // multi-geometries and collections contain complete WKB geometries
+            let n = r.read_u32(little_endian)? as usize;
+            for _ in 0..n {
+                read_wkb(r, fg, has_z, has_m)?;
+            }
+        }
+        t => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The WKB geometry type {} is not supported.", t),
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Writes a Vector to a feature table of a GeoPackage, named by the layer of the file
+/// name (`data.gpkg:layer`) or otherwise by the file stem. If the GeoPackage already exists
+/// and contains other layers, they are kept; this is only possible for GeoPackages made
+/// up of plain tables (e.g. those written by this library), because indexes, triggers, and
+/// virtual tables (e.g. R-tree spatial indexes) are not supported.
+pub fn write_geopackage(sf: &Vector) -> Result<(), Error> {
+    let (path, layer) = split_layer(&sf.file_name);
+    let layer = match layer {
+        Some(l) => l,
+        None => Path::new(&path)
+            .file_stem()
+            .map_or("layer".to_string(), |s| s.to_string_lossy().to_string()),
+    };
+    let mut tables = if Path::new(&path).exists() {
+        existing_tables(&path, &layer)?
+    } else {
+        vec![]
+    };
+    if tables.is_empty() {
+        tables = vec![
+            SqliteTable::new("gpkg_spatial_ref_sys", SRS_TABLE_SQL),
+            SqliteTable::new("gpkg_contents", CONTENTS_TABLE_SQL),
+            SqliteTable::new("gpkg_geometry_columns", GEOMETRY_COLUMNS_TABLE_SQL),
+            SqliteTable::new("sqlite_sequence", SEQUENCE_TABLE_SQL),
+        ];
+        add_srs(
+            &mut tables,
+            -1,
+            "Undefined cartesian SRS",
+            "NONE",
+            "undefined",
+        );
+        add_srs(
+            &mut tables,
+            0,
+            "Undefined geographic SRS",
+            "NONE",
+            "undefined",
+        );
+        add_srs(&mut tables, 4326, "WGS 84 geodetic", "EPSG", WGS84_WKT);
+    }
+    add_feature_table(&mut tables, sf, &layer);
+    write_sqlite_database(&path, &tables, GPKG_APPLICATION_ID, GPKG_USER_VERSION)
+}
+
+/// Reads the tables of an existing GeoPackage, less those of a layer that will be replaced.
+/// Returns an empty list if the GeoPackage contains no other layers.
+fn existing_tables(path: &str, layer: &str) -> Result<Vec<SqliteTable>, Error> {
+    let db = SqliteDatabase::open(path)?;
+    let contents = db.read_table("gpkg_contents")?;
+    let layers = (0..contents.rows.len())
+        .filter_map(|r| contents.get(r, "table_name").as_str())
+        .filter(|t| !t.eq_ignore_ascii_case(layer))
+        .count();
+    if layers == 0 {
+        return Ok(vec![]);
+    }
+    let mut tables = vec![];
+    for entry in &db.schema {
+        let supported = (entry.entry_type == "table" && entry.root_page != 0)
+            || (entry.entry_type == "index" && entry.name.starts_with("sqlite_autoindex_"));
+        if !supported {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "A layer cannot be added to the GeoPackage {} because it contains the {} '{}'. Write the layer to a new GeoPackage instead.",
+                    path, entry.entry_type, entry.name
+                ),
+            ));
+        }
+        if entry.entry_type == "table" && !entry.name.eq_ignore_ascii_case(layer) {
+            tables.push(db.read_table(&entry.name)?);
+        }
+    }
+    for name in &[
+        "gpkg_spatial_ref_sys",
+        "gpkg_contents",
+        "gpkg_geometry_columns",
+    ] {
+        if !tables.iter().any(|t| t.name == *name) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The GeoPackage {} does not contain a {} table.", path, name),
+            ));
+        }
+    }
+    if !tables.iter().any(|t| t.name == "sqlite_sequence") {
+        tables.push(SqliteTable::new("sqlite_sequence", SEQUENCE_TABLE_SQL));
+    }
+    // remove the metadata of the replaced layer
+    for table in tables.iter_mut() {
+        let column = match table.name.as_str() {
+            "gpkg_contents" | "gpkg_geometry_columns" => "table_name",
+            "sqlite_sequence" => "name",
+            _ => continue,
+        };
+        if let Some(c) = table.column_index(column) {
+            table.rows.retain(|row| {
+                row[c]
+                    .as_str()
+                    .map_or(true, |t| !t.eq_ignore_ascii_case(layer))
+            });
+        }
+    }
+    Ok(tables)
+}
+
+fn table_mut<'a>(tables: &'a mut Vec<SqliteTable>, name: &str) -> &'a mut SqliteTable {
+    tables.iter_mut().find(|t| t.name == name).unwrap()
+}
+
+/// Adds a spatial reference system, if it is not already defined.
+fn add_srs(
+    tables: &mut Vec<SqliteTable>,
+    srs_id: i64,
+    name: &str,
+    organization: &str,
+    definition: &str,
+) {
+    let srs = table_mut(tables, "gpkg_spatial_ref_sys");
+    let c = srs.column_index("srs_id").unwrap();
+    if srs.rows.iter().any(|row| row[c].as_i64() == Some(srs_id)) {
+        return;
+    }
+    let description = if definition == "undefined" {
+        SqliteValue::Text(name.to_lowercase())
+    } else {
+        SqliteValue::Null
+    };
+    let mut row = vec![SqliteValue::Null; srs.columns.len()];
+    for (column, value) in [
+        ("srs_name", SqliteValue::Text(name.to_string())),
+        ("srs_id", SqliteValue::Integer(srs_id)),
+        ("organization", SqliteValue::Text(organization.to_string())),
+        ("organization_coordsys_id", SqliteValue::Integer(srs_id)),
+        ("definition", SqliteValue::Text(definition.to_string())),
+        ("description", description),
+    ]
+    .iter()
+    {
+        if let Some(i) = srs.column_index(column) {
+            row[i] = value.clone();
+        }
+    }
+    srs.rows.push(row);
+    // the rows of a table are written in order of their INTEGER PRIMARY KEY
+    srs.rows
+        .sort_by_key(|row| row[c].as_i64().unwrap_or(i64::MIN));
+}
+
+fn push_row(table: &mut SqliteTable, values: Vec<(&str, SqliteValue)>) {
+    let mut row = vec![SqliteValue::Null; table.columns.len()];
+    for (column, value) in values {
+        if let Some(i) = table.column_index(column) {
+            row[i] = value;
+        }
+    }
+    table.rows.push(row);
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Adds the feature table of a layer and its metadata.
+fn add_feature_table(tables: &mut Vec<SqliteTable>, sf: &Vector, layer: &str) {
+    // the spatial reference system
+    let srs_id = if sf.projection.trim().is_empty() {
+        -1
+    } else {
+        let name = sf
+            .projection
+            .split('"')
+            .nth(1)
+            .unwrap_or("Unknown")
+            .to_string();
+        match find_epsg_code(&sf.projection) {
+            Some(code) => {
+                add_srs(tables, code as i64, &name, "EPSG", &sf.projection);
+                code as i64
+            }
+            None => {
+                // a custom coordinate system
+                let srs = table_mut(tables, "gpkg_spatial_ref_sys");
+                let c = srs.column_index("srs_id").unwrap();
+                let d = srs.column_index("definition").unwrap();
+                match srs
+                    .rows
+                    .iter()
+                    .find(|row| row[d].as_str() == Some(&sf.projection))
+                {
+                    Some(row) => row[c].as_i64().unwrap(),
+                    None => {
+                        let id = srs
+                            .rows
+                            .iter()
+                            .filter_map(|row| row[c].as_i64())
+                            .max()
+                            .unwrap_or(0)
+                            .max(99_999)
+                            + 1;
+                        add_srs(tables, id, &name, "NONE", &sf.projection);
+                        id
+                    }
+                }
+            }
+        }
+    };
+
+    // the column names, avoiding those of the attribute fields
+    let fields = sf.attributes.get_fields();
+    let unique_name = |base: &str| {
+        let mut name = base.to_string();
+        while fields.iter().any(|f| f.name.eq_ignore_ascii_case(&name)) {
+            name.push('_');
+        }
+        name
+    };
+    let fid_column = unique_name("fid");
+    let geometry_column = unique_name("geom");
+
+    // the geometry type
+    let shape_type = sf.header.shape_type;
+    let dimension = shape_type.dimension();
+    let is_multi = match shape_type.base_shape_type() {
+        ShapeType::PolyLine => sf.records.iter().any(|r| r.num_parts > 1),
+        ShapeType::Polygon => sf
+            .records
+            .iter()
+            .any(|r| r.num_parts > 1 && group_polygon_rings(r).len() > 1),
+        _ => false,
+    };
+    let geometry_type_name = match (shape_type.base_shape_type(), is_multi) {
+        (ShapeType::Point, _) => "POINT",
+        (ShapeType::MultiPoint, _) => "MULTIPOINT",
+        (ShapeType::PolyLine, false) => "LINESTRING",
+        (ShapeType::PolyLine, true) => "MULTILINESTRING",
+        (ShapeType::Polygon, false) => "POLYGON",
+        (ShapeType::Polygon, true) => "MULTIPOLYGON",
+        _ => "GEOMETRY",
+    };
+
+    // the feature table
+    let mut sql = format!(
+        "CREATE TABLE {} ({} INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, {} {}",
+        quote_identifier(layer),
+        quote_identifier(&fid_column),
+        quote_identifier(&geometry_column),
+        geometry_type_name
+    );
+    for field in fields {
+        let field_type = match field.field_type {
+            'N' | 'I' if field.decimal_count == 0 => "MEDIUMINT",
+            'N' | 'F' | 'I' | 'O' => "REAL",
+            'D' => "DATE",
+            'L' => "BOOLEAN",
+            _ => "TEXT",
+        };
+        sql.push_str(&format!(
+            ", {} {}",
+            quote_identifier(&field.name),
+            field_type
+        ));
+    }
+    sql.push(')');
+    let mut table = SqliteTable::new(layer, &sql);
+    let (mut x_min, mut y_min) = (f64::INFINITY, f64::INFINITY);
+    let (mut x_max, mut y_max) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for (i, record) in sf.records.iter().enumerate() {
+        let geometry = if record.shape_type == ShapeType::Null || record.points.is_empty() {
+            SqliteValue::Null
+        } else {
+            x_min = x_min.min(record.x_min);
+            y_min = y_min.min(record.y_min);
+            x_max = x_max.max(record.x_max);
+            y_max = y_max.max(record.y_max);
+            SqliteValue::Blob(gpkg_geometry(record, srs_id as i32, is_multi, &dimension))
+        };
+        let mut row = vec![SqliteValue::Integer(i as i64 + 1), geometry];
+        let rec = if i < sf.attributes.header.num_records as usize {
+            sf.attributes.get_record(i)
+        } else {
+            vec![]
+        };
+        for j in 0..fields.len() {
+            row.push(match rec.get(j) {
+                Some(FieldData::Int(v)) => SqliteValue::Integer(*v as i64),
+                Some(FieldData::Real(v)) if v.is_finite() => SqliteValue::Real(*v),
+                Some(FieldData::Text(v)) => SqliteValue::Text(v.clone()),
+                Some(FieldData::Date(v)) => {
+                    SqliteValue::Text(format!("{:04}-{:02}-{:02}", v.year, v.month, v.day))
+                }
+                Some(FieldData::Bool(v)) => SqliteValue::Integer(*v as i64),
+                _ => SqliteValue::Null,
+            });
+        }
+        table.rows.push(row);
+    }
+    let extent = |v: f64| {
+        if v.is_finite() {
+            SqliteValue::Real(v)
+        } else {
+            SqliteValue::Null
+        }
+    };
+
+    // the metadata
+    push_row(
+        table_mut(tables, "gpkg_contents"),
+        vec![
+            ("table_name", SqliteValue::Text(layer.to_string())),
+            ("data_type", SqliteValue::Text("features".to_string())),
+            ("identifier", SqliteValue::Text(layer.to_string())),
+            ("description", SqliteValue::Text(String::new())),
+            (
+                "last_change",
+                SqliteValue::Text(Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()),
+            ),
+            ("min_x", extent(x_min)),
+            ("min_y", extent(y_min)),
+            ("max_x", extent(x_max)),
+            ("max_y", extent(y_max)),
+            ("srs_id", SqliteValue::Integer(srs_id)),
+        ],
+    );
+    push_row(
+        table_mut(tables, "gpkg_geometry_columns"),
+        vec![
+            ("table_name", SqliteValue::Text(layer.to_string())),
+            ("column_name", SqliteValue::Text(geometry_column)),
+            (
+                "geometry_type_name",
+                SqliteValue::Text(geometry_type_name.to_string()),
+            ),
+            ("srs_id", SqliteValue::Integer(srs_id)),
+            (
+                "z",
+                SqliteValue::Integer((dimension == ShapeTypeDimension::Z) as i64),
+            ),
+            (
+                "m",
+                SqliteValue::Integer((dimension == ShapeTypeDimension::Measure) as i64),
+            ),
+        ],
+    );
+    push_row(
+        table_mut(tables, "sqlite_sequence"),
+        vec![
+            ("name", SqliteValue::Text(layer.to_string())),
+            ("seq", SqliteValue::Integer(sf.records.len() as i64)),
+        ],
+    );
+    tables.push(table);
+}
+
+/// Encodes a record as a GeoPackage binary geometry, i.e. a header with the xy envelope
+/// followed by little-endian ISO WKB. Polygon exterior rings are counter-clockwise.
+fn gpkg_geometry(
+    sfg: &ShapefileGeometry,
+    srs_id: i32,
+    is_multi: bool,
+    dimension: &ShapeTypeDimension,
+) -> Vec<u8> {
+    let mut b = vec![b'G', b'P', 0u8, 0x03];
+    b.extend_from_slice(&srs_id.to_le_bytes());
+    for v in &[sfg.x_min, sfg.x_max, sfg.y_min, sfg.y_max] {
+        b.extend_from_slice(&v.to_le_bytes());
+    }
+    let type_offset = match dimension {
+        ShapeTypeDimension::Z => 1000,
+        ShapeTypeDimension::Measure => 2000,
+        ShapeTypeDimension::XY => 0,
+    };
+    let push_header = |b: &mut Vec<u8>, geometry_type: u32| {
+        b.push(1u8);
+        b.extend_from_slice(&(geometry_type + type_offset).to_le_bytes());
+    };
+    let push_count = |b: &mut Vec<u8>, n: usize| b.extend_from_slice(&(n as u32).to_le_bytes());
+    let push_point = |b: &mut Vec<u8>, i: usize| {
+        b.extend_from_slice(&sfg.points[i].x.to_le_bytes());
+        b.extend_from_slice(&sfg.points[i].y.to_le_bytes());
+        match dimension {
+            ShapeTypeDimension::Z => {
+                b.extend_from_slice(&sfg.z_array.get(i).unwrap_or(&0f64).to_le_bytes())
+            }
+            ShapeTypeDimension::Measure => {
+                b.extend_from_slice(&sfg.m_array.get(i).unwrap_or(&0f64).to_le_bytes())
+            }
+            ShapeTypeDimension::XY => {}
+        }
+    };
+    let push_line = |b: &mut Vec<u8>, indices: Vec<usize>| {
+        push_count(b, indices.len());
+        for i in indices {
+            push_point(b, i);
+        }
+    };
+    match sfg.shape_type.base_shape_type() {
+        ShapeType::Point => {
+            push_header(&mut b, 1);
+            push_point(&mut b, 0);
+        }
+        ShapeType::MultiPoint => {
+            push_header(&mut b, 4);
+            push_count(&mut b, sfg.points.len());
+            for i in 0..sfg.points.len() {
+                push_header(&mut b, 1);
+                push_point(&mut b, i);
+            }
+        }
+        ShapeType::PolyLine => {
+            if is_multi {
+                push_header(&mut b, 5);
+                push_count(&mut b, sfg.parts.len());
+            }
+            for part in 0..sfg.parts.len() {
+                push_header(&mut b, 2);
+                push_line(&mut b, part_range(sfg, part).collect());
+            }
+        }
+        _ => {
+            let polygons = group_polygon_rings(sfg);
+            if is_multi {
+                push_header(&mut b, 6);
+                push_count(&mut b, polygons.len());
+            }
+            for polygon in &polygons {
+                push_header(&mut b, 3);
+                push_count(&mut b, polygon.len());
+                for (r, part) in polygon.iter().enumerate() {
+                    let range = part_range(sfg, *part);
+                    let reverse = is_clockwise_order(&sfg.points[range.clone()]) == (r == 0);
+                    if reverse {
+                        push_line(&mut b, range.rev().collect());
+                    } else {
+                        push_line(&mut b, range.collect());
+                    }
+                }
+            }
+        }
+    }
+    b
+}
+
+#[cfg(test)]
+mod test {
+    use super::{feature_layers, read_geopackage, write_geopackage};
+    use crate::vector::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_geopackage_round_trip() {
+        let file_name = env::temp_dir()
+            .join("wbt_geopackage_round_trip.gpkg")
+            .to_string_lossy()
+            .to_string();
+        let _ = fs::remove_file(&file_name);
+
+        // a polygon layer, with a hole and a multipart record
+        let mut sf = Vector::new(&format!("{}:parcels", file_name), ShapeType::Polygon).unwrap();
+        sf.projection = crate::spatial_ref_system::esri_wkt_from_epsg(26917);
+        sf.attributes
+            .add_field(&AttributeField::new("NAME", FieldDataType::Text, 10u8, 0u8));
+        sf.attributes
+            .add_field(&AttributeField::new("COUNT", FieldDataType::Int, 4u8, 0u8));
+        sf.attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 10u8, 2u8));
+        let ring = |pts: &[(f64, f64)]| {
+            pts.iter()
+                .map(|p| Point2D::new(p.0, p.1))
+                .collect::<Vec<Point2D>>()
+        };
+        let mut g = ShapefileGeometry::new(ShapeType::Polygon);
+        g.add_part(&ring(&[
+            (0., 0.),
+            (0., 10.),
+            (10., 10.),
+            (10., 0.),
+            (0., 0.),
+        ]));
+        g.add_part(&ring(&[(2., 2.), (4., 2.), (4., 4.), (2., 4.), (2., 2.)]));
+        sf.add_record(g);
+        sf.attributes.add_record(
+            vec![
+                FieldData::Text("Lake Ω".to_string()),
+                FieldData::Int(3),
+                FieldData::Real(96.5),
+            ],
+            false,
+        );
+        let mut g = ShapefileGeometry::new(ShapeType::Polygon);
+        g.add_part(&ring(&[(20., 0.), (20., 10.), (30., 0.), (20., 0.)]));
+        g.add_part(&ring(&[(40., 0.), (40., 10.), (50., 0.), (40., 0.)]));
+        sf.add_record(g);
+        sf.attributes.add_record(
+            vec![FieldData::Null, FieldData::Int(-7), FieldData::Null],
+            false,
+        );
+        write_geopackage(&sf).unwrap();
+
+        // a second layer is added to the same file
+        let mut pts = Vector::new(&format!("{}:wells", file_name), ShapeType::PointZ).unwrap();
+        let mut g = ShapefileGeometry::new(ShapeType::PointZ);
+        g.add_pointz(Point2D::new(1.0, 2.0), 0.0, 3.0);
+        pts.add_record(g);
+        pts.attributes
+            .add_field(&AttributeField::new("OK", FieldDataType::Bool, 1u8, 0u8));
+        pts.attributes
+            .add_record(vec![FieldData::Bool(true)], false);
+        write_geopackage(&pts).unwrap();
+        assert_eq!(
+            feature_layers(&file_name).unwrap(),
+            vec!["parcels".to_string(), "wells".to_string()]
+        );
+
+        let mut sf2 = Vector {
+            file_name: format!("{}:parcels", file_name),
+            ..Default::default()
+        };
+        read_geopackage(&mut sf2).unwrap();
+        assert_eq!(sf2.header.shape_type, ShapeType::Polygon);
+        assert_eq!(sf2.num_records, 2);
+        assert!(sf2.projection.contains("26917"));
+        for i in 0..2 {
+            assert_eq!(sf2.records[i].points, sf.records[i].points);
+            assert_eq!(sf2.attributes.get_record(i), sf.attributes.get_record(i));
+        }
+        assert!(sf2.records[0].is_hole(1));
+
+        let mut pts2 = Vector {
+            file_name: format!("{}:WELLS", file_name),
+            ..Default::default()
+        };
+        read_geopackage(&mut pts2).unwrap();
+        let _ = fs::remove_file(&file_name);
+        assert_eq!(pts2.header.shape_type, ShapeType::PointZ);
+        assert_eq!(pts2.records[0].z_array, vec![3.0]);
+        assert_eq!(pts2.attributes.get_value(0, "OK"), FieldData::Bool(true));
+    }
+}
@@ -2,19 +2,25 @@
 This code is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: June 21, 2017
-Last Modified: 12/04/2018
+Last Modified: 18/10/2026
 License: MIT
 */
 
 /*
-Eventually this will be used to support multiple vector formats but
-for now it's just Shapefiles.
+Vector data are held in memory by the format-agnostic `Vector` type, using the Shapefile
+data model. ESRI Shapefiles, GeoJSON files, and GeoPackages are supported, with the format
+selected by file extension. Each format is a backend providing a reader and a writer, which
+are dispatched on the `VectorType`. A layer of a multi-layer GeoPackage is selected using
+the syntax `file.gpkg:layer`.
 */
 
 // use std::fmt;
 
 // private sub-module defined in other files
+pub mod geojson;
+pub mod geopackage;
 pub mod shapefile;
+mod simple_features;
 
 // exports identifiers from private sub-modules in the current module namespace
 // pub use self::shapefile::attributes::{
@@ -25,8 +31,507 @@ pub use crate::vector::shapefile::attributes::*;
 pub use crate::vector::shapefile::geometry::*;
 // pub use self::shapefile::geometry::{ShapeType, ShapeTypeDimension, ShapefileGeometry};
 pub use crate::structures::Point2D;
+use crate::structures::{BoundingBox, RTree};
 pub use crate::vector::shapefile::geometry::ShapeType;
 pub use crate::vector::shapefile::Shapefile;
+use crate::vector::shapefile::{read_shapefile, write_shapefile, ShapefileHeader};
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// `Vector` is an in-memory vector data set, held using the Shapefile data model of
+/// geometry records, an attribute table, and a projection. The file format is selected by
+/// the file extension (see `VectorType`) and tools need not be aware of it.
+///
+/// Examples:
+///
+/// ```
+/// // Read a vector from a file.
+/// let input = Vector::read(&input_file)?;
+///
+/// // Create a new output vector
+/// let mut output = Vector::initialize_using_file(&output_file, &input, ShapeType::PolyLine, true)?;
+///
+/// // add attributes
+/// let fid = AttributeField::new("FID", FieldDataType::Int, 2u8, 0u8);
+/// let val = AttributeField::new("Value", FieldDataType::Real, 12u8, 4u8);
+/// output.attributes.add_field(&fid);
+/// output.attributes.add_field(&val);
+///
+/// ```
+#[derive(Default, Clone)]
+pub struct Vector {
+    pub file_name: String,
+    pub file_mode: String,
+    pub header: ShapefileHeader,
+    pub num_records: usize,
+    pub records: Vec<ShapefileGeometry>,
+    pub attributes: ShapefileAttributes,
+    pub projection: String,
+}
+
+impl Vector {
+    pub fn read<'a>(file_name: &'a str) -> Result<Vector, Error> {
+        let mut vector = Vector {
+            file_name: file_name.to_string(),
+            file_mode: "r".to_string(),
+            ..Default::default()
+        };
+        match get_vector_type_from_file(file_name) {
+            VectorType::GeoJson => geojson::read_geojson(&mut vector)?,
+            VectorType::GeoPackage => geopackage::read_geopackage(&mut vector)?,
+            VectorType::Shapefile => read_shapefile(&mut vector)?,
+            VectorType::Unknown => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unrecognized vector file type: {}", file_name),
+                ))
+            }
+        }
+        Ok(vector)
+    }
+
+    pub fn new<'a>(file_name: &'a str, file_type: ShapeType) -> Result<Vector, Error> {
+        let mut vector = Vector {
+            file_name: default_extension(file_name),
+            file_mode: "w".to_string(),
+            ..Default::default()
+        };
+        vector.header.shape_type = file_type;
+        Ok(vector)
+    }
+
+    pub fn initialize_using_file<'a>(
+        file_name: &'a str,
+        other: &'a Vector,
+        shape_type: ShapeType,
+        copy_fields: bool,
+    ) -> Result<Vector, Error> {
+        let mut vector = Vector {
+            file_name: default_extension(file_name),
+            file_mode: "w".to_string(),
+            projection: other.projection.clone(),
+            ..Default::default()
+        };
+        vector.header.shape_type = shape_type;
+        if copy_fields {
+            vector.attributes.fields = other.attributes.fields.clone();
+            vector.attributes.header.num_fields = vector.attributes.fields.len() as u32;
+        }
+        Ok(vector)
+    }
+
+    /// Returns the format of the vector, as determined by its file extension.
+    pub fn get_vector_type(&self) -> VectorType {
+        get_vector_type_from_file(&self.file_name)
+    }
+
+    pub fn get_total_num_parts(&self) -> usize {
+        let mut ret = 0;
+        for a in 0..self.num_records {
+            ret += self.records[a].num_parts as usize;
+        }
+        ret
+    }
+
+    pub fn get_total_num_points(&self) -> usize {
+        let mut ret = 0;
+        for a in 0..self.num_records {
+            ret += self.records[a].num_points as usize;
+        }
+        ret
+    }
+
+    /// Returns the filename, in shortened form (e.g. file.shp).
+    pub fn get_short_filename(&self) -> String {
+        let path = Path::new(&self.file_name);
+        let file_name = path.file_stem().unwrap();
+        let f = file_name.to_str().unwrap();
+        f.to_string()
+    }
+
+    /// Returns the ShapefileGeometry for a specified index, starting at zero.
+    pub fn get_record<'a>(&'a self, index: usize) -> &'a ShapefileGeometry {
+        if index >= self.records.len() {
+            panic!("Record index out of bounds");
+        }
+        &self.records[index]
+    }
+
+    /// Adds a new ShapefileGeometry.
+    pub fn add_record(&mut self, geometry: ShapefileGeometry) {
+        if self.file_mode == "r" {
+            panic!("The file was opened in read-only mode.");
+        }
+        if geometry.shape_type == self.header.shape_type || geometry.shape_type == ShapeType::Null {
+            self.records.push(geometry);
+            self.num_records += 1;
+        } else {
+            panic!("Attempt to add a ShapefileGeometry record of the wrong ShapeType.");
+        }
+    }
+
+    /// Adds a new Point record.
+    pub fn add_point_record(&mut self, x: f64, y: f64) {
+        if self.file_mode == "r" {
+            panic!("The file was opened in read-only mode.");
+        }
+        if self.header.shape_type == ShapeType::Point {
+            let mut sfg = ShapefileGeometry {
+                shape_type: ShapeType::Point,
+                ..Default::default()
+            };
+            sfg.add_point(Point2D { x: x, y: y });
+            self.records.push(sfg);
+            self.num_records += 1;
+        } else {
+            panic!("Attempt to add a ShapefileGeometry record of the wrong ShapeType.");
+        }
+    }
+
+    /// Returns the bounding boxes of the records. Null records have an empty bounding box,
+    /// which does not overlap any other.
+    pub fn get_record_bounding_boxes(&self) -> Vec<BoundingBox> {
+        self.records
+            .iter()
+            .map(|r| {
+                if r.shape_type == ShapeType::Null || r.num_points == 0 {
+                    BoundingBox::default()
+                } else {
+                    r.get_bounding_box()
+                }
+            })
+            .collect()
+    }
+
+    /// Returns an R-tree spatial index of the record bounding boxes, identified by record
+    /// number. The index is read from the spatial index file (see `write_spatial_index`) if
+    /// there is one that is newer than the data, and is built in memory otherwise.
+    pub fn get_spatial_index(&self) -> RTree {
+        match self.read_spatial_index() {
+            Some(tree) => tree,
+            None => RTree::new(&self.get_record_bounding_boxes()),
+        }
+    }
+
+    /// Writes an R-tree spatial index of the record bounding boxes to a sidecar file
+    /// (see `get_spatial_index_file`), so that it does not need to be rebuilt each time
+    /// the vector is used.
+    pub fn write_spatial_index(&self) -> Result<(), Error> {
+        RTree::new(&self.get_record_bounding_boxes()).write(&self.get_spatial_index_file())
+    }
+
+    /// Returns the name of the spatial index sidecar file, i.e. the file name with a .rix
+    /// extension, or data.layer.rix for a layer of a GeoPackage data.gpkg.
+    pub fn get_spatial_index_file(&self) -> String {
+        let (file_name, layer) = geopackage::split_layer(&self.file_name);
+        let path = Path::new(&file_name);
+        let index_file = match layer {
+            Some(layer) => {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                path.with_file_name(format!("{}.{}.rix", stem, layer))
+            }
+            None => path.with_extension("rix"),
+        };
+        index_file.to_string_lossy().to_string()
+    }
+
+    fn read_spatial_index(&self) -> Option<RTree> {
+        if self.file_mode != "r" {
+            return None;
+        }
+        let index_file = self.get_spatial_index_file();
+        let (data_file, _) = geopackage::split_layer(&self.file_name);
+        let data_modified = fs::metadata(&data_file).ok()?.modified().ok()?;
+        let index_modified = fs::metadata(&index_file).ok()?.modified().ok()?;
+        if index_modified < data_modified {
+            return None; // the index is out of date
+        }
+        let tree = RTree::read(&index_file).ok()?;
+        if tree.size() != self.num_records {
+            return None;
+        }
+        Some(tree)
+    }
+
+    pub fn write(&mut self) -> Result<(), Error> {
+        if self.file_mode == "r" {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "The file was opened in read-only mode.",
+            ));
+        }
+
+        self.num_records = self.records.len(); // make sure they are the same.
+
+        match self.get_vector_type() {
+            VectorType::GeoJson => geojson::write_geojson(self),
+            VectorType::GeoPackage => geopackage::write_geopackage(self),
+            VectorType::Shapefile => write_shapefile(self),
+            VectorType::Unknown => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized vector file type: {}", self.file_name),
+            )),
+        }
+    }
+
+    pub(crate) fn calculate_extent(&mut self) {
+        match self.header.shape_type {
+            ShapeType::Null => {
+                self.header.x_min = 0f64;
+                self.header.x_max = 0f64;
+                self.header.y_min = 0f64;
+                self.header.y_max = 0f64;
+                self.header.m_min = 0f64;
+                self.header.m_max = 0f64;
+                self.header.z_min = 0f64;
+                self.header.z_max = 0f64;
+            }
+            ShapeType::Point => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = 0f64;
+                self.header.m_max = 0f64;
+                self.header.z_min = 0f64;
+                self.header.z_max = 0f64;
+                for sg in self.records.iter().filter(|sg| !sg.points.is_empty()) {
+                    if sg.points[0].x < self.header.x_min {
+                        self.header.x_min = sg.points[0].x;
+                    }
+                    if sg.points[0].y < self.header.y_min {
+                        self.header.y_min = sg.points[0].y;
+                    }
+
+                    if sg.points[0].x > self.header.x_max {
+                        self.header.x_max = sg.points[0].x;
+                    }
+                    if sg.points[0].y > self.header.y_max {
+                        self.header.y_max = sg.points[0].y;
+                    }
+                }
+            }
+            ShapeType::PolyLine | ShapeType::Polygon | ShapeType::MultiPoint => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = 0f64;
+                self.header.m_max = 0f64;
+                self.header.z_min = 0f64;
+                self.header.z_max = 0f64;
+                for sg in &self.records {
+                    if sg.x_min < self.header.x_min {
+                        self.header.x_min = sg.x_min;
+                    }
+                    if sg.y_min < self.header.y_min {
+                        self.header.y_min = sg.y_min;
+                    }
+
+                    if sg.x_max > self.header.x_max {
+                        self.header.x_max = sg.x_max;
+                    }
+                    if sg.y_max > self.header.y_max {
+                        self.header.y_max = sg.y_max;
+                    }
+                }
+            }
+            ShapeType::PointM => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = f64::INFINITY;
+                self.header.m_max = f64::NEG_INFINITY;
+                self.header.z_min = 0f64;
+                self.header.z_max = 0f64;
+                for sg in self.records.iter().filter(|sg| !sg.points.is_empty()) {
+                    if sg.points[0].x < self.header.x_min {
+                        self.header.x_min = sg.points[0].x;
+                    }
+                    if sg.points[0].y < self.header.y_min {
+                        self.header.y_min = sg.points[0].y;
+                    }
+                    if sg.m_array[0] < self.header.m_min {
+                        self.header.m_min = sg.m_array[0];
+                    }
+
+                    if sg.points[0].x > self.header.x_max {
+                        self.header.x_max = sg.points[0].x;
+                    }
+                    if sg.points[0].y > self.header.y_max {
+                        self.header.y_max = sg.points[0].y;
+                    }
+                    if sg.m_array[0] > self.header.m_max {
+                        self.header.m_max = sg.m_array[0];
+                    }
+                }
+            }
+            ShapeType::PolyLineM | ShapeType::PolygonM | ShapeType::MultiPointM => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = f64::INFINITY;
+                self.header.m_max = f64::NEG_INFINITY;
+                self.header.z_min = 0f64;
+                self.header.z_max = 0f64;
+
+                for sg in &self.records {
+                    if sg.x_min < self.header.x_min {
+                        self.header.x_min = sg.x_min;
+                    }
+                    if sg.y_min < self.header.y_min {
+                        self.header.y_min = sg.y_min;
+                    }
+                    if sg.m_min < self.header.m_min {
+                        self.header.m_min = sg.m_min;
+                    }
+
+                    if sg.x_max > self.header.x_max {
+                        self.header.x_max = sg.x_max;
+                    }
+                    if sg.y_max > self.header.y_max {
+                        self.header.y_max = sg.y_max;
+                    }
+                    if sg.m_max > self.header.m_max {
+                        self.header.m_max = sg.m_max;
+                    }
+                }
+            }
+            ShapeType::PointZ => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = f64::INFINITY;
+                self.header.m_max = f64::NEG_INFINITY;
+                self.header.z_min = f64::INFINITY;
+                self.header.z_max = f64::NEG_INFINITY;
+                for sg in self.records.iter().filter(|sg| !sg.points.is_empty()) {
+                    if sg.points[0].x < self.header.x_min {
+                        self.header.x_min = sg.points[0].x;
+                    }
+                    if sg.points[0].y < self.header.y_min {
+                        self.header.y_min = sg.points[0].y;
+                    }
+                    if sg.m_array[0] < self.header.m_min {
+                        self.header.m_min = sg.m_array[0];
+                    }
+                    if sg.z_array[0] < self.header.z_min {
+                        self.header.z_min = sg.z_array[0];
+                    }
+
+                    if sg.points[0].x > self.header.x_max {
+                        self.header.x_max = sg.points[0].x;
+                    }
+                    if sg.points[0].y > self.header.y_max {
+                        self.header.y_max = sg.points[0].y;
+                    }
+                    if sg.m_array[0] > self.header.m_max {
+                        self.header.m_max = sg.m_array[0];
+                    }
+                    if sg.z_array[0] > self.header.z_min {
+                        self.header.z_min = sg.z_array[0];
+                    }
+                }
+            }
+            ShapeType::PolyLineZ | ShapeType::PolygonZ | ShapeType::MultiPointZ => {
+                self.header.x_min = f64::INFINITY;
+                self.header.x_max = f64::NEG_INFINITY;
+                self.header.y_min = f64::INFINITY;
+                self.header.y_max = f64::NEG_INFINITY;
+                self.header.m_min = f64::INFINITY;
+                self.header.m_max = f64::NEG_INFINITY;
+                self.header.z_min = f64::INFINITY;
+                self.header.z_max = f64::NEG_INFINITY;
+
+                for sg in &self.records {
+                    if sg.x_min < self.header.x_min {
+                        self.header.x_min = sg.x_min;
+                    }
+                    if sg.y_min < self.header.y_min {
+                        self.header.y_min = sg.y_min;
+                    }
+                    if sg.m_min < self.header.m_min {
+                        self.header.m_min = sg.m_min;
+                    }
+                    if sg.z_min < self.header.z_min {
+                        self.header.z_min = sg.z_min;
+                    }
+
+                    if sg.x_max > self.header.x_max {
+                        self.header.x_max = sg.x_max;
+                    }
+                    if sg.y_max > self.header.y_max {
+                        self.header.y_max = sg.y_max;
+                    }
+                    if sg.m_max > self.header.m_max {
+                        self.header.m_max = sg.m_max;
+                    }
+                    if sg.z_max > self.header.z_max {
+                        self.header.z_max = sg.z_max;
+                    }
+                }
+            }
+        }
+        if !self.header.x_min.is_finite() {
+            // an empty layer, or one with only null shapes, has no extent
+            self.header.x_min = 0f64;
+            self.header.x_max = 0f64;
+            self.header.y_min = 0f64;
+            self.header.y_max = 0f64;
+            self.header.m_min = 0f64;
+            self.header.m_max = 0f64;
+            self.header.z_min = 0f64;
+            self.header.z_max = 0f64;
+        }
+    }
+}
+
+/// The file formats that a `Vector` can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VectorType {
+    Unknown,
+    GeoJson,
+    GeoPackage,
+    Shapefile,
+}
+
+impl Default for VectorType {
+    fn default() -> VectorType {
+        VectorType::Unknown
+    }
+}
+
+/// Returns the VectorType of a file, based on its extension.
+pub fn get_vector_type_from_file(file_name: &str) -> VectorType {
+    if geojson::is_geojson_file(file_name) {
+        VectorType::GeoJson
+    } else if geopackage::is_geopackage_file(file_name) {
+        VectorType::GeoPackage
+    } else if file_name.to_lowercase().ends_with(".shp") {
+        VectorType::Shapefile
+    } else {
+        VectorType::Unknown
+    }
+}
+
+/// Returns true if the file name has the extension of a supported vector format.
+pub fn is_vector_file(file_name: &str) -> bool {
+    get_vector_type_from_file(file_name) != VectorType::Unknown
+}
+
+fn default_extension(file_name: &str) -> String {
+    if file_name.contains(".") {
+        file_name.to_string()
+    } else {
+        // likely no extension provided; default to .shp
+        format!("{}.shp", file_name)
+    }
+}
 
 // /// A 2-D point, with x and y fields.
 // #[derive(Default, Copy, Clone, Debug)]
@@ -96,3 +601,135 @@ pub use crate::vector::shapefile::Shapefile;
 //     }
 //     wn != 0i32
 // }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    fn test_vector(file_name: &str) -> Vector {
+        let mut sf = Vector::new(file_name, ShapeType::Polygon).unwrap();
+        sf.projection = crate::spatial_ref_system::esri_wkt_from_epsg(26917);
+        sf.attributes
+            .add_field(&AttributeField::new("NAME", FieldDataType::Text, 10u8, 0u8));
+        sf.attributes
+            .add_field(&AttributeField::new("COUNT", FieldDataType::Int, 4u8, 0u8));
+        sf.attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 10u8, 2u8));
+        let ring = |pts: &[(f64, f64)]| {
+            pts.iter()
+                .map(|p| Point2D::new(p.0, p.1))
+                .collect::<Vec<Point2D>>()
+        };
+        // a polygon with a hole, and a multipart polygon
+        let mut g = ShapefileGeometry::new(ShapeType::Polygon);
+        g.add_part(&ring(&[
+            (0., 0.),
+            (0., 10.),
+            (10., 10.),
+            (10., 0.),
+            (0., 0.),
+        ]));
+        g.add_part(&ring(&[(2., 2.), (4., 2.), (4., 4.), (2., 4.), (2., 2.)]));
+        sf.add_record(g);
+        sf.attributes.add_record(
+            vec![
+                FieldData::Text("Lake".to_string()),
+                FieldData::Int(3),
+                FieldData::Real(96.5),
+            ],
+            false,
+        );
+        let mut g = ShapefileGeometry::new(ShapeType::Polygon);
+        g.add_part(&ring(&[(20., 0.), (20., 10.), (30., 0.), (20., 0.)]));
+        g.add_part(&ring(&[(40., 0.), (40., 10.), (50., 0.), (40., 0.)]));
+        sf.add_record(g);
+        sf.attributes.add_record(
+            vec![
+                FieldData::Text("Field".to_string()),
+                FieldData::Int(-7),
+                FieldData::Real(50.25),
+            ],
+            false,
+        );
+        sf
+    }
+
+    fn assert_same_features(a: &Vector, b: &Vector) {
+        assert_eq!(b.header.shape_type, a.header.shape_type);
+        assert_eq!(b.num_records, a.num_records);
+        assert!(b.projection.contains("26917"));
+        for i in 0..a.num_records {
+            assert_eq!(b.records[i].points, a.records[i].points);
+            assert_eq!(b.records[i].parts, a.records[i].parts);
+            for field in &["NAME", "COUNT", "AREA"] {
+                assert_eq!(
+                    b.attributes.get_value(i, field),
+                    a.attributes.get_value(i, field)
+                );
+            }
+        }
+        assert!(b.records[0].is_hole(1));
+        assert_eq!(b.header.x_max, 50.0);
+    }
+
+    #[test]
+    fn test_vector_round_trip() {
+        let dir = env::temp_dir().join("wbt_vector_round_trip");
+        fs::create_dir_all(&dir).unwrap();
+        for extension in &["shp", "geojson", "gpkg"] {
+            let file_name = dir
+                .join(format!("polygons.{}", extension))
+                .to_string_lossy()
+                .to_string();
+            let mut sf = test_vector(&file_name);
+            sf.write().unwrap();
+            let sf2 = Vector::read(&file_name).unwrap();
+            assert_eq!(sf2.get_vector_type(), sf.get_vector_type());
+            assert_same_features(&sf, &sf2);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_vector_geopackage_layers() {
+        let dir = env::temp_dir().join("wbt_vector_geopackage_layers");
+        fs::create_dir_all(&dir).unwrap();
+        let file_name = dir.join("data.gpkg").to_string_lossy().to_string();
+        let layer = format!("{}:parcels", file_name);
+        assert_eq!(get_vector_type_from_file(&layer), VectorType::GeoPackage);
+        let mut sf = test_vector(&layer);
+        sf.write().unwrap();
+
+        let mut pts = Vector::new(&format!("{}:wells", file_name), ShapeType::Point).unwrap();
+        pts.projection = sf.projection.clone();
+        pts.add_point_record(1.0, 2.0);
+        pts.attributes
+            .add_field(&AttributeField::new("ID", FieldDataType::Int, 4u8, 0u8));
+        pts.attributes.add_record(vec![FieldData::Int(12)], false);
+        pts.write().unwrap();
+
+        // each layer is selected by name, and the first layer by default
+        assert_same_features(&sf, &Vector::read(&layer).unwrap());
+        assert_same_features(&sf, &Vector::read(&file_name).unwrap());
+        let pts2 = Vector::read(&format!("{}:wells", file_name)).unwrap();
+        assert_eq!(pts2.header.shape_type, ShapeType::Point);
+        assert_eq!(pts2.records[0].points, vec![Point2D::new(1.0, 2.0)]);
+        assert_eq!(pts2.attributes.get_value(0, "ID"), FieldData::Int(12));
+
+        // re-writing a layer replaces it and keeps the other layers
+        pts.add_point_record(3.0, 4.0);
+        pts.attributes.add_record(vec![FieldData::Int(13)], false);
+        pts.write().unwrap();
+        assert_eq!(
+            Vector::read(&format!("{}:wells", file_name))
+                .unwrap()
+                .num_records,
+            2
+        );
+        assert_same_features(&sf, &Vector::read(&layer).unwrap());
+
+        assert!(Vector::read(&format!("{}:roads", file_name)).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
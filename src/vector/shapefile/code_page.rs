@@ -0,0 +1,227 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: The code page of a dBASE (.dbf) attribute table is given by an optional .cpg sidecar
+file, which takes precedence, or by the language driver ID (LDID) byte of the table header.
+Text is decoded from the code page to UTF-8 on reading and is always written as UTF-8.
+*/
+
+use std::fs;
+use std::path::Path;
+
+/// The code page used to encode the text of a dBASE (.dbf) attribute table.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CodePage {
+    /// The code page is not specified. Text is decoded as UTF-8 if it is valid UTF-8 and
+    /// as Windows-1252 otherwise.
+    Unknown,
+    Utf8,
+    Latin1,
+    Windows1250,
+    Windows1251,
+    Windows1252,
+    Oem437,
+    Oem850,
+}
+
+impl Default for CodePage {
+    fn default() -> CodePage {
+        CodePage::Unknown
+    }
+}
+
+impl CodePage {
+    /// Reads the code page from the .cpg file associated with a .dbf file, if one exists.
+    pub fn from_cpg_file(dbf_file: &str) -> Option<CodePage> {
+        let cpg_file = Path::new(dbf_file).with_extension("cpg");
+        match fs::read(&cpg_file) {
+            Ok(bytes) => Some(CodePage::from_cpg(&String::from_utf8_lossy(&bytes))),
+            Err(_) => None,
+        }
+    }
+
+    /// Parses the contents of a .cpg file, e.g. 'UTF-8', '1252', 'ANSI 1252', or 'ISO-8859-1'.
+    pub fn from_cpg(contents: &str) -> CodePage {
+        let s = contents
+            .trim()
+            .to_uppercase()
+            .replace(|c: char| c == '-' || c == '_' || c.is_whitespace(), "");
+        if s.contains("UTF8") || s == "65001" {
+            return CodePage::Utf8;
+        }
+        if s.contains("88591") || s.contains("LATIN1") {
+            return CodePage::Latin1;
+        }
+        let digits = s.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+        match digits.as_str() {
+            "1250" => CodePage::Windows1250,
+            "1251" => CodePage::Windows1251,
+            "1252" => CodePage::Windows1252,
+            "437" => CodePage::Oem437,
+            "850" => CodePage::Oem850,
+            _ => CodePage::Unknown,
+        }
+    }
+
+    /// Returns the code page identified by the language driver ID byte of a .dbf header.
+    pub fn from_language_driver_id(ldid: u8) -> CodePage {
+        match ldid {
+            0x01 | 0x09 | 0x0B | 0x0D | 0x0F | 0x11 | 0x15 | 0x18 | 0x19 | 0x1B => CodePage::Oem437,
+            0x02 | 0x0A | 0x0E | 0x10 | 0x12 | 0x14 | 0x16 | 0x1A | 0x1D | 0x25 | 0x37 => {
+                CodePage::Oem850
+            }
+            0x03 | 0x57 | 0x58 | 0x59 => CodePage::Windows1252,
+            0xC8 => CodePage::Windows1250,
+            0xC9 => CodePage::Windows1251,
+            _ => CodePage::Unknown,
+        }
+    }
+
+    /// Decodes text in the code page to a (UTF-8) String.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let table = match self {
+            CodePage::Utf8 => return String::from_utf8_lossy(bytes).to_string(),
+            CodePage::Unknown => match std::str::from_utf8(bytes) {
+                Ok(s) => return s.to_string(),
+                Err(_) => &WINDOWS_1252,
+            },
+            CodePage::Latin1 => return bytes.iter().map(|&b| char::from(b)).collect(),
+            CodePage::Windows1250 => &WINDOWS_1250,
+            CodePage::Windows1251 => &WINDOWS_1251,
+            CodePage::Windows1252 => &WINDOWS_1252,
+            CodePage::Oem437 => &OEM_437,
+            CodePage::Oem850 => &OEM_850,
+        };
+        bytes
+            .iter()
+            .map(|&b| {
+                if b < 0x80 {
+                    char::from(b)
+                } else {
+                    std::char::from_u32(table[b as usize - 0x80] as u32).unwrap_or('\u{FFFD}')
+                }
+            })
+            .collect()
+    }
+}
+
+/// Truncates a string to at most `max_bytes` bytes of UTF-8 without splitting a character.
+pub fn truncate_to_bytes(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[0..end]
+}
+
+// The characters of the upper halves (bytes 0x80 to 0xFF) of the single-byte code pages.
+// Bytes that a code page leaves undefined are mapped to the C1 control of the same value.
+
+const WINDOWS_1250: [u16; 128] = [
+    0x20AC, 0x0081, 0x201A, 0x0083, 0x201E, 0x2026, 0x2020, 0x2021, 0x0088, 0x2030, 0x0160, 0x2039,
+    0x015A, 0x0164, 0x017D, 0x0179, 0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x0098, 0x2122, 0x0161, 0x203A, 0x015B, 0x0165, 0x017E, 0x017A, 0x00A0, 0x02C7, 0x02D8, 0x0141,
+    0x00A4, 0x0104, 0x00A6, 0x00A7, 0x00A8, 0x00A9, 0x015E, 0x00AB, 0x00AC, 0x00AD, 0x00AE, 0x017B,
+    0x00B0, 0x00B1, 0x02DB, 0x0142, 0x00B4, 0x00B5, 0x00B6, 0x00B7, 0x00B8, 0x0105, 0x015F, 0x00BB,
+    0x013D, 0x02DD, 0x013E, 0x017C, 0x0154, 0x00C1, 0x00C2, 0x0102, 0x00C4, 0x0139, 0x0106, 0x00C7,
+    0x010C, 0x00C9, 0x0118, 0x00CB, 0x011A, 0x00CD, 0x00CE, 0x010E, 0x0110, 0x0143, 0x0147, 0x00D3,
+    0x00D4, 0x0150, 0x00D6, 0x00D7, 0x0158, 0x016E, 0x00DA, 0x0170, 0x00DC, 0x00DD, 0x0162, 0x00DF,
+    0x0155, 0x00E1, 0x00E2, 0x0103, 0x00E4, 0x013A, 0x0107, 0x00E7, 0x010D, 0x00E9, 0x0119, 0x00EB,
+    0x011B, 0x00ED, 0x00EE, 0x010F, 0x0111, 0x0144, 0x0148, 0x00F3, 0x00F4, 0x0151, 0x00F6, 0x00F7,
+    0x0159, 0x016F, 0x00FA, 0x0171, 0x00FC, 0x00FD, 0x0163, 0x02D9,
+];
+
+const WINDOWS_1251: [u16; 128] = [
+    0x0402, 0x0403, 0x201A, 0x0453, 0x201E, 0x2026, 0x2020, 0x2021, 0x20AC, 0x2030, 0x0409, 0x2039,
+    0x040A, 0x040C, 0x040B, 0x040F, 0x0452, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x0098, 0x2122, 0x0459, 0x203A, 0x045A, 0x045C, 0x045B, 0x045F, 0x00A0, 0x040E, 0x045E, 0x0408,
+    0x00A4, 0x0490, 0x00A6, 0x00A7, 0x0401, 0x00A9, 0x0404, 0x00AB, 0x00AC, 0x00AD, 0x00AE, 0x0407,
+    0x00B0, 0x00B1, 0x0406, 0x0456, 0x0491, 0x00B5, 0x00B6, 0x00B7, 0x0451, 0x2116, 0x0454, 0x00BB,
+    0x0458, 0x0405, 0x0455, 0x0457, 0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417,
+    0x0418, 0x0419, 0x041A, 0x041B, 0x041C, 0x041D, 0x041E, 0x041F, 0x0420, 0x0421, 0x0422, 0x0423,
+    0x0424, 0x0425, 0x0426, 0x0427, 0x0428, 0x0429, 0x042A, 0x042B, 0x042C, 0x042D, 0x042E, 0x042F,
+    0x0430, 0x0431, 0x0432, 0x0433, 0x0434, 0x0435, 0x0436, 0x0437, 0x0438, 0x0439, 0x043A, 0x043B,
+    0x043C, 0x043D, 0x043E, 0x043F, 0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447,
+    0x0448, 0x0449, 0x044A, 0x044B, 0x044C, 0x044D, 0x044E, 0x044F,
+];
+
+const WINDOWS_1252: [u16; 128] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0x008D, 0x017D, 0x008F, 0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x009D, 0x017E, 0x0178, 0x00A0, 0x00A1, 0x00A2, 0x00A3,
+    0x00A4, 0x00A5, 0x00A6, 0x00A7, 0x00A8, 0x00A9, 0x00AA, 0x00AB, 0x00AC, 0x00AD, 0x00AE, 0x00AF,
+    0x00B0, 0x00B1, 0x00B2, 0x00B3, 0x00B4, 0x00B5, 0x00B6, 0x00B7, 0x00B8, 0x00B9, 0x00BA, 0x00BB,
+    0x00BC, 0x00BD, 0x00BE, 0x00BF, 0x00C0, 0x00C1, 0x00C2, 0x00C3, 0x00C4, 0x00C5, 0x00C6, 0x00C7,
+    0x00C8, 0x00C9, 0x00CA, 0x00CB, 0x00CC, 0x00CD, 0x00CE, 0x00CF, 0x00D0, 0x00D1, 0x00D2, 0x00D3,
+    0x00D4, 0x00D5, 0x00D6, 0x00D7, 0x00D8, 0x00D9, 0x00DA, 0x00DB, 0x00DC, 0x00DD, 0x00DE, 0x00DF,
+    0x00E0, 0x00E1, 0x00E2, 0x00E3, 0x00E4, 0x00E5, 0x00E6, 0x00E7, 0x00E8, 0x00E9, 0x00EA, 0x00EB,
+    0x00EC, 0x00ED, 0x00EE, 0x00EF, 0x00F0, 0x00F1, 0x00F2, 0x00F3, 0x00F4, 0x00F5, 0x00F6, 0x00F7,
+    0x00F8, 0x00F9, 0x00FA, 0x00FB, 0x00FC, 0x00FD, 0x00FE, 0x00FF,
+];
+
+const OEM_437: [u16; 128] = [
+    0x00C7, 0x00FC, 0x00E9, 0x00E2, 0x00E4, 0x00E0, 0x00E5, 0x00E7, 0x00EA, 0x00EB, 0x00E8, 0x00EF,
+    0x00EE, 0x00EC, 0x00C4, 0x00C5, 0x00C9, 0x00E6, 0x00C6, 0x00F4, 0x00F6, 0x00F2, 0x00FB, 0x00F9,
+    0x00FF, 0x00D6, 0x00DC, 0x00A2, 0x00A3, 0x00A5, 0x20A7, 0x0192, 0x00E1, 0x00ED, 0x00F3, 0x00FA,
+    0x00F1, 0x00D1, 0x00AA, 0x00BA, 0x00BF, 0x2310, 0x00AC, 0x00BD, 0x00BC, 0x00A1, 0x00AB, 0x00BB,
+    0x2591, 0x2592, 0x2593, 0x2502, 0x2524, 0x2561, 0x2562, 0x2556, 0x2555, 0x2563, 0x2551, 0x2557,
+    0x255D, 0x255C, 0x255B, 0x2510, 0x2514, 0x2534, 0x252C, 0x251C, 0x2500, 0x253C, 0x255E, 0x255F,
+    0x255A, 0x2554, 0x2569, 0x2566, 0x2560, 0x2550, 0x256C, 0x2567, 0x2568, 0x2564, 0x2565, 0x2559,
+    0x2558, 0x2552, 0x2553, 0x256B, 0x256A, 0x2518, 0x250C, 0x2588, 0x2584, 0x258C, 0x2590, 0x2580,
+    0x03B1, 0x00DF, 0x0393, 0x03C0, 0x03A3, 0x03C3, 0x00B5, 0x03C4, 0x03A6, 0x0398, 0x03A9, 0x03B4,
+    0x221E, 0x03C6, 0x03B5, 0x2229, 0x2261, 0x00B1, 0x2265, 0x2264, 0x2320, 0x2321, 0x00F7, 0x2248,
+    0x00B0, 0x2219, 0x00B7, 0x221A, 0x207F, 0x00B2, 0x25A0, 0x00A0,
+];
+
+const OEM_850: [u16; 128] = [
+    0x00C7, 0x00FC, 0x00E9, 0x00E2, 0x00E4, 0x00E0, 0x00E5, 0x00E7, 0x00EA, 0x00EB, 0x00E8, 0x00EF,
+    0x00EE, 0x00EC, 0x00C4, 0x00C5, 0x00C9, 0x00E6, 0x00C6, 0x00F4, 0x00F6, 0x00F2, 0x00FB, 0x00F9,
+    0x00FF, 0x00D6, 0x00DC, 0x00F8, 0x00A3, 0x00D8, 0x00D7, 0x0192, 0x00E1, 0x00ED, 0x00F3, 0x00FA,
+    0x00F1, 0x00D1, 0x00AA, 0x00BA, 0x00BF, 0x00AE, 0x00AC, 0x00BD, 0x00BC, 0x00A1, 0x00AB, 0x00BB,
+    0x2591, 0x2592, 0x2593, 0x2502, 0x2524, 0x00C1, 0x00C2, 0x00C0, 0x00A9, 0x2563, 0x2551, 0x2557,
+    0x255D, 0x00A2, 0x00A5, 0x2510, 0x2514, 0x2534, 0x252C, 0x251C, 0x2500, 0x253C, 0x00E3, 0x00C3,
+    0x255A, 0x2554, 0x2569, 0x2566, 0x2560, 0x2550, 0x256C, 0x00A4, 0x00F0, 0x00D0, 0x00CA, 0x00CB,
+    0x00C8, 0x0131, 0x00CD, 0x00CE, 0x00CF, 0x2518, 0x250C, 0x2588, 0x2584, 0x00A6, 0x00CC, 0x2580,
+    0x00D3, 0x00DF, 0x00D4, 0x00D2, 0x00F5, 0x00D5, 0x00B5, 0x00FE, 0x00DE, 0x00DA, 0x00DB, 0x00D9,
+    0x00FD, 0x00DD, 0x00AF, 0x00B4, 0x00AD, 0x00B1, 0x2017, 0x00BE, 0x00B6, 0x00A7, 0x00F7, 0x00B8,
+    0x00B0, 0x00A8, 0x00B7, 0x00B9, 0x00B3, 0x00B2, 0x25A0, 0x00A0,
+];
+
+#[cfg(test)]
+mod test {
+    use super::CodePage;
+
+    #[test]
+    fn test_code_page_decoding() {
+        assert_eq!(CodePage::from_cpg("UTF-8\r\n"), CodePage::Utf8);
+        assert_eq!(CodePage::from_cpg("ANSI 1252"), CodePage::Windows1252);
+        assert_eq!(CodePage::from_cpg("ISO-8859-1"), CodePage::Latin1);
+        assert_eq!(
+            CodePage::from_language_driver_id(0x57),
+            CodePage::Windows1252
+        );
+        assert_eq!(
+            CodePage::Windows1252.decode(&[77, 111, 110, 116, 114, 0xE9, 97, 108]),
+            "Montréal"
+        );
+        assert_eq!(CodePage::Oem850.decode(&[0x8A]), "è");
+        assert_eq!(CodePage::Windows1251.decode(&[0xC0]), "А");
+        // text without a code page is decoded as UTF-8 if valid and as Windows-1252 otherwise
+        assert_eq!(
+            CodePage::Unknown.decode("Kuujjuaq ᑰᔪᐊᖅ".as_bytes()),
+            "Kuujjuaq ᑰᔪᐊᖅ"
+        );
+        assert_eq!(
+            CodePage::Unknown.decode(&[0x4C, 0xE9, 0x76, 0x69, 0x73]),
+            "Lévis"
+        );
+        assert_eq!(super::truncate_to_bytes("Québec", 3), "Qu");
+    }
+}
@@ -226,6 +226,21 @@ impl ShapefileGeometry {
         BoundingBox::new(self.x_min, self.x_max, self.y_min, self.y_max)
     }
 
+    /// Returns the points of each part of the ShapefileGeometry.
+    pub fn get_parts(&self) -> Vec<Vec<Point2D>> {
+        let mut ret = Vec::with_capacity(self.num_parts as usize);
+        for part in 0..self.num_parts as usize {
+            let first_point = self.parts[part] as usize;
+            let last_point = if part < self.num_parts as usize - 1 {
+                self.parts[part + 1] as usize
+            } else {
+                self.num_points as usize
+            };
+            ret.push(self.points[first_point..last_point].to_vec());
+        }
+        ret
+    }
+
     /// Returns the length of the ShapefileGeometry, including the header, in bytes.
     pub fn get_length(&self) -> i32 {
         let mut ret = 4; //12; // 12 bytes in the record number, content length, and shape type
@@ -0,0 +1,241 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 18/10/2026
+Last Modified: 18/10/2026
+License: MIT
+
+Notes: Functions shared by the readers and writers of simple-features formats (GeoJSON and
+GeoPackage) for converting between their geometries and the Shapefile data model.
+*/
+
+use super::shapefile::geometry::*;
+use crate::algorithms::{is_clockwise_order, point_in_poly, polygon_area};
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use crate::structures::Point2D;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+
+/// The coordinates of a feature's geometry, as [x, y, z, m], with NaN z and m values
+/// where a coordinate does not have them.
+#[derive(Default)]
+pub struct FeatureGeometry {
+    pub points: Vec<[f64; 4]>,
+    pub lines: Vec<Vec<[f64; 4]>>,
+    pub polygons: Vec<Vec<Vec<[f64; 4]>>>,
+}
+
+impl FeatureGeometry {
+    /// Returns the base ShapeType of the feature, or None if it is empty.
+    fn base_shape_type(&self) -> Result<Option<ShapeType>, Error> {
+        let mixed = (!self.polygons.is_empty()
+            && (!self.lines.is_empty() || !self.points.is_empty()))
+            || (!self.lines.is_empty() && !self.points.is_empty());
+        if mixed {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A GeometryCollection contains mixed geometry types, which are not supported.",
+            ));
+        }
+        Ok(if !self.polygons.is_empty() {
+            Some(ShapeType::Polygon)
+        } else if !self.lines.is_empty() {
+            Some(ShapeType::PolyLine)
+        } else if self.points.len() > 1 {
+            Some(ShapeType::MultiPoint)
+        } else if self.points.len() == 1 {
+            Some(ShapeType::Point)
+        } else {
+            None
+        })
+    }
+}
+
+/// Finds the single ShapeType that can hold a set of feature geometries. Points are
+/// promoted to MultiPoints if any feature contains more than one point, and the Z
+/// (or, without z values, the M) variant is used if the coordinates have them.
+pub fn find_shape_type(
+    geometries: &[FeatureGeometry],
+    has_z: bool,
+    has_m: bool,
+) -> Result<ShapeType, Error> {
+    let mut base_type: Option<ShapeType> = None;
+    for fg in geometries {
+        base_type = match (base_type, fg.base_shape_type()?) {
+            (b, None) => b,
+            (None, t) => t,
+            (Some(b), Some(t)) if b == t => Some(b),
+            (Some(ShapeType::Point), Some(ShapeType::MultiPoint))
+            | (Some(ShapeType::MultiPoint), Some(ShapeType::Point)) => Some(ShapeType::MultiPoint),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The features contain mixed geometry types, which are not supported.",
+                ))
+            }
+        };
+    }
+    Ok(match (base_type, has_z, has_m) {
+        (None, _, _) => ShapeType::Null,
+        (Some(ShapeType::Point), true, _) => ShapeType::PointZ,
+        (Some(ShapeType::Point), false, true) => ShapeType::PointM,
+        (Some(ShapeType::Point), false, false) => ShapeType::Point,
+        (Some(ShapeType::MultiPoint), true, _) => ShapeType::MultiPointZ,
+        (Some(ShapeType::MultiPoint), false, true) => ShapeType::MultiPointM,
+        (Some(ShapeType::MultiPoint), false, false) => ShapeType::MultiPoint,
+        (Some(ShapeType::PolyLine), true, _) => ShapeType::PolyLineZ,
+        (Some(ShapeType::PolyLine), false, true) => ShapeType::PolyLineM,
+        (Some(ShapeType::PolyLine), false, false) => ShapeType::PolyLine,
+        (Some(_), true, _) => ShapeType::PolygonZ,
+        (Some(_), false, true) => ShapeType::PolygonM,
+        (Some(_), false, false) => ShapeType::Polygon,
+    })
+}
+
+/// Converts a feature geometry to a ShapefileGeometry of the given ShapeType. Polygon
+/// rings are closed and re-ordered to the Shapefile convention of clockwise exteriors
+/// and counter-clockwise holes.
+pub fn to_shapefile_geometry(fg: FeatureGeometry, shape_type: ShapeType) -> ShapefileGeometry {
+    if fg.points.is_empty() && fg.lines.is_empty() && fg.polygons.is_empty() {
+        return ShapefileGeometry::new(ShapeType::Null);
+    }
+    let mut sfg = ShapefileGeometry::new(shape_type);
+    let dimension = shape_type.dimension();
+    match shape_type.base_shape_type() {
+        ShapeType::Point | ShapeType::MultiPoint => {
+            for p in &fg.points {
+                let point = Point2D::new(p[0], p[1]);
+                match dimension {
+                    ShapeTypeDimension::Z => sfg.add_pointz(point, or_zero(p[3]), or_zero(p[2])),
+                    ShapeTypeDimension::Measure => sfg.add_pointm(point, or_zero(p[3])),
+                    ShapeTypeDimension::XY => sfg.add_point(point),
+                }
+            }
+        }
+        ShapeType::PolyLine => {
+            for line in &fg.lines {
+                add_part(&mut sfg, line, &dimension);
+            }
+        }
+        _ => {
+            for polygon in fg.polygons {
+                for (r, mut ring) in polygon.into_iter().enumerate() {
+                    let last = ring[ring.len() - 1];
+                    if ring[0][0] != last[0] || ring[0][1] != last[1] {
+                        ring.push(ring[0]);
+                    }
+                    let points = ring
+                        .iter()
+                        .map(|p| Point2D::new(p[0], p[1]))
+                        .collect::<Vec<Point2D>>();
+                    // exterior rings are clockwise and holes are counter-clockwise
+                    if is_clockwise_order(&points) != (r == 0) {
+                        ring.reverse();
+                    }
+                    add_part(&mut sfg, &ring, &dimension);
+                }
+            }
+        }
+    }
+    sfg
+}
+
+fn or_zero(v: f64) -> f64 {
+    if v.is_nan() {
+        0f64
+    } else {
+        v
+    }
+}
+
+fn add_part(sfg: &mut ShapefileGeometry, coordinates: &[[f64; 4]], dimension: &ShapeTypeDimension) {
+    let points = coordinates
+        .iter()
+        .map(|p| Point2D::new(p[0], p[1]))
+        .collect::<Vec<Point2D>>();
+    let values = |i: usize| {
+        coordinates
+            .iter()
+            .map(|p| or_zero(p[i]))
+            .collect::<Vec<f64>>()
+    };
+    match dimension {
+        ShapeTypeDimension::Z => sfg.add_partz(&points, &values(3), &values(2)),
+        ShapeTypeDimension::Measure => sfg.add_partm(&points, &values(3)),
+        ShapeTypeDimension::XY => sfg.add_part(&points),
+    }
+}
+
+/// Returns the range of point indices of a part of a ShapefileGeometry.
+pub fn part_range(sfg: &ShapefileGeometry, part: usize) -> Range<usize> {
+    let start = sfg.parts[part] as usize;
+    let end = if part < sfg.parts.len() - 1 {
+        sfg.parts[part + 1] as usize
+    } else {
+        sfg.points.len()
+    };
+    start..end
+}
+
+/// Groups the parts of a polygon ShapefileGeometry into polygons, each an exterior ring
+/// followed by its holes. Each hole is assigned to the smallest exterior ring that contains
+/// it; a hole that is not contained by any exterior becomes a polygon of its own.
+pub fn group_polygon_rings(sfg: &ShapefileGeometry) -> Vec<Vec<usize>> {
+    let rings = (0..sfg.parts.len())
+        .map(|part| sfg.points[part_range(sfg, part)].to_vec())
+        .collect::<Vec<Vec<Point2D>>>();
+    let mut polygons: Vec<Vec<usize>> = vec![];
+    let mut holes = vec![];
+    for part in 0..rings.len() {
+        if sfg.is_hole(part as i32) {
+            holes.push(part);
+        } else {
+            polygons.push(vec![part]);
+        }
+    }
+    for hole in holes {
+        let mut container = None;
+        let mut min_area = f64::INFINITY;
+        for (k, polygon) in polygons.iter().enumerate() {
+            let exterior = &rings[polygon[0]];
+            if sfg.is_hole(polygon[0] as i32) {
+                continue; // an unmatched hole
+            }
+            let area = polygon_area(exterior);
+            if area < min_area && point_in_poly(&rings[hole][0], exterior) {
+                min_area = area;
+                container = Some(k);
+            }
+        }
+        match container {
+            Some(k) => polygons[k].push(hole),
+            None => polygons.push(vec![hole]),
+        }
+    }
+    polygons
+}
+
+/// Finds the EPSG code of the outermost coordinate system of a WKT string, i.e. the
+/// last EPSG authority.
+pub fn find_epsg_code(wkt: &str) -> Option<u16> {
+    let upper = wkt.to_uppercase();
+    let index = upper.rfind("AUTHORITY[\"EPSG\"")?;
+    let rest = &wkt[index + 16..];
+    let code = rest
+        .trim_start_matches(|c: char| c == ',' || c == '"' || c.is_whitespace())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    code.parse::<u16>().ok()
+}
+
+/// Returns the ESRI WKT of an EPSG code, or an empty string if the code is unknown.
+pub fn projection_from_epsg(code: u16) -> String {
+    let wkt = esri_wkt_from_epsg(code);
+    if wkt == "Unknown EPSG Code" {
+        String::new()
+    } else {
+        wkt
+    }
+}